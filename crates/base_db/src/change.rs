@@ -3,10 +3,11 @@
 
 use std::{fmt, sync::Arc};
 
-use salsa::Durability;
+use rustc_hash::FxHashSet;
+use salsa::{debug::DebugQueryTable, Durability};
 use vfs::FileId;
 
-use crate::{CrateGraph, SourceDatabaseExt, SourceRoot, SourceRootId};
+use crate::{CrateGraph, SourceDatabaseExt, SourceRoot, SourceRootId, SourceRootQuery};
 
 /// Encapsulate a bunch of raw `.set` calls on the database.
 #[derive(Default)]
@@ -51,7 +52,23 @@ impl Change {
 
     pub fn apply(self, db: &mut dyn SourceDatabaseExt) {
         let _p = profile::span("RootDatabase::apply_change");
+        // Source roots whose files might be parsed with a different edition after this change.
+        let mut edition_roots = FxHashSet::default();
+        // Files that weren't in any source root before, and so don't have an edition yet.
+        let mut new_files = FxHashSet::default();
+        let roots_changed = self.roots.is_some();
         if let Some(roots) = self.roots {
+            let old_files = SourceRootQuery
+                .in_db(db)
+                .entries::<Vec<_>>()
+                .into_iter()
+                .filter_map(|entry| entry.value)
+                .flat_map(|root| root.iter().collect::<Vec<_>>())
+                .collect::<FxHashSet<_>>();
+            new_files.extend(
+                roots.iter().flat_map(|root| root.iter()).filter(|it| !old_files.contains(it)),
+            );
+            edition_roots.extend((0..roots.len()).map(|idx| SourceRootId(idx as u32)));
             for (idx, root) in roots.into_iter().enumerate() {
                 let root_id = SourceRootId(idx as u32);
                 let durability = durability(&root);
@@ -71,8 +88,52 @@ impl Change {
             db.set_file_text_with_durability(file_id, text, durability)
         }
         if let Some(crate_graph) = self.crate_graph {
+            if !roots_changed {
+                // Only the roots of crates, old or new, can have an edition other than the default.
+                let old_crate_graph = db.crate_graph();
+                edition_roots.extend(
+                    old_crate_graph
+                        .iter()
+                        .map(|krate| db.file_source_root(old_crate_graph[krate].root_file_id)),
+                );
+                edition_roots.extend(
+                    crate_graph
+                        .iter()
+                        .map(|krate| db.file_source_root(crate_graph[krate].root_file_id)),
+                );
+            }
             db.set_crate_graph_with_durability(Arc::new(crate_graph), Durability::HIGH)
         }
+
+        for root_id in edition_roots {
+            let source_root = db.source_root(root_id);
+            let durability = durability(&source_root);
+            let edition = source_root_edition(db, root_id);
+            for file_id in source_root.iter() {
+                // Setting an input always invalidates its dependents, so only new files and files
+                // whose edition changed get one, instead of reparsing every file.
+                if new_files.contains(&file_id) || db.file_edition(file_id) != edition {
+                    db.set_file_edition_with_durability(file_id, edition, durability);
+                }
+            }
+        }
+    }
+}
+
+/// The edition files of the source root are parsed with. Files shared by crates of different
+/// editions are parsed with the most recent edition.
+fn source_root_edition(db: &dyn SourceDatabaseExt, root_id: SourceRootId) -> syntax::Edition {
+    let crate_graph = db.crate_graph();
+    let mut editions = db
+        .source_root_crates(root_id)
+        .iter()
+        .map(|&krate| crate_graph[krate].edition)
+        .collect::<Vec<_>>();
+    editions.sort();
+    editions.dedup();
+    match editions.as_slice() {
+        [edition] => (*edition).into(),
+        _ => syntax::Edition::CURRENT,
     }
 }
 
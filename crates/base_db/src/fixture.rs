@@ -395,12 +395,15 @@ struct FileMeta {
 
 fn parse_crate(crate_str: String) -> (String, CrateOrigin, Option<String>) {
     if let Some((a, b)) = crate_str.split_once('@') {
+        if b == "Lang" {
+            return (a.to_owned(), CrateOrigin::Lang, None);
+        }
         let (version, origin) = match b.split_once(':') {
             Some(("CratesIo", data)) => match data.split_once(',') {
                 Some((version, url)) => {
                     (version, CrateOrigin::CratesIo { repo: Some(url.to_owned()) })
                 }
-                _ => panic!("Bad crates.io parameter: {}", data),
+                None => (data, CrateOrigin::CratesIo { repo: None }),
             },
             _ => panic!("Bad string for crate origin: {}", b),
         };
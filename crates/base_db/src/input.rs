@@ -242,6 +242,16 @@ impl Edition {
     pub const CURRENT: Edition = Edition::Edition2018;
 }
 
+impl From<Edition> for syntax::Edition {
+    fn from(edition: Edition) -> syntax::Edition {
+        match edition {
+            Edition::Edition2015 => syntax::Edition::Edition2015,
+            Edition::Edition2018 => syntax::Edition::Edition2018,
+            Edition::Edition2021 => syntax::Edition::Edition2021,
+        }
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Eq)]
 pub struct Env {
     entries: FxHashMap<String, String>,
@@ -413,18 +423,44 @@ impl CrateGraph {
     /// Extends this crate graph by adding a complete disjoint second crate
     /// graph.
     ///
-    /// The ids of the crates in the `other` graph are shifted by the return
-    /// amount.
-    pub fn extend(&mut self, other: CrateGraph) -> u32 {
-        let start = self.arena.len() as u32;
-        self.arena.extend(other.arena.into_iter().map(|(id, mut data)| {
-            let new_id = id.shift(start);
+    /// Crates provided by the language are shared: if this graph already has
+    /// an identical sysroot crate, the `other` graph's copy is dropped and its
+    /// dependents point at the existing one, so that the sysroot of several
+    /// workspaces is only analyzed once. Returns the new ids of the crates in
+    /// `other`.
+    pub fn extend(&mut self, mut other: CrateGraph) -> FxHashMap<CrateId, CrateId> {
+        let mut id_map = FxHashMap::default();
+        for id in other.crates_in_topological_order() {
+            let mut data = other.arena.remove(&id).unwrap();
             for dep in &mut data.dependencies {
-                dep.crate_id = dep.crate_id.shift(start);
+                dep.crate_id = id_map[&dep.crate_id];
             }
-            (new_id, data)
-        }));
-        start
+            let new_id = match self.find_shared_lang_crate(&data) {
+                Some(it) => it,
+                None => {
+                    let new_id = CrateId(self.arena.len() as u32);
+                    self.arena.insert(new_id, data);
+                    new_id
+                }
+            };
+            id_map.insert(id, new_id);
+        }
+        id_map
+    }
+
+    fn find_shared_lang_crate(&self, data: &CrateData) -> Option<CrateId> {
+        if !matches!(data.origin, CrateOrigin::Lang) {
+            return None;
+        }
+        let (&crate_id, _) = self.arena.iter().find(|(_, it)| {
+            matches!(it.origin, CrateOrigin::Lang)
+                && it.root_file_id == data.root_file_id
+                && it.edition == data.edition
+                && it.cfg_options == data.cfg_options
+                && it.env == data.env
+                && it.dependencies == data.dependencies
+        })?;
+        Some(crate_id)
     }
 
     fn find_path(
@@ -483,12 +519,6 @@ impl ops::Index<CrateId> for CrateGraph {
     }
 }
 
-impl CrateId {
-    fn shift(self, amount: u32) -> CrateId {
-        CrateId(self.0 + amount)
-    }
-}
-
 impl CrateData {
     fn add_dep(&mut self, dep: Dependency) {
         self.dependencies.push(dep)
@@ -585,7 +615,10 @@ impl fmt::Display for CyclicDependenciesError {
 
 #[cfg(test)]
 mod tests {
-    use super::{CfgOptions, CrateGraph, CrateName, Dependency, Edition::Edition2018, Env, FileId};
+    use super::{
+        CfgOptions, CrateGraph, CrateName, CrateOrigin, Dependency, Edition::Edition2018, Env,
+        FileId,
+    };
 
     #[test]
     fn detect_cyclic_dependency_indirect() {
@@ -757,4 +790,47 @@ mod tests {
             vec![Dependency::new(CrateName::new("crate_name_with_dashes").unwrap(), crate2)]
         );
     }
+
+    #[test]
+    fn extend_shares_sysroot_crates() {
+        fn workspace(root: u32) -> CrateGraph {
+            let mut graph = CrateGraph::default();
+            let core = graph.add_crate_root(
+                FileId(1u32),
+                Edition2018,
+                None,
+                None,
+                CfgOptions::default(),
+                CfgOptions::default(),
+                Env::default(),
+                Default::default(),
+                false,
+                CrateOrigin::Lang,
+            );
+            let krate = graph.add_crate_root(
+                FileId(root),
+                Edition2018,
+                None,
+                None,
+                CfgOptions::default(),
+                CfgOptions::default(),
+                Env::default(),
+                Default::default(),
+                false,
+                Default::default(),
+            );
+            graph.add_dep(krate, Dependency::new(CrateName::new("core").unwrap(), core)).unwrap();
+            graph
+        }
+
+        let mut graph = CrateGraph::default();
+        graph.extend(workspace(2));
+        let ids = graph.extend(workspace(3));
+
+        assert_eq!(graph.iter().count(), 3);
+        let krate = graph.iter().find(|&it| graph[it].root_file_id == FileId(3u32)).unwrap();
+        let core = graph.crate_id_for_crate_root(FileId(1u32)).unwrap();
+        assert_eq!(graph[krate].dependencies[0].crate_id, core);
+        assert!(ids.values().any(|&it| it == core));
+    }
 }
@@ -69,12 +69,17 @@ pub trait SourceDatabase: FileLoader + std::fmt::Debug {
     /// The crate graph.
     #[salsa::input]
     fn crate_graph(&self) -> Arc<CrateGraph>;
+
+    /// The edition to parse the file with, derived from the crate graph by [`Change::apply`], so
+    /// that parsing doesn't depend on the whole graph.
+    #[salsa::input]
+    fn file_edition(&self, file_id: FileId) -> syntax::Edition;
 }
 
 fn parse_query(db: &dyn SourceDatabase, file_id: FileId) -> Parse<ast::SourceFile> {
     let _p = profile::span("parse_query").detail(|| format!("{:?}", file_id));
     let text = db.file_text(file_id);
-    SourceFile::parse(&*text)
+    SourceFile::parse_with_edition(&*text, db.file_edition(file_id))
 }
 
 /// We don't want to give HIR knowledge of source roots, hence we extract these
@@ -66,6 +66,11 @@ impl CfgOptions {
         }
     }
 
+    /// The enabled options, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = &CfgAtom> {
+        self.enabled.iter()
+    }
+
     pub fn get_cfg_keys(&self) -> impl Iterator<Item = &SmolStr> {
         self.enabled.iter().map(|x| match x {
             CfgAtom::Flag(key) => key,
@@ -7,6 +7,7 @@ pub use hir_def::db::*;
 pub use hir_expand::db::{
     AstDatabase, AstDatabaseStorage, AstIdMapQuery, HygieneFrameQuery, InternMacroCallQuery,
     MacroArgTextQuery, MacroDefQuery, MacroExpandQuery, ParseMacroExpansionQuery,
+    SpeculativeExpansionQuery,
 };
 pub use hir_ty::db::*;
 
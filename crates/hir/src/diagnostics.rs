@@ -39,7 +39,10 @@ diagnostics![
     MissingMatchArms,
     MissingUnsafe,
     NoSuchField,
+    OrphanImpl,
+    OverlappingImpls,
     ReplaceFilterMapNextWithFindMap,
+    TraitSolverOverflow,
     TypeMismatch,
     UnimplementedBuiltinMacro,
     UnresolvedExternCrate,
@@ -47,8 +50,41 @@ diagnostics![
     UnresolvedMacroCall,
     UnresolvedModule,
     UnresolvedProcMacro,
+    UseAfterMove,
 ];
 
+impl AnyDiagnostic {
+    /// The file of the node the diagnostic is about, a macro file if the node comes from a
+    /// macro expansion.
+    pub fn file_id(&self) -> HirFileId {
+        match self {
+            AnyDiagnostic::BreakOutsideOfLoop(d) => d.expr.file_id,
+            AnyDiagnostic::InactiveCode(d) => d.node.file_id,
+            AnyDiagnostic::IncorrectCase(d) => d.file,
+            AnyDiagnostic::InvalidDeriveTarget(d) => d.node.file_id,
+            AnyDiagnostic::MacroError(d) => d.node.file_id,
+            AnyDiagnostic::MalformedDerive(d) => d.node.file_id,
+            AnyDiagnostic::MismatchedArgCount(d) => d.call_expr.file_id,
+            AnyDiagnostic::MissingFields(d) => d.file,
+            AnyDiagnostic::MissingMatchArms(d) => d.file,
+            AnyDiagnostic::MissingUnsafe(d) => d.expr.file_id,
+            AnyDiagnostic::NoSuchField(d) => d.field.file_id,
+            AnyDiagnostic::OrphanImpl(d) => d.impl_.file_id,
+            AnyDiagnostic::OverlappingImpls(d) => d.impl_.file_id,
+            AnyDiagnostic::ReplaceFilterMapNextWithFindMap(d) => d.file,
+            AnyDiagnostic::TraitSolverOverflow(d) => d.expr.file_id,
+            AnyDiagnostic::TypeMismatch(d) => d.expr.file_id,
+            AnyDiagnostic::UnimplementedBuiltinMacro(d) => d.node.file_id,
+            AnyDiagnostic::UnresolvedExternCrate(d) => d.decl.file_id,
+            AnyDiagnostic::UnresolvedImport(d) => d.decl.file_id,
+            AnyDiagnostic::UnresolvedMacroCall(d) => d.macro_call.file_id,
+            AnyDiagnostic::UnresolvedModule(d) => d.decl.file_id,
+            AnyDiagnostic::UnresolvedProcMacro(d) => d.node.file_id,
+            AnyDiagnostic::UseAfterMove(d) => d.expr.file_id,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct UnresolvedModule {
     pub decl: InFile<AstPtr<ast::Module>>,
@@ -118,6 +154,11 @@ pub struct BreakOutsideOfLoop {
     pub expr: InFile<AstPtr<ast::Expr>>,
 }
 
+#[derive(Debug)]
+pub struct TraitSolverOverflow {
+    pub expr: InFile<AstPtr<ast::Expr>>,
+}
+
 #[derive(Debug)]
 pub struct MissingUnsafe {
     pub expr: InFile<AstPtr<ast::Expr>>,
@@ -159,4 +200,27 @@ pub struct TypeMismatch {
     pub actual: Type,
 }
 
+#[derive(Debug)]
+pub struct UseAfterMove {
+    /// The use of the moved binding.
+    pub expr: InFile<AstPtr<ast::Expr>>,
+    /// The expression which moved it.
+    pub moved_at: InFile<AstPtr<ast::Expr>>,
+    pub name: Name,
+}
+
+#[derive(Debug)]
+pub struct OverlappingImpls {
+    pub impl_: InFile<AstPtr<ast::Impl>>,
+    /// The impl `impl_` overlaps with, which may be in a dependency.
+    pub other: InFile<AstPtr<ast::Impl>>,
+    pub trait_name: Name,
+    pub self_ty: Type,
+}
+
+#[derive(Debug)]
+pub struct OrphanImpl {
+    pub impl_: InFile<AstPtr<ast::Impl>>,
+}
+
 pub use hir_ty::diagnostics::IncorrectCase;
@@ -17,8 +17,8 @@ use hir_ty::{
 use syntax::SmolStr;
 
 use crate::{
-    Adt, Const, ConstParam, Enum, Field, Function, GenericParam, HasCrate, HasVisibility,
-    LifetimeParam, Macro, Module, Static, Struct, Trait, TyBuilder, Type, TypeAlias,
+    Adt, CallObligation, Const, ConstParam, Enum, Field, Function, GenericParam, HasCrate,
+    HasVisibility, LifetimeParam, Macro, Module, Static, Struct, Trait, TyBuilder, Type, TypeAlias,
     TypeOrConstParam, TypeParam, Union, Variant,
 };
 
@@ -229,6 +229,12 @@ impl HirDisplay for Type {
     }
 }
 
+impl HirDisplay for CallObligation {
+    fn hir_fmt(&self, f: &mut HirFormatter) -> Result<(), HirDisplayError> {
+        self.predicate.skip_binders().hir_fmt(f)
+    }
+}
+
 impl HirDisplay for GenericParam {
     fn hir_fmt(&self, f: &mut HirFormatter) -> Result<(), HirDisplayError> {
         match self {
@@ -334,6 +340,9 @@ fn write_generic_params(def: GenericDefId, f: &mut HirFormatter) -> Result<(), H
                     delim(f)?;
                     write!(f, "const {}: ", name)?;
                     c.ty.hir_fmt(f)?;
+                    if let Some(default) = &c.default {
+                        write!(f, " = {}", default)?;
+                    }
                 }
             }
         }
@@ -29,18 +29,22 @@ mod has_source;
 pub mod diagnostics;
 pub mod db;
 pub mod symbols;
+#[cfg(feature = "visitor")]
+pub mod visit;
 
 mod display;
 
 use std::{iter, ops::ControlFlow, sync::Arc};
 
 use arrayvec::ArrayVec;
-use base_db::{CrateDisplayName, CrateId, CrateOrigin, Edition, FileId, ProcMacroKind};
+use base_db::{
+    CrateDisplayName, CrateId, CrateOrigin, Edition, Env, FileId, FileRange, ProcMacroKind,
+};
 use either::Either;
 use hir_def::{
     adt::{ReprKind, VariantData},
     body::{BodyDiagnostic, SyntheticSyntax},
-    expr::{BindingAnnotation, LabelId, Pat, PatId},
+    expr::{BindingAnnotation, Expr, LabelId, Pat, PatId},
     item_tree::ItemTreeNode,
     lang_item::LangItemTarget,
     nameres::{self, diagnostics::DefDiagnostic},
@@ -73,7 +77,7 @@ use rustc_hash::FxHashSet;
 use stdx::{format_to, impl_from, never};
 use syntax::{
     ast::{self, HasAttrs as _, HasDocComments, HasName},
-    AstNode, AstPtr, SmolStr, SyntaxNodePtr, T,
+    AstNode, AstPtr, SmolStr, SyntaxNodePtr, TextRange, T,
 };
 use tt::{Ident, Leaf, Literal, TokenTree};
 
@@ -84,9 +88,9 @@ pub use crate::{
     diagnostics::{
         AnyDiagnostic, BreakOutsideOfLoop, InactiveCode, IncorrectCase, InvalidDeriveTarget,
         MacroError, MalformedDerive, MismatchedArgCount, MissingFields, MissingMatchArms,
-        MissingUnsafe, NoSuchField, ReplaceFilterMapNextWithFindMap, TypeMismatch,
-        UnimplementedBuiltinMacro, UnresolvedExternCrate, UnresolvedImport, UnresolvedMacroCall,
-        UnresolvedModule, UnresolvedProcMacro,
+        MissingUnsafe, NoSuchField, OrphanImpl, OverlappingImpls, ReplaceFilterMapNextWithFindMap,
+        TraitSolverOverflow, TypeMismatch, UnimplementedBuiltinMacro, UnresolvedExternCrate,
+        UnresolvedImport, UnresolvedMacroCall, UnresolvedModule, UnresolvedProcMacro, UseAfterMove,
     },
     has_source::HasSource,
     semantics::{PathResolution, Semantics, SemanticsScope, TypeInfo},
@@ -104,8 +108,8 @@ pub use crate::{
 pub use {
     cfg::{CfgAtom, CfgExpr, CfgOptions},
     hir_def::{
-        adt::StructKind,
-        attr::{Attr, Attrs, AttrsWithOwner, Documentation},
+        adt::{ReprKind, StructKind},
+        attr::{eval_str_arg, parse_cfg, Attr, Attrs, AttrsWithOwner, Deprecation, Documentation},
         builtin_attr::AttributeTemplate,
         find_path::PrefixKind,
         import_map,
@@ -118,7 +122,13 @@ pub use {
         name::{known, Name},
         ExpandResult, HirFileId, InFile, MacroFile, Origin,
     },
-    hir_ty::display::HirDisplay,
+    hir_ty::{
+        diagnostics::UnsafeExprKind,
+        display::HirDisplay,
+        inference_profiling::{set_enabled as set_inference_profiling, InferenceTiming},
+        layout::Layout,
+        traits::DEFAULT_TRAIT_SOLVER_FUEL,
+    },
 };
 
 // These are negative re-exports: pub using these names is forbidden, they
@@ -201,6 +211,11 @@ impl Crate {
         db.crate_graph()[self.id].display_name.clone()
     }
 
+    /// Whether the crate enables the unstable `feature` with `#![feature(...)]`.
+    pub fn enables_feature(self, db: &dyn HirDatabase, feature: &str) -> bool {
+        db.attrs(AttrDefId::ModuleId(self.root_module(db).into())).enables_feature(feature)
+    }
+
     pub fn query_external_importables(
         self,
         db: &dyn DefDatabase,
@@ -250,6 +265,11 @@ impl Crate {
     pub fn potential_cfg(&self, db: &dyn HirDatabase) -> CfgOptions {
         db.crate_graph()[self.id].potential_cfg_options.clone()
     }
+
+    /// The environment variables `env!` and `option_env!` see in this crate.
+    pub fn env(&self, db: &dyn HirDatabase) -> Env {
+        db.crate_graph()[self.id].env.clone()
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -544,6 +564,7 @@ impl Module {
         }
 
         for impl_def in self.impl_defs(db) {
+            impl_def.coherence_diagnostics(db, acc);
             for item in impl_def.items(db) {
                 let def: DefWithBody = match item {
                     AssocItem::Function(it) => it.into(),
@@ -850,6 +871,11 @@ impl Struct {
         db.struct_data(self.id).repr.clone()
     }
 
+    /// Whether the fields are laid out in declaration order, as with `#[repr(C)]`.
+    pub fn is_repr_c(self, db: &dyn HirDatabase) -> bool {
+        matches!(self.repr(db), Some(ReprKind::C))
+    }
+
     pub fn kind(self, db: &dyn HirDatabase) -> StructKind {
         self.variant_data(db).kind()
     }
@@ -892,6 +918,10 @@ impl Union {
             .collect()
     }
 
+    pub fn repr(self, db: &dyn HirDatabase) -> Option<ReprKind> {
+        db.union_data(self.id).repr.clone()
+    }
+
     fn variant_data(self, db: &dyn HirDatabase) -> Arc<VariantData> {
         db.union_data(self.id).variant_data.clone()
     }
@@ -921,9 +951,29 @@ impl Enum {
         db.enum_data(self.id).variants.iter().map(|(id, _)| Variant { parent: self, id }).collect()
     }
 
+    pub fn repr(self, db: &dyn HirDatabase) -> Option<ReprKind> {
+        db.enum_data(self.id).repr.clone()
+    }
+
     pub fn ty(self, db: &dyn HirDatabase) -> Type {
         Type::from_def(db, self.id.lookup(db.upcast()).container.krate(), self.id)
     }
+
+    /// The type of the enum's discriminants, `isize` unless set by `#[repr]`.
+    pub fn variant_body_ty(self, db: &dyn HirDatabase) -> Type {
+        let krate = self.id.lookup(db.upcast()).container.krate();
+        let builtin = match db.enum_data(self.id).variant_body_type() {
+            Either::Left(builtin) => hir_def::builtin_type::BuiltinType::Int(builtin),
+            Either::Right(builtin) => hir_def::builtin_type::BuiltinType::Uint(builtin),
+        };
+        let ty = TyBuilder::builtin(builtin);
+        Type::new_with_resolver_inner(db, krate, &self.id.resolver(db.upcast()), ty)
+    }
+
+    /// Whether some variant of the enum has fields.
+    pub fn is_data_carrying(self, db: &dyn HirDatabase) -> bool {
+        self.variants(db).iter().any(|v| !matches!(v.kind(db), StructKind::Unit))
+    }
 }
 
 impl HasVisibility for Enum {
@@ -966,6 +1016,16 @@ impl Variant {
     pub(crate) fn variant_data(self, db: &dyn HirDatabase) -> Arc<VariantData> {
         db.enum_data(self.parent.id).variants[self.id].variant_data.clone()
     }
+
+    /// The explicit discriminant expression of the variant, if any.
+    pub fn value(self, db: &dyn HirDatabase) -> Option<ast::Expr> {
+        self.source(db)?.value.expr()
+    }
+
+    /// Evaluates the discriminant of the variant, explicit or implicit.
+    pub fn eval(self, db: &dyn HirDatabase) -> Result<i128, ConstEvalError> {
+        db.const_eval_variant(self.into())
+    }
 }
 
 /// Variants inherit visibility from the parent enum.
@@ -1102,8 +1162,10 @@ pub enum DefWithBody {
     Function(Function),
     Static(Static),
     Const(Const),
+    /// The discriminant expression of an enum variant.
+    Variant(Variant),
 }
-impl_from!(Function, Const, Static for DefWithBody);
+impl_from!(Function, Const, Static, Variant for DefWithBody);
 
 impl DefWithBody {
     pub fn module(self, db: &dyn HirDatabase) -> Module {
@@ -1111,6 +1173,7 @@ impl DefWithBody {
             DefWithBody::Const(c) => c.module(db),
             DefWithBody::Function(f) => f.module(db),
             DefWithBody::Static(s) => s.module(db),
+            DefWithBody::Variant(v) => v.module(db),
         }
     }
 
@@ -1119,7 +1182,83 @@ impl DefWithBody {
             DefWithBody::Function(f) => Some(f.name(db)),
             DefWithBody::Static(s) => Some(s.name(db)),
             DefWithBody::Const(c) => c.name(db),
+            DefWithBody::Variant(v) => Some(v.name(db)),
+        }
+    }
+
+    /// How long inferring the body took the last time it was inferred, if inference profiling
+    /// is enabled, see [`set_inference_profiling`].
+    pub fn inference_timing(self) -> Option<InferenceTiming> {
+        hir_ty::inference_profiling::timing(self.into())
+    }
+
+    /// The `n` bodies which took the longest to infer, slowest first.
+    pub fn slowest_to_infer(n: usize) -> Vec<(DefWithBody, InferenceTiming)> {
+        hir_ty::inference_profiling::slowest(n)
+            .into_iter()
+            .map(|(def, timing)| (def.into(), timing))
+            .collect()
+    }
+
+    /// Infers the body and returns the functions it calls directly, which are the ones most
+    /// likely to be looked at next.
+    pub fn callees(self, db: &dyn HirDatabase) -> Vec<Function> {
+        let body = db.body(self.into());
+        let infer = db.infer(self.into());
+        let mut res = Vec::new();
+        for (expr_id, expr) in body.exprs.iter() {
+            let func = match expr {
+                Expr::Call { callee, .. } => match infer[*callee].callable_def(db) {
+                    Some(CallableDefId::FunctionId(it)) => it,
+                    _ => continue,
+                },
+                Expr::MethodCall { .. } => match infer.method_resolution(expr_id) {
+                    Some((it, _)) => it,
+                    None => continue,
+                },
+                _ => continue,
+            };
+            let func = Function::from(func);
+            if !res.contains(&func) {
+                res.push(func);
+            }
         }
+        res
+    }
+
+    /// The `unsafe` blocks of the body, including nested ones.
+    pub fn unsafe_blocks(self, db: &dyn HirDatabase) -> Vec<InFile<AstPtr<ast::Expr>>> {
+        let (body, source_map) = db.body_with_source_map(self.into());
+        body.exprs
+            .iter()
+            .filter(|(_, expr)| matches!(expr, Expr::Unsafe { .. }))
+            .filter_map(|(expr_id, _)| source_map.expr_syntax(expr_id).ok())
+            .collect()
+    }
+
+    /// The operations in the body which are only allowed in unsafe code, like calls of unsafe
+    /// functions or dereferences of raw pointers.
+    pub fn unsafe_operations(self, db: &dyn HirDatabase) -> Vec<UnsafeOperation> {
+        let (body, source_map) = db.body_with_source_map(self.into());
+        let infer = db.infer(self.into());
+        let mut res = Vec::new();
+        hir_ty::diagnostics::unsafe_expressions(
+            db,
+            &infer,
+            self.into(),
+            &body,
+            body.body_expr,
+            &mut |it| {
+                let expr = match source_map.expr_syntax(it.expr) {
+                    Ok(it) => it,
+                    Err(SyntheticSyntax) => return,
+                };
+                let unsafe_block =
+                    it.unsafe_block.and_then(|block| source_map.expr_syntax(block).ok());
+                res.push(UnsafeOperation { kind: it.kind, expr, unsafe_block });
+            },
+        );
+        res
     }
 
     /// Returns the type this def's body has to evaluate to.
@@ -1128,6 +1267,7 @@ impl DefWithBody {
             DefWithBody::Function(it) => it.ret_type(db),
             DefWithBody::Static(it) => it.ty(db),
             DefWithBody::Const(it) => it.ty(db),
+            DefWithBody::Variant(it) => it.parent.variant_body_ty(db),
         }
     }
 
@@ -1196,6 +1336,12 @@ impl DefWithBody {
                         Err(SyntheticSyntax) => (),
                     }
                 }
+                hir_ty::InferenceDiagnostic::TraitSolverOverflow { expr, .. } => {
+                    match source_map.expr_syntax(*expr) {
+                        Ok(expr) => acc.push(TraitSolverOverflow { expr }.into()),
+                        Err(SyntheticSyntax) => (),
+                    }
+                }
             }
         }
         for (expr, mismatch) in infer.expr_type_mismatches() {
@@ -1233,6 +1379,19 @@ impl DefWithBody {
             }
         }
 
+        let body = db.body(self.into());
+        for d in hir_ty::diagnostics::use_after_move(db, self.into()) {
+            let name = match &body[d.binding] {
+                Pat::Bind { name, .. } => name.clone(),
+                _ => continue,
+            };
+            if let (Ok(expr), Ok(moved_at)) =
+                (source_map.expr_syntax(d.used_at), source_map.expr_syntax(d.moved_at))
+            {
+                acc.push(UseAfterMove { expr, moved_at, name }.into());
+            }
+        }
+
         for diagnostic in BodyValidationDiagnostic::collect(db, self.into()) {
             match diagnostic {
                 BodyValidationDiagnostic::RecordMissingFields {
@@ -1338,6 +1497,7 @@ impl DefWithBody {
             DefWithBody::Function(it) => it.into(),
             DefWithBody::Static(it) => it.into(),
             DefWithBody::Const(it) => it.into(),
+            DefWithBody::Variant(it) => it.into(),
         };
         for diag in hir_ty::diagnostics::incorrect_case(db, krate, def.into()) {
             acc.push(diag.into())
@@ -1345,6 +1505,16 @@ impl DefWithBody {
     }
 }
 
+/// An operation which needs `unsafe`, see [`DefWithBody::unsafe_operations`].
+#[derive(Debug, Clone)]
+pub struct UnsafeOperation {
+    pub kind: UnsafeExprKind,
+    pub expr: InFile<AstPtr<ast::Expr>>,
+    /// The innermost `unsafe` block the operation is in. Operations outside of `unsafe` blocks
+    /// are only allowed in the body of an `unsafe fn`.
+    pub unsafe_block: Option<InFile<AstPtr<ast::Expr>>>,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Function {
     pub(crate) id: FunctionId,
@@ -1436,6 +1606,28 @@ impl Function {
         db.function_data(self.id).has_body()
     }
 
+    /// The ABI of `extern "abi" fn` definitions and of functions declared in `extern "abi"`
+    /// blocks, `"C"` when left out.
+    pub fn abi(self, db: &dyn HirDatabase) -> Option<SmolStr> {
+        let abi = match self.id.lookup(db.upcast()).container {
+            ItemContainerId::ExternBlockId(block) => {
+                let loc = block.lookup(db.upcast());
+                loc.id.item_tree(db.upcast())[loc.id.value].abi.clone()
+            }
+            _ => db.function_data(self.id).abi.clone(),
+        };
+        abi.map(|it| SmolStr::new(&*it))
+    }
+
+    /// Whether this function is declared in an `extern` block, rather than defined in Rust.
+    pub fn is_in_extern_block(self, db: &dyn HirDatabase) -> bool {
+        db.function_data(self.id).is_in_extern_block()
+    }
+
+    pub fn is_varargs(self, db: &dyn HirDatabase) -> bool {
+        db.function_data(self.id).is_varargs()
+    }
+
     pub fn as_proc_macro(self, db: &dyn HirDatabase) -> Option<Macro> {
         let function_data = db.function_data(self.id);
         let attrs = &function_data.attrs;
@@ -1464,6 +1656,61 @@ impl Function {
         result
     }
 
+    /// The body of this function with `?`, `for` loops, `.await`, and overloaded indexing and
+    /// derefs desugared, along with the source range of every printed expression.
+    pub fn desugared_body(self, db: &dyn HirDatabase) -> (String, Vec<(TextRange, FileRange)>) {
+        let body = hir_ty::desugared_body(db, self.id.into());
+        let (_, source_map) = db.body_with_source_map(self.id.into());
+        let ranges = body
+            .exprs
+            .into_iter()
+            .filter_map(|(range, expr)| {
+                let src = source_map.expr_syntax(expr).ok()?;
+                let root = db.parse_or_expand(src.file_id)?;
+                let node = src.value.to_node(&root);
+                Some((range, src.with_value(node.syntax()).original_file_range(db.upcast())))
+            })
+            .collect();
+        (body.text, ranges)
+    }
+
+    /// A textual dump of what inference gave up on in this function's body: goals the trait
+    /// solver overflowed on, together with the program they were solved against, and types
+    /// autoderef stopped at. Meant for inclusion in bug reports.
+    pub fn debug_trait_solver_overflows(self, db: &dyn HirDatabase) -> String {
+        let infer = db.infer(self.id.into());
+        let (_, source_map) = db.body_with_source_map(self.id.into());
+        let krate = self.krate_id(db);
+
+        let mut result = String::new();
+        for d in &infer.diagnostics {
+            if let hir_ty::InferenceDiagnostic::TraitSolverOverflow { expr, overflow } = d {
+                let location = match source_map.expr_syntax(*expr) {
+                    Ok(src) => format!("{:?}", src.value.syntax_node_ptr().text_range()),
+                    Err(SyntheticSyntax) => format!("{:?}", expr),
+                };
+                let details = match overflow {
+                    hir_ty::Overflow::Goal(goal, _) => {
+                        hir_ty::traits::dump_trait_solve(db, krate, goal.clone())
+                    }
+                    hir_ty::Overflow::Autoderef(ty) => format!(
+                        "autoderef reached the recursion limit at `{}`",
+                        ty.value.display(db)
+                    ),
+                };
+                format_to!(
+                    result,
+                    "Trait solver overflow at {} in the body of `{}`:\n{}\n\n",
+                    location,
+                    self.name(db),
+                    details
+                );
+            }
+        }
+
+        result
+    }
+
     fn krate_id(self, db: &dyn HirDatabase) -> CrateId {
         self.id.lookup(db.upcast()).module(db.upcast()).krate()
     }
@@ -1545,11 +1792,20 @@ impl SelfParam {
             .unwrap_or(Access::Owned)
     }
 
-    pub fn display(self, db: &dyn HirDatabase) -> &'static str {
-        match self.access(db) {
-            Access::Shared => "&self",
-            Access::Exclusive => "&mut self",
-            Access::Owned => "self",
+    /// Renders the self parameter as it would be written in the function signature, including
+    /// the explicit type of arbitrary self receivers like `self: Rc<Self>`.
+    pub fn display(self, db: &dyn HirDatabase) -> String {
+        let func_data = db.function_data(self.func);
+        match func_data.params.first().map(|(_, param)| &**param) {
+            Some(TypeRef::Path(p)) if p.is_self_type() => "self".to_owned(),
+            Some(TypeRef::Reference(inner, _, mutability)) if matches!(&**inner, TypeRef::Path(p) if p.is_self_type()) => {
+                match mutability {
+                    hir_def::type_ref::Mutability::Shared => "&self".to_owned(),
+                    hir_def::type_ref::Mutability::Mut => "&mut self".to_owned(),
+                }
+            }
+            Some(ty) => format!("self: {}", ty.display(db)),
+            None => "self".to_owned(),
         }
     }
 
@@ -1607,6 +1863,25 @@ impl Const {
     pub fn eval(self, db: &dyn HirDatabase) -> Result<ComputedExpr, ConstEvalError> {
         db.const_eval(self.id)
     }
+
+    /// Evaluates the constant, returning its value if it's a `bool`.
+    pub fn eval_bool(self, db: &dyn HirDatabase) -> Option<bool> {
+        match self.eval(db) {
+            Ok(ComputedExpr::Literal(hir_def::expr::Literal::Bool(it))) => Some(it),
+            _ => None,
+        }
+    }
+
+    /// Evaluates the constant, returning its value if it's a non-negative integer.
+    pub fn eval_bits(self, db: &dyn HirDatabase) -> Option<u128> {
+        match self.eval(db) {
+            Ok(ComputedExpr::Literal(hir_def::expr::Literal::Uint(it, _))) => Some(it),
+            Ok(ComputedExpr::Literal(hir_def::expr::Literal::Int(it, _))) if it >= 0 => {
+                Some(it as u128)
+            }
+            _ => None,
+        }
+    }
 }
 
 impl HasVisibility for Const {
@@ -1681,6 +1956,24 @@ impl Trait {
         db.trait_data(self.id).is_auto
     }
 
+    /// The supertraits of the trait, direct and transitive, not including itself.
+    pub fn all_supertraits(self, db: &dyn HirDatabase) -> Vec<Trait> {
+        hir_ty::all_super_traits(db.upcast(), self.id)
+            .into_iter()
+            .filter(|&it| it != self.id)
+            .map(Trait::from)
+            .collect()
+    }
+
+    /// The number of generic parameters of the trait, not counting `Self`.
+    pub fn type_or_const_param_count(self, db: &dyn HirDatabase) -> usize {
+        db.generic_params(self.id.into())
+            .type_or_consts
+            .iter()
+            .filter(|(_, param)| !param.is_trait_self())
+            .count()
+    }
+
     pub fn is_unsafe(&self, db: &dyn HirDatabase) -> bool {
         db.trait_data(self.id).is_unsafe
     }
@@ -1849,6 +2142,33 @@ impl Macro {
         }
     }
 
+    /// Whether this is a builtin derive which accepts `helper` as an attribute
+    /// on the item, like `#[default]` for `Default` on newer toolchains.
+    pub fn has_builtin_derive_helper(&self, db: &dyn HirDatabase, helper: &str) -> bool {
+        if !self.is_builtin_derive(db) {
+            return false;
+        }
+        let attrs = self.attrs(db);
+        let tt = match attrs.by_key("rustc_builtin_macro").tt_values().next() {
+            Some(it) => it,
+            None => return false,
+        };
+        // `#[rustc_builtin_macro(Default, attributes(default))]`
+        let helpers = tt
+            .token_trees
+            .iter()
+            .skip_while(|it| {
+                !matches!(it, TokenTree::Leaf(Leaf::Ident(Ident { text, .. })) if text == "attributes")
+            })
+            .nth(1);
+        match helpers {
+            Some(TokenTree::Subtree(helpers)) => helpers.token_trees.iter().any(
+                |it| matches!(it, TokenTree::Leaf(Leaf::Ident(Ident { text, .. })) if text == helper),
+            ),
+            _ => false,
+        }
+    }
+
     pub fn is_attr(&self, db: &dyn HirDatabase) -> bool {
         matches!(self.kind(db), MacroKind::Attr)
     }
@@ -2561,13 +2881,18 @@ impl Impl {
     pub fn all_for_trait(db: &dyn HirDatabase, trait_: Trait) -> Vec<Impl> {
         let krate = trait_.module(db).krate();
         let mut all = Vec::new();
-        for Crate { id } in krate.transitive_reverse_dependencies(db).into_iter() {
-            let impls = db.trait_impls_in_crate(id);
-            all.extend(impls.for_trait(trait_.id).map(Self::from))
+        for krate in krate.transitive_reverse_dependencies(db).into_iter() {
+            all.extend(Self::all_for_trait_in_crate(db, trait_, krate));
         }
         all
     }
 
+    /// The impls of `trait_` in `krate`, which only needs the name resolution of
+    /// `krate`. Only crates depending on the trait's crate can have any.
+    pub fn all_for_trait_in_crate(db: &dyn HirDatabase, trait_: Trait, krate: Crate) -> Vec<Impl> {
+        db.trait_impls_in_crate(krate.id).for_trait(trait_.id).map(Self::from).collect()
+    }
+
     // FIXME: the return type is wrong. This should be a hir version of
     // `TraitRef` (to account for parameters and qualifiers)
     pub fn trait_(self, db: &dyn HirDatabase) -> Option<Trait> {
@@ -2601,6 +2926,39 @@ impl Impl {
         let src = self.source(db)?;
         src.file_id.is_builtin_derive(db.upcast())
     }
+
+    /// Returns the `#[derive]` attribute this impl was generated from, if any.
+    pub fn derive_attr(self, db: &dyn HirDatabase) -> Option<InFile<ast::Attr>> {
+        let src = self.source(db)?;
+        src.file_id.is_derive(db.upcast())
+    }
+
+    fn coherence_diagnostics(self, db: &dyn HirDatabase, acc: &mut Vec<AnyDiagnostic>) {
+        let trait_ = match self.trait_(db) {
+            Some(it) => it,
+            None => return,
+        };
+        let impl_ = match self.source(db) {
+            Some(it) => it.map(|it| AstPtr::new(&it)),
+            None => return,
+        };
+        if hir_ty::diagnostics::is_orphan_impl(db, self.id) {
+            acc.push(OrphanImpl { impl_: impl_.clone() }.into());
+        }
+        for other in hir_ty::diagnostics::overlapping_impls(db, self.id) {
+            if let Some(other) = Impl::from(other).source(db) {
+                acc.push(
+                    OverlappingImpls {
+                        impl_: impl_.clone(),
+                        other: other.map(|it| AstPtr::new(&it)),
+                        trait_name: trait_.name(db),
+                        self_ty: self.self_ty(db),
+                    }
+                    .into(),
+                );
+            }
+        }
+    }
 }
 
 #[derive(Clone, PartialEq, Eq, Debug)]
@@ -2693,6 +3051,14 @@ impl Type {
         matches!(self.ty.kind(Interner), TyKind::Slice(..))
     }
 
+    pub fn is_str(&self) -> bool {
+        matches!(self.ty.kind(Interner), TyKind::Str)
+    }
+
+    pub fn is_tuple(&self) -> bool {
+        matches!(self.ty.kind(Interner), TyKind::Tuple(..))
+    }
+
     pub fn is_usize(&self) -> bool {
         matches!(self.ty.kind(Interner), TyKind::Scalar(Scalar::Uint(UintTy::Usize)))
     }
@@ -2856,10 +3222,38 @@ impl Type {
         }
     }
 
+    /// The size and alignment of the type, for the types simple enough for them to be known.
+    pub fn layout(&self, db: &dyn HirDatabase) -> Option<Layout> {
+        hir_ty::layout::layout_of_ty(db, &self.ty, self.krate)
+    }
+
     pub fn is_raw_ptr(&self) -> bool {
         matches!(&self.ty.kind(Interner), TyKind::Raw(..))
     }
 
+    pub fn as_raw_ptr(&self) -> Option<(Type, Mutability)> {
+        match self.ty.kind(Interner) {
+            TyKind::Raw(m, ty) => Some((
+                self.derived(ty.clone()),
+                Mutability::from_mutable(matches!(m, hir_ty::Mutability::Mut)),
+            )),
+            _ => None,
+        }
+    }
+
+    /// The element type and length of an array type whose length is known.
+    pub fn as_array(&self) -> Option<(Type, u64)> {
+        match self.ty.kind(Interner) {
+            TyKind::Array(ty, len) => Some((self.derived(ty.clone()), len.as_usize()?)),
+            _ => None,
+        }
+    }
+
+    /// Whether this is a function pointer type, like `fn(u8) -> u8`.
+    pub fn is_fn_ptr(&self) -> bool {
+        matches!(&self.ty.kind(Interner), TyKind::Function(_))
+    }
+
     pub fn contains_unknown(&self) -> bool {
         return go(&self.ty);
 
@@ -2926,6 +3320,73 @@ impl Type {
         }
     }
 
+    /// The types this type is built from, each with a description of where it
+    /// appears. Unless there is an explicit impl, a type implements an auto
+    /// trait like `Send` if and only if all of these do.
+    pub fn auto_trait_components(&self, db: &dyn HirDatabase) -> Vec<(String, Type)> {
+        let fields_of = |variant: VariantDef, substs: &Substitution| {
+            let field_types = db.field_types(variant.into());
+            variant
+                .fields(db)
+                .into_iter()
+                .map(|field| {
+                    let ty = field_types[field.id].clone().substitute(Interner, substs);
+                    (field.name(db), self.derived(ty))
+                })
+                .collect::<Vec<_>>()
+        };
+        match self.ty.kind(Interner) {
+            TyKind::Adt(hir_ty::AdtId(adt_id), substs) => match Adt::from(*adt_id) {
+                Adt::Struct(it) => fields_of(it.into(), substs)
+                    .into_iter()
+                    .map(|(name, ty)| (format!("field `{}`", name), ty))
+                    .collect(),
+                Adt::Union(it) => fields_of(it.into(), substs)
+                    .into_iter()
+                    .map(|(name, ty)| (format!("field `{}`", name), ty))
+                    .collect(),
+                Adt::Enum(it) => it
+                    .variants(db)
+                    .into_iter()
+                    .flat_map(|variant| {
+                        let variant_name = variant.name(db);
+                        fields_of(variant.into(), substs).into_iter().map(move |(name, ty)| {
+                            (format!("field `{}` of variant `{}`", name, variant_name), ty)
+                        })
+                    })
+                    .collect(),
+            },
+            TyKind::Tuple(_, substs) => substs
+                .iter(Interner)
+                .enumerate()
+                .map(|(idx, ty)| {
+                    (format!("element {}", idx), self.derived(ty.assert_ty_ref(Interner).clone()))
+                })
+                .collect(),
+            TyKind::Array(ty, _) | TyKind::Slice(ty) => {
+                vec![("element type".to_string(), self.derived(ty.clone()))]
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Returns the generic parameter this type is, inside of the item declaring it.
+    pub fn as_type_param(&self, db: &dyn HirDatabase) -> Option<TypeParam> {
+        match self.ty.kind(Interner) {
+            TyKind::Placeholder(idx) => {
+                let id = hir_ty::from_placeholder_idx(db, *idx);
+                Some(TypeParam { id: TypeParamId::from_unchecked(id) })
+            }
+            _ => None,
+        }
+    }
+
+    /// Checks whether both types have the same constructor, ignoring their
+    /// generic arguments, like impls are matched against a type.
+    pub fn equals_ctor(&self, other: &Type) -> bool {
+        self.ty.equals_ctor(&other.ty)
+    }
+
     pub fn autoderef<'a>(&'a self, db: &'a dyn HirDatabase) -> impl Iterator<Item = Type> + 'a {
         self.autoderef_(db).map(move |ty| self.derived(ty))
     }
@@ -3271,6 +3732,33 @@ impl Type {
         hir_ty::could_unify(db, self.env.clone(), &tys)
     }
 
+    /// If this type is `alias` instantiated with some type arguments, returns those arguments.
+    pub fn as_instance_of_alias(
+        &self,
+        db: &dyn HirDatabase,
+        alias: TypeAlias,
+    ) -> Option<Vec<Type>> {
+        let alias_ty = db.ty(alias.id.into());
+        if !alias_ty.binders.iter(Interner).all(|kind| matches!(kind, hir_ty::VariableKind::Ty(_)))
+        {
+            return None;
+        }
+        let tys = hir_ty::make_canonical(
+            (alias_ty.skip_binders().clone(), self.ty.clone()),
+            alias_ty.binders.iter(Interner).map(|_| TyVariableKind::General),
+        );
+        let subst = hir_ty::unify(db, self.env.clone(), &tys)?;
+        subst
+            .iter(Interner)
+            .map(|arg| {
+                // Parameters the alias doesn't constrain are left as bound variables.
+                let ty = arg.ty(Interner)?;
+                (!matches!(ty.kind(Interner), TyKind::BoundVar(_)))
+                    .then(|| self.derived(ty.clone()))
+            })
+            .collect()
+    }
+
     pub fn could_coerce_to(&self, db: &dyn HirDatabase, to: &Type) -> bool {
         let tys = hir_ty::replace_errors_with_variables(&(self.ty.clone(), to.ty.clone()));
         hir_ty::could_coerce(db, self.env.clone(), &tys)
@@ -3345,6 +3833,31 @@ impl Callable {
     }
 }
 
+/// A where-clause of a generic function, with the generic arguments of one of its calls
+/// substituted.
+#[derive(Clone, Debug)]
+pub struct CallObligation {
+    krate: CrateId,
+    env: Arc<TraitEnvironment>,
+    predicate: QuantifiedWhereClause,
+}
+
+impl CallObligation {
+    /// Whether the where-clause holds at the call, or `None` if the trait solver can't tell,
+    /// usually because some of the types aren't known.
+    pub fn is_satisfied(&self, db: &dyn HirDatabase) -> Option<bool> {
+        let goal = Canonical {
+            value: InEnvironment::new(&self.env.env, self.predicate.clone().cast(Interner)),
+            binders: CanonicalVarKinds::empty(Interner),
+        };
+        match db.trait_solve(self.krate, goal) {
+            Some(Solution::Unique(_)) => Some(true),
+            Some(Solution::Ambig(_)) => None,
+            None => Some(false),
+        }
+    }
+}
+
 /// For IDE only
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum ScopeDef {
@@ -30,9 +30,9 @@ use crate::{
     db::HirDatabase,
     semantics::source_to_def::{ChildContainer, SourceToDefCache, SourceToDefCtx},
     source_analyzer::{resolve_hir_path, SourceAnalyzer},
-    Access, AssocItem, BuiltinAttr, Callable, ConstParam, Crate, Field, Function, HasSource,
-    HirFileId, Impl, InFile, Label, LifetimeParam, Local, Macro, Module, ModuleDef, Name, Path,
-    ScopeDef, ToolModule, Trait, Type, TypeAlias, TypeParam, VariantDef,
+    Access, AssocItem, BuiltinAttr, CallObligation, Callable, ConstParam, Crate, Field, Function,
+    HasSource, HirFileId, Impl, InFile, Label, LifetimeParam, Local, Macro, Module, ModuleDef,
+    Name, Path, ScopeDef, ToolModule, Trait, Type, TypeAlias, TypeParam, VariantDef,
 };
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -164,6 +164,12 @@ impl<'db, DB: HirDatabase> Semantics<'db, DB> {
         self.imp.expand_derive_macro(derive)
     }
 
+    /// Expands each path of the given `#[derive]` attribute separately, yielding `None` for paths
+    /// that failed to resolve or expand.
+    pub fn derive_macro_expansions(&self, derive: &ast::Attr) -> Option<Vec<Option<SyntaxNode>>> {
+        self.imp.derive_macro_expansions(derive)
+    }
+
     pub fn is_attr_macro_call(&self, item: &ast::Item) -> bool {
         self.imp.is_attr_macro_call(item)
     }
@@ -339,6 +345,47 @@ impl<'db, DB: HirDatabase> Semantics<'db, DB> {
         self.imp.resolve_method_call_as_callable(call)
     }
 
+    /// Resolves the operator trait method called by `bin_expr`, e.g. `Add::add` for `a + b`.
+    /// Points at the impl method when the impl is known.
+    pub fn resolve_bin_expr(&self, bin_expr: &ast::BinExpr) -> Option<Function> {
+        self.imp.resolve_bin_expr(bin_expr).map(Function::from)
+    }
+
+    pub fn resolve_index_expr(&self, index_expr: &ast::IndexExpr) -> Option<Function> {
+        self.imp.resolve_index_expr(index_expr).map(Function::from)
+    }
+
+    /// The where-clauses of the function called by `call`, instantiated with the generic
+    /// arguments inferred for the call.
+    pub fn call_obligations(&self, call: &ast::Expr) -> Option<Vec<CallObligation>> {
+        self.imp.call_obligations(call)
+    }
+
+    /// Whether some value could be matched by both patterns from the arms of `match_expr`.
+    /// Answers `true` when unsure.
+    pub fn match_patterns_overlap(
+        &self,
+        match_expr: &ast::MatchExpr,
+        pat: &ast::Pat,
+        other: &ast::Pat,
+    ) -> bool {
+        self.imp.match_patterns_overlap(match_expr, pat, other)
+    }
+
+    /// For each arm, given as a pattern from `match_expr` and whether it has a guard, whether it
+    /// would be reachable if `match_expr` had exactly these arms, in this order.
+    pub fn match_arms_reachability(
+        &self,
+        match_expr: &ast::MatchExpr,
+        arms: &[(ast::Pat, bool)],
+    ) -> Option<Vec<bool>> {
+        self.imp.match_arms_reachability(match_expr, arms)
+    }
+
+    pub fn resolve_try_expr(&self, try_expr: &ast::TryExpr) -> Option<Function> {
+        self.imp.resolve_try_expr(try_expr).map(Function::from)
+    }
+
     pub fn resolve_field(&self, field: &ast::FieldExpr) -> Option<Field> {
         self.imp.resolve_field(field)
     }
@@ -492,10 +539,15 @@ impl<'db> SemanticsImpl<'db> {
     }
 
     fn expand_derive_macro(&self, attr: &ast::Attr) -> Option<Vec<SyntaxNode>> {
-        let res: Vec<_> = self
+        let res = self.derive_macro_expansions(attr)?.into_iter().flatten().collect();
+        Some(res)
+    }
+
+    fn derive_macro_expansions(&self, attr: &ast::Attr) -> Option<Vec<Option<SyntaxNode>>> {
+        let res = self
             .derive_macro_calls(attr)?
             .into_iter()
-            .flat_map(|call| {
+            .map(|call| {
                 let file_id = call?.as_file();
                 let node = self.db.parse_or_expand(file_id)?;
                 self.cache(node.clone(), file_id);
@@ -944,6 +996,41 @@ impl<'db> SemanticsImpl<'db> {
         Some(res)
     }
 
+    fn resolve_bin_expr(&self, bin_expr: &ast::BinExpr) -> Option<FunctionId> {
+        self.analyze(bin_expr.syntax()).resolve_bin_expr(self.db, bin_expr)
+    }
+
+    fn resolve_index_expr(&self, index_expr: &ast::IndexExpr) -> Option<FunctionId> {
+        self.analyze(index_expr.syntax()).resolve_index_expr(self.db, index_expr)
+    }
+
+    fn call_obligations(&self, call: &ast::Expr) -> Option<Vec<CallObligation>> {
+        self.analyze(call.syntax()).call_obligations(self.db, call)
+    }
+
+    fn match_patterns_overlap(
+        &self,
+        match_expr: &ast::MatchExpr,
+        pat: &ast::Pat,
+        other: &ast::Pat,
+    ) -> bool {
+        self.analyze(match_expr.syntax())
+            .match_patterns_overlap(self.db, match_expr, pat, other)
+            .unwrap_or(true)
+    }
+
+    fn match_arms_reachability(
+        &self,
+        match_expr: &ast::MatchExpr,
+        arms: &[(ast::Pat, bool)],
+    ) -> Option<Vec<bool>> {
+        self.analyze(match_expr.syntax()).match_arms_reachability(self.db, match_expr, arms)
+    }
+
+    fn resolve_try_expr(&self, try_expr: &ast::TryExpr) -> Option<FunctionId> {
+        self.analyze(try_expr.syntax()).resolve_try_expr(self.db, try_expr)
+    }
+
     fn resolve_field(&self, field: &ast::FieldExpr) -> Option<Field> {
         self.analyze(field.syntax()).resolve_field(self.db, field)
     }
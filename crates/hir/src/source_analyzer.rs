@@ -18,30 +18,35 @@ use hir_def::{
     },
     expr::{ExprId, Pat, PatId},
     macro_id_to_def_id,
-    path::{ModPath, Path, PathKind},
+    path::{path, ModPath, Path, PathKind},
     resolver::{resolver_for_scope, Resolver, TypeNs, ValueNs},
     type_ref::Mutability,
     AsMacroCall, DefWithBodyId, FieldId, FunctionId, LocalFieldId, Lookup, ModuleDefId, VariantId,
 };
 use hir_expand::{
-    builtin_fn_macro::BuiltinFnLikeExpander, hygiene::Hygiene, name::AsName, HirFileId, InFile,
+    builtin_fn_macro::BuiltinFnLikeExpander,
+    hygiene::Hygiene,
+    name::{name, AsName},
+    HirFileId, InFile,
 };
 use hir_ty::{
     diagnostics::{
         record_literal_missing_fields, record_pattern_missing_fields, unsafe_expressions,
-        UnsafeExpr,
+        MatchArmsCheck, UnsafeExpr,
     },
-    Adjust, Adjustment, AutoBorrow, InferenceResult, Interner, Substitution, TyExt,
-    TyLoweringContext,
+    method_resolution, Adjust, Adjustment, AliasEq, AliasTy, AutoBorrow, CallableDefId,
+    InferenceResult, Interner, Substitution, TraitEnvironment, TraitRefExt, TyBuilder, TyExt,
+    TyKind, TyLoweringContext, WhereClause,
 };
 use syntax::{
     ast::{self, AstNode},
-    SyntaxKind, SyntaxNode, TextRange, TextSize,
+    SmolStr, SyntaxKind, SyntaxNode, TextRange, TextSize,
 };
 
 use crate::{
-    db::HirDatabase, semantics::PathResolution, Adt, BuiltinAttr, BuiltinType, Const, Field,
-    Function, Local, Macro, ModuleDef, Static, Struct, ToolModule, Trait, Type, TypeAlias, Variant,
+    db::HirDatabase, semantics::PathResolution, Adt, BuiltinAttr, BuiltinType, CallObligation,
+    Const, Field, Function, Local, Macro, ModuleDef, Static, Struct, ToolModule, Trait, Type,
+    TypeAlias, Variant,
 };
 use base_db::CrateId;
 
@@ -208,6 +213,161 @@ impl SourceAnalyzer {
         self.infer.as_ref()?.method_resolution(expr_id)
     }
 
+    pub(crate) fn resolve_bin_expr(
+        &self,
+        db: &dyn HirDatabase,
+        bin_expr: &ast::BinExpr,
+    ) -> Option<FunctionId> {
+        let expr_id = self.expr_id(db, &bin_expr.clone().into())?;
+        let (func, subst) = self.infer.as_ref()?.method_resolution(expr_id)?;
+        Some(self.resolve_impl_method(db, func, subst))
+    }
+
+    pub(crate) fn resolve_index_expr(
+        &self,
+        db: &dyn HirDatabase,
+        index_expr: &ast::IndexExpr,
+    ) -> Option<FunctionId> {
+        let base_id = self.expr_id(db, &index_expr.base()?)?;
+        let index_id = self.expr_id(db, &index_expr.index()?)?;
+        let infer = self.infer.as_ref()?;
+        let base_ty = infer
+            .expr_adjustments
+            .get(&base_id)
+            .and_then(|adjusts| adjusts.last().map(|adjust| adjust.target.clone()))
+            .unwrap_or_else(|| infer[base_id].clone());
+
+        let krate = self.resolver.krate()?;
+        let index_trait = db.lang_item(krate, name![index].to_smol_str())?.as_trait()?;
+        let func = db.trait_data(index_trait).method_by_name(&name![index])?;
+        let subst = TyBuilder::subst_for_def(db, func)
+            .push(base_ty.strip_references().clone())
+            .push(infer[index_id].clone())
+            .fill_with_unknown()
+            .build();
+        Some(self.resolve_impl_method(db, func, subst))
+    }
+
+    pub(crate) fn match_patterns_overlap(
+        &self,
+        db: &dyn HirDatabase,
+        match_expr: &ast::MatchExpr,
+        pat: &ast::Pat,
+        other: &ast::Pat,
+    ) -> Option<bool> {
+        let check = self.match_arms_check(db, match_expr)?;
+        Some(check.overlap(db, self.pat_id(pat)?, self.pat_id(other)?))
+    }
+
+    pub(crate) fn match_arms_reachability(
+        &self,
+        db: &dyn HirDatabase,
+        match_expr: &ast::MatchExpr,
+        arms: &[(ast::Pat, bool)],
+    ) -> Option<Vec<bool>> {
+        let check = self.match_arms_check(db, match_expr)?;
+        let arms = arms
+            .iter()
+            .map(|(pat, has_guard)| Some((self.pat_id(pat)?, *has_guard)))
+            .collect::<Option<Vec<_>>>()?;
+        check.reachability(db, &arms)
+    }
+
+    fn match_arms_check(
+        &self,
+        db: &dyn HirDatabase,
+        match_expr: &ast::MatchExpr,
+    ) -> Option<MatchArmsCheck> {
+        let (def, ..) = self.def.as_ref()?;
+        MatchArmsCheck::new(db, *def, self.expr_id(db, &match_expr.clone().into())?)
+    }
+
+    pub(crate) fn call_obligations(
+        &self,
+        db: &dyn HirDatabase,
+        call: &ast::Expr,
+    ) -> Option<Vec<CallObligation>> {
+        let infer = self.infer.as_ref()?;
+        let (func, subst) = match call {
+            ast::Expr::MethodCallExpr(_) => infer.method_resolution(self.expr_id(db, call)?)?,
+            ast::Expr::CallExpr(call) => {
+                let callee = &infer[self.expr_id(db, &call.expr()?)?];
+                match (callee.callable_def(db)?, callee.kind(Interner)) {
+                    (CallableDefId::FunctionId(func), TyKind::FnDef(_, subst)) => {
+                        (func, subst.clone())
+                    }
+                    _ => return None,
+                }
+            }
+            _ => return None,
+        };
+
+        let krate = self.resolver.krate()?;
+        let env = match self.resolver.generic_def() {
+            Some(def) => db.trait_environment(def),
+            None => Arc::new(TraitEnvironment::empty(krate)),
+        };
+        // Every type parameter is `Sized` unless it opts out, listing that is just noise. So are
+        // clauses about types which weren't inferred, as nothing can be said about them.
+        let sized_trait = db
+            .lang_item(krate, SmolStr::new_inline("sized"))
+            .and_then(|lang_item| lang_item.as_trait());
+        let has_unknown = |args: &Substitution| {
+            args.iter(Interner).filter_map(|arg| arg.ty(Interner)).any(|ty| ty.is_unknown())
+        };
+        let obligations = db
+            .generic_predicates(func.into())
+            .iter()
+            .map(|predicate| predicate.clone().substitute(Interner, &subst))
+            .filter(|predicate| match predicate.skip_binders() {
+                WhereClause::Implemented(trait_ref) => {
+                    Some(trait_ref.hir_trait_id()) != sized_trait
+                        && !has_unknown(&trait_ref.substitution)
+                }
+                WhereClause::AliasEq(AliasEq { alias: AliasTy::Projection(projection), ty }) => {
+                    !has_unknown(&projection.substitution) && !ty.is_unknown()
+                }
+                WhereClause::AliasEq(_)
+                | WhereClause::LifetimeOutlives(_)
+                | WhereClause::TypeOutlives(_) => false,
+            })
+            .map(|predicate| CallObligation { krate, env: env.clone(), predicate })
+            .collect();
+        Some(obligations)
+    }
+
+    pub(crate) fn resolve_try_expr(
+        &self,
+        db: &dyn HirDatabase,
+        try_expr: &ast::TryExpr,
+    ) -> Option<FunctionId> {
+        let expr_id = self.expr_id(db, &try_expr.expr()?)?;
+        let ty = self.infer.as_ref()?[expr_id].clone();
+
+        // FIXME resolve via lang_item once try v2 is stable
+        let try_trait = self.resolver.resolve_known_trait(db.upcast(), &path![core::ops::Try])?;
+        let func = db.trait_data(try_trait).method_by_name(&name![branch])?;
+        let subst = TyBuilder::subst_for_def(db, func).push(ty).fill_with_unknown().build();
+        Some(self.resolve_impl_method(db, func, subst))
+    }
+
+    fn resolve_impl_method(
+        &self,
+        db: &dyn HirDatabase,
+        func: FunctionId,
+        subst: Substitution,
+    ) -> FunctionId {
+        let krate = match self.resolver.krate() {
+            Some(krate) => krate,
+            None => return func,
+        };
+        let env = match self.resolver.generic_def() {
+            Some(def) => db.trait_environment(def),
+            None => Arc::new(TraitEnvironment::empty(krate)),
+        };
+        method_resolution::lookup_impl_method(db, env, func, subst)
+    }
+
     pub(crate) fn resolve_field(
         &self,
         db: &dyn HirDatabase,
@@ -518,9 +678,7 @@ impl SourceAnalyzer {
                         *def,
                         body,
                         expr_id,
-                        &mut |UnsafeExpr { inside_unsafe_block, .. }| {
-                            is_unsafe |= !inside_unsafe_block
-                        },
+                        &mut |UnsafeExpr { unsafe_block, .. }| is_unsafe |= unsafe_block.is_none(),
                     );
                     if is_unsafe {
                         return true;
@@ -2,8 +2,9 @@
 
 use base_db::FileRange;
 use hir_def::{
-    item_tree::ItemTreeNode, src::HasSource, AdtId, AssocItemId, AssocItemLoc, DefWithBodyId,
-    HasModule, ImplId, ItemContainerId, Lookup, MacroId, ModuleDefId, ModuleId, TraitId,
+    item_tree::ItemTreeNode, src::HasSource, AdtId, AssocItemId, AssocItemLoc, AttrDefId,
+    DefWithBodyId, HasModule, ImplId, ItemContainerId, Lookup, MacroId, ModuleDefId, ModuleId,
+    TraitId,
 };
 use hir_expand::{HirFileId, InFile};
 use hir_ty::db::HirDatabase;
@@ -19,6 +20,8 @@ pub struct FileSymbol {
     pub loc: DeclarationLocation,
     pub kind: FileSymbolKind,
     pub container_name: Option<SmolStr>,
+    /// Whether this symbol is a `#[doc(alias)]` of the declaration rather than its actual name.
+    pub is_alias: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -46,6 +49,12 @@ impl DeclarationLocation {
         let node = resolve_node(db, self.hir_file_id, &self.name_ptr)?;
         node.as_ref().original_file_range_opt(db.upcast())
     }
+
+    /// The declared name, which differs from [`FileSymbol::name`] for doc aliases.
+    pub fn declared_name(&self, db: &dyn HirDatabase) -> Option<SmolStr> {
+        let node = resolve_node(db, self.hir_file_id, &self.name_ptr)?;
+        Some(node.value.text().to_string().into())
+    }
 }
 
 fn resolve_node(
@@ -242,6 +251,9 @@ impl<'a> SymbolCollector<'a> {
             DefWithBodyId::ConstId(id) => Some(
                 id.lookup(self.db.upcast()).source(self.db.upcast()).value.name()?.text().into(),
             ),
+            DefWithBodyId::VariantId(id) => {
+                Some(self.db.enum_data(id.parent).variants[id.local_id].name.to_smol_str())
+            }
         }
     }
 
@@ -255,7 +267,7 @@ impl<'a> SymbolCollector<'a> {
 
     fn push_decl_assoc<L, T>(&mut self, id: L, kind: FileSymbolKind)
     where
-        L: Lookup<Data = AssocItemLoc<T>>,
+        L: Lookup<Data = AssocItemLoc<T>> + Into<AttrDefId> + Copy,
         T: ItemTreeNode,
         <T as ItemTreeNode>::Source: HasName,
     {
@@ -273,7 +285,7 @@ impl<'a> SymbolCollector<'a> {
             }
         }
 
-        self.push_file_symbol(|s| {
+        self.push_file_symbol(id.into(), |s| {
             let loc = id.lookup(s.db.upcast());
             let source = loc.source(s.db.upcast());
             let name_node = source.value.name()?;
@@ -289,17 +301,18 @@ impl<'a> SymbolCollector<'a> {
                     ptr: SyntaxNodePtr::new(source.value.syntax()),
                     name_ptr: SyntaxNodePtr::new(name_node.syntax()),
                 },
+                is_alias: false,
             })
         })
     }
 
     fn push_decl<L>(&mut self, id: L, kind: FileSymbolKind)
     where
-        L: Lookup,
+        L: Lookup + Into<AttrDefId> + Copy,
         <L as Lookup>::Data: HasSource,
         <<L as Lookup>::Data as HasSource>::Value: HasName,
     {
-        self.push_file_symbol(|s| {
+        self.push_file_symbol(id.into(), |s| {
             let loc = id.lookup(s.db.upcast());
             let source = loc.source(s.db.upcast());
             let name_node = source.value.name()?;
@@ -313,12 +326,13 @@ impl<'a> SymbolCollector<'a> {
                     ptr: SyntaxNodePtr::new(source.value.syntax()),
                     name_ptr: SyntaxNodePtr::new(name_node.syntax()),
                 },
+                is_alias: false,
             })
         })
     }
 
     fn push_module(&mut self, module_id: ModuleId) {
-        self.push_file_symbol(|s| {
+        self.push_file_symbol(module_id.into(), |s| {
             let def_map = module_id.def_map(s.db.upcast());
             let module_data = &def_map[module_id.local_id];
             let declaration = module_data.origin.declaration()?;
@@ -334,12 +348,20 @@ impl<'a> SymbolCollector<'a> {
                     ptr: SyntaxNodePtr::new(module.syntax()),
                     name_ptr: SyntaxNodePtr::new(name_node.syntax()),
                 },
+                is_alias: false,
             })
         })
     }
 
-    fn push_file_symbol(&mut self, f: impl FnOnce(&Self) -> Option<FileSymbol>) {
+    fn push_file_symbol(&mut self, def: AttrDefId, f: impl FnOnce(&Self) -> Option<FileSymbol>) {
         if let Some(file_symbol) = f(self) {
+            for alias in self.db.attrs(def).doc_aliases() {
+                self.symbols.push(FileSymbol {
+                    name: alias,
+                    is_alias: true,
+                    ..file_symbol.clone()
+                });
+            }
             self.symbols.push(file_symbol);
         }
     }
@@ -0,0 +1,116 @@
+//! Visitors over the items of a crate and over the expressions of bodies.
+//!
+//! These are meant for tools built on top of rust-analyzer, like linters and codemods, which
+//! want to look at every item or every expression without depending on `hir_def` internals
+//! like the `ItemTree` or the body arenas. Everything handed to a visitor is a `hir` type, or a
+//! syntax node.
+//!
+//! This module is only available with the `visitor` feature.
+
+use hir_def::{expr::ExprId, resolver::HasResolver, DefWithBodyId};
+use hir_expand::InFile;
+use syntax::ast;
+
+use crate::{db::HirDatabase, AssocItem, Crate, DefWithBody, Impl, Module, ModuleDef, Type};
+
+/// Callbacks for [`walk_crate`] and [`walk_module`]. The methods do nothing by default, so a
+/// visitor only implements the ones it is interested in.
+pub trait ItemVisitor {
+    /// Called for each module before its items. Returning `false` skips the items and the
+    /// submodules of the module.
+    fn visit_module(&mut self, _db: &dyn HirDatabase, _module: Module) -> bool {
+        true
+    }
+
+    /// Called for each item declared in a module, including the declarations of submodules.
+    fn visit_item(&mut self, _db: &dyn HirDatabase, _item: ModuleDef) {}
+
+    /// Called for each impl block of a module.
+    fn visit_impl(&mut self, _db: &dyn HirDatabase, _impl: Impl) {}
+
+    /// Called for each item of a trait or an impl block, after the trait or impl itself.
+    fn visit_assoc_item(&mut self, _db: &dyn HirDatabase, _item: AssocItem) {}
+}
+
+/// Walks all modules of `krate`, starting at its root, and the items declared in them.
+///
+/// Items declared inside of bodies, like a `struct` in a function, are not visited.
+pub fn walk_crate(db: &dyn HirDatabase, krate: Crate, visitor: &mut dyn ItemVisitor) {
+    let _p = profile::span("walk_crate");
+    walk_module(db, krate.root_module(db), visitor);
+}
+
+/// Walks `module`, the items declared in it and its submodules.
+pub fn walk_module(db: &dyn HirDatabase, module: Module, visitor: &mut dyn ItemVisitor) {
+    let mut stack = vec![module];
+    while let Some(module) = stack.pop() {
+        if !visitor.visit_module(db, module) {
+            continue;
+        }
+        for item in module.declarations(db) {
+            visitor.visit_item(db, item);
+            if let ModuleDef::Trait(trait_) = item {
+                for assoc_item in trait_.items(db) {
+                    visitor.visit_assoc_item(db, assoc_item);
+                }
+            }
+        }
+        for impl_ in module.impl_defs(db) {
+            visitor.visit_impl(db, impl_);
+            for assoc_item in impl_.items(db) {
+                visitor.visit_assoc_item(db, assoc_item);
+            }
+        }
+        let mut children = module.children(db).collect::<Vec<_>>();
+        children.reverse();
+        stack.extend(children);
+    }
+}
+
+/// An expression of a body together with the type inferred for it.
+#[derive(Debug, Clone)]
+pub struct BodyExpr {
+    /// The expression. Expressions which come from a macro call are in the file of the
+    /// expansion.
+    pub source: InFile<ast::Expr>,
+    /// The type of the expression before adjustments like autoderef or coercions.
+    pub ty: Type,
+}
+
+/// Callbacks for [`walk_body`].
+pub trait BodyVisitor {
+    /// Called for each expression of the body, before its subexpressions. Returning `false`
+    /// skips the subexpressions.
+    fn visit_expr(&mut self, db: &dyn HirDatabase, expr: BodyExpr) -> bool;
+}
+
+/// Walks the expressions of the body of `def` in source order, with their inferred types.
+///
+/// Expressions which have no syntax, like the ones `for` loops are desugared into, are not
+/// passed to the visitor, but their subexpressions are.
+pub fn walk_body(db: &dyn HirDatabase, def: DefWithBody, visitor: &mut dyn BodyVisitor) {
+    let _p = profile::span("walk_body");
+    let def_id = DefWithBodyId::from(def);
+    let (body, source_map) = db.body_with_source_map(def_id);
+    let infer = db.infer(def_id);
+    let resolver = def_id.resolver(db.upcast());
+    let krate = def.module(db).id.krate();
+
+    let mut stack: Vec<ExprId> = vec![body.body_expr];
+    while let Some(expr_id) = stack.pop() {
+        let source = source_map.expr_syntax(expr_id).ok().and_then(|ptr| {
+            let root = db.parse_or_expand(ptr.file_id)?;
+            Some(ptr.map(|it| it.to_node(&root)))
+        });
+        if let Some(source) = source {
+            let ty = Type::new_with_resolver_inner(db, krate, &resolver, infer[expr_id].clone());
+            if !visitor.visit_expr(db, BodyExpr { source, ty }) {
+                continue;
+            }
+        }
+        let mut children = Vec::new();
+        body[expr_id].walk_child_exprs(|it| children.push(it));
+        children.reverse();
+        stack.extend(children);
+    }
+}
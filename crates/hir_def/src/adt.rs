@@ -14,6 +14,7 @@ use tt::{Delimiter, DelimiterKind, Leaf, Subtree, TokenTree};
 
 use crate::{
     body::{CfgExpander, LowerCtx},
+    builtin_type::{BuiltinInt, BuiltinUint},
     db::DefDatabase,
     intern::Interned,
     item_tree::{AttrOwner, Field, Fields, ItemTree, ModItem, RawVisibilityId},
@@ -39,6 +40,7 @@ pub struct StructData {
 pub struct EnumData {
     pub name: Name,
     pub variants: Arena<EnumVariantData>,
+    pub repr: Option<ReprKind>,
     pub visibility: RawVisibility,
 }
 
@@ -66,6 +68,12 @@ pub struct FieldData {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ReprKind {
     Packed,
+    /// An integer type, which sets the type of an enum's discriminants.
+    Int(Either<BuiltinInt, BuiltinUint>),
+    /// `#[repr(C)]`, without an alignment modifier.
+    C,
+    /// `#[repr(transparent)]`, laid out as its only non-zero-sized field.
+    Transparent,
     Other,
 }
 
@@ -84,11 +92,32 @@ fn parse_repr_tt(tt: &Subtree) -> Option<ReprKind> {
         _ => return None,
     }
 
-    let mut it = tt.token_trees.iter();
-    match it.next()? {
-        TokenTree::Leaf(Leaf::Ident(ident)) if ident.text == "packed" => Some(ReprKind::Packed),
-        _ => Some(ReprKind::Other),
+    let mut res = ReprKind::Other;
+    let (mut c, mut align) = (false, false);
+    for tt in &tt.token_trees {
+        let ident = match tt {
+            TokenTree::Leaf(Leaf::Ident(ident)) => ident,
+            _ => continue,
+        };
+        if ident.text == "packed" {
+            return Some(ReprKind::Packed);
+        }
+        if ident.text == "transparent" {
+            return Some(ReprKind::Transparent);
+        }
+        c |= ident.text == "C";
+        align |= ident.text == "align";
+        // `#[repr(C, u8)]` is an enum with `u8` discriminants.
+        if let Some(builtin) = BuiltinInt::from_suffix(&ident.text) {
+            res = ReprKind::Int(Either::Left(builtin));
+        } else if let Some(builtin) = BuiltinUint::from_suffix(&ident.text) {
+            res = ReprKind::Int(Either::Right(builtin));
+        }
+    }
+    if c && !align && res == ReprKind::Other {
+        res = ReprKind::C;
     }
+    Some(res)
 }
 
 impl StructData {
@@ -134,6 +163,7 @@ impl EnumData {
         let item_tree = loc.id.item_tree(db);
         let cfg_options = db.crate_graph()[krate].cfg_options.clone();
 
+        let repr = repr_from_value(db, krate, &item_tree, ModItem::from(loc.id.value).into());
         let enum_ = &item_tree[loc.id.value];
         let mut variants = Arena::new();
         for var_id in enum_.variants.clone() {
@@ -158,6 +188,7 @@ impl EnumData {
         Arc::new(EnumData {
             name: enum_.name.clone(),
             variants,
+            repr,
             visibility: item_tree[enum_.visibility].clone(),
         })
     }
@@ -166,6 +197,14 @@ impl EnumData {
         let (id, _) = self.variants.iter().find(|(_id, data)| &data.name == name)?;
         Some(id)
     }
+
+    /// The type of the discriminants, `isize` unless set by `#[repr]`.
+    pub fn variant_body_type(&self) -> Either<BuiltinInt, BuiltinUint> {
+        match self.repr {
+            Some(ReprKind::Int(builtin)) => builtin,
+            _ => Either::Left(BuiltinInt::Isize),
+        }
+    }
 }
 
 impl HasChildSource<LocalEnumVariantId> for EnumId {
@@ -2,7 +2,7 @@
 
 use std::{fmt, hash::Hash, ops, sync::Arc};
 
-use base_db::CrateId;
+use base_db::{AnchoredPath, CrateId, Env, FileId};
 use cfg::{CfgExpr, CfgOptions};
 use either::Either;
 use hir_expand::{hygiene::Hygiene, name::AsName, HirFileId, InFile};
@@ -12,7 +12,7 @@ use mbe::{syntax_node_to_token_tree, DelimiterKind, Punct};
 use smallvec::{smallvec, SmallVec};
 use syntax::{
     ast::{self, AstNode, HasAttrs, IsString},
-    match_ast, AstPtr, AstToken, SmolStr, SyntaxNode, TextRange, TextSize,
+    match_ast, AstPtr, AstToken, SmolStr, SyntaxElement, SyntaxNode, TextRange, TextSize, T,
 };
 use tt::Subtree;
 
@@ -41,6 +41,26 @@ impl Documentation {
     }
 }
 
+/// The version and note given in a `#[deprecated]` attribute.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Deprecation {
+    pub since: Option<SmolStr>,
+    pub note: Option<SmolStr>,
+}
+
+impl fmt::Display for Deprecation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Deprecated")?;
+        if let Some(since) = &self.since {
+            write!(f, " since {}", since)?;
+        }
+        if let Some(note) = &self.note {
+            write!(f, ": {}", note)?;
+        }
+        Ok(())
+    }
+}
+
 impl From<Documentation> for String {
     fn from(Documentation(string): Documentation) -> Self {
         string
@@ -288,6 +308,30 @@ impl Attrs {
         }
     }
 
+    /// The feature an item declared `#[unstable(feature = "...")]` is gated behind.
+    pub fn unstable_feature(&self) -> Option<SmolStr> {
+        self.by_key("unstable").tt_values().find_map(|tt| {
+            let is_ident = |it: &tt::TokenTree, text: &str| {
+                matches!(it, tt::TokenTree::Leaf(tt::Leaf::Ident(ident)) if ident.text == text)
+            };
+            // `feature = "..."`
+            let value = tt.token_trees.iter().skip_while(|it| !is_ident(it, "feature")).nth(2);
+            match value {
+                Some(tt::TokenTree::Leaf(tt::Leaf::Literal(lit))) => {
+                    Some(SmolStr::new(lit.text.trim_matches('"')))
+                }
+                _ => None,
+            }
+        })
+    }
+
+    /// Whether these crate attributes enable `feature` with `#![feature(...)]`.
+    pub fn enables_feature(&self, feature: &str) -> bool {
+        self.by_key("feature").tt_values().flat_map(|tt| tt.token_trees.iter()).any(
+            |it| matches!(it, tt::TokenTree::Leaf(tt::Leaf::Ident(ident)) if ident.text == feature),
+        )
+    }
+
     pub fn has_doc_hidden(&self) -> bool {
         self.by_key("doc").tt_values().any(|tt| {
             tt.delimiter_kind() == Some(DelimiterKind::Parenthesis) &&
@@ -295,6 +339,93 @@ impl Attrs {
         })
     }
 
+    /// Returns the aliases declared with `#[doc(alias = "...")]` or `#[doc(alias("...", ...))]`.
+    pub fn doc_aliases(&self) -> Vec<SmolStr> {
+        fn unquote(lit: &tt::Literal) -> Option<SmolStr> {
+            let text = lit.text.strip_prefix('"')?.strip_suffix('"')?;
+            Some(SmolStr::new(text))
+        }
+
+        let mut aliases = Vec::new();
+        for tt in self.by_key("doc").tt_values() {
+            if tt.delimiter_kind() != Some(DelimiterKind::Parenthesis) {
+                continue;
+            }
+            let mut tts = tt.token_trees.iter().peekable();
+            while let Some(tt) = tts.next() {
+                match tt {
+                    tt::TokenTree::Leaf(tt::Leaf::Ident(ident)) if ident.text == "alias" => {}
+                    _ => continue,
+                }
+                match tts.peek() {
+                    // `alias = "..."`
+                    Some(tt::TokenTree::Leaf(tt::Leaf::Punct(punct))) if punct.char == '=' => {
+                        tts.next();
+                        if let Some(tt::TokenTree::Leaf(tt::Leaf::Literal(lit))) = tts.next() {
+                            aliases.extend(unquote(lit));
+                        }
+                    }
+                    // `alias("...", "...")`
+                    Some(tt::TokenTree::Subtree(subtree))
+                        if subtree.delimiter_kind() == Some(DelimiterKind::Parenthesis) =>
+                    {
+                        aliases.extend(subtree.token_trees.iter().filter_map(|tt| match tt {
+                            tt::TokenTree::Leaf(tt::Leaf::Literal(lit)) => unquote(lit),
+                            _ => None,
+                        }));
+                        tts.next();
+                    }
+                    _ => {}
+                }
+            }
+        }
+        aliases
+    }
+
+    /// Returns the contents of a `#[deprecated]` attribute, if there is one.
+    pub fn deprecation(&self) -> Option<Deprecation> {
+        let attr = self
+            .by_key("deprecated")
+            .attrs()
+            .chain(self.by_key("rustc_deprecated").attrs())
+            .next()?;
+        let mut res = Deprecation::default();
+        match attr.input.as_deref() {
+            // `#[deprecated = "note"]`
+            Some(AttrInput::Literal(note)) => res.note = Some(note.clone()),
+            // `#[deprecated(since = "1.0", note = "...")]`
+            Some(AttrInput::TokenTree(tt, _)) => {
+                let mut tts = tt.token_trees.iter();
+                while let Some(tt) = tts.next() {
+                    let key = match tt {
+                        tt::TokenTree::Leaf(tt::Leaf::Ident(ident)) => &ident.text,
+                        _ => continue,
+                    };
+                    match tts.next() {
+                        Some(tt::TokenTree::Leaf(tt::Leaf::Punct(punct))) if punct.char == '=' => {}
+                        _ => continue,
+                    }
+                    let value = match tts.next() {
+                        Some(tt::TokenTree::Leaf(tt::Leaf::Literal(lit))) => lit
+                            .text
+                            .strip_prefix('"')
+                            .and_then(|it| it.strip_suffix('"'))
+                            .map(SmolStr::new),
+                        _ => None,
+                    };
+                    match key.as_str() {
+                        "since" => res.since = value,
+                        // `rustc_deprecated` calls it `reason`.
+                        "note" | "reason" => res.note = value,
+                        _ => {}
+                    }
+                }
+            }
+            None => {}
+        }
+        Some(res)
+    }
+
     pub fn is_proc_macro(&self) -> bool {
         self.by_key("proc_macro").exists()
     }
@@ -398,7 +529,43 @@ impl AttrsWithOwner {
         };
 
         let attrs = raw_attrs.filter(db, def.krate(db));
-        Self { attrs, owner: def }
+        Self { attrs, owner: def }.with_doc_includes(db)
+    }
+
+    /// Replaces `#[doc = include_str!("...")]` attributes, which are lowered
+    /// without input, by the contents of the included file, like `README.md`
+    /// files used as crate docs.
+    fn with_doc_includes(mut self, db: &dyn DefDatabase) -> Self {
+        let is_include = |attr: &Attr| {
+            attr.input.is_none() && attr.path.as_ident() == Some(&hir_expand::name![doc])
+        };
+        if !self.iter().any(is_include) {
+            return self;
+        }
+        let source_map = self.source_map(db);
+        let env = db.crate_graph()[self.owner.krate(db)].env.clone();
+        let entries =
+            self.iter()
+                .map(|attr| {
+                    if !is_include(attr) {
+                        return attr.clone();
+                    }
+                    let InFile { file_id, value } = source_map.source_of(attr);
+                    // FIXME: `include_str!`s in `cfg_attr`s map back to the `cfg_attr` and are missed.
+                    let text = value.as_ref().left().and_then(|it| {
+                        included_str(db, &env, file_id.original_file(db.upcast()), it)
+                    });
+                    match text {
+                        Some(text) => Attr {
+                            input: Some(Interned::new(AttrInput::Literal(text.into()))),
+                            ..attr.clone()
+                        },
+                        None => attr.clone(),
+                    }
+                })
+                .collect::<Arc<_>>();
+        self.attrs = Attrs(RawAttrs { entries: Some(entries) });
+        self
     }
 
     pub fn source_map(&self, db: &dyn DefDatabase) -> AttrSourceMap {
@@ -735,6 +902,61 @@ impl fmt::Display for AttrInput {
     }
 }
 
+/// Parses the predicate of a `#[cfg(...)]` or `#[cfg_attr(...)]` attribute,
+/// given its token tree as written in the source.
+pub fn parse_cfg(tt: &ast::TokenTree) -> CfgExpr {
+    let (tree, _) = syntax_node_to_token_tree(tt.syntax());
+    CfgExpr::parse(&tree)
+}
+
+/// The contents of the file included by the `include_str!` of a
+/// `#[doc = include_str!("...")]` attribute, anchored at `file_id`.
+fn included_str(
+    db: &dyn DefDatabase,
+    env: &Env,
+    file_id: FileId,
+    attr: &ast::Attr,
+) -> Option<String> {
+    let call = match attr.expr()? {
+        ast::Expr::MacroCall(it) => it,
+        _ => return None,
+    };
+    if call.path()?.segment()?.name_ref()?.text() != "include_str" {
+        return None;
+    }
+    let mut args = call.token_tree()?.syntax().children_with_tokens().skip(1);
+    let path = eval_str_arg(env, &mut args)?;
+    let file_id = db.resolve_path(AnchoredPath { anchor: file_id, path: &path })?;
+    Some(db.file_text(file_id).to_string())
+}
+
+/// Evaluates the next string argument of a builtin macro like `include_str!`
+/// in `args`: a string literal, or a `concat!` or `env!` call.
+pub fn eval_str_arg(env: &Env, args: &mut dyn Iterator<Item = SyntaxElement>) -> Option<String> {
+    let mut args = args.filter(|it| !it.kind().is_trivia());
+    let token = args.next()?.into_token()?;
+    if let Some(string) = ast::String::cast(token.clone()) {
+        return Some(string.value()?.into_owned());
+    }
+    args.next()?.into_token().filter(|it| it.kind() == T![!])?;
+    let input = args.next()?.into_node()?;
+    let mut inner =
+        input.children_with_tokens().skip(1).filter(|it| !it.kind().is_trivia()).peekable();
+    match token.text() {
+        "concat" => {
+            let mut res = String::new();
+            while inner.peek().map_or(false, |it| !matches!(it.kind(), T![')'] | T![']'] | T!['}']))
+            {
+                res.push_str(&eval_str_arg(env, &mut inner)?);
+                inner.next_if(|it| it.kind() == T![,]);
+            }
+            Some(res)
+        }
+        "env" => env.get(&eval_str_arg(env, &mut inner)?),
+        _ => None,
+    }
+}
+
 impl Attr {
     fn from_src(
         db: &dyn DefDatabase,
@@ -53,6 +53,8 @@ pub enum Literal {
 pub enum Expr {
     /// This is produced if the syntax tree does not have a required expression piece.
     Missing,
+    /// `_` in expression position, either a destructuring assignee or a typed hole.
+    Underscore,
     Path(Path),
     If {
         condition: ExprId,
@@ -170,6 +172,7 @@ pub enum Expr {
         arg_types: Box<[Option<Interned<TypeRef>>]>,
         ret_type: Option<Interned<TypeRef>>,
         body: ExprId,
+        closure_kind: ClosureKind,
     },
     Tuple {
         exprs: Box<[ExprId]>,
@@ -190,6 +193,20 @@ pub enum Array {
     Repeat { initializer: ExprId, repeat: ExprId },
 }
 
+/// Whether a closure is a plain closure or a generator, i.e. contains a `yield`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ClosureKind {
+    Closure,
+    Generator(Movability),
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Movability {
+    /// A `static` generator, which may hold references to its own locals across `yield`s.
+    Static,
+    Movable,
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct MatchArm {
     pub pat: PatId,
@@ -220,7 +237,7 @@ pub enum Statement {
 impl Expr {
     pub fn walk_child_exprs(&self, mut f: impl FnMut(ExprId)) {
         match self {
-            Expr::Missing => {}
+            Expr::Missing | Expr::Underscore => {}
             Expr::Path(_) => {}
             Expr::If { condition, then_branch, else_branch } => {
                 f(*condition);
@@ -670,6 +670,8 @@ pub struct Trait {
     pub generic_params: Interned<GenericParams>,
     pub is_auto: bool,
     pub is_unsafe: bool,
+    /// Whether this is a trait alias, `trait Foo = Bar;`. Its bounds are stored like supertraits.
+    pub is_alias: bool,
     pub items: Box<[AssocItem]>,
     pub ast_id: FileAstId<ast::Trait>,
 }
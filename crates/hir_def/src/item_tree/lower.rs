@@ -424,6 +424,7 @@ impl<'a> Ctx<'a> {
         let generic_params = self.lower_generic_params(GenericsOwner::Trait(trait_def), trait_def);
         let is_auto = trait_def.auto_token().is_some();
         let is_unsafe = trait_def.unsafe_token().is_some();
+        let is_alias = trait_def.eq_token().is_some();
         let items = trait_def.assoc_item_list().map(|list| {
             let db = self.db;
             self.with_inherited_visibility(visibility, |this| {
@@ -445,6 +446,7 @@ impl<'a> Ctx<'a> {
             generic_params,
             is_auto,
             is_unsafe,
+            is_alias,
             items: items.unwrap_or_default(),
             ast_id,
         };
@@ -348,6 +348,7 @@ impl<'a> Printer<'a> {
                     visibility,
                     is_auto,
                     is_unsafe,
+                    is_alias,
                     items,
                     generic_params,
                     ast_id: _,
@@ -361,13 +362,35 @@ impl<'a> Printer<'a> {
                 }
                 w!(self, "trait {}", name);
                 self.print_generic_params(generic_params);
-                self.print_where_clause_and_opening_brace(generic_params);
-                self.indented(|this| {
-                    for item in &**items {
-                        this.print_mod_item((*item).into());
-                    }
-                });
-                wln!(self, "}}");
+                if *is_alias {
+                    // The aliased bounds are stored like supertraits, as bounds on `Self`.
+                    let (bounds, rest): (Vec<_>, Vec<_>) =
+                        generic_params.where_predicates.iter().partition(|pred| {
+                            matches!(pred, WherePredicate::TypeBound {
+                                target: WherePredicateTypeTarget::TypeRef(ty),
+                                ..
+                            } if matches!(&**ty, TypeRef::Path(path) if path.is_self_type()))
+                        });
+                    let bounds = bounds
+                        .into_iter()
+                        .filter_map(|pred| match pred {
+                            WherePredicate::TypeBound { bound, .. } => Some(bound.clone()),
+                            _ => None,
+                        })
+                        .collect::<Vec<_>>();
+                    w!(self, " = ");
+                    self.print_type_bounds(&bounds);
+                    self.print_where_predicates(generic_params, &rest);
+                    wln!(self, ";");
+                } else {
+                    self.print_where_clause_and_opening_brace(generic_params);
+                    self.indented(|this| {
+                        for item in &**items {
+                            this.print_mod_item((*item).into());
+                        }
+                    });
+                    wln!(self, "}}");
+                }
             }
             ModItem::Impl(it) => {
                 let Impl { target_trait, self_ty, is_negative, items, generic_params, ast_id: _ } =
@@ -669,13 +692,22 @@ impl<'a> Printer<'a> {
     }
 
     fn print_where_clause(&mut self, params: &GenericParams) -> bool {
-        if params.where_predicates.is_empty() {
+        let preds = params.where_predicates.iter().collect::<Vec<_>>();
+        self.print_where_predicates(params, &preds)
+    }
+
+    fn print_where_predicates(
+        &mut self,
+        params: &GenericParams,
+        preds: &[&WherePredicate],
+    ) -> bool {
+        if preds.is_empty() {
             return false;
         }
 
         w!(self, "\nwhere");
         self.indented(|this| {
-            for (i, pred) in params.where_predicates.iter().enumerate() {
+            for (i, pred) in preds.iter().enumerate() {
                 if i != 0 {
                     wln!(this, ",");
                 }
@@ -401,3 +401,20 @@ pub(crate) trait Tr {
         "#]],
     )
 }
+
+#[test]
+fn trait_alias() {
+    check(
+        r#"
+trait Simple = Foo;
+trait Alias<T> = Foo + Bar<T> + 'static where T: Clone;
+        "#,
+        expect![[r#"
+            pub(self) trait Simple<Self> = Foo;
+
+            pub(self) trait Alias<Self, T> = Foo + Bar<T> + 'static
+            where
+                T: Clone;
+        "#]],
+    )
+}
@@ -4,6 +4,7 @@
 //! features, such as Fn family of traits.
 use std::sync::Arc;
 
+use base_db::CrateOrigin;
 use rustc_hash::FxHashMap;
 use syntax::SmolStr;
 
@@ -147,9 +148,16 @@ impl LangItems {
         if let Some(&target) = start_crate_target {
             return Some(target);
         }
-        db.crate_graph()[start_crate]
+        // Lang items of the user's own crates (e.g. the lang item crate of a
+        // `#![no_core]` kernel) take precedence over the ones of the sysroot.
+        let crate_graph = db.crate_graph();
+        let (sysroot_deps, other_deps): (Vec<_>, Vec<_>) = crate_graph[start_crate]
             .dependencies
             .iter()
+            .partition(|dep| matches!(crate_graph[dep.crate_id].origin, CrateOrigin::Lang));
+        other_deps
+            .into_iter()
+            .chain(sysroot_deps)
             .find_map(|dep| db.lang_item(dep.crate_id, item.clone()))
     }
 
@@ -69,6 +69,10 @@ impl ModDir {
 
         let mut candidate_files = ArrayVec::<_, 2>::new();
         match attr_path {
+            // `#[path]` inside an `include!`d file is relative to that file.
+            Some(attr_path) if file_id.is_include_macro(db.upcast()) => {
+                candidate_files.push(DirPath::empty().join_attr(attr_path, false))
+            }
             Some(attr_path) => {
                 candidate_files.push(self.dir_path.join_attr(attr_path, self.root_non_dir_owner))
             }
@@ -142,8 +146,11 @@ impl DirPath {
     /// Here, we need to join logical dir path to a string path from an
     /// attribute. Ideally, we should somehow losslessly communicate the whole
     /// construction to `FileLoader`.
+    ///
+    /// Absolute attribute paths are returned as is.
     fn join_attr(&self, mut attr: &str, relative_to_parent: bool) -> String {
         let base = if relative_to_parent { self.parent().unwrap() } else { &self.0 };
+        let base = if is_absolute(attr) { "" } else { base };
 
         if attr.starts_with("./") {
             attr = &attr["./".len()..];
@@ -159,3 +166,11 @@ impl DirPath {
         res
     }
 }
+
+fn is_absolute(path: &str) -> bool {
+    match path.as_bytes() {
+        [b'/' | b'\\', ..] => true,
+        [drive, b':', ..] => drive.is_ascii_alphabetic(),
+        _ => false,
+    }
+}
@@ -313,6 +313,43 @@ pub struct FromLib;
     );
 }
 
+#[test]
+fn edition_2015_keywords_are_identifiers() {
+    check(
+        r#"
+//- /main.rs crate:main edition:2015
+fn async() {}
+fn dyn() {}
+mod try {}
+"#,
+        expect![[r#"
+            crate
+            async: v
+            dyn: v
+            try: t
+
+            crate::try
+        "#]],
+    );
+}
+
+#[test]
+fn edition_2015_keywords_are_identifiers_in_macro_expansions() {
+    check(
+        r#"
+//- /main.rs crate:main edition:2015
+macro_rules! m {
+    () => { fn async() {} }
+}
+m!();
+"#,
+        expect![[r#"
+            crate
+            async: v
+        "#]],
+    );
+}
+
 #[test]
 fn item_map_using_self() {
     check(
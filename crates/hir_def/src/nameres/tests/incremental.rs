@@ -1,8 +1,14 @@
 use std::sync::Arc;
 
-use base_db::SourceDatabaseExt;
+use base_db::{
+    Change, CrateGraph, CrateOrigin, Edition, Env, FileId, FileSet, SourceDatabaseExt, SourceRoot,
+    VfsPath,
+};
+use cfg::CfgOptions;
+use hir_expand::{db::expand_speculative, InFile, MacroCallId};
+use syntax::{ast, AstNode, SourceFile, SyntaxElement, SyntaxToken};
 
-use crate::{AdtId, ModuleDefId};
+use crate::{macro_id_to_def_id, resolver::HasResolver, AdtId, AsMacroCall, ModuleDefId};
 
 use super::*;
 
@@ -235,3 +241,141 @@ pub type Ty = ();
         assert_eq!(n_reparsed_files, 0);
     }
 }
+
+/// The first macro call of `ra_fixture`.
+fn first_macro_call(ra_fixture: &str) -> (TestDB, MacroCallId) {
+    let (db, file_id) = TestDB::with_single_file(ra_fixture);
+    let krate = db.test_crate();
+    let def_map = db.crate_def_map(krate);
+    let resolver = def_map.module_id(def_map.root()).resolver(&db);
+    let macro_call = db.parse(file_id).tree().syntax().descendants().find_map(ast::MacroCall::cast);
+    let macro_call_id = InFile::new(file_id.into(), &macro_call.unwrap())
+        .as_call_id_with_errors(
+            &db,
+            krate,
+            |path| resolver.resolve_path_as_macro(&db, &path).map(|it| macro_id_to_def_id(&db, it)),
+            &mut |_| (),
+        )
+        .unwrap()
+        .unwrap();
+    (db, macro_call_id)
+}
+
+/// The token tree of a call to `m` with `args`, and the identifier `ident` in it.
+fn speculative_args(args: &str, ident: &str) -> (ast::TokenTree, SyntaxToken) {
+    let file = SourceFile::parse(&format!("m!({});", args)).tree();
+    let args = file.syntax().descendants().find_map(ast::TokenTree::cast).unwrap();
+    let token = args
+        .syntax()
+        .descendants_with_tokens()
+        .filter_map(SyntaxElement::into_token)
+        .find(|it| it.text() == ident)
+        .unwrap();
+    (args, token)
+}
+
+#[test]
+fn typing_in_a_macro_call_reuses_speculative_expansion() {
+    let (db, macro_call_id) = first_macro_call(
+        r#"
+macro_rules! m { ($($tt:tt)*) => { $($tt)* } }
+m!(fn f() { foo });
+"#,
+    );
+
+    let expand = |item: &str, ident: &str| {
+        let (args, token) = speculative_args(item, ident);
+        let events = db.log_executed(|| {
+            let (_, token) = expand_speculative(&db, macro_call_id, args.syntax(), token).unwrap();
+            assert_eq!(token.text(), ident);
+        });
+        format!("{:?}", events).contains("speculative_expansion")
+    };
+
+    assert!(expand("fn f() { fo }", "fo"));
+    assert!(!expand("fn f() { foo }", "foo"));
+    assert!(!expand("fn f() { fooIntellijRulezz }", "fooIntellijRulezz"));
+    assert!(expand("fn g() { foo }", "foo"));
+}
+
+#[test]
+fn speculative_expansion_keeps_identifiers_matched_by_the_macro() {
+    let (db, macro_call_id) = first_macro_call(
+        r#"
+macro_rules! m {
+    (foo) => { struct Literal; };
+    ($i:ident) => { fn $i() {} };
+}
+m!(f);
+"#,
+    );
+
+    // The first rule doesn't pass `foo` on, so there's nothing to map it to.
+    let (args, token) = speculative_args("foo", "foo");
+    assert!(expand_speculative(&db, macro_call_id, args.syntax(), token).is_none());
+
+    let (args, token) = speculative_args("bar", "bar");
+    let (node, token) = expand_speculative(&db, macro_call_id, args.syntax(), token).unwrap();
+    assert!(!node.to_string().contains("Literal"));
+    assert_eq!(token.text(), "bar");
+}
+
+#[test]
+fn crate_graph_change_reparses_only_on_edition_change() {
+    let (mut db, file_id) = TestDB::with_single_file("fn async() {}");
+    assert!(!db.parse(file_id).errors().is_empty());
+
+    let set_crate_graph = |db: &mut TestDB, edition, cfg_options: CfgOptions| {
+        let mut crate_graph = CrateGraph::default();
+        crate_graph.add_crate_root(
+            file_id,
+            edition,
+            None,
+            None,
+            cfg_options.clone(),
+            cfg_options,
+            Env::default(),
+            Vec::new(),
+            false,
+            CrateOrigin::default(),
+        );
+        let mut change = Change::new();
+        change.set_crate_graph(crate_graph);
+        change.apply(db);
+    };
+
+    let mut cfg_options = CfgOptions::default();
+    cfg_options.insert_atom("test".into());
+    set_crate_graph(&mut db, Edition::CURRENT, cfg_options);
+    let events = db.log_executed(|| {
+        db.parse(file_id);
+    });
+    assert!(!format!("{:?}", events).contains("parse"), "{:#?}", events);
+
+    set_crate_graph(&mut db, Edition::Edition2015, CfgOptions::default());
+    let events = db.log_executed(|| {
+        assert!(db.parse(file_id).errors().is_empty());
+    });
+    assert!(format!("{:?}", events).contains("parse"), "{:#?}", events);
+}
+
+#[test]
+fn adding_a_file_does_not_reparse_the_others() {
+    let (mut db, file_id) = TestDB::with_single_file("fn f() {}");
+    db.parse(file_id);
+
+    let new_file = FileId(file_id.0 + 1);
+    let mut file_set = FileSet::default();
+    file_set.insert(file_id, VfsPath::new_virtual_path("/main.rs".to_string()));
+    file_set.insert(new_file, VfsPath::new_virtual_path("/new.rs".to_string()));
+    let mut change = Change::new();
+    change.set_roots(vec![SourceRoot::new_local(file_set)]);
+    change.change_file(new_file, Some(Arc::new("fn g() {}".to_string())));
+    change.apply(&mut db);
+
+    let events = db.log_executed(|| {
+        db.parse(file_id);
+    });
+    assert!(!format!("{:?}", events).contains("parse"), "{:#?}", events);
+    assert!(db.parse(new_file).errors().is_empty());
+}
@@ -163,6 +163,64 @@ pub struct Bar;
     );
 }
 
+#[test]
+fn module_resolution_decl_absolute_path() {
+    check(
+        r#"
+//- /lib.rs
+mod nested {
+    #[path = "/out/generated.rs"]
+    mod generated;
+}
+
+//- /out/generated.rs
+pub struct Generated;
+"#,
+        expect![[r#"
+            crate
+            nested: t
+
+            crate::nested
+            generated: t
+
+            crate::nested::generated
+            Generated: t v
+        "#]],
+    );
+}
+
+#[test]
+fn module_resolution_decl_path_in_included_file() {
+    check(
+        r#"
+//- /lib.rs
+#[rustc_builtin_macro]
+macro_rules! include { () => {} }
+
+mod out {
+    include!("out/bindings.rs");
+}
+
+//- /out/bindings.rs
+#[path = "gen/types.rs"]
+pub mod types;
+
+//- /out/gen/types.rs
+pub struct Type;
+"#,
+        expect![[r#"
+            crate
+            out: t
+
+            crate::out
+            types: t
+
+            crate::out::types
+            Type: t v
+        "#]],
+    );
+}
+
 #[test]
 fn module_resolution_module_with_path_in_mod_rs() {
     check(
@@ -396,8 +396,31 @@ impl ConstScalarOrPath {
 
     // FIXME: as per the comments on `TypeRef::Array`, this evaluation should not happen at this
     // parse stage.
-    fn from_expr(expr: ast::Expr) -> Self {
+    pub(crate) fn from_expr(expr: ast::Expr) -> Self {
         match expr {
+            ast::Expr::BlockExpr(block) => match block.stmt_list() {
+                Some(stmts) if stmts.statements().next().is_none() => {
+                    Self::from_expr_opt(stmts.tail_expr())
+                }
+                _ => Self::Scalar(ConstScalar::Unknown),
+            },
+            ast::Expr::ParenExpr(paren) => Self::from_expr_opt(paren.expr()),
+            ast::Expr::BinExpr(bin) => {
+                let lhs = Self::from_expr_opt(bin.lhs());
+                let rhs = Self::from_expr_opt(bin.rhs());
+                match (lhs, rhs, bin.op_kind()) {
+                    (
+                        Self::Scalar(ConstScalar::Usize(lhs)),
+                        Self::Scalar(ConstScalar::Usize(rhs)),
+                        Some(ast::BinaryOp::ArithOp(op)),
+                    ) => Self::Scalar(
+                        eval_usize_arith(op, lhs, rhs)
+                            .map(ConstScalar::Usize)
+                            .unwrap_or(ConstScalar::Unknown),
+                    ),
+                    _ => Self::Scalar(ConstScalar::Unknown),
+                }
+            }
             ast::Expr::PathExpr(p) => {
                 match p.path().and_then(|x| x.segment()).and_then(|x| x.name_ref()) {
                     Some(x) => Self::Path(x.as_name()),
@@ -425,6 +448,21 @@ impl ConstScalarOrPath {
     }
 }
 
+fn eval_usize_arith(op: ast::ArithOp, lhs: u64, rhs: u64) -> Option<u64> {
+    match op {
+        ast::ArithOp::Add => lhs.checked_add(rhs),
+        ast::ArithOp::Sub => lhs.checked_sub(rhs),
+        ast::ArithOp::Mul => lhs.checked_mul(rhs),
+        ast::ArithOp::Div => lhs.checked_div(rhs),
+        ast::ArithOp::Rem => lhs.checked_rem(rhs),
+        ast::ArithOp::Shl => lhs.checked_shl(rhs.try_into().ok()?),
+        ast::ArithOp::Shr => lhs.checked_shr(rhs.try_into().ok()?),
+        ast::ArithOp::BitXor => Some(lhs ^ rhs),
+        ast::ArithOp::BitOr => Some(lhs | rhs),
+        ast::ArithOp::BitAnd => Some(lhs & rhs),
+    }
+}
+
 /// A concrete constant value
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum ConstScalar {
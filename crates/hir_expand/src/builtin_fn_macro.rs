@@ -277,36 +277,95 @@ fn asm_expand(
     _id: MacroCallId,
     tt: &tt::Subtree,
 ) -> ExpandResult<tt::Subtree> {
-    // We expand all assembly snippets to `format_args!` invocations to get format syntax
-    // highlighting for them.
+    let body = asm_body(tt);
+    ExpandResult::ok(quote! {{
+        ##body
+        ()
+    }})
+}
 
-    let mut literals = Vec::new();
-    for tt in tt.token_trees.chunks(2) {
-        match tt {
-            [tt::TokenTree::Leaf(tt::Leaf::Literal(lit))]
-            | [tt::TokenTree::Leaf(tt::Leaf::Literal(lit)), tt::TokenTree::Leaf(tt::Leaf::Punct(tt::Punct { char: ',', id: _, spacing: _ }))] =>
+fn global_asm_expand(
+    _db: &dyn AstDatabase,
+    _id: MacroCallId,
+    tt: &tt::Subtree,
+) -> ExpandResult<tt::Subtree> {
+    // Expand to an anonymous const at item level.
+    let body = asm_body(tt);
+    ExpandResult::ok(quote! {
+        const _: () = {
+            ##body
+        };
+    })
+}
+
+/// We expand the template strings of assembly snippets to `format_args!` invocations, to get
+/// format syntax highlighting for them, and their operands to `let _ = &(operand);` statements,
+/// so that they get resolved and type checked. Anything we don't understand is skipped rather
+/// than reported: the compiler checks the snippet anyway.
+fn asm_body(tt: &tt::Subtree) -> Vec<tt::Subtree> {
+    let mut statements = Vec::new();
+    let krate = DOLLAR_CRATE.clone();
+    for arg in tt.token_trees.split(|it| is_punct(it, ',')) {
+        let arg = match arg {
+            // Named operands, `name = in(reg) expr`.
+            [tt::TokenTree::Leaf(tt::Leaf::Ident(_)), eq, rest @ ..]
+                if is_punct(eq, '=') && !rest.first().map_or(false, |it| is_punct(it, '>')) =>
+            {
+                rest
+            }
+            _ => arg,
+        };
+        match arg {
+            [tt::TokenTree::Leaf(tt::Leaf::Literal(lit))] => {
+                statements.push(quote!(#krate::format_args!(#lit);));
+            }
+            [tt::TokenTree::Leaf(tt::Leaf::Ident(kind)), tt::TokenTree::Subtree(_), operands @ ..]
+                if matches!(&*kind.text, "in" | "out" | "lateout" | "inout" | "inlateout") =>
+            {
+                // `inout(reg) in_expr => out_expr`
+                let split = operands
+                    .windows(2)
+                    .position(|pair| is_punct(&pair[0], '=') && is_punct(&pair[1], '>'));
+                let operands = match split {
+                    Some(idx) => vec![&operands[..idx], &operands[idx + 2..]],
+                    None => vec![operands],
+                };
+                for operand in operands {
+                    // `_` discards an output.
+                    if let [tt::TokenTree::Leaf(tt::Leaf::Ident(ident))] = operand {
+                        if ident.text == "_" {
+                            continue;
+                        }
+                    }
+                    let operand = parenthesized(operand);
+                    statements.push(quote!(let _ = &#operand;));
+                }
+            }
+            [tt::TokenTree::Leaf(tt::Leaf::Ident(kind)), operand @ ..]
+                if matches!(&*kind.text, "const" | "sym") && !operand.is_empty() =>
             {
-                let krate = DOLLAR_CRATE.clone();
-                literals.push(quote!(#krate::format_args!(#lit);));
+                let operand = parenthesized(operand);
+                statements.push(quote!(let _ = #operand;));
             }
-            _ => break,
+            // `options(...)`, `clobber_abi(...)` and trailing commas.
+            _ => (),
         }
     }
+    statements
+}
 
-    let expanded = quote! {{
-        ##literals
-        ()
-    }};
-    ExpandResult::ok(expanded)
+fn is_punct(tt: &tt::TokenTree, char: char) -> bool {
+    matches!(tt, tt::TokenTree::Leaf(tt::Leaf::Punct(punct)) if punct.char == char)
 }
 
-fn global_asm_expand(
-    _db: &dyn AstDatabase,
-    _id: MacroCallId,
-    _tt: &tt::Subtree,
-) -> ExpandResult<tt::Subtree> {
-    // Expand to nothing (at item-level)
-    ExpandResult::ok(quote! {})
+fn parenthesized(tokens: &[tt::TokenTree]) -> tt::Subtree {
+    tt::Subtree {
+        delimiter: Some(tt::Delimiter {
+            id: tt::TokenId::unspecified(),
+            kind: tt::DelimiterKind::Parenthesis,
+        }),
+        token_trees: tokens.to_vec(),
+    }
 }
 
 fn cfg_expand(
@@ -637,12 +696,21 @@ fn env_expand(
 
     let mut err = None;
     let s = get_env_inner(db, arg_id, &key).unwrap_or_else(|| {
-        // The only variable rust-analyzer ever sets is `OUT_DIR`, so only diagnose that to avoid
-        // unnecessary diagnostics for eg. `CARGO_PKG_NAME`.
+        // Other variables might be set in the environment of the build, so we only diagnose
+        // `OUT_DIR` and misspelled variables from Cargo, if Cargo set up the crate.
+        let set_by_cargo = get_env_inner(db, arg_id, "CARGO_PKG_NAME").is_some();
         if key == "OUT_DIR" {
             err = Some(ExpandError::Other(
                 r#"`OUT_DIR` not set, enable "run build scripts" to fix"#.into(),
             ));
+        } else if set_by_cargo
+            && key.starts_with("CARGO_PKG_")
+            // Newer Cargo versions set these, but we don't.
+            && !matches!(&*key, "CARGO_PKG_README" | "CARGO_PKG_RUST_VERSION")
+        {
+            err = Some(ExpandError::Other(
+                format!("environment variable `{}` not defined at compile time", key).into(),
+            ));
         }
 
         // If the variable is unset, still return a dummy string to help type inference along.
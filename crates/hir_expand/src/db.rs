@@ -9,8 +9,9 @@ use mbe::syntax_node_to_token_tree;
 use rustc_hash::FxHashSet;
 use syntax::{
     algo::diff,
-    ast::{self, HasAttrs, HasDocComments},
-    AstNode, GreenNode, Parse, SyntaxNode, SyntaxToken, T,
+    ast::{self, make, HasAttrs, HasDocComments},
+    ted, AstNode, Edition, GreenNode, NodeOrToken, Parse, SyntaxKind, SyntaxNode, SyntaxToken,
+    TextRange, T,
 };
 
 use crate::{
@@ -28,6 +29,9 @@ use crate::{
 /// Actual max for `analysis-stats .` at some point: 30672.
 static TOKEN_LIMIT: Limit = Limit::new(524_288);
 
+/// Identifier that stands in for the one being mapped by [`expand_speculative`].
+const SPECULATIVE_IDENT: &str = "__ra_speculative_ident";
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum TokenExpander {
     /// Old-style `macro_rules` or the new macros 2.0
@@ -128,6 +132,20 @@ pub trait AstDatabase: SourceDatabase {
     /// non-determinism breaks salsa in a very, very, very bad way. @edwin0cheng
     /// heroically debugged this once!
     fn expand_proc_macro(&self, call: MacroCallId) -> ExpandResult<tt::Subtree>;
+    /// Implementation of [`expand_speculative`], with the arguments and the token to map given
+    /// relative to the arguments. Completion asks for the same speculative expansion again and
+    /// again while the cursor stays in a macro call, and heavy macros take seconds to expand.
+    /// An identifier being mapped is replaced by a fixed one before getting here when the macro
+    /// can't tell the two apart, so typing it out doesn't change the key.
+    ///
+    /// This query is LRUed. Unlike `expand_proc_macro`, that's fine for proc macros: nothing
+    /// depends on it, so a non-deterministic re-expansion can't break anything.
+    fn speculative_expansion(
+        &self,
+        actual_macro_call: MacroCallId,
+        speculative_args: GreenNode,
+        token_to_map: (TextRange, SyntaxKind),
+    ) -> Option<(GreenNode, TextRange)>;
     /// Firewall query that returns the error from the `macro_expand` query.
     fn macro_expand_error(&self, macro_call: MacroCallId) -> Option<ExpandError>;
 
@@ -144,9 +162,82 @@ pub fn expand_speculative(
     speculative_args: &SyntaxNode,
     token_to_map: SyntaxToken,
 ) -> Option<(SyntaxNode, SyntaxToken)> {
+    let token_range =
+        token_to_map.text_range().checked_sub(speculative_args.text_range().start())?;
+
+    // Completion inserts a fake identifier at the cursor, which changes with every keystroke.
+    // Unless the macro matches it literally, it can't tell one identifier from another, so swap
+    // it for a fixed one to let the query be reused for as long as the rest of the arguments
+    // stay the same.
+    let normalize = token_to_map.kind() == SyntaxKind::IDENT
+        && ident_is_opaque_to(db, actual_macro_call, token_to_map.text());
+    let (speculative_args, token_range) = if normalize {
+        let args = speculative_args.clone_subtree().clone_for_update();
+        let token = args.covering_element(token_range).into_token()?;
+        let new_token = make::name(SPECULATIVE_IDENT).clone_for_update().syntax().first_token()?;
+        new_token.detach();
+        ted::replace(token, new_token.clone());
+        (args.green().into_owned(), new_token.text_range())
+    } else {
+        (speculative_args.green().into_owned(), token_range)
+    };
+
+    let (expansion, range) = db.speculative_expansion(
+        actual_macro_call,
+        speculative_args,
+        (token_range, token_to_map.kind()),
+    )?;
+    let mut node = SyntaxNode::new_root(expansion);
+    let mut token = node.covering_element(range).into_token()?;
+    if normalize {
+        // Put the identifier that was actually typed back everywhere the macro copied it to.
+        let mutable = node.clone_for_update();
+        let mut mapped = mutable.covering_element(range).into_token()?;
+        let placeholders = mutable
+            .descendants_with_tokens()
+            .filter_map(NodeOrToken::into_token)
+            .filter(|it| it.text() == SPECULATIVE_IDENT)
+            .collect::<Vec<_>>();
+        for placeholder in placeholders {
+            let ident =
+                make::name(token_to_map.text()).clone_for_update().syntax().first_token()?;
+            ident.detach();
+            if placeholder == mapped {
+                mapped = ident.clone();
+            }
+            ted::replace(placeholder, ident);
+        }
+        node = SyntaxNode::new_root(mutable.green().into_owned());
+        token = node.covering_element(mapped.text_range()).into_token()?;
+    }
+    Some((node, token))
+}
+
+/// Whether `call` expands the same for `ident` as for any other identifier, because it's a call
+/// to a declarative macro whose rules don't mention `ident`. Other macros might look at its text,
+/// like `stringify!` or proc macros do.
+fn ident_is_opaque_to(db: &dyn AstDatabase, call: MacroCallId, ident: &str) -> bool {
+    match db.lookup_intern_macro_call(call).def.kind {
+        MacroDefKind::Declarative(ast_id) => !ast_id
+            .to_node(db)
+            .syntax()
+            .descendants_with_tokens()
+            .filter_map(NodeOrToken::into_token)
+            .any(|it| it.kind() == SyntaxKind::IDENT && it.text() == ident),
+        _ => false,
+    }
+}
+
+fn speculative_expansion(
+    db: &dyn AstDatabase,
+    actual_macro_call: MacroCallId,
+    speculative_args: GreenNode,
+    (token_range, token_kind): (TextRange, SyntaxKind),
+) -> Option<(GreenNode, TextRange)> {
+    let _p = profile::span("speculative_expansion");
+    let speculative_args = &SyntaxNode::new_root(speculative_args);
     let loc = db.lookup_intern_macro_call(actual_macro_call);
     let macro_def = db.macro_def(loc.def).ok()?;
-    let token_range = token_to_map.text_range();
 
     // Build the subtree and token mapping for the speculative args
     let censor = censor_for_macro_input(&loc, speculative_args);
@@ -220,11 +311,14 @@ pub fn expand_speculative(
 
     let expand_to = macro_expand_to(db, actual_macro_call);
     fixup::reverse_fixups(&mut speculative_expansion.value, &spec_args_tmap, &fixups.undo_info);
-    let (node, rev_tmap) = token_tree_to_syntax_node(&speculative_expansion.value, expand_to);
-
-    let range = rev_tmap.first_range_by_token(token_id, token_to_map.kind())?;
-    let token = node.syntax_node().covering_element(range).into_token()?;
-    Some((node.syntax_node(), token))
+    let edition = macro_edition(db, actual_macro_call);
+    let (node, rev_tmap) =
+        token_tree_to_syntax_node(&speculative_expansion.value, expand_to, edition);
+
+    let range = rev_tmap.first_range_by_token(token_id, token_kind)?;
+    let node = node.syntax_node();
+    let token = node.covering_element(range).into_token()?;
+    Some((node.green().into_owned(), token.text_range()))
 }
 
 fn ast_id_map(db: &dyn AstDatabase, file_id: HirFileId) -> Arc<AstIdMap> {
@@ -281,7 +375,8 @@ fn parse_macro_expansion(
     tracing::debug!("expanded = {}", tt.as_debug_string());
     tracing::debug!("kind = {:?}", expand_to);
 
-    let (parse, rev_token_map) = token_tree_to_syntax_node(&tt, expand_to);
+    let edition = macro_edition(db, macro_file.macro_call_id);
+    let (parse, rev_token_map) = token_tree_to_syntax_node(&tt, expand_to, edition);
 
     match result.err {
         Some(err) => {
@@ -533,9 +628,17 @@ fn macro_expand_to(db: &dyn AstDatabase, id: MacroCallId) -> ExpandTo {
     loc.kind.expand_to()
 }
 
+/// The edition the expansion of a macro is parsed with: the one of the crate defining the macro,
+/// where most tokens of the expansion come from.
+fn macro_edition(db: &dyn AstDatabase, id: MacroCallId) -> Edition {
+    let loc: MacroCallLoc = db.lookup_intern_macro_call(id);
+    db.crate_graph()[loc.def.krate].edition.into()
+}
+
 fn token_tree_to_syntax_node(
     tt: &tt::Subtree,
     expand_to: ExpandTo,
+    edition: Edition,
 ) -> (Parse<SyntaxNode>, mbe::TokenMap) {
     let entry_point = match expand_to {
         ExpandTo::Statements => mbe::TopEntryPoint::MacroStmts,
@@ -544,5 +647,5 @@ fn token_tree_to_syntax_node(
         ExpandTo::Type => mbe::TopEntryPoint::Type,
         ExpandTo::Expr => mbe::TopEntryPoint::Expr,
     };
-    mbe::token_tree_to_syntax_node(tt, entry_point)
+    mbe::token_tree_to_syntax_node_with_edition(tt, entry_point, edition)
 }
@@ -277,6 +277,22 @@ impl HirFileId {
         }
     }
 
+    /// Indicate it is macro file generated for a builtin or custom derive, returning the
+    /// `#[derive]` attribute that invoked it
+    pub fn is_derive(&self, db: &dyn db::AstDatabase) -> Option<InFile<ast::Attr>> {
+        match self.0 {
+            HirFileIdRepr::FileId(_) => None,
+            HirFileIdRepr::MacroFile(macro_file) => {
+                let loc: MacroCallLoc = db.lookup_intern_macro_call(macro_file.macro_call_id);
+                let attr = match loc.kind {
+                    MacroCallKind::Derive { .. } => loc.kind.to_node(db),
+                    _ => return None,
+                };
+                Some(attr.with_value(ast::Attr::cast(attr.value.clone())?))
+            }
+        }
+    }
+
     pub fn is_custom_derive(&self, db: &dyn db::AstDatabase) -> bool {
         match self.0 {
             HirFileIdRepr::FileId(_) => false,
@@ -319,6 +319,7 @@ pub mod known {
         gt,
         le,
         lt,
+        branch,
         // lang items
         add_assign,
         add,
@@ -93,6 +93,16 @@ macro_rules! __quote {
         }]
     };
 
+    // Underscore
+    ( _ ) => {
+        vec![ {
+            tt::Leaf::Ident(tt::Ident {
+                text: "_".into(),
+                id: tt::TokenId::unspecified(),
+            }).into()
+        }]
+    };
+
     // Puncts
     // FIXME: Not all puncts are handled
     ( -> ) => {$crate::__quote!(@PUNCT '-', '>')};
@@ -100,6 +110,7 @@ macro_rules! __quote {
     ( , ) => {$crate::__quote!(@PUNCT ',')};
     ( : ) => {$crate::__quote!(@PUNCT ':')};
     ( ; ) => {$crate::__quote!(@PUNCT ';')};
+    ( = ) => {$crate::__quote!(@PUNCT '=')};
     ( :: ) => {$crate::__quote!(@PUNCT ':', ':')};
     ( . ) => {$crate::__quote!(@PUNCT '.')};
     ( < ) => {$crate::__quote!(@PUNCT '<')};
@@ -11,8 +11,8 @@ use limit::Limit;
 use syntax::SmolStr;
 
 use crate::{
-    db::HirDatabase, infer::unify::InferenceTable, Canonical, Goal, Interner, ProjectionTyExt,
-    TraitEnvironment, Ty, TyBuilder, TyKind,
+    db::HirDatabase, infer::unify::InferenceTable, AdtId, Canonical, Goal, Interner, Overflow,
+    ProjectionTyExt, TraitEnvironment, Ty, TyBuilder, TyKind,
 };
 
 static AUTODEREF_RECURSION_LIMIT: Limit = Limit::new(10);
@@ -57,12 +57,14 @@ impl Iterator for Autoderef<'_, '_> {
             return Some((self.ty.clone(), 0));
         }
 
+        let (kind, new_ty) = autoderef_step(self.table, self.ty.clone())?;
+
         if AUTODEREF_RECURSION_LIMIT.check(self.steps.len() + 1).is_err() {
+            let ty = self.table.canonicalize(self.ty.clone()).value;
+            self.table.record_overflow(Overflow::Autoderef(ty));
             return None;
         }
 
-        let (kind, new_ty) = autoderef_step(self.table, self.ty.clone())?;
-
         self.steps.push((kind, self.ty.clone()));
         self.ty = new_ty;
 
@@ -71,7 +73,7 @@ impl Iterator for Autoderef<'_, '_> {
 }
 
 pub(crate) fn autoderef_step(table: &mut InferenceTable, ty: Ty) -> Option<(AutoderefKind, Ty)> {
-    if let Some(derefed) = builtin_deref(&ty) {
+    if let Some(derefed) = builtin_deref(table, &ty) {
         Some((AutoderefKind::Builtin, table.resolve_ty_shallow(derefed)))
     } else {
         Some((AutoderefKind::Overloaded, deref_by_trait(table, ty)?))
@@ -99,14 +101,22 @@ pub(crate) fn deref(table: &mut InferenceTable, ty: Ty) -> Option<Ty> {
     autoderef_step(table, ty).map(|(_, ty)| ty)
 }
 
-fn builtin_deref(ty: &Ty) -> Option<&Ty> {
+fn builtin_deref<'ty>(table: &InferenceTable, ty: &'ty Ty) -> Option<&'ty Ty> {
     match ty.kind(Interner) {
         TyKind::Ref(.., ty) => Some(ty),
         TyKind::Raw(.., ty) => Some(ty),
+        // Like rustc, deref `Box` without going through its `Deref` impl, so that its contents,
+        // and methods with `self: Box<Self>` receivers, are found even without one.
+        TyKind::Adt(AdtId(adt), subst) if is_box(table, *adt) => subst.at(Interner, 0).ty(Interner),
         _ => None,
     }
 }
 
+fn is_box(table: &InferenceTable, adt: hir_def::AdtId) -> bool {
+    let owned_box = table.db.lang_item(table.trait_env.krate, SmolStr::new_inline("owned_box"));
+    owned_box.and_then(|it| it.as_struct()).map_or(false, |it| hir_def::AdtId::from(it) == adt)
+}
+
 fn deref_by_trait(table: &mut InferenceTable, ty: Ty) -> Option<Ty> {
     let _p = profile::span("deref_by_trait");
     if table.resolve_ty_shallow(&ty).inference_var(Interner).is_some() {
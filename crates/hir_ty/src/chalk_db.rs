@@ -1,6 +1,6 @@
 //! The implementation of `RustIrDatabase` for Chalk, which provides information
 //! about the code that Chalk needs.
-use std::sync::Arc;
+use std::{iter, sync::Arc};
 
 use cov_mark::hit;
 use syntax::SmolStr;
@@ -11,6 +11,7 @@ use chalk_solve::rust_ir::{self, OpaqueTyDatumBound, WellKnownTrait};
 
 use base_db::CrateId;
 use hir_def::{
+    expr::{ClosureKind, Expr, Movability},
     lang_item::{lang_attr, LangItemTarget},
     AssocItemId, GenericDefId, HasModule, ItemContainerId, Lookup, ModuleId, TypeAliasId,
 };
@@ -212,6 +213,19 @@ impl<'a> chalk_solve::RustIrDatabase<Interner> for ChalkContext<'a> {
                 };
                 chalk_ir::Binders::new(binders, bound)
             }
+            crate::ImplTraitId::TypeAliasImplTrait(alias, idx) => {
+                let datas = self
+                    .db
+                    .type_alias_impl_traits(alias)
+                    .expect("impl trait id without impl traits");
+                let (datas, binders) = (*datas).as_ref().into_value_and_skipped_binders();
+                let data = &datas.impl_traits[idx as usize];
+                let bound = OpaqueTyDatumBound {
+                    bounds: make_single_type_binders(data.bounds.skip_binders().to_vec()),
+                    where_clauses: chalk_ir::Binders::empty(Interner, vec![]),
+                };
+                chalk_ir::Binders::new(binders, bound)
+            }
             crate::ImplTraitId::AsyncBlockTypeImplTrait(..) => {
                 if let Some((future_trait, future_output)) = self
                     .db
@@ -353,17 +367,38 @@ impl<'a> chalk_solve::RustIrDatabase<Interner> for ChalkContext<'a> {
     }
     fn generator_datum(
         &self,
-        _: chalk_ir::GeneratorId<Interner>,
+        id: chalk_ir::GeneratorId<Interner>,
     ) -> std::sync::Arc<chalk_solve::rust_ir::GeneratorDatum<Interner>> {
-        // FIXME
-        unimplemented!()
+        let (owner, expr) = self.db.lookup_intern_generator(id.into());
+        let movability = match &self.db.body(owner)[expr] {
+            Expr::Lambda { closure_kind: ClosureKind::Generator(Movability::Static), .. } => {
+                rust_ir::Movability::Static
+            }
+            _ => rust_ir::Movability::Movable,
+        };
+        // The substitution of a generator type is its resume, yield and return types.
+        let bound_var = |index| TyKind::BoundVar(BoundVar::new(DebruijnIndex::INNERMOST, index));
+        let input_output = rust_ir::GeneratorInputOutputDatum {
+            resume_type: bound_var(0).intern(Interner),
+            yield_type: bound_var(1).intern(Interner),
+            return_type: bound_var(2).intern(Interner),
+            // FIXME: captures aren't tracked, like for closures
+            upvars: Vec::new(),
+        };
+        let input_output = chalk_ir::Binders::new(generator_binders(), input_output);
+        Arc::new(rust_ir::GeneratorDatum { movability, input_output })
     }
     fn generator_witness_datum(
         &self,
         _: chalk_ir::GeneratorId<Interner>,
     ) -> std::sync::Arc<chalk_solve::rust_ir::GeneratorWitnessDatum<Interner>> {
-        // FIXME
-        unimplemented!()
+        // FIXME: the types held across `yield`s aren't tracked
+        let witness = rust_ir::GeneratorWitnessExistential {
+            types: chalk_ir::Binders::empty(Interner, Vec::new()),
+        };
+        Arc::new(rust_ir::GeneratorWitnessDatum {
+            inner_types: chalk_ir::Binders::new(generator_binders(), witness),
+        })
     }
 
     fn unification_database(&self) -> &dyn chalk_ir::UnificationDatabase<Interner> {
@@ -384,6 +419,15 @@ impl<'a> chalk_ir::UnificationDatabase<Interner> for &'a dyn HirDatabase {
     }
 }
 
+/// The binders of a generator's input/output and witness data, for its resume, yield and return
+/// types.
+fn generator_binders() -> chalk_ir::VariableKinds<Interner> {
+    chalk_ir::VariableKinds::from_iter(
+        Interner,
+        iter::repeat(chalk_ir::VariableKind::Ty(chalk_ir::TyVariableKind::General)).take(3),
+    )
+}
+
 pub(crate) fn program_clauses_for_chalk_env_query(
     db: &dyn HirDatabase,
     krate: CrateId,
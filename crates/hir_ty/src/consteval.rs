@@ -12,9 +12,9 @@ use hir_def::{
     path::ModPath,
     resolver::{resolver_for_expr, ResolveValueResult, Resolver, ValueNs},
     type_ref::ConstScalar,
-    ConstId, DefWithBodyId,
+    ConstId, DefWithBodyId, EnumVariantId, LocalEnumVariantId,
 };
-use la_arena::{Arena, Idx};
+use la_arena::{Arena, Idx, RawIdx};
 use stdx::never;
 
 use crate::{
@@ -27,6 +27,9 @@ use crate::{
 pub trait ConstExt {
     /// Is a [`Const`] unknown?
     fn is_unknown(&self) -> bool;
+
+    /// The value of a known `usize` [`Const`], like the length of an array.
+    fn as_usize(&self) -> Option<u64>;
 }
 
 impl ConstExt for Const {
@@ -49,6 +52,15 @@ impl ConstExt for Const {
             }
         }
     }
+
+    fn as_usize(&self) -> Option<u64> {
+        match &self.data(Interner).value {
+            chalk_ir::ConstValue::Concrete(chalk_ir::ConcreteConst {
+                interned: ConstScalar::Usize(value),
+            }) => Some(*value),
+            _ => None,
+        }
+    }
 }
 
 pub struct ConstEvalCtx<'a> {
@@ -155,7 +167,7 @@ pub fn eval_const(
 ) -> Result<ComputedExpr, ConstEvalError> {
     let expr = &ctx.exprs[expr_id];
     match expr {
-        Expr::Missing => Err(ConstEvalError::IncompleteExpr),
+        Expr::Missing | Expr::Underscore => Err(ConstEvalError::IncompleteExpr),
         Expr::Literal(l) => Ok(ComputedExpr::Literal(l.clone())),
         &Expr::UnaryOp { expr, op } => {
             let ty = &ctx.expr_ty(expr);
@@ -449,6 +461,54 @@ pub(crate) fn const_eval_query(
     result
 }
 
+pub(crate) fn const_eval_variant_recover(
+    _: &dyn HirDatabase,
+    _: &[String],
+    _: &EnumVariantId,
+) -> Result<i128, ConstEvalError> {
+    Err(ConstEvalError::Loop)
+}
+
+/// Evaluates the discriminant of an enum variant, which is one more than the previous variant's
+/// when not given explicitly.
+pub(crate) fn const_eval_variant_query(
+    db: &dyn HirDatabase,
+    variant_id: EnumVariantId,
+) -> Result<i128, ConstEvalError> {
+    let def = variant_id.into();
+    let body = db.body(def);
+    if body.exprs[body.body_expr] == Expr::Missing {
+        let prev_idx: u32 = variant_id.local_id.into_raw().into();
+        if prev_idx == 0 {
+            return Ok(0);
+        }
+        let prev = EnumVariantId {
+            parent: variant_id.parent,
+            local_id: LocalEnumVariantId::from_raw(RawIdx::from(prev_idx - 1)),
+        };
+        return Ok(db.const_eval_variant(prev)? + 1);
+    }
+    let infer = &db.infer(def);
+    let result = eval_const(
+        body.body_expr,
+        &mut ConstEvalCtx {
+            db,
+            owner: def,
+            exprs: &body.exprs,
+            pats: &body.pats,
+            local_data: HashMap::default(),
+            infer,
+        },
+    )?;
+    match result {
+        ComputedExpr::Literal(Literal::Int(value, _)) => Ok(value),
+        ComputedExpr::Literal(Literal::Uint(value, _)) => {
+            value.try_into().map_err(|_| ConstEvalError::NotSupported("too big u128"))
+        }
+        _ => Err(ConstEvalError::SemanticError("discriminant is not an integer")),
+    }
+}
+
 pub(crate) fn eval_to_const<'a>(
     expr: Idx<Expr>,
     mode: ParamLoweringMode,
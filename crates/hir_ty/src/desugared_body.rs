@@ -0,0 +1,653 @@
+//! Renders a body the way the compiler sees it after desugaring: `?`, `for`
+//! loops and `.await` are spelled out, and overloaded indexing and derefs turn
+//! into the trait method calls they stand for.
+//!
+//! This is a user-facing view, not something we parse back, so the output only
+//! has to be readable Rust, not necessarily valid Rust.
+
+use std::fmt::Write;
+
+use hir_def::{
+    body::Body,
+    expr::{
+        Array, BinaryOp, BindingAnnotation, ClosureKind, Expr, ExprId, LabelId, Literal,
+        Movability, Pat, PatId, RangeOp, Statement, UnaryOp,
+    },
+    type_ref::{Mutability, Rawness, TypeRef},
+    DefWithBodyId,
+};
+use itertools::Itertools;
+use syntax::{TextRange, TextSize};
+
+use crate::{db::HirDatabase, display::HirDisplay, InferenceResult, Interner, TyKind};
+
+pub struct DesugaredBody {
+    pub text: String,
+    /// The range every printed expression occupies in `text`. Ranges of
+    /// subexpressions are nested in (and listed before) the ranges of their
+    /// parents, desugared constructs cover the whole expansion.
+    pub exprs: Vec<(TextRange, ExprId)>,
+}
+
+pub fn desugared_body(db: &dyn HirDatabase, def: DefWithBodyId) -> DesugaredBody {
+    let body = db.body(def);
+    let infer = db.infer(def);
+    let mut p = Printer {
+        db,
+        body: &body,
+        infer: &infer,
+        buf: String::new(),
+        indent: 0,
+        exprs: Vec::new(),
+    };
+
+    if let DefWithBodyId::FunctionId(it) = def {
+        let data = db.function_data(it);
+        p.buf.push_str("fn ");
+        let _ = write!(p.buf, "{}(", data.name);
+        for (i, (&pat, (_, ty))) in body.params.iter().zip(data.params.iter()).enumerate() {
+            if i != 0 {
+                p.buf.push_str(", ");
+            }
+            p.print_pat(pat);
+            let _ = write!(p.buf, ": {}", ty.display(db));
+        }
+        p.buf.push_str(") ");
+        if !matches!(&*data.ret_type, TypeRef::Tuple(it) if it.is_empty()) {
+            let _ = write!(p.buf, "-> {} ", data.ret_type.display(db));
+        }
+    }
+    p.print_expr(body.body_expr);
+    p.buf.push('\n');
+
+    DesugaredBody { text: p.buf, exprs: p.exprs }
+}
+
+struct Printer<'a> {
+    db: &'a dyn HirDatabase,
+    body: &'a Body,
+    infer: &'a InferenceResult,
+    buf: String,
+    indent: usize,
+    exprs: Vec<(TextRange, ExprId)>,
+}
+
+impl Printer<'_> {
+    fn offset(&self) -> TextSize {
+        TextSize::of(self.buf.as_str())
+    }
+
+    fn newline(&mut self) {
+        self.buf.push('\n');
+        for _ in 0..self.indent {
+            self.buf.push_str("    ");
+        }
+    }
+
+    /// Prints `{`, the lines produced by `f`, and the closing `}`.
+    fn block(&mut self, f: impl FnOnce(&mut Self)) {
+        self.buf.push('{');
+        self.indent += 1;
+        f(self);
+        self.indent -= 1;
+        self.newline();
+        self.buf.push('}');
+    }
+
+    fn label(&mut self, label: Option<LabelId>) {
+        if let Some(label) = label {
+            let _ = write!(self.buf, "{}: ", self.body.labels[label].name);
+        }
+    }
+
+    fn print_expr(&mut self, id: ExprId) {
+        let start = self.offset();
+        self.print_expr_inner(id);
+        self.exprs.push((TextRange::new(start, self.offset()), id));
+    }
+
+    /// Prints an operand of a prefix or postfix operator, parenthesized if
+    /// needed.
+    fn print_operand(&mut self, id: ExprId) {
+        let needs_parens = matches!(
+            self.body[id],
+            Expr::BinaryOp { .. }
+                | Expr::Range { .. }
+                | Expr::Cast { .. }
+                | Expr::Lambda { .. }
+                | Expr::Let { .. }
+                | Expr::Ref { .. }
+                | Expr::UnaryOp { .. }
+                | Expr::Box { .. }
+                | Expr::Return { .. }
+                | Expr::Break { .. }
+                | Expr::Yield { .. }
+        );
+        if needs_parens {
+            self.buf.push('(');
+        }
+        self.print_expr(id);
+        if needs_parens {
+            self.buf.push(')');
+        }
+    }
+
+    fn print_args(&mut self, args: &[ExprId]) {
+        self.buf.push('(');
+        for (i, &arg) in args.iter().enumerate() {
+            if i != 0 {
+                self.buf.push_str(", ");
+            }
+            self.print_expr(arg);
+        }
+        self.buf.push(')');
+    }
+
+    fn print_expr_inner(&mut self, id: ExprId) {
+        let (db, body) = (self.db, self.body);
+        match &body[id] {
+            Expr::Missing => self.buf.push_str("{missing}"),
+            Expr::Underscore => self.buf.push('_'),
+            Expr::Path(path) => {
+                let _ = write!(self.buf, "{}", path.display(db));
+            }
+            Expr::If { condition, then_branch, else_branch } => {
+                self.buf.push_str("if ");
+                self.print_expr(*condition);
+                self.buf.push(' ');
+                self.print_expr(*then_branch);
+                if let Some(else_branch) = else_branch {
+                    self.buf.push_str(" else ");
+                    self.print_expr(*else_branch);
+                }
+            }
+            Expr::Let { pat, expr } => {
+                self.buf.push_str("let ");
+                self.print_pat(*pat);
+                self.buf.push_str(" = ");
+                self.print_expr(*expr);
+            }
+            Expr::Block { statements, tail, label, .. } => {
+                self.label(*label);
+                self.block(|p| {
+                    for stmt in statements.iter() {
+                        p.newline();
+                        p.print_stmt(stmt);
+                    }
+                    if let Some(tail) = tail {
+                        p.newline();
+                        p.print_expr(*tail);
+                    }
+                });
+            }
+            Expr::Loop { body, label } => {
+                self.label(*label);
+                self.buf.push_str("loop ");
+                self.print_expr(*body);
+            }
+            Expr::While { condition, body, label } => {
+                self.label(*label);
+                self.buf.push_str("while ");
+                self.print_expr(*condition);
+                self.buf.push(' ');
+                self.print_expr(*body);
+            }
+            Expr::For { iterable, pat, body, label } => {
+                self.buf.push_str("match IntoIterator::into_iter(");
+                self.print_expr(*iterable);
+                self.buf.push_str(") ");
+                self.block(|p| {
+                    p.newline();
+                    p.buf.push_str("mut iter => ");
+                    p.label(*label);
+                    p.buf.push_str("loop ");
+                    p.block(|p| {
+                        p.newline();
+                        p.buf.push_str("match Iterator::next(&mut iter) ");
+                        p.block(|p| {
+                            p.newline();
+                            p.buf.push_str("None => break,");
+                            p.newline();
+                            p.buf.push_str("Some(");
+                            p.print_pat(*pat);
+                            p.buf.push_str(") => ");
+                            p.print_expr(*body);
+                            p.buf.push(',');
+                        });
+                    });
+                    p.buf.push(',');
+                });
+            }
+            Expr::Call { callee, args } => {
+                self.print_operand(*callee);
+                self.print_args(args);
+            }
+            Expr::MethodCall { receiver, method_name, args, generic_args } => {
+                self.print_operand(*receiver);
+                let _ = write!(self.buf, ".{}", method_name);
+                if let Some(generic_args) = generic_args {
+                    let args = generic_args.args.iter().map(|it| it.display(db)).join(", ");
+                    let _ = write!(self.buf, "::<{}>", args);
+                }
+                self.print_args(args);
+            }
+            Expr::Match { expr, arms } => {
+                self.buf.push_str("match ");
+                self.print_expr(*expr);
+                self.buf.push(' ');
+                self.block(|p| {
+                    for arm in arms.iter() {
+                        p.newline();
+                        p.print_pat(arm.pat);
+                        if let Some(guard) = arm.guard {
+                            p.buf.push_str(" if ");
+                            p.print_expr(guard);
+                        }
+                        p.buf.push_str(" => ");
+                        p.print_expr(arm.expr);
+                        p.buf.push(',');
+                    }
+                });
+            }
+            Expr::Continue { label } => {
+                self.buf.push_str("continue");
+                if let Some(label) = label {
+                    let _ = write!(self.buf, " {}", label);
+                }
+            }
+            Expr::Break { expr, label } => {
+                self.buf.push_str("break");
+                if let Some(label) = label {
+                    let _ = write!(self.buf, " {}", label);
+                }
+                if let Some(expr) = expr {
+                    self.buf.push(' ');
+                    self.print_expr(*expr);
+                }
+            }
+            Expr::Return { expr } => {
+                self.buf.push_str("return");
+                if let Some(expr) = expr {
+                    self.buf.push(' ');
+                    self.print_expr(*expr);
+                }
+            }
+            Expr::Yield { expr } => {
+                self.buf.push_str("yield");
+                if let Some(expr) = expr {
+                    self.buf.push(' ');
+                    self.print_expr(*expr);
+                }
+            }
+            Expr::RecordLit { path, fields, spread } => {
+                match path {
+                    Some(path) => {
+                        let _ = write!(self.buf, "{} ", path.display(db));
+                    }
+                    None => self.buf.push_str("{missing} "),
+                }
+                self.block(|p| {
+                    for field in fields.iter() {
+                        p.newline();
+                        let _ = write!(p.buf, "{}: ", field.name);
+                        p.print_expr(field.expr);
+                        p.buf.push(',');
+                    }
+                    if let Some(spread) = spread {
+                        p.newline();
+                        p.buf.push_str("..");
+                        p.print_expr(*spread);
+                    }
+                });
+            }
+            Expr::Field { expr, name } => {
+                self.print_operand(*expr);
+                let _ = write!(self.buf, ".{}", name);
+            }
+            Expr::Await { expr } => {
+                self.buf.push_str("match IntoFuture::into_future(");
+                self.print_expr(*expr);
+                self.buf.push_str(") ");
+                self.block(|p| {
+                    p.newline();
+                    p.buf.push_str("mut pinned => loop ");
+                    p.block(|p| {
+                        p.newline();
+                        p.buf.push_str(
+                            "match unsafe { Future::poll(Pin::new_unchecked(&mut pinned), get_context(task_context)) } ",
+                        );
+                        p.block(|p| {
+                            p.newline();
+                            p.buf.push_str("Poll::Ready(result) => break result,");
+                            p.newline();
+                            p.buf.push_str("Poll::Pending => {}");
+                        });
+                        p.newline();
+                        p.buf.push_str("task_context = yield ();");
+                    });
+                    p.buf.push(',');
+                });
+            }
+            Expr::Try { expr } => {
+                self.buf.push_str("match Try::branch(");
+                self.print_expr(*expr);
+                self.buf.push_str(") ");
+                self.block(|p| {
+                    p.newline();
+                    p.buf.push_str("ControlFlow::Continue(val) => val,");
+                    p.newline();
+                    p.buf.push_str(
+                        "ControlFlow::Break(residual) => return FromResidual::from_residual(residual),",
+                    );
+                });
+            }
+            Expr::TryBlock { body } => {
+                self.buf.push_str("try ");
+                self.print_expr(*body);
+            }
+            Expr::Async { body } => {
+                self.buf.push_str("async ");
+                self.print_expr(*body);
+            }
+            Expr::Const { body } => {
+                self.buf.push_str("const ");
+                self.print_expr(*body);
+            }
+            Expr::Unsafe { body } => {
+                self.buf.push_str("unsafe ");
+                self.print_expr(*body);
+            }
+            Expr::Cast { expr, type_ref } => {
+                self.print_operand(*expr);
+                let _ = write!(self.buf, " as {}", type_ref.display(db));
+            }
+            Expr::Ref { expr, rawness, mutability } => {
+                self.buf.push('&');
+                match (rawness, mutability) {
+                    (Rawness::RawPtr, Mutability::Mut) => self.buf.push_str("raw mut "),
+                    (Rawness::RawPtr, Mutability::Shared) => self.buf.push_str("raw const "),
+                    (Rawness::Ref, Mutability::Mut) => self.buf.push_str("mut "),
+                    (Rawness::Ref, Mutability::Shared) => (),
+                }
+                self.print_operand(*expr);
+            }
+            Expr::Box { expr } => {
+                self.buf.push_str("box ");
+                self.print_operand(*expr);
+            }
+            Expr::UnaryOp { expr, op: UnaryOp::Deref } if self.is_overloaded_deref(*expr) => {
+                self.buf.push_str("*Deref::deref(&");
+                self.print_operand(*expr);
+                self.buf.push(')');
+            }
+            Expr::UnaryOp { expr, op } => {
+                self.buf.push_str(match op {
+                    UnaryOp::Deref => "*",
+                    UnaryOp::Not => "!",
+                    UnaryOp::Neg => "-",
+                });
+                self.print_operand(*expr);
+            }
+            Expr::BinaryOp { lhs, rhs, op } => {
+                self.print_operand(*lhs);
+                let _ = match op {
+                    Some(BinaryOp::Assignment { op: Some(op) }) => write!(self.buf, " {}= ", op),
+                    Some(op) => write!(self.buf, " {} ", op),
+                    None => write!(self.buf, " {{missing}} "),
+                };
+                self.print_operand(*rhs);
+            }
+            Expr::Range { lhs, rhs, range_type } => {
+                if let Some(lhs) = lhs {
+                    self.print_operand(*lhs);
+                }
+                self.buf.push_str(match range_type {
+                    RangeOp::HalfOpen => "..",
+                    RangeOp::Inclusive => "..=",
+                });
+                if let Some(rhs) = rhs {
+                    self.print_operand(*rhs);
+                }
+            }
+            Expr::Index { base, index } => {
+                if self.is_overloaded_index(*base) {
+                    self.buf.push_str("*Index::index(&");
+                    self.print_operand(*base);
+                    self.buf.push_str(", ");
+                    self.print_expr(*index);
+                    self.buf.push(')');
+                } else {
+                    self.print_operand(*base);
+                    self.buf.push('[');
+                    self.print_expr(*index);
+                    self.buf.push(']');
+                }
+            }
+            Expr::Lambda { args, arg_types, ret_type, body, closure_kind } => {
+                if let ClosureKind::Generator(Movability::Static) = closure_kind {
+                    self.buf.push_str("static ");
+                }
+                self.buf.push('|');
+                for (i, (&pat, ty)) in args.iter().zip(arg_types.iter()).enumerate() {
+                    if i != 0 {
+                        self.buf.push_str(", ");
+                    }
+                    self.print_pat(pat);
+                    if let Some(ty) = ty {
+                        let _ = write!(self.buf, ": {}", ty.display(db));
+                    }
+                }
+                self.buf.push_str("| ");
+                if let Some(ret_type) = ret_type {
+                    let _ = write!(self.buf, "-> {} ", ret_type.display(db));
+                }
+                self.print_expr(*body);
+            }
+            Expr::Tuple { exprs } => {
+                self.print_args(exprs);
+                if exprs.len() == 1 {
+                    self.buf.insert(self.buf.len() - 1, ',');
+                }
+            }
+            Expr::MacroStmts { tail } => self.print_expr(*tail),
+            Expr::Array(Array::ElementList(exprs)) => {
+                self.buf.push('[');
+                for (i, &expr) in exprs.iter().enumerate() {
+                    if i != 0 {
+                        self.buf.push_str(", ");
+                    }
+                    self.print_expr(expr);
+                }
+                self.buf.push(']');
+            }
+            Expr::Array(Array::Repeat { initializer, repeat }) => {
+                self.buf.push('[');
+                self.print_expr(*initializer);
+                self.buf.push_str("; ");
+                self.print_expr(*repeat);
+                self.buf.push(']');
+            }
+            Expr::Literal(lit) => self.print_literal(lit),
+        }
+    }
+
+    fn print_stmt(&mut self, stmt: &Statement) {
+        match stmt {
+            Statement::Let { pat, type_ref, initializer, else_branch } => {
+                self.buf.push_str("let ");
+                self.print_pat(*pat);
+                if let Some(ty) = type_ref {
+                    let _ = write!(self.buf, ": {}", ty.display(self.db));
+                }
+                if let Some(initializer) = initializer {
+                    self.buf.push_str(" = ");
+                    self.print_expr(*initializer);
+                }
+                if let Some(else_branch) = else_branch {
+                    self.buf.push_str(" else ");
+                    self.print_expr(*else_branch);
+                }
+                self.buf.push(';');
+            }
+            Statement::Expr { expr, has_semi } => {
+                self.print_expr(*expr);
+                if *has_semi {
+                    self.buf.push(';');
+                }
+            }
+        }
+    }
+
+    fn print_literal(&mut self, lit: &Literal) {
+        let _ = match lit {
+            Literal::String(it) => write!(self.buf, "{:?}", it),
+            Literal::ByteString(it) => {
+                let escaped =
+                    it.iter().flat_map(|&b| std::ascii::escape_default(b)).map(char::from);
+                write!(self.buf, "b\"{}\"", escaped.collect::<String>())
+            }
+            Literal::Char(it) => write!(self.buf, "{:?}", it),
+            Literal::Bool(it) => write!(self.buf, "{}", it),
+            Literal::Int(it, _) => write!(self.buf, "{}", it),
+            Literal::Uint(it, _) => write!(self.buf, "{}", it),
+            Literal::Float(it, _) => write!(self.buf, "{:?}", f64::from_bits(*it)),
+        };
+    }
+
+    fn print_pat(&mut self, id: PatId) {
+        let (db, body) = (self.db, self.body);
+        match &body[id] {
+            Pat::Missing => self.buf.push_str("{missing}"),
+            Pat::Wild => self.buf.push('_'),
+            Pat::Tuple { args, ellipsis } => {
+                self.print_pat_list(args, *ellipsis);
+                if args.len() == 1 && ellipsis.is_none() {
+                    self.buf.insert(self.buf.len() - 1, ',');
+                }
+            }
+            Pat::Or(pats) => {
+                for (i, &pat) in pats.iter().enumerate() {
+                    if i != 0 {
+                        self.buf.push_str(" | ");
+                    }
+                    self.print_pat(pat);
+                }
+            }
+            Pat::Record { path, args, ellipsis } => {
+                match path {
+                    Some(path) => {
+                        let _ = write!(self.buf, "{}", path.display(db));
+                    }
+                    None => self.buf.push_str("{missing}"),
+                }
+                self.buf.push_str(" {");
+                for (i, field) in args.iter().enumerate() {
+                    self.buf.push_str(if i == 0 { " " } else { ", " });
+                    let _ = write!(self.buf, "{}: ", field.name);
+                    self.print_pat(field.pat);
+                }
+                if *ellipsis {
+                    self.buf.push_str(if args.is_empty() { " .." } else { ", .." });
+                }
+                self.buf.push_str(" }");
+            }
+            Pat::Range { start, end } => {
+                self.print_expr(*start);
+                self.buf.push_str("..=");
+                self.print_expr(*end);
+            }
+            Pat::Slice { prefix, slice, suffix } => {
+                self.buf.push('[');
+                let pats = prefix.iter().chain(slice.iter()).chain(suffix.iter());
+                for (i, &pat) in pats.enumerate() {
+                    if i != 0 {
+                        self.buf.push_str(", ");
+                    }
+                    self.print_pat(pat);
+                }
+                self.buf.push(']');
+            }
+            Pat::Path(path) => {
+                let _ = write!(self.buf, "{}", path.display(db));
+            }
+            Pat::Lit(expr) => self.print_expr(*expr),
+            Pat::Bind { mode, name, subpat } => {
+                self.buf.push_str(match mode {
+                    BindingAnnotation::Unannotated => "",
+                    BindingAnnotation::Mutable => "mut ",
+                    BindingAnnotation::Ref => "ref ",
+                    BindingAnnotation::RefMut => "ref mut ",
+                });
+                let _ = write!(self.buf, "{}", name);
+                if let Some(subpat) = subpat {
+                    self.buf.push_str(" @ ");
+                    self.print_pat(*subpat);
+                }
+            }
+            Pat::TupleStruct { path, args, ellipsis } => {
+                match path {
+                    Some(path) => {
+                        let _ = write!(self.buf, "{}", path.display(db));
+                    }
+                    None => self.buf.push_str("{missing}"),
+                }
+                self.print_pat_list(args, *ellipsis);
+            }
+            Pat::Ref { pat, mutability } => {
+                self.buf.push_str(match mutability {
+                    Mutability::Shared => "&",
+                    Mutability::Mut => "&mut ",
+                });
+                self.print_pat(*pat);
+            }
+            Pat::Box { inner } => {
+                self.buf.push_str("box ");
+                self.print_pat(*inner);
+            }
+            Pat::ConstBlock(expr) => {
+                self.buf.push_str("const ");
+                self.print_expr(*expr);
+            }
+        }
+    }
+
+    fn print_pat_list(&mut self, args: &[PatId], ellipsis: Option<usize>) {
+        let mut pats = args.iter().map(|&it| Some(it)).collect::<Vec<_>>();
+        if let Some(idx) = ellipsis {
+            pats.insert(idx.min(pats.len()), None);
+        }
+        self.buf.push('(');
+        for (i, pat) in pats.into_iter().enumerate() {
+            if i != 0 {
+                self.buf.push_str(", ");
+            }
+            match pat {
+                Some(pat) => self.print_pat(pat),
+                None => self.buf.push_str(".."),
+            }
+        }
+        self.buf.push(')');
+    }
+
+    /// Whether `*expr` goes through `Deref::deref`, which is the case unless
+    /// `expr` is a reference or a raw pointer.
+    fn is_overloaded_deref(&self, expr: ExprId) -> bool {
+        match self.infer.type_of_expr.get(expr).map(|ty| ty.kind(Interner)) {
+            Some(TyKind::Ref(..) | TyKind::Raw(..) | TyKind::Error) | None => false,
+            Some(_) => true,
+        }
+    }
+
+    /// Whether indexing into `base` goes through `Index::index`, which is the
+    /// case unless `base` is an array or a slice, possibly behind references.
+    fn is_overloaded_index(&self, base: ExprId) -> bool {
+        let mut ty = match self.infer.type_of_expr.get(base) {
+            Some(it) => it,
+            None => return false,
+        };
+        while let TyKind::Ref(_, _, inner) = ty.kind(Interner) {
+            ty = inner;
+        }
+        !matches!(ty.kind(Interner), TyKind::Array(..) | TyKind::Slice(_) | TyKind::Error)
+    }
+}
@@ -1,13 +1,18 @@
 //! Type inference-based diagnostics.
+mod coherence;
 mod expr;
 mod match_check;
 mod unsafe_check;
 mod decl_check;
+mod moves;
 
 pub use crate::diagnostics::{
+    coherence::{is_orphan_impl, overlapping_impls},
     decl_check::{incorrect_case, IncorrectCase},
     expr::{
         record_literal_missing_fields, record_pattern_missing_fields, BodyValidationDiagnostic,
+        MatchArmsCheck,
     },
-    unsafe_check::{missing_unsafe, unsafe_expressions, UnsafeExpr},
+    moves::{use_after_move, UseAfterMove},
+    unsafe_check::{missing_unsafe, unsafe_expressions, UnsafeExpr, UnsafeExprKind},
 };
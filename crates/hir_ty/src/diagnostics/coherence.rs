@@ -0,0 +1,161 @@
+//! A conservative version of the coherence checks: trait impls which overlap
+//! with another impl of the same trait, and impls which break the orphan rules.
+//!
+//! Like the other diagnostics, these must never fire on code `rustc` accepts,
+//! so both checks give up whenever the answer depends on something we don't
+//! model well: specialization, where clauses the trait solver can't decide,
+//! trait objects or associated type projections.
+
+use std::sync::Arc;
+
+use base_db::CrateId;
+use chalk_ir::cast::Cast;
+use hir_def::{lang_item::LangItemTarget, AdtId, AttrDefId, HasModule, ImplId, Lookup};
+use syntax::SmolStr;
+
+use crate::{
+    db::HirDatabase, infer::unify::InferenceTable, method_resolution::TyFingerprint, Goal,
+    Interner, Solution, TraitEnvironment, TraitRefExt, Ty, TyBuilder, TyKind, WhereClause,
+};
+
+/// The impls of the same trait `impl_id` overlaps with, in its crate and its dependencies.
+pub fn overlapping_impls(db: &dyn HirDatabase, impl_id: ImplId) -> Vec<ImplId> {
+    let _p = profile::span("overlapping_impls");
+    if db.impl_data(impl_id).is_negative {
+        return Vec::new();
+    }
+    let trait_ref = match db.impl_trait(impl_id) {
+        Some(it) => it,
+        None => return Vec::new(),
+    };
+    let trait_id = trait_ref.skip_binders().hir_trait_id();
+    let krate = impl_id.lookup(db.upcast()).container.krate();
+    if specialization_enabled(db, impl_id) {
+        return Vec::new();
+    }
+
+    let trait_impls = db.trait_impls_in_deps(krate);
+    let candidates: Vec<ImplId> =
+        match TyFingerprint::for_trait_impl(db.impl_self_ty(impl_id).skip_binders()) {
+            Some(fp) => trait_impls.for_trait_and_self_ty(trait_id, fp).collect(),
+            None => trait_impls.for_trait(trait_id).collect(),
+        };
+    let env = Arc::new(TraitEnvironment::empty(krate));
+    candidates
+        .into_iter()
+        .filter(|&other| other != impl_id && !db.impl_data(other).is_negative)
+        .filter(|&other| impls_overlap(db, env.clone(), impl_id, other))
+        .collect()
+}
+
+/// Whether `impl_id` implements a trait of another crate for types which all are from other
+/// crates too, which the orphan rules forbid.
+pub fn is_orphan_impl(db: &dyn HirDatabase, impl_id: ImplId) -> bool {
+    let trait_ref = match db.impl_trait(impl_id) {
+        Some(it) => it,
+        None => return false,
+    };
+    let krate = impl_id.lookup(db.upcast()).container.krate();
+    let trait_id = trait_ref.skip_binders().hir_trait_id();
+    if trait_id.lookup(db.upcast()).container.krate() == krate {
+        return false;
+    }
+    trait_ref
+        .skip_binders()
+        .substitution
+        .iter(Interner)
+        .filter_map(|arg| arg.ty(Interner))
+        .all(|ty| is_foreign_type(db, krate, ty))
+}
+
+/// Whether two impls of the same trait apply to the same types: their trait refs unify, and the
+/// trait solver can't rule out any of their where clauses for the unified types.
+fn impls_overlap(
+    db: &dyn HirDatabase,
+    env: Arc<TraitEnvironment>,
+    impl_id: ImplId,
+    other: ImplId,
+) -> bool {
+    let krate = env.krate;
+    let sized_trait = match db.lang_item(krate, SmolStr::new_inline("sized")) {
+        Some(LangItemTarget::TraitId(it)) => Some(it),
+        _ => None,
+    };
+    let mut table = InferenceTable::new(db, env);
+    let instantiate = |table: &mut InferenceTable, impl_id: ImplId| {
+        let subst = TyBuilder::subst_for_def(db, impl_id).fill_with_inference_vars(table).build();
+        let trait_ref = db.impl_trait(impl_id)?.substitute(Interner, &subst);
+        Some((trait_ref, subst))
+    };
+    let ((trait_ref, subst), (other_trait_ref, other_subst)) =
+        match (instantiate(&mut table, impl_id), instantiate(&mut table, other)) {
+            (Some(it), Some(other)) => (it, other),
+            _ => return false,
+        };
+    match table.try_unify(&trait_ref.substitution, &other_trait_ref.substitution) {
+        Ok(result) => table.register_infer_ok(result),
+        Err(_) => return false,
+    }
+
+    for (impl_id, subst) in [(impl_id, subst), (other, other_subst)] {
+        for predicate in db.generic_predicates(impl_id.into()).iter() {
+            let (predicate, binders) =
+                predicate.clone().substitute(Interner, &subst).into_value_and_skipped_binders();
+            if binders.len(Interner) != 0 {
+                // Higher-ranked where clauses are not handled.
+                return false;
+            }
+            let is_sized_bound = matches!(
+                &predicate,
+                WhereClause::Implemented(it) if Some(it.hir_trait_id()) == sized_trait
+            );
+            let goal: Goal = predicate.cast(Interner);
+            match table.try_obligation(goal) {
+                // The clause never holds, so the impls can't apply at the same time.
+                None => return false,
+                Some(Solution::Unique(_)) => (),
+                // Any type the variable is inferred to is likely to be `Sized`.
+                Some(Solution::Ambig(_)) if is_sized_bound => (),
+                Some(Solution::Ambig(_)) => return false,
+            }
+        }
+    }
+    true
+}
+
+/// Whether `ty` is certainly not a local type for the orphan rules. Generic parameters aren't
+/// local, and references are as local as the type they point to, like the other fundamental
+/// types we don't look into.
+fn is_foreign_type(db: &dyn HirDatabase, krate: CrateId, ty: &Ty) -> bool {
+    match ty.kind(Interner) {
+        TyKind::Adt(chalk_ir::AdtId(adt), _) => {
+            adt.module(db.upcast()).krate() != krate && !is_fundamental(db, *adt)
+        }
+        TyKind::Ref(_, _, inner) => is_foreign_type(db, krate, inner),
+        TyKind::Scalar(_)
+        | TyKind::Str
+        | TyKind::Never
+        | TyKind::Raw(..)
+        | TyKind::Slice(_)
+        | TyKind::Array(..)
+        | TyKind::Tuple(..)
+        | TyKind::Function(_)
+        | TyKind::BoundVar(_)
+        | TyKind::Placeholder(_) => true,
+        _ => false,
+    }
+}
+
+fn is_fundamental(db: &dyn HirDatabase, adt: AdtId) -> bool {
+    db.attrs(AttrDefId::AdtId(adt)).by_key("fundamental").exists()
+}
+
+/// Specialization allows overlapping impls, we don't check which one is more specific.
+fn specialization_enabled(db: &dyn HirDatabase, impl_id: ImplId) -> bool {
+    let def_map = db.crate_def_map(impl_id.lookup(db.upcast()).container.krate());
+    let root = def_map.module_id(def_map.root());
+    db.attrs(AttrDefId::ModuleId(root))
+        .by_key("feature")
+        .tt_values()
+        .any(|tt| tt.to_string().contains("specialization"))
+}
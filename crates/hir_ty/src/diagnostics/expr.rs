@@ -15,9 +15,9 @@ use crate::{
     diagnostics::match_check::{
         self,
         deconstruct_pat::DeconstructedPat,
-        usefulness::{compute_match_usefulness, MatchCheckCtx},
+        usefulness::{compute_match_usefulness, MatchCheckCtx, Reachability},
     },
-    InferenceResult, TyExt,
+    InferenceResult, Ty, TyExt,
 };
 
 pub(crate) use hir_def::{
@@ -50,6 +50,82 @@ impl BodyValidationDiagnostic {
     }
 }
 
+/// Answers how a `match` would behave with its arms rearranged, using the same pattern lowering
+/// and usefulness algorithm as the exhaustiveness check.
+pub struct MatchArmsCheck {
+    owner: DefWithBodyId,
+    body: Arc<Body>,
+    infer: Arc<InferenceResult>,
+    scrutinee_ty: Ty,
+}
+
+impl MatchArmsCheck {
+    pub fn new(db: &dyn HirDatabase, owner: DefWithBodyId, match_expr: ExprId) -> Option<Self> {
+        let body = db.body(owner);
+        let infer = db.infer(owner);
+        let scrutinee = match body[match_expr] {
+            Expr::Match { expr, .. } => expr,
+            _ => return None,
+        };
+        let scrutinee_ty = infer[scrutinee].clone();
+        if scrutinee_ty.is_unknown() {
+            return None;
+        }
+        Some(MatchArmsCheck { owner, body, infer, scrutinee_ty })
+    }
+
+    /// Whether some value is matched by both patterns. Answers `true` when either pattern can't be
+    /// analyzed.
+    pub fn overlap(&self, db: &dyn HirDatabase, pat: PatId, other: PatId) -> bool {
+        match (self.lower(db, pat), self.lower(db, other)) {
+            (Some(pat), Some(other)) => pat.overlaps(&other),
+            _ => true,
+        }
+    }
+
+    /// For each arm, given as its pattern and whether it has a guard, whether it would be reachable
+    /// in a `match` with exactly these arms, in this order.
+    pub fn reachability(&self, db: &dyn HirDatabase, arms: &[(PatId, bool)]) -> Option<Vec<bool>> {
+        let pats = arms.iter().map(|&(pat, _)| self.lower(db, pat)).collect::<Option<Vec<_>>>()?;
+        let pattern_arena = Arena::new();
+        let cx = MatchCheckCtx {
+            module: self.owner.module(db.upcast()),
+            body: self.owner,
+            db,
+            pattern_arena: &pattern_arena,
+        };
+        let m_arms = pats
+            .iter()
+            .zip(arms)
+            .map(|(pat, &(_, has_guard))| match_check::MatchArm {
+                pat: pattern_arena.alloc(DeconstructedPat::from_pat(&cx, pat)),
+                has_guard,
+            })
+            .collect::<Vec<_>>();
+        let report = compute_match_usefulness(&cx, &m_arms, &self.scrutinee_ty);
+        Some(
+            report
+                .arm_usefulness
+                .iter()
+                .map(|(_, reachability)| matches!(reachability, Reachability::Reachable))
+                .collect(),
+        )
+    }
+
+    fn lower(&self, db: &dyn HirDatabase, pat: PatId) -> Option<match_check::Pat> {
+        // Like `validate_match`, only look at patterns whose type is the scrutinee's.
+        let pat_ty = self.infer.type_of_pat.get(pat)?;
+        let fits = pat_ty == &self.scrutinee_ty
+            || self.scrutinee_ty.as_reference().map_or(false, |(ty, ..)| ty == pat_ty);
+        if !fits || !types_of_subpatterns_do_match(pat, &self.body, &self.infer) {
+            return None;
+        }
+        let mut patcx = match_check::PatCtxt::new(db, &self.infer, &self.body);
+        let pat = patcx.lower_pattern(pat);
+        patcx.errors.is_empty().then(|| pat)
+    }
+}
+
 struct ExprValidator {
     owner: DefWithBodyId,
     infer: Arc<InferenceResult>,
@@ -83,6 +83,48 @@ pub(crate) enum PatKind {
     },
 }
 
+impl Pat {
+    /// Whether some value is matched by both `self` and `other`.
+    pub(crate) fn overlaps(&self, other: &Pat) -> bool {
+        match (&*self.kind, &*other.kind) {
+            (PatKind::Wild, _) | (_, PatKind::Wild) => true,
+            (PatKind::Binding { subpattern }, _) => {
+                subpattern.as_ref().map_or(true, |it| it.overlaps(other))
+            }
+            (_, PatKind::Binding { subpattern }) => {
+                subpattern.as_ref().map_or(true, |it| self.overlaps(it))
+            }
+            (PatKind::Or { pats }, _) => pats.iter().any(|it| it.overlaps(other)),
+            (_, PatKind::Or { pats }) => pats.iter().any(|it| self.overlaps(it)),
+            (PatKind::Deref { subpattern }, PatKind::Deref { subpattern: other }) => {
+                subpattern.overlaps(other)
+            }
+            (PatKind::LiteralBool { value }, PatKind::LiteralBool { value: other }) => {
+                value == other
+            }
+            (
+                PatKind::Variant { enum_variant, subpatterns, .. },
+                PatKind::Variant { enum_variant: other_variant, subpatterns: other, .. },
+            ) => enum_variant == other_variant && fields_overlap(subpatterns, other),
+            (PatKind::Leaf { subpatterns }, PatKind::Leaf { subpatterns: other }) => {
+                fields_overlap(subpatterns, other)
+            }
+            // Lowering doesn't produce differently shaped patterns for the same type.
+            _ => true,
+        }
+    }
+}
+
+fn fields_overlap(fields: &[FieldPat], other: &[FieldPat]) -> bool {
+    // A field missing from one side is a wildcard there.
+    fields.iter().all(|field| {
+        other
+            .iter()
+            .find(|it| it.field == field.field)
+            .map_or(true, |it| field.pattern.overlaps(&it.pattern))
+    })
+}
+
 pub(crate) struct PatCtxt<'a> {
     db: &'a dyn HirDatabase,
     infer: &'a InferenceResult,
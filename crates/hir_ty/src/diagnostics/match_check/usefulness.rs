@@ -751,7 +751,7 @@ pub(crate) enum Reachability {
 /// The output of checking a match for exhaustiveness and arm reachability.
 pub(crate) struct UsefulnessReport<'p> {
     /// For each arm of the input, whether that arm is reachable after the arms above it.
-    pub(crate) _arm_usefulness: Vec<(MatchArm<'p>, Reachability)>,
+    pub(crate) arm_usefulness: Vec<(MatchArm<'p>, Reachability)>,
     /// If the match is exhaustive, this is empty. If not, this contains witnesses for the lack of
     /// exhaustiveness.
     pub(crate) non_exhaustiveness_witnesses: Vec<DeconstructedPat<'p>>,
@@ -793,7 +793,7 @@ pub(crate) fn compute_match_usefulness<'p>(
         WithWitnesses(pats) => pats.into_iter().map(Witness::single_pattern).collect(),
         NoWitnesses { .. } => panic!("bug"),
     };
-    UsefulnessReport { _arm_usefulness: arm_usefulness, non_exhaustiveness_witnesses }
+    UsefulnessReport { arm_usefulness, non_exhaustiveness_witnesses }
 }
 
 pub(crate) mod helper {
@@ -0,0 +1,292 @@
+//! A conservative use-after-move check.
+//!
+//! This is nowhere near a borrow checker: we only track whole local bindings
+//! of non-`Copy` types which are moved by unconditionally executed code, and
+//! flag any later mention of them. Moves in branches, loops and closures are
+//! forgotten once we leave them, and a binding which is assigned to anywhere
+//! is considered initialized again, so we may miss errors, but should never
+//! report code `rustc` accepts.
+
+use std::sync::Arc;
+
+use hir_def::{
+    body::Body,
+    expr::{Array, BinaryOp, BindingAnnotation, Expr, ExprId, Pat, PatId, Statement},
+    lang_item::LangItemTarget,
+    resolver::{resolver_for_expr, ValueNs},
+    DefWithBodyId, TraitId,
+};
+use rustc_hash::{FxHashMap, FxHashSet};
+use syntax::SmolStr;
+
+use crate::{
+    db::HirDatabase, method_resolution::implements_trait, Adjust, Canonical, CanonicalVarKinds,
+    InferenceResult, Interner, TraitEnvironment, Ty, TyKind,
+};
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct UseAfterMove {
+    /// The local binding which was moved.
+    pub binding: PatId,
+    /// The expression moving the binding.
+    pub moved_at: ExprId,
+    /// The first mention of the binding after the move.
+    pub used_at: ExprId,
+}
+
+pub fn use_after_move(db: &dyn HirDatabase, def: DefWithBodyId) -> Vec<UseAfterMove> {
+    let _p = profile::span("use_after_move");
+    let krate = def.module(db.upcast()).krate();
+    let copy_trait = match db.lang_item(krate, SmolStr::new_inline("copy")) {
+        Some(LangItemTarget::TraitId(it)) => it,
+        // Without `Copy`, every value would look like it's moved.
+        _ => return Vec::new(),
+    };
+    let env = def
+        .as_generic_def_id()
+        .map_or_else(|| Arc::new(TraitEnvironment::empty(krate)), |d| db.trait_environment(d));
+    let body = db.body(def);
+    let infer = db.infer(def);
+
+    let mut checker = MoveChecker {
+        db,
+        def,
+        body: &body,
+        infer: &infer,
+        env,
+        copy_trait,
+        moved: FxHashMap::default(),
+        reinitialized: Vec::new(),
+        reported: FxHashSet::default(),
+        res: Vec::new(),
+    };
+    checker.walk(body.body_expr, false);
+    checker.res
+}
+
+struct MoveChecker<'a> {
+    db: &'a dyn HirDatabase,
+    def: DefWithBodyId,
+    body: &'a Body,
+    infer: &'a InferenceResult,
+    env: Arc<TraitEnvironment>,
+    copy_trait: TraitId,
+    /// Bindings which are definitely moved, with the expression moving them.
+    moved: FxHashMap<PatId, ExprId>,
+    /// Every binding assigned to so far, in order.
+    reinitialized: Vec<PatId>,
+    reported: FxHashSet<PatId>,
+    res: Vec<UseAfterMove>,
+}
+
+impl MoveChecker<'_> {
+    /// Walks `expr` in evaluation order. `consumed` is true if the value of
+    /// `expr` is moved into its context.
+    fn walk(&mut self, expr: ExprId, consumed: bool) {
+        let body = self.body;
+        match &body[expr] {
+            Expr::Path(_) => {
+                let binding = match self.local_binding(expr) {
+                    Some(it) => it,
+                    None => return,
+                };
+                if let Some(moved_at) = self.moved.remove(&binding) {
+                    if self.reported.insert(binding) {
+                        self.res.push(UseAfterMove { binding, moved_at, used_at: expr });
+                    }
+                    return;
+                }
+                if consumed && self.is_move(expr) {
+                    self.moved.insert(binding, expr);
+                }
+            }
+            Expr::Let { expr, .. } => self.walk(*expr, false),
+            Expr::Block { statements, tail, .. } => {
+                for stmt in statements.iter() {
+                    match stmt {
+                        Statement::Let { pat, initializer, else_branch, .. } => {
+                            if let Some(init) = initializer {
+                                self.walk(*init, self.binds_by_value(*pat));
+                            }
+                            if let Some(else_branch) = else_branch {
+                                self.walk_conditionally(*else_branch);
+                            }
+                        }
+                        Statement::Expr { expr, .. } => self.walk(*expr, false),
+                    }
+                }
+                if let Some(tail) = tail {
+                    self.walk(*tail, consumed);
+                }
+            }
+            Expr::If { condition, then_branch, else_branch } => {
+                self.walk(*condition, false);
+                self.walk_conditionally(*then_branch);
+                if let Some(else_branch) = else_branch {
+                    self.walk_conditionally(*else_branch);
+                }
+            }
+            Expr::Match { expr, arms } => {
+                self.walk(*expr, false);
+                for arm in arms.iter() {
+                    if let Some(guard) = arm.guard {
+                        self.walk_conditionally(guard);
+                    }
+                    self.walk_conditionally(arm.expr);
+                }
+            }
+            Expr::Loop { body, .. } => self.walk_conditionally(*body),
+            Expr::While { condition, body, .. } => {
+                self.walk_conditionally(*condition);
+                self.walk_conditionally(*body);
+            }
+            Expr::For { iterable, body, .. } => {
+                self.walk(*iterable, true);
+                self.walk_conditionally(*body);
+            }
+            Expr::Call { callee, args } => {
+                self.walk(*callee, false);
+                for arg in args.iter() {
+                    self.walk(*arg, true);
+                }
+            }
+            Expr::MethodCall { receiver, args, .. } => {
+                // Auto-referenced receivers are not moved, see `is_move`.
+                self.walk(*receiver, true);
+                for arg in args.iter() {
+                    self.walk(*arg, true);
+                }
+            }
+            Expr::Return { expr } | Expr::Break { expr, .. } | Expr::Yield { expr } => {
+                if let Some(expr) = expr {
+                    self.walk(*expr, true);
+                }
+            }
+            Expr::RecordLit { fields, spread, .. } => {
+                for field in fields.iter() {
+                    self.walk(field.expr, true);
+                }
+                if let Some(spread) = spread {
+                    self.walk(*spread, false);
+                }
+            }
+            Expr::Tuple { exprs } => {
+                for expr in exprs.iter() {
+                    self.walk(*expr, true);
+                }
+            }
+            Expr::Array(Array::ElementList(elements)) => {
+                for element in elements.iter() {
+                    self.walk(*element, true);
+                }
+            }
+            Expr::Array(Array::Repeat { initializer, repeat }) => {
+                self.walk(*initializer, false);
+                self.walk(*repeat, false);
+            }
+            Expr::Box { expr } | Expr::Await { expr } | Expr::Try { expr } => {
+                self.walk(*expr, true)
+            }
+            Expr::BinaryOp { lhs, rhs, op } => match op {
+                Some(BinaryOp::Assignment { op: None }) => {
+                    self.walk(*rhs, true);
+                    match body[*lhs] {
+                        Expr::Path(_) => {
+                            if let Some(binding) = self.local_binding(*lhs) {
+                                self.moved.remove(&binding);
+                                self.reinitialized.push(binding);
+                            }
+                        }
+                        _ => self.walk(*lhs, false),
+                    }
+                }
+                Some(BinaryOp::LogicOp(_)) => {
+                    self.walk(*lhs, false);
+                    self.walk_conditionally(*rhs);
+                }
+                _ => {
+                    self.walk(*lhs, false);
+                    self.walk(*rhs, false);
+                }
+            },
+            Expr::Unsafe { body } => self.walk(*body, consumed),
+            Expr::MacroStmts { tail } => self.walk(*tail, consumed),
+            Expr::Lambda { body, .. } | Expr::Async { body } | Expr::TryBlock { body } => {
+                self.walk_conditionally(*body)
+            }
+            // The body of an inline `const` can't refer to locals.
+            Expr::Const { .. } => (),
+            Expr::Missing
+            | Expr::Underscore
+            | Expr::Continue { .. }
+            | Expr::Field { .. }
+            | Expr::Cast { .. }
+            | Expr::Ref { .. }
+            | Expr::UnaryOp { .. }
+            | Expr::Range { .. }
+            | Expr::Index { .. }
+            | Expr::Literal(_) => {
+                body[expr].walk_child_exprs(|child| self.walk(child, false));
+            }
+        }
+    }
+
+    /// Walks code which may not run, or may run several times. Uses inside are
+    /// still checked, but moves are forgotten afterwards, and bindings assigned
+    /// to inside count as initialized again.
+    fn walk_conditionally(&mut self, expr: ExprId) {
+        let moved = self.moved.clone();
+        let reinitialized = self.reinitialized.len();
+        self.walk(expr, false);
+        self.moved = moved;
+        for binding in self.reinitialized[reinitialized..].iter() {
+            self.moved.remove(binding);
+        }
+    }
+
+    fn local_binding(&self, expr: ExprId) -> Option<PatId> {
+        let path = match &self.body[expr] {
+            Expr::Path(it) => it,
+            _ => return None,
+        };
+        let resolver = resolver_for_expr(self.db.upcast(), self.def, expr);
+        match resolver.resolve_path_in_value_ns_fully(self.db.upcast(), path.mod_path())? {
+            ValueNs::LocalBinding(pat) => Some(pat),
+            _ => None,
+        }
+    }
+
+    /// Whether using `expr` by value moves it, i.e. it's neither auto-borrowed
+    /// nor `Copy`.
+    fn is_move(&self, expr: ExprId) -> bool {
+        let adjustments = self.infer.expr_adjustments.get(&expr).map_or(&[][..], |it| &**it);
+        if adjustments.iter().any(|adj| matches!(adj.kind, Adjust::Borrow(_))) {
+            return false;
+        }
+        let ty = &self.infer[expr];
+        match ty.kind(Interner) {
+            // `&mut` references are implicitly reborrowed in most places, and
+            // we don't know the `Copy`-ness of types we failed to infer.
+            TyKind::Ref(..) | TyKind::Closure(..) | TyKind::Error => false,
+            _ => !self.is_copy(ty),
+        }
+    }
+
+    fn is_copy(&self, ty: &Ty) -> bool {
+        let canonical =
+            Canonical { value: ty.clone(), binders: CanonicalVarKinds::empty(Interner) };
+        implements_trait(&canonical, self.db, self.env.clone(), self.copy_trait)
+    }
+
+    /// Whether `pat` takes its scrutinee by value as a whole, like `let x = ..`.
+    fn binds_by_value(&self, pat: PatId) -> bool {
+        matches!(
+            self.body[pat],
+            Pat::Bind {
+                mode: BindingAnnotation::Unannotated | BindingAnnotation::Mutable,
+                subpat: None,
+                ..
+            }
+        )
+    }
+}
@@ -5,7 +5,7 @@ use hir_def::{
     body::Body,
     expr::{Expr, ExprId, UnaryOp},
     resolver::{resolver_for_expr, ResolveValueResult, ValueNs},
-    DefWithBodyId,
+    DefWithBodyId, FunctionId,
 };
 
 use crate::{db::HirDatabase, InferenceResult, Interner, TyExt, TyKind};
@@ -16,7 +16,9 @@ pub fn missing_unsafe(db: &dyn HirDatabase, def: DefWithBodyId) -> Vec<ExprId> {
 
     let is_unsafe = match def {
         DefWithBodyId::FunctionId(it) => db.function_data(it).is_unsafe(),
-        DefWithBodyId::StaticId(_) | DefWithBodyId::ConstId(_) => false,
+        DefWithBodyId::StaticId(_) | DefWithBodyId::ConstId(_) | DefWithBodyId::VariantId(_) => {
+            false
+        }
     };
     if is_unsafe {
         return res;
@@ -24,7 +26,7 @@ pub fn missing_unsafe(db: &dyn HirDatabase, def: DefWithBodyId) -> Vec<ExprId> {
 
     let body = db.body(def);
     unsafe_expressions(db, &infer, def, &body, body.body_expr, &mut |expr| {
-        if !expr.inside_unsafe_block {
+        if expr.unsafe_block.is_none() {
             res.push(expr.expr);
         }
     });
@@ -34,7 +36,22 @@ pub fn missing_unsafe(db: &dyn HirDatabase, def: DefWithBodyId) -> Vec<ExprId> {
 
 pub struct UnsafeExpr {
     pub expr: ExprId,
-    pub inside_unsafe_block: bool,
+    pub kind: UnsafeExprKind,
+    /// The innermost `unsafe` block containing the expression, if any.
+    pub unsafe_block: Option<ExprId>,
+}
+
+/// Why an expression needs `unsafe`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UnsafeExprKind {
+    /// A call of an `unsafe fn` or method defined in Rust.
+    UnsafeFnCall,
+    /// A call of a function declared in an `extern` block.
+    ExternFnCall,
+    /// A read or write of a `static mut`.
+    MutableStatic,
+    /// A dereference of a raw pointer.
+    RawPtrDeref,
 }
 
 // FIXME: Move this out, its not a diagnostic only thing anymore, and handle unsafe pattern accesses as well
@@ -46,7 +63,7 @@ pub fn unsafe_expressions(
     current: ExprId,
     unsafe_expr_cb: &mut dyn FnMut(UnsafeExpr),
 ) {
-    walk_unsafe(db, infer, def, body, current, false, unsafe_expr_cb)
+    walk_unsafe(db, infer, def, body, current, None, unsafe_expr_cb)
 }
 
 fn walk_unsafe(
@@ -55,16 +72,15 @@ fn walk_unsafe(
     def: DefWithBodyId,
     body: &Body,
     current: ExprId,
-    inside_unsafe_block: bool,
+    unsafe_block: Option<ExprId>,
     unsafe_expr_cb: &mut dyn FnMut(UnsafeExpr),
 ) {
     let expr = &body.exprs[current];
     match expr {
         &Expr::Call { callee, .. } => {
-            if let Some(func) = infer[callee].as_fn_def(db) {
-                if db.function_data(func).is_unsafe() {
-                    unsafe_expr_cb(UnsafeExpr { expr: current, inside_unsafe_block });
-                }
+            if let Some(kind) = infer[callee].as_fn_def(db).and_then(|it| unsafe_call_kind(db, it))
+            {
+                unsafe_expr_cb(UnsafeExpr { expr: current, kind, unsafe_block });
             }
         }
         Expr::Path(path) => {
@@ -72,31 +88,44 @@ fn walk_unsafe(
             let value_or_partial = resolver.resolve_path_in_value_ns(db.upcast(), path.mod_path());
             if let Some(ResolveValueResult::ValueNs(ValueNs::StaticId(id))) = value_or_partial {
                 if db.static_data(id).mutable {
-                    unsafe_expr_cb(UnsafeExpr { expr: current, inside_unsafe_block });
+                    let kind = UnsafeExprKind::MutableStatic;
+                    unsafe_expr_cb(UnsafeExpr { expr: current, kind, unsafe_block });
                 }
             }
         }
         Expr::MethodCall { .. } => {
-            if infer
-                .method_resolution(current)
-                .map(|(func, _)| db.function_data(func).is_unsafe())
-                .unwrap_or(false)
+            if let Some(kind) =
+                infer.method_resolution(current).and_then(|(func, _)| unsafe_call_kind(db, func))
             {
-                unsafe_expr_cb(UnsafeExpr { expr: current, inside_unsafe_block });
+                unsafe_expr_cb(UnsafeExpr { expr: current, kind, unsafe_block });
             }
         }
         Expr::UnaryOp { expr, op: UnaryOp::Deref } => {
             if let TyKind::Raw(..) = &infer[*expr].kind(Interner) {
-                unsafe_expr_cb(UnsafeExpr { expr: current, inside_unsafe_block });
+                let kind = UnsafeExprKind::RawPtrDeref;
+                unsafe_expr_cb(UnsafeExpr { expr: current, kind, unsafe_block });
             }
         }
         Expr::Unsafe { body: child } => {
-            return walk_unsafe(db, infer, def, body, *child, true, unsafe_expr_cb);
+            return walk_unsafe(db, infer, def, body, *child, Some(current), unsafe_expr_cb);
         }
         _ => {}
     }
 
     expr.walk_child_exprs(|child| {
-        walk_unsafe(db, infer, def, body, child, inside_unsafe_block, unsafe_expr_cb);
+        walk_unsafe(db, infer, def, body, child, unsafe_block, unsafe_expr_cb);
     });
 }
+
+fn unsafe_call_kind(db: &dyn HirDatabase, func: FunctionId) -> Option<UnsafeExprKind> {
+    let data = db.function_data(func);
+    if !data.is_unsafe() {
+        return None;
+    }
+    let kind = if data.is_in_extern_block() {
+        UnsafeExprKind::ExternFnCall
+    } else {
+        UnsafeExprKind::UnsafeFnCall
+    };
+    Some(kind)
+}
@@ -404,10 +404,21 @@ impl HirDisplay for Ty {
                     | TyKind::OpaqueType(opaque_ty_id, parameters) => {
                         let impl_trait_id =
                             f.db.lookup_intern_impl_trait_id((*opaque_ty_id).into());
-                        if let ImplTraitId::ReturnTypeImplTrait(func, idx) = impl_trait_id {
-                            let datas =
-                                f.db.return_type_impl_traits(func)
-                                    .expect("impl trait id without data");
+                        let impl_traits = match impl_trait_id {
+                            ImplTraitId::ReturnTypeImplTrait(func, idx) => Some((
+                                f.db.return_type_impl_traits(func),
+                                idx,
+                                func.lookup(f.db.upcast()).module(f.db.upcast()).krate(),
+                            )),
+                            ImplTraitId::TypeAliasImplTrait(alias, idx) => Some((
+                                f.db.type_alias_impl_traits(alias),
+                                idx,
+                                alias.module(f.db.upcast()).krate(),
+                            )),
+                            ImplTraitId::AsyncBlockTypeImplTrait(..) => None,
+                        };
+                        if let Some((datas, idx, krate)) = impl_traits {
+                            let datas = datas.expect("impl trait id without data");
                             let data = (*datas)
                                 .as_ref()
                                 .map(|rpit| rpit.impl_traits[idx as usize].bounds.clone());
@@ -416,9 +427,7 @@ impl HirDisplay for Ty {
 
                             // Don't count Sized but count when it absent
                             // (i.e. when explicit ?Sized bound is set).
-                            let default_sized = SizedByDefault::Sized {
-                                anchor: func.lookup(f.db.upcast()).module(f.db.upcast()).krate(),
-                            };
+                            let default_sized = SizedByDefault::Sized { anchor: krate };
                             let sized_bounds = bounds
                                 .skip_binders()
                                 .iter()
@@ -659,6 +668,21 @@ impl HirDisplay for Ty {
                         )?;
                         // FIXME: it would maybe be good to distinguish this from the alias type (when debug printing), and to show the substitution
                     }
+                    ImplTraitId::TypeAliasImplTrait(alias, idx) => {
+                        let datas =
+                            f.db.type_alias_impl_traits(alias).expect("impl trait id without data");
+                        let data = (*datas)
+                            .as_ref()
+                            .map(|rpit| rpit.impl_traits[idx as usize].bounds.clone());
+                        let bounds = data.substitute(Interner, &parameters);
+                        let krate = alias.module(f.db.upcast()).krate();
+                        write_bounds_like_dyn_trait_with_prefix(
+                            "impl",
+                            bounds.skip_binders(),
+                            SizedByDefault::Sized { anchor: krate },
+                            f,
+                        )?;
+                    }
                     ImplTraitId::AsyncBlockTypeImplTrait(..) => {
                         write!(f, "impl Future<Output = ")?;
                         parameters.at(Interner, 0).hir_fmt(f)?;
@@ -758,6 +782,21 @@ impl HirDisplay for Ty {
                             f,
                         )?;
                     }
+                    ImplTraitId::TypeAliasImplTrait(alias, idx) => {
+                        let datas =
+                            f.db.type_alias_impl_traits(alias).expect("impl trait id without data");
+                        let data = (*datas)
+                            .as_ref()
+                            .map(|rpit| rpit.impl_traits[idx as usize].bounds.clone());
+                        let bounds = data.substitute(Interner, &opaque_ty.substitution);
+                        let krate = alias.module(f.db.upcast()).krate();
+                        write_bounds_like_dyn_trait_with_prefix(
+                            "impl",
+                            bounds.skip_binders(),
+                            SizedByDefault::Sized { anchor: krate },
+                            f,
+                        )?;
+                    }
                     ImplTraitId::AsyncBlockTypeImplTrait(..) => {
                         write!(f, "{{async block}}")?;
                     }
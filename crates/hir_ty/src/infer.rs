@@ -13,14 +13,19 @@
 //! to certain types. To record this, we use the union-find implementation from
 //! the `ena` crate, which is extracted from rustc.
 
-use std::ops::Index;
-use std::sync::Arc;
+use std::{
+    fmt,
+    ops::{Deref, Index},
+    sync::Arc,
+};
 
 use chalk_ir::{cast::Cast, ConstValue, DebruijnIndex, Mutability, Safety, Scalar, TypeFlags};
 use hir_def::{
     body::Body,
+    builtin_type::BuiltinType,
     data::{ConstData, FunctionData, StaticData},
     expr::{BindingAnnotation, ExprId, PatId},
+    intern::{Internable, Interned},
     lang_item::LangItemTarget,
     path::{path, Path},
     resolver::{HasResolver, ResolveValueResult, Resolver, TypeNs, ValueNs},
@@ -30,14 +35,16 @@ use hir_def::{
 };
 use hir_expand::name::{name, Name};
 use itertools::Either;
-use la_arena::ArenaMap;
+use la_arena::{ArenaMap, Idx};
 use rustc_hash::FxHashMap;
 use stdx::impl_from;
 
 use crate::{
-    db::HirDatabase, fold_tys_and_consts, infer::coerce::CoerceMany, lower::ImplTraitLoweringMode,
-    to_assoc_type_id, AliasEq, AliasTy, Const, DomainGoal, GenericArg, Goal, InEnvironment,
-    Interner, ProjectionTy, Substitution, TraitEnvironment, TraitRef, Ty, TyBuilder, TyExt, TyKind,
+    db::HirDatabase, fold_tys, fold_tys_and_consts, infer::coerce::CoerceMany, inference_profiling,
+    interner::InternedWrapper, lower::ImplTraitLoweringMode, to_assoc_type_id,
+    traits::SolverOverflow, AliasEq, AliasTy, Canonical, Const, DomainGoal, GenericArg, Goal,
+    ImplTraitId, InEnvironment, Interner, ProjectionTy, Substitution, TraitEnvironment, TraitRef,
+    Ty, TyBuilder, TyExt, TyKind,
 };
 
 // This lint has a false positive here. See the link below for details.
@@ -46,7 +53,7 @@ use crate::{
 #[allow(unreachable_pub)]
 pub use coerce::could_coerce;
 #[allow(unreachable_pub)]
-pub use unify::could_unify;
+pub use unify::{could_unify, unify};
 
 pub(crate) mod unify;
 mod path;
@@ -58,6 +65,18 @@ mod closure;
 /// The entry point of type inference.
 pub(crate) fn infer_query(db: &dyn HirDatabase, def: DefWithBodyId) -> Arc<InferenceResult> {
     let _p = profile::span("infer_query");
+    inference_profiling::record_inference(
+        def,
+        || infer_body(db, def),
+        |res| {
+            res.diagnostics
+                .iter()
+                .any(|it| matches!(it, InferenceDiagnostic::TraitSolverOverflow { .. }))
+        },
+    )
+}
+
+fn infer_body(db: &dyn HirDatabase, def: DefWithBodyId) -> Arc<InferenceResult> {
     let resolver = def.resolver(db.upcast());
     let mut ctx = InferenceContext::new(db, def, resolver);
 
@@ -65,6 +84,12 @@ pub(crate) fn infer_query(db: &dyn HirDatabase, def: DefWithBodyId) -> Arc<Infer
         DefWithBodyId::ConstId(c) => ctx.collect_const(&db.const_data(c)),
         DefWithBodyId::FunctionId(f) => ctx.collect_fn(&db.function_data(f)),
         DefWithBodyId::StaticId(s) => ctx.collect_static(&db.static_data(s)),
+        DefWithBodyId::VariantId(v) => {
+            ctx.return_ty = TyBuilder::builtin(match db.enum_data(v.parent).variant_body_type() {
+                Either::Left(builtin) => BuiltinType::Int(builtin),
+                Either::Right(builtin) => BuiltinType::Uint(builtin),
+            });
+        }
     }
 
     ctx.infer_body();
@@ -144,6 +169,16 @@ pub enum InferenceDiagnostic {
     NoSuchField { expr: ExprId },
     BreakOutsideOfLoop { expr: ExprId },
     MismatchedArgCount { call_expr: ExprId, expected: usize, found: usize },
+    TraitSolverOverflow { expr: ExprId, overflow: Overflow },
+}
+
+/// Something inference gave up on, leaving types unknown.
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub enum Overflow {
+    /// The trait solver gave up on this goal.
+    Goal(Canonical<InEnvironment<Goal>>, SolverOverflow),
+    /// Autoderef reached its recursion limit, stopping at this type.
+    Autoderef(Canonical<Ty>),
 }
 
 /// A mismatch between an expected and an inferred type.
@@ -285,19 +320,93 @@ pub struct InferenceResult {
     /// For each associated item record what it resolves to
     assoc_resolutions: FxHashMap<ExprOrPatId, AssocItemId>,
     pub diagnostics: Vec<InferenceDiagnostic>,
-    pub type_of_expr: ArenaMap<ExprId, Ty>,
+    pub type_of_expr: TypeTable<ExprId>,
     /// For each pattern record the type it resolves to.
     ///
     /// **Note**: When a pattern type is resolved it may still contain
     /// unresolved or missing subpatterns or subpatterns of mismatched types.
-    pub type_of_pat: ArenaMap<PatId, Ty>,
+    pub type_of_pat: TypeTable<PatId>,
     type_mismatches: FxHashMap<ExprOrPatId, TypeMismatch>,
     /// Interned Unknown to return references to.
     standard_types: InternedStandardTypes,
     /// Stores the types which were implicitly dereferenced in pattern binding modes.
-    pub pat_adjustments: FxHashMap<PatId, Vec<Adjustment>>,
+    pub pat_adjustments: FxHashMap<PatId, Adjustments>,
     pub pat_binding_modes: FxHashMap<PatId, BindingMode>,
-    pub expr_adjustments: FxHashMap<ExprId, Vec<Adjustment>>,
+    pub expr_adjustments: FxHashMap<ExprId, Adjustments>,
+}
+
+/// The type of each expression, or pattern, of a body.
+///
+/// Interned, as lots of bodies have the same ones: constants, enum discriminants and
+/// macro-generated functions. The types themselves are interned already.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct TypeTable<Id>(Interned<InternedWrapper<ArenaMap<Id, Ty>>>)
+where
+    InternedWrapper<ArenaMap<Id, Ty>>: Internable;
+
+impl<T> TypeTable<Idx<T>>
+where
+    InternedWrapper<ArenaMap<Idx<T>, Ty>>: Internable,
+{
+    fn new(mut types: ArenaMap<Idx<T>, Ty>) -> Self {
+        types.shrink_to_fit();
+        TypeTable(Interned::new(InternedWrapper(types)))
+    }
+}
+
+impl<T> Default for TypeTable<Idx<T>>
+where
+    InternedWrapper<ArenaMap<Idx<T>, Ty>>: Internable,
+{
+    fn default() -> Self {
+        TypeTable::new(ArenaMap::default())
+    }
+}
+
+impl<Id> Deref for TypeTable<Id>
+where
+    InternedWrapper<ArenaMap<Id, Ty>>: Internable,
+{
+    type Target = ArenaMap<Id, Ty>;
+
+    fn deref(&self) -> &ArenaMap<Id, Ty> {
+        &self.0
+    }
+}
+
+impl<Id: fmt::Debug> fmt::Debug for TypeTable<Id>
+where
+    InternedWrapper<ArenaMap<Id, Ty>>: Internable,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+/// The adjustments of an expression or pattern.
+///
+/// Interned, as the same few chains of autoderefs and autorefs come up over and over.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Adjustments(Interned<InternedWrapper<Box<[Adjustment]>>>);
+
+impl Adjustments {
+    fn new(adjustments: Vec<Adjustment>) -> Self {
+        Adjustments(Interned::new(InternedWrapper(adjustments.into_boxed_slice())))
+    }
+}
+
+impl Deref for Adjustments {
+    type Target = [Adjustment];
+
+    fn deref(&self) -> &[Adjustment] {
+        &self.0
+    }
+}
+
+impl fmt::Debug for Adjustments {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
 }
 
 impl InferenceResult {
@@ -365,6 +474,14 @@ pub(crate) struct InferenceContext<'a> {
     table: unify::InferenceTable<'a>,
     trait_env: Arc<TraitEnvironment>,
     pub(crate) result: InferenceResult,
+    /// The tables of `result` as they're being inferred, they're resolved and interned at the end.
+    type_of_expr: ArenaMap<ExprId, Ty>,
+    type_of_pat: ArenaMap<PatId, Ty>,
+    expr_adjustments: FxHashMap<ExprId, Vec<Adjustment>>,
+    pat_adjustments: FxHashMap<PatId, Vec<Adjustment>>,
+    /// The inference variables standing for the hidden types of `type Foo = impl Trait;` aliases
+    /// defined in the module of the body, which is where they are inferred.
+    tait_vars: FxHashMap<Ty, Ty>,
     /// The return type of the function being inferred, the closure or async block if we're
     /// currently within one.
     ///
@@ -372,6 +489,8 @@ pub(crate) struct InferenceContext<'a> {
     /// closures, but currently this is the only field that will change there,
     /// so it doesn't make sense.
     return_ty: Ty,
+    /// The resume and yield types of the generator we're currently within, if any.
+    resume_yield_tys: Option<(Ty, Ty)>,
     diverges: Diverges,
     breakables: Vec<BreakableContext>,
 }
@@ -401,9 +520,15 @@ impl<'a> InferenceContext<'a> {
             .map_or_else(|| Arc::new(TraitEnvironment::empty(krate)), |d| db.trait_environment(d));
         InferenceContext {
             result: InferenceResult::default(),
+            type_of_expr: ArenaMap::default(),
+            type_of_pat: ArenaMap::default(),
+            expr_adjustments: FxHashMap::default(),
+            pat_adjustments: FxHashMap::default(),
+            tait_vars: FxHashMap::default(),
             table: unify::InferenceTable::new(db, trait_env.clone()),
             trait_env,
             return_ty: TyKind::Error.intern(Interner), // set in collect_fn_signature
+            resume_yield_tys: None,
             db,
             owner,
             body: db.body(owner),
@@ -421,15 +546,24 @@ impl<'a> InferenceContext<'a> {
         // FIXME resolve obligations as well (use Guidance if necessary)
         self.table.resolve_obligations_as_possible();
 
+        // Type aliases whose hidden type the body doesn't constrain stay opaque.
+        for (opaque, var) in std::mem::take(&mut self.tait_vars) {
+            if self.table.resolve_ty_shallow(&var).inference_var(Interner).is_some() {
+                self.table.unify(&var, &opaque);
+            }
+        }
+
         // make sure diverging type variables are marked as such
         self.table.propagate_diverging_flag();
         let mut result = std::mem::take(&mut self.result);
-        for ty in result.type_of_expr.values_mut() {
+        for ty in self.type_of_expr.values_mut() {
             *ty = self.table.resolve_completely(ty.clone());
         }
-        for ty in result.type_of_pat.values_mut() {
+        result.type_of_expr = TypeTable::new(std::mem::take(&mut self.type_of_expr));
+        for ty in self.type_of_pat.values_mut() {
             *ty = self.table.resolve_completely(ty.clone());
         }
+        result.type_of_pat = TypeTable::new(std::mem::take(&mut self.type_of_pat));
         for mismatch in result.type_mismatches.values_mut() {
             mismatch.expected = self.table.resolve_completely(mismatch.expected.clone());
             mismatch.actual = self.table.resolve_completely(mismatch.actual.clone());
@@ -437,21 +571,39 @@ impl<'a> InferenceContext<'a> {
         for (_, subst) in result.method_resolutions.values_mut() {
             *subst = self.table.resolve_completely(subst.clone());
         }
-        for adjustment in result.expr_adjustments.values_mut().flatten() {
+        for adjustment in self.expr_adjustments.values_mut().flatten() {
             adjustment.target = self.table.resolve_completely(adjustment.target.clone());
         }
-        for adjustment in result.pat_adjustments.values_mut().flatten() {
+        result.expr_adjustments = std::mem::take(&mut self.expr_adjustments)
+            .into_iter()
+            .map(|(expr, adjustments)| (expr, Adjustments::new(adjustments)))
+            .collect();
+        for adjustment in self.pat_adjustments.values_mut().flatten() {
             adjustment.target = self.table.resolve_completely(adjustment.target.clone());
         }
+        result.pat_adjustments = std::mem::take(&mut self.pat_adjustments)
+            .into_iter()
+            .map(|(pat, adjustments)| (pat, Adjustments::new(adjustments)))
+            .collect();
+
+        // Results stay in memory for as long as the body doesn't change, don't keep the slack
+        // the tables grew with.
+        result.method_resolutions.shrink_to_fit();
+        result.field_resolutions.shrink_to_fit();
+        result.variant_resolutions.shrink_to_fit();
+        result.assoc_resolutions.shrink_to_fit();
+        result.diagnostics.shrink_to_fit();
+        result.type_mismatches.shrink_to_fit();
+        result.pat_binding_modes.shrink_to_fit();
         result
     }
 
     fn write_expr_ty(&mut self, expr: ExprId, ty: Ty) {
-        self.result.type_of_expr.insert(expr, ty);
+        self.type_of_expr.insert(expr, ty);
     }
 
     fn write_expr_adj(&mut self, expr: ExprId, adjustments: Vec<Adjustment>) {
-        self.result.expr_adjustments.insert(expr, adjustments);
+        self.expr_adjustments.insert(expr, adjustments);
     }
 
     fn write_method_resolution(&mut self, expr: ExprId, func: FunctionId, subst: Substitution) {
@@ -467,7 +619,7 @@ impl<'a> InferenceContext<'a> {
     }
 
     fn write_pat_ty(&mut self, pat: PatId, ty: Ty) {
-        self.result.type_of_pat.insert(pat, ty);
+        self.type_of_pat.insert(pat, ty);
     }
 
     fn push_diagnostic(&mut self, diagnostic: InferenceDiagnostic) {
@@ -484,6 +636,7 @@ impl<'a> InferenceContext<'a> {
             .with_impl_trait_mode(impl_trait_mode);
         let ty = ctx.lower_ty(type_ref);
         let ty = self.insert_type_vars(ty);
+        let ty = self.insert_tait_vars(ty);
         self.normalize_associated_types_in(ty)
     }
 
@@ -532,6 +685,36 @@ impl<'a> InferenceContext<'a> {
         )
     }
 
+    /// Replaces the `impl Trait` types of type aliases defined in the module of the body by
+    /// inference variables, so that their hidden type is inferred from the body.
+    fn insert_tait_vars(&mut self, ty: Ty) -> Ty {
+        let module = self.owner.module(self.db.upcast());
+        fold_tys(
+            ty,
+            |ty, _| {
+                let alias = match ty.kind(Interner) {
+                    TyKind::OpaqueType(id, _) => {
+                        match self.db.lookup_intern_impl_trait_id((*id).into()) {
+                            ImplTraitId::TypeAliasImplTrait(alias, _) => alias,
+                            _ => return ty,
+                        }
+                    }
+                    _ => return ty,
+                };
+                if alias.module(self.db.upcast()) != module {
+                    return ty;
+                }
+                if let Some(var) = self.tait_vars.get(&ty) {
+                    return var.clone();
+                }
+                let var = self.table.new_type_var();
+                self.tait_vars.insert(ty, var.clone());
+                var
+            },
+            DebruijnIndex::INNERMOST,
+        )
+    }
+
     fn resolve_obligations_as_possible(&mut self) {
         self.table.resolve_obligations_as_possible();
     }
@@ -742,6 +925,8 @@ impl<'a> InferenceContext<'a> {
         let param_tys =
             data.params.iter().map(|(_, type_ref)| ctx.lower_ty(type_ref)).collect::<Vec<_>>();
         for (ty, pat) in param_tys.into_iter().zip(body.params.iter()) {
+            // Parameters are values of the opaque types of type aliases handed in by callers, so
+            // they don't constrain the hidden types, unlike the return type.
             let ty = self.insert_type_vars(ty);
             let ty = self.normalize_associated_types_in(ty);
 
@@ -11,7 +11,10 @@ use chalk_ir::{
     cast::Cast, fold::Shift, DebruijnIndex, GenericArgData, Mutability, TyVariableKind,
 };
 use hir_def::{
-    expr::{ArithOp, Array, BinaryOp, CmpOp, Expr, ExprId, Literal, Ordering, Statement, UnaryOp},
+    expr::{
+        ArithOp, Array, BinaryOp, ClosureKind, CmpOp, Expr, ExprId, Literal, Ordering, Statement,
+        UnaryOp,
+    },
     generics::TypeOrConstParamData,
     path::{GenericArg, GenericArgs},
     resolver::resolver_for_expr,
@@ -79,10 +82,16 @@ impl<'a> InferenceContext<'a> {
 
     fn infer_expr_inner(&mut self, tgt_expr: ExprId, expected: &Expectation) -> Ty {
         self.db.unwind_if_cancelled();
+        let parent_overflow = self.table.take_overflow();
 
         let body = Arc::clone(&self.body); // avoid borrow checker problem
         let ty = match &body[tgt_expr] {
             Expr::Missing => self.err_ty(),
+            // Outside of a destructuring assignment this is a typed hole, give it the type the
+            // context expects so that the IDE can report it.
+            Expr::Underscore => {
+                expected.only_has_type(&mut self.table).unwrap_or_else(|| self.err_ty())
+            }
             &Expr::If { condition, then_branch, else_branch } => {
                 self.infer_expr(
                     condition,
@@ -220,7 +229,7 @@ impl<'a> InferenceContext<'a> {
                 self.diverges = Diverges::Maybe;
                 TyBuilder::unit()
             }
-            Expr::Lambda { body, args, ret_type, arg_types } => {
+            Expr::Lambda { body, args, ret_type, arg_types, closure_kind } => {
                 assert_eq!(args.len(), arg_types.len());
 
                 let mut sig_tys = Vec::new();
@@ -240,28 +249,52 @@ impl<'a> InferenceContext<'a> {
                     None => self.table.new_type_var(),
                 };
                 sig_tys.push(ret_ty.clone());
-                let sig_ty = TyKind::Function(FnPointer {
-                    num_binders: 0,
-                    sig: FnSig { abi: (), safety: chalk_ir::Safety::Safe, variadic: false },
-                    substitution: FnSubst(
-                        Substitution::from_iter(Interner, sig_tys.clone()).shifted_in(Interner),
-                    ),
-                })
-                .intern(Interner);
-                let closure_id = self.db.intern_closure((self.owner, tgt_expr)).into();
-                let closure_ty =
-                    TyKind::Closure(closure_id, Substitution::from1(Interner, sig_ty.clone()))
+
+                let (closure_ty, resume_yield_tys) = match closure_kind {
+                    ClosureKind::Generator(_) => {
+                        // A generator has at most one parameter, the type it's resumed with.
+                        let resume_ty = match args.len() {
+                            0 => TyBuilder::unit(),
+                            _ => sig_tys[0].clone(),
+                        };
+                        let yield_ty = self.table.new_type_var();
+                        let generator_id = self.db.intern_generator((self.owner, tgt_expr)).into();
+                        let subst = Substitution::from_iter(
+                            Interner,
+                            [resume_ty.clone(), yield_ty.clone(), ret_ty.clone()],
+                        );
+                        let generator_ty = TyKind::Generator(generator_id, subst).intern(Interner);
+                        (generator_ty, Some((resume_ty, yield_ty)))
+                    }
+                    ClosureKind::Closure => {
+                        let sig_ty = TyKind::Function(FnPointer {
+                            num_binders: 0,
+                            sig: FnSig { abi: (), safety: chalk_ir::Safety::Safe, variadic: false },
+                            substitution: FnSubst(
+                                Substitution::from_iter(Interner, sig_tys.clone())
+                                    .shifted_in(Interner),
+                            ),
+                        })
+                        .intern(Interner);
+                        let closure_id = self.db.intern_closure((self.owner, tgt_expr)).into();
+                        let closure_ty = TyKind::Closure(
+                            closure_id,
+                            Substitution::from1(Interner, sig_ty.clone()),
+                        )
                         .intern(Interner);
 
-                // Eagerly try to relate the closure type with the expected
-                // type, otherwise we often won't have enough information to
-                // infer the body.
-                self.deduce_closure_type_from_expectations(
-                    tgt_expr,
-                    &closure_ty,
-                    &sig_ty,
-                    expected,
-                );
+                        // Eagerly try to relate the closure type with the expected
+                        // type, otherwise we often won't have enough information to
+                        // infer the body.
+                        self.deduce_closure_type_from_expectations(
+                            tgt_expr,
+                            &closure_ty,
+                            &sig_ty,
+                            expected,
+                        );
+                        (closure_ty, None)
+                    }
+                };
 
                 // Now go through the argument patterns
                 for (arg_pat, arg_ty) in args.iter().zip(sig_tys) {
@@ -270,11 +303,14 @@ impl<'a> InferenceContext<'a> {
 
                 let prev_diverges = mem::replace(&mut self.diverges, Diverges::Maybe);
                 let prev_ret_ty = mem::replace(&mut self.return_ty, ret_ty.clone());
+                let prev_resume_yield_tys =
+                    mem::replace(&mut self.resume_yield_tys, resume_yield_tys);
 
                 self.infer_expr_coerce(*body, &Expectation::has_type(ret_ty));
 
                 self.diverges = prev_diverges;
                 self.return_ty = prev_ret_ty;
+                self.resume_yield_tys = prev_resume_yield_tys;
 
                 closure_ty
             }
@@ -417,11 +453,24 @@ impl<'a> InferenceContext<'a> {
                 TyKind::Never.intern(Interner)
             }
             Expr::Yield { expr } => {
-                // FIXME: track yield type for coercion
-                if let Some(expr) = expr {
-                    self.infer_expr(*expr, &Expectation::none());
+                match self.resume_yield_tys.clone() {
+                    Some((resume_ty, yield_ty)) => {
+                        if let Some(expr) = expr {
+                            self.infer_expr_coerce(*expr, &Expectation::has_type(yield_ty));
+                        } else {
+                            let unit = TyBuilder::unit();
+                            let _ = self.coerce(Some(tgt_expr), &unit, &yield_ty);
+                        }
+                        resume_ty
+                    }
+                    None => {
+                        // `yield` outside of a generator
+                        if let Some(expr) = expr {
+                            self.infer_expr(*expr, &Expectation::none());
+                        }
+                        self.err_ty()
+                    }
                 }
-                TyKind::Never.intern(Interner)
             }
             Expr::RecordLit { path, fields, spread } => {
                 let (ty, def_id) = self.resolve_variant(path.as_deref(), false);
@@ -796,6 +845,14 @@ impl<'a> InferenceContext<'a> {
         // use a new type variable if we got unknown here
         let ty = self.insert_type_vars_shallow(ty);
         self.write_expr_ty(tgt_expr, ty.clone());
+        if let Some(overflow) = self.table.take_overflow() {
+            self.push_diagnostic(InferenceDiagnostic::TraitSolverOverflow {
+                expr: tgt_expr,
+                overflow,
+            });
+        }
+        // an overflow that happened before we got here belongs to the enclosing expression
+        self.table.set_overflow(parent_overflow);
         if self.resolve_ty_shallow(&ty).is_never() {
             // Any expression that produces a value of type `!` must have diverged
             self.diverges = Diverges::Always;
@@ -119,8 +119,7 @@ impl<'a> InferenceContext<'a> {
             }
 
             if !pat_adjustments.is_empty() {
-                pat_adjustments.shrink_to_fit();
-                self.result.pat_adjustments.insert(pat, pat_adjustments);
+                self.pat_adjustments.insert(pat, pat_adjustments);
             }
         } else if let Pat::Ref { .. } = &body[pat] {
             cov_mark::hit!(match_ergonomics_ref);
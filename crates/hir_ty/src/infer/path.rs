@@ -62,7 +62,7 @@ impl<'a> InferenceContext<'a> {
 
         let typable: ValueTyDefId = match value {
             ValueNs::LocalBinding(pat) => {
-                let ty = self.result.type_of_pat.get(pat)?.clone();
+                let ty = self.type_of_pat.get(pat)?.clone();
                 return Some(ty);
             }
             ValueNs::FunctionId(it) => it.into(),
@@ -9,9 +9,10 @@ use chalk_ir::{
 use chalk_solve::infer::ParameterEnaVariableExt;
 use ena::unify::UnifyKey;
 use hir_expand::name;
+use rustc_hash::FxHashSet;
 use stdx::never;
 
-use super::{InferOk, InferResult, InferenceContext, TypeError};
+use super::{InferOk, InferResult, InferenceContext, Overflow, TypeError};
 use crate::{
     db::HirDatabase, fold_tys, static_lifetime, traits::FnTrait, AliasEq, AliasTy, BoundVar,
     Canonical, Const, DebruijnIndex, GenericArg, GenericArgData, Goal, Guidance, InEnvironment,
@@ -80,7 +81,8 @@ pub fn could_unify(
     unify(db, env, tys).is_some()
 }
 
-pub(crate) fn unify(
+/// Unifies the two types, returning the values of their shared canonical variables.
+pub fn unify(
     db: &dyn HirDatabase,
     env: Arc<TraitEnvironment>,
     tys: &Canonical<(Ty, Ty)>,
@@ -142,6 +144,10 @@ pub(crate) struct InferenceTable<'a> {
     var_unification_table: ChalkInferenceTable,
     type_variable_table: Vec<TypeVariableData>,
     pending_obligations: Vec<Canonicalized<InEnvironment<Goal>>>,
+    /// The first thing inference gave up on since this was last checked.
+    overflow: Option<Overflow>,
+    /// Everything inference gave up on, so that each one is only reported once.
+    reported_overflows: FxHashSet<Overflow>,
 }
 
 pub(crate) struct InferenceTableSnapshot {
@@ -158,6 +164,8 @@ impl<'a> InferenceTable<'a> {
             var_unification_table: ChalkInferenceTable::new(),
             type_variable_table: Vec::new(),
             pending_obligations: Vec::new(),
+            overflow: None,
+            reported_overflows: FxHashSet::default(),
         }
     }
 
@@ -404,7 +412,30 @@ impl<'a> InferenceTable<'a> {
     pub(crate) fn try_obligation(&mut self, goal: Goal) -> Option<Solution> {
         let in_env = InEnvironment::new(&self.trait_env.env, goal);
         let canonicalized = self.canonicalize(in_env);
-        let solution = self.db.trait_solve(self.trait_env.krate, canonicalized.value);
+        self.solve(canonicalized.value)
+    }
+
+    /// Returns the first thing inference gave up on since the last call, if any.
+    pub(crate) fn take_overflow(&mut self) -> Option<Overflow> {
+        self.overflow.take()
+    }
+
+    pub(crate) fn set_overflow(&mut self, overflow: Option<Overflow>) {
+        self.overflow = overflow;
+    }
+
+    pub(crate) fn record_overflow(&mut self, overflow: Overflow) {
+        if self.reported_overflows.insert(overflow.clone()) && self.overflow.is_none() {
+            self.overflow = Some(overflow);
+        }
+    }
+
+    fn solve(&mut self, goal: Canonical<InEnvironment<Goal>>) -> Option<Solution> {
+        let krate = self.trait_env.krate;
+        let solution = self.db.trait_solve(krate, goal.clone());
+        if let Some(kind) = self.db.trait_solve_overflow(krate, goal.clone()) {
+            self.record_overflow(Overflow::Goal(goal, kind));
+        }
         solution
     }
 
@@ -539,7 +570,7 @@ impl<'a> InferenceTable<'a> {
         &mut self,
         canonicalized: &Canonicalized<InEnvironment<Goal>>,
     ) -> bool {
-        let solution = self.db.trait_solve(self.trait_env.krate, canonicalized.value.clone());
+        let solution = self.solve(canonicalized.value.clone());
 
         match solution {
             Some(Solution::Unique(canonical_subst)) => {
@@ -0,0 +1,109 @@
+//! Records how long type inference took for each body, to find the functions
+//! making analysis slow.
+//!
+//! This is process-wide and off by default: timings are a side channel of the
+//! queries, not something salsa knows about, so they're simply those of the
+//! last time a body was inferred.
+
+use std::{
+    cell::RefCell,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use hir_def::DefWithBodyId;
+use once_cell::sync::Lazy;
+use rustc_hash::FxHashMap;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct InferenceTiming {
+    /// The whole inference of the body, including what it solved.
+    pub total: Duration,
+    /// The part of it spent in the trait solver.
+    pub trait_solving: Duration,
+    /// Whether the trait solver gave up on some goal, which usually means
+    /// recursive trait bounds.
+    pub overflowed: bool,
+}
+
+impl InferenceTiming {
+    /// A guess at what made the inference slow, to help restructuring the code.
+    pub fn likely_cause(&self) -> Option<&'static str> {
+        if self.overflowed {
+            Some("likely due to recursive trait bounds")
+        } else if self.trait_solving * 2 > self.total {
+            Some("mostly spent solving trait bounds")
+        } else {
+            None
+        }
+    }
+}
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static TIMINGS: Lazy<Mutex<FxHashMap<DefWithBodyId, InferenceTiming>>> =
+    Lazy::new(Default::default);
+
+thread_local! {
+    /// The bodies being inferred on this thread, innermost last, with the
+    /// trait solving time accumulated so far.
+    static IN_PROGRESS: RefCell<Vec<Duration>> = RefCell::new(Vec::new());
+}
+
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+    if !enabled {
+        TIMINGS.lock().unwrap().clear();
+    }
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+pub fn timing(def: DefWithBodyId) -> Option<InferenceTiming> {
+    TIMINGS.lock().unwrap().get(&def).copied()
+}
+
+/// The `n` bodies which took the longest to infer, slowest first.
+pub fn slowest(n: usize) -> Vec<(DefWithBodyId, InferenceTiming)> {
+    let mut res: Vec<_> = TIMINGS.lock().unwrap().iter().map(|(&def, &it)| (def, it)).collect();
+    res.sort_by(|(_, a), (_, b)| b.total.cmp(&a.total));
+    res.truncate(n);
+    res
+}
+
+pub(crate) fn record_inference<T>(
+    def: DefWithBodyId,
+    infer: impl FnOnce() -> T,
+    overflowed: impl FnOnce(&T) -> bool,
+) -> T {
+    if !is_enabled() {
+        return infer();
+    }
+    IN_PROGRESS.with(|it| it.borrow_mut().push(Duration::ZERO));
+    let start = Instant::now();
+    let res = infer();
+    let total = start.elapsed();
+    let trait_solving = IN_PROGRESS.with(|it| it.borrow_mut().pop()).unwrap_or_default();
+    let timing = InferenceTiming { total, trait_solving, overflowed: overflowed(&res) };
+    TIMINGS.lock().unwrap().insert(def, timing);
+    res
+}
+
+pub(crate) fn record_trait_solving<T>(solve: impl FnOnce() -> T) -> T {
+    if !is_enabled() {
+        return solve();
+    }
+    let start = Instant::now();
+    let res = solve();
+    let elapsed = start.elapsed();
+    IN_PROGRESS.with(|it| {
+        if let Some(trait_solving) = it.borrow_mut().last_mut() {
+            *trait_solving += elapsed;
+        }
+    });
+    res
+}
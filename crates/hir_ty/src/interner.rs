@@ -1,14 +1,16 @@
 //! Implementation of the Chalk `Interner` trait, which allows customizing the
 //! representation of the various objects Chalk deals with (types, goals etc.).
 
-use crate::{chalk_db, tls, GenericArg};
+use crate::{chalk_db, tls, Adjustment, GenericArg, Ty};
 use base_db::salsa::InternId;
 use chalk_ir::{Goal, GoalData};
 use hir_def::{
+    expr::{ExprId, PatId},
     intern::{impl_internable, InternStorage, Internable, Interned},
     type_ref::ConstScalar,
     TypeAliasId,
 };
+use la_arena::ArenaMap;
 use smallvec::SmallVec;
 use std::{fmt, sync::Arc};
 
@@ -16,7 +18,7 @@ use std::{fmt, sync::Arc};
 pub struct Interner;
 
 #[derive(PartialEq, Eq, Hash)]
-pub struct InternedWrapper<T>(T);
+pub struct InternedWrapper<T>(pub(crate) T);
 
 impl<T: fmt::Debug> fmt::Debug for InternedWrapper<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -43,6 +45,9 @@ impl_internable!(
     InternedWrapper<Vec<chalk_ir::ProgramClause<Interner>>>,
     InternedWrapper<Vec<chalk_ir::QuantifiedWhereClause<Interner>>>,
     InternedWrapper<Vec<chalk_ir::Variance>>,
+    InternedWrapper<ArenaMap<ExprId, Ty>>,
+    InternedWrapper<ArenaMap<PatId, Ty>>,
+    InternedWrapper<Box<[Adjustment]>>,
 );
 
 impl chalk_ir::interner::Interner for Interner {
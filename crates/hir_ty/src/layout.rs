@@ -0,0 +1,165 @@
+//! Size and alignment of types.
+//!
+//! This does not ask rustc for the target's data layout: only types built from primitives,
+//! pointers, arrays, tuples and structs are supported, and the parts of the data layout they need
+//! are derived from the crate's `target_*` cfgs. Enums and unions are left out, their layout
+//! depends on niche optimizations rustc is free to change.
+
+use base_db::CrateId;
+use chalk_ir::{ConcreteConst, ConstValue, FloatTy, IntTy, UintTy};
+use hir_def::{adt::ReprKind, type_ref::ConstScalar, AdtId};
+
+use crate::{db::HirDatabase, Interner, Scalar, Ty, TyKind};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Layout {
+    pub size: u64,
+    pub align: u64,
+}
+
+impl Layout {
+    /// The layout of a struct with these fields, laid out in order as `#[repr(C)]` does.
+    pub fn sequential(fields: &[Layout]) -> Option<Layout> {
+        let end = match (Layout::sequential_offsets(fields)?.last(), fields.last()) {
+            (Some(offset), Some(field)) => offset.checked_add(field.size)?,
+            _ => 0,
+        };
+        let align = fields.iter().map(|it| it.align).max().unwrap_or(1);
+        Some(Layout { size: round_up(end, align)?, align })
+    }
+
+    /// The offsets of the fields in [`Layout::sequential`].
+    pub fn sequential_offsets(fields: &[Layout]) -> Option<Vec<u64>> {
+        let mut end = 0u64;
+        fields
+            .iter()
+            .map(|field| {
+                let offset = round_up(end, field.align)?;
+                end = offset.checked_add(field.size)?;
+                Some(offset)
+            })
+            .collect()
+    }
+
+    /// The layout of a struct with these fields, once rustc reordered them by decreasing
+    /// alignment, leaving no padding between them.
+    pub fn reordered(fields: &[Layout]) -> Option<Layout> {
+        let align = fields.iter().map(|it| it.align).max().unwrap_or(1);
+        let size = fields.iter().try_fold(0u64, |size, it| size.checked_add(it.size))?;
+        Some(Layout { size: round_up(size, align)?, align })
+    }
+}
+
+fn round_up(offset: u64, align: u64) -> Option<u64> {
+    Some(offset.checked_add(align - 1)? / align * align)
+}
+
+/// The parts of the target's data layout the supported types depend on.
+struct TargetDataLayout {
+    pointer_size: u64,
+    /// Alignment of `i64`, `u64` and `f64`.
+    align_64: u64,
+    /// Alignment of `i128` and `u128`, if known for the target.
+    align_128: Option<u64>,
+}
+
+impl TargetDataLayout {
+    fn of_crate(db: &dyn HirDatabase, krate: CrateId) -> Option<TargetDataLayout> {
+        let crate_graph = db.crate_graph();
+        let cfg_options = &crate_graph[krate].cfg_options;
+        let cfg_value = |key| cfg_options.get_cfg_values(key).next().map(|it| it.as_str());
+        // Crates without the cfgs, like the ones of tests, are assumed to be built for x86_64.
+        let pointer_size = match cfg_value("target_pointer_width") {
+            Some(width) => width.parse::<u64>().ok()? / 8,
+            None => 8,
+        };
+        // LLVM aligns integers without an alignment of their own in the data layout like the
+        // largest integer that has one, which is `i64` for `i128` on most targets.
+        let (align_64, align_128) = match cfg_value("target_arch").unwrap_or("x86_64") {
+            // The i386 System V ABI only aligns 8-byte scalars to 4 bytes.
+            "x86" if cfg_value("target_os") != Some("windows") => (4, Some(4)),
+            "x86" | "x86_64" | "arm" | "wasm32" => (8, Some(8)),
+            "aarch64" | "riscv64" => (8, Some(16)),
+            _ => (8, None),
+        };
+        Some(TargetDataLayout { pointer_size, align_64, align_128 })
+    }
+
+    fn scalar(&self, size: u64) -> Option<Layout> {
+        let align = match size {
+            8 => self.align_64,
+            16 => self.align_128?,
+            _ => size,
+        };
+        Some(Layout { size, align })
+    }
+}
+
+/// Returns `None` for types whose layout depends on things not known here, like generic
+/// parameters, or not modeled, like enums.
+pub fn layout_of_ty(db: &dyn HirDatabase, ty: &Ty, krate: CrateId) -> Option<Layout> {
+    layout_of(db, ty, &TargetDataLayout::of_crate(db, krate)?, 0)
+}
+
+/// Struct fields are looked through, which would never end for a struct that (erroneously)
+/// contains itself.
+const MAX_DEPTH: usize = 32;
+
+fn layout_of(db: &dyn HirDatabase, ty: &Ty, dl: &TargetDataLayout, depth: usize) -> Option<Layout> {
+    if depth > MAX_DEPTH {
+        return None;
+    }
+    let layout = match ty.kind(Interner) {
+        TyKind::Scalar(scalar) => dl.scalar(match scalar {
+            Scalar::Bool | Scalar::Int(IntTy::I8) | Scalar::Uint(UintTy::U8) => 1,
+            Scalar::Int(IntTy::I16) | Scalar::Uint(UintTy::U16) => 2,
+            Scalar::Char
+            | Scalar::Int(IntTy::I32)
+            | Scalar::Uint(UintTy::U32)
+            | Scalar::Float(FloatTy::F32) => 4,
+            Scalar::Int(IntTy::I64) | Scalar::Uint(UintTy::U64) | Scalar::Float(FloatTy::F64) => 8,
+            Scalar::Int(IntTy::I128) | Scalar::Uint(UintTy::U128) => 16,
+            Scalar::Int(IntTy::Isize) | Scalar::Uint(UintTy::Usize) => dl.pointer_size,
+        })?,
+        TyKind::Ref(_, _, pointee) | TyKind::Raw(_, pointee) => {
+            let is_fat =
+                matches!(pointee.kind(Interner), TyKind::Str | TyKind::Slice(_) | TyKind::Dyn(_));
+            let size = if is_fat { 2 * dl.pointer_size } else { dl.pointer_size };
+            Layout { size, align: dl.pointer_size }
+        }
+        TyKind::Function(_) => Layout { size: dl.pointer_size, align: dl.pointer_size },
+        TyKind::FnDef(..) => Layout { size: 0, align: 1 },
+        TyKind::Array(elem, len) => {
+            let len = match &len.data(Interner).value {
+                ConstValue::Concrete(ConcreteConst { interned: ConstScalar::Usize(len) }) => *len,
+                _ => return None,
+            };
+            let elem = layout_of(db, elem, dl, depth + 1)?;
+            Layout { size: elem.size.checked_mul(len)?, align: elem.align }
+        }
+        TyKind::Tuple(_, substs) => {
+            let fields = substs
+                .iter(Interner)
+                .map(|it| layout_of(db, it.assert_ty_ref(Interner), dl, depth + 1))
+                .collect::<Option<Vec<_>>>()?;
+            Layout::reordered(&fields)?
+        }
+        TyKind::Adt(crate::AdtId(AdtId::StructId(id)), substs) => {
+            let field_types = db.field_types((*id).into());
+            let fields = field_types
+                .iter()
+                .map(|(_, ty)| {
+                    let ty = ty.clone().substitute(Interner, substs);
+                    layout_of(db, &ty, dl, depth + 1)
+                })
+                .collect::<Option<Vec<_>>>()?;
+            match db.struct_data(*id).repr {
+                None => Layout::reordered(&fields)?,
+                Some(ReprKind::C) => Layout::sequential(&fields)?,
+                Some(_) => return None,
+            }
+        }
+        _ => return None,
+    };
+    Some(layout)
+}
@@ -10,6 +10,7 @@ mod autoderef;
 mod builder;
 mod chalk_db;
 mod chalk_ext;
+mod desugared_body;
 pub mod consteval;
 mod infer;
 mod interner;
@@ -21,6 +22,8 @@ mod walk;
 pub mod db;
 pub mod diagnostics;
 pub mod display;
+pub mod inference_profiling;
+pub mod layout;
 pub mod method_resolution;
 pub mod primitive;
 pub mod traits;
@@ -50,8 +53,10 @@ use crate::{db::HirDatabase, utils::generics};
 pub use autoderef::autoderef;
 pub use builder::{ParamKind, TyBuilder};
 pub use chalk_ext::*;
+pub use desugared_body::{desugared_body, DesugaredBody};
 pub use infer::{
-    could_coerce, could_unify, Adjust, Adjustment, AutoBorrow, InferenceDiagnostic, InferenceResult,
+    could_coerce, could_unify, unify, Adjust, Adjustment, Adjustments, AutoBorrow,
+    InferenceDiagnostic, InferenceResult, Overflow, TypeTable,
 };
 pub use interner::Interner;
 pub use lower::{
@@ -75,6 +80,7 @@ pub type ForeignDefId = chalk_ir::ForeignDefId<Interner>;
 pub type AssocTypeId = chalk_ir::AssocTypeId<Interner>;
 pub type FnDefId = chalk_ir::FnDefId<Interner>;
 pub type ClosureId = chalk_ir::ClosureId<Interner>;
+pub type GeneratorId = chalk_ir::GeneratorId<Interner>;
 pub type OpaqueTyId = chalk_ir::OpaqueTyId<Interner>;
 pub type PlaceholderIndex = chalk_ir::PlaceholderIndex;
 
@@ -284,6 +290,7 @@ impl Fold<Interner> for CallableSig {
 #[derive(Copy, Clone, PartialEq, Eq, Debug, Hash)]
 pub enum ImplTraitId {
     ReturnTypeImplTrait(hir_def::FunctionId, u16),
+    TypeAliasImplTrait(hir_def::TypeAliasId, u16),
     AsyncBlockTypeImplTrait(hir_def::DefWithBodyId, ExprId),
 }
 
@@ -8,7 +8,7 @@
 use std::cell::{Cell, RefCell};
 use std::{iter, sync::Arc};
 
-use base_db::CrateId;
+use base_db::{CrateId, Edition};
 use chalk_ir::fold::Fold;
 use chalk_ir::interner::HasInterner;
 use chalk_ir::{cast::Cast, fold::Shift, Mutability, Safety};
@@ -249,10 +249,19 @@ impl<'a> TyLoweringContext<'a> {
                     ImplTraitLoweringMode::Opaque => {
                         let idx = self.impl_trait_counter.get();
                         self.impl_trait_counter.set(idx + 1);
-                        let func = match self.resolver.generic_def() {
-                            Some(GenericDefId::FunctionId(f)) => f,
-                            _ => panic!("opaque impl trait lowering in non-function"),
-                        };
+                        // `impl Trait` is opaque in the return type of functions and in type
+                        // aliases (`type_alias_impl_trait`).
+                        let (def, impl_trait_id): (GenericDefId, _) =
+                            match self.resolver.generic_def() {
+                                Some(GenericDefId::FunctionId(f)) => {
+                                    (f.into(), ImplTraitId::ReturnTypeImplTrait(f, idx))
+                                }
+                                Some(GenericDefId::TypeAliasId(t)) => {
+                                    (t.into(), ImplTraitId::TypeAliasImplTrait(t, idx))
+                                }
+                                _ => panic!("opaque impl trait lowering in non-function"),
+                            };
+                        let krate = def.module(self.db.upcast()).krate();
 
                         assert!(idx as usize == self.opaque_type_data.borrow().len());
                         // this dance is to make sure the data is in the right
@@ -272,13 +281,12 @@ impl<'a> TyLoweringContext<'a> {
                         // away instead of two.
                         let actual_opaque_type_data = self
                             .with_debruijn(DebruijnIndex::INNERMOST, |ctx| {
-                                ctx.lower_impl_trait(bounds, func)
+                                ctx.lower_impl_trait(bounds, krate)
                             });
                         self.opaque_type_data.borrow_mut()[idx as usize] = actual_opaque_type_data;
 
-                        let impl_trait_id = ImplTraitId::ReturnTypeImplTrait(func, idx);
                         let opaque_ty_id = self.db.intern_impl_trait_id(impl_trait_id).into();
-                        let generics = generics(self.db.upcast(), func.into());
+                        let generics = generics(self.db.upcast(), def);
                         let parameters = generics.bound_vars_subst(self.db, self.in_binders);
                         TyKind::OpaqueType(opaque_ty_id, parameters).intern(Interner)
                     }
@@ -457,6 +465,12 @@ impl<'a> TyLoweringContext<'a> {
                         }
                     }
                     0 => {
+                        // Bare trait objects without `dyn` are an error since the 2021 edition.
+                        let edition =
+                            self.resolver.krate().map(|krate| self.db.crate_graph()[krate].edition);
+                        if edition >= Some(Edition::Edition2021) {
+                            return (TyKind::Error.intern(Interner), None);
+                        }
                         let self_ty = Some(
                             TyKind::BoundVar(BoundVar::new(DebruijnIndex::INNERMOST, 0))
                                 .intern(Interner),
@@ -853,7 +867,7 @@ impl<'a> TyLoweringContext<'a> {
         ignore_bindings: bool,
     ) -> impl Iterator<Item = QuantifiedWhereClause> + 'a {
         let mut bindings = None;
-        let trait_ref = match bound {
+        let mut trait_ref = match bound {
             TypeBound::Path(path, TraitBoundModifier::None) => {
                 bindings = self.lower_trait_ref_from_path(path, Some(self_ty));
                 bindings.clone().map(WhereClause::Implemented).map(crate::wrap_empty_binders)
@@ -883,7 +897,21 @@ impl<'a> TyLoweringContext<'a> {
             TypeBound::Lifetime(_) => None,
             TypeBound::Error => None,
         };
-        trait_ref.into_iter().chain(
+        // A trait alias stands for its bounds, with its parameters replaced by the arguments.
+        let mut alias_bounds = Vec::new();
+        if let Some(alias) =
+            bindings.clone().filter(|it| self.db.trait_data(it.hir_trait_id()).is_alias)
+        {
+            alias_bounds = self
+                .db
+                .trait_alias_bounds(alias.hir_trait_id())
+                .iter()
+                .map(|it| it.clone().substitute(Interner, &alias.substitution))
+                .collect();
+            trait_ref = None;
+            bindings = None;
+        }
+        trait_ref.into_iter().chain(alias_bounds).chain(
             bindings
                 .into_iter()
                 .filter(move |_| !ignore_bindings)
@@ -945,7 +973,7 @@ impl<'a> TyLoweringContext<'a> {
     fn lower_impl_trait(
         &self,
         bounds: &[Interned<TypeBound>],
-        func: FunctionId,
+        krate: CrateId,
     ) -> ReturnTypeImplTrait {
         cov_mark::hit!(lower_rpit);
         let self_ty = TyKind::BoundVar(BoundVar::new(DebruijnIndex::INNERMOST, 0)).intern(Interner);
@@ -956,7 +984,6 @@ impl<'a> TyLoweringContext<'a> {
                 .collect();
 
             if !ctx.unsized_types.borrow().contains(&self_ty) {
-                let krate = func.lookup(ctx.db.upcast()).module(ctx.db.upcast()).krate();
                 let sized_trait = ctx
                     .db
                     .lang_item(krate, SmolStr::new_inline("sized"))
@@ -1303,11 +1330,25 @@ pub(crate) fn generic_defaults_query(
         .map(|(idx, (id, p))| {
             let p = match p {
                 TypeOrConstParamData::TypeParamData(p) => p,
-                TypeOrConstParamData::ConstParamData(_) => {
-                    // FIXME: implement const generic defaults
-                    let val = unknown_const_as_generic(
-                        db.const_param_ty(ConstParamId::from_unchecked(id)),
-                    );
+                TypeOrConstParamData::ConstParamData(p) => {
+                    let val = match &p.default {
+                        Some(default) => {
+                            let c = const_or_path_to_chalk(
+                                db,
+                                &resolver,
+                                default,
+                                ParamLoweringMode::Variable,
+                                || generics(db.upcast(), def),
+                                DebruijnIndex::INNERMOST,
+                            );
+                            GenericArgData::Const(c).intern(Interner)
+                        }
+                        None => unknown_const_as_generic(
+                            db.const_param_ty(ConstParamId::from_unchecked(id)),
+                        ),
+                    };
+                    // Same as for types, defaults can only refer to previous parameters.
+                    let val = fallback_bound_vars(val, idx);
                     return crate::make_binders_with_count(db, idx, &generic_params, val);
                 }
             };
@@ -1464,8 +1505,9 @@ fn type_for_adt(db: &dyn HirDatabase, adt: AdtId) -> Binders<Ty> {
 fn type_for_type_alias(db: &dyn HirDatabase, t: TypeAliasId) -> Binders<Ty> {
     let generics = generics(db.upcast(), t.into());
     let resolver = t.resolver(db.upcast());
-    let ctx =
-        TyLoweringContext::new(db, &resolver).with_type_param_mode(ParamLoweringMode::Variable);
+    let ctx = TyLoweringContext::new(db, &resolver)
+        .with_impl_trait_mode(ImplTraitLoweringMode::Opaque)
+        .with_type_param_mode(ParamLoweringMode::Variable);
     if db.type_alias_data(t).is_extern {
         Binders::empty(Interner, TyKind::Foreign(crate::to_foreign_def_id(t)).intern(Interner))
     } else {
@@ -1630,6 +1672,54 @@ pub(crate) fn return_type_impl_traits(
     }
 }
 
+/// The bounds of the `impl Trait` types in a type alias, like `type Foo = impl Iterator;` with
+/// the `type_alias_impl_trait` feature.
+pub(crate) fn type_alias_impl_traits(
+    db: &dyn HirDatabase,
+    def: TypeAliasId,
+) -> Option<Arc<Binders<ReturnTypeImplTraits>>> {
+    let data = db.type_alias_data(def);
+    let type_ref = data.type_ref.as_deref()?;
+    let resolver = def.resolver(db.upcast());
+    let ctx = TyLoweringContext::new(db, &resolver)
+        .with_impl_trait_mode(ImplTraitLoweringMode::Opaque)
+        .with_type_param_mode(ParamLoweringMode::Variable);
+    let _ty = (&ctx).lower_ty(type_ref);
+    let generics = generics(db.upcast(), def.into());
+    let impl_traits = ReturnTypeImplTraits { impl_traits: ctx.opaque_type_data.into_inner() };
+    if impl_traits.impl_traits.is_empty() {
+        None
+    } else {
+        Some(Arc::new(make_binders(db, &generics, impl_traits)))
+    }
+}
+
+/// The bounds a trait alias like `trait Alias<T> = Foo<T> + Bar where T: Baz;` stands for, with
+/// the parameters of the alias (`Self` and `T`) bound.
+pub(crate) fn trait_alias_bounds_query(
+    db: &dyn HirDatabase,
+    trait_: TraitId,
+) -> Arc<[Binders<QuantifiedWhereClause>]> {
+    let resolver = trait_.resolver(db.upcast());
+    let ctx =
+        TyLoweringContext::new(db, &resolver).with_type_param_mode(ParamLoweringMode::Variable);
+    let generics = generics(db.upcast(), trait_.into());
+    resolver
+        .where_predicates_in_scope()
+        .flat_map(|pred| {
+            ctx.lower_where_predicate(pred, false).map(|p| make_binders(db, &generics, p))
+        })
+        .collect()
+}
+
+pub(crate) fn trait_alias_bounds_recover(
+    _db: &dyn HirDatabase,
+    _cycle: &[String],
+    _trait: &TraitId,
+) -> Arc<[Binders<QuantifiedWhereClause>]> {
+    Arc::new([])
+}
+
 pub(crate) fn lower_to_chalk_mutability(m: hir_def::type_ref::Mutability) -> Mutability {
     match m {
         hir_def::type_ref::Mutability::Shared => Mutability::Not,
@@ -23,9 +23,9 @@ use crate::{
     infer::{unify::InferenceTable, Adjust, Adjustment, AutoBorrow, OverloadedDeref, PointerCast},
     primitive::{self, FloatTy, IntTy, UintTy},
     static_lifetime,
-    utils::all_super_traits,
+    utils::{all_super_traits, generics},
     AdtId, Canonical, CanonicalVarKinds, DebruijnIndex, ForeignDefId, InEnvironment, Interner,
-    Scalar, TraitEnvironment, TraitRefExt, Ty, TyBuilder, TyExt, TyKind,
+    Scalar, Substitution, TraitEnvironment, TraitRefExt, Ty, TyBuilder, TyExt, TyKind,
 };
 
 /// This is used as a key for indexing impls.
@@ -1089,6 +1089,61 @@ pub fn implements_trait_unique(
     matches!(solution, Some(crate::Solution::Unique(_)))
 }
 
+/// Looks up the impl method that actually runs for a call to the trait method `func` with the
+/// given substitution. Returns `func` itself if it isn't a trait method or no impl matches.
+pub fn lookup_impl_method(
+    db: &dyn HirDatabase,
+    env: Arc<TraitEnvironment>,
+    func: FunctionId,
+    fn_subst: Substitution,
+) -> FunctionId {
+    let trait_id = match func.lookup(db.upcast()).container {
+        ItemContainerId::TraitId(id) => id,
+        _ => return func,
+    };
+    let trait_params = generics(db.upcast(), trait_id.into()).len();
+    let trait_args: Vec<Ty> = fn_subst
+        .iter(Interner)
+        .take(trait_params)
+        .filter_map(|arg| arg.ty(Interner).cloned())
+        .collect();
+    let self_ty = match trait_args.first() {
+        Some(ty) => ty,
+        None => return func,
+    };
+    let self_ty_fp = match TyFingerprint::for_trait_impl(self_ty) {
+        Some(fp) => fp,
+        None => return func,
+    };
+    let name = &db.function_data(func).name;
+
+    let trait_impls = db.trait_impls_in_deps(env.krate);
+    let mut table = InferenceTable::new(db, env.clone());
+    for impl_id in trait_impls.for_trait_and_self_ty(trait_id, self_ty_fp) {
+        let impl_trait = match db.impl_trait(impl_id) {
+            Some(it) => it,
+            None => continue,
+        };
+        let matches = table.run_in_snapshot(|table| {
+            let impl_subst =
+                TyBuilder::subst_for_def(db, impl_id).fill_with_inference_vars(table).build();
+            let impl_trait = impl_trait.substitute(Interner, &impl_subst);
+            let impl_args =
+                impl_trait.substitution.iter(Interner).filter_map(|arg| arg.ty(Interner));
+            impl_args.zip(trait_args.iter()).all(|(impl_arg, arg)| table.unify(impl_arg, arg))
+        });
+        if !matches {
+            continue;
+        }
+        let impl_fn = db.impl_data(impl_id).items.iter().find_map(|item| match *item {
+            AssocItemId::FunctionId(f) if db.function_data(f).name == *name => Some(f),
+            _ => None,
+        });
+        return impl_fn.unwrap_or(func);
+    }
+    func
+}
+
 /// This creates Substs for a trait with the given Self type and type variables
 /// for all other parameters, to query Chalk with it.
 fn generic_implements_goal(
@@ -135,6 +135,10 @@ fn check_impl(ra_fixture: &str, allow_none: bool, only_types: bool, display_sour
             let loc = it.lookup(&db);
             loc.source(&db).value.syntax().text_range().start()
         }
+        DefWithBodyId::VariantId(it) => {
+            let loc = it.parent.lookup(&db);
+            loc.source(&db).value.syntax().text_range().start()
+        }
     });
     let mut unexpected_type_mismatches = String::new();
     for def in defs {
@@ -388,6 +392,10 @@ fn infer_with_mismatches(content: &str, include_mismatches: bool) -> String {
             let loc = it.lookup(&db);
             loc.source(&db).value.syntax().text_range().start()
         }
+        DefWithBodyId::VariantId(it) => {
+            let loc = it.parent.lookup(&db);
+            loc.source(&db).value.syntax().text_range().start()
+        }
     });
     for def in defs {
         let (_body, source_map) = db.body_with_source_map(def);
@@ -576,3 +584,32 @@ fn salsa_bug() {
         db.infer(def);
     });
 }
+
+#[test]
+fn identical_bodies_share_inference_tables() {
+    let (db, file_id) = TestDB::with_single_file(
+        r#"
+struct S;
+impl S { fn get(&self) -> u32 { 0 } }
+const A: u32 = 1 + 2;
+const B: u32 = 1 + 2;
+fn f(s: S) -> u32 { s.get() }
+fn g(s: S) -> u32 { s.get() }
+"#,
+    );
+    let module = db.module_for_file(file_id);
+    let crate_def_map = module.def_map(&db);
+    let mut results = Vec::new();
+    visit_module(&db, &crate_def_map, module.local_id, &mut |def| {
+        if !matches!(def, DefWithBodyId::VariantId(_)) {
+            results.push(db.infer(def));
+        }
+    });
+    let [a, b, f, g, _get]: [_; 5] = results.try_into().unwrap();
+    assert_eq!(a.type_of_expr, b.type_of_expr);
+    assert_eq!(f.type_of_expr, g.type_of_expr);
+    assert_eq!(f.type_of_pat, g.type_of_pat);
+    let adjustments = |it: &InferenceResult| it.expr_adjustments.values().next().cloned();
+    assert!(adjustments(&f).is_some());
+    assert_eq!(adjustments(&f), adjustments(&g));
+}
@@ -913,6 +913,37 @@ fn bar() -> u32 {0}
     );
 }
 
+#[test]
+fn infer_builtin_macros_include_out_dir_in_module() {
+    check_types(
+        r#"
+//- /main.rs crate:main env:OUT_DIR=/out
+#[rustc_builtin_macro]
+macro_rules! include {() => {}}
+
+#[rustc_builtin_macro]
+macro_rules! concat {() => {}}
+
+#[rustc_builtin_macro]
+macro_rules! env {() => {}}
+
+mod bindings {
+    include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+}
+
+fn main() {
+    bindings::generated::bar();
+} //^^^^^^^^^^^^^^^^^^^^^^^^^^ u32
+
+//- /out/bindings.rs
+pub mod generated;
+
+//- /out/generated.rs
+pub fn bar() -> u32 {0}
+"#,
+    );
+}
+
 #[test]
 fn infer_builtin_macros_include_itself_should_failed() {
     check_types(
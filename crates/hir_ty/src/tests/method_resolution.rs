@@ -1484,6 +1484,37 @@ fn f(x: U2) {
     );
 }
 
+#[test]
+fn const_generic_defaults_and_arguments() {
+    check_types(
+        r#"
+struct ArrayVec<T, const N: usize = 16>;
+impl<T, const N: usize> ArrayVec<T, N> {
+    fn new() -> Self { loop {} }
+    fn array(self) -> [T; N] { loop {} }
+}
+struct Pair<const N: usize, const M: usize = N>;
+
+fn f() {
+    let v: ArrayVec<u8> = ArrayVec::new();
+    v;
+  //^ ArrayVec<u8, 16>
+    v.array();
+  //^^^^^^^^^ [u8; 16]
+    let w: ArrayVec<u8, { 4 }> = ArrayVec::new();
+    w;
+  //^ ArrayVec<u8, 4>
+    let x: ArrayVec<u8, { 4 * 2 + 1 }> = ArrayVec::new();
+    x.array();
+  //^^^^^^^^^ [u8; 9]
+    let p: Pair<3>;
+    p;
+  //^ Pair<3, 3>
+}
+    "#,
+    );
+}
+
 #[test]
 fn skip_array_during_method_dispatch() {
     check_types(
@@ -1762,3 +1793,77 @@ fn foo() {
 "#,
     );
 }
+
+#[test]
+fn arbitrary_self_types() {
+    check_types(
+        r#"
+//- minicore: deref, deref_ref, pin, sized
+use core::ops::Deref;
+use core::pin::Pin;
+
+struct Rc<T>(T);
+impl<T> Deref for Rc<T> {
+    type Target = T;
+    fn deref(&self) -> &T { &self.0 }
+}
+
+struct Foo;
+impl Foo {
+    fn by_rc(self: Rc<Self>) -> i32 { 0 }
+    fn by_pin(self: Pin<&mut Self>) -> u32 { 0 }
+}
+
+trait Trait {
+    fn by_rc_trait(self: Rc<Self>) -> i64;
+}
+impl Trait for Foo {
+    fn by_rc_trait(self: Rc<Self>) -> i64 { 0 }
+}
+
+fn test(rc: Rc<Foo>, pin: Pin<&mut Foo>) {
+    rc.by_rc();
+ // ^^^^^^^^^^ i32
+    rc.by_rc_trait();
+ // ^^^^^^^^^^^^^^^^ i64
+    pin.by_pin();
+ // ^^^^^^^^^^^^ u32
+}
+"#,
+    );
+}
+
+#[test]
+fn box_self_receiver_without_deref_impl() {
+    check_types(
+        r#"
+//- minicore: sized
+#[lang = "owned_box"]
+struct Box<T: ?Sized>(*mut T);
+
+struct Foo;
+impl Foo {
+    fn by_box(self: Box<Self>) -> i32 { 0 }
+    fn by_ref(&self) -> u32 { 0 }
+}
+
+trait Trait {
+    fn by_box_trait(self: Box<Self>) -> i64;
+}
+impl Trait for Foo {
+    fn by_box_trait(self: Box<Self>) -> i64 { 0 }
+}
+
+fn test(b: Box<Foo>, d: Box<dyn Trait>) {
+    b.by_box();
+ // ^^^^^^^^^^ i32
+    b.by_ref();
+ // ^^^^^^^^^^ u32
+    d.by_box_trait();
+ // ^^^^^^^^^^^^^^^^ i64
+    *b;
+ // ^^ Foo
+}
+"#,
+    );
+}
@@ -953,6 +953,32 @@ fn main() {
     );
 }
 
+#[test]
+fn let_chains() {
+    check_types(
+        r#"
+//- minicore: option
+fn main(a: Option<u8>, b: Option<&str>) {
+    if let Some(x) = a && let Some(y) = b && x > 1 {
+        (x, y);
+      //^^^^^^ (u8, &str)
+    }
+    while let Some(x) = a && (let Some(y) = b) {
+        y;
+      //^ &str
+    }
+    match a {
+        Some(x) if x > 1 && let Some(y) = b => {
+            y;
+          //^ &str
+        }
+        _ => {}
+    }
+}
+    "#,
+    );
+}
+
 #[test]
 fn if_let_guards() {
     check_types(
@@ -649,6 +649,39 @@ fn test(s: Arc<S>) {
     );
 }
 
+#[test]
+fn user_lang_items_take_precedence_over_sysroot() {
+    check_types(
+        r#"
+//- /main.rs crate:main deps:core,kernel_lang
+use kernel_lang::Deref;
+
+struct Arc<T>(T);
+impl<T> Deref for Arc<T> {
+    type Target = T;
+}
+
+fn test(s: Arc<u8>) {
+    *s;
+} //^^ u8
+
+//- /core.rs crate:core@Lang
+pub mod ops {
+    #[lang = "deref"]
+    pub trait Deref {
+        type Target;
+    }
+}
+
+//- /kernel_lang.rs crate:kernel_lang
+#[lang = "deref"]
+pub trait Deref {
+    type Target;
+}
+"#,
+    );
+}
+
 #[test]
 fn deref_trait_with_inference_var() {
     check_types(
@@ -1380,6 +1413,22 @@ fn test(x: Trait, y: &Trait) -> u64 {
     );
 }
 
+#[test]
+fn dyn_trait_bare_2021() {
+    check_types(
+        r#"
+//- /main.rs crate:main edition:2021
+trait Trait {}
+fn test(x: &Trait, y: &dyn Trait) {
+    x;
+  //^ &{unknown}
+    y;
+  //^ &dyn Trait
+}
+"#,
+    );
+}
+
 #[test]
 fn weird_bounds() {
     check_infer(
@@ -1487,6 +1536,120 @@ fn test<T: Trait<Type = u32>>(x: T, y: impl Trait<Type = i64>) {
     );
 }
 
+#[test]
+fn type_alias_impl_trait() {
+    check_types(
+        r#"
+//- minicore: iterator
+#![feature(type_alias_impl_trait)]
+struct S;
+impl Iterator for S {
+    type Item = u32;
+    fn next(&mut self) -> Option<u32> { None }
+}
+mod m {
+    pub type Foo = impl Iterator<Item = u32>;
+    pub fn make() -> Foo {
+        let it: Foo = super::S;
+        it
+      //^^ S
+    }
+}
+fn test() {
+    let it = m::make();
+      //^^ impl Iterator<Item = u32>
+    let mut it = it;
+    it.next();
+  //^^^^^^^^^ Option<u32>
+}
+"#,
+    );
+}
+
+#[test]
+fn type_alias_impl_trait_used_without_defining() {
+    check_types(
+        r#"
+//- minicore: iterator
+#![feature(type_alias_impl_trait)]
+struct S;
+impl Iterator for S {
+    type Item = u32;
+    fn next(&mut self) -> Option<u32> { None }
+}
+pub type Foo = impl Iterator<Item = u32>;
+pub fn make() -> Foo {
+    S
+}
+fn consume(mut it: Foo) {
+    it;
+  //^^ impl Iterator<Item = u32>
+    it.next();
+  //^^^^^^^^^ Option<u32>
+}
+fn forward() -> Foo {
+    let it = make();
+    it
+  //^^ impl Iterator<Item = u32>
+}
+"#,
+    );
+}
+
+#[test]
+fn trait_alias() {
+    check_types(
+        r#"
+//- minicore: sized
+#![feature(trait_alias)]
+trait Foo {
+    fn foo(&self) -> u8;
+}
+trait Bar<T> {
+    fn bar(&self) -> T;
+}
+trait FooBar<T> = Foo + Bar<T>;
+trait FooBarU16 = FooBar<u16>;
+
+fn test<T: FooBarU16>(t: T, u: impl FooBar<i32>) {
+    t.foo();
+  //^^^^^^^ u8
+    t.bar();
+  //^^^^^^^ u16
+    u.bar();
+  //^^^^^^^ i32
+}
+"#,
+    );
+}
+
+#[test]
+fn generator_types() {
+    check_types(
+        r#"
+//- minicore: generator
+use core::ops::Generator;
+
+fn generator_types<G: Generator<R>, R>(g: G) -> (R, G::Yield, G::Return) { loop {} }
+
+fn test() {
+    let g = |x: u8| {
+        let resumed = yield 1u32;
+          //^^^^^^^ u8
+        "done"
+    };
+    let (r, y, ret) = generator_types(g);
+    r;
+  //^ u8
+    y;
+  //^ u32
+    ret;
+  //^^^ &str
+}
+"#,
+    );
+}
+
 #[test]
 fn impl_trait_assoc_binding_projection_bug() {
     check_types(
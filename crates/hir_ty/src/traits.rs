@@ -1,6 +1,11 @@
 //! Trait solving using Chalk.
 
-use std::env::var;
+use std::{
+    cell::Cell,
+    convert::TryInto,
+    env::var,
+    panic::{self, AssertUnwindSafe},
+};
 
 use chalk_ir::GoalData;
 use chalk_recursive::Cache;
@@ -12,12 +17,16 @@ use stdx::panic_context;
 use syntax::SmolStr;
 
 use crate::{
-    db::HirDatabase, AliasEq, AliasTy, Canonical, DomainGoal, Goal, Guidance, InEnvironment,
-    Interner, Solution, TraitRefExt, Ty, TyKind, WhereClause,
+    db::HirDatabase, inference_profiling, AliasEq, AliasTy, Canonical, DomainGoal, Goal, Guidance,
+    InEnvironment, Interner, Solution, TraitRefExt, Ty, TyKind, WhereClause,
 };
 
-/// This controls how much 'time' we give the Chalk solver before giving up.
-const CHALK_SOLVER_FUEL: i32 = 100;
+/// This controls how much 'time' we give the Chalk solver before giving up, unless overridden via
+/// [`HirDatabase::trait_solver_fuel`].
+pub const DEFAULT_TRAIT_SOLVER_FUEL: u32 = 100;
+
+/// The stack depth Chalk is allowed to reach, unless the crate's `recursion_limit` is higher.
+const CHALK_OVERFLOW_DEPTH: usize = 300;
 
 #[derive(Debug, Copy, Clone)]
 pub(crate) struct ChalkContext<'a> {
@@ -25,9 +34,14 @@ pub(crate) struct ChalkContext<'a> {
     pub(crate) krate: CrateId,
 }
 
-fn create_chalk_solver() -> chalk_recursive::RecursiveSolver<Interner> {
+fn create_chalk_solver(
+    db: &dyn HirDatabase,
+    krate: CrateId,
+) -> chalk_recursive::RecursiveSolver<Interner> {
     let overflow_depth =
-        var("CHALK_OVERFLOW_DEPTH").ok().and_then(|s| s.parse().ok()).unwrap_or(300);
+        var("CHALK_OVERFLOW_DEPTH").ok().and_then(|s| s.parse().ok()).unwrap_or_else(|| {
+            CHALK_OVERFLOW_DEPTH.max(db.crate_limits(krate).recursion_limit as usize)
+        });
     let max_size = var("CHALK_SOLVER_MAX_SIZE").ok().and_then(|s| s.parse().ok()).unwrap_or(150);
     chalk_recursive::RecursiveSolver::new(overflow_depth, max_size, Some(Cache::new()))
 }
@@ -64,12 +78,30 @@ impl TraitEnvironment {
     }
 }
 
+/// Why the trait solver gave up on a goal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SolverOverflow {
+    /// It ran out of fuel, see [`HirDatabase::trait_solver_fuel`].
+    Fuel,
+    /// Its stack got deeper than the overflow depth, which follows the crate's `recursion_limit`.
+    Depth,
+}
+
+/// The result of solving a trait goal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraitSolveResult {
+    pub solution: Option<Solution>,
+    /// Set if the solver gave up before reaching a definite answer, in which case `solution` is
+    /// at best ambiguous.
+    pub overflow: Option<SolverOverflow>,
+}
+
 /// Solve a trait goal using Chalk.
 pub(crate) fn trait_solve_query(
     db: &dyn HirDatabase,
     krate: CrateId,
     goal: Canonical<InEnvironment<Goal>>,
-) -> Option<Solution> {
+) -> TraitSolveResult {
     let _p = profile::span("trait_solve_query").detail(|| match &goal.value.goal.data(Interner) {
         GoalData::DomainGoal(DomainGoal::Holds(WhereClause::Implemented(it))) => {
             db.trait_data(it.hir_trait_id()).name.to_string()
@@ -86,28 +118,82 @@ pub(crate) fn trait_solve_query(
     {
         if let TyKind::BoundVar(_) = projection_ty.self_type_parameter(Interner).kind(Interner) {
             // Hack: don't ask Chalk to normalize with an unknown self type, it'll say that's impossible
-            return Some(Solution::Ambig(Guidance::Unknown));
+            return TraitSolveResult {
+                solution: Some(Solution::Ambig(Guidance::Unknown)),
+                overflow: None,
+            };
         }
     }
 
     // We currently don't deal with universes (I think / hope they're not yet
     // relevant for our use cases?)
     let u_canonical = chalk_ir::UCanonical { canonical: goal, universes: 1 };
-    solve(db, krate, &u_canonical)
+    inference_profiling::record_trait_solving(|| solve(db, krate, &u_canonical))
 }
 
-fn solve(
+pub(crate) fn trait_solve_overflow(
     db: &dyn HirDatabase,
     krate: CrateId,
-    goal: &chalk_ir::UCanonical<chalk_ir::InEnvironment<chalk_ir::Goal<Interner>>>,
-) -> Option<chalk_solve::Solution<Interner>> {
-    let context = ChalkContext { db, krate };
-    tracing::debug!("solve goal: {:?}", goal);
-    let mut solver = create_chalk_solver();
+    goal: Canonical<InEnvironment<Goal>>,
+) -> Option<SolverOverflow> {
+    db.trait_solve_query(krate, goal).overflow
+}
+
+/// Solves the goal again while recording the Chalk program it depends on, returning a textual
+/// dump of both that can be attached to bug reports.
+pub fn dump_trait_solve(
+    db: &dyn HirDatabase,
+    krate: CrateId,
+    goal: Canonical<InEnvironment<Goal>>,
+) -> String {
+    let logging_db = LoggingRustIrDatabase::new(ChalkContext { db, krate });
+    let goal = chalk_ir::UCanonical { canonical: goal, universes: 1 };
+    let mut solver = create_chalk_solver(db, krate);
+    let fuel = Cell::new(initial_fuel(db));
+    let should_continue = fuel_checker(db, &fuel);
+
+    crate::tls::set_current_program(db, || {
+        let (solution, depth_reached) =
+            catch_overflow_depth(|| solver.solve_limited(&logging_db, &goal, &should_continue));
+        format!(
+            "goal:\n{:?}\n\nsolution (overflow: {:?}):\n{:?}\n\nprogram:\n{}",
+            goal.canonical.value,
+            overflow(&fuel, depth_reached),
+            solution,
+            logging_db
+        )
+    })
+}
+
+/// Chalk panics once its stack reaches the overflow depth. That's caught here and turned into
+/// an ambiguous solution, returning whether it happened.
+fn catch_overflow_depth(solve: impl FnOnce() -> Option<Solution>) -> (Option<Solution>, bool) {
+    match panic::catch_unwind(AssertUnwindSafe(solve)) {
+        Ok(solution) => (solution, false),
+        Err(payload) if payload.downcast_ref::<&str>() == Some(&"overflow depth reached") => {
+            (Some(Solution::Ambig(Guidance::Unknown)), true)
+        }
+        Err(payload) => panic::resume_unwind(payload),
+    }
+}
 
-    let fuel = std::cell::Cell::new(CHALK_SOLVER_FUEL);
+fn overflow(fuel: &Cell<i32>, depth_reached: bool) -> Option<SolverOverflow> {
+    if depth_reached {
+        Some(SolverOverflow::Depth)
+    } else if fuel.get() < 0 {
+        // the fuel only drops below zero once `should_continue` has returned `false`
+        Some(SolverOverflow::Fuel)
+    } else {
+        None
+    }
+}
 
-    let should_continue = || {
+fn initial_fuel(db: &dyn HirDatabase) -> i32 {
+    db.trait_solver_fuel().try_into().unwrap_or(i32::MAX)
+}
+
+fn fuel_checker<'a>(db: &'a dyn HirDatabase, fuel: &'a Cell<i32>) -> impl Fn() -> bool + 'a {
+    move || {
         db.unwind_if_cancelled();
         let remaining = fuel.get();
         fuel.set(remaining - 1);
@@ -115,7 +201,20 @@ fn solve(
             tracing::debug!("fuel exhausted");
         }
         remaining > 0
-    };
+    }
+}
+
+fn solve(
+    db: &dyn HirDatabase,
+    krate: CrateId,
+    goal: &chalk_ir::UCanonical<chalk_ir::InEnvironment<chalk_ir::Goal<Interner>>>,
+) -> TraitSolveResult {
+    let context = ChalkContext { db, krate };
+    tracing::debug!("solve goal: {:?}", goal);
+    let mut solver = create_chalk_solver(db, krate);
+
+    let fuel = Cell::new(initial_fuel(db));
+    let should_continue = fuel_checker(db, &fuel);
 
     let mut solve = || {
         let _ctx = if is_chalk_debug() || is_chalk_print() {
@@ -138,11 +237,14 @@ fn solve(
 
     // don't set the TLS for Chalk unless Chalk debugging is active, to make
     // extra sure we only use it for debugging
-    if is_chalk_debug() {
-        crate::tls::set_current_program(db, solve)
-    } else {
-        solve()
-    }
+    let (solution, depth_reached) = catch_overflow_depth(|| {
+        if is_chalk_debug() {
+            crate::tls::set_current_program(db, solve)
+        } else {
+            solve()
+        }
+    });
+    TraitSolveResult { solution, overflow: overflow(&fuel, depth_reached) }
 }
 
 struct LoggingRustIrDatabaseLoggingOnDrop<'a>(LoggingRustIrDatabase<Interner, ChalkContext<'a>>);
@@ -185,3 +287,13 @@ impl FnTrait {
         }
     }
 }
+
+#[test]
+fn overflow_depth_is_caught() {
+    let (solution, depth_reached) = catch_overflow_depth(|| panic!("overflow depth reached"));
+    assert_eq!(solution, Some(Solution::Ambig(Guidance::Unknown)));
+    assert!(depth_reached);
+
+    let res = panic::catch_unwind(|| catch_overflow_depth(|| panic!("something else")));
+    assert!(res.is_err());
+}
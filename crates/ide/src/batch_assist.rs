@@ -0,0 +1,267 @@
+//! Applies an assist, or the fix of a diagnostic, at every position it is
+//! applicable in a file, a crate or the whole workspace, for mechanical
+//! migrations.
+//!
+//! Assists are position-based, so this simply asks for them at the start of
+//! every token. That's slow, but these are one-off operations, and it is the
+//! only way to be sure to find all the positions an assist handles.
+
+use hir::{Crate, Semantics};
+use ide_db::{
+    assists::{AssistResolveStrategy, SingleResolve},
+    base_db::{FileId, FileRange, SourceDatabaseExt},
+    source_change::SourceChange,
+    RootDatabase,
+};
+use ide_diagnostics::DiagnosticsConfig;
+use rustc_hash::FxHashMap;
+use syntax::{AstNode, TextRange};
+use text_edit::{Indel, TextEdit};
+
+use crate::{AssistConfig, AssistKind};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BatchAssistScope {
+    File,
+    /// All the files of the crates the file belongs to.
+    Crate,
+    /// All the files of the crates in the workspace, not of dependencies.
+    Workspace,
+}
+
+#[derive(Debug, Default)]
+pub struct BatchAssist {
+    pub source_change: SourceChange,
+    /// How many times the assist was applied.
+    pub applied: usize,
+    /// How many times it was applicable, but its edit conflicted with one
+    /// which was already applied, or it had to create or move files.
+    pub skipped: usize,
+}
+
+pub(crate) fn batch_assist(
+    db: &RootDatabase,
+    assist_config: &AssistConfig,
+    diagnostics_config: &DiagnosticsConfig,
+    assist_id: &str,
+    scope: BatchAssistScope,
+    file_id: FileId,
+) -> BatchAssist {
+    let _p = profile::span("batch_assist");
+    // A single consolidated edit can't have placeholders.
+    let assist_config = AssistConfig { snippet_cap: None, allowed: None, ..assist_config.clone() };
+    let mut acc = Acc::default();
+    for file_id in files_in_scope(db, scope, file_id) {
+        let fixes = ide_diagnostics::diagnostics(
+            db,
+            diagnostics_config,
+            &AssistResolveStrategy::Single(SingleResolve {
+                assist_id: assist_id.to_string(),
+                assist_kind: AssistKind::QuickFix,
+            }),
+            file_id,
+        )
+        .into_iter()
+        .flat_map(|it| it.fixes.unwrap_or_default())
+        .filter(|it| it.id.0 == assist_id);
+        for fix in fixes {
+            acc.add(fix.source_change);
+        }
+
+        let source_file = db.parse(file_id).tree();
+        for token in source_file.syntax().descendants_with_tokens().filter_map(|it| it.into_token())
+        {
+            if token.kind().is_trivia() {
+                continue;
+            }
+            let frange = FileRange { file_id, range: TextRange::empty(token.text_range().start()) };
+            // Only the matching assist is worth resolving, but its kind is
+            // needed to ask for it.
+            let kind =
+                ide_assists::assists(db, &assist_config, AssistResolveStrategy::None, frange)
+                    .into_iter()
+                    .find(|it| it.id.0 == assist_id)
+                    .map(|it| it.id.1);
+            let assist_kind = match kind {
+                Some(it) => it,
+                None => continue,
+            };
+            let resolve = AssistResolveStrategy::Single(SingleResolve {
+                assist_id: assist_id.to_string(),
+                assist_kind,
+            });
+            // Assists offering several alternatives, like `auto_import`, get the
+            // first one applied.
+            let assist = ide_assists::assists(db, &assist_config, resolve, frange)
+                .into_iter()
+                .find(|it| it.id.0 == assist_id);
+            if let Some(assist) = assist {
+                acc.add(assist.source_change);
+            }
+        }
+    }
+    acc.res
+}
+
+#[derive(Default)]
+struct Acc {
+    res: BatchAssist,
+    applied_indels: FxHashMap<FileId, Vec<Indel>>,
+}
+
+impl Acc {
+    fn add(&mut self, source_change: Option<SourceChange>) {
+        let source_change = match source_change {
+            Some(it) => it,
+            None => return,
+        };
+        let conflicts = |(file_id, edit): (&FileId, &TextEdit)| {
+            let applied = self.applied_indels.get(file_id).map_or(&[][..], |it| it.as_slice());
+            edit.iter().any(|indel| applied.iter().any(|it| overlap(indel, it)))
+        };
+        if !source_change.file_system_edits.is_empty()
+            || source_change.source_file_edits.iter().any(conflicts)
+        {
+            self.res.skipped += 1;
+            return;
+        }
+        for (file_id, edit) in source_change.source_file_edits {
+            self.applied_indels.entry(file_id).or_default().extend(edit.iter().cloned());
+            self.res.source_change.insert_source_edit(file_id, edit);
+        }
+        self.res.applied += 1;
+    }
+}
+
+/// Whether two edits can't be applied together. The same assist found from
+/// two positions inserts the same text at the same offset, which counts too.
+fn overlap(a: &Indel, b: &Indel) -> bool {
+    let (a, b) = (a.delete, b.delete);
+    a.start() == b.start() || (a.start() < b.end() && b.start() < a.end())
+}
+
+fn files_in_scope(db: &RootDatabase, scope: BatchAssistScope, file_id: FileId) -> Vec<FileId> {
+    let crates: Vec<Crate> = match scope {
+        BatchAssistScope::File => return vec![file_id],
+        BatchAssistScope::Crate => {
+            let sema = Semantics::new(db);
+            let mut crates: Vec<_> = sema.to_module_defs(file_id).map(|it| it.krate()).collect();
+            crates.dedup();
+            crates
+        }
+        BatchAssistScope::Workspace => Crate::all(db)
+            .into_iter()
+            .filter(|krate| {
+                let root_id = db.file_source_root(krate.root_file(db));
+                !db.source_root(root_id).is_library
+            })
+            .collect(),
+    };
+    let mut files = Vec::new();
+    for krate in crates {
+        for module in krate.modules(db) {
+            let file_id = module.definition_source(db).file_id.original_file(db);
+            if !files.contains(&file_id) {
+                files.push(file_id);
+            }
+        }
+    }
+    if files.is_empty() {
+        files.push(file_id);
+    }
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{expect, Expect};
+    use hir::PrefixKind;
+    use ide_db::imports::insert_use::{ImportGranularity, InsertUseConfig};
+    use stdx::format_to;
+
+    use crate::{fixture, AssistConfig, BatchAssistScope, BoundsPlacement, DiagnosticsConfig};
+
+    fn check(ra_fixture: &str, assist_id: &str, scope: BatchAssistScope, expect: Expect) {
+        let (analysis, file_id) = fixture::file(ra_fixture);
+        let assist_config = AssistConfig {
+            snippet_cap: None,
+            allowed: None,
+            insert_use: InsertUseConfig {
+                granularity: ImportGranularity::Crate,
+                enforce_granularity: true,
+                prefix_kind: PrefixKind::Plain,
+                group: true,
+                skip_glob_imports: true,
+            },
+            always_qualify: Vec::new(),
+            bounds_placement: BoundsPlacement::WhereClause,
+        };
+        let res = analysis
+            .batch_assist(&assist_config, &DiagnosticsConfig::default(), assist_id, scope, file_id)
+            .unwrap();
+        let mut edits: Vec<_> = res.source_change.source_file_edits.into_iter().collect();
+        edits.sort_by_key(|(file_id, _)| *file_id);
+        let mut actual = format!("applied: {}, skipped: {}\n", res.applied, res.skipped);
+        for (file_id, edit) in edits {
+            let mut text = analysis.file_text(file_id).unwrap().to_string();
+            edit.apply(&mut text);
+            format_to!(actual, "{:?}\n{}", file_id, text);
+        }
+        expect.assert_eq(&actual);
+    }
+
+    #[test]
+    fn applies_assist_everywhere_in_file() {
+        check(
+            r#"
+fn f() {
+    let a = 1;
+    let b = "two";
+    let c: u8 = 3;
+}
+"#,
+            "add_explicit_type",
+            BatchAssistScope::File,
+            expect![[r#"
+                applied: 2, skipped: 0
+                FileId(0)
+                fn f() {
+                    let a: i32 = 1;
+                    let b: &str = "two";
+                    let c: u8 = 3;
+                }
+            "#]],
+        );
+    }
+
+    #[test]
+    fn applies_assist_in_all_files_of_crate() {
+        check(
+            r#"
+//- /main.rs
+mod foo;
+fn main() {
+    let x = 92;
+}
+//- /foo.rs
+fn g() {
+    let y = 1.0;
+}
+"#,
+            "add_explicit_type",
+            BatchAssistScope::Crate,
+            expect![[r#"
+                applied: 2, skipped: 0
+                FileId(0)
+                mod foo;
+                fn main() {
+                    let x: i32 = 92;
+                }
+                FileId(1)
+                fn g() {
+                    let y: f64 = 1.0;
+                }
+            "#]],
+        );
+    }
+}
@@ -4,7 +4,7 @@ use hir::Semantics;
 use ide_db::{
     defs::{Definition, NameClass, NameRefClass},
     helpers::pick_best_token,
-    search::FileReference,
+    search::{FileReference, UsageSearchResult},
     FxIndexMap, RootDatabase,
 };
 use syntax::{ast, AstNode, SyntaxKind::NAME, TextRange};
@@ -31,18 +31,43 @@ pub(crate) fn call_hierarchy(
     goto_definition::goto_definition(db, position)
 }
 
-pub(crate) fn incoming_calls(
+pub(crate) fn incoming_calls(db: &RootDatabase, position: FilePosition) -> Option<Vec<CallItem>> {
+    let sema = &Semantics::new(db);
+    let mut calls = CallLocations::default();
+    for func in callees(sema, position) {
+        calls.add_callers(sema, func.usages(sema).all());
+    }
+    Some(calls.into_items())
+}
+
+/// Like [`incoming_calls`], but hands out the calls crate by crate as soon as
+/// each crate has been searched, until `sink` returns `true`.
+pub(crate) fn incoming_calls_by_crate(
     db: &RootDatabase,
-    FilePosition { file_id, offset }: FilePosition,
-) -> Option<Vec<CallItem>> {
+    position: FilePosition,
+    sink: &mut dyn FnMut(Vec<CallItem>) -> bool,
+) {
     let sema = &Semantics::new(db);
+    for func in callees(sema, position) {
+        let mut stopped = false;
+        func.usages(sema).all_by_crate(0, &mut |usages| {
+            let mut calls = CallLocations::default();
+            calls.add_callers(sema, usages);
+            stopped = sink(calls.into_items());
+            stopped
+        });
+        if stopped {
+            break;
+        }
+    }
+}
 
+fn callees(
+    sema: &Semantics<RootDatabase>,
+    FilePosition { file_id, offset }: FilePosition,
+) -> Vec<Definition> {
     let file = sema.parse(file_id);
-    let file = file.syntax();
-    let mut calls = CallLocations::default();
-
-    let references = sema
-        .find_nodes_at_offset_with_descend(file, offset)
+    sema.find_nodes_at_offset_with_descend(file.syntax(), offset)
         .filter_map(move |node| match node {
             ast::NameLike::NameRef(name_ref) => match NameRefClass::classify(sema, &name_ref)? {
                 NameRefClass::Definition(def @ Definition::Function(_)) => Some(def),
@@ -54,23 +79,7 @@ pub(crate) fn incoming_calls(
             },
             ast::NameLike::Lifetime(_) => None,
         })
-        .flat_map(|func| func.usages(sema).all());
-
-    for (_, references) in references {
-        let references = references.into_iter().map(|FileReference { name, .. }| name);
-        for name in references {
-            // This target is the containing function
-            let nav = sema.ancestors_with_macros(name.syntax().clone()).find_map(|node| {
-                let def = ast::Fn::cast(node).and_then(|fn_| sema.to_def(&fn_))?;
-                def.try_to_nav(sema.db)
-            });
-            if let Some(nav) = nav {
-                calls.add(nav, sema.original_range(name.syntax()).range);
-            }
-        }
-    }
-
-    Some(calls.into_items())
+        .collect()
 }
 
 pub(crate) fn outgoing_calls(db: &RootDatabase, position: FilePosition) -> Option<Vec<CallItem>> {
@@ -131,6 +140,21 @@ impl CallLocations {
         self.funcs.entry(target).or_default().push(range);
     }
 
+    fn add_callers(&mut self, sema: &Semantics<RootDatabase>, usages: UsageSearchResult) {
+        for (_, references) in usages {
+            for FileReference { name, .. } in references {
+                // This target is the containing function
+                let nav = sema.ancestors_with_macros(name.syntax().clone()).find_map(|node| {
+                    let def = ast::Fn::cast(node).and_then(|fn_| sema.to_def(&fn_))?;
+                    def.try_to_nav(sema.db)
+                });
+                if let Some(nav) = nav {
+                    self.add(nav, sema.original_range(name.syntax()).range);
+                }
+            }
+        }
+    }
+
     fn into_items(self) -> Vec<CallItem> {
         self.funcs.into_iter().map(|(target, ranges)| CallItem { target, ranges }).collect()
     }
@@ -0,0 +1,241 @@
+//! Finds crate-private items which are never referenced, across all the crates
+//! of the workspace.
+//!
+//! This reuses the find-all-references machinery, so it is only as good as our
+//! name resolution is: uses from inside of macros we fail to expand, or from
+//! code which is `cfg`d out, are missed.
+
+use std::time::Duration;
+
+use hir::{AsAssocItem, AssocItem, Crate, HasAttrs, Module, ModuleDef, Semantics, Visibility};
+use ide_db::{
+    base_db::{
+        salsa::{Database, ParallelDatabase, Snapshot},
+        Cancelled, SourceDatabaseExt,
+    },
+    defs::Definition,
+    RootDatabase,
+};
+
+use crate::{navigation_target::TryToNav, NavigationTarget};
+
+#[derive(Debug, Clone)]
+pub struct DeadItem {
+    pub nav: NavigationTarget,
+    /// What kind of item this is, like `function` or `struct`.
+    pub kind: &'static str,
+    /// The name of the crate declaring the item.
+    pub crate_name: String,
+}
+
+pub(crate) fn dead_code(db: &RootDatabase, num_worker_threads: u8) -> Vec<DeadItem> {
+    let _p = profile::span("dead_code");
+    let crates: Vec<Crate> = Crate::all(db)
+        .into_iter()
+        .filter(|krate| {
+            let root_id = db.file_source_root(krate.root_file(db));
+            !db.source_root(root_id).is_library
+        })
+        .collect();
+
+    let (work_sender, result_receiver) = {
+        let (result_sender, result_receiver) = crossbeam_channel::unbounded();
+        let (work_sender, work_receiver) = crossbeam_channel::unbounded::<Crate>();
+        let worker = move |db: Snapshot<RootDatabase>| {
+            while let Ok(krate) = work_receiver.recv() {
+                result_sender.send(dead_code_in_crate(&db, krate))?;
+            }
+            Ok::<_, crossbeam_channel::SendError<_>>(())
+        };
+        for _ in 0..num_worker_threads.max(1) {
+            let worker = worker.clone();
+            let db = db.snapshot();
+            std::thread::spawn(move || Cancelled::catch(|| worker(db)));
+        }
+        (work_sender, result_receiver)
+    };
+    for &krate in &crates {
+        work_sender.send(krate).ok();
+    }
+    drop(work_sender);
+
+    let mut res = Vec::new();
+    let mut crates_done = 0;
+    while crates_done < crates.len() {
+        db.unwind_if_cancelled();
+        match result_receiver.recv_timeout(Duration::from_millis(10)) {
+            Ok(items) => {
+                res.extend(items);
+                crates_done += 1;
+            }
+            Err(crossbeam_channel::RecvTimeoutError::Timeout) => continue,
+            Err(crossbeam_channel::RecvTimeoutError::Disconnected) => {
+                // The workers die if the analysis gets cancelled.
+                db.unwind_if_cancelled();
+                break;
+            }
+        }
+    }
+    res.sort_by(|a, b| {
+        (a.nav.file_id, a.nav.full_range.start()).cmp(&(b.nav.file_id, b.nav.full_range.start()))
+    });
+    res
+}
+
+fn dead_code_in_crate(db: &RootDatabase, krate: Crate) -> Vec<DeadItem> {
+    let _p = profile::span("dead_code_in_crate");
+    let sema = Semantics::new(db);
+    let crate_name = krate.display_name(db).as_deref().unwrap_or("unknown").to_string();
+
+    let mut candidates = Vec::new();
+    let mut worklist = vec![krate.root_module(db)];
+    while let Some(module) = worklist.pop() {
+        worklist.extend(module.children(db));
+        candidates.extend(module.declarations(db).into_iter().filter_map(|def| {
+            let def = match def {
+                ModuleDef::Function(it) => {
+                    if module.is_crate_root(db) && it.name(db).to_smol_str() == "main" {
+                        return None;
+                    }
+                    Definition::Function(it)
+                }
+                ModuleDef::Adt(it) => Definition::Adt(it),
+                ModuleDef::Const(it) => Definition::Const(it),
+                ModuleDef::Static(it) => Definition::Static(it),
+                ModuleDef::Trait(it) => Definition::Trait(it),
+                ModuleDef::TypeAlias(it) => Definition::TypeAlias(it),
+                // Modules are dead if their items are, macros can be used
+                // without us seeing it.
+                ModuleDef::Module(_)
+                | ModuleDef::Macro(_)
+                | ModuleDef::Variant(_)
+                | ModuleDef::BuiltinType(_) => return None,
+            };
+            is_private(db, &def).then(|| def)
+        }));
+        // Items of trait impls are used through the trait.
+        let inherent_impls = module.impl_defs(db).into_iter().filter(|it| it.trait_(db).is_none());
+        for impl_ in inherent_impls {
+            candidates.extend(impl_.items(db).into_iter().filter_map(|item| {
+                let def = match item {
+                    AssocItem::Function(it) => Definition::Function(it),
+                    AssocItem::Const(it) => Definition::Const(it),
+                    AssocItem::TypeAlias(it) => Definition::TypeAlias(it),
+                };
+                is_private(db, &def).then(|| def)
+            }));
+        }
+    }
+
+    candidates
+        .into_iter()
+        .filter(|def| !is_exempt(db, def))
+        .filter_map(|def| {
+            let nav = def.try_to_nav(db)?;
+            let is_used = def.usages(&sema).all().file_ranges().any(|reference| {
+                // Recursion doesn't count as a use.
+                reference.file_id != nav.file_id || !nav.full_range.contains_range(reference.range)
+            });
+            let kind = match def {
+                Definition::Function(it) if it.self_param(db).is_some() => "method",
+                Definition::Function(it) if it.as_assoc_item(db).is_some() => "associated function",
+                Definition::Function(_) => "function",
+                Definition::Adt(hir::Adt::Struct(_)) => "struct",
+                Definition::Adt(hir::Adt::Enum(_)) => "enum",
+                Definition::Adt(hir::Adt::Union(_)) => "union",
+                Definition::Const(_) => "constant",
+                Definition::Static(_) => "static",
+                Definition::Trait(_) => "trait",
+                Definition::TypeAlias(_) => "type alias",
+                _ => "item",
+            };
+            (!is_used).then(|| DeadItem { nav, kind, crate_name: crate_name.clone() })
+        })
+        .collect()
+}
+
+fn is_private(db: &RootDatabase, def: &Definition) -> bool {
+    matches!(def.visibility(db), Some(Visibility::Module(_)))
+}
+
+/// Items which are used by the compiler or the linker rather than by code.
+fn is_exempt(db: &RootDatabase, def: &Definition) -> bool {
+    let name = def.name(db).map(|it| it.to_smol_str());
+    if name.map_or(true, |it| it.starts_with('_')) {
+        return true;
+    }
+    let attrs = match def {
+        Definition::Function(it) => it.attrs(db),
+        Definition::Adt(it) => it.attrs(db),
+        Definition::Const(it) => it.attrs(db),
+        Definition::Static(it) => it.attrs(db),
+        Definition::Trait(it) => it.attrs(db),
+        Definition::TypeAlias(it) => it.attrs(db),
+        _ => return false,
+    };
+    ["test", "bench", "no_mangle", "export_name", "used", "lang", "panic_handler"]
+        .iter()
+        .any(|&key| attrs.by_key(key).exists())
+        || attrs.by_key("allow").tt_values().any(|tt| tt.to_string().contains("dead_code"))
+        || in_test_module(db, def.module(db))
+}
+
+fn in_test_module(db: &RootDatabase, module: Option<Module>) -> bool {
+    module.map_or(false, |module| {
+        module.path_to_root(db).into_iter().any(|it| {
+            it.attrs(db).by_key("cfg").tt_values().any(|tt| tt.to_string().contains("test"))
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{expect, Expect};
+
+    use crate::fixture;
+
+    fn check(ra_fixture: &str, expect: Expect) {
+        let (analysis, _) = fixture::file(ra_fixture);
+        let items = analysis.dead_code(2).unwrap();
+        let actual =
+            items.iter().map(|it| format!("{} {}\n", it.kind, it.nav.name)).collect::<String>();
+        expect.assert_eq(&actual);
+    }
+
+    #[test]
+    fn finds_unused_items() {
+        check(
+            r#"
+fn main() {
+    used();
+    S::new();
+}
+fn used() {}
+fn unused() {}
+fn recursive() { recursive() }
+pub fn exported() {}
+pub(crate) struct S;
+impl S {
+    fn new() -> S { S }
+    fn unused_method(&self) {}
+}
+struct Unused;
+const C: u32 = 0;
+#[no_mangle]
+extern "C" fn linked() {}
+#[allow(dead_code)]
+fn allowed() {}
+fn _ignored() {}
+trait Tr { fn f(&self); }
+impl Tr for S { fn f(&self) {} }
+"#,
+            expect![[r#"
+                function unused
+                function recursive
+                method unused_method
+                struct Unused
+                constant C
+            "#]],
+        );
+    }
+}
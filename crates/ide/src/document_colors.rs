@@ -0,0 +1,532 @@
+//! Finds the colors written in a file, for the editor to show color swatches and a
+//! color picker next to them.
+//!
+//! Recognized are hex color strings like `"#ff8000"` that initialize a `const` or a
+//! `static` (elsewhere, `#`-prefixed strings are too often something else), and for
+//! the types listed in the config, calls of their constructors like
+//! `Color::rgb(1.0, 0.5, 0.0)` and struct literals like
+//! `Color { r: 255, g: 128, b: 0, a: 255 }`. The components must be number literals:
+//! floats in `0.0..=1.0`, or integers in `0..=255`.
+
+use hir::{PathResolution, Semantics};
+use ide_db::{
+    base_db::{FileId, FileRange},
+    RootDatabase,
+};
+use itertools::Itertools;
+use syntax::{
+    ast::{self, AstNode, AstToken},
+    match_ast, NodeOrToken, TextRange, WalkEvent,
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ColorConfig {
+    /// The types whose constructors and struct literals are colors, either as the full path of
+    /// the type (`wgpu::Color`) or just its name (`Color`).
+    pub type_paths: Vec<String>,
+}
+
+/// A color with components in `0.0..=1.0`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Color {
+    pub red: f32,
+    pub green: f32,
+    pub blue: f32,
+    pub alpha: f32,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColorInformation {
+    pub range: TextRange,
+    pub color: Color,
+}
+
+/// The constructors of color types taking the components in order, `alpha` last.
+const CONSTRUCTORS: &[&str] =
+    &["rgb", "rgba", "rgb_u8", "rgba_u8", "from_rgb", "from_rgba", "from_rgb_u8", "from_rgba_u8"];
+
+pub(crate) fn document_colors(
+    db: &RootDatabase,
+    config: &ColorConfig,
+    file_id: FileId,
+) -> Vec<ColorInformation> {
+    let _p = profile::span("document_colors");
+    find_colors(&Semantics::new(db), config, file_id)
+        .into_iter()
+        .map(|it| ColorInformation { range: it.range, color: it.color })
+        .collect()
+}
+
+/// The ways to write `color` in place of the color at `frange`, in the same style.
+pub(crate) fn color_presentations(
+    db: &RootDatabase,
+    config: &ColorConfig,
+    frange: FileRange,
+    color: Color,
+) -> Vec<String> {
+    let sema = Semantics::new(db);
+    find_colors(&sema, config, frange.file_id)
+        .into_iter()
+        .filter(|it| it.range == frange.range)
+        .map(|it| it.syntax.present(color))
+        .collect()
+}
+
+struct FoundColor {
+    range: TextRange,
+    color: Color,
+    syntax: ColorSyntax,
+}
+
+enum ColorSyntax {
+    Hex {
+        uppercase: bool,
+        has_alpha: bool,
+    },
+    Call {
+        /// The callee up to the name of the constructor, like `Color::`.
+        prefix: String,
+        name: String,
+        components: Components,
+    },
+    Record {
+        path: String,
+        field_names: Vec<String>,
+        components: Components,
+    },
+}
+
+struct Components {
+    values: Vec<f32>,
+    /// Whether the components are in `0..=255` rather than `0.0..=1.0`.
+    is_u8: bool,
+}
+
+impl Components {
+    fn color(&self) -> Color {
+        let scale = if self.is_u8 { 255.0 } else { 1.0 };
+        let value = |idx: usize| self.values.get(idx).map_or(1.0, |it| it / scale);
+        Color { red: value(0), green: value(1), blue: value(2), alpha: value(3) }
+    }
+
+    /// The components of `color` in the same format, alpha included if `has_alpha`.
+    fn present(&self, color: Color, has_alpha: bool) -> Vec<String> {
+        let values = [color.red, color.green, color.blue, color.alpha];
+        let len = if has_alpha { 4 } else { 3 };
+        values[..len]
+            .iter()
+            .map(|&it| {
+                if self.is_u8 {
+                    ((it * 255.0).round() as u8).to_string()
+                } else {
+                    format_float(it)
+                }
+            })
+            .collect()
+    }
+}
+
+impl ColorSyntax {
+    fn present(&self, color: Color) -> String {
+        match self {
+            ColorSyntax::Hex { uppercase, has_alpha } => {
+                let mut hex = [color.red, color.green, color.blue]
+                    .iter()
+                    .map(|it| format!("{:02x}", (it * 255.0).round() as u8))
+                    .join("");
+                if *has_alpha || color.alpha < 1.0 {
+                    hex.push_str(&format!("{:02x}", (color.alpha * 255.0).round() as u8));
+                }
+                if *uppercase {
+                    hex.make_ascii_uppercase();
+                }
+                format!("\"#{}\"", hex)
+            }
+            ColorSyntax::Call { prefix, name, components } => {
+                let had_alpha = components.values.len() == 4;
+                // Switch to the constructor taking an alpha if the new color needs it.
+                let name = if !had_alpha && color.alpha < 1.0 {
+                    name.replacen("rgb", "rgba", 1)
+                } else {
+                    name.clone()
+                };
+                let has_alpha = had_alpha || name.contains("rgba");
+                let args = components.present(color, has_alpha).join(", ");
+                format!("{}{}({})", prefix, name, args)
+            }
+            ColorSyntax::Record { path, field_names, components } => {
+                let values = components.present(color, field_names.len() == 4);
+                let fields = field_names
+                    .iter()
+                    .zip(values)
+                    .map(|(name, value)| format!("{}: {}", name, value))
+                    .join(", ");
+                format!("{} {{ {} }}", path, fields)
+            }
+        }
+    }
+}
+
+fn find_colors(
+    sema: &Semantics<RootDatabase>,
+    config: &ColorConfig,
+    file_id: FileId,
+) -> Vec<FoundColor> {
+    let file = sema.parse(file_id);
+    let mut res = Vec::new();
+    for event in file.syntax().preorder_with_tokens() {
+        let element = match event {
+            WalkEvent::Enter(it) => it,
+            WalkEvent::Leave(_) => continue,
+        };
+        let found = match element {
+            NodeOrToken::Token(token) => ast::String::cast(token).and_then(hex_color),
+            NodeOrToken::Node(node) => {
+                if let Some(call) = ast::CallExpr::cast(node.clone()) {
+                    constructor_color(sema, config, &call)
+                } else if let Some(record) = ast::RecordExpr::cast(node) {
+                    record_color(sema, config, &record)
+                } else {
+                    None
+                }
+            }
+        };
+        res.extend(found);
+    }
+    res
+}
+
+fn hex_color(string: ast::String) -> Option<FoundColor> {
+    let literal = ast::Literal::cast(string.syntax().parent()?)?;
+    let initializer = literal.syntax().parent().and_then(|parent| {
+        match_ast! {
+            match parent {
+                ast::Const(it) => it.body(),
+                ast::Static(it) => it.body(),
+                _ => None,
+            }
+        }
+    })?;
+    if initializer.syntax() != literal.syntax() {
+        return None;
+    }
+    let value = string.value()?;
+    let hex = value.strip_prefix('#')?;
+    if !hex.chars().all(|it| it.is_ascii_hexdigit()) {
+        return None;
+    }
+    let digits: Vec<u8> = match hex.len() {
+        // `#rgb` and `#rgba` repeat each digit.
+        3 | 4 => hex.chars().map(|it| it.to_digit(16).unwrap() as u8 * 0x11).collect(),
+        6 | 8 => (0..hex.len())
+            .step_by(2)
+            .map(|idx| u8::from_str_radix(&hex[idx..idx + 2], 16).unwrap())
+            .collect(),
+        _ => return None,
+    };
+    let components =
+        Components { values: digits.iter().map(|&it| it as f32).collect(), is_u8: true };
+    Some(FoundColor {
+        range: string.syntax().text_range(),
+        color: components.color(),
+        syntax: ColorSyntax::Hex {
+            uppercase: hex.chars().any(|it| it.is_ascii_uppercase()),
+            has_alpha: digits.len() == 4,
+        },
+    })
+}
+
+fn constructor_color(
+    sema: &Semantics<RootDatabase>,
+    config: &ColorConfig,
+    call: &ast::CallExpr,
+) -> Option<FoundColor> {
+    let path = match call.expr()? {
+        ast::Expr::PathExpr(it) => it.path()?,
+        _ => return None,
+    };
+    let name = path.segment()?.name_ref()?.to_string();
+    if !CONSTRUCTORS.contains(&name.as_str()) {
+        return None;
+    }
+    let qualifier = path.qualifier()?;
+    if !is_color_type(sema, config, &qualifier) {
+        return None;
+    }
+    let args = call.arg_list()?.args().collect::<Vec<_>>();
+    let takes_alpha = name.contains("rgba");
+    if args.len() != if takes_alpha { 4 } else { 3 } {
+        return None;
+    }
+    let components = components(&args, name.ends_with("_u8"))?;
+    Some(FoundColor {
+        range: call.syntax().text_range(),
+        color: components.color(),
+        syntax: ColorSyntax::Call { prefix: format!("{}::", qualifier), name, components },
+    })
+}
+
+fn record_color(
+    sema: &Semantics<RootDatabase>,
+    config: &ColorConfig,
+    record: &ast::RecordExpr,
+) -> Option<FoundColor> {
+    let path = record.path()?;
+    if !is_color_type(sema, config, &path) {
+        return None;
+    }
+    let field_list = record.record_expr_field_list()?;
+    if field_list.spread().is_some() {
+        return None;
+    }
+    let mut fields = Vec::new();
+    for field in field_list.fields() {
+        let name = field.name_ref()?.to_string();
+        let idx = match name.as_str() {
+            "r" | "red" => 0,
+            "g" | "green" => 1,
+            "b" | "blue" => 2,
+            "a" | "alpha" => 3,
+            _ => return None,
+        };
+        fields.push((idx, name, field.expr()?));
+    }
+    // The fields are written in any order, but the components are kept in `rgba` order.
+    fields.sort_by_key(|(idx, ..)| *idx);
+    if !fields.iter().map(|(idx, ..)| *idx).eq(0..fields.len()) || fields.len() < 3 {
+        return None;
+    }
+    let exprs = fields.iter().map(|(_, _, expr)| expr.clone()).collect::<Vec<_>>();
+    let components = components(&exprs, false)?;
+    Some(FoundColor {
+        range: record.syntax().text_range(),
+        color: components.color(),
+        syntax: ColorSyntax::Record {
+            path: path.to_string(),
+            field_names: fields.into_iter().map(|(_, name, _)| name).collect(),
+            components,
+        },
+    })
+}
+
+/// The values of the number literals `exprs`, which are in `0..=255` if they're all integers
+/// or if `is_u8`, and in `0.0..=1.0` otherwise.
+fn components(exprs: &[ast::Expr], is_u8: bool) -> Option<Components> {
+    let mut all_ints = true;
+    let mut values = Vec::new();
+    for expr in exprs {
+        let literal = match expr {
+            ast::Expr::Literal(it) => it,
+            _ => return None,
+        };
+        let value = match literal.kind() {
+            ast::LiteralKind::IntNumber(it) => it.value()? as f32,
+            ast::LiteralKind::FloatNumber(it) => {
+                all_ints = false;
+                let text = it.text();
+                let text = text.strip_suffix(it.suffix().unwrap_or_default()).unwrap_or(text);
+                text.replace('_', "").parse::<f32>().ok()?
+            }
+            _ => return None,
+        };
+        values.push(value);
+    }
+    let is_u8 = is_u8 || all_ints;
+    let max = if is_u8 { 255.0 } else { 1.0 };
+    if values.iter().any(|it| !(0.0..=max).contains(it)) {
+        return None;
+    }
+    Some(Components { values, is_u8 })
+}
+
+fn is_color_type(sema: &Semantics<RootDatabase>, config: &ColorConfig, path: &ast::Path) -> bool {
+    let db = sema.db;
+    let adt = match sema.resolve_path(path) {
+        Some(PathResolution::Def(hir::ModuleDef::Adt(it))) => it,
+        Some(PathResolution::Def(hir::ModuleDef::TypeAlias(it))) => match it.ty(db).as_adt() {
+            Some(it) => it,
+            None => return false,
+        },
+        Some(PathResolution::SelfType(it)) => match it.self_ty(db).as_adt() {
+            Some(it) => it,
+            None => return false,
+        },
+        _ => return false,
+    };
+    let name = adt.name(db).to_string();
+    let def = hir::ModuleDef::Adt(adt);
+    let full_path = match (adt.module(db).krate().display_name(db), def.canonical_path(db)) {
+        (Some(krate), Some(path)) => format!("{}::{}", krate.crate_name(), path),
+        _ => name.clone(),
+    };
+    config
+        .type_paths
+        .iter()
+        .any(|it| if it.contains("::") { *it == full_path } else { *it == name })
+}
+
+/// `value` with at most 3 decimals, but at least one.
+fn format_float(value: f32) -> String {
+    let text = format!("{:.3}", value);
+    let text = text.trim_end_matches('0');
+    if text.ends_with('.') {
+        format!("{}0", text)
+    } else {
+        text.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{expect, Expect};
+
+    use crate::fixture;
+
+    use super::*;
+
+    fn config() -> ColorConfig {
+        ColorConfig { type_paths: vec!["Color".to_string(), "gfx::Rgba".to_string()] }
+    }
+
+    fn check(ra_fixture: &str, expect: Expect) {
+        let (analysis, file_id) = fixture::file(ra_fixture);
+        let text = analysis.file_text(file_id).unwrap();
+        let colors = analysis.document_colors(&config(), file_id).unwrap();
+        let actual = colors
+            .iter()
+            .map(|it| {
+                let Color { red, green, blue, alpha } = it.color;
+                format!("{} => {:.3} {:.3} {:.3} {:.3}\n", &text[it.range], red, green, blue, alpha)
+            })
+            .collect::<String>();
+        expect.assert_eq(&actual);
+    }
+
+    fn check_presentation(ra_fixture: &str, color: Color, expect: Expect) {
+        let (analysis, frange) = fixture::range(ra_fixture);
+        let presentations = analysis.color_presentations(&config(), frange, color).unwrap();
+        expect.assert_debug_eq(&presentations);
+    }
+
+    const ORANGE: Color = Color { red: 1.0, green: 0.5, blue: 0.0, alpha: 1.0 };
+
+    #[test]
+    fn finds_colors() {
+        check(
+            r##"
+//- /main.rs crate:main deps:gfx
+struct Color { r: f32, g: f32, b: f32, a: f32 }
+impl Color {
+    fn rgb(r: f32, g: f32, b: f32) -> Color { loop {} }
+    fn rgba_u8(r: u8, g: u8, b: u8, a: u8) -> Color { loop {} }
+    fn white() -> Color { Self::rgb(1.0, 1.0, 1.0) }
+}
+struct Other { r: u8, g: u8, b: u8 }
+const ACCENT: &str = "#FF8000";
+static HIGHLIGHT: &str = "#0f08";
+fn f() {
+    let _ = Color::rgb(0.5, 0.2, 0.1);
+    let _ = Color::rgba_u8(255, 128, 0, 0x80);
+    let _ = Color { a: 0.5, r: 1.0, g: 0.0, b: 0.0 };
+    let _ = gfx::Rgba { red: 0, green: 255, blue: 0 };
+    let _ = Other { r: 1, g: 2, b: 3 };
+    let _ = Color::rgb(2.0, 0.0, 0.0);
+}
+//- /gfx.rs crate:gfx
+pub struct Rgba { pub red: u8, pub green: u8, pub blue: u8 }
+"##,
+            expect![[r##"
+                Self::rgb(1.0, 1.0, 1.0) => 1.000 1.000 1.000 1.000
+                "#FF8000" => 1.000 0.502 0.000 1.000
+                "#0f08" => 0.000 1.000 0.000 0.533
+                Color::rgb(0.5, 0.2, 0.1) => 0.500 0.200 0.100 1.000
+                Color::rgba_u8(255, 128, 0, 0x80) => 1.000 0.502 0.000 0.502
+                Color { a: 0.5, r: 1.0, g: 0.0, b: 0.0 } => 1.000 0.000 0.000 0.500
+                gfx::Rgba { red: 0, green: 255, blue: 0 } => 0.000 1.000 0.000 1.000
+            "##]],
+        );
+    }
+
+    #[test]
+    fn hex_colors_only_in_const_and_static_initializers() {
+        check(
+            r##"
+const INVALID: &str = "#12345";
+const NESTED: (&str, &str) = ("#fff", "#000");
+static TAG: &str = concat!("#fff");
+fn issue(_: &str) {}
+fn f() {
+    let _ = "#fff";
+    issue("#123");
+    const LOCAL: &str = "#abc";
+}
+"##,
+            expect![[r##"
+                "#abc" => 0.667 0.733 0.800 1.000
+            "##]],
+        );
+    }
+
+    #[test]
+    fn presents_in_the_same_style() {
+        check_presentation(
+            r#"
+struct Color;
+impl Color {
+    fn rgb(r: f32, g: f32, b: f32) -> Color { loop {} }
+}
+fn f() {
+    let _ = $0Color::rgb(0.5, 0.2, 0.1)$0;
+}
+"#,
+            ORANGE,
+            expect![[r#"
+                [
+                    "Color::rgb(1.0, 0.5, 0.0)",
+                ]
+            "#]],
+        );
+        check_presentation(
+            r#"
+struct Color;
+impl Color {
+    fn rgb_u8(r: u8, g: u8, b: u8) -> Color { loop {} }
+}
+fn f() {
+    let _ = $0Color::rgb_u8(0, 0, 0)$0;
+}
+"#,
+            Color { alpha: 0.5, ..ORANGE },
+            expect![[r#"
+                [
+                    "Color::rgba_u8(255, 128, 0, 128)",
+                ]
+            "#]],
+        );
+        check_presentation(
+            r#"
+struct Color { r: u8, g: u8, b: u8 }
+fn f() {
+    let _ = $0Color { b: 0, g: 0, r: 0 }$0;
+}
+"#,
+            ORANGE,
+            expect![[r#"
+                [
+                    "Color { r: 255, g: 128, b: 0 }",
+                ]
+            "#]],
+        );
+        check_presentation(
+            r##"
+const ACCENT: &str = $0"#FFFFFF"$0;
+"##,
+            Color { alpha: 0.0, ..ORANGE },
+            expect![[r##"
+                [
+                    "\"#FF800000\"",
+                ]
+            "##]],
+        );
+    }
+}
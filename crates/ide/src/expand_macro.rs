@@ -1,9 +1,15 @@
-use hir::Semantics;
+use hir::{InFile, Semantics};
 use ide_db::{
-    helpers::pick_best_token, syntax_helpers::insert_whitespace_into_node::insert_ws_into,
+    base_db::{FileId, FileRange},
+    helpers::pick_best_token,
+    syntax_helpers::insert_whitespace_into_node::insert_ws_into,
     RootDatabase,
 };
-use syntax::{ast, ted, AstNode, NodeOrToken, SyntaxKind, SyntaxNode, T};
+use rustc_hash::FxHashMap;
+use syntax::{
+    ast, ted, AstNode, NodeOrToken, SyntaxKind, SyntaxNode, SyntaxToken, TextRange, TextSize,
+    WalkEvent, T,
+};
 
 use crate::FilePosition;
 
@@ -48,9 +54,9 @@ pub(crate) fn expand_macro(db: &RootDatabase, position: FilePosition) -> Option<
 
         let name = descended.ancestors().filter_map(ast::Path::cast).last()?.to_string();
         // up map out of the #[derive] expansion
-        let token = hir::InFile::new(hir_file, descended).upmap(db)?.value;
+        let token = InFile::new(hir_file, descended).upmap(db)?.value;
         let attr = token.ancestors().find_map(ast::Attr::cast)?;
-        let expansions = sema.expand_derive_macro(&attr)?;
+        let expansions = sema.derive_macro_expansions(&attr)?;
         let idx = attr
             .token_tree()?
             .token_trees_and_tokens()
@@ -60,7 +66,7 @@ pub(crate) fn expand_macro(db: &RootDatabase, position: FilePosition) -> Option<
             .count();
         Some(ExpandedMacro {
             name,
-            expansion: expansions.get(idx).cloned().map(insert_ws_into)?.to_string(),
+            expansion: expansions.get(idx).cloned().flatten().map(insert_ws_into)?.to_string(),
         })
     });
 
@@ -130,11 +136,218 @@ fn expand<T: AstNode>(
     Some(expanded)
 }
 
+/// The recursive expansion of a macro, rendered as a document of its own.
+#[derive(Debug)]
+pub struct ExpansionDocument {
+    pub name: String,
+    pub text: String,
+    /// Maps ranges of `text` back to the source tokens they were expanded from.
+    pub ranges: Vec<(TextRange, FileRange)>,
+}
+
+/// Identifies a macro expansion in a file by the path of its macro and the number of expansions of
+/// macros with the same path before it. Unlike an offset or the position among all the macros of
+/// the file, this only changes when a macro with the same path is added or removed before it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExpansionId {
+    /// The path of a macro call, or the name of an attribute or derive macro.
+    pub path: String,
+    pub occurrence: usize,
+}
+
+/// A place in a file where a macro gets expanded.
+enum MacroSite {
+    Call(ast::MacroCall),
+    Attr(ast::Item),
+    /// The derive at the given index of a `#[derive]` attribute.
+    Derive(ast::Attr, usize),
+}
+
+// Feature: Macro Expansion Documents
+//
+// Opens the recursive expansion of the macro at the cursor as a read-only document which follows
+// the edits made to the source. Going to the definition of or hovering anything in the expansion
+// acts on the source it was expanded from.
+//
+// |===
+// | Editor  | Action Name
+//
+// | VS Code | **Rust Analyzer: Expand macro recursively**
+// |===
+pub(crate) fn expansion_id(db: &RootDatabase, position: FilePosition) -> Option<ExpansionId> {
+    let sema = Semantics::new(db);
+    let file = sema.parse(position.file_id);
+    let sites = macro_sites(&sema, &file);
+
+    let tok = pick_best_token(file.syntax().token_at_offset(position.offset), |kind| match kind {
+        SyntaxKind::IDENT => 1,
+        _ => 0,
+    })?;
+    let idx = tok.ancestors().find_map(|node| {
+        sites.iter().position(|(_, site)| match site {
+            MacroSite::Call(call) => call.syntax() == &node,
+            MacroSite::Attr(item) => item.syntax() == &node,
+            MacroSite::Derive(attr, idx) => {
+                attr.syntax() == &node && derive_index(attr, &tok) == Some(*idx)
+            }
+        })
+    })?;
+    let path = sites[idx].0.clone();
+    let occurrence = sites[..idx].iter().filter(|(it, _)| *it == path).count();
+    Some(ExpansionId { path, occurrence })
+}
+
+pub(crate) fn expansion_document(
+    db: &RootDatabase,
+    file_id: FileId,
+    id: &ExpansionId,
+) -> Option<ExpansionDocument> {
+    let sema = Semantics::new(db);
+    let file = sema.parse(file_id);
+    let site = macro_sites(&sema, &file)
+        .into_iter()
+        .filter(|(path, _)| *path == id.path)
+        .nth(id.occurrence)?
+        .1;
+    let (name, expansion) = match site {
+        MacroSite::Call(call) => {
+            (call.path()?.segment()?.name_ref()?.to_string(), sema.expand(&call)?)
+        }
+        MacroSite::Attr(item) => (
+            sema.resolve_attr_macro_call(&item)?.name(db).to_string(),
+            sema.expand_attr_macro(&item)?,
+        ),
+        MacroSite::Derive(attr, idx) => (
+            sema.resolve_derive_macro(&attr)?.get(idx).cloned().flatten()?.name(db).to_string(),
+            sema.derive_macro_expansions(&attr)?.get(idx).cloned().flatten()?,
+        ),
+    };
+
+    let mut origins = FxHashMap::default();
+    let expanded = expand_with_origins(&sema, &expansion, &mut origins);
+    let formatted = insert_ws_into(expanded.clone());
+
+    let header = format!("// Recursive expansion of `{}`\n\n", name);
+    let offset = TextSize::of(&header);
+    // Formatting only adds whitespace, so the other tokens still line up.
+    let tokens = |node: &SyntaxNode| {
+        node.descendants_with_tokens()
+            .filter_map(NodeOrToken::into_token)
+            .filter(|it| it.kind() != SyntaxKind::WHITESPACE)
+            .collect::<Vec<_>>()
+    };
+    let ranges = tokens(&expanded)
+        .into_iter()
+        .zip(tokens(&formatted))
+        .filter_map(|(token, formatted)| {
+            Some((formatted.text_range() + offset, *origins.get(&token)?))
+        })
+        .collect();
+    Some(ExpansionDocument { name, text: format!("{}{}", header, formatted), ranges })
+}
+
+/// The places in `file` where macros get expanded with the paths of their macros, in source order.
+fn macro_sites(sema: &Semantics<RootDatabase>, file: &ast::SourceFile) -> Vec<(String, MacroSite)> {
+    let mut sites = Vec::new();
+    for node in file.syntax().descendants() {
+        if let Some(call) = ast::MacroCall::cast(node.clone()) {
+            if let Some(path) = call.path() {
+                sites.push((path.syntax().text().to_string(), MacroSite::Call(call)));
+            }
+        } else if let Some(item) = ast::Item::cast(node.clone()) {
+            if let Some(mac) = sema.resolve_attr_macro_call(&item) {
+                sites.push((mac.name(sema.db).to_string(), MacroSite::Attr(item)));
+            }
+        } else if let Some(attr) = ast::Attr::cast(node) {
+            let derives = sema.resolve_derive_macro(&attr).unwrap_or_default();
+            sites.extend(derives.into_iter().enumerate().filter_map(|(idx, mac)| {
+                Some((mac?.name(sema.db).to_string(), MacroSite::Derive(attr.clone(), idx)))
+            }));
+        }
+    }
+    sites
+}
+
+/// The index of the derive `token` is part of in the `#[derive]` attribute `attr`.
+fn derive_index(attr: &ast::Attr, token: &SyntaxToken) -> Option<usize> {
+    let token_tree = attr.token_tree()?;
+    if !token_tree.syntax().text_range().contains_range(token.text_range()) {
+        return None;
+    }
+    let idx = token_tree
+        .token_trees_and_tokens()
+        .filter_map(NodeOrToken::into_token)
+        .take_while(|it| it.text_range().end() <= token.text_range().start())
+        .filter(|it| it.kind() == T![,])
+        .count();
+    Some(idx)
+}
+
+/// Clones `expansion` with the macros in it replaced by their own expansions, recording the
+/// source token each token of the clone was expanded from in `origins`.
+fn expand_with_origins(
+    sema: &Semantics<RootDatabase>,
+    expansion: &SyntaxNode,
+    origins: &mut FxHashMap<SyntaxToken, FileRange>,
+) -> SyntaxNode {
+    let expanded = expansion.clone_for_update();
+    let mut nested = Vec::new();
+    let mut original = expansion.preorder_with_tokens();
+    let mut cloned = expanded.preorder_with_tokens();
+    while let (Some(event), Some(cloned_event)) = (original.next(), cloned.next()) {
+        let (node, cloned_node) = match (event, cloned_event) {
+            (WalkEvent::Enter(NodeOrToken::Node(it)), WalkEvent::Enter(NodeOrToken::Node(cl))) => {
+                (it, cl)
+            }
+            (
+                WalkEvent::Enter(NodeOrToken::Token(it)),
+                WalkEvent::Enter(NodeOrToken::Token(cl)),
+            ) => {
+                if let Some(range) = original_token_range(sema, &it) {
+                    origins.insert(cl, range);
+                }
+                continue;
+            }
+            _ => continue,
+        };
+        let inner = if let Some(call) = ast::MacroCall::cast(node.clone()) {
+            sema.expand(&call)
+        } else {
+            ast::Item::cast(node).and_then(|item| sema.expand_attr_macro(&item))
+        };
+        if let Some(inner) = inner {
+            original.skip_subtree();
+            cloned.skip_subtree();
+            nested.push((cloned_node, expand_with_origins(sema, &inner, origins)));
+        }
+    }
+
+    // The expansion might consist of nothing but another macro call.
+    if let Some(idx) = nested.iter().position(|(node, _)| node == &expanded) {
+        return nested.swap_remove(idx).1;
+    }
+    for (node, inner) in nested {
+        ted::replace(node, inner);
+    }
+    expanded
+}
+
+/// The range of the token of a file on disk `token` was expanded from, if any.
+fn original_token_range(sema: &Semantics<RootDatabase>, token: &SyntaxToken) -> Option<FileRange> {
+    let db = sema.db;
+    let mut token = InFile::new(sema.hir_file_for(&token.parent()?), token.clone());
+    while token.file_id.is_macro() {
+        token = token.upmap(db)?;
+    }
+    Some(FileRange { file_id: token.file_id.original_file(db), range: token.value.text_range() })
+}
+
 #[cfg(test)]
 mod tests {
     use expect_test::{expect, Expect};
+    use stdx::format_to;
 
-    use crate::fixture;
+    use crate::{fixture, ExpansionId};
 
     #[track_caller]
     fn check(ra_fixture: &str, expect: Expect) {
@@ -144,6 +357,26 @@ mod tests {
         expect.assert_eq(&actual);
     }
 
+    #[track_caller]
+    fn check_document(ra_fixture: &str, expect: Expect) {
+        let (analysis, pos) = fixture::position(ra_fixture);
+        let id = analysis.expansion_id(pos).unwrap().unwrap();
+        let document = analysis.expansion_document(pos.file_id, &id).unwrap().unwrap();
+        let source = analysis.file_text(pos.file_id).unwrap();
+        let mut actual = format!("{} #{}\n{}\n---", id.path, id.occurrence, document.text);
+        for (range, origin) in document.ranges {
+            let line = source[..usize::from(origin.range.start())].matches('\n').count();
+            format_to!(
+                actual,
+                "\n{} <- {} (line {})",
+                &document.text[range],
+                &source[origin.range],
+                line
+            );
+        }
+        expect.assert_eq(&actual);
+    }
+
     #[test]
     fn macro_expand_as_keyword() {
         check(
@@ -398,4 +631,71 @@ struct Foo {}
             "#]],
         );
     }
+
+    #[test]
+    fn expansion_document_maps_tokens_to_source() {
+        check_document(
+            r#"
+macro_rules! double {
+    ($e:expr) => { $e + $e }
+}
+macro_rules! wrap {
+    ($e:expr) => { double!($e) }
+}
+fn f(x: i32) -> i32 {
+    wrap!(x$0)
+}
+"#,
+            expect![[r#"
+                wrap #0
+                // Recursive expansion of `wrap`
+
+                x+x
+                ---
+                x <- x (line 7)
+                + <- + (line 1)
+                x <- x (line 7)"#]],
+        );
+    }
+
+    #[test]
+    fn expansion_id_counts_derives_separately() {
+        let (analysis, pos) = fixture::position(
+            r#"
+//- minicore: copy, clone, derive
+macro_rules! unit { () => { () } }
+fn f() {
+    unit!();
+}
+#[derive(Clone)]
+struct Bar;
+#[derive(Copy, Cl$0one)]
+struct Foo;
+"#,
+        );
+        let id = analysis.expansion_id(pos).unwrap().unwrap();
+        assert_eq!(id, ExpansionId { path: "Clone".to_string(), occurrence: 1 });
+        let document = analysis.expansion_document(pos.file_id, &id).unwrap().unwrap();
+        assert_eq!(document.name, "Clone");
+    }
+
+    #[test]
+    fn expansion_id_survives_other_macros_being_added() {
+        let before = r#"
+macro_rules! unit { () => { () } }
+macro_rules! one { () => { 1 } }
+fn f() {
+    unit!();
+    $0one!();
+}
+"#;
+        let (analysis, pos) = fixture::position(before);
+        let id = analysis.expansion_id(pos).unwrap().unwrap();
+        assert_eq!(id, ExpansionId { path: "one".to_string(), occurrence: 0 });
+
+        let (analysis, file_id) =
+            fixture::file(&before.replace("$0", "").replace("unit!();", "unit!();\n    unit!();"));
+        let document = analysis.expansion_document(file_id, &id).unwrap().unwrap();
+        assert_eq!(document.name, "one");
+    }
 }
@@ -0,0 +1,266 @@
+use either::Either;
+use hir::{HasSource, HirDisplay, Impl, ModuleDef, PathResolution, Semantics, Trait, Type};
+use ide_db::{base_db::FileRange, RootDatabase};
+use rustc_hash::FxHashSet;
+use syntax::{ast, AstNode};
+
+/// How deep the explanation follows the components of a type.
+const MAX_DEPTH: usize = 8;
+
+/// Why a type does or doesn't implement a trait.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImplExplanation {
+    /// The goal being explained, like `Rc<u8>: Send`.
+    pub goal: String,
+    pub holds: bool,
+    pub reason: String,
+    /// The goals this one depends on, like the fields of a struct for an auto
+    /// trait. Only the ones which explain the outcome are expanded.
+    pub children: Vec<ImplExplanation>,
+}
+
+impl ImplExplanation {
+    /// Renders the explanation as an indented tree, one goal per line.
+    pub fn render(&self) -> String {
+        let mut buf = String::new();
+        self.render_to(&mut buf, 0);
+        buf
+    }
+
+    fn render_to(&self, buf: &mut String, depth: usize) {
+        let mark = if self.holds { "✓" } else { "✗" };
+        buf.push_str(&"    ".repeat(depth));
+        buf.push_str(&format!("{} {}", mark, self.goal));
+        if !self.reason.is_empty() {
+            buf.push_str(&format!(": {}", self.reason));
+        }
+        buf.push('\n');
+        for child in &self.children {
+            child.render_to(buf, depth + 1);
+        }
+    }
+}
+
+// Feature: Explain Trait Implementation
+//
+// Explains whether the type of the selected expression, pattern or type implements a trait, and
+// why. For auto traits like `Send`, the explanation follows the fields which are missing the
+// trait down to the type which causes it, like `Rc`.
+//
+// The trait solver is asked about every step, so each line agrees with what the rest of
+// rust-analyzer thinks.
+//
+// |===
+// | Editor  | Action Name
+//
+// | VS Code | **Rust Analyzer: Explain Trait Implementation**
+// |===
+pub(crate) fn explain_impl(
+    db: &RootDatabase,
+    FileRange { file_id, range }: FileRange,
+    trait_path: &str,
+) -> Result<ImplExplanation, String> {
+    let sema = Semantics::new(db);
+    let file = sema.parse(file_id);
+    let element = file
+        .syntax()
+        .covering_element(range.intersect(file.syntax().text_range()).unwrap_or(range));
+    let node = element.ancestors().find_map(|node| {
+        ast::Type::cast(node.clone())
+            .map(Either::Left)
+            .or_else(|| ast::Expr::cast(node.clone()).map(|it| Either::Right(Either::Left(it))))
+            .or_else(|| ast::Pat::cast(node).map(|it| Either::Right(Either::Right(it))))
+    });
+    let ty = match &node {
+        Some(Either::Left(ty)) => sema.resolve_type(ty),
+        Some(Either::Right(Either::Left(expr))) => sema.type_of_expr(expr).map(|it| it.original),
+        Some(Either::Right(Either::Right(pat))) => sema.type_of_pat(pat).map(|it| it.original),
+        None => None,
+    };
+    let ty = ty.ok_or_else(|| "No type, expression or pattern is selected".to_string())?;
+
+    let trait_ = {
+        let scope_node = element.ancestors().next().unwrap_or_else(|| file.syntax().clone());
+        let scope = sema.scope(&scope_node);
+        let path = ast::make::path_from_text(trait_path.trim());
+        match scope.speculative_resolve(&path) {
+            Some(PathResolution::Def(ModuleDef::Trait(it))) => it,
+            _ => return Err(format!("`{}` is not a trait in scope", trait_path.trim())),
+        }
+    };
+    if trait_.type_or_const_param_count(db) > 0 {
+        return Err(format!("`{}` has generic parameters, which are not supported", trait_path));
+    }
+
+    let mut visiting = FxHashSet::default();
+    Ok(explain(db, &ty, trait_, 0, &mut visiting))
+}
+
+fn explain(
+    db: &RootDatabase,
+    ty: &Type,
+    trait_: Trait,
+    depth: usize,
+    visiting: &mut FxHashSet<String>,
+) -> ImplExplanation {
+    let goal = format!("{}: {}", ty.display(db), trait_.name(db));
+    let holds = ty.impls_trait(db, trait_, &[]);
+    let mut res = ImplExplanation { goal, holds, reason: String::new(), children: Vec::new() };
+
+    if ty.is_unknown() {
+        res.reason = "the type is unknown".to_string();
+        return res;
+    }
+
+    let impls: Vec<Impl> = Impl::all_for_trait(db, trait_)
+        .into_iter()
+        .filter(|imp| imp.self_ty(db).equals_ctor(ty))
+        .collect();
+    if let Some(negative) = impls.iter().find(|imp| imp.is_negative(db)) {
+        res.reason = format!("opted out by `{}`", impl_header(db, *negative));
+        return res;
+    }
+    if let Some(imp) = impls.first() {
+        res.reason = if holds {
+            format!("implemented by `{}`", impl_header(db, *imp))
+        } else {
+            format!("the bounds of `{}` are not met", impl_header(db, *imp))
+        };
+        return res;
+    }
+
+    if !trait_.is_auto(db) {
+        res.reason = if holds {
+            "implemented by a blanket impl, or assumed by a where clause".to_string()
+        } else {
+            "no impl applies".to_string()
+        };
+        return res;
+    }
+
+    let components = ty.auto_trait_components(db);
+    if components.is_empty() {
+        res.reason =
+            if holds { "implemented by the compiler" } else { "not implemented" }.to_string();
+        return res;
+    }
+    res.reason = if holds {
+        "all of its components implement it".to_string()
+    } else {
+        "some of its components don't implement it".to_string()
+    };
+
+    // Only follow the components which don't implement the trait, those are the ones to fix.
+    // The components of the type at the root are all listed, to show what was checked.
+    if depth >= MAX_DEPTH || !visiting.insert(res.goal.clone()) {
+        return res;
+    }
+    for (label, component) in components {
+        let component_holds = component.impls_trait(db, trait_, &[]);
+        if component_holds && depth > 0 {
+            continue;
+        }
+        let mut child = if component_holds {
+            ImplExplanation {
+                goal: format!("{}: {}", component.display(db), trait_.name(db)),
+                holds: true,
+                reason: String::new(),
+                children: Vec::new(),
+            }
+        } else {
+            explain(db, &component, trait_, depth + 1, visiting)
+        };
+        child.goal = format!("{} ({})", child.goal, label);
+        res.children.push(child);
+    }
+    visiting.remove(&res.goal);
+    res
+}
+
+/// The source of an impl up to its items, with whitespace collapsed.
+fn impl_header(db: &RootDatabase, imp: Impl) -> String {
+    let src = match imp.source(db) {
+        Some(it) => it.value,
+        None => return format!("impl for {}", imp.self_ty(db).display(db)),
+    };
+    let text = src.syntax().text().to_string();
+    let header = match src.assoc_item_list() {
+        Some(items) => {
+            let len = items.syntax().text_range().start() - src.syntax().text_range().start();
+            text[..usize::from(len)].to_string()
+        }
+        None => text,
+    };
+    header.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{expect, Expect};
+
+    use crate::fixture;
+
+    fn check(trait_path: &str, ra_fixture: &str, expect: Expect) {
+        let (analysis, range) = fixture::range(ra_fixture);
+        let actual = match analysis.explain_impl(range, trait_path).unwrap() {
+            Ok(it) => it.render(),
+            Err(err) => err,
+        };
+        expect.assert_eq(&actual);
+    }
+
+    #[test]
+    fn field_is_not_send() {
+        check(
+            "Send",
+            r#"
+//- minicore: sized
+pub unsafe auto trait Send {}
+pub struct Rc<T>(*const T);
+impl<T> !Send for Rc<T> {}
+impl<T> !Send for *const T {}
+
+struct Inner { rc: Rc<u8>, n: u32 }
+struct Outer { inner: Inner, flag: bool }
+fn f(o: Outer) {
+    $0o$0;
+}
+"#,
+            expect![[r#"
+                ✗ Outer: Send: some of its components don't implement it
+                    ✗ Inner: Send (field `inner`): some of its components don't implement it
+                        ✗ Rc<u8>: Send (field `rc`): opted out by `impl<T> !Send for Rc<T>`
+                    ✓ bool: Send (field `flag`)
+            "#]],
+        );
+    }
+
+    #[test]
+    fn trait_with_impl() {
+        check(
+            "Tr",
+            r#"
+trait Tr {}
+struct S;
+impl Tr for S {}
+fn f(s: $0S$0) {}
+"#,
+            expect![[r#"
+                ✓ S: Tr: implemented by `impl Tr for S`
+            "#]],
+        );
+    }
+
+    #[test]
+    fn not_a_trait() {
+        check(
+            "Nope",
+            r#"
+fn f() {
+    $01$0;
+}
+"#,
+            expect!["`Nope` is not a trait in scope"],
+        );
+    }
+}
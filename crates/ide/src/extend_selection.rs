@@ -140,50 +140,65 @@ fn extend_tokens_from_range(
         last_token = skip_trivia_token(last_token.prev_token()?, Direction::Prev)?;
     }
 
-    // compute original mapped token range
-    let extended = {
-        let fst_expanded = sema.descend_into_macros_single(first_token.clone());
-        let lst_expanded = sema.descend_into_macros_single(last_token.clone());
-        let mut lca =
-            algo::least_common_ancestor(&fst_expanded.parent()?, &lst_expanded.parent()?)?;
-        lca = shallowest_node(&lca);
-        if lca.first_token() == Some(fst_expanded) && lca.last_token() == Some(lst_expanded) {
-            lca = lca.parent()?;
+    let fst_expanded = sema.descend_into_macros_single(first_token.clone());
+    let lst_expanded = sema.descend_into_macros_single(last_token.clone());
+    if fst_expanded == first_token || lst_expanded == last_token {
+        // The selection doesn't start or end in the expansion.
+        return None;
+    }
+    let mut extended =
+        algo::least_common_ancestor(&fst_expanded.parent()?, &lst_expanded.parent()?)?;
+    extended = shallowest_node(&extended);
+
+    // Walk up the expansion until a node covers more of the macro input than
+    // what's already selected. Nodes introduced by the macro itself, like the
+    // `[` and `]` around the elements of a `vec!`, don't contribute anything.
+    let tt_range = macro_call.token_tree()?.syntax().text_range();
+    loop {
+        let first = extend_mapped_tokens(sema, tt_range, &extended, &first_token, Direction::Prev);
+        let last = extend_mapped_tokens(sema, tt_range, &extended, &last_token, Direction::Next);
+        let range = first.text_range().cover(last.text_range());
+        if range.contains_range(original_range) && original_range != range {
+            return Some(range);
         }
-        lca
-    };
+        extended = extended.parent()?;
+    }
+}
 
-    // Compute parent node range
-    let validate = |token: &SyntaxToken| -> bool {
+/// Extends from `token` in `dir` over all the tokens of the macro input which
+/// are mapped into `node`, returning the outermost one. Tokens which don't make
+/// it into the expansion at all, like the separators a macro matches on, are
+/// skipped over.
+fn extend_mapped_tokens(
+    sema: &Semantics<RootDatabase>,
+    tt_range: TextRange,
+    node: &SyntaxNode,
+    token: &SyntaxToken,
+    dir: Direction,
+) -> SyntaxToken {
+    let mapped_into_node = |token: &SyntaxToken| -> Option<bool> {
         let expanded = sema.descend_into_macros_single(token.clone());
-        let parent = match expanded.parent() {
-            Some(it) => it,
-            None => return false,
-        };
-        algo::least_common_ancestor(&extended, &parent).as_ref() == Some(&extended)
+        if expanded == *token {
+            return None;
+        }
+        let parent = expanded.parent()?;
+        Some(algo::least_common_ancestor(node, &parent).as_ref() == Some(node))
     };
 
-    // Find the first and last text range under expanded parent
-    let first = successors(Some(first_token), |token| {
-        let token = token.prev_token()?;
-        skip_trivia_token(token, Direction::Prev)
-    })
-    .take_while(validate)
-    .last()?;
-
-    let last = successors(Some(last_token), |token| {
-        let token = token.next_token()?;
-        skip_trivia_token(token, Direction::Next)
+    successors(Some(token.clone()), |token| {
+        let token = match dir {
+            Direction::Next => token.next_token()?,
+            Direction::Prev => token.prev_token()?,
+        };
+        skip_trivia_token(token, dir)
     })
-    .take_while(validate)
-    .last()?;
-
-    let range = first.text_range().cover(last.text_range());
-    if range.contains_range(original_range) && original_range != range {
-        Some(range)
-    } else {
-        None
-    }
+    .skip(1)
+    .take_while(|token| tt_range.contains_range(token.text_range()))
+    .map(|token| (mapped_into_node(&token), token))
+    .take_while(|(mapped, _)| *mapped != Some(false))
+    .filter_map(|(mapped, token)| mapped.map(|_| token))
+    .last()
+    .unwrap_or_else(|| token.clone())
 }
 
 /// Find the shallowest node with same range, which allows us to traverse siblings.
@@ -659,4 +674,13 @@ fn main() { let (
             ],
         );
     }
+
+    #[test]
+    fn extend_selection_through_expansion_nodes() {
+        do_check(
+            r#"macro_rules! sum { ($a:expr, $b:expr) => { ($a * 2) + $b } }
+                fn f() { sum!(x$0, y); }"#,
+            &["x", "x, y", "(x, y)", "sum!(x, y)"],
+        );
+    }
 }
@@ -0,0 +1,149 @@
+//! Finds where a Cargo feature is used in the code of the workspace, to clean
+//! up features or to find out what a feature enabled by unification changes.
+//!
+//! This looks at the `cfg` predicates as we lower them, rather than at their
+//! text, so `any(feature = "a", not(feature = "b"))` counts for both features.
+//! Code which is currently `cfg`d out is searched too.
+//!
+//! Only Rust code is covered here, the `[features]` tables of manifests are
+//! the business of the server, which knows about Cargo.
+
+use hir::{CfgAtom, CfgExpr};
+use ide_db::{
+    base_db::{FileRange, SourceDatabaseExt},
+    symbol_index::SymbolsDatabase,
+    RootDatabase,
+};
+use syntax::{ast, AstNode};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeatureUsageKind {
+    /// `#[cfg(feature = "...")]`
+    Cfg,
+    /// `#[cfg_attr(feature = "...", ...)]`
+    CfgAttr,
+    /// `cfg!(feature = "...")`
+    CfgMacro,
+}
+
+#[derive(Debug, Clone)]
+pub struct FeatureUsage {
+    /// The whole attribute or macro call.
+    pub range: FileRange,
+    pub kind: FeatureUsageKind,
+}
+
+pub(crate) fn feature_usages(db: &RootDatabase, feature: &str) -> Vec<FeatureUsage> {
+    let _p = profile::span("feature_usages");
+    let mut res = Vec::new();
+    for &root_id in db.local_roots().iter() {
+        let source_root = db.source_root(root_id);
+        for file_id in source_root.iter() {
+            let is_rust = source_root
+                .path_for_file(&file_id)
+                .and_then(|path| path.name_and_extension())
+                .map_or(false, |(_, ext)| ext == Some("rs"));
+            if !is_rust {
+                continue;
+            }
+            let source_file = db.parse(file_id).tree();
+            for node in source_file.syntax().descendants() {
+                let (kind, tt) = if let Some(attr) = ast::Attr::cast(node.clone()) {
+                    let kind = match attr.simple_name().as_deref() {
+                        Some("cfg") => FeatureUsageKind::Cfg,
+                        Some("cfg_attr") => FeatureUsageKind::CfgAttr,
+                        _ => continue,
+                    };
+                    match attr.meta().and_then(|it| it.token_tree()) {
+                        Some(tt) => (kind, tt),
+                        None => continue,
+                    }
+                } else if let Some(call) = ast::MacroCall::cast(node.clone()) {
+                    let is_cfg = call
+                        .path()
+                        .and_then(|it| it.as_single_name_ref())
+                        .map_or(false, |it| it.text() == "cfg");
+                    match call.token_tree() {
+                        Some(tt) if is_cfg => (FeatureUsageKind::CfgMacro, tt),
+                        _ => continue,
+                    }
+                } else {
+                    continue;
+                };
+                // For `cfg_attr`, this is only the predicate.
+                if mentions_feature(&hir::parse_cfg(&tt), feature) {
+                    let range = FileRange { file_id, range: node.text_range() };
+                    res.push(FeatureUsage { range, kind });
+                }
+            }
+        }
+    }
+    res.sort_by_key(|it| (it.range.file_id, it.range.range.start()));
+    res
+}
+
+fn mentions_feature(cfg: &CfgExpr, feature: &str) -> bool {
+    match cfg {
+        CfgExpr::Invalid | CfgExpr::Atom(CfgAtom::Flag(_)) => false,
+        CfgExpr::Atom(CfgAtom::KeyValue { key, value }) => key == "feature" && value == feature,
+        CfgExpr::All(preds) | CfgExpr::Any(preds) => {
+            preds.iter().any(|it| mentions_feature(it, feature))
+        }
+        CfgExpr::Not(pred) => mentions_feature(pred, feature),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{expect, Expect};
+    use stdx::format_to;
+
+    use crate::fixture;
+
+    fn check(ra_fixture: &str, feature: &str, expect: Expect) {
+        let (analysis, _) = fixture::file(ra_fixture);
+        let mut actual = String::new();
+        for usage in analysis.feature_usages(feature).unwrap() {
+            let text = analysis.file_text(usage.range.file_id).unwrap();
+            format_to!(
+                actual,
+                "{:?} {:?} {}\n",
+                usage.range.file_id,
+                usage.kind,
+                &text[usage.range.range]
+            );
+        }
+        expect.assert_eq(&actual);
+    }
+
+    #[test]
+    fn finds_cfgs_mentioning_feature() {
+        check(
+            r#"
+//- /lib.rs
+#[cfg(feature = "serde")]
+mod serde_impls;
+#[cfg(not(feature = "serde"))]
+fn fallback() {}
+#[cfg_attr(any(test, feature = "serde"), derive(Debug))]
+struct S;
+#[cfg_attr(test, doc = "feature = \"serde\"")]
+struct T;
+#[cfg(feature = "std")]
+fn std() {
+    if cfg!(all(unix, feature = "serde")) {}
+}
+//- /serde_impls.rs
+#![cfg(feature = "serde")]
+"#,
+            "serde",
+            expect![[r#"
+                FileId(0) Cfg #[cfg(feature = "serde")]
+                FileId(0) Cfg #[cfg(not(feature = "serde"))]
+                FileId(0) CfgAttr #[cfg_attr(any(test, feature = "serde"), derive(Debug))]
+                FileId(0) CfgMacro cfg!(all(unix, feature = "serde"))
+                FileId(1) Cfg #![cfg(feature = "serde")]
+            "#]],
+        );
+    }
+}
@@ -5,11 +5,12 @@ use hir::{AsAssocItem, Semantics};
 use ide_db::{
     base_db::{AnchoredPath, FileId, FileLoader},
     defs::{Definition, IdentClass},
+    format_args::{self, FormatArgTarget},
     helpers::pick_best_token,
     RootDatabase,
 };
 use itertools::Itertools;
-use syntax::{ast, AstNode, AstToken, SyntaxKind::*, SyntaxToken, TextRange, T};
+use syntax::{ast, AstNode, AstToken, NodeOrToken, SyntaxKind::*, SyntaxToken, TextRange, T};
 
 // Feature: Go to Definition
 //
@@ -37,7 +38,9 @@ pub(crate) fn goto_definition(
             | T![super]
             | T![crate]
             | T![Self]
-            | COMMENT => 2,
+            | COMMENT => 3,
+            // index and try operators
+            T!['['] | T![']'] | T![?] => 2,
             kind if kind.is_trivia() => 0,
             _ => 1,
         })?;
@@ -47,19 +50,28 @@ pub(crate) fn goto_definition(
             Some(RangeInfo::new(original_token.text_range(), vec![nav]))
         });
     }
-    let navs = sema
+    if let Some(string) = format_args::format_string(sema, &original_token) {
+        return format_arg_definition(sema, position, &string);
+    }
+    let mut navs = sema
         .descend_into_macros(original_token.clone())
         .into_iter()
         .filter_map(|token| {
             let parent = token.parent()?;
-            if let Some(tt) = ast::TokenTree::cast(parent) {
-                if let Some(x) = try_lookup_include_path(sema, tt, token.clone(), position.file_id)
-                {
+            if ast::TokenTree::can_cast(parent.kind()) {
+                if let Some(x) = try_lookup_include_path(sema, token.clone(), position.file_id) {
                     return Some(vec![x]);
                 }
             }
+            let class = IdentClass::classify_token(sema, &token)?;
+            // operators already resolve to the impl method, don't bounce back to the trait
+            if let IdentClass::Operator(_) = class {
+                return Some(
+                    class.definitions().into_iter().flat_map(|def| def_to_nav(db, def)).collect(),
+                );
+            }
             Some(
-                IdentClass::classify_token(sema, &token)?
+                class
                     .definitions()
                     .into_iter()
                     .flat_map(|def| {
@@ -72,23 +84,89 @@ pub(crate) fn goto_definition(
         .flatten()
         .unique()
         .collect::<Vec<NavigationTarget>>();
+    navs.extend(try_lookup_derive_impls(sema, &original_token).into_iter().flatten());
 
     Some(RangeInfo::new(original_token.text_range(), navs))
 }
 
+/// Finds the impls generated by the derive under the cursor, e.g. `Clone` in `#[derive(Clone)]`.
+fn try_lookup_derive_impls(
+    sema: &Semantics<RootDatabase>,
+    token: &SyntaxToken,
+) -> Option<Vec<NavigationTarget>> {
+    let attr = token.ancestors().find_map(ast::Attr::cast)?;
+    let (name, tt) = attr.as_simple_call()?;
+    if name != "derive" {
+        return None;
+    }
+    // `foo` in `foo::Clone` names a module, not the derive itself
+    let is_derive = sema.descend_into_macros(token.clone()).into_iter().any(|token| {
+        IdentClass::classify_token(sema, &token).map_or(false, |class| {
+            class.definitions().into_iter().any(|def| {
+                matches!(def, Definition::Macro(mac) if mac.kind(sema.db) == hir::MacroKind::Derive)
+            })
+        })
+    });
+    if !is_derive {
+        return None;
+    }
+    let idx = tt
+        .token_trees_and_tokens()
+        .filter_map(NodeOrToken::into_token)
+        .take_while(|it| it != token)
+        .filter(|it| it.kind() == T![,])
+        .count();
+    let expansion = sema.derive_macro_expansions(&attr)?.into_iter().nth(idx)??;
+    let navs = expansion
+        .descendants()
+        .filter_map(ast::Impl::cast)
+        .filter_map(|impl_| sema.to_def(&impl_))
+        .filter_map(|impl_| impl_.try_to_nav(sema.db))
+        .collect();
+    Some(navs)
+}
+
+/// Finds the file included by the `include!`-like macro call around `token`,
+/// whose path may be built with `concat!` and `env!`.
+/// Goes from a `{name}` in a format string to the argument or the captured binding it names.
+fn format_arg_definition(
+    sema: &Semantics<RootDatabase>,
+    position: FilePosition,
+    string: &ast::String,
+) -> Option<RangeInfo<Vec<NavigationTarget>>> {
+    let (range, name) = format_args::identifiers(string)
+        .into_iter()
+        .find(|(range, _)| range.contains_inclusive(position.offset))?;
+    let nav = match format_args::resolve(sema, string, &name)? {
+        FormatArgTarget::Named(token) => NavigationTarget {
+            file_id: position.file_id,
+            full_range: token.text_range(),
+            name: name.into(),
+            focus_range: Some(token.text_range()),
+            kind: None,
+            container_name: None,
+            description: None,
+            docs: None,
+        },
+        FormatArgTarget::Captured(def) => def.try_to_nav(sema.db)?,
+    };
+    Some(RangeInfo::new(range, vec![nav]))
+}
+
 fn try_lookup_include_path(
     sema: &Semantics<RootDatabase>,
-    tt: ast::TokenTree,
     token: SyntaxToken,
     file_id: FileId,
 ) -> Option<NavigationTarget> {
-    let token = ast::String::cast(token)?;
-    let path = token.value()?.into_owned();
-    let macro_call = tt.syntax().parent().and_then(ast::MacroCall::cast)?;
+    ast::String::cast(token.clone())?;
+    let macro_call = token.parent_ancestors().find_map(ast::MacroCall::cast)?;
     let name = macro_call.path()?.segment()?.name_ref()?;
     if !matches!(&*name.text(), "include" | "include_str" | "include_bytes") {
         return None;
     }
+    let krate = sema.scope(macro_call.syntax()).krate()?;
+    let mut args = macro_call.token_tree()?.syntax().children_with_tokens().skip(1);
+    let path = hir::eval_str_arg(&krate.env(sema.db), &mut args)?;
     let file_id = sema.db.resolve_path(AnchoredPath { anchor: file_id, path: &path })?;
     let size = sema.db.file_text(file_id).len().try_into().ok()?;
     Some(NavigationTarget {
@@ -448,6 +526,89 @@ fn bar(foo: &Foo) {
         );
     }
 
+    #[test]
+    fn goto_def_for_bin_op() {
+        check(
+            r#"
+//- minicore: add
+struct Foo;
+impl core::ops::Add for Foo {
+    type Output = Foo;
+    fn add(self, _: Foo) -> Foo { Foo }
+     //^^^
+}
+
+fn f() {
+    let _ = Foo $0+ Foo;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn goto_def_for_eq_op() {
+        check(
+            r#"
+//- minicore: eq
+struct Foo;
+impl PartialEq for Foo {
+    fn eq(&self, _: &Foo) -> bool { true }
+     //^^
+}
+
+fn f(a: Foo, b: &Foo) -> bool {
+    a $0== *b
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn goto_def_for_index_op() {
+        check(
+            r#"
+//- minicore: index
+struct Foo;
+impl core::ops::Index<usize> for Foo {
+    type Output = ();
+    fn index(&self, _: usize) -> &() { &() }
+     //^^^^^
+}
+
+fn f(foo: &Foo) {
+    foo[0]$0;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn goto_def_for_try_op() {
+        check(
+            r#"
+//- minicore: try
+use core::ops::{ControlFlow, FromResidual, Try};
+
+struct Foo;
+impl FromResidual for Foo {
+    fn from_residual(_: ()) -> Foo { Foo }
+}
+impl Try for Foo {
+    type Output = ();
+    type Residual = ();
+    fn from_output(_: ()) -> Foo { Foo }
+    fn branch(self) -> ControlFlow<(), ()> { ControlFlow::Continue(()) }
+     //^^^^^^
+}
+
+fn f() -> Foo {
+    Foo?$0;
+    Foo
+}
+"#,
+        );
+    }
+
     #[test]
     fn goto_def_for_fields() {
         check(
@@ -1314,6 +1475,41 @@ fn main() {
         );
     }
 
+    #[test]
+    fn goto_include_in_doc_attr() {
+        check(
+            r#"
+//- /main.rs
+#[doc = include_str!("README$0.md")]
+fn main() {}
+//- /README.md
+// empty
+//^file
+"#,
+        );
+    }
+
+    #[test]
+    fn goto_include_with_env() {
+        check(
+            r#"
+//- /main.rs env:OUT_DIR=/out
+#[rustc_builtin_macro]
+macro_rules! include_str {}
+#[rustc_builtin_macro]
+macro_rules! concat {}
+#[rustc_builtin_macro]
+macro_rules! env {}
+fn main() {
+    let str = include_str!(concat!(env!("OUT_DIR"), "/foo.t$0xt"));
+}
+//- /out/foo.txt
+// empty
+//^file
+"#,
+        );
+    }
+
     #[test]
     fn goto_def_of_trait_impl_fn() {
         check(
@@ -1377,6 +1573,7 @@ impl Twait for Stwuct {
         pub macro Copy {}
                // ^^^^
         #[derive(Copy$0)]
+      //^^^^^^^^^^^^^^^
         struct Foo;
                     "#,
         );
@@ -1387,7 +1584,8 @@ impl Twait for Stwuct {
 pub macro Copy {}
        // ^^^^
 #[cfg_attr(feature = "false", derive)]
-#[derive(Copy$0)]
+  #[derive(Copy$0)]
+//^^^^^^^^^^^^^^^
 struct Foo;
             "#,
         );
@@ -1399,7 +1597,8 @@ mod foo {
     pub macro Copy {}
            // ^^^^
 }
-#[derive(foo::Copy$0)]
+  #[derive(foo::Copy$0)]
+//^^^^^^^^^^^^^^^^^^^^
 struct Foo;
             "#,
         );
@@ -1417,6 +1616,36 @@ struct Foo;
         );
     }
 
+    #[test]
+    fn goto_def_derive_generated_impls() {
+        check(
+            r#"
+//- minicore:derive
+#[rustc_builtin_macro]
+pub macro Clone {}
+#[rustc_builtin_macro]
+pub macro Copy {}
+       // ^^^^
+  #[derive(Clone, Copy$0)]
+//^^^^^^^^^^^^^^^^^^^^^^
+struct Foo;
+"#,
+        );
+        check(
+            r#"
+//- minicore:derive
+#[rustc_builtin_macro]
+pub macro Clone {}
+       // ^^^^^
+#[rustc_builtin_macro]
+pub macro Copy {}
+  #[derive(Clone$0, Copy)]
+//^^^^^^^^^^^^^^^^^^^^^^
+struct Foo;
+"#,
+        );
+    }
+
     #[test]
     fn goto_def_in_macro_multi() {
         check(
@@ -1450,6 +1679,64 @@ macro_rules! foo {
 }
 
 foo!(bar$0);
+"#,
+        );
+    }
+
+    #[test]
+    fn goto_def_in_format_string() {
+        check(
+            r#"
+macro_rules! format_args {
+    ($lit:literal $($tt:tt)*) => { 0 };
+}
+fn main() {
+    let captured = 0;
+      //^^^^^^^^
+    format_args!("{captured$0} {width}", width = 2);
+}
+"#,
+        );
+        check(
+            r#"
+macro_rules! format_args {
+    ($lit:literal $($tt:tt)*) => { 0 };
+}
+fn main() {
+    format_args!("{:>width$0}", 1, width = 2);
+                              //^^^^^
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn goto_def_in_asm_operands() {
+        check(
+            r#"
+#[rustc_builtin_macro]
+#[macro_export]
+macro_rules! format_args {}
+#[rustc_builtin_macro]
+macro_rules! asm {}
+fn f() {
+    let value = 0u64;
+      //^^^^^
+    unsafe { asm!("mov {tmp}, {}", in(reg) value$0, tmp = out(reg) _) };
+}
+"#,
+        );
+        check(
+            r#"
+#[rustc_builtin_macro]
+#[macro_export]
+macro_rules! format_args {}
+#[rustc_builtin_macro]
+macro_rules! asm {}
+fn f() {
+    unsafe { asm!("mov {tmp$0}, 1", tmp = out(reg) _) };
+                                //^^^
+}
 "#,
         );
     }
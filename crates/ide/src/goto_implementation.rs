@@ -1,5 +1,6 @@
-use hir::{AsAssocItem, Impl, Semantics};
+use hir::{AsAssocItem, Crate, Impl, Semantics};
 use ide_db::{
+    base_db::{CrateId, SourceDatabaseExt},
     defs::{Definition, NameClass, NameRefClass},
     helpers::pick_best_token,
     RootDatabase,
@@ -89,6 +90,84 @@ pub(crate) fn goto_implementation(
     Some(RangeInfo { range, info: navs })
 }
 
+/// A crate which can contain impls of a trait, because it depends on the crate
+/// defining the trait.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImplCrate {
+    pub crate_id: CrateId,
+    pub name: Option<String>,
+    /// Whether the crate is a dependency rather than a workspace member.
+    pub is_library: bool,
+}
+
+/// Lists the crates which can implement the trait at `position`, workspace
+/// members first. Their impls can then be loaded one crate at a time with
+/// [`trait_impls_in_crate`], so that looking at the impls in the workspace
+/// doesn't need the name resolution of every dependency.
+pub(crate) fn implementing_crates(
+    db: &RootDatabase,
+    position: FilePosition,
+) -> Option<Vec<ImplCrate>> {
+    let sema = Semantics::new(db);
+    let trait_ = trait_at(&sema, position)?;
+    let mut crates: Vec<ImplCrate> = trait_
+        .module(db)
+        .krate()
+        .transitive_reverse_dependencies(db)
+        .into_iter()
+        .map(|krate| {
+            let source_root = db.file_source_root(krate.root_file(db));
+            ImplCrate {
+                crate_id: krate.into(),
+                name: krate.display_name(db).map(|it| it.to_string()),
+                is_library: db.source_root(source_root).is_library,
+            }
+        })
+        .collect();
+    crates.sort_by(|a, b| (a.is_library, &a.name).cmp(&(b.is_library, &b.name)));
+    Some(crates)
+}
+
+pub(crate) fn trait_impls_in_crate(
+    db: &RootDatabase,
+    position: FilePosition,
+    crate_id: CrateId,
+) -> Option<Vec<NavigationTarget>> {
+    let sema = Semantics::new(db);
+    let trait_ = trait_at(&sema, position)?;
+    let navs = Impl::all_for_trait_in_crate(db, trait_, Crate::from(crate_id))
+        .into_iter()
+        .filter_map(|imp| imp.try_to_nav(db))
+        .collect();
+    Some(navs)
+}
+
+fn trait_at(sema: &Semantics<RootDatabase>, position: FilePosition) -> Option<hir::Trait> {
+    let file = sema.parse(position.file_id);
+    let token =
+        pick_best_token(file.syntax().token_at_offset(position.offset), |kind| match kind {
+            IDENT => 1,
+            _ => 0,
+        })?;
+    sema.descend_into_macros(token).into_iter().find_map(|token| {
+        let def = match ast::NameLike::cast(token.parent()?)? {
+            ast::NameLike::Name(name) => match NameClass::classify(sema, &name)? {
+                NameClass::Definition(it) => it,
+                _ => return None,
+            },
+            ast::NameLike::NameRef(name_ref) => match NameRefClass::classify(sema, &name_ref)? {
+                NameRefClass::Definition(it) => it,
+                _ => return None,
+            },
+            ast::NameLike::Lifetime(_) => return None,
+        };
+        match def {
+            Definition::Trait(it) => Some(it),
+            _ => None,
+        }
+    })
+}
+
 fn impls_for_ty(sema: &Semantics<RootDatabase>, ty: hir::Type) -> Vec<NavigationTarget> {
     Impl::all_for_type(sema.db, ty).into_iter().filter_map(|imp| imp.try_to_nav(sema.db)).collect()
 }
@@ -341,4 +420,42 @@ impl Tr for S {
 "#,
         );
     }
+
+    #[test]
+    fn implementing_crates_are_loaded_separately() {
+        let (analysis, position) = fixture::position(
+            r#"
+//- /main.rs crate:main deps:dep,tr
+use tr::Tr$0;
+struct S;
+impl Tr for S {}
+//- /dep.rs crate:dep deps:tr
+pub struct D;
+impl tr::Tr for D {}
+//- /tr.rs crate:tr
+pub trait Tr {}
+struct Unrelated;
+//- /other.rs crate:other
+pub trait Tr {}
+impl Tr for () {}
+"#,
+        );
+        let crates = analysis.implementing_crates(position).unwrap().unwrap();
+        let names: Vec<_> = crates.iter().map(|it| it.name.clone().unwrap()).collect();
+        assert_eq!(names, ["dep", "main", "tr"]);
+
+        let impls = |name: &str| {
+            let krate = crates.iter().find(|it| it.name.as_deref() == Some(name)).unwrap();
+            analysis
+                .trait_impls_in_crate(position, krate.crate_id)
+                .unwrap()
+                .unwrap()
+                .into_iter()
+                .map(|nav| nav.name.to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(impls("main"), ["S"]);
+        assert_eq!(impls("dep"), ["D"]);
+        assert!(impls("tr").is_empty());
+    }
 }
@@ -32,15 +32,17 @@ pub struct HighlightRelatedConfig {
     pub exit_points: bool,
     pub break_points: bool,
     pub yield_points: bool,
+    pub function_points: bool,
 }
 
 // Feature: Highlight Related
 //
 // Highlights constructs related to the thing under the cursor:
 // - if on an identifier, highlights all references to that identifier in the current file
-// - if on an `async` or `await token, highlights all yield points for that async context
+// - if on an `async`, `await` or `yield` token, highlights all yield points for that async context or generator
 // - if on a `return` or `fn` keyword, `?` character or `->` return type arrow, highlights all exit points for that context
 // - if on a `break`, `loop`, `while` or `for` token, highlights all break points for that loop or block context
+// - otherwise, if enabled, highlights all exit and yield points of the surrounding function or closure
 //
 // Note: `?` and `->` do not currently trigger this behavior in the VSCode editor.
 pub(crate) fn highlight_related(
@@ -63,15 +65,39 @@ pub(crate) fn highlight_related(
             highlight_exit_points(sema, token)
         }
         T![fn] | T![return] | T![->] if config.exit_points => highlight_exit_points(sema, token),
-        T![await] | T![async] if config.yield_points => highlight_yield_points(token),
+        T![await] | T![async] | T![yield] if config.yield_points => highlight_yield_points(token),
         T![for] if config.break_points && token.parent().and_then(ast::ForExpr::cast).is_some() => {
             highlight_break_points(token)
         }
         T![break] | T![loop] | T![while] | T![continue] if config.break_points => {
             highlight_break_points(token)
         }
-        _ if config.references => highlight_references(sema, &syntax, token, file_id),
-        _ => None,
+        _ => {
+            let references = match config.references {
+                true => highlight_references(sema, &syntax, token.clone(), file_id),
+                false => None,
+            };
+            match references {
+                None if config.function_points => highlight_function_points(sema, token),
+                references => references,
+            }
+        }
+    }
+}
+
+/// Highlights where the function or closure around the cursor can return or suspend.
+fn highlight_function_points(
+    sema: &Semantics<RootDatabase>,
+    token: SyntaxToken,
+) -> Option<Vec<HighlightedRange>> {
+    let mut res = highlight_exit_points(sema, token.clone()).unwrap_or_default();
+    res.extend(highlight_yield_points(token).into_iter().flatten());
+    res.sort_by_key(|it| it.range.start());
+    res.dedup();
+    if res.is_empty() {
+        None
+    } else {
+        Some(res)
     }
 }
 
@@ -289,24 +315,36 @@ fn highlight_yield_points(token: SyntaxToken) -> Option<Vec<HighlightedRange>> {
         async_token: Option<SyntaxToken>,
         body: Option<ast::Expr>,
     ) -> Option<Vec<HighlightedRange>> {
-        let mut highlights =
-            vec![HighlightedRange { category: None, range: async_token?.text_range() }];
+        // Generators have no `async` to start from, only their `yield`s.
+        let mut highlights: Vec<_> = async_token
+            .map(|it| HighlightedRange { category: None, range: it.text_range() })
+            .into_iter()
+            .collect();
         if let Some(body) = body {
             walk_expr(&body, &mut |expr| {
-                if let ast::Expr::AwaitExpr(expr) = expr {
-                    if let Some(token) = expr.await_token() {
-                        highlights
-                            .push(HighlightedRange { category: None, range: token.text_range() });
-                    }
+                let token = match expr {
+                    ast::Expr::AwaitExpr(expr) => expr.await_token(),
+                    ast::Expr::YieldExpr(expr) => expr.yield_token(),
+                    _ => None,
+                };
+                if let Some(token) = token {
+                    highlights.push(HighlightedRange { category: None, range: token.text_range() });
                 }
             });
         }
-        Some(highlights)
+        if highlights.is_empty() {
+            None
+        } else {
+            Some(highlights)
+        }
     }
     for anc in token.ancestors() {
         return match_ast! {
             match anc {
-                ast::Fn(fn_) => hl(fn_.async_token(), fn_.body().map(ast::Expr::BlockExpr)),
+                ast::Fn(fn_) => match fn_.async_token() {
+                    Some(async_token) => hl(Some(async_token), fn_.body().map(ast::Expr::BlockExpr)),
+                    None => None,
+                },
                 ast::BlockExpr(block_expr) => {
                     if block_expr.async_token().is_none() {
                         continue;
@@ -351,6 +389,7 @@ mod tests {
             exit_points: true,
             references: true,
             yield_points: true,
+            function_points: false,
         };
 
         check_with_config(ra_fixture, config);
@@ -1065,6 +1104,7 @@ fn function(field: u32) {
             break_points: true,
             exit_points: true,
             yield_points: true,
+            function_points: false,
         };
 
         check_with_config(
@@ -1085,6 +1125,7 @@ fn foo() {
             break_points: true,
             exit_points: true,
             yield_points: true,
+            function_points: false,
         };
 
         check_with_config(
@@ -1125,6 +1166,7 @@ fn foo() {
             break_points: true,
             exit_points: true,
             yield_points: true,
+            function_points: false,
         };
 
         check_with_config(
@@ -1161,6 +1203,7 @@ async fn foo() {
             break_points: true,
             exit_points: true,
             yield_points: true,
+            function_points: false,
         };
 
         check_with_config(
@@ -1204,6 +1247,7 @@ fn foo() ->$0 i32 {
             break_points: false,
             exit_points: true,
             yield_points: true,
+            function_points: false,
         };
 
         check_with_config(
@@ -1225,6 +1269,7 @@ fn foo() {
             break_points: true,
             exit_points: true,
             yield_points: false,
+            function_points: false,
         };
 
         check_with_config(
@@ -1244,6 +1289,7 @@ async$0 fn foo() {
             break_points: true,
             exit_points: false,
             yield_points: true,
+            function_points: false,
         };
 
         check_with_config(
@@ -1259,6 +1305,60 @@ fn foo() ->$0 i32 {
         );
     }
 
+    #[test]
+    fn test_hl_function_points() {
+        let config =
+            HighlightRelatedConfig { function_points: true, ..HighlightRelatedConfig::default() };
+
+        check_with_config(
+            r#"
+fn foo() -> u32 {
+    if true {
+        return 0;
+     // ^^^^^^
+    }
+    let x = 1 +$0 2;
+    0?;
+  // ^
+    x
+ // ^
+}
+"#,
+            config.clone(),
+        );
+        check_with_config(
+            r#"
+pub async fn foo() -> u32 {
+ // ^^^^^
+    bar().await;
+       // ^^^^^
+    $0
+    0
+ // ^
+}
+"#,
+            config,
+        );
+    }
+
+    #[test]
+    fn test_hl_generator_yield_points() {
+        check(
+            r#"
+fn foo() {
+    let gen = || {
+        yield$0 1;
+     // ^^^^^
+        if true {
+            yield 2;
+         // ^^^^^
+        }
+    };
+}
+"#,
+        );
+    }
+
     #[test]
     fn test_hl_multi_local() {
         check(
@@ -11,6 +11,7 @@ use ide_db::{
     base_db::FileRange,
     defs::{Definition, IdentClass},
     famous_defs::FamousDefs,
+    format_args::{self, FormatArgTarget},
     helpers::pick_best_token,
     FxIndexSet, RootDatabase,
 };
@@ -126,6 +127,32 @@ pub(crate) fn hover(
         return Some(RangeInfo::new(original_token.text_range(), res));
     }
 
+    let hovered_cfg = descended.iter().find_map(|token| {
+        let attr = token.ancestors().find_map(ast::Attr::cast)?;
+        render::try_for_cfg(sema, &attr, token)
+    });
+    if let Some(res) = hovered_cfg {
+        return Some(RangeInfo::new(original_token.text_range(), res));
+    }
+
+    if let Some(res) = render::try_for_env_var(sema, &original_token) {
+        return Some(RangeInfo::new(original_token.text_range(), res));
+    }
+
+    if let Some(string) = format_args::format_string(sema, &original_token) {
+        let (range, name) = format_args::identifiers(&string)
+            .into_iter()
+            .find(|(range, _)| range.contains_inclusive(offset))?;
+        return match format_args::resolve(sema, &string, &name)? {
+            FormatArgTarget::Captured(def) => {
+                let node = original_token.parent()?;
+                let res = hover_for_definition(sema, file_id, def, &node, config)?;
+                Some(RangeInfo::new(range, res))
+            }
+            FormatArgTarget::Named(_) => None,
+        };
+    }
+
     let result = descended
         .iter()
         .filter_map(|token| {
@@ -149,6 +176,10 @@ pub(crate) fn hover(
         if let Some(res) = res {
             return Some(RangeInfo::new(original_token.text_range(), res));
         }
+        let res = descended.iter().find_map(|token| render::try_for_int_literal(sema, token));
+        if let Some(res) = res {
+            return Some(RangeInfo::new(original_token.text_range(), res));
+        }
         let res = descended
             .iter()
             .find_map(|token| hover_type_fallback(sema, config, token, &original_token));
@@ -174,6 +205,13 @@ pub(crate) fn hover_for_definition(
         _ => None,
     };
     if let Some(markup) = render::definition(sema.db, definition, famous_defs.as_ref(), config) {
+        let markup = match definition {
+            Definition::Function(func) => match render::call_obligations(sema, func, node) {
+                Some(obligations) => Markup::from(format!("{}\n___\n\n{}", markup, obligations)),
+                None => markup,
+            },
+            _ => markup,
+        };
         let mut res = HoverResult::default();
         res.markup = render::process_markup(sema.db, definition, &markup, config);
         if let Some(action) = show_implementations_action(sema.db, definition) {
@@ -1,6 +1,7 @@
 //! Logic for rendering the different hover messages
 use std::fmt::Display;
 
+use cfg::DnfExpr;
 use either::Either;
 use hir::{AsAssocItem, AttributeTemplate, HasAttrs, HirDisplay, Semantics, TypeInfo};
 use ide_db::{
@@ -8,6 +9,7 @@ use ide_db::{
     defs::Definition,
     famous_defs::FamousDefs,
     generated::lints::{CLIPPY_LINTS, DEFAULT_LINTS, FEATURES},
+    syntax_helpers::node_ext::macro_name_of_input,
     RootDatabase,
 };
 use itertools::Itertools;
@@ -15,7 +17,7 @@ use stdx::format_to;
 use syntax::{
     algo, ast, match_ast, AstNode, Direction,
     SyntaxKind::{LET_EXPR, LET_STMT},
-    SyntaxToken, T,
+    SyntaxNode, SyntaxToken, T,
 };
 
 use crate::{
@@ -290,6 +292,115 @@ pub(super) fn try_for_lint(attr: &ast::Attr, token: &SyntaxToken) -> Option<Hove
     })
 }
 
+pub(super) fn try_for_env_var(
+    sema: &Semantics<RootDatabase>,
+    token: &SyntaxToken,
+) -> Option<HoverResult> {
+    let key = ast::String::cast(token.clone())?.value()?.into_owned();
+    let input = token.parent().and_then(ast::TokenTree::cast)?;
+    let name = macro_name_of_input(&input)?;
+    let value = sema.scope(input.syntax()).krate()?.env(sema.db).get(&key);
+    let status = match (&*name, value) {
+        ("env", Some(value)) => format!("Evaluates to `{:?}`.", value),
+        ("env", None) => "The variable is not set for this crate.".to_string(),
+        ("option_env", Some(value)) => format!("Evaluates to `Some({:?})`.", value),
+        ("option_env", None) => {
+            "Evaluates to `None`, the variable is not set for this crate.".to_string()
+        }
+        _ => return None,
+    };
+    Some(HoverResult {
+        markup: Markup::from(format!("```\n{}!({:?})\n```\n___\n\n{}", name, key, status)),
+        ..Default::default()
+    })
+}
+
+pub(super) fn try_for_int_literal(
+    sema: &Semantics<RootDatabase>,
+    token: &SyntaxToken,
+) -> Option<HoverResult> {
+    let value = ast::IntNumber::cast(token.clone())?.value()?;
+    let literal = token.parent().and_then(ast::Literal::cast)?;
+    let ty = sema.type_of_expr(&ast::Expr::Literal(literal.clone()))?.original;
+    let builtin = ty.as_builtin().filter(|it| it.is_int() || it.is_uint())?;
+    // `isize` and `usize` depend on the target, their bit patterns aren't padded.
+    let bits = builtin.name().to_string()[1..].parse::<u32>().ok();
+
+    let negated = builtin.is_int()
+        && literal
+            .syntax()
+            .parent()
+            .and_then(ast::PrefixExpr::cast)
+            .map_or(false, |it| it.op_kind() == Some(ast::UnaryOp::Neg));
+    let (decimal, mut pattern) = if negated {
+        (format!("-{}", value), value.wrapping_neg())
+    } else {
+        (value.to_string(), value)
+    };
+    if let Some(bits) = bits.filter(|&it| it < 128) {
+        pattern &= (1 << bits) - 1;
+    }
+    let mut binary = format!("{:b}", pattern);
+    if let Some(bits) = bits {
+        binary = format!("{:0>width$}", binary, width = bits as usize);
+    }
+    let binary =
+        binary.as_bytes().rchunks(4).rev().map(|it| std::str::from_utf8(it).unwrap()).join("_");
+
+    Some(HoverResult {
+        markup: Markup::from(format!(
+            "```rust\n{}\n```\n___\n\n```text\ndec: {}\nhex: {:#X}\noct: {:#o}\nbin: 0b{}\n```",
+            builtin.name(),
+            decimal,
+            pattern,
+            pattern,
+            binary
+        )),
+        ..Default::default()
+    })
+}
+
+pub(super) fn try_for_cfg(
+    sema: &Semantics<RootDatabase>,
+    attr: &ast::Attr,
+    token: &SyntaxToken,
+) -> Option<HoverResult> {
+    let (path, tt) = attr.as_simple_call()?;
+    if !tt.syntax().text_range().contains(token.text_range().start()) {
+        return None;
+    }
+    match &*path {
+        "cfg" => (),
+        // Only the predicate, not the attributes applied if it holds.
+        "cfg_attr" => {
+            let in_predicate = tt
+                .syntax()
+                .children_with_tokens()
+                .take_while(|it| it.text_range().end() <= token.text_range().start())
+                .all(|it| it.kind() != T![,]);
+            if !in_predicate {
+                return None;
+            }
+        }
+        _ => return None,
+    }
+
+    let cfg = hir::parse_cfg(&tt);
+    let cfg_options = sema.scope(attr.syntax()).krate()?.cfg(sema.db);
+    let status = match cfg_options.check(&cfg) {
+        Some(true) => "Enabled for the active configuration.".to_string(),
+        Some(false) => match DnfExpr::new(cfg).why_inactive(&cfg_options) {
+            Some(reason) => format!("Disabled for the active configuration: {}.", reason),
+            None => "Disabled for the active configuration.".to_string(),
+        },
+        None => "Not a valid cfg predicate.".to_string(),
+    };
+    Some(HoverResult {
+        markup: Markup::from(format!("```\n{}{}\n```\n___\n\n{}", path, tt, status)),
+        ..Default::default()
+    })
+}
+
 pub(super) fn process_markup(
     db: &RootDatabase,
     def: Definition,
@@ -307,6 +418,50 @@ pub(super) fn process_markup(
     Markup::from(markup)
 }
 
+/// The where-clauses of a generic function instantiated for the call `name_ref` is the callee of,
+/// and whether they hold there.
+pub(super) fn call_obligations(
+    sema: &Semantics<RootDatabase>,
+    func: hir::Function,
+    name_ref: &SyntaxNode,
+) -> Option<String> {
+    let db = sema.db;
+    if hir::GenericDef::from(func).type_params(db).is_empty() {
+        return None;
+    }
+    let name_ref = ast::NameRef::cast(name_ref.clone())?;
+    let call: ast::Expr = match name_ref.syntax().parent().and_then(ast::MethodCallExpr::cast) {
+        Some(method_call) => method_call.into(),
+        None => {
+            let path_expr = name_ref.syntax().ancestors().find_map(ast::PathExpr::cast)?;
+            if path_expr.path()?.segment()?.name_ref()? != name_ref {
+                return None;
+            }
+            let call = ast::CallExpr::cast(path_expr.syntax().parent()?)?;
+            if call.expr()?.syntax() != path_expr.syntax() {
+                return None;
+            }
+            call.into()
+        }
+    };
+
+    let obligations = sema.call_obligations(&call)?;
+    if obligations.is_empty() {
+        return None;
+    }
+    let mut buf = String::from("```rust\n// at this call\nwhere\n");
+    for obligation in obligations {
+        let status = match obligation.is_satisfied(db) {
+            Some(true) => "holds",
+            Some(false) => "does not hold",
+            None => "unknown",
+        };
+        format_to!(buf, "    {}, // {}\n", obligation.display(db), status);
+    }
+    buf.push_str("```");
+    Some(buf)
+}
+
 fn definition_owner_name(db: &RootDatabase, def: &Definition) -> Option<String> {
     match def {
         Definition::Field(f) => Some(f.parent_def(db).name(db)),
@@ -345,11 +500,24 @@ pub(super) fn definition(
         Definition::Module(it) => label_and_docs(db, it),
         Definition::Function(it) => label_and_docs(db, it),
         Definition::Adt(it) => label_and_docs(db, it),
-        Definition::Variant(it) => label_and_docs(db, it),
+        Definition::Variant(it) => label_value_and_docs(db, it, |&it| {
+            // The discriminants of enums with fields are rarely interesting.
+            if it.parent_enum(db).is_data_carrying(db) {
+                return None;
+            }
+            match it.eval(db) {
+                Ok(x) if x >= 10 => Some(format!("{} ({:#X})", x, x)),
+                Ok(x) => Some(format!("{}", x)),
+                Err(_) => it.value(db).map(|x| format!("{}", x)),
+            }
+        }),
         Definition::Const(it) => label_value_and_docs(db, it, |it| {
             let body = it.eval(db);
             match body {
-                Ok(x) => Some(format!("{}", x)),
+                Ok(x) => match bit_flags(db, *it) {
+                    Some(flags) => Some(format!("{} // {}", x, flags)),
+                    None => Some(format!("{}", x)),
+                },
                 Err(_) => it.value(db).map(|x| format!("{}", x)),
             }
         }),
@@ -372,7 +540,65 @@ pub(super) fn definition(
         Definition::ToolModule(it) => return Some(Markup::fenced_block(&it.name(db))),
     };
 
-    markup(docs.filter(|_| config.documentation.is_some()).map(Into::into), label, mod_path)
+    let docs: Option<String> = docs.filter(|_| config.documentation.is_some()).map(Into::into);
+    let docs = match def.deprecation(db) {
+        Some(deprecation) => Some(match docs {
+            Some(docs) => format!("*{}*\n\n{}", deprecation, docs),
+            None => format!("*{}*", deprecation),
+        }),
+        None => docs,
+    };
+    markup(docs, label, mod_path)
+}
+
+/// For a constant which combines several of the single bit constants declared next to it, like
+/// `READ | WRITE` for bitflags-style constants, the names of these constants.
+fn bit_flags(db: &RootDatabase, konst: hir::Const) -> Option<String> {
+    let value = konst.eval_bits(db)?;
+    if value.count_ones() < 2 {
+        return None;
+    }
+    let siblings: Vec<hir::Const> = match konst.as_assoc_item(db).map(|it| it.container(db)) {
+        Some(hir::AssocItemContainer::Impl(impl_)) => impl_
+            .items(db)
+            .into_iter()
+            .filter_map(|it| match it {
+                hir::AssocItem::Const(it) => Some(it),
+                _ => None,
+            })
+            .collect(),
+        Some(hir::AssocItemContainer::Trait(_)) => return None,
+        None => konst
+            .module(db)
+            .declarations(db)
+            .into_iter()
+            .filter_map(|it| match it {
+                hir::ModuleDef::Const(it) => Some(it),
+                _ => None,
+            })
+            .collect(),
+    };
+
+    let ty = konst.ty(db);
+    let mut flags = Vec::new();
+    let mut rest = value;
+    for sibling in siblings {
+        let bit = match sibling.eval_bits(db) {
+            Some(bit) if bit.count_ones() == 1 && value & bit != 0 && sibling.ty(db) == ty => bit,
+            _ => continue,
+        };
+        if let Some(name) = sibling.name(db) {
+            flags.push(name.to_string());
+            rest &= !bit;
+        }
+    }
+    if flags.is_empty() {
+        return None;
+    }
+    if rest != 0 {
+        flags.push(format!("{:#X}", rest));
+    }
+    Some(flags.join(" | "))
 }
 
 fn render_builtin_attr(db: &RootDatabase, attr: hir::BuiltinAttr) -> Option<Markup> {
@@ -407,6 +407,76 @@ fn main() { let foo_test = fo$0o(); }
     );
 }
 
+#[test]
+fn hover_shows_call_obligations() {
+    check(
+        r#"
+trait Speak {}
+struct Dog;
+struct Rock;
+impl Speak for Dog {}
+
+fn talk<T: Speak, U>(t: T, u: U) where U: Speak {}
+
+fn main() { ta$0lk(Dog, Rock); }
+"#,
+        expect![[r#"
+            *talk*
+
+            ```rust
+            test
+            ```
+
+            ```rust
+            fn talk<T, U>(t: T, u: U)
+            where
+                T: Speak,
+                U: Speak,
+            ```
+            ___
+
+            ```rust
+            // at this call
+            where
+                Dog: Speak, // holds
+                Rock: Speak, // does not hold
+            ```
+        "#]],
+    );
+    check(
+        r#"
+trait Speak {}
+struct Dog;
+impl Speak for Dog {}
+impl Dog {
+    fn talk_with<T: Speak>(&self, other: T) {}
+}
+
+fn main() { Dog.talk_with$0(Dog); }
+"#,
+        expect![[r#"
+            *talk_with*
+
+            ```rust
+            test::Dog
+            ```
+
+            ```rust
+            fn talk_with<T>(&self, other: T)
+            where
+                T: Speak,
+            ```
+            ___
+
+            ```rust
+            // at this call
+            where
+                Dog: Speak, // holds
+            ```
+        "#]],
+    );
+}
+
 #[test]
 fn hover_shows_fn_signature_on_fn_name() {
     check(
@@ -429,6 +499,34 @@ fn main() { }
     );
 }
 
+#[test]
+fn hover_shows_deprecation() {
+    check(
+        r#"
+/// Does foo.
+#[deprecated(since = "1.2.0", note = "use `bar` instead")]
+pub fn foo$0() {}
+"#,
+        expect![[r#"
+            *foo*
+
+            ```rust
+            test
+            ```
+
+            ```rust
+            pub fn foo()
+            ```
+
+            ---
+
+            *Deprecated since 1.2.0: use `bar` instead*
+
+            Does foo.
+        "#]],
+    );
+}
+
 #[test]
 fn hover_shows_fn_doc() {
     check(
@@ -682,7 +780,7 @@ enum Option<T> {
                 ```
 
                 ```rust
-                None
+                None = 0
                 ```
 
                 ---
@@ -719,6 +817,52 @@ fn main() {
     );
 }
 
+#[test]
+fn hover_enum_variant_discriminant() {
+    check(
+        r#"
+#[repr(u8)]
+enum Flag {
+    A = 1 << 2,
+    B,
+}
+fn main() {
+    let _ = Flag::B$0;
+}
+"#,
+        expect![[r#"
+                *B*
+
+                ```rust
+                test::Flag
+                ```
+
+                ```rust
+                B = 5
+                ```
+            "#]],
+    );
+    check(
+        r#"
+enum Shape {
+    Circle(u32),
+    Poin$0t,
+}
+"#,
+        expect![[r#"
+                *Point*
+
+                ```rust
+                test::Shape
+                ```
+
+                ```rust
+                Point
+                ```
+            "#]],
+    );
+}
+
 #[test]
 fn hover_for_local_variable() {
     check(
@@ -3028,6 +3172,64 @@ mod Foo$0 {
     );
 }
 
+#[test]
+fn hover_doc_include_str() {
+    check(
+        r#"
+//- /src/lib.rs
+#[doc = include_str!("../README.md")]
+/// More docs.
+pub struct Foo$0;
+//- /README.md
+Docs from a file.
+"#,
+        expect![[r#"
+            *Foo*
+
+            ```rust
+            test
+            ```
+
+            ```rust
+            pub struct Foo
+            ```
+
+            ---
+
+            Docs from a file.
+            More docs.
+        "#]],
+    );
+}
+
+#[test]
+fn hover_doc_include_str_with_env() {
+    check(
+        r#"
+//- /lib.rs env:OUT_DIR=/out
+#[doc = include_str!(concat!(env!("OUT_DIR"), "/docs.md"))]
+pub struct Foo$0;
+//- /out/docs.md
+Generated docs.
+"#,
+        expect![[r#"
+            *Foo*
+
+            ```rust
+            test
+            ```
+
+            ```rust
+            pub struct Foo
+            ```
+
+            ---
+
+            Generated docs.
+        "#]],
+    );
+}
+
 #[test]
 fn hover_doc_outer_inner_attribue() {
     check(
@@ -3399,6 +3601,46 @@ impl<const LEN: usize> Foo<LEN$0> {}
     );
 }
 
+#[test]
+fn hover_const_generic_default() {
+    check(
+        r#"
+struct ArrayVec<T, const N: usize = 16>(T);
+impl<T, const N: usize> ArrayVec<T, N> {
+    fn new(t: T) -> Self { ArrayVec(t) }
+}
+fn main() {
+    let v$0: ArrayVec<u8> = ArrayVec::new(0);
+}
+"#,
+        expect![[r#"
+                *v*
+
+                ```rust
+                let v: ArrayVec<u8>
+                ```
+            "#]],
+    );
+    check(
+        r#"
+struct ArrayVec<T, const N: usize = 16>(T);
+impl<T, const N: usize> ArrayVec<T, N> {
+    fn new(t: T) -> Self { ArrayVec(t) }
+}
+fn main() {
+    let v$0: ArrayVec<u8, { 8 * 4 }> = ArrayVec::new(0);
+}
+"#,
+        expect![[r#"
+                *v*
+
+                ```rust
+                let v: ArrayVec<u8, 32>
+                ```
+            "#]],
+    );
+}
+
 #[test]
 fn hover_const_eval() {
     // show hex for <10
@@ -4633,3 +4875,214 @@ pub struct Type;
         "#]],
     );
 }
+
+#[test]
+fn hover_cfg() {
+    check(
+        r#"
+//- /lib.rs cfg:test
+#[cfg(all(test, not(unix$0)))]
+fn f() {}
+"#,
+        expect![[r#"
+            *unix*
+            ```
+            cfg(all(test, not(unix)))
+            ```
+            ___
+
+            Enabled for the active configuration.
+        "#]],
+    );
+    check(
+        r#"
+//- /lib.rs cfg:test
+#[cfg_attr(feature = "fo$0o", derive(Debug))]
+struct S;
+"#,
+        expect![[r#"
+            *"foo"*
+            ```
+            cfg_attr(feature = "foo", derive(Debug))
+            ```
+            ___
+
+            Disabled for the active configuration: feature = "foo" is disabled.
+        "#]],
+    );
+}
+
+#[test]
+fn hover_env_var() {
+    check(
+        r#"
+//- /main.rs env:CARGO_PKG_VERSION=0.1.0
+#[rustc_builtin_macro]
+macro_rules! env { () => {} }
+#[rustc_builtin_macro]
+macro_rules! option_env { () => {} }
+#[rustc_builtin_macro]
+macro_rules! concat { () => {} }
+const VERSION: &str = concat!("v", env!("CARGO_PKG_$0VERSION"));
+"#,
+        expect![[r#"
+            *"CARGO_PKG_VERSION"*
+            ```
+            env!("CARGO_PKG_VERSION")
+            ```
+            ___
+
+            Evaluates to `"0.1.0"`.
+        "#]],
+    );
+    check(
+        r#"
+#[rustc_builtin_macro]
+macro_rules! option_env { () => {} }
+const HASH: Option<&str> = option_env!("GIT_$0HASH");
+"#,
+        expect![[r#"
+            *"GIT_HASH"*
+            ```
+            option_env!("GIT_HASH")
+            ```
+            ___
+
+            Evaluates to `None`, the variable is not set for this crate.
+        "#]],
+    );
+}
+
+#[test]
+fn hover_format_string_capture() {
+    check(
+        r#"
+macro_rules! format_args {
+    ($lit:literal $($tt:tt)*) => { 0 };
+}
+fn main() {
+    let local = 0;
+    format_args!("{loc$0al:?}");
+}
+"#,
+        expect![[r#"
+            *local*
+
+            ```rust
+            let local: i32
+            ```
+        "#]],
+    );
+}
+
+#[test]
+fn hover_int_literal() {
+    check(
+        r#"
+fn f() {
+    let x: u8 = 25$05;
+}
+"#,
+        expect![[r#"
+            *255*
+            ```rust
+            u8
+            ```
+            ___
+
+            ```text
+            dec: 255
+            hex: 0xFF
+            oct: 0o377
+            bin: 0b1111_1111
+            ```
+        "#]],
+    );
+    check(
+        r#"
+fn f() {
+    let x = -1$0i16;
+}
+"#,
+        expect![[r#"
+            *1i16*
+            ```rust
+            i16
+            ```
+            ___
+
+            ```text
+            dec: -1
+            hex: 0xFFFF
+            oct: 0o177777
+            bin: 0b1111_1111_1111_1111
+            ```
+        "#]],
+    );
+    check(
+        r#"
+fn f() {
+    let x: usize = 0x1$00;
+}
+"#,
+        expect![[r#"
+            *0x10*
+            ```rust
+            usize
+            ```
+            ___
+
+            ```text
+            dec: 16
+            hex: 0x10
+            oct: 0o20
+            bin: 0b1_0000
+            ```
+        "#]],
+    );
+}
+
+#[test]
+fn hover_const_bit_flags() {
+    check(
+        r#"
+struct Perms;
+impl Perms {
+    const READ: u32 = 1;
+    const WRITE: u32 = 1 << 1;
+    const EXEC: u32 = 0b100;
+    const ALL$0: u32 = Self::READ | Self::WRITE | Self::EXEC | 8;
+}
+"#,
+        expect![[r#"
+            *ALL*
+
+            ```rust
+            test
+            ```
+
+            ```rust
+            const ALL: u32 = 15 (0xF) // READ | WRITE | EXEC | 0x8
+            ```
+        "#]],
+    );
+    check(
+        r#"
+const READ: u8 = 1;
+const WRITE: u8 = 2;
+const OTHER: u16 = 4;
+const READ_WRITE$0: u8 = READ | WRITE;
+"#,
+        expect![[r#"
+            *READ_WRITE*
+
+            ```rust
+            test
+            ```
+
+            ```rust
+            const READ_WRITE: u8 = 3 // READ | WRITE
+            ```
+        "#]],
+    );
+}
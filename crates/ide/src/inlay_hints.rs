@@ -1,5 +1,5 @@
 use either::Either;
-use hir::{known, Callable, HasVisibility, HirDisplay, Semantics, TypeInfo};
+use hir::{known, Callable, HasAttrs, HasVisibility, HirDisplay, Semantics, TypeInfo};
 use ide_db::{
     base_db::FileRange, famous_defs::FamousDefs, syntax_helpers::node_ext::walk_ty, RootDatabase,
 };
@@ -23,6 +23,8 @@ pub struct InlayHintsConfig {
     pub closure_return_type_hints: bool,
     pub lifetime_elision_hints: LifetimeElisionHints,
     pub param_names_for_lifetime_elision_hints: bool,
+    pub discriminant_hints: DiscriminantHints,
+    pub await_point_hints: bool,
     pub hide_named_constructor_hints: bool,
     pub max_length: Option<usize>,
 }
@@ -34,6 +36,13 @@ pub enum LifetimeElisionHints {
     Never,
 }
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DiscriminantHints {
+    Always,
+    Never,
+    Fieldless,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum InlayKind {
     ImplicitReborrow,
@@ -43,6 +52,8 @@ pub enum InlayKind {
     ChainingHint,
     GenericParamListHint,
     LifetimeHint,
+    DiscriminantHint,
+    AwaitPointHint,
 }
 
 #[derive(Debug)]
@@ -68,6 +79,8 @@ pub struct InlayHint {
 // * return types of closure expressions with blocks
 // * elided lifetimes
 // * compiler inserted reborrows
+// * discriminants of enum variants
+// * `.await` points, where an async function may suspend
 //
 // |===
 // | Editor  | Action Name
@@ -123,15 +136,64 @@ fn hints(
             // We could show reborrows for all expressions, but usually that is just noise to the user
             // and the main point here is to show why "moving" a mutable reference doesn't necessarily move it
             ast::Expr::PathExpr(_) => reborrow_hints(hints, sema, config, &expr),
+            ast::Expr::AwaitExpr(it) => await_point_hints(hints, config, it),
             _ => None,
         };
     } else if let Some(it) = ast::IdentPat::cast(node.clone()) {
         bind_pat_hints(hints, sema, config, &it);
+    } else if let Some(it) = ast::Variant::cast(node.clone()) {
+        discriminant_hints(hints, sema, config, &it);
     } else if let Some(it) = ast::Fn::cast(node) {
         lifetime_hints(hints, config, it);
     }
 }
 
+fn discriminant_hints(
+    acc: &mut Vec<InlayHint>,
+    sema: &Semantics<RootDatabase>,
+    config: &InlayHintsConfig,
+    variant: &ast::Variant,
+) -> Option<()> {
+    if config.discriminant_hints == DiscriminantHints::Never {
+        return None;
+    }
+    // An explicit literal already shows the value.
+    let range = match variant.expr() {
+        Some(ast::Expr::Literal(_)) => return None,
+        Some(expr) => expr.syntax().text_range(),
+        None => variant.name()?.syntax().text_range(),
+    };
+    let def = sema.to_def(variant)?;
+    if config.discriminant_hints == DiscriminantHints::Fieldless
+        && def.parent_enum(sema.db).is_data_carrying(sema.db)
+    {
+        return None;
+    }
+    let value = def.eval(sema.db).ok()?;
+    acc.push(InlayHint {
+        range,
+        kind: InlayKind::DiscriminantHint,
+        label: format!("= {}", value).into(),
+    });
+    Some(())
+}
+
+fn await_point_hints(
+    acc: &mut Vec<InlayHint>,
+    config: &InlayHintsConfig,
+    await_expr: ast::AwaitExpr,
+) -> Option<()> {
+    if !config.await_point_hints {
+        return None;
+    }
+    acc.push(InlayHint {
+        range: await_expr.await_token()?.text_range(),
+        kind: InlayKind::AwaitPointHint,
+        label: "yield point".into(),
+    });
+    Some(())
+}
+
 fn lifetime_hints(
     acc: &mut Vec<InlayHint>,
     config: &InlayHintsConfig,
@@ -456,19 +518,22 @@ fn param_name_hints(
     }
 
     let (callable, arg_list) = get_callable(sema, &expr)?;
+    let field_names = tuple_ctor_field_names(sema, &callable);
     let hints = callable
         .params(sema.db)
         .into_iter()
         .zip(arg_list.args())
-        .filter_map(|((param, _ty), arg)| {
+        .enumerate()
+        .filter_map(|(idx, ((param, _ty), arg))| {
             // Only annotate hints for expressions that exist in the original file
             let range = sema.original_range_opt(arg.syntax())?;
-            let param_name = match param? {
-                Either::Left(_) => "self".to_string(),
-                Either::Right(pat) => match pat {
+            let param_name = match param {
+                Some(Either::Left(_)) => "self".to_string(),
+                Some(Either::Right(pat)) => match pat {
                     ast::Pat::IdentPat(it) => it.name()?.to_string(),
                     _ => return None,
                 },
+                None => field_names.get(idx)?.clone()?,
             };
             Some((param_name, arg, range))
         })
@@ -485,6 +550,30 @@ fn param_name_hints(
     Some(())
 }
 
+/// The names of the fields of a tuple struct or variant constructor. Tuple fields don't have names
+/// of their own, so they are taken from doc comments like `/// red` or `/// red: the red channel`.
+fn tuple_ctor_field_names(
+    sema: &Semantics<RootDatabase>,
+    callable: &Callable,
+) -> Vec<Option<String>> {
+    let fields = match callable.kind() {
+        hir::CallableKind::TupleStruct(it) => it.fields(sema.db),
+        hir::CallableKind::TupleEnumVariant(it) => it.fields(sema.db),
+        hir::CallableKind::Function(_) | hir::CallableKind::Closure => return Vec::new(),
+    };
+    fields
+        .into_iter()
+        .map(|field| {
+            let docs = field.docs(sema.db)?;
+            let first_line = docs.as_str().lines().next()?;
+            let name = first_line.split_once(':').map_or(first_line, |(name, _)| name).trim();
+            let is_ident = name.starts_with(|c: char| c == '_' || c.is_alphabetic())
+                && name.chars().all(|c| c == '_' || c.is_alphanumeric());
+            is_ident.then(|| name.to_string())
+        })
+        .collect()
+}
+
 fn bind_pat_hints(
     acc: &mut Vec<InlayHint>,
     sema: &Semantics<RootDatabase>,
@@ -856,6 +945,8 @@ mod tests {
         closure_return_type_hints: false,
         reborrow_hints: false,
         param_names_for_lifetime_elision_hints: false,
+        discriminant_hints: DiscriminantHints::Never,
+        await_point_hints: false,
         max_length: None,
     };
     const TEST_CONFIG: InlayHintsConfig = InlayHintsConfig {
@@ -1065,6 +1156,39 @@ fn main() {
         )
     }
 
+    #[test]
+    fn param_hints_for_tuple_ctors_with_documented_fields() {
+        check_params(
+            r#"
+struct Color(
+    /// r
+    u8,
+    /// g: the green channel
+    u8,
+    /// The blue channel.
+    u8,
+);
+enum Shape {
+    Rect(
+        /// width
+        u32,
+        /// height
+        u32,
+    ),
+}
+struct Undocumented(u32, u32);
+fn main() {
+    Color(255, 0, 0);
+        //^^^ r
+             //^ g
+    Shape::Rect(1, 2);
+              //^ width
+                 //^ height
+    Undocumented(1, 2);
+}"#,
+        );
+    }
+
     #[test]
     fn function_call_parameter_hint() {
         check_params(
@@ -2042,6 +2166,63 @@ fn main() {
         );
     }
 
+    #[test]
+    fn hints_discriminants() {
+        check_with_config(
+            InlayHintsConfig {
+                discriminant_hints: DiscriminantHints::Fieldless,
+                ..DISABLED_CONFIG
+            },
+            r#"
+const BASE: u8 = 4;
+#[repr(u8)]
+enum Flag {
+    A,
+  //^ = 0
+    B = 2,
+    C,
+  //^ = 3
+    D = BASE << 1,
+      //^^^^^^^^^ = 8
+}
+enum Shape {
+    Circle(u32),
+    Point,
+}
+"#,
+        );
+        check_with_config(
+            InlayHintsConfig { discriminant_hints: DiscriminantHints::Always, ..DISABLED_CONFIG },
+            r#"
+enum Shape {
+    Circle(u32),
+  //^^^^^^ = 0
+    Point,
+  //^^^^^ = 1
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn hints_await_points() {
+        check_with_config(
+            InlayHintsConfig { await_point_hints: true, ..DISABLED_CONFIG },
+            r#"
+//- minicore: future
+async fn foo() {}
+async fn bar() {
+    foo().await;
+        //^^^^^ yield point
+    let _ = async {
+        foo().await
+            //^^^^^ yield point
+    };
+}
+"#,
+        );
+    }
+
     #[test]
     fn hints_lifetimes() {
         check(
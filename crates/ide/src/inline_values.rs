@@ -0,0 +1,143 @@
+use hir::{ScopeDef, Semantics};
+use ide_db::{
+    base_db::{FileId, SourceDatabase},
+    defs::Definition,
+    search::SearchScope,
+    RootDatabase,
+};
+use rustc_hash::FxHashSet;
+use syntax::{AstNode, TextRange, TextSize};
+
+use crate::navigation_target::TryToNav;
+
+/// A place in the source where a debugger can show the value of the local `name`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InlineValue {
+    pub range: TextRange,
+    pub name: String,
+}
+
+// Feature: Inline Values
+//
+// When a debugger stops, shows the values of the locals in scope next to their bindings and
+// uses, up to the line the debugger stopped at.
+pub(crate) fn inline_values(
+    db: &RootDatabase,
+    file_id: FileId,
+    range: TextRange,
+    stopped_at: TextRange,
+) -> Vec<InlineValue> {
+    let sema = Semantics::new(db);
+    let file = sema.parse(file_id);
+    let node = match file.syntax().token_at_offset(stopped_at.start()).right_biased() {
+        Some(token) => token.parent().unwrap_or_else(|| file.syntax().clone()),
+        None => return Vec::new(),
+    };
+    let scope = sema.scope_at_offset(&node, stopped_at.start());
+
+    // A debugger looks values up by name, so only the innermost of shadowed locals has one.
+    let mut names = FxHashSet::default();
+    let mut locals = Vec::new();
+    scope.process_all_names(&mut |name, def| {
+        if let ScopeDef::Local(local) = def {
+            if names.insert(name) {
+                locals.push(local);
+            }
+        }
+    });
+
+    let text = db.file_text(file_id);
+    let line_end = text[usize::from(stopped_at.end())..]
+        .find('\n')
+        .map_or(TextSize::of(&*text), |it| stopped_at.end() + TextSize::from(it as u32));
+
+    let mut res = Vec::new();
+    for local in locals {
+        let name = local.name(db).to_string();
+        let def = Definition::Local(local);
+        let decl =
+            def.try_to_nav(db).filter(|nav| nav.file_id == file_id).and_then(|nav| nav.focus_range);
+        let usages = def
+            .usages(&sema)
+            .set_scope(Some(SearchScope::single_file(file_id)))
+            .all()
+            .references
+            .remove(&file_id)
+            .unwrap_or_default();
+        res.extend(
+            decl.into_iter()
+                .chain(usages.into_iter().map(|it| it.range))
+                .filter(|it| range.contains_range(*it) && it.end() <= line_end)
+                .map(|range| InlineValue { range, name: name.clone() }),
+        );
+    }
+    res.sort_by_key(|it| it.range.start());
+    res.dedup();
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use syntax::{TextRange, TextSize};
+
+    use crate::fixture;
+
+    fn check(ra_fixture: &str) {
+        let (analysis, position, annotations) = fixture::annotations(ra_fixture);
+        let text = analysis.file_text(position.file_id).unwrap();
+        let values = analysis
+            .inline_values(
+                position.file_id,
+                TextRange::up_to(TextSize::of(&*text)),
+                TextRange::empty(position.offset),
+            )
+            .unwrap()
+            .into_iter()
+            .map(|it| (it.range, it.name))
+            .collect::<Vec<_>>();
+        let expected =
+            annotations.into_iter().map(|(range, name)| (range.range, name)).collect::<Vec<_>>();
+        assert_eq!(values, expected);
+    }
+
+    #[test]
+    fn locals_up_to_the_stopped_line() {
+        check(
+            r#"
+fn f(a: i32) -> i32 {
+   //^ a
+    let b = a + 1;
+      //^ b ^ a
+    let c = b * 2;
+      //^ c ^ b
+    $0let d = c;
+          //^ c
+    let e = d;
+    b + e
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn only_innermost_shadowed_local() {
+        check(
+            r#"
+fn f() {
+    let x = 1;
+    let y = x;
+      //^ y
+    let x = "";
+      //^ x
+    {
+        let z = x;
+          //^ z ^ x
+        $0z;
+      //^ z
+    }
+    y;
+}
+"#,
+        );
+    }
+}
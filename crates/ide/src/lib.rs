@@ -20,15 +20,21 @@ mod fixture;
 
 mod markup;
 mod prime_caches;
+mod public_api;
 mod navigation_target;
 
 mod annotations;
+mod batch_assist;
 mod call_hierarchy;
+mod dead_code;
 mod signature_help;
 mod doc_links;
+mod document_colors;
 mod highlight_related;
 mod expand_macro;
+mod explain_impl;
 mod extend_selection;
+mod feature_usages;
 mod file_structure;
 mod fn_references;
 mod folding_ranges;
@@ -38,12 +44,15 @@ mod goto_implementation;
 mod goto_type_definition;
 mod hover;
 mod inlay_hints;
+mod inline_values;
 mod join_lines;
+mod linked_editing;
 mod markdown_remove;
 mod matching_brace;
 mod moniker;
 mod move_item;
 mod parent_module;
+mod reindent;
 mod references;
 mod rename;
 mod runnables;
@@ -52,13 +61,18 @@ mod static_index;
 mod status;
 mod syntax_highlighting;
 mod syntax_tree;
+mod trait_impl_matrix;
+mod type_of_range;
 mod typing;
+mod unsafe_report;
+mod view_c_header;
 mod view_crate_graph;
 mod view_hir;
 mod view_item_tree;
+mod view_trait_solver_overflows;
 mod shuffle_crate_graph;
 
-use std::sync::Arc;
+use std::{ops::ControlFlow, panic::AssertUnwindSafe, sync::Arc};
 
 use cfg::CfgOptions;
 use ide_db::{
@@ -74,19 +88,29 @@ use crate::navigation_target::{ToNav, TryToNav};
 
 pub use crate::{
     annotations::{Annotation, AnnotationConfig, AnnotationKind},
+    batch_assist::{BatchAssist, BatchAssistScope},
     call_hierarchy::CallItem,
-    expand_macro::ExpandedMacro,
+    dead_code::DeadItem,
+    document_colors::{Color, ColorConfig, ColorInformation},
+    expand_macro::{ExpandedMacro, ExpansionDocument, ExpansionId},
+    explain_impl::ImplExplanation,
+    feature_usages::{FeatureUsage, FeatureUsageKind},
     file_structure::{StructureNode, StructureNodeKind},
     folding_ranges::{Fold, FoldKind},
+    goto_implementation::ImplCrate,
     highlight_related::{HighlightRelatedConfig, HighlightedRange},
     hover::{HoverAction, HoverConfig, HoverDocFormat, HoverGotoTypeData, HoverResult},
-    inlay_hints::{InlayHint, InlayHintsConfig, InlayKind, LifetimeElisionHints},
+    inlay_hints::{
+        DiscriminantHints, InlayHint, InlayHintsConfig, InlayKind, LifetimeElisionHints,
+    },
+    inline_values::InlineValue,
     join_lines::JoinLinesConfig,
     markup::Markup,
     moniker::{MonikerKind, MonikerResult, PackageInformation},
     move_item::Direction,
     navigation_target::NavigationTarget,
     prime_caches::ParallelPrimeCachesProgress,
+    public_api::{diff_public_api, ApiDiff, ApiItem},
     references::ReferenceSearchResult,
     rename::RenameError,
     runnables::{Runnable, RunnableKind, TestId},
@@ -96,10 +120,15 @@ pub use crate::{
         tags::{Highlight, HlMod, HlMods, HlOperator, HlPunct, HlTag},
         HlRange,
     },
+    trait_impl_matrix::{TraitImplMatrix, TraitImplRow},
+    type_of_range::TypeOfRange,
+    unsafe_report::{UnsafeItem, UnsafeItemKind, UnsafeOperation},
+    view_hir::DesugaredBody,
 };
-pub use hir::{Documentation, Semantics};
+pub use hir::{set_inference_profiling, Documentation, Semantics, DEFAULT_TRAIT_SOLVER_FUEL};
 pub use ide_assists::{
-    Assist, AssistConfig, AssistId, AssistKind, AssistResolveStrategy, SingleResolve,
+    Assist, AssistConfig, AssistId, AssistKind, AssistResolveStrategy, BoundsPlacement,
+    QualifyItemKind, SingleResolve,
 };
 pub use ide_completion::{
     CompletionConfig, CompletionItem, CompletionItemKind, CompletionRelevance, ImportEdit, Snippet,
@@ -117,7 +146,9 @@ pub use ide_db::{
     symbol_index::Query,
     RootDatabase, SymbolKind,
 };
-pub use ide_diagnostics::{Diagnostic, DiagnosticsConfig, ExprFillDefaultMode, Severity};
+pub use ide_diagnostics::{
+    Diagnostic, DiagnosticCode, DiagnosticsConfig, ExprFillDefaultMode, Severity,
+};
 pub use ide_ssr::SsrError;
 pub use syntax::{TextRange, TextSize};
 pub use text_edit::{Indel, TextEdit};
@@ -245,13 +276,43 @@ impl Analysis {
         self.with_db(|db| status::status(&*db, file_id))
     }
 
+    /// Primes the caches of the crates in the graph, stopping early once `cb`
+    /// returns `ControlFlow::Break`.
     pub fn parallel_prime_caches<F>(&self, num_worker_threads: u8, cb: F) -> Cancellable<()>
     where
-        F: Fn(ParallelPrimeCachesProgress) + Sync + std::panic::UnwindSafe,
+        F: Fn(ParallelPrimeCachesProgress) -> ControlFlow<()> + Sync + std::panic::UnwindSafe,
     {
         self.with_db(move |db| prime_caches::parallel_prime_caches(db, num_worker_threads, &cb))
     }
 
+    /// Finds the `cfg` predicates of the workspace mentioning a Cargo feature.
+    pub fn feature_usages(&self, feature: &str) -> Cancellable<Vec<FeatureUsage>> {
+        self.with_db(|db| feature_usages::feature_usages(db, feature))
+    }
+
+    /// Infers the items of a file and the functions they call, to make the
+    /// first requests on a freshly opened file fast.
+    pub fn prime_caches_for_file(&self, file_id: FileId) -> Cancellable<()> {
+        self.with_db(|db| prime_caches::prime_caches_for_file(db, file_id))
+    }
+
+    /// Finds the crate-private items of the workspace which are never used,
+    /// searching several crates at once.
+    pub fn dead_code(&self, num_worker_threads: u8) -> Cancellable<Vec<DeadItem>> {
+        self.with_db(|db| dead_code::dead_code(db, num_worker_threads))
+    }
+
+    /// Lists the unsafe functions, traits, impls and blocks of the workspace,
+    /// with the operations which need `unsafe` in them.
+    pub fn unsafe_report(&self) -> Cancellable<Vec<UnsafeItem>> {
+        self.with_db(|db| unsafe_report::unsafe_report(db))
+    }
+
+    /// Lists the public items of a crate, sorted by path.
+    pub fn public_api(&self, crate_id: CrateId) -> Cancellable<Vec<ApiItem>> {
+        self.with_db(|db| public_api::public_api(db, crate_id))
+    }
+
     /// Gets the text of the source file.
     pub fn file_text(&self, file_id: FileId) -> Cancellable<Arc<String>> {
         self.with_db(|db| db.file_text(file_id))
@@ -303,10 +364,23 @@ impl Analysis {
         self.with_db(|db| view_hir::view_hir(db, position))
     }
 
+    pub fn view_desugared_body(&self, position: FilePosition) -> Cancellable<DesugaredBody> {
+        self.with_db(|db| view_hir::view_desugared_body(db, position))
+    }
+
     pub fn view_item_tree(&self, file_id: FileId) -> Cancellable<String> {
         self.with_db(|db| view_item_tree::view_item_tree(db, file_id))
     }
 
+    /// Renders the `#[repr(C)]` types and `extern "C"` functions of the crate as C declarations.
+    pub fn view_c_header(&self, file_id: FileId) -> Cancellable<String> {
+        self.with_db(|db| view_c_header::view_c_header(db, file_id))
+    }
+
+    pub fn view_trait_solver_overflows(&self, position: FilePosition) -> Cancellable<String> {
+        self.with_db(|db| view_trait_solver_overflows::view_trait_solver_overflows(db, position))
+    }
+
     /// Renders the crate graph to GraphViz "dot" syntax.
     pub fn view_crate_graph(&self, full: bool) -> Cancellable<Result<String, String>> {
         self.with_db(|db| view_crate_graph::view_crate_graph(db, full))
@@ -316,6 +390,20 @@ impl Analysis {
         self.with_db(|db| expand_macro::expand_macro(db, position))
     }
 
+    /// Returns the id of the macro expansion at `position`, which keeps referring to it while the
+    /// file is edited.
+    pub fn expansion_id(&self, position: FilePosition) -> Cancellable<Option<ExpansionId>> {
+        self.with_db(|db| expand_macro::expansion_id(db, position))
+    }
+
+    pub fn expansion_document(
+        &self,
+        file_id: FileId,
+        id: &ExpansionId,
+    ) -> Cancellable<Option<ExpansionDocument>> {
+        self.with_db(|db| expand_macro::expansion_document(db, file_id, id))
+    }
+
     /// Returns an edit to remove all newlines in the range, cleaning up minor
     /// stuff like trailing commas.
     pub fn join_lines(&self, config: &JoinLinesConfig, frange: FileRange) -> Cancellable<TextEdit> {
@@ -325,6 +413,14 @@ impl Analysis {
         })
     }
 
+    /// Returns an edit which fixes up the indentation of the lines in the range.
+    pub fn reindent(&self, frange: FileRange) -> Cancellable<TextEdit> {
+        self.with_db(|db| {
+            let parse = db.parse(frange.file_id);
+            reindent::reindent(&parse.tree(), frange.range)
+        })
+    }
+
     /// Returns an edit which should be applied when opening a new line, fixing
     /// up minor stuff like continuing the comment.
     /// The edit will be a snippet (with `$0`).
@@ -364,6 +460,43 @@ impl Analysis {
         self.with_db(|db| inlay_hints::inlay_hints(db, file_id, range, config))
     }
 
+    /// Returns the colors written in the file.
+    pub fn document_colors(
+        &self,
+        config: &ColorConfig,
+        file_id: FileId,
+    ) -> Cancellable<Vec<ColorInformation>> {
+        self.with_db(|db| document_colors::document_colors(db, config, file_id))
+    }
+
+    /// Returns the ways to write `color` in place of the color at `frange`.
+    pub fn color_presentations(
+        &self,
+        config: &ColorConfig,
+        frange: FileRange,
+        color: Color,
+    ) -> Cancellable<Vec<String>> {
+        self.with_db(|db| document_colors::color_presentations(db, config, frange, color))
+    }
+
+    /// Returns the places where a debugger stopped at `stopped_at` can show the values of locals.
+    pub fn inline_values(
+        &self,
+        file_id: FileId,
+        range: TextRange,
+        stopped_at: TextRange,
+    ) -> Cancellable<Vec<InlineValue>> {
+        self.with_db(|db| inline_values::inline_values(db, file_id, range, stopped_at))
+    }
+
+    /// Returns the ranges to edit together with the parameter name at `position`.
+    pub fn linked_editing_ranges(
+        &self,
+        position: FilePosition,
+    ) -> Cancellable<Option<Vec<TextRange>>> {
+        self.with_db(|db| linked_editing::linked_editing_ranges(db, position))
+    }
+
     /// Returns the set of folding ranges.
     pub fn folding_ranges(&self, file_id: FileId) -> Cancellable<Vec<Fold>> {
         self.with_db(|db| folding_ranges::folding_ranges(&db.parse(file_id).tree()))
@@ -379,6 +512,21 @@ impl Analysis {
         })
     }
 
+    /// Fuzzy searches for a symbol, handing the matches to `sink` one crate at
+    /// a time, until it returns `true`.
+    pub fn symbol_search_by_crate(
+        &self,
+        query: Query,
+        sink: &mut dyn FnMut(Vec<NavigationTarget>) -> bool,
+    ) -> Cancellable<()> {
+        Cancelled::catch(AssertUnwindSafe(|| {
+            let db = &self.db;
+            symbol_index::world_symbols_by_crate(db, query, &mut |symbols| {
+                sink(symbols.into_iter().filter_map(|s| s.try_to_nav(db)).collect())
+            })
+        }))
+    }
+
     /// Returns the definitions from the symbol at `position`.
     pub fn goto_definition(
         &self,
@@ -403,6 +551,23 @@ impl Analysis {
         self.with_db(|db| goto_implementation::goto_implementation(db, position))
     }
 
+    /// Returns the crates which can contain impls of the trait at `position`.
+    pub fn implementing_crates(
+        &self,
+        position: FilePosition,
+    ) -> Cancellable<Option<Vec<ImplCrate>>> {
+        self.with_db(|db| goto_implementation::implementing_crates(db, position))
+    }
+
+    /// Returns the impls of the trait at `position` in one crate.
+    pub fn trait_impls_in_crate(
+        &self,
+        position: FilePosition,
+        crate_id: CrateId,
+    ) -> Cancellable<Option<Vec<NavigationTarget>>> {
+        self.with_db(|db| goto_implementation::trait_impls_in_crate(db, position, crate_id))
+    }
+
     /// Returns the type definitions for the symbol at `position`.
     pub fn goto_type_definition(
         &self,
@@ -420,6 +585,23 @@ impl Analysis {
         self.with_db(|db| references::find_all_refs(&Semantics::new(db), position, search_scope))
     }
 
+    /// Finds all usages of the reference at point, handing them to `sink` one
+    /// crate at a time, until it returns `true`.
+    ///
+    /// The first `skip` batches are left out. Returns the number of batches
+    /// covered, which is the `skip` to pick the search up where it stopped.
+    pub fn find_all_refs_by_crate(
+        &self,
+        position: FilePosition,
+        skip: usize,
+        sink: &mut dyn FnMut(ReferenceSearchResult) -> bool,
+    ) -> Cancellable<Option<usize>> {
+        // `sink` only sees complete batches, so unwinding can't leave it half-updated.
+        Cancelled::catch(AssertUnwindSafe(|| {
+            references::find_all_refs_by_crate(&Semantics::new(&self.db), position, skip, sink)
+        }))
+    }
+
     /// Finds all methods and free functions for the file. Does not return tests!
     pub fn find_all_methods(&self, file_id: FileId) -> Cancellable<Vec<FileRange>> {
         self.with_db(|db| fn_references::find_all_methods(db, file_id))
@@ -434,6 +616,28 @@ impl Analysis {
         self.with_db(|db| hover::hover(db, range, config))
     }
 
+    /// Returns the inferred type of the smallest expression or pattern covering `range`.
+    pub fn type_of_range(&self, range: FileRange) -> Cancellable<Option<RangeInfo<TypeOfRange>>> {
+        self.with_db(|db| type_of_range::type_of_range(db, range))
+    }
+
+    /// Explains why the type at `range` does or doesn't implement the trait at `trait_path`.
+    pub fn explain_impl(
+        &self,
+        range: FileRange,
+        trait_path: &str,
+    ) -> Cancellable<Result<ImplExplanation, String>> {
+        self.with_db(|db| explain_impl::explain_impl(db, range, trait_path))
+    }
+
+    /// Shows which of the commonly expected traits the type at `position` implements.
+    pub fn trait_impl_matrix(
+        &self,
+        position: FilePosition,
+    ) -> Cancellable<Option<TraitImplMatrix>> {
+        self.with_db(|db| trait_impl_matrix::trait_impl_matrix(db, position))
+    }
+
     /// Returns moniker of symbol at position.
     pub fn moniker(
         &self,
@@ -468,6 +672,18 @@ impl Analysis {
         self.with_db(|db| call_hierarchy::incoming_calls(db, position))
     }
 
+    /// Computes incoming calls for the given file position, handing them to
+    /// `sink` one crate at a time, until it returns `true`.
+    pub fn incoming_calls_by_crate(
+        &self,
+        position: FilePosition,
+        sink: &mut dyn FnMut(Vec<CallItem>) -> bool,
+    ) -> Cancellable<()> {
+        Cancelled::catch(AssertUnwindSafe(|| {
+            call_hierarchy::incoming_calls_by_crate(&self.db, position, sink)
+        }))
+    }
+
     /// Computes outgoing calls for the given file position.
     pub fn outgoing_calls(&self, position: FilePosition) -> Cancellable<Option<Vec<CallItem>>> {
         self.with_db(|db| call_hierarchy::outgoing_calls(db, position))
@@ -600,6 +816,28 @@ impl Analysis {
         })
     }
 
+    /// Applies the assist or diagnostic fix with the given id at every position
+    /// it is applicable to in `scope`, as a single change.
+    pub fn batch_assist(
+        &self,
+        assist_config: &AssistConfig,
+        diagnostics_config: &DiagnosticsConfig,
+        assist_id: &str,
+        scope: BatchAssistScope,
+        file_id: FileId,
+    ) -> Cancellable<BatchAssist> {
+        self.with_db(|db| {
+            batch_assist::batch_assist(
+                db,
+                assist_config,
+                diagnostics_config,
+                assist_id,
+                scope,
+                file_id,
+            )
+        })
+    }
+
     /// Returns the edit required to rename reference at the position to the new
     /// name.
     pub fn rename(
@@ -0,0 +1,111 @@
+use hir::{GenericParam, Semantics};
+use ide_db::{
+    base_db::FilePosition,
+    defs::{Definition, IdentClass},
+    search::{FileReference, SearchScope},
+    RootDatabase,
+};
+use syntax::{AstNode, SyntaxKind::*, TextRange};
+
+use crate::navigation_target::TryToNav;
+
+// Feature: Linked Editing
+//
+// Editing the name of a generic or lifetime parameter also edits all its uses in the item,
+// without going through a rename.
+pub(crate) fn linked_editing_ranges(
+    db: &RootDatabase,
+    position: FilePosition,
+) -> Option<Vec<TextRange>> {
+    let sema = Semantics::new(db);
+    let file = sema.parse(position.file_id);
+    let token = file
+        .syntax()
+        .token_at_offset(position.offset)
+        .find(|it| matches!(it.kind(), IDENT | LIFETIME_IDENT))?;
+
+    let def = match IdentClass::classify_token(&sema, &token)?.definitions().as_slice() {
+        [def @ Definition::GenericParam(
+            GenericParam::TypeParam(_)
+            | GenericParam::ConstParam(_)
+            | GenericParam::LifetimeParam(_),
+        )] => *def,
+        _ => return None,
+    };
+    let decl = def.try_to_nav(db).filter(|nav| nav.file_id == position.file_id)?.focus_range?;
+    let text = file.syntax().text().slice(decl).to_string();
+
+    let mut res = vec![decl];
+    let usages = def
+        .usages(&sema)
+        .set_scope(Some(SearchScope::single_file(position.file_id)))
+        .all()
+        .references
+        .remove(&position.file_id)
+        .unwrap_or_default();
+    for FileReference { range, .. } in usages {
+        // Uses spelled differently, like `r#T`, can't be edited in lockstep with the declaration.
+        if file.syntax().text().slice(range) != text.as_str() {
+            return None;
+        }
+        res.push(range);
+    }
+    res.sort_by_key(|range| range.start());
+    res.dedup();
+    Some(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixture;
+
+    fn check(ra_fixture: &str) {
+        let (analysis, position, annotations) = fixture::annotations(ra_fixture);
+        let ranges = analysis.linked_editing_ranges(position).unwrap().unwrap_or_default();
+        let mut expected =
+            annotations.into_iter().map(|(range, _)| range.range).collect::<Vec<_>>();
+        expected.sort_by_key(|range| range.start());
+        assert_eq!(ranges, expected);
+    }
+
+    #[test]
+    fn type_param() {
+        check(
+            r#"
+struct Wrapper<T$0>(T);
+             //^  ^
+impl<T> Wrapper<T> {}
+"#,
+        );
+    }
+
+    #[test]
+    fn lifetime_param_from_use() {
+        check(
+            r#"
+fn first<'a>(x: &'a str, _: &str) -> &'a$0 str { x }
+       //^^      ^^                   ^^
+"#,
+        );
+    }
+
+    #[test]
+    fn const_param() {
+        check(
+            r#"
+fn zeroes<const N$0: usize>() -> [u8; N] { [0; N] }
+              //^                   ^        ^
+"#,
+        );
+    }
+
+    #[test]
+    fn not_for_other_items() {
+        check(
+            r#"
+struct Foo$0;
+fn f(_: Foo) {}
+"#,
+        );
+    }
+}
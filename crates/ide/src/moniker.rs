@@ -15,8 +15,8 @@ use crate::{doc_links::token_as_doc_comment, RangeInfo};
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct MonikerIdentifier {
-    crate_name: String,
-    path: Vec<Name>,
+    pub crate_name: String,
+    pub path: Vec<Name>,
 }
 
 impl ToString for MonikerIdentifier {
@@ -42,10 +42,19 @@ pub struct MonikerResult {
     pub package_information: PackageInformation,
 }
 
+impl MonikerResult {
+    /// The identifier prefixed with the package and version it comes from, like
+    /// `foo@0.1.0/foo::module::func`, which stays the same across repositories depending on it.
+    pub fn versioned_identifier(&self) -> String {
+        let package = &self.package_information;
+        format!("{}@{}/{}", package.name, package.version, self.identifier.to_string())
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct PackageInformation {
     pub name: String,
-    pub repo: String,
+    pub repo: Option<String>,
     pub version: String,
 }
 
@@ -150,9 +159,9 @@ pub(crate) fn def_to_moniker(
         package_information: {
             let name = krate.display_name(db)?.to_string();
             let (repo, version) = match krate.origin(db) {
-                CrateOrigin::CratesIo { repo } => (repo?, krate.version(db)?),
+                CrateOrigin::CratesIo { repo } => (repo, krate.version(db)?),
                 CrateOrigin::Lang => (
-                    "https://github.com/rust-lang/rust/".to_string(),
+                    Some("https://github.com/rust-lang/rust/".to_string()),
                     "compiler_version".to_string(),
                 ),
                 CrateOrigin::Unknown => return None,
@@ -202,7 +211,7 @@ pub mod module {
 }
 "#,
             "foo::module::func",
-            r#"PackageInformation { name: "foo", repo: "https://a.b/foo.git", version: "0.1.0" }"#,
+            r#"PackageInformation { name: "foo", repo: Some("https://a.b/foo.git"), version: "0.1.0" }"#,
             MonikerKind::Import,
         );
         check_moniker(
@@ -218,7 +227,7 @@ pub mod module {
 }
 "#,
             "foo::module::func",
-            r#"PackageInformation { name: "foo", repo: "https://a.b/foo.git", version: "0.1.0" }"#,
+            r#"PackageInformation { name: "foo", repo: Some("https://a.b/foo.git"), version: "0.1.0" }"#,
             MonikerKind::Export,
         );
     }
@@ -235,7 +244,7 @@ pub mod module {
 }
 "#,
             "foo::module::MyTrait::func",
-            r#"PackageInformation { name: "foo", repo: "https://a.b/foo.git", version: "0.1.0" }"#,
+            r#"PackageInformation { name: "foo", repo: Some("https://a.b/foo.git"), version: "0.1.0" }"#,
             MonikerKind::Export,
         );
     }
@@ -252,7 +261,7 @@ pub mod module {
 }
 "#,
             "foo::module::MyTrait::MY_CONST",
-            r#"PackageInformation { name: "foo", repo: "https://a.b/foo.git", version: "0.1.0" }"#,
+            r#"PackageInformation { name: "foo", repo: Some("https://a.b/foo.git"), version: "0.1.0" }"#,
             MonikerKind::Export,
         );
     }
@@ -269,7 +278,7 @@ pub mod module {
 }
 "#,
             "foo::module::MyTrait::MyType",
-            r#"PackageInformation { name: "foo", repo: "https://a.b/foo.git", version: "0.1.0" }"#,
+            r#"PackageInformation { name: "foo", repo: Some("https://a.b/foo.git"), version: "0.1.0" }"#,
             MonikerKind::Export,
         );
     }
@@ -292,7 +301,7 @@ pub mod module {
 }
 "#,
             "foo::module::MyStruct::MyTrait::func",
-            r#"PackageInformation { name: "foo", repo: "https://a.b/foo.git", version: "0.1.0" }"#,
+            r#"PackageInformation { name: "foo", repo: Some("https://a.b/foo.git"), version: "0.1.0" }"#,
             MonikerKind::Export,
         );
     }
@@ -312,11 +321,48 @@ pub struct St {
 }
 "#,
             "foo::St::a",
-            r#"PackageInformation { name: "foo", repo: "https://a.b/foo.git", version: "0.1.0" }"#,
+            r#"PackageInformation { name: "foo", repo: Some("https://a.b/foo.git"), version: "0.1.0" }"#,
+            MonikerKind::Import,
+        );
+    }
+
+    #[test]
+    fn moniker_without_repository() {
+        check_moniker(
+            r#"
+//- /lib.rs crate:main deps:foo
+use foo::func;
+fn main() {
+    func$0();
+}
+//- /foo/lib.rs crate:foo@CratesIo:0.1.0
+pub fn func() {}
+"#,
+            "foo::func",
+            r#"PackageInformation { name: "foo", repo: None, version: "0.1.0" }"#,
             MonikerKind::Import,
         );
     }
 
+    #[test]
+    fn versioned_identifier() {
+        let (analysis, position) = fixture::position(
+            r#"
+//- /lib.rs crate:main deps:foo
+fn main() {
+    foo::module::func$0();
+}
+//- /foo/lib.rs crate:foo@CratesIo:0.1.0,https://a.b/foo.git
+pub mod module {
+    pub fn func() {}
+}
+"#,
+        );
+        let monikers = analysis.moniker(position).unwrap().unwrap().info;
+        assert_eq!(monikers.len(), 1);
+        assert_eq!(monikers[0].versioned_identifier(), "foo@0.1.0/foo::module::func");
+    }
+
     #[test]
     fn no_moniker_for_local() {
         no_moniker(
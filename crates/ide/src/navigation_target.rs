@@ -163,9 +163,11 @@ impl TryToNav for FileSymbol {
         let full_range = self.loc.original_range(db)?;
         let name_range = self.loc.original_name_range(db)?;
 
+        let name = if self.is_alias { self.loc.declared_name(db)? } else { self.name.clone() };
+
         Some(NavigationTarget {
             file_id: full_range.file_id,
-            name: self.name.clone(),
+            name,
             kind: Some(self.kind.into()),
             full_range: full_range.range,
             focus_range: Some(name_range.range),
@@ -288,7 +290,7 @@ impl ToNav for hir::Module {
 impl TryToNav for hir::Impl {
     fn try_to_nav(&self, db: &RootDatabase) -> Option<NavigationTarget> {
         let InFile { file_id, value } = self.source(db)?;
-        let derive_attr = self.is_builtin_derive(db);
+        let derive_attr = self.derive_attr(db);
 
         let focus_range = if derive_attr.is_some() {
             None
@@ -619,4 +621,37 @@ struct Foo;
         let navs = analysis.symbol_search(Query::new("foo".to_string())).unwrap();
         assert_eq!(navs.len(), 2)
     }
+
+    #[test]
+    fn test_world_symbols_include_doc_aliases() {
+        let (analysis, _) = fixture::file(
+            r#"
+#[doc(alias = "mmap")]
+fn memmap() {}
+#[doc(alias("VecDeque", "ring"))]
+struct RingBuffer;
+"#,
+        );
+
+        let navs = analysis.symbol_search(Query::new("mmap".to_string())).unwrap();
+        expect![[r#"
+            [
+                NavigationTarget {
+                    file_id: FileId(
+                        0,
+                    ),
+                    full_range: 1..38,
+                    focus_range: 27..33,
+                    name: "memmap",
+                    kind: Function,
+                    description: "fn memmap()",
+                },
+            ]
+        "#]]
+        .assert_debug_eq(&navs);
+
+        let navs = analysis.symbol_search(Query::new("ring".to_string())).unwrap();
+        assert_eq!(navs.len(), 1);
+        assert_eq!(navs[0].name, "RingBuffer");
+    }
 }
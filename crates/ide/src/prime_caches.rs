@@ -2,9 +2,10 @@
 //! sometimes is counter productive when, for example, the first goto definition
 //! request takes longer to compute. This modules implemented prepopulation of
 //! various caches, it's not really advanced at the moment.
+mod open_file;
 mod topologic_sort;
 
-use std::time::Duration;
+use std::{ops::ControlFlow, time::Duration};
 
 use hir::db::DefDatabase;
 use ide_db::{
@@ -18,6 +19,8 @@ use rustc_hash::FxHashSet;
 
 use crate::RootDatabase;
 
+pub(crate) use open_file::prime_caches_for_file;
+
 /// We're indexing many crates.
 #[derive(Debug)]
 pub struct ParallelPrimeCachesProgress {
@@ -32,7 +35,7 @@ pub struct ParallelPrimeCachesProgress {
 pub(crate) fn parallel_prime_caches(
     db: &RootDatabase,
     num_worker_threads: u8,
-    cb: &(dyn Fn(ParallelPrimeCachesProgress) + Sync),
+    cb: &(dyn Fn(ParallelPrimeCachesProgress) -> ControlFlow<()> + Sync),
 ) {
     let _p = profile::span("prime_caches");
 
@@ -138,7 +141,9 @@ pub(crate) fn parallel_prime_caches(
             crates_total,
         };
 
-        cb(progress);
+        if let ControlFlow::Break(()) = cb(progress) {
+            break;
+        }
     }
 }
 
@@ -157,3 +162,37 @@ fn compute_crates_to_prime(db: &RootDatabase, graph: &CrateGraph) -> FxHashSet<C
         .flat_map(|id| graph[id].dependencies.iter().map(|krate| krate.crate_id))
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use crate::fixture;
+
+    use super::*;
+
+    #[test]
+    fn breaking_from_the_callback_stops_priming() {
+        let (analysis, _) = fixture::file(
+            r#"
+//- /main.rs crate:main deps:a,b,c
+//- /a.rs crate:a
+//- /b.rs crate:b
+//- /c.rs crate:c
+"#,
+        );
+        let reports = Mutex::new(Vec::new());
+        analysis
+            .parallel_prime_caches(1, |progress| {
+                let done = progress.crates_done;
+                reports.lock().unwrap().push((done, progress.crates_total));
+                if done == 1 {
+                    ControlFlow::Break(())
+                } else {
+                    ControlFlow::Continue(())
+                }
+            })
+            .unwrap();
+        assert_eq!(reports.into_inner().unwrap(), vec![(0, 3), (1, 3)]);
+    }
+}
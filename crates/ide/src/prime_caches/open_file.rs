@@ -0,0 +1,60 @@
+//! Infers the items of a file which was just opened, and the functions they
+//! call, so that the first hover or goto on it doesn't have to wait for a cold
+//! inference cascade through its dependencies.
+
+use hir::{AssocItem, DefWithBody, Module, ModuleDef, Semantics};
+use ide_db::base_db::FileId;
+use rustc_hash::FxHashSet;
+
+use crate::RootDatabase;
+
+pub(crate) fn prime_caches_for_file(db: &RootDatabase, file_id: FileId) {
+    let _p = profile::span("prime_caches_for_file");
+    let sema = Semantics::new(db);
+
+    let mut bodies = Vec::new();
+    for module in sema.to_module_defs(file_id) {
+        collect_bodies(db, file_id, module, &mut bodies);
+    }
+
+    let mut seen: FxHashSet<DefWithBody> = bodies.iter().copied().collect();
+    let mut callees = Vec::new();
+    for def in bodies {
+        // This is where most of the time goes: inferring the body.
+        for callee in def.callees(db) {
+            if seen.insert(callee.into()) {
+                callees.push(callee);
+            }
+        }
+    }
+    // Only one level deep, what the callees call is usually not looked at.
+    for callee in callees {
+        let _ = DefWithBody::from(callee).callees(db);
+    }
+}
+
+fn collect_bodies(db: &RootDatabase, file_id: FileId, module: Module, acc: &mut Vec<DefWithBody>) {
+    let assoc_body = |item: AssocItem| match item {
+        AssocItem::Function(it) => Some(DefWithBody::from(it)),
+        AssocItem::Const(it) => Some(it.into()),
+        AssocItem::TypeAlias(_) => None,
+    };
+    for def in module.declarations(db) {
+        match def {
+            ModuleDef::Module(it) => {
+                // Inline modules only, the others are warmed up when their file is opened.
+                if it.definition_source(db).file_id.original_file(db) == file_id {
+                    collect_bodies(db, file_id, it, acc);
+                }
+            }
+            ModuleDef::Trait(it) => acc.extend(it.items(db).into_iter().filter_map(assoc_body)),
+            ModuleDef::Adt(hir::Adt::Enum(it)) => {
+                acc.extend(it.variants(db).into_iter().map(DefWithBody::from))
+            }
+            _ => acc.extend(def.as_def_with_body()),
+        }
+    }
+    for impl_ in module.impl_defs(db) {
+        acc.extend(impl_.items(db).into_iter().filter_map(assoc_body));
+    }
+}
@@ -0,0 +1,307 @@
+//! Lists the public API of a crate, to spot breaking changes between two
+//! versions of it.
+//!
+//! Items are listed where they are declared, and only if they can be named
+//! from outside of the crate through public modules. Re-exports are not
+//! followed, so items only reachable through `pub use` are missed.
+
+use hir::{Adt, AssocItem, Crate, HasVisibility, HirDisplay, Impl, Module, ModuleDef, Visibility};
+use ide_db::{base_db::CrateId, RootDatabase};
+use rustc_hash::{FxHashMap, FxHashSet};
+
+/// A public item, with the path it is reachable at.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ApiItem {
+    /// The path of the item, like `krate::module::Type::method`. Trait impls
+    /// use `impl Trait for Type` instead.
+    pub path: String,
+    /// The item as it would be rendered on hover, without its body.
+    pub signature: String,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ApiDiff {
+    pub added: Vec<ApiItem>,
+    pub removed: Vec<ApiItem>,
+    /// Items whose path stayed the same, with their old and new versions.
+    pub changed: Vec<(ApiItem, ApiItem)>,
+}
+
+impl ApiDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+pub(crate) fn public_api(db: &RootDatabase, crate_id: CrateId) -> Vec<ApiItem> {
+    let _p = profile::span("public_api");
+    let krate = Crate::from(crate_id);
+    let crate_name =
+        krate.display_name(db).map_or_else(|| "crate".to_string(), |it| it.to_string());
+
+    let mut collector = Collector { db, items: Vec::new(), public_defs: FxHashMap::default() };
+    collector.collect_module(krate.root_module(db), &crate_name);
+    for impl_ in Impl::all_in_crate(db, krate) {
+        collector.collect_impl(impl_, krate);
+    }
+
+    let mut items = collector.items;
+    items.sort();
+    items.dedup();
+    items
+}
+
+/// Compares two versions of a public API, as returned by `public_api`.
+pub fn diff_public_api(old: &[ApiItem], new: &[ApiItem]) -> ApiDiff {
+    let old_by_path: FxHashMap<&str, FxHashSet<&ApiItem>> =
+        old.iter().fold(FxHashMap::default(), |mut acc, it| {
+            acc.entry(it.path.as_str()).or_default().insert(it);
+            acc
+        });
+    let new_by_path: FxHashMap<&str, FxHashSet<&ApiItem>> =
+        new.iter().fold(FxHashMap::default(), |mut acc, it| {
+            acc.entry(it.path.as_str()).or_default().insert(it);
+            acc
+        });
+
+    let mut diff = ApiDiff::default();
+    for item in new {
+        match old_by_path.get(item.path.as_str()) {
+            None => diff.added.push(item.clone()),
+            Some(old_items) if old_items.contains(item) => (),
+            // Several items can share a path, like a function and a macro.
+            // Only call it a change if there is one of each.
+            Some(old_items)
+                if old_items.len() == 1 && new_by_path[item.path.as_str()].len() == 1 =>
+            {
+                let old_item = old_items.iter().next().unwrap();
+                diff.changed.push(((*old_item).clone(), item.clone()));
+            }
+            Some(_) => diff.added.push(item.clone()),
+        }
+    }
+    for item in old {
+        let is_changed = diff.changed.iter().any(|(old_item, _)| old_item == item);
+        let still_there =
+            new_by_path.get(item.path.as_str()).map_or(false, |new_items| new_items.contains(item));
+        if !is_changed && !still_there {
+            diff.removed.push(item.clone());
+        }
+    }
+    diff
+}
+
+struct Collector<'a> {
+    db: &'a RootDatabase,
+    items: Vec<ApiItem>,
+    /// The paths of the public items of the crate which can have impls.
+    public_defs: FxHashMap<ModuleDef, String>,
+}
+
+impl Collector<'_> {
+    fn push(&mut self, path: String, item: &impl HirDisplay) {
+        let signature = item.display(self.db).to_string();
+        self.items.push(ApiItem { path, signature });
+    }
+
+    fn collect_module(&mut self, module: Module, path: &str) {
+        let db = self.db;
+        for def in module.declarations(db) {
+            if !is_public(db, def) {
+                continue;
+            }
+            let name = match def.name(db) {
+                Some(it) => it,
+                None => continue,
+            };
+            let item_path = format!("{}::{}", path, name);
+            match def {
+                ModuleDef::Module(it) => {
+                    self.push(item_path.clone(), &it);
+                    self.collect_module(it, &item_path);
+                }
+                ModuleDef::Function(it) => self.push(item_path, &it),
+                ModuleDef::Adt(adt) => {
+                    self.push(item_path.clone(), &adt);
+                    match adt {
+                        Adt::Struct(it) => self.collect_fields(it.fields(db), &item_path),
+                        Adt::Union(it) => self.collect_fields(it.fields(db), &item_path),
+                        Adt::Enum(it) => {
+                            for variant in it.variants(db) {
+                                let variant_path = format!("{}::{}", item_path, variant.name(db));
+                                self.push(variant_path, &variant);
+                            }
+                        }
+                    }
+                    self.public_defs.insert(def, item_path);
+                }
+                ModuleDef::Trait(it) => {
+                    self.push(item_path.clone(), &it);
+                    for item in it.items(db) {
+                        self.collect_assoc_item(item, &item_path);
+                    }
+                    self.public_defs.insert(def, item_path);
+                }
+                ModuleDef::Const(it) => self.push(item_path, &it),
+                ModuleDef::Static(it) => self.push(item_path, &it),
+                ModuleDef::TypeAlias(it) => self.push(item_path, &it),
+                ModuleDef::Macro(it) => self.push(item_path, &it),
+                ModuleDef::Variant(_) | ModuleDef::BuiltinType(_) => (),
+            }
+        }
+    }
+
+    fn collect_fields(&mut self, fields: Vec<hir::Field>, path: &str) {
+        for field in fields {
+            if field.visibility(self.db) == Visibility::Public {
+                self.push(format!("{}::{}", path, field.name(self.db)), &field);
+            }
+        }
+    }
+
+    fn collect_assoc_item(&mut self, item: AssocItem, path: &str) {
+        let db = self.db;
+        match item {
+            AssocItem::Function(it) => self.push(format!("{}::{}", path, it.name(db)), &it),
+            AssocItem::Const(it) => {
+                let name = it.name(db).map_or_else(|| "_".to_string(), |it| it.to_string());
+                self.push(format!("{}::{}", path, name), &it)
+            }
+            AssocItem::TypeAlias(it) => self.push(format!("{}::{}", path, it.name(db)), &it),
+        }
+    }
+
+    fn collect_impl(&mut self, impl_: Impl, krate: Crate) {
+        let db = self.db;
+        let self_ty = impl_.self_ty(db);
+        // Impls for private types of this crate can't be used from outside.
+        let self_adt_path = match self_ty.as_adt() {
+            Some(adt) if adt.module(db).krate() == krate => {
+                match self.public_defs.get(&ModuleDef::Adt(adt)) {
+                    Some(path) => Some(path.clone()),
+                    None => return,
+                }
+            }
+            _ => None,
+        };
+        match impl_.trait_(db) {
+            Some(trait_) => {
+                let is_public = trait_.module(db).krate() != krate
+                    || self.public_defs.contains_key(&ModuleDef::Trait(trait_));
+                if is_public {
+                    let signature = format!("impl {} for {}", trait_.name(db), self_ty.display(db));
+                    self.items.push(ApiItem { path: signature.clone(), signature });
+                }
+            }
+            None => {
+                // Inherent impls of foreign types only exist in `core` and `std`.
+                let path = match self_adt_path {
+                    Some(it) => it,
+                    None => return,
+                };
+                for item in impl_.items(db) {
+                    if item.visibility(db) == Visibility::Public {
+                        self.collect_assoc_item(item, &path);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn is_public(db: &RootDatabase, def: ModuleDef) -> bool {
+    match def {
+        // `#[macro_export]`ed macros live at the crate root, whatever their
+        // visibility says.
+        ModuleDef::Macro(it) => it.is_macro_export(db) || it.visibility(db) == Visibility::Public,
+        _ => def.visibility(db) == Visibility::Public,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{expect, Expect};
+
+    use super::*;
+    use crate::fixture;
+
+    fn check(ra_fixture: &str, expect: Expect) {
+        let (analysis, file_id) = fixture::file(ra_fixture);
+        let krate = analysis.crate_for(file_id).unwrap()[0];
+        let items = analysis.public_api(krate).unwrap();
+        let actual =
+            items.iter().map(|it| format!("{}: {}\n", it.path, it.signature)).collect::<String>();
+        expect.assert_eq(&actual);
+    }
+
+    #[test]
+    fn lists_public_items() {
+        check(
+            r#"
+//- /lib.rs crate:foo
+pub mod m {
+    pub struct S {
+        pub a: u32,
+        b: u32,
+    }
+    impl S {
+        pub fn new(a: u32) -> S { S { a, b: 0 } }
+        fn private(&self) {}
+    }
+    pub trait Tr {
+        fn f(&self) -> u32;
+    }
+    impl Tr for S {
+        fn f(&self) -> u32 { self.a }
+    }
+}
+mod private {
+    pub fn hidden() {}
+}
+pub enum E { A, B(u32) }
+pub const C: u32 = 0;
+fn private() {}
+"#,
+            expect![[r#"
+                foo::C: pub const C: u32
+                foo::E: pub enum E
+                foo::E::A: A
+                foo::E::B: B(u32)
+                foo::m: mod m
+                foo::m::S: pub struct S
+                foo::m::S::a: pub a: u32
+                foo::m::S::new: pub fn new(a: u32) -> S
+                foo::m::Tr: pub trait Tr
+                foo::m::Tr::f: fn f(&self) -> u32
+                impl Tr for S: impl Tr for S
+            "#]],
+        );
+    }
+
+    #[test]
+    fn diffs_apis() {
+        let item = |path: &str, signature: &str| ApiItem {
+            path: path.to_string(),
+            signature: signature.to_string(),
+        };
+        let old = vec![
+            item("a::f", "pub fn f()"),
+            item("a::g", "pub fn g()"),
+            item("a::S", "pub struct S"),
+        ];
+        let new = vec![
+            item("a::f", "pub fn f(x: u32)"),
+            item("a::S", "pub struct S"),
+            item("a::h", "pub fn h()"),
+        ];
+        let diff = diff_public_api(&old, &new);
+        assert_eq!(
+            diff,
+            ApiDiff {
+                added: vec![item("a::h", "pub fn h()")],
+                removed: vec![item("a::g", "pub fn g()")],
+                changed: vec![(item("a::f", "pub fn f()"), item("a::f", "pub fn f(x: u32)"))],
+            }
+        );
+    }
+}
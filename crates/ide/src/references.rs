@@ -54,44 +54,91 @@ pub(crate) fn find_all_refs(
     search_scope: Option<SearchScope>,
 ) -> Option<Vec<ReferenceSearchResult>> {
     let _p = profile::span("find_all_refs");
-    let syntax = sema.parse(position.file_id).syntax().clone();
-    let make_searcher = |literal_search: bool| {
-        move |def: Definition| {
-            let declaration = match def {
-                Definition::Module(module) => {
-                    Some(NavigationTarget::from_module_to_decl(sema.db, module))
-                }
-                def => def.try_to_nav(sema.db),
-            }
-            .map(|nav| {
-                let decl_range = nav.focus_or_full_range();
-                Declaration {
-                    is_mut: decl_mutability(&def, sema.parse(nav.file_id).syntax(), decl_range),
-                    nav,
-                }
-            });
+    let (defs, literal_search) = defs_to_search(sema, position)?;
+    let res = defs
+        .into_iter()
+        .map(|def| {
             let mut usages =
                 def.usages(sema).set_scope(search_scope.clone()).include_self_refs().all();
             if literal_search {
                 retain_adt_literal_usages(&mut usages, def, sema);
             }
-
-            let references = usages
-                .into_iter()
-                .map(|(file_id, refs)| {
-                    (
-                        file_id,
-                        refs.into_iter()
-                            .map(|file_ref| (file_ref.range, file_ref.category))
-                            .collect(),
-                    )
-                })
-                .collect();
-
-            ReferenceSearchResult { declaration, references }
+            let mut references = references(usages);
+            let operator_usages =
+                def.usages(sema).set_scope(search_scope.clone()).operator_usages();
+            add_operator_usages(&mut references, operator_usages);
+            ReferenceSearchResult { declaration: declaration(sema, def), references }
+        })
+        .collect();
+    Some(res)
+}
+
+/// Like [`find_all_refs`], but hands out the references crate by crate as
+/// soon as each crate has been searched, the declaration coming with the first
+/// batch.
+///
+/// The first `skip` batches are left out, and the search stops once `sink`
+/// returns `true`. Returns the number of batches covered, which is the `skip`
+/// to pick the search up where it stopped.
+pub(crate) fn find_all_refs_by_crate(
+    sema: &Semantics<RootDatabase>,
+    position: FilePosition,
+    mut skip: usize,
+    sink: &mut dyn FnMut(ReferenceSearchResult) -> bool,
+) -> Option<usize> {
+    let _p = profile::span("find_all_refs_by_crate");
+    let (defs, literal_search) = defs_to_search(sema, position)?;
+    let mut covered = skip;
+    for def in defs {
+        // The declaration and operator usages go with the first batch of the definition, which
+        // has already been reported when resuming past it.
+        let (mut declaration, mut operator_usages) = if skip == 0 {
+            (declaration(sema, def), Some(def.usages(sema).operator_usages()))
+        } else {
+            (None, None)
+        };
+        let mut stopped = false;
+        let mut len = def.usages(sema).include_self_refs().all_by_crate(skip, &mut |mut usages| {
+            if literal_search {
+                retain_adt_literal_usages(&mut usages, def, sema);
+            }
+            let mut references = references(usages);
+            if let Some(operator_usages) = operator_usages.take() {
+                add_operator_usages(&mut references, operator_usages);
+            }
+            covered += 1;
+            stopped = sink(ReferenceSearchResult { declaration: declaration.take(), references });
+            stopped
+        });
+        if len == 0 {
+            // There are no usages to carry the declaration, so it makes up a
+            // batch on its own.
+            len = 1;
+            if skip == 0 {
+                covered += 1;
+                let mut references = FxHashMap::default();
+                if let Some(operator_usages) = operator_usages.take() {
+                    add_operator_usages(&mut references, operator_usages);
+                }
+                stopped =
+                    sink(ReferenceSearchResult { declaration: declaration.take(), references });
+            }
         }
-    };
+        if stopped {
+            break;
+        }
+        skip = skip.saturating_sub(len);
+    }
+    Some(covered)
+}
 
+/// Returns the definitions to search references of, and whether only literal
+/// usages of an ADT are wanted.
+fn defs_to_search(
+    sema: &Semantics<RootDatabase>,
+    position: FilePosition,
+) -> Option<(Vec<Definition>, bool)> {
+    let syntax = sema.parse(position.file_id).syntax().clone();
     match name_for_constructor_search(&syntax, position) {
         Some(name) => {
             let def = match NameClass::classify(sema, &name)? {
@@ -100,12 +147,48 @@ pub(crate) fn find_all_refs(
                     Definition::Field(field_ref)
                 }
             };
-            Some(vec![make_searcher(true)(def)])
-        }
-        None => {
-            let search = make_searcher(false);
-            Some(find_defs(sema, &syntax, position.offset).into_iter().map(search).collect())
+            Some((vec![def], true))
         }
+        None => Some((find_defs(sema, &syntax, position.offset).collect(), false)),
+    }
+}
+
+fn declaration(sema: &Semantics<RootDatabase>, def: Definition) -> Option<Declaration> {
+    let nav = match def {
+        Definition::Module(module) => Some(NavigationTarget::from_module_to_decl(sema.db, module)),
+        def => def.try_to_nav(sema.db),
+    }?;
+    let decl_range = nav.focus_or_full_range();
+    Some(Declaration {
+        is_mut: decl_mutability(&def, sema.parse(nav.file_id).syntax(), decl_range),
+        nav,
+    })
+}
+
+fn references(
+    usages: UsageSearchResult,
+) -> FxHashMap<FileId, Vec<(TextRange, Option<ReferenceCategory>)>> {
+    usages
+        .into_iter()
+        .map(|(file_id, refs)| {
+            (
+                file_id,
+                refs.into_iter().map(|file_ref| (file_ref.range, file_ref.category)).collect(),
+            )
+        })
+        .collect()
+}
+
+/// Adds the operator expressions calling the searched function, these have no category.
+fn add_operator_usages(
+    references: &mut FxHashMap<FileId, Vec<(TextRange, Option<ReferenceCategory>)>>,
+    operator_usages: FxHashMap<FileId, Vec<TextRange>>,
+) {
+    for (file_id, ranges) in operator_usages {
+        references
+            .entry(file_id)
+            .or_default()
+            .extend(ranges.into_iter().map(|range| (range, None)));
     }
 }
 
@@ -274,7 +357,7 @@ mod tests {
     use ide_db::{base_db::FileId, search::ReferenceCategory};
     use stdx::format_to;
 
-    use crate::{fixture, SearchScope};
+    use crate::{fixture, ReferenceSearchResult, SearchScope};
 
     #[test]
     fn test_struct_literal_after_space() {
@@ -953,6 +1036,30 @@ fn g() { f(); }
         );
     }
 
+    #[test]
+    fn test_find_all_refs_operator_impl() {
+        check(
+            r#"
+//- minicore: add
+struct Foo;
+impl core::ops::Add for Foo {
+    type Output = Foo;
+    fn add$0(self, _: Foo) -> Foo { Foo }
+}
+
+fn f() {
+    let _ = Foo + Foo;
+    let _ = 1 + 2;
+}
+"#,
+            expect![[r#"
+                add Function FileId(0) 69..104 72..75
+
+                FileId(0) 133..134
+            "#]],
+        );
+    }
+
     #[test]
     fn test_find_all_refs_struct_pat() {
         check(
@@ -1076,7 +1183,10 @@ impl Foo {
     fn check_with_scope(ra_fixture: &str, search_scope: Option<SearchScope>, expect: Expect) {
         let (analysis, pos) = fixture::position(ra_fixture);
         let refs = analysis.find_all_refs(pos, search_scope).unwrap().unwrap();
+        expect.assert_eq(render(refs).trim_start())
+    }
 
+    fn render(refs: Vec<ReferenceSearchResult>) -> String {
         let mut actual = String::new();
         for refs in refs {
             actual += "\n\n";
@@ -1103,7 +1213,56 @@ impl Foo {
                 actual += "(no references)\n";
             }
         }
-        expect.assert_eq(actual.trim_start())
+        actual
+    }
+
+    #[test]
+    fn test_find_all_refs_by_crate() {
+        let (analysis, pos) = fixture::position(
+            r#"
+//- /lib.rs crate:lib
+pub fn foo$0() {}
+fn local() { foo(); }
+//- /main.rs crate:main deps:lib new_source_root:local
+fn main() { lib::foo(); }
+"#,
+        );
+        let expect = expect![[r#"
+            foo Function FileId(0) 0..15 7..10
+
+            FileId(0) 29..32
+
+
+            FileId(1) 17..20
+        "#]];
+
+        let mut batches = Vec::new();
+        let covered = analysis
+            .find_all_refs_by_crate(pos, 0, &mut |refs| {
+                batches.push(refs);
+                false
+            })
+            .unwrap();
+        assert_eq!(covered, Some(2));
+        expect.assert_eq(render(batches).trim_start());
+
+        // Stop after the first crate, then pick the search up again.
+        let mut batches = Vec::new();
+        let covered = analysis
+            .find_all_refs_by_crate(pos, 0, &mut |refs| {
+                batches.push(refs);
+                true
+            })
+            .unwrap();
+        assert_eq!(covered, Some(1));
+        let covered = analysis
+            .find_all_refs_by_crate(pos, 1, &mut |refs| {
+                batches.push(refs);
+                false
+            })
+            .unwrap();
+        assert_eq!(covered, Some(2));
+        expect.assert_eq(render(batches).trim_start());
     }
 
     #[test]
@@ -0,0 +1,275 @@
+use rustc_hash::FxHashMap;
+use syntax::{
+    SourceFile, SyntaxKind, SyntaxNode, SyntaxToken, TextRange, TextSize, TokenAtOffset, T,
+};
+use text_edit::TextEdit;
+
+const INDENT: usize = 4;
+
+// Feature: Reindent
+//
+// Fixes up the leading whitespace of the selected lines according to their
+// syntactic nesting. This is what range formatting falls back to when rustfmt's
+// range formatting is unavailable, and what reindents a line after typing `}`.
+//
+// Only indentation is touched; everything else on the lines is left as is.
+pub(crate) fn reindent(file: &SourceFile, range: TextRange) -> TextEdit {
+    let text = file.syntax().text().to_string();
+    let mut reindenter = Reindenter { file, text: &text, new_indents: FxHashMap::default() };
+    let mut edit = TextEdit::builder();
+
+    let mut line_start = line_start(&text, range.start());
+    loop {
+        let line_end = text[usize::from(line_start)..]
+            .find('\n')
+            .map_or(TextSize::of(&*text), |it| line_start + TextSize::from(it as u32));
+        if let Some((indent_end, indent)) = reindenter.reindent_line(line_start, line_end) {
+            let current = &text[TextRange::new(line_start, indent_end)];
+            let new_indent = " ".repeat(indent);
+            if current != new_indent {
+                edit.replace(TextRange::new(line_start, indent_end), new_indent);
+            }
+        }
+        if line_end >= range.end() || line_end == TextSize::of(&*text) {
+            break;
+        }
+        line_start = line_end + TextSize::of('\n');
+    }
+    edit.finish()
+}
+
+struct Reindenter<'a> {
+    file: &'a SourceFile,
+    text: &'a str,
+    /// The indentation of the lines we already fixed up, by line start.
+    new_indents: FxHashMap<TextSize, usize>,
+}
+
+impl Reindenter<'_> {
+    /// Computes the indentation of the line, returning the end of its current
+    /// indentation and the new width. Blank lines and lines starting inside of
+    /// a multi-line token, like a string, are left alone.
+    fn reindent_line(
+        &mut self,
+        line_start: TextSize,
+        line_end: TextSize,
+    ) -> Option<(TextSize, usize)> {
+        let line = &self.text[TextRange::new(line_start, line_end)];
+        let content = line.trim_start_matches(|c| c == ' ' || c == '\t');
+        if content.trim().is_empty() {
+            return None;
+        }
+        let indent_end = line_end - TextSize::of(content);
+        let token = match self.file.syntax().token_at_offset(indent_end) {
+            TokenAtOffset::None => return None,
+            TokenAtOffset::Single(it) => it,
+            TokenAtOffset::Between(_, it) => it,
+        };
+        if token.text_range().start() != indent_end {
+            return None;
+        }
+        let indent = self.indent_for(&token);
+        self.new_indents.insert(line_start, indent);
+        Some((indent_end, indent))
+    }
+
+    /// The indentation of a line starting with `token`.
+    fn indent_for(&self, token: &SyntaxToken) -> usize {
+        let line = line_start(self.text, token.text_range().start());
+        // The element of the innermost enclosing list which contains `token`.
+        let mut element: Option<SyntaxNode> = None;
+        for node in token.parent_ancestors() {
+            if is_delimited(node.kind()) {
+                let is_open = node.first_token().as_ref() == Some(token);
+                if !is_open {
+                    let base = self.indent_of_line(node.text_range().start());
+                    if node.last_token().as_ref() == Some(token) {
+                        return base;
+                    }
+                    // Continuation lines of an element get an extra level.
+                    let continues = element
+                        .as_ref()
+                        .map_or(false, |it| line_start(self.text, it.text_range().start()) < line);
+                    return base + if continues { 2 * INDENT } else { INDENT };
+                }
+            }
+            if matches!(node.kind(), SyntaxKind::METHOD_CALL_EXPR | SyntaxKind::FIELD_EXPR)
+                && token.kind() == T![.]
+                && token.parent().as_ref() == Some(&node)
+            {
+                return self.indent_of_line(node.text_range().start()) + INDENT;
+            }
+            element = Some(node);
+        }
+        0
+    }
+
+    /// The indentation of the line containing `offset`, as fixed up so far.
+    fn indent_of_line(&self, offset: TextSize) -> usize {
+        let line = line_start(self.text, offset);
+        if let Some(&indent) = self.new_indents.get(&line) {
+            return indent;
+        }
+        self.text[usize::from(line)..]
+            .chars()
+            .take_while(|c| *c == ' ' || *c == '\t')
+            .map(|c| if c == '\t' { INDENT } else { 1 })
+            .sum()
+    }
+}
+
+fn line_start(text: &str, offset: TextSize) -> TextSize {
+    text[..usize::from(offset)].rfind('\n').map_or(0.into(), |it| TextSize::from(it as u32 + 1))
+}
+
+fn is_delimited(kind: SyntaxKind) -> bool {
+    matches!(
+        kind,
+        SyntaxKind::STMT_LIST
+            | SyntaxKind::ITEM_LIST
+            | SyntaxKind::ASSOC_ITEM_LIST
+            | SyntaxKind::EXTERN_ITEM_LIST
+            | SyntaxKind::RECORD_FIELD_LIST
+            | SyntaxKind::TUPLE_FIELD_LIST
+            | SyntaxKind::VARIANT_LIST
+            | SyntaxKind::MATCH_ARM_LIST
+            | SyntaxKind::RECORD_EXPR_FIELD_LIST
+            | SyntaxKind::RECORD_PAT_FIELD_LIST
+            | SyntaxKind::USE_TREE_LIST
+            | SyntaxKind::ARG_LIST
+            | SyntaxKind::PARAM_LIST
+            | SyntaxKind::ARRAY_EXPR
+            | SyntaxKind::TUPLE_EXPR
+            | SyntaxKind::TOKEN_TREE
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use syntax::SourceFile;
+    use test_utils::{assert_eq_text, extract_range};
+
+    use super::*;
+
+    fn check(ra_fixture_before: &str, ra_fixture_after: &str) {
+        let (range, before) = extract_range(ra_fixture_before);
+        let file = SourceFile::parse(&before).ok().unwrap();
+        let mut actual = before.clone();
+        reindent(&file, range).apply(&mut actual);
+        assert_eq_text!(ra_fixture_after, &actual);
+    }
+
+    #[test]
+    fn reindents_blocks() {
+        check(
+            r#"
+$0fn foo() {
+let x = 1;
+      if x == 1 {
+  bar();
+        }
+}$0
+"#,
+            r#"
+fn foo() {
+    let x = 1;
+    if x == 1 {
+        bar();
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn only_touches_selected_lines() {
+        check(
+            r#"
+fn foo() {
+  let x = 1;
+$0      let y = 2;$0
+  let z = 3;
+}
+"#,
+            r#"
+fn foo() {
+  let x = 1;
+    let y = 2;
+  let z = 3;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn nests_relative_to_opening_line() {
+        check(
+            r#"
+fn foo() {
+    $0bar(baz(
+1,
+2,
+    ));
+    let s = S {
+a: 1,
+    };$0
+}
+"#,
+            r#"
+fn foo() {
+    bar(baz(
+        1,
+        2,
+    ));
+    let s = S {
+        a: 1,
+    };
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn method_chains_and_continuations() {
+        check(
+            r#"
+fn foo() {
+    $0let x = a
+.b()
+  .c();
+    let y =
+1;$0
+}
+"#,
+            r#"
+fn foo() {
+    let x = a
+        .b()
+        .c();
+    let y =
+        1;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn leaves_strings_alone() {
+        check(
+            r#"
+fn foo() {
+$0let s = "
+  text
+";$0
+}
+"#,
+            r#"
+fn foo() {
+    let s = "
+  text
+";
+}
+"#,
+        );
+    }
+}
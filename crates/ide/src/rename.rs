@@ -8,7 +8,7 @@ use hir::{AsAssocItem, InFile, Semantics};
 use ide_db::{
     base_db::FileId,
     defs::{Definition, NameClass, NameRefClass},
-    rename::{bail, format_err, source_edit_from_references, IdentifierKind},
+    rename::{bail, format_err, path_attr, source_edit_from_references, IdentifierKind},
     RootDatabase,
 };
 use itertools::Itertools;
@@ -66,6 +66,9 @@ pub(crate) fn prepare_rename(
 //
 // Renames the item below the cursor and all of its references
 //
+// Renaming a module moves its file, and its directory if it has one. Conversely, renaming a file
+// in the editor updates the `mod` declaration, or the `#[path]` attribute, and the paths using it.
+//
 // |===
 // | Editor  | Shortcut
 //
@@ -113,6 +116,21 @@ pub(crate) fn will_rename_file(
 ) -> Option<SourceChange> {
     let sema = Semantics::new(db);
     let module = sema.to_module_def(file_id)?;
+    if let Some(src) = module.declaration_source(db) {
+        if let Some(path) = path_attr(&src.value) {
+            // The module keeps its name, only the attribute follows the file.
+            let literal = path.syntax().parent()?;
+            let range = src.with_value(&literal).original_file_range_opt(db)?;
+            let path = path.value()?;
+            let file_name_start = path.rfind(|c| c == '/' || c == '\\').map_or(0, |it| it + 1);
+            if &path[file_name_start..] == "mod.rs" {
+                return None;
+            }
+            let new_path = format!("{}{}.rs", &path[..file_name_start], new_name_stem);
+            let edit = TextEdit::replace(range.range, format!("{:?}", new_path));
+            return Some(SourceChange::from_text_edit(range.file_id, edit));
+        }
+    }
     let def = Definition::Module(module);
     let mut change = def.rename(&sema, new_name_stem).ok()?;
     change.file_system_edits.clear();
@@ -750,6 +768,33 @@ impl Foo {
         );
     }
 
+    #[test]
+    fn test_rename_local_captured_in_format_string() {
+        check(
+            "count",
+            r#"
+macro_rules! format_args {
+    ($lit:literal $($tt:tt)*) => { 0 };
+}
+fn main() {
+    let n$0 = 1;
+    let width = 4;
+    format_args!("{n} {n:>width$} {{n}} {width}");
+}
+"#,
+            r#"
+macro_rules! format_args {
+    ($lit:literal $($tt:tt)*) => { 0 };
+}
+fn main() {
+    let count = 1;
+    let width = 4;
+    format_args!("{count} {count:>width$} {{n}} {width}");
+}
+"#,
+        );
+    }
+
     #[test]
     fn test_rename_local_in_field_shorthand() {
         cov_mark::check!(test_rename_local_in_field_shorthand);
@@ -962,6 +1007,57 @@ mod fo$0o;
                             ],
                         },
                     },
+                    file_system_edits: [
+                        MoveDir {
+                            src: AnchoredPathBuf {
+                                anchor: FileId(
+                                    1,
+                                ),
+                                path: "../foo",
+                            },
+                            src_id: FileId(
+                                1,
+                            ),
+                            dst: AnchoredPathBuf {
+                                anchor: FileId(
+                                    1,
+                                ),
+                                path: "../foo2",
+                            },
+                        },
+                    ],
+                    is_snippet: false,
+                }
+            "#]],
+        );
+    }
+
+    #[test]
+    fn test_rename_mod_with_submodule_dir() {
+        check_expect(
+            "bar",
+            r#"
+//- /lib.rs
+mod fo$0o;
+//- /foo.rs
+mod child;
+//- /foo/child.rs
+// empty
+"#,
+            expect![[r#"
+                SourceChange {
+                    source_file_edits: {
+                        FileId(
+                            0,
+                        ): TextEdit {
+                            indels: [
+                                Indel {
+                                    insert: "bar",
+                                    delete: 4..7,
+                                },
+                            ],
+                        },
+                    },
                     file_system_edits: [
                         MoveFile {
                             src: FileId(
@@ -971,7 +1067,24 @@ mod fo$0o;
                                 anchor: FileId(
                                     1,
                                 ),
-                                path: "../foo2/mod.rs",
+                                path: "bar.rs",
+                            },
+                        },
+                        MoveDir {
+                            src: AnchoredPathBuf {
+                                anchor: FileId(
+                                    1,
+                                ),
+                                path: "foo",
+                            },
+                            src_id: FileId(
+                                1,
+                            ),
+                            dst: AnchoredPathBuf {
+                                anchor: FileId(
+                                    1,
+                                ),
+                                path: "bar",
                             },
                         },
                     ],
@@ -981,6 +1094,103 @@ mod fo$0o;
         );
     }
 
+    #[test]
+    fn test_rename_mod_with_path_attr_keeps_file() {
+        check_expect(
+            "bar",
+            r#"
+//- /lib.rs
+#[path = "other.rs"]
+mod fo$0o;
+//- /other.rs
+// empty
+"#,
+            expect![[r#"
+                SourceChange {
+                    source_file_edits: {
+                        FileId(
+                            0,
+                        ): TextEdit {
+                            indels: [
+                                Indel {
+                                    insert: "bar",
+                                    delete: 25..28,
+                                },
+                            ],
+                        },
+                    },
+                    file_system_edits: [],
+                    is_snippet: false,
+                }
+            "#]],
+        );
+    }
+
+    #[test]
+    fn test_rename_crate_root_mod() {
+        check(
+            "foo",
+            r#"
+//- /lib.rs
+mod m {
+    use crate$0::S;
+}
+struct S;
+"#,
+            "error: Cannot rename a crate root module",
+        );
+    }
+
+    #[test]
+    fn test_will_rename_file() {
+        let (analysis, file_id) = fixture::file(
+            r#"
+//- /lib.rs
+mod foo;
+use foo::S;
+//- /foo.rs
+pub struct S;
+"#,
+        );
+        let foo = FileId(file_id.0 + 1);
+        let change = analysis.will_rename_file(foo, "bar").unwrap().unwrap();
+        let mut text = analysis.file_text(file_id).unwrap().to_string();
+        change.source_file_edits[&file_id].apply(&mut text);
+        assert_eq_text!("mod bar;\nuse bar::S;\n", &text);
+        assert!(change.file_system_edits.is_empty());
+    }
+
+    #[test]
+    fn test_will_rename_file_with_path_attr() {
+        let (analysis, file_id) = fixture::file(
+            r#"
+//- /lib.rs
+#[path = "impls/foo_impl.rs"]
+mod foo;
+use foo::S;
+//- /impls/foo_impl.rs
+pub struct S;
+"#,
+        );
+        let foo = FileId(file_id.0 + 1);
+        let change = analysis.will_rename_file(foo, "bar_impl").unwrap().unwrap();
+        let mut text = analysis.file_text(file_id).unwrap().to_string();
+        change.source_file_edits[&file_id].apply(&mut text);
+        assert_eq_text!("#[path = \"impls/bar_impl.rs\"]\nmod foo;\nuse foo::S;\n", &text);
+    }
+
+    #[test]
+    fn test_will_rename_crate_root() {
+        let (analysis, file_id) = fixture::file(
+            r#"
+//- /main.rs
+use crate::S;
+struct S;
+"#,
+        );
+        assert!(analysis.will_rename_file(file_id, "lib").unwrap().is_none());
+    }
+
     #[test]
     fn test_rename_unusually_nested_mod() {
         check_expect(
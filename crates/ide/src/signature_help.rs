@@ -2,7 +2,7 @@
 //! a call or use-site.
 
 use either::Either;
-use hir::{GenericParam, HasAttrs, HirDisplay, Semantics};
+use hir::{known, GenericParam, HasAttrs, HirDisplay, Semantics};
 use ide_db::{active_parameter::callable_for_node, base_db::FilePosition};
 use stdx::format_to;
 use syntax::{
@@ -35,14 +35,18 @@ impl SignatureHelp {
     }
 
     fn push_call_param(&mut self, param: &str) {
-        self.push_param('(', param);
+        self.push_param("(", param);
     }
 
     fn push_generic_param(&mut self, param: &str) {
-        self.push_param('<', param);
+        self.push_param("<", param);
     }
 
-    fn push_param(&mut self, opening_delim: char, param: &str) {
+    fn push_record_field(&mut self, field: &str) {
+        self.push_param("{ ", field);
+    }
+
+    fn push_param(&mut self, opening_delim: &str, param: &str) {
         if !self.signature.ends_with(opening_delim) {
             self.signature.push_str(", ");
         }
@@ -74,6 +78,21 @@ pub(crate) fn signature_help(db: &RootDatabase, position: FilePosition) -> Optio
         return Some(help);
     }
 
+    // Of record literals and index expressions nested in each other, the innermost one is the
+    // one being edited.
+    for node in token.parent()?.ancestors() {
+        let help = if let Some(record) = ast::RecordExpr::cast(node.clone()) {
+            signature_help_for_record_lit(&sema, &record, &token)
+        } else if let Some(index_expr) = ast::IndexExpr::cast(node) {
+            signature_help_for_index(&sema, &index_expr, &token)
+        } else {
+            None
+        };
+        if help.is_some() {
+            return help;
+        }
+    }
+
     None
 }
 
@@ -100,6 +119,17 @@ fn signature_help_for_call(
                 return None;
             }
         }
+        // Record literals and index expressions get their own signature help.
+        if let Some(list) = ast::RecordExprFieldList::cast(node.clone()) {
+            if in_record_fields(&list, token) {
+                return None;
+            }
+        }
+        if let Some(index_expr) = ast::IndexExpr::cast(node.clone()) {
+            if in_index_brackets(&index_expr, token) {
+                return None;
+            }
+        }
 
         node = node.parent()?;
     };
@@ -285,6 +315,11 @@ fn signature_help_for_generics(
 
         buf.clear();
         format_to!(buf, "{}", param.display(db));
+        if let hir::GenericParam::TypeParam(ty) = param {
+            if let Some(default) = ty.default(db).filter(|it| !it.is_unknown()) {
+                format_to!(buf, " = {}", default.display(db));
+            }
+        }
         res.push_generic_param(&buf);
     }
     res.signature.push('>');
@@ -292,6 +327,149 @@ fn signature_help_for_generics(
     Some(res)
 }
 
+fn signature_help_for_record_lit(
+    sema: &Semantics<RootDatabase>,
+    record: &ast::RecordExpr,
+    token: &SyntaxToken,
+) -> Option<SignatureHelp> {
+    let list = record.record_expr_field_list()?;
+    if !in_record_fields(&list, token) {
+        return None;
+    }
+
+    let mut res = SignatureHelp {
+        doc: None,
+        signature: String::new(),
+        parameters: vec![],
+        active_parameter: None,
+    };
+
+    let db = sema.db;
+    let fields = match sema.resolve_path(&record.path()?) {
+        Some(hir::PathResolution::Def(hir::ModuleDef::Variant(variant))) => {
+            res.doc = variant.docs(db).map(|it| it.into());
+            format_to!(
+                res.signature,
+                "enum {}::{}",
+                variant.parent_enum(db).name(db),
+                variant.name(db)
+            );
+            variant.fields(db)
+        }
+        _ => match sema.type_of_expr(&record.clone().into())?.original.as_adt()? {
+            hir::Adt::Struct(strukt) => {
+                res.doc = strukt.docs(db).map(|it| it.into());
+                format_to!(res.signature, "struct {}", strukt.name(db));
+                strukt.fields(db)
+            }
+            hir::Adt::Union(_) | hir::Adt::Enum(_) => return None,
+        },
+    };
+
+    let written = list
+        .fields()
+        .filter_map(|field| field.field_name())
+        .map(|name_ref| name_ref.text().to_string())
+        .collect::<Vec<_>>();
+    let edited = token
+        .parent()?
+        .ancestors()
+        .take_while(|it| it != list.syntax())
+        .find_map(ast::RecordExprField::cast)
+        .and_then(|field| field.field_name())
+        .map(|name_ref| name_ref.text().to_string());
+    // Outside of a field, the next field to write is the first one missing.
+    res.active_parameter = match edited {
+        Some(edited) => fields.iter().position(|field| field.name(db).to_string() == edited),
+        None => fields.iter().position(|field| !written.contains(&field.name(db).to_string())),
+    };
+
+    res.signature.push_str(" { ");
+    let mut buf = String::new();
+    for field in fields {
+        buf.clear();
+        format_to!(buf, "{}: {}", field.name(db), field.ty(db).display(db));
+        res.push_record_field(&buf);
+    }
+    res.signature.push_str(" }");
+
+    Some(res)
+}
+
+fn signature_help_for_index(
+    sema: &Semantics<RootDatabase>,
+    index_expr: &ast::IndexExpr,
+    token: &SyntaxToken,
+) -> Option<SignatureHelp> {
+    if !in_index_brackets(index_expr, token) {
+        return None;
+    }
+
+    let db = sema.db;
+    let func = match index_expr.index() {
+        Some(_) => sema.resolve_index_expr(index_expr)?,
+        // Without an index the impl can't be picked by its type, so only help if there is one.
+        None => {
+            let krate = sema.scope(index_expr.syntax()).krate()?;
+            let index_trait = hir::Trait::lang(db, krate, &known::index)?;
+            let base_ty = sema.type_of_expr(&index_expr.base()?)?.adjusted().strip_references();
+            let mut index_fns = hir::Impl::all_for_type(db, base_ty)
+                .into_iter()
+                .filter(|imp| imp.trait_(db) == Some(index_trait))
+                .filter_map(|imp| {
+                    imp.items(db).into_iter().find_map(|item| match item {
+                        hir::AssocItem::Function(func) => Some(func),
+                        _ => None,
+                    })
+                });
+            let func = index_fns.next()?;
+            if index_fns.next().is_some() {
+                return None;
+            }
+            func
+        }
+    };
+
+    let mut res = SignatureHelp {
+        doc: func.docs(db).map(|it| it.into()),
+        signature: String::new(),
+        parameters: vec![],
+        active_parameter: Some(0),
+    };
+    format_to!(res.signature, "fn {}(", func.name(db));
+    if let Some(self_param) = func.self_param(db) {
+        res.signature.push_str(&self_param.display(db));
+    }
+    let mut buf = String::new();
+    for param in func.method_params(db)? {
+        buf.clear();
+        if let Some(name) = param.name(db) {
+            format_to!(buf, "{}: ", name);
+        }
+        format_to!(buf, "{}", param.ty().display(db));
+        res.push_call_param(&buf);
+    }
+    res.signature.push(')');
+    let ret_type = func.ret_type(db);
+    if !ret_type.is_unit() {
+        format_to!(res.signature, " -> {}", ret_type.display(db));
+    }
+
+    Some(res)
+}
+
+fn in_record_fields(list: &ast::RecordExprFieldList, token: &SyntaxToken) -> bool {
+    let offset = token.text_range().start();
+    list.syntax().text_range().contains(offset)
+        && list.r_curly_token().map_or(true, |r_curly| offset < r_curly.text_range().start())
+}
+
+fn in_index_brackets(index_expr: &ast::IndexExpr, token: &SyntaxToken) -> bool {
+    let offset = token.text_range().start();
+    index_expr.l_brack_token().map_or(false, |l_brack| l_brack.text_range().start() <= offset)
+        && index_expr.r_brack_token().map_or(true, |r_brack| offset < r_brack.text_range().start())
+}
+
 #[cfg(test)]
 mod tests {
     use std::iter;
@@ -1058,4 +1236,111 @@ fn f() {
             "#]],
         );
     }
+
+    #[test]
+    fn test_generics_with_defaults() {
+        check(
+            r#"
+struct S<T, U = u8>(T, U);
+
+fn f(_: S<i32, $0>) {}
+"#,
+            expect![[r#"
+                struct S<T, U = u8>
+                         -  ^^^^^^
+            "#]],
+        );
+    }
+
+    #[test]
+    fn record_literal_next_missing_field() {
+        check(
+            r#"
+/// A struct
+struct S { a: u32, b: i32, c: u8 }
+fn main() {
+    let s = S { a: 0, $0 };
+}
+"#,
+            expect![[r#"
+                A struct
+                ------
+                struct S { a: u32, b: i32, c: u8 }
+                           ------  ^^^^^^  -----
+            "#]],
+        );
+    }
+
+    #[test]
+    fn record_literal_of_enum_variant() {
+        check(
+            r#"
+enum E {
+    C { a: i32, b: i32 }
+}
+fn main() {
+    let e = E::C { b: $0 };
+}
+"#,
+            expect![[r#"
+                enum E::C { a: i32, b: i32 }
+                            ------  ^^^^^^
+            "#]],
+        );
+    }
+
+    #[test]
+    fn call_inside_record_literal() {
+        check(
+            r#"
+struct S { a: u32 }
+fn f(x: u32) -> u32 { x }
+fn main() {
+    let s = S { a: f($0) };
+}
+"#,
+            expect![[r#"
+                fn f(x: u32) -> u32
+                     ^^^^^^
+            "#]],
+        );
+    }
+
+    #[test]
+    fn index_expr() {
+        let fixture = r#"
+#[lang = "index"]
+trait Index<Idx> {
+    type Output;
+    fn index(&self, index: Idx) -> &Self::Output;
+}
+struct Grid;
+impl Index<(usize, usize)> for Grid {
+    type Output = u8;
+    /// Gets a cell.
+    fn index(&self, index: (usize, usize)) -> &u8 { loop {} }
+}
+fn f(g: Grid) {
+    g[$0];
+}
+"#;
+        check(
+            fixture,
+            expect![[r#"
+                Gets a cell.
+                ------
+                fn index(&self, index: (usize, usize)) -> &u8
+                                ^^^^^^^^^^^^^^^^^^^^^
+            "#]],
+        );
+        check(
+            &fixture.replace("g[$0]", "g[(0, $0)]"),
+            expect![[r#"
+                Gets a cell.
+                ------
+                fn index(&self, index: (usize, usize)) -> &u8
+                                ^^^^^^^^^^^^^^^^^^^^^
+            "#]],
+        );
+    }
 }
@@ -18,7 +18,7 @@ use crate::{
 };
 use crate::{
     moniker::{crate_for_file, def_to_moniker, MonikerResult},
-    LifetimeElisionHints,
+    DiscriminantHints, LifetimeElisionHints,
 };
 
 /// A static representation of fully analyzed source code.
@@ -117,6 +117,8 @@ impl StaticIndex<'_> {
                     reborrow_hints: false,
                     hide_named_constructor_hints: false,
                     param_names_for_lifetime_elision_hints: false,
+                    discriminant_hints: DiscriminantHints::Never,
+                    await_point_hints: false,
                     max_length: Some(25),
                 },
                 file_id,
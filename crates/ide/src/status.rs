@@ -1,6 +1,6 @@
 use std::{fmt, iter::FromIterator, sync::Arc};
 
-use hir::{ExpandResult, MacroFile};
+use hir::{DefWithBody, ExpandResult, MacroFile};
 use ide_db::base_db::{
     salsa::debug::{DebugQueryTable, TableEntry},
     CrateId, FileId, FileTextQuery, SourceDatabase, SourceRootId,
@@ -24,7 +24,8 @@ fn macro_syntax_tree_stats(db: &RootDatabase) -> SyntaxTreeStats {
 
 // Feature: Status
 //
-// Shows internal statistic about memory usage of rust-analyzer.
+// Shows internal statistic about memory usage of rust-analyzer, and with
+// `rust-analyzer.inferenceProfiling.enable`, the functions slowest to analyze.
 //
 // |===
 // | Editor  | Action Name
@@ -39,6 +40,17 @@ pub(crate) fn status(db: &RootDatabase, file_id: Option<FileId>) -> String {
     format_to!(buf, "{}\n", syntax_tree_stats(db));
     format_to!(buf, "{} (Macros)\n", macro_syntax_tree_stats(db));
     format_to!(buf, "{} in total\n", memory_usage());
+    let slowest = DefWithBody::slowest_to_infer(10);
+    if !slowest.is_empty() {
+        format_to!(buf, "\nSlowest bodies to infer:\n");
+        for (def, timing) in slowest {
+            format_to!(buf, "{:>10.2?} {}", timing.total, body_path(db, def));
+            if let Some(cause) = timing.likely_cause() {
+                format_to!(buf, " ({})", cause);
+            }
+            buf.push('\n');
+        }
+    }
     if env::var("RA_COUNT").is_ok() {
         format_to!(buf, "\nCounts:\n{}", profile::countme::get_all());
     }
@@ -68,6 +80,23 @@ pub(crate) fn status(db: &RootDatabase, file_id: Option<FileId>) -> String {
     buf.trim().to_string()
 }
 
+fn body_path(db: &RootDatabase, def: DefWithBody) -> String {
+    let module = def.module(db);
+    let krate =
+        module.krate().display_name(db).map_or_else(|| "crate".to_string(), |it| it.to_string());
+    let mut path = vec![krate];
+    path.extend(
+        module
+            .path_to_root(db)
+            .into_iter()
+            .rev()
+            .filter_map(|it| it.name(db))
+            .map(|it| it.to_string()),
+    );
+    path.push(def.name(db).map_or_else(|| "_".to_string(), |it| it.to_string()));
+    path.join("::")
+}
+
 #[derive(Default)]
 struct FilesStats {
     total: usize,
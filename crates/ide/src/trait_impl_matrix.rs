@@ -0,0 +1,302 @@
+use std::iter;
+
+use hir::{Adt, HasSource, HirDisplay, ModuleDef, ScopeDef, Semantics, Trait, Type};
+use ide_db::{
+    base_db::{FilePosition, SourceDatabase},
+    famous_defs::FamousDefs,
+    helpers::pick_best_token,
+    source_change::SourceChange,
+    RootDatabase,
+};
+use syntax::{
+    ast::{self, edit::IndentLevel, AstNode, HasAttrs},
+    SyntaxKind, TextSize, T,
+};
+use text_edit::TextEdit;
+
+/// Which of the commonly expected traits a type implements.
+#[derive(Debug)]
+pub struct TraitImplMatrix {
+    /// The type, like `Point<u8>`.
+    pub ty: String,
+    pub rows: Vec<TraitImplRow>,
+    /// Derives all the missing traits which can be derived, if there are any.
+    pub derive_missing: Option<SourceChange>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraitImplRow {
+    /// The trait, as it would be written in a derive, like `serde::Serialize`.
+    pub trait_: String,
+    pub implemented: bool,
+    /// Whether the trait can be derived on the type, all of its fields
+    /// implementing the trait. Always `false` for implemented traits.
+    pub derivable: bool,
+}
+
+// Feature: Trait Implementation Matrix
+//
+// Shows which of the traits most types are expected to implement, like `Debug`, `Clone` or
+// `PartialEq`, the type under the cursor implements. `Serialize` is included when the crate
+// depends on `serde`. The missing traits whose fields all implement them can be derived at once.
+//
+// |===
+// | Editor  | Action Name
+//
+// | VS Code | **Rust Analyzer: Show Trait Implementation Matrix**
+// |===
+pub(crate) fn trait_impl_matrix(
+    db: &RootDatabase,
+    FilePosition { file_id, offset }: FilePosition,
+) -> Option<TraitImplMatrix> {
+    let sema = Semantics::new(db);
+    let file = sema.parse(file_id);
+    let token = pick_best_token(file.syntax().token_at_offset(offset), |kind| match kind {
+        SyntaxKind::IDENT | T![Self] => 2,
+        kind if kind.is_trivia() => 0,
+        _ => 1,
+    })?;
+    let ty = token.parent_ancestors().find_map(|node| {
+        if let Some(adt) = ast::Name::cast(node.clone()).and_then(|it| it.syntax().parent()) {
+            return ast::Adt::cast(adt).and_then(|it| sema.to_def(&it)).map(|it| it.ty(db));
+        }
+        if let Some(ty) = ast::Type::cast(node.clone()) {
+            return sema.resolve_type(&ty);
+        }
+        if let Some(expr) = ast::Expr::cast(node.clone()) {
+            return sema.type_of_expr(&expr).map(|it| it.original);
+        }
+        ast::Pat::cast(node).and_then(|pat| sema.type_of_pat(&pat)).map(|it| it.original)
+    })?;
+    if ty.is_unknown() {
+        return None;
+    }
+
+    let krate = sema.to_module_def(file_id).map(|it| it.krate());
+    let famous_defs = FamousDefs(&sema, krate);
+    let traits: Vec<(&str, Option<Trait>)> = vec![
+        ("Debug", famous_defs.core_fmt_Debug()),
+        ("Clone", famous_defs.core_clone_Clone()),
+        ("PartialEq", famous_defs.core_cmp_PartialEq()),
+        ("Eq", famous_defs.core_cmp_Eq()),
+        ("Hash", famous_defs.core_hash_Hash()),
+        ("Send", famous_defs.core_marker_Send()),
+        ("Sync", famous_defs.core_marker_Sync()),
+        ("serde::Serialize", krate.and_then(|krate| serde_trait(db, krate, "Serialize"))),
+    ];
+
+    let adt = ty.as_adt();
+    let editable_adt = adt.filter(|adt| is_editable(db, *adt));
+    let mut rows = Vec::new();
+    for (name, trait_) in traits {
+        let trait_ = match trait_ {
+            Some(it) => it,
+            None => continue,
+        };
+        let implemented = ty.impls_trait(db, trait_, &self_args(trait_, db, &ty));
+        let derivable = !implemented
+            && !trait_.is_auto(db)
+            && editable_adt.is_some()
+            && fields_implement(db, &ty, trait_);
+        rows.push(TraitImplRow { trait_: name.to_string(), implemented, derivable });
+    }
+    // `Eq` can only be derived along with `PartialEq`.
+    let partial_eq_available =
+        rows.iter().any(|row| row.trait_ == "PartialEq" && (row.implemented || row.derivable));
+    for row in rows.iter_mut().filter(|row| row.trait_ == "Eq") {
+        row.derivable &= partial_eq_available;
+    }
+
+    let derive_missing = editable_adt.and_then(|adt| {
+        let missing: Vec<&str> =
+            rows.iter().filter(|row| row.derivable).map(|row| row.trait_.as_str()).collect();
+        derive_edit(db, adt, &missing)
+    });
+    Some(TraitImplMatrix { ty: ty.display(db).to_string(), rows, derive_missing })
+}
+
+/// The trait arguments besides `Self`: `PartialEq` compares the type with itself.
+fn self_args(trait_: Trait, db: &RootDatabase, ty: &Type) -> Vec<Type> {
+    iter::repeat(ty.clone()).take(trait_.type_or_const_param_count(db)).collect()
+}
+
+/// Whether all the fields of the type implement the trait, so that it can be derived. Fields
+/// of a generic parameter type are fine, the derive adds the bound.
+fn fields_implement(db: &RootDatabase, ty: &Type, trait_: Trait) -> bool {
+    ty.auto_trait_components(db).into_iter().all(|(_, field)| {
+        field.as_type_param(db).is_some()
+            || field.impls_trait(db, trait_, &self_args(trait_, db, &field))
+    })
+}
+
+fn serde_trait(db: &RootDatabase, krate: hir::Crate, name: &str) -> Option<Trait> {
+    let serde = krate.dependencies(db).into_iter().find(|dep| dep.name.to_smol_str() == "serde")?;
+    serde.krate.root_module(db).scope(db, None).into_iter().find_map(|(it, def)| match def {
+        ScopeDef::ModuleDef(ModuleDef::Trait(trait_)) if it.to_smol_str() == name => Some(trait_),
+        _ => None,
+    })
+}
+
+/// Whether the definition of the ADT can be edited, i.e. it is written by hand in the workspace.
+fn is_editable(db: &RootDatabase, adt: Adt) -> bool {
+    let src = match adt.source(db) {
+        Some(it) => it,
+        None => return false,
+    };
+    if src.file_id.is_macro() {
+        return false;
+    }
+    let file_id = src.file_id.original_file(db);
+    !db.source_root(db.file_source_root(file_id)).is_library
+}
+
+/// Adds `traits` to the existing `#[derive]` of the ADT, or adds one.
+fn derive_edit(db: &RootDatabase, adt: Adt, traits: &[&str]) -> Option<SourceChange> {
+    if traits.is_empty() {
+        return None;
+    }
+    let src = adt.source(db)?;
+    let file_id = src.file_id.original_file(db);
+    let adt = src.value;
+    let existing = adt
+        .attrs()
+        .find(|attr| attr.as_simple_call().map_or(false, |(name, _)| name == "derive"))
+        .and_then(|attr| attr.token_tree())
+        .and_then(|tt| tt.r_paren_token());
+    let list = traits.join(", ");
+    let edit = match existing {
+        Some(r_paren) => {
+            let last = iter::successors(r_paren.prev_token(), |it| it.prev_token())
+                .find(|it| it.kind() != SyntaxKind::WHITESPACE);
+            let text = match last.map(|it| it.kind()) {
+                Some(T![,]) => format!(" {}", list),
+                Some(T!['(']) => list,
+                _ => format!(", {}", list),
+            };
+            TextEdit::insert(r_paren.text_range().start(), text)
+        }
+        None => {
+            let indent = IndentLevel::from_node(adt.syntax());
+            TextEdit::insert(item_start(&adt), format!("#[derive({})]\n{}", list, indent))
+        }
+    };
+    Some(SourceChange::from_text_edit(file_id, edit))
+}
+
+/// The start of the item after its attributes and doc comments.
+fn item_start(adt: &ast::Adt) -> TextSize {
+    adt.syntax()
+        .children_with_tokens()
+        .find(|it| {
+            !matches!(it.kind(), SyntaxKind::ATTR | SyntaxKind::COMMENT | SyntaxKind::WHITESPACE)
+        })
+        .map_or_else(|| adt.syntax().text_range().start(), |it| it.text_range().start())
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{expect, Expect};
+    use ide_db::base_db::FileId;
+
+    use crate::fixture;
+
+    fn check(ra_fixture: &str, expect: Expect) {
+        let (analysis, position) = fixture::position(ra_fixture);
+        let matrix = analysis.trait_impl_matrix(position).unwrap().unwrap();
+        let mut actual = format!("{}\n", matrix.ty);
+        for row in &matrix.rows {
+            let mark = if row.implemented {
+                "implemented"
+            } else if row.derivable {
+                "derivable"
+            } else {
+                "missing"
+            };
+            actual += &format!("{}: {}\n", row.trait_, mark);
+        }
+        if let Some(change) = matrix.derive_missing {
+            let mut text = analysis.file_text(FileId(0)).unwrap().to_string();
+            for edit in change.source_file_edits.values() {
+                edit.apply(&mut text);
+            }
+            actual += "---\n";
+            actual += &text;
+        }
+        expect.assert_eq(&actual);
+    }
+
+    #[test]
+    fn derives_missing_traits() {
+        check(
+            r#"
+//- minicore: fmt, clone, eq, hash, derive
+struct Id;
+impl core::fmt::Debug for Id {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result { loop {} }
+}
+impl core::clone::Clone for Id {
+    fn clone(&self) -> Id { Id }
+}
+impl core::cmp::PartialEq for Id {
+    fn eq(&self, _: &Id) -> bool { true }
+}
+impl core::cmp::Eq for Id {}
+
+/// A point.
+#[derive(Clone)]
+struct Point$0 {
+    id: Id,
+}
+"#,
+            expect![[r#"
+                Point
+                Debug: derivable
+                Clone: implemented
+                PartialEq: derivable
+                Eq: derivable
+                Hash: missing
+                ---
+                struct Id;
+                impl core::fmt::Debug for Id {
+                    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result { loop {} }
+                }
+                impl core::clone::Clone for Id {
+                    fn clone(&self) -> Id { Id }
+                }
+                impl core::cmp::PartialEq for Id {
+                    fn eq(&self, _: &Id) -> bool { true }
+                }
+                impl core::cmp::Eq for Id {}
+
+                /// A point.
+                #[derive(Clone, Debug, PartialEq, Eq)]
+                struct Point {
+                    id: Id,
+                }
+            "#]],
+        );
+    }
+
+    #[test]
+    fn adds_derive_attribute() {
+        check(
+            r#"
+//- minicore: clone, derive
+struct Unit;
+fn f() {
+    let _ = Unit$0;
+}
+"#,
+            expect![[r#"
+                Unit
+                Clone: derivable
+                ---
+                #[derive(Clone)]
+                struct Unit;
+                fn f() {
+                    let _ = Unit;
+                }
+            "#]],
+        );
+    }
+}
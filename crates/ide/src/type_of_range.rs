@@ -0,0 +1,111 @@
+use either::Either;
+use hir::{HirDisplay, Semantics, TypeInfo};
+use ide_db::{base_db::FileRange, RootDatabase};
+use syntax::{ast, AstNode};
+
+use crate::RangeInfo;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct TypeOfRange {
+    /// The inferred type of the expression or pattern.
+    pub original: String,
+    /// The type after coercions, if it differs from `original`.
+    pub adjusted: Option<String>,
+}
+
+// Feature: Type Of Range
+//
+// Editor extensions can ask for the inferred type of the smallest expression or
+// pattern covering a selection, including the type it was coerced to, through the
+// `rust-analyzer/typeOfRange` request.
+pub(crate) fn type_of_range(
+    db: &RootDatabase,
+    FileRange { file_id, range }: FileRange,
+) -> Option<RangeInfo<TypeOfRange>> {
+    let sema = Semantics::new(db);
+    let file = sema.parse(file_id);
+    let element = file.syntax().covering_element(range.intersect(file.syntax().text_range())?);
+    let expr_or_pat = element.ancestors().find_map(|node| {
+        ast::Expr::cast(node.clone())
+            .map(Either::Left)
+            .or_else(|| ast::Pat::cast(node).map(Either::Right))
+    })?;
+
+    let TypeInfo { original, adjusted } = match &expr_or_pat {
+        Either::Left(expr) => sema.type_of_expr(expr)?,
+        Either::Right(pat) => sema.type_of_pat(pat)?,
+    };
+    let info = TypeOfRange {
+        original: original.display(db).to_string(),
+        adjusted: adjusted.map(|it| it.display(db).to_string()),
+    };
+    let range = match &expr_or_pat {
+        Either::Left(it) => it.syntax().text_range(),
+        Either::Right(it) => it.syntax().text_range(),
+    };
+    Some(RangeInfo::new(range, info))
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{expect, Expect};
+
+    use crate::fixture;
+
+    fn check(ra_fixture: &str, expect: Expect) {
+        let (analysis, range) = fixture::range(ra_fixture);
+        let res = analysis.type_of_range(range).unwrap().unwrap();
+        let text = analysis.file_text(range.file_id).unwrap();
+        let actual = format!("{}: {:?}", &text[res.range], res.info);
+        expect.assert_eq(&actual);
+    }
+
+    #[test]
+    fn selected_expression() {
+        check(
+            r#"
+fn f() {
+    let x = $01 + 2$0;
+}
+"#,
+            expect![[r#"1 + 2: TypeOfRange { original: "i32", adjusted: None }"#]],
+        );
+    }
+
+    #[test]
+    fn smallest_covering_expression() {
+        check(
+            r#"
+fn f(v: (u8, bool)) {
+    let _ = v.$00$0;
+}
+"#,
+            expect![[r#"v.0: TypeOfRange { original: "u8", adjusted: None }"#]],
+        );
+    }
+
+    #[test]
+    fn coerced_expression() {
+        check(
+            r#"
+fn f(s: &[u8]) {}
+fn g(a: [u8; 2]) {
+    f($0&a$0);
+}
+"#,
+            expect![[r#"&a: TypeOfRange { original: "&[u8; 2]", adjusted: Some("&[u8]") }"#]],
+        );
+    }
+
+    #[test]
+    fn pattern() {
+        check(
+            r#"
+fn f(v: (u8, bool)) {
+    let (a, $0b$0) = v;
+}
+"#,
+            expect![[r#"b: TypeOfRange { original: "bool", adjusted: None }"#]],
+        );
+    }
+}
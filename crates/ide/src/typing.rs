@@ -22,7 +22,7 @@ use ide_db::{
 use syntax::{
     algo::find_node_at_offset,
     ast::{self, edit::IndentLevel, AstToken},
-    AstNode, Parse, SourceFile, SyntaxKind, TextRange, TextSize,
+    AstNode, Edition, Parse, SourceFile, SyntaxKind, TextRange, TextSize,
 };
 
 use text_edit::{Indel, TextEdit};
@@ -32,7 +32,7 @@ use crate::SourceChange;
 pub(crate) use on_enter::on_enter;
 
 // Don't forget to add new trigger characters to `server_capabilities` in `caps.rs`.
-pub(crate) const TRIGGER_CHARS: &str = ".=>{";
+pub(crate) const TRIGGER_CHARS: &str = ".=>{}";
 
 // Feature: On Typing Assists
 //
@@ -41,6 +41,7 @@ pub(crate) const TRIGGER_CHARS: &str = ".=>{";
 // - typing `let =` tries to smartly add `;` if `=` is followed by an existing expression
 // - typing `.` in a chain method call auto-indents
 // - typing `{` in front of an expression inserts a closing `}` after the expression
+// - typing `}` at the start of a line reindents the line
 //
 // VS Code::
 //
@@ -64,7 +65,8 @@ pub(crate) fn on_char_typed(
     if !stdx::always!(file.tree().syntax().text().char_at(position.offset) == Some(char_typed)) {
         return None;
     }
-    let edit = on_char_typed_inner(file, position.offset, char_typed)?;
+    let edition = db.file_edition(position.file_id);
+    let edit = on_char_typed_inner(file, position.offset, char_typed, edition)?;
     Some(SourceChange::from_text_edit(position.file_id, edit))
 }
 
@@ -72,6 +74,7 @@ fn on_char_typed_inner(
     file: &Parse<SourceFile>,
     offset: TextSize,
     char_typed: char,
+    edition: Edition,
 ) -> Option<TextEdit> {
     if !stdx::always!(TRIGGER_CHARS.contains(char_typed)) {
         return None;
@@ -80,14 +83,34 @@ fn on_char_typed_inner(
         '.' => on_dot_typed(&file.tree(), offset),
         '=' => on_eq_typed(&file.tree(), offset),
         '>' => on_arrow_typed(&file.tree(), offset),
-        '{' => on_opening_brace_typed(file, offset),
+        '{' => on_opening_brace_typed(file, offset, edition),
+        '}' => on_closing_brace_typed(&file.tree(), offset),
         _ => unreachable!(),
     }
 }
 
+/// Reindents the line when the user types a `}` at its start, so the brace lines
+/// up with the line opening the block.
+fn on_closing_brace_typed(file: &SourceFile, offset: TextSize) -> Option<TextEdit> {
+    let text = file.syntax().text().to_string();
+    let line_start = text[..usize::from(offset)].rfind('\n').map_or(0, |it| it + 1);
+    if !text[line_start..usize::from(offset)].chars().all(|c| c == ' ' || c == '\t') {
+        return None;
+    }
+    let edit = crate::reindent::reindent(file, TextRange::empty(offset));
+    if edit.is_empty() {
+        return None;
+    }
+    Some(edit)
+}
+
 /// Inserts a closing `}` when the user types an opening `{`, wrapping an existing expression in a
 /// block, or a part of a `use` item.
-fn on_opening_brace_typed(file: &Parse<SourceFile>, offset: TextSize) -> Option<TextEdit> {
+fn on_opening_brace_typed(
+    file: &Parse<SourceFile>,
+    offset: TextSize,
+    edition: Edition,
+) -> Option<TextEdit> {
     if !stdx::always!(file.tree().syntax().text().char_at(offset) == Some('{')) {
         return None;
     }
@@ -102,7 +125,7 @@ fn on_opening_brace_typed(file: &Parse<SourceFile>, offset: TextSize) -> Option<
     if !stdx::always!(range.len() == TextSize::of('{')) {
         return None;
     }
-    let file = file.reparse(&Indel::delete(range));
+    let file = file.reparse(&Indel::delete(range), edition);
 
     if let Some(edit) = brace_expr(&file.tree(), offset) {
         return Some(edit);
@@ -267,7 +290,7 @@ mod tests {
         let edit = TextEdit::insert(offset, char_typed.to_string());
         edit.apply(&mut before);
         let parse = SourceFile::parse(&before);
-        on_char_typed_inner(&parse, offset, char_typed).map(|it| {
+        on_char_typed_inner(&parse, offset, char_typed, Edition::CURRENT).map(|it| {
             it.apply(&mut before);
             before.to_string()
         })
@@ -711,6 +734,45 @@ fn foo() {
     )
     $0
 }
+"#,
+        );
+    }
+
+    #[test]
+    fn reindents_closing_brace() {
+        type_char(
+            '}',
+            r#"
+fn foo() {
+    if true {
+        bar();
+        $0
+}
+"#,
+            r#"
+fn foo() {
+    if true {
+        bar();
+    }
+}
+"#,
+        );
+        type_char_noop(
+            '}',
+            r#"
+fn foo() {
+    if true { bar(); $0
+}
+"#,
+        );
+        type_char_noop(
+            '}',
+            r#"
+fn foo() {
+    if true {
+        bar();
+    $0
+}
 "#,
         );
     }
@@ -0,0 +1,304 @@
+//! Lists the unsafe code of all the crates of the workspace, for auditing: unsafe
+//! functions, traits and impls, and `unsafe` blocks together with the operations
+//! which need them.
+//!
+//! This works on the `hir` of the bodies rather than on the text, so operations
+//! are found through macro calls, and an operation is attributed to the innermost
+//! `unsafe` block it is in. Items declared inside of function bodies are not
+//! visited.
+
+use hir::{
+    AssocItem, Crate, DefWithBody, HasSource, HirDisplay, InFile, Module, ModuleDef, Semantics,
+    UnsafeExprKind,
+};
+use ide_db::{
+    base_db::{FileRange, SourceDatabaseExt},
+    RootDatabase,
+};
+use syntax::{ast, AstPtr};
+
+use crate::navigation_target::TryToNav;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnsafeItemKind {
+    Function,
+    Block,
+    Trait,
+    Impl,
+}
+
+impl UnsafeItemKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            UnsafeItemKind::Function => "unsafe fn",
+            UnsafeItemKind::Block => "unsafe block",
+            UnsafeItemKind::Trait => "unsafe trait",
+            UnsafeItemKind::Impl => "unsafe impl",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct UnsafeItem {
+    pub kind: UnsafeItemKind,
+    /// The name of the function or trait, `Trait for Type` for impls, and the
+    /// name of the item containing the block for blocks.
+    pub name: String,
+    /// The name of the item for functions, traits and impls, the whole block
+    /// for blocks.
+    pub range: FileRange,
+    /// The operations which are only allowed because of this `unsafe`. For an
+    /// `unsafe fn`, these are the ones outside of `unsafe` blocks.
+    pub operations: Vec<UnsafeOperation>,
+    /// The name of the crate the code is in.
+    pub crate_name: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct UnsafeOperation {
+    pub kind: UnsafeExprKind,
+    pub range: FileRange,
+}
+
+impl UnsafeOperation {
+    pub fn description(&self) -> &'static str {
+        match self.kind {
+            UnsafeExprKind::UnsafeFnCall => "call to unsafe function",
+            UnsafeExprKind::ExternFnCall => "call to foreign function",
+            UnsafeExprKind::MutableStatic => "use of mutable static",
+            UnsafeExprKind::RawPtrDeref => "dereference of raw pointer",
+        }
+    }
+}
+
+pub(crate) fn unsafe_report(db: &RootDatabase) -> Vec<UnsafeItem> {
+    let _p = profile::span("unsafe_report");
+    let sema = Semantics::new(db);
+    let mut res = Vec::new();
+    for krate in Crate::all(db) {
+        let root_id = db.file_source_root(krate.root_file(db));
+        if db.source_root(root_id).is_library {
+            continue;
+        }
+        unsafe_in_crate(&sema, krate, &mut res);
+    }
+    res.sort_by(|a, b| {
+        (a.range.file_id, a.range.range.start()).cmp(&(b.range.file_id, b.range.range.start()))
+    });
+    res
+}
+
+fn unsafe_in_crate(sema: &Semantics<RootDatabase>, krate: Crate, acc: &mut Vec<UnsafeItem>) {
+    let db = sema.db;
+    let crate_name = krate.display_name(db).as_deref().unwrap_or("unknown").to_string();
+    let mut report = Report { sema, crate_name, acc };
+
+    let mut worklist = vec![krate.root_module(db)];
+    while let Some(module) = worklist.pop() {
+        worklist.extend(module.children(db));
+        report.module(module);
+    }
+}
+
+struct Report<'a, 'db> {
+    sema: &'a Semantics<'db, RootDatabase>,
+    crate_name: String,
+    acc: &'a mut Vec<UnsafeItem>,
+}
+
+impl Report<'_, '_> {
+    fn module(&mut self, module: Module) {
+        let db = self.sema.db;
+        for def in module.declarations(db) {
+            match def {
+                ModuleDef::Function(it) => self.body(it.into()),
+                ModuleDef::Const(it) => self.body(it.into()),
+                ModuleDef::Static(it) => self.body(it.into()),
+                ModuleDef::Trait(trait_) => {
+                    if trait_.is_unsafe(db) {
+                        let name = trait_.name(db).to_string();
+                        self.item(UnsafeItemKind::Trait, trait_, name);
+                    }
+                    trait_.items(db).into_iter().for_each(|it| self.assoc_item(it));
+                }
+                _ => (),
+            }
+        }
+        for impl_ in module.impl_defs(db) {
+            let is_unsafe = impl_.source(db).map_or(false, |it| it.value.unsafe_token().is_some());
+            if is_unsafe {
+                let self_ty = impl_.self_ty(db).display(db).to_string();
+                let name = match impl_.trait_(db) {
+                    Some(trait_) => format!("{} for {}", trait_.name(db), self_ty),
+                    None => self_ty,
+                };
+                self.item(UnsafeItemKind::Impl, impl_, name);
+            }
+            impl_.items(db).into_iter().for_each(|it| self.assoc_item(it));
+        }
+    }
+
+    fn assoc_item(&mut self, item: AssocItem) {
+        match item {
+            AssocItem::Function(it) => self.body(it.into()),
+            AssocItem::Const(it) => self.body(it.into()),
+            AssocItem::TypeAlias(_) => (),
+        }
+    }
+
+    fn body(&mut self, def: DefWithBody) {
+        let db = self.sema.db;
+        let name = def.name(db).map_or_else(|| "_".to_string(), |it| it.to_string());
+        let mut operations = def.unsafe_operations(db);
+
+        for block in def.unsafe_blocks(db) {
+            let (inside, outside) =
+                operations.into_iter().partition(|it| it.unsafe_block.as_ref() == Some(&block));
+            operations = outside;
+            let range = self.range(block);
+            let operations = self.operations(inside);
+            self.push(UnsafeItemKind::Block, name.clone(), range, operations);
+        }
+
+        if let DefWithBody::Function(func) = def {
+            // Declarations in `extern` blocks are unsafe to call, but there is nothing to audit
+            // in them.
+            if func.is_unsafe(db) && !func.is_in_extern_block(db) {
+                if let Some(nav) = func.try_to_nav(db) {
+                    let range =
+                        FileRange { file_id: nav.file_id, range: nav.focus_or_full_range() };
+                    let operations = self.operations(operations);
+                    self.push(UnsafeItemKind::Function, name, range, operations);
+                }
+            }
+        }
+    }
+
+    fn item(&mut self, kind: UnsafeItemKind, item: impl TryToNav, name: String) {
+        if let Some(nav) = item.try_to_nav(self.sema.db) {
+            let range = FileRange { file_id: nav.file_id, range: nav.focus_or_full_range() };
+            self.push(kind, name, range, Vec::new());
+        }
+    }
+
+    fn push(
+        &mut self,
+        kind: UnsafeItemKind,
+        name: String,
+        range: FileRange,
+        operations: Vec<UnsafeOperation>,
+    ) {
+        let crate_name = self.crate_name.clone();
+        self.acc.push(UnsafeItem { kind, name, range, operations, crate_name });
+    }
+
+    fn operations(&self, operations: Vec<hir::UnsafeOperation>) -> Vec<UnsafeOperation> {
+        let mut res: Vec<_> = operations
+            .into_iter()
+            .map(|it| UnsafeOperation { kind: it.kind, range: self.range(it.expr) })
+            .collect();
+        res.sort_by_key(|it| it.range.range.start());
+        res
+    }
+
+    fn range(&self, ptr: InFile<AstPtr<ast::Expr>>) -> FileRange {
+        self.sema.diagnostics_display_range(ptr.map(|it| it.syntax_node_ptr()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{expect, Expect};
+    use itertools::Itertools;
+
+    use crate::fixture;
+
+    fn check(ra_fixture: &str, expect: Expect) {
+        let (analysis, file_id) = fixture::file(ra_fixture);
+        let text = analysis.file_text(file_id).unwrap();
+        let items = analysis.unsafe_report().unwrap();
+        let actual = items
+            .iter()
+            .map(|it| {
+                let operations = it
+                    .operations
+                    .iter()
+                    .map(|op| format!("    {} `{}`\n", op.description(), &text[op.range.range]))
+                    .join("");
+                format!("{} {}\n{}", it.kind.as_str(), it.name, operations)
+            })
+            .collect::<String>();
+        expect.assert_eq(&actual);
+    }
+
+    #[test]
+    fn lists_unsafe_code() {
+        check(
+            r#"
+extern "C" {
+    fn abs(x: i32) -> i32;
+}
+static mut COUNTER: u32 = 0;
+
+unsafe fn bump(p: *mut u32) {
+    *p += 1;
+    unsafe { COUNTER += 1 }
+}
+
+fn safe(p: *const u32) -> u32 {
+    let x = unsafe { abs(-1) };
+    unsafe {
+        bump(&mut COUNTER);
+        *p
+    }
+}
+
+unsafe trait Zeroable {}
+struct S;
+unsafe impl Zeroable for S {}
+impl S {
+    fn method(&self) {
+        unsafe {}
+    }
+}
+"#,
+            expect![[r#"
+                unsafe fn bump
+                    dereference of raw pointer `*p`
+                unsafe block bump
+                    use of mutable static `COUNTER`
+                unsafe block safe
+                    call to foreign function `abs(-1)`
+                unsafe block safe
+                    call to unsafe function `bump(&mut COUNTER)`
+                    use of mutable static `COUNTER`
+                    dereference of raw pointer `*p`
+                unsafe trait Zeroable
+                unsafe impl Zeroable for S
+                unsafe block method
+            "#]],
+        );
+    }
+
+    #[test]
+    fn operations_belong_to_the_innermost_block() {
+        check(
+            r#"
+unsafe fn f() {}
+fn g() {
+    unsafe {
+        f();
+        unsafe { f() }
+    }
+}
+"#,
+            expect![[r#"
+                unsafe fn f
+                unsafe block g
+                    call to unsafe function `f()`
+                unsafe block g
+                    call to unsafe function `f()`
+            "#]],
+        );
+    }
+}
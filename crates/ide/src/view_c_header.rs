@@ -0,0 +1,284 @@
+use hir::{Adt, Function, HasAttrs, HirDisplay, ModuleDef, ReprKind, Semantics, StructKind, Type};
+use ide_db::{
+    base_db::FileId,
+    famous_defs::FamousDefs,
+    ffi::{c_declaration, c_return_declaration, CTypeError},
+    RootDatabase,
+};
+use stdx::format_to;
+
+// Feature: View C Header
+//
+// Renders the FFI surface of the crate of the current file as C declarations, like cbindgen
+// would: its `#[repr(C)]` types and the `extern "C"` functions it exports. Declarations using
+// types without a C equivalent are commented out, with the reason.
+//
+// |===
+// | Editor  | Action Name
+//
+// | VS Code | **Rust Analyzer: View C Header**
+// |===
+pub(crate) fn view_c_header(db: &RootDatabase, file_id: FileId) -> String {
+    let sema = Semantics::new(db);
+    let krate = match sema.to_module_def(file_id) {
+        Some(it) => it.krate(),
+        None => return String::new(),
+    };
+    let famous_defs = FamousDefs(&sema, Some(krate));
+
+    let mut types = String::new();
+    let mut functions = String::new();
+    for module in krate.modules(db) {
+        for def in module.declarations(db) {
+            match def {
+                ModuleDef::Adt(adt) => c_type_definition(&famous_defs, adt, &mut types),
+                ModuleDef::Function(func) => c_function(&famous_defs, func, &mut functions),
+                _ => (),
+            }
+        }
+    }
+
+    let mut buf = String::new();
+    let name = krate.display_name(db).map_or_else(|| "crate".to_string(), |it| it.to_string());
+    format_to!(buf, "/* C declarations of the FFI surface of `{}`. */\n\n", name);
+    buf.push_str("#include <stdbool.h>\n#include <stdint.h>\n");
+    for section in [types, functions] {
+        if !section.is_empty() {
+            buf.push('\n');
+            buf.push_str(&section);
+        }
+    }
+    buf.truncate(buf.trim_end().len());
+    buf.push('\n');
+    buf
+}
+
+fn c_type_definition(famous_defs: &FamousDefs, adt: Adt, buf: &mut String) {
+    let db = famous_defs.0.db;
+    let name = adt.name(db);
+    match adt {
+        Adt::Struct(strukt) if strukt.repr(db) == Some(ReprKind::C) => {
+            let fields = strukt.fields(db).into_iter().map(|field| {
+                let name = match strukt.kind(db) {
+                    StructKind::Tuple => format!("_{}", field.name(db)),
+                    _ => field.name(db).to_string(),
+                };
+                (field.ty(db), name)
+            });
+            c_record(famous_defs, "struct", &name.to_string(), fields.collect(), buf);
+        }
+        Adt::Union(union) if union.repr(db) == Some(ReprKind::C) => {
+            let fields = union
+                .fields(db)
+                .into_iter()
+                .map(|field| (field.ty(db), field.name(db).to_string()));
+            c_record(famous_defs, "union", &name.to_string(), fields.collect(), buf);
+        }
+        Adt::Enum(enum_) if !enum_.is_data_carrying(db) => {
+            let repr = match enum_.repr(db) {
+                Some(it @ (ReprKind::C | ReprKind::Int(_))) => it,
+                _ => return,
+            };
+            let variants: Vec<String> = enum_
+                .variants(db)
+                .into_iter()
+                .map(|variant| match variant.value(db) {
+                    Some(value) => format!("{} = {}", variant.name(db), value),
+                    None => variant.name(db).to_string(),
+                })
+                .collect();
+            match repr {
+                ReprKind::C => {
+                    format_to!(buf, "enum {} {{\n", name);
+                    for variant in variants {
+                        format_to!(buf, "    {},\n", variant);
+                    }
+                    buf.push_str("};\n\n");
+                }
+                // C enums are `int`s, integer representations need a typedef.
+                _ => {
+                    let discriminant = c_declaration(famous_defs, &enum_.variant_body_ty(db), "");
+                    if let Ok(discriminant) = discriminant {
+                        format_to!(buf, "typedef {} {};\n", discriminant, name);
+                    }
+                    format_to!(buf, "enum {{\n");
+                    for variant in variants {
+                        format_to!(buf, "    {}_{},\n", name, variant);
+                    }
+                    buf.push_str("};\n\n");
+                }
+            }
+        }
+        _ => (),
+    }
+}
+
+fn c_record(
+    famous_defs: &FamousDefs,
+    keyword: &str,
+    name: &str,
+    fields: Vec<(Type, String)>,
+    buf: &mut String,
+) {
+    let mut errors = Vec::new();
+    let mut definition = format!("{} {} {{\n", keyword, name);
+    for (ty, name) in fields {
+        format_to!(definition, "    {};\n", declare(famous_defs, &ty, &name, &mut errors));
+    }
+    definition.push_str("};\n");
+    emit(buf, &definition, &errors);
+}
+
+fn c_function(famous_defs: &FamousDefs, func: Function, buf: &mut String) {
+    let db = famous_defs.0.db;
+    if func.is_in_extern_block(db) || !func.has_body(db) {
+        return;
+    }
+    if !matches!(func.abi(db).as_deref(), Some("C" | "C-unwind" | "system")) {
+        return;
+    }
+    let attrs = func.attrs(db);
+    let name = match attrs.by_key("export_name").string_value() {
+        Some(it) => it.to_string(),
+        None => func.name(db).to_string(),
+    };
+
+    let mut errors = Vec::new();
+    let mut params: Vec<String> = func
+        .assoc_fn_params(db)
+        .iter()
+        .enumerate()
+        .map(|(idx, param)| {
+            let name = param.name(db).map_or_else(|| format!("arg{}", idx), |it| it.to_string());
+            declare(famous_defs, param.ty(), &name, &mut errors)
+        })
+        .collect();
+    if func.is_varargs(db) {
+        params.push("...".to_string());
+    }
+    let params = if params.is_empty() { "void".to_string() } else { params.join(", ") };
+
+    let declarator = format!("{}({})", name, params);
+    let ret_type = func.ret_type(db);
+    let declaration = match c_return_declaration(famous_defs, &ret_type, &declarator) {
+        Ok(it) => it,
+        Err(err) => {
+            errors.push(error_message(db, &ret_type, err));
+            format!("/* {} */ {}", ret_type.display(db), declarator)
+        }
+    };
+    emit(buf, &format!("{};\n", declaration), &errors);
+}
+
+/// Declares `name` with type `ty`, or with the Rust type in a comment if it has no C equivalent.
+fn declare(famous_defs: &FamousDefs, ty: &Type, name: &str, errors: &mut Vec<String>) -> String {
+    let db = famous_defs.0.db;
+    c_declaration(famous_defs, ty, name).unwrap_or_else(|err| {
+        errors.push(error_message(db, ty, err));
+        format!("/* {} */ {}", ty.display(db), name)
+    })
+}
+
+fn error_message(db: &RootDatabase, ty: &Type, err: CTypeError) -> String {
+    err.message(db).unwrap_or_else(|| format!("`{}` has no known C equivalent", ty.display(db)))
+}
+
+/// Comments out declarations which C can't express, along with why.
+fn emit(buf: &mut String, declaration: &str, errors: &[String]) {
+    if errors.is_empty() {
+        buf.push_str(declaration);
+    } else {
+        for error in errors {
+            format_to!(buf, "/* {} */\n", error);
+        }
+        for line in declaration.lines() {
+            format_to!(buf, "// {}\n", line);
+        }
+    }
+    buf.push('\n');
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{expect, Expect};
+
+    use crate::fixture;
+
+    fn check(ra_fixture: &str, expect: Expect) {
+        let (analysis, file_id) = fixture::file(ra_fixture);
+        let header = analysis.view_c_header(file_id).unwrap();
+        expect.assert_eq(&header);
+    }
+
+    #[test]
+    fn renders_ffi_surface() {
+        check(
+            r#"
+//- minicore: option
+#[repr(C)]
+pub struct Point {
+    x: i32,
+    y: *const u8,
+}
+
+#[repr(u8)]
+pub enum Color {
+    Red,
+    Blue = 4,
+}
+
+#[repr(C)]
+pub struct Tuple(u8, Option<&'static Point>);
+
+pub struct NotC(u8);
+
+#[no_mangle]
+pub extern "C" fn area(p: &mut Point, color: Color, cb: extern "C" fn(i32) -> u8) -> f64 {
+    0.0
+}
+
+#[export_name = "renamed"]
+pub extern "C" fn original(data: *const *mut u32, len: usize) {}
+
+#[no_mangle]
+pub extern "C" fn takes_str(s: &str, n: NotC) {}
+
+pub fn not_exported(x: i32) {}
+
+extern "C" {
+    fn imported(x: i32);
+}
+"#,
+            expect![[r#"
+                /* C declarations of the FFI surface of `test`. */
+
+                #include <stdbool.h>
+                #include <stdint.h>
+
+                struct Point {
+                    int32_t x;
+                    const uint8_t *y;
+                };
+
+                typedef uint8_t Color;
+                enum {
+                    Color_Red,
+                    Color_Blue = 4,
+                };
+
+                struct Tuple {
+                    uint8_t _0;
+                    const struct Point *_1;
+                };
+
+                double area(struct Point *p, Color color, uint8_t (*cb)(int32_t));
+
+                void renamed(uint32_t *const *data, uintptr_t len);
+
+                /* `&str` is not FFI-safe: string slices have no C equivalent */
+                /* `NotC` is not FFI-safe: this struct has unspecified layout */
+                // void takes_str(/* &str */ s, /* NotC */ n);
+            "#]],
+        );
+    }
+}
@@ -1,7 +1,7 @@
 use hir::{Function, Semantics};
-use ide_db::base_db::FilePosition;
+use ide_db::base_db::{FilePosition, FileRange};
 use ide_db::RootDatabase;
-use syntax::{algo::find_node_at_offset, ast, AstNode};
+use syntax::{algo::find_node_at_offset, ast, AstNode, TextRange};
 
 // Feature: View Hir
 //
@@ -24,3 +24,108 @@ fn body_hir(db: &RootDatabase, position: FilePosition) -> Option<String> {
     let function: Function = sema.to_def(&function)?;
     Some(function.debug_hir(db))
 }
+
+#[derive(Debug)]
+pub struct DesugaredBody {
+    pub text: String,
+    /// Maps ranges of `text` back to the source expressions they were printed
+    /// from, innermost expressions first.
+    pub ranges: Vec<(TextRange, FileRange)>,
+}
+
+// Feature: View Desugared Body
+//
+// Shows the body of the function at the cursor the way the compiler sees it: `?`,
+// `for` loops and `.await` are expanded into the matches and loops they stand for,
+// and overloaded indexing and derefs are spelled out as trait method calls.
+// Going to the definition of an expression in the desugared view jumps back to
+// the source expression it comes from.
+//
+// |===
+// | Editor  | Action Name
+//
+// | VS Code | **Rust Analyzer: View Desugared Body**
+// |===
+pub(crate) fn view_desugared_body(db: &RootDatabase, position: FilePosition) -> DesugaredBody {
+    let sema = Semantics::new(db);
+    let source_file = sema.parse(position.file_id);
+    let function = find_node_at_offset::<ast::Fn>(source_file.syntax(), position.offset)
+        .and_then(|it| sema.to_def(&it));
+    match function {
+        Some(function) => {
+            let (text, ranges) = function.desugared_body(db);
+            DesugaredBody { text, ranges }
+        }
+        None => {
+            DesugaredBody { text: "Not inside a function body".to_string(), ranges: Vec::new() }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{expect, Expect};
+
+    use crate::fixture;
+
+    fn check(ra_fixture: &str, expect: Expect) {
+        let (analysis, position) = fixture::position(ra_fixture);
+        let body = analysis.view_desugared_body(position).unwrap();
+        expect.assert_eq(&body.text);
+    }
+
+    #[test]
+    fn desugars_try_and_for() {
+        check(
+            r#"
+//- minicore: option, result, iterator
+fn foo(xs: [u32; 2]) -> Option<u32> {
+    let mut sum = 0;
+    for x in xs {
+        sum += Some(x)?;
+    }
+    Some(xs[0] + sum)
+}$0
+"#,
+            expect![[r#"
+                fn foo(xs: [u32; 2]) -> Option<u32> {
+                    let mut sum = 0;
+                    match IntoIterator::into_iter(xs) {
+                        mut iter => loop {
+                            match Iterator::next(&mut iter) {
+                                None => break,
+                                Some(x) => {
+                                    sum += match Try::branch(Some(x)) {
+                                        ControlFlow::Continue(val) => val,
+                                        ControlFlow::Break(residual) => return FromResidual::from_residual(residual),
+                                    };
+                                },
+                            }
+                        },
+                    }
+                    Some(xs[0] + sum)
+                }
+            "#]],
+        );
+    }
+
+    #[test]
+    fn maps_back_to_source() {
+        let (analysis, position) = fixture::position(
+            r#"
+fn foo(a: u32) -> u32 {
+    a + 1$0
+}
+"#,
+        );
+        let body = analysis.view_desugared_body(position).unwrap();
+        let text = analysis.file_text(position.file_id).unwrap();
+        let (_, source) = body
+            .ranges
+            .iter()
+            .find(|(range, _)| &body.text[*range] == "a + 1")
+            .expect("no mapping for `a + 1`");
+        assert_eq!(source.file_id, position.file_id);
+        assert_eq!(&text[source.range], "a + 1");
+    }
+}
@@ -0,0 +1,63 @@
+use hir::{Function, Semantics};
+use ide_db::base_db::FilePosition;
+use ide_db::RootDatabase;
+use syntax::{algo::find_node_at_offset, ast, AstNode};
+
+// Feature: View Trait Solver Overflows
+//
+// Dumps the goals the trait solver gave up on in the function containing the cursor, along with
+// the program they were solved against, and the types autoderef stopped at after reaching its
+// recursion limit. Useful for attaching to bug reports about `trait-solver-overflow` diagnostics.
+//
+// |===
+// | Editor  | Action Name
+//
+// | VS Code | **Rust Analyzer: View Trait Solver Overflows**
+// |===
+pub(crate) fn view_trait_solver_overflows(db: &RootDatabase, position: FilePosition) -> String {
+    let sema = Semantics::new(db);
+    let source_file = sema.parse(position.file_id);
+
+    let function = find_node_at_offset::<ast::Fn>(source_file.syntax(), position.offset)
+        .and_then(|function| sema.to_def(&function));
+    let function: Function = match function {
+        Some(it) => it,
+        None => return "Not inside a function body".to_string(),
+    };
+    let res = function.debug_trait_solver_overflows(db);
+    if res.is_empty() {
+        return "The trait solver did not overflow in this function".to_string();
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixture;
+
+    #[test]
+    fn autoderef_overflow() {
+        let (analysis, position) = fixture::position(
+            r#"
+//- minicore: deref
+struct S;
+impl core::ops::Deref for S {
+    type Target = S;
+    fn deref(&self) -> &S { self }
+}
+fn main() {
+    S.foo$0;
+}
+"#,
+        );
+        let res = analysis.view_trait_solver_overflows(position).unwrap();
+        assert!(res.contains("autoderef reached the recursion limit at `S`"), "{}", res);
+    }
+
+    #[test]
+    fn no_overflow() {
+        let (analysis, position) = fixture::position(r#"fn main() { 92$0; }"#);
+        let res = analysis.view_trait_solver_overflows(position).unwrap();
+        assert_eq!(res, "The trait solver did not overflow in this function");
+    }
+}
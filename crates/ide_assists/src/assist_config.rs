@@ -4,6 +4,7 @@
 //! module, and we use to statically check that we only produce snippet
 //! assists if we are allowed to.
 
+use hir::{ItemInNs, ModuleDef};
 use ide_db::{imports::insert_use::InsertUseConfig, SnippetCap};
 
 use crate::AssistKind;
@@ -13,4 +14,47 @@ pub struct AssistConfig {
     pub snippet_cap: Option<SnippetCap>,
     pub allowed: Option<Vec<AssistKind>>,
     pub insert_use: InsertUseConfig,
+    /// Kinds of items which are only ever qualified with their full path,
+    /// `auto_import` doesn't offer to import them.
+    pub always_qualify: Vec<QualifyItemKind>,
+    /// Where `normalize_bounds` puts the bounds of type parameters.
+    pub bounds_placement: BoundsPlacement,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BoundsPlacement {
+    /// `fn f<T>() where T: Clone`
+    WhereClause,
+    /// `fn f<T: Clone>()`
+    Inline,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QualifyItemKind {
+    Macro,
+    Function,
+    /// Structs, enums, unions, traits and type aliases.
+    Type,
+    /// Constants and statics.
+    Constant,
+}
+
+impl AssistConfig {
+    pub(crate) fn always_qualifies(&self, item: ItemInNs) -> bool {
+        let kind = match item {
+            ItemInNs::Macros(_) => QualifyItemKind::Macro,
+            ItemInNs::Types(def) | ItemInNs::Values(def) => match def {
+                ModuleDef::Function(_) => QualifyItemKind::Function,
+                ModuleDef::Adt(_)
+                | ModuleDef::Variant(_)
+                | ModuleDef::Trait(_)
+                | ModuleDef::TypeAlias(_)
+                | ModuleDef::BuiltinType(_) => QualifyItemKind::Type,
+                ModuleDef::Const(_) | ModuleDef::Static(_) => QualifyItemKind::Constant,
+                ModuleDef::Macro(_) => QualifyItemKind::Macro,
+                ModuleDef::Module(_) => return false,
+            },
+        };
+        self.always_qualify.contains(&kind)
+    }
 }
@@ -332,6 +332,10 @@ impl AssistBuilder {
         let file_system_edit = FileSystemEdit::MoveFile { src, dst };
         self.source_change.push_file_system_edit(file_system_edit);
     }
+    pub(crate) fn delete_file(&mut self, src: FileId) {
+        let file_system_edit = FileSystemEdit::DeleteFile { src };
+        self.source_change.push_file_system_edit(file_system_edit);
+    }
 
     fn finish(mut self) -> SourceChange {
         self.commit();
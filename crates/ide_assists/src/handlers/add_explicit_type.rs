@@ -1,12 +1,26 @@
-use hir::HirDisplay;
-use ide_db::syntax_helpers::node_ext::walk_ty;
+use hir::{
+    known, HasVisibility, HirDisplay, ModPath, Module, ModuleDef, Name, PathKind, PrefixKind,
+    ScopeDef, SemanticsScope, Visibility,
+};
+use ide_db::{
+    famous_defs::FamousDefs,
+    helpers::mod_path_to_ast,
+    imports::insert_use::{insert_use, ImportScope},
+    syntax_helpers::node_ext::walk_ty,
+    RootDatabase,
+};
+use itertools::Itertools;
+use rustc_hash::FxHashMap;
+use stdx::format_to;
 use syntax::ast::{self, AstNode, LetStmt, Param};
 
 use crate::{AssistContext, AssistId, AssistKind, Assists};
 
 // Assist: add_explicit_type
 //
-// Specify type for a let binding.
+// Specify type for a let binding, importing the items it names. Types that can't be named, like
+// closures, are approximated by an `impl Trait` they implement, which documents the binding but
+// isn't accepted by the compiler in a `let`.
 //
 // ```
 // fn main() {
@@ -41,7 +55,8 @@ pub(crate) fn add_explicit_type(acc: &mut Assists, ctx: &AssistContext) -> Optio
         return None;
     };
 
-    let module = ctx.sema.scope(pat.syntax()).module()?;
+    let scope = ctx.sema.scope(pat.syntax());
+    let module = scope.module()?;
     let pat_range = pat.syntax().text_range();
 
     // Don't enable the assist if there is a type ascription without any placeholders
@@ -54,34 +69,360 @@ pub(crate) fn add_explicit_type(acc: &mut Assists, ctx: &AssistContext) -> Optio
         }
     }
 
-    let ty = match (pat, expr) {
-        (ast::Pat::IdentPat(_), Some(expr)) => ctx.sema.type_of_expr(&expr)?,
-        (pat, _) => ctx.sema.type_of_pat(&pat)?,
+    let ty = match (&pat, &expr) {
+        (ast::Pat::IdentPat(_), Some(expr)) => ctx.sema.type_of_expr(expr)?,
+        (pat, _) => ctx.sema.type_of_pat(pat)?,
     }
     .adjusted();
 
-    // Fully unresolved or unnameable types can't be annotated
-    if (ty.contains_unknown() && ty.type_arguments().count() == 0) || ty.is_closure() {
+    // Fully unresolved types can't be annotated
+    if ty.contains_unknown() && ty.type_arguments().count() == 0 {
         cov_mark::hit!(add_explicit_type_not_applicable_if_ty_not_inferred);
         return None;
     }
 
-    let inferred_type = ty.display_source_code(ctx.db(), module.into()).ok()?;
+    let mut renderer = TypeRenderer::new(ctx, &scope, module);
+    if let Some(ast::Expr::ClosureExpr(closure)) = &expr {
+        renderer.closure_kind = Some(closure_kind(ctx, closure));
+    }
+    let (label, inferred_type) = match renderer.render(&ty) {
+        Some(it) => (format!("Insert explicit type `{}`", it), it),
+        None => {
+            let it = renderer.render_abbreviated(&ty)?;
+            (format!("Insert approximate type `{}`", it), it)
+        }
+    };
+    let imports = renderer.imports;
+    let import_scope = if imports.is_empty() {
+        None
+    } else {
+        Some(ImportScope::find_insert_use_container(pat.syntax(), &ctx.sema)?)
+    };
+
     acc.add(
         AssistId("add_explicit_type", AssistKind::RefactorRewrite),
-        format!("Insert explicit type `{}`", inferred_type),
+        label,
         pat_range,
-        |builder| match ascribed_ty {
-            Some(ascribed_ty) => {
-                builder.replace(ascribed_ty.syntax().text_range(), inferred_type);
+        |builder| {
+            if let Some(scope) = import_scope {
+                let scope = match scope {
+                    ImportScope::File(it) => ImportScope::File(builder.make_mut(it)),
+                    ImportScope::Module(it) => ImportScope::Module(builder.make_mut(it)),
+                    ImportScope::Block(it) => ImportScope::Block(builder.make_mut(it)),
+                };
+                for path in &imports {
+                    insert_use(&scope, mod_path_to_ast(path), &ctx.config.insert_use);
+                }
             }
-            None => {
-                builder.insert(pat_range.end(), format!(": {}", inferred_type));
+            match ascribed_ty {
+                Some(ascribed_ty) => {
+                    builder.replace(ascribed_ty.syntax().text_range(), inferred_type);
+                }
+                None => {
+                    builder.insert(pat_range.end(), format!(": {}", inferred_type));
+                }
             }
         },
     )
 }
 
+/// Renders types for the annotation, collecting the imports that let the items they mention be
+/// written by name.
+struct TypeRenderer<'a> {
+    db: &'a RootDatabase,
+    iterator: Option<hir::Trait>,
+    module: Module,
+    prefix_kind: PrefixKind,
+    names_in_scope: FxHashMap<Name, ScopeDef>,
+    imports: Vec<ModPath>,
+    /// The `Fn*` trait of the closure being annotated, if it's one.
+    closure_kind: Option<&'static str>,
+}
+
+impl<'a> TypeRenderer<'a> {
+    fn new(ctx: &'a AssistContext, scope: &SemanticsScope, module: Module) -> TypeRenderer<'a> {
+        let mut names_in_scope = FxHashMap::default();
+        scope.process_all_names(&mut |name, def| {
+            names_in_scope.entry(name).or_insert(def);
+        });
+        TypeRenderer {
+            db: ctx.db(),
+            iterator: FamousDefs(&ctx.sema, Some(module.krate())).core_iter_Iterator(),
+            module,
+            prefix_kind: ctx.config.insert_use.prefix_kind,
+            names_in_scope,
+            imports: Vec::new(),
+            closure_kind: None,
+        }
+    }
+
+    fn render(&mut self, ty: &hir::Type) -> Option<String> {
+        let mut items = Vec::new();
+        let text = match self.render_as_alias(ty) {
+            Some((alias, args, text)) => {
+                items.push(ModuleDef::TypeAlias(alias));
+                args.iter().for_each(|arg| collect_adts(self.db, arg, &mut items));
+                text
+            }
+            None => {
+                collect_adts(self.db, ty, &mut items);
+                ty.display_source_code(self.db, self.module.into()).ok()?
+            }
+        };
+        Some(items.into_iter().unique().fold(text, |text, item| self.shorten(text, item)))
+    }
+
+    /// Renders `ty` as the public type alias it is an instance of, if any, like `io::Result<T>`
+    /// for `Result<T, io::Error>`.
+    fn render_as_alias(&self, ty: &hir::Type) -> Option<(hir::TypeAlias, Vec<hir::Type>, String)> {
+        let adt = ty.as_adt()?;
+        if ty.contains_unknown() {
+            return None;
+        }
+        // Aliases live next to the types they abbreviate, so only look in the modules of the
+        // types `ty` is made of.
+        let mut adts = Vec::new();
+        collect_adts(self.db, ty, &mut adts);
+        let (alias, args) = adts
+            .into_iter()
+            .filter_map(|it| it.module(self.db))
+            .unique()
+            .flat_map(|module| module.declarations(self.db))
+            .find_map(|def| match def {
+                ModuleDef::TypeAlias(alias)
+                    if alias.visibility(self.db) == Visibility::Public
+                        && alias.ty(self.db).as_adt() == Some(adt) =>
+                {
+                    Some((alias, ty.as_instance_of_alias(self.db, alias)?))
+                }
+                _ => None,
+            })?;
+
+        let mut text = self.module.find_use_path(self.db, ModuleDef::TypeAlias(alias))?.to_string();
+        if !args.is_empty() {
+            let args = args
+                .iter()
+                .map(|arg| arg.display_source_code(self.db, self.module.into()).ok())
+                .collect::<Option<Vec<_>>>()?;
+            format_to!(text, "<{}>", args.iter().format(", "));
+        }
+        Some((alias, args, text))
+    }
+
+    /// Renders a type that can't be named, like a closure or an iterator adapter over one, as an
+    /// `impl Trait` it implements.
+    fn render_abbreviated(&mut self, ty: &hir::Type) -> Option<String> {
+        let imports = self.imports.len();
+        let res = self.render_abbreviated_(ty);
+        if res.is_none() {
+            self.imports.truncate(imports);
+        }
+        res
+    }
+
+    fn render_abbreviated_(&mut self, ty: &hir::Type) -> Option<String> {
+        if ty.is_closure() {
+            // Without the closure's body, only `FnOnce` is known to hold.
+            let kind = self.closure_kind.take().unwrap_or("FnOnce");
+            let callable = ty.as_callable(self.db)?;
+            let params = callable
+                .params(self.db)
+                .iter()
+                .map(|(_, ty)| self.render_or_abbreviate(ty))
+                .collect::<Option<Vec<_>>>()?;
+            let mut text = format!("impl {}({})", kind, params.iter().format(", "));
+            let ret = callable.return_type();
+            if !ret.is_unit() {
+                format_to!(text, " -> {}", self.render_or_abbreviate(&ret)?);
+            }
+            return Some(text);
+        }
+
+        let iterator = self.iterator?;
+        if !ty.impls_trait(self.db, iterator, &[]) {
+            return None;
+        }
+        let item = iterator.items(self.db).into_iter().find_map(|it| match it {
+            hir::AssocItem::TypeAlias(alias) if alias.name(self.db) == known::Item => Some(alias),
+            _ => None,
+        })?;
+        let item = ty.normalize_trait_assoc_type(self.db, &[], item)?;
+        Some(format!("impl Iterator<Item = {}>", self.render_or_abbreviate(&item)?))
+    }
+
+    fn render_or_abbreviate(&mut self, ty: &hir::Type) -> Option<String> {
+        self.render(ty).or_else(|| self.render_abbreviated(ty))
+    }
+
+    /// Imports `item` if that lets `text` refer to it by a shorter path.
+    fn shorten(&mut self, text: String, item: ModuleDef) -> String {
+        let (path, name) = match (self.module.find_use_path(self.db, item), item.name(self.db)) {
+            (Some(path), Some(name)) if !path.is_ident() => (path, name),
+            _ => return text,
+        };
+        if path.kind == PathKind::Plain {
+            // Paths through a module that's already in scope, like `io::Error`, are short enough.
+            let first = path.segments().first().and_then(|it| self.names_in_scope.get(it));
+            if let Some(ScopeDef::ModuleDef(ModuleDef::Module(module))) = first {
+                if module.parent(self.db).is_some() {
+                    return text;
+                }
+            }
+        }
+        let mut import = match self.module.find_use_path_prefixed(self.db, item, self.prefix_kind) {
+            Some(it) => it,
+            None => return text,
+        };
+
+        // When the name is taken, import the parent module instead, like `use std::io;` to write
+        // `io::Result<T>` next to the prelude's `Result`.
+        let (short, def, replacement) = if self.names_in_scope.contains_key(&name) {
+            let parent = match path.segments() {
+                [.., parent, _] if path.kind == PathKind::Plain => parent.clone(),
+                _ => return text,
+            };
+            if self.names_in_scope.contains_key(&parent) || import.pop_segment().is_none() {
+                return text;
+            }
+            let replacement = format!("{}::{}", parent, name);
+            (parent, ScopeDef::Unknown, replacement)
+        } else {
+            let replacement = name.to_string();
+            (name, ScopeDef::ModuleDef(item), replacement)
+        };
+
+        let shortened = replace_path(&text, &path.to_string(), &replacement);
+        if shortened == text {
+            return text;
+        }
+        self.names_in_scope.insert(short, def);
+        self.imports.push(import);
+        shortened
+    }
+}
+
+/// Finds the narrowest `Fn*` trait `closure` implements from how its body uses the locals it
+/// captures.
+fn closure_kind(ctx: &AssistContext, closure: &ast::ClosureExpr) -> &'static str {
+    let db = ctx.db();
+    let closure_range = closure.syntax().text_range();
+    let mut kind = "Fn";
+    for path_expr in closure.syntax().descendants().filter_map(ast::PathExpr::cast) {
+        let local = match path_expr.path().and_then(|it| ctx.sema.resolve_path(&it)) {
+            Some(hir::PathResolution::Local(it)) => it,
+            _ => continue,
+        };
+        let src = local.source(db);
+        let def_range =
+            src.value.either(|it| it.syntax().text_range(), |it| it.syntax().text_range());
+        if src.file_id == ctx.file_id().into() && closure_range.contains_range(def_range) {
+            continue;
+        }
+
+        let expr = ast::Expr::from(path_expr);
+        if is_moved_out(ctx, &expr) && !local.ty(db).is_copy(db) {
+            return "FnOnce";
+        }
+        if requires_exclusive_access(ctx, &expr) {
+            kind = "FnMut";
+        }
+    }
+    kind
+}
+
+/// Checks if the value of `expr` is moved somewhere else.
+fn is_moved_out(ctx: &AssistContext, expr: &ast::Expr) -> bool {
+    let parent = match expr.syntax().parent() {
+        Some(it) => it,
+        None => return false,
+    };
+    if ast::ArgList::can_cast(parent.kind())
+        || ast::LetStmt::can_cast(parent.kind())
+        || ast::ReturnExpr::can_cast(parent.kind())
+        || ast::RecordExprField::can_cast(parent.kind())
+        || ast::ClosureExpr::can_cast(parent.kind())
+    {
+        return true;
+    }
+    if let Some(paren) = ast::ParenExpr::cast(parent.clone()) {
+        return is_moved_out(ctx, &paren.into());
+    }
+    if let Some(stmt_list) = ast::StmtList::cast(parent.clone()) {
+        let block = stmt_list.syntax().parent().and_then(ast::BlockExpr::cast);
+        return match (stmt_list.tail_expr(), block) {
+            (Some(tail), Some(block)) if tail.syntax() == expr.syntax() => {
+                is_moved_out(ctx, &block.into())
+            }
+            _ => false,
+        };
+    }
+    if let Some(bin_expr) = ast::BinExpr::cast(parent.clone()) {
+        return matches!(bin_expr.op_kind(), Some(ast::BinaryOp::Assignment { op: None }))
+            && bin_expr.rhs().map_or(false, |it| it.syntax() == expr.syntax());
+    }
+    if let Some(method_call) = ast::MethodCallExpr::cast(parent) {
+        let access = ctx
+            .sema
+            .resolve_method_call(&method_call)
+            .and_then(|it| it.self_param(ctx.db()))
+            .map(|it| it.access(ctx.db()));
+        return matches!(access, Some(hir::Access::Owned));
+    }
+    false
+}
+
+/// Checks if `expr` is assigned to or borrowed mutably, looking through field accesses.
+fn requires_exclusive_access(ctx: &AssistContext, expr: &ast::Expr) -> bool {
+    let parent = match expr.syntax().parent() {
+        Some(it) => it,
+        None => return false,
+    };
+    if let Some(bin_expr) = ast::BinExpr::cast(parent.clone()) {
+        return matches!(bin_expr.op_kind(), Some(ast::BinaryOp::Assignment { .. }))
+            && bin_expr.lhs().map_or(false, |it| it.syntax() == expr.syntax());
+    }
+    if let Some(ref_expr) = ast::RefExpr::cast(parent.clone()) {
+        return ref_expr.mut_token().is_some();
+    }
+    if let Some(method_call) = ast::MethodCallExpr::cast(parent.clone()) {
+        let access = ctx
+            .sema
+            .resolve_method_call(&method_call)
+            .and_then(|it| it.self_param(ctx.db()))
+            .map(|it| it.access(ctx.db()));
+        return matches!(access, Some(hir::Access::Exclusive));
+    }
+    if let Some(field) = ast::FieldExpr::cast(parent) {
+        return requires_exclusive_access(ctx, &field.into());
+    }
+    false
+}
+
+fn collect_adts(db: &RootDatabase, ty: &hir::Type, acc: &mut Vec<ModuleDef>) {
+    ty.walk(db, |it| {
+        if let Some(adt) = it.as_adt() {
+            acc.push(ModuleDef::Adt(adt));
+        }
+    });
+}
+
+/// Replaces the occurrences of `path` in `text` that aren't part of a longer path.
+fn replace_path(text: &str, path: &str, replacement: &str) -> String {
+    let is_path_char = |c: char| c.is_alphanumeric() || c == '_' || c == ':';
+    let mut res = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(idx) = rest.find(path) {
+        let (before, after) = rest.split_at(idx);
+        res.push_str(before);
+        let after = &after[path.len()..];
+        let standalone = !res.ends_with(is_path_char) && !after.starts_with(is_path_char);
+        res.push_str(if standalone { replacement } else { path });
+        rest = after;
+    }
+    res.push_str(rest);
+    res
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -168,8 +509,218 @@ fn f() {
     }
 
     #[test]
-    fn add_explicit_type_not_applicable_closure_expr() {
-        check_assist_not_applicable(add_explicit_type, r#"fn f() { let a$0 = || {}; }"#);
+    fn add_explicit_type_abbreviates_closure_expr() {
+        check_assist(
+            add_explicit_type,
+            r#"fn f() { let a$0 = || {}; }"#,
+            r#"fn f() { let a: impl Fn() = || {}; }"#,
+        );
+        check_assist(
+            add_explicit_type,
+            r#"fn f() { let a$0 = |x: i32| x; }"#,
+            r#"fn f() { let a: impl Fn(i32) -> i32 = |x: i32| x; }"#,
+        );
+    }
+
+    #[test]
+    fn add_explicit_type_abbreviates_fn_mut_closure() {
+        check_assist(
+            add_explicit_type,
+            r#"
+struct V;
+impl V {
+    fn push(&mut self, _: i32) {}
+}
+fn f() {
+    let mut v = V;
+    let a$0 = || v.push(1);
+}
+"#,
+            r#"
+struct V;
+impl V {
+    fn push(&mut self, _: i32) {}
+}
+fn f() {
+    let mut v = V;
+    let a: impl FnMut() = || v.push(1);
+}
+"#,
+        );
+        check_assist(
+            add_explicit_type,
+            r#"
+fn f() {
+    let mut n = 0;
+    let a$0 = || n += 1;
+}
+"#,
+            r#"
+fn f() {
+    let mut n = 0;
+    let a: impl FnMut() = || n += 1;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn add_explicit_type_abbreviates_fn_once_closure() {
+        check_assist(
+            add_explicit_type,
+            r#"
+struct S;
+fn drop(_: S) {}
+fn f() {
+    let s = S;
+    let a$0 = move || drop(s);
+}
+"#,
+            r#"
+struct S;
+fn drop(_: S) {}
+fn f() {
+    let s = S;
+    let a: impl FnOnce() = move || drop(s);
+}
+"#,
+        );
+        check_assist(
+            add_explicit_type,
+            r#"
+struct S;
+fn f() {
+    let s = S;
+    let a$0 = || s;
+}
+"#,
+            r#"
+struct S;
+fn f() {
+    let s = S;
+    let a: impl FnOnce() -> S = || s;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn add_explicit_type_abbreviates_iterator_over_closure() {
+        check_assist(
+            add_explicit_type,
+            r#"
+//- minicore: iterator, option
+struct Repeat<F>(F);
+impl<F> Iterator for Repeat<F> {
+    type Item = u8;
+    fn next(&mut self) -> Option<u8> {
+        None
+    }
+}
+fn f() {
+    let it$0 = Repeat(|| 1);
+}
+"#,
+            r#"
+struct Repeat<F>(F);
+impl<F> Iterator for Repeat<F> {
+    type Item = u8;
+    fn next(&mut self) -> Option<u8> {
+        None
+    }
+}
+fn f() {
+    let it: impl Iterator<Item = u8> = Repeat(|| 1);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn add_explicit_type_imports_type() {
+        check_assist(
+            add_explicit_type,
+            r#"
+//- /main.rs crate:main deps:foo
+fn f() {
+    let x$0 = foo::make();
+}
+//- /foo.rs crate:foo
+pub mod bar {
+    pub struct Baz<T>(T);
+}
+pub struct Qux;
+pub fn make() -> bar::Baz<Qux> {
+    bar::Baz(Qux)
+}
+"#,
+            r#"
+use foo::{bar::Baz, Qux};
+
+fn f() {
+    let x: Baz<Qux> = foo::make();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn add_explicit_type_keeps_path_when_name_is_taken() {
+        check_assist(
+            add_explicit_type,
+            r#"
+//- /main.rs crate:main deps:foo
+struct Baz;
+fn f() {
+    let x$0 = foo::make();
+}
+//- /foo.rs crate:foo
+pub struct Baz;
+pub fn make() -> Baz {
+    Baz
+}
+"#,
+            r#"
+struct Baz;
+fn f() {
+    let x: foo::Baz = foo::make();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn add_explicit_type_prefers_public_alias() {
+        check_assist(
+            add_explicit_type,
+            r#"
+//- minicore: result
+//- /main.rs crate:main deps:std
+fn f() {
+    let x$0 = std::io::read();
+}
+//- /std.rs crate:std
+pub mod prelude {
+    pub mod v1 {
+        pub use core::result::Result;
+    }
+}
+pub mod io {
+    pub struct Error;
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub fn read() -> Result<u8> {
+        loop {}
+    }
+}
+"#,
+            r#"
+use std::io;
+
+fn f() {
+    let x: io::Result<u8> = std::io::read();
+}
+"#,
+        );
     }
 
     #[test]
@@ -63,6 +63,14 @@ use crate::{AssistContext, AssistId, AssistKind, Assists, GroupLabel};
 //
 // In `VS Code` the configuration for this is `rust-analyzer.assist.importPrefix`.
 //
+// .Always Qualify
+//
+// Items of the kinds listed in the `qualifyInsteadOfImport` setting (`macro`, `function`,
+// `type` and `constant`) are never imported, the `qualify_path` assist writes out their path
+// instead.
+//
+// In `VS Code` the configuration for this is `rust-analyzer.assist.qualifyInsteadOfImport`.
+//
 // image::https://user-images.githubusercontent.com/48062697/113020673-b85be580-917a-11eb-9022-59585f35d4f8.gif[]
 
 // Assist: auto_import
@@ -88,6 +96,10 @@ pub(crate) fn auto_import(acc: &mut Assists, ctx: &AssistContext) -> Option<()>
     let (import_assets, syntax_under_caret) = find_importable_node(ctx)?;
     let mut proposed_imports =
         import_assets.search_for_imports(&ctx.sema, ctx.config.insert_use.prefix_kind);
+    if let ImportCandidate::Path(_) = import_assets.import_candidate() {
+        // These are left to `qualify_path`.
+        proposed_imports.retain(|import| !ctx.config.always_qualifies(import.original_item));
+    }
     if proposed_imports.is_empty() {
         return None;
     }
@@ -0,0 +1,340 @@
+use ide_db::syntax_helpers::node_ext::{is_pattern_cond, single_let};
+use itertools::Itertools;
+use stdx::format_to;
+use syntax::{
+    ast::{
+        self,
+        edit::{AstNodeEdit, IndentLevel},
+        AstNode,
+    },
+    NodeOrToken, SyntaxKind, T,
+};
+
+use crate::{AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: convert_nested_if_to_let_chain
+//
+// Merges an `if let` whose body is only another `if` into a single `if` with a let-chain.
+//
+// ```
+// fn main() {
+//     if$0 let Some(a) = foo() {
+//         if let Some(b) = bar(a) {
+//             baz(b);
+//         }
+//     }
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     if let Some(a) = foo() && let Some(b) = bar(a) {
+//         baz(b);
+//     }
+// }
+// ```
+pub(crate) fn convert_nested_if_to_let_chain(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let if_expr = if_at_cursor(ctx)?;
+    let cond = if_expr.condition()?;
+    let stmt_list = if_expr.then_branch()?.stmt_list()?;
+    if if_expr.else_branch().is_some() {
+        return None;
+    }
+
+    // The body must be nothing but the inner `if`, comments included.
+    let inner_if = match stmt_list.tail_expr()? {
+        ast::Expr::IfExpr(it) => it,
+        _ => return None,
+    };
+    let only_inner_if = stmt_list.syntax().children_with_tokens().all(|it| match it {
+        NodeOrToken::Node(node) => node == *inner_if.syntax(),
+        NodeOrToken::Token(token) => {
+            matches!(token.kind(), T!['{'] | T!['}'] | SyntaxKind::WHITESPACE)
+        }
+    });
+    if !only_inner_if || inner_if.else_branch().is_some() {
+        return None;
+    }
+    let inner_cond = inner_if.condition()?;
+    let inner_then = inner_if.then_branch()?;
+    if !is_pattern_cond(cond.clone()) && !is_pattern_cond(inner_cond.clone()) {
+        return None;
+    }
+
+    let target = if_expr.syntax().text_range();
+    acc.add(
+        AssistId("convert_nested_if_to_let_chain", AssistKind::RefactorRewrite),
+        "Convert to let-chain",
+        target,
+        |builder| {
+            let then = inner_then.dedent(IndentLevel(1));
+            let text = format!(
+                "if {} && {} {}",
+                chain_operand(&cond),
+                chain_operand(&inner_cond),
+                then.syntax()
+            );
+            builder.replace(target, text);
+        },
+    )
+}
+
+// Assist: convert_let_chain_to_nested_if
+//
+// Splits an `if` with a let-chain into nested `if`s, one per condition.
+//
+// ```
+// fn main() {
+//     if$0 let Some(a) = foo() && let Some(b) = bar(a) {
+//         baz(b);
+//     }
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     if let Some(a) = foo() {
+//         if let Some(b) = bar(a) {
+//             baz(b);
+//         }
+//     }
+// }
+// ```
+pub(crate) fn convert_let_chain_to_nested_if(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let if_expr = if_at_cursor(ctx)?;
+    let cond = if_expr.condition()?;
+    let then = if_expr.then_branch()?;
+    // An `else` would run when any of the conditions is false, which nested `if`s can't express.
+    if if_expr.else_branch().is_some() || !is_pattern_cond(cond.clone()) {
+        return None;
+    }
+    let mut operands = Vec::new();
+    collect_chain_operands(cond, &mut operands);
+    if operands.len() < 2 {
+        return None;
+    }
+
+    let target = if_expr.syntax().text_range();
+    acc.add(
+        AssistId("convert_let_chain_to_nested_if", AssistKind::RefactorRewrite),
+        "Convert to nested `if`s",
+        target,
+        |builder| {
+            let indent = if_expr.indent_level();
+            let depth = operands.len() as u8 - 1;
+            let mut buf = String::new();
+            for (level, operand) in operands.iter().enumerate() {
+                if level != 0 {
+                    format_to!(buf, "\n{}", indent + level as u8);
+                }
+                format_to!(buf, "if {} ", operand);
+                if level as u8 != depth {
+                    buf.push('{');
+                }
+            }
+            format_to!(buf, "{}", then.indent(IndentLevel(depth)).syntax());
+            let closing = (0..depth).rev().map(|level| format!("\n{}}}", indent + level)).join("");
+            buf.push_str(&closing);
+            builder.replace(target, buf);
+        },
+    )
+}
+
+fn if_at_cursor(ctx: &AssistContext) -> Option<ast::IfExpr> {
+    let if_keyword = ctx.find_token_syntax_at_offset(T![if])?;
+    let if_expr = ast::IfExpr::cast(if_keyword.parent()?)?;
+    if !if_keyword.text_range().contains_range(ctx.selection_trimmed()) {
+        return None;
+    }
+    Some(if_expr)
+}
+
+/// The operands of the `&&`s making up `cond`, from left to right.
+fn collect_chain_operands(cond: ast::Expr, acc: &mut Vec<ast::Expr>) {
+    match &cond {
+        ast::Expr::BinExpr(bin_expr)
+            if bin_expr.op_kind() == Some(ast::BinaryOp::LogicOp(ast::LogicOp::And)) =>
+        {
+            if let (Some(lhs), Some(rhs)) = (bin_expr.lhs(), bin_expr.rhs()) {
+                collect_chain_operands(lhs, acc);
+                collect_chain_operands(rhs, acc);
+                return;
+            }
+        }
+        ast::Expr::ParenExpr(_) => {
+            if let Some(let_expr) = single_let(cond.clone()) {
+                acc.push(let_expr.into());
+                return;
+            }
+        }
+        _ => (),
+    }
+    acc.push(cond);
+}
+
+/// `cond` as written, parenthesized if it would otherwise bind differently next to a `&&`.
+fn chain_operand(cond: &ast::Expr) -> String {
+    match cond {
+        ast::Expr::BinExpr(bin_expr)
+            if bin_expr.op_kind() == Some(ast::BinaryOp::LogicOp(ast::LogicOp::Or)) =>
+        {
+            format!("({})", cond)
+        }
+        _ => cond.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn merges_nested_if_lets() {
+        check_assist(
+            convert_nested_if_to_let_chain,
+            r#"
+fn f(a: Option<i32>, b: Option<i32>) {
+    if$0 let Some(x) = a {
+        if let Some(y) = b {
+            if x > y {
+                g();
+            }
+        }
+    }
+}
+"#,
+            r#"
+fn f(a: Option<i32>, b: Option<i32>) {
+    if let Some(x) = a && let Some(y) = b {
+        if x > y {
+            g();
+        }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn merges_with_plain_condition() {
+        check_assist(
+            convert_nested_if_to_let_chain,
+            r#"
+fn f(a: Option<i32>, b: bool, c: bool) {
+    $0if b || c {
+        if let Some(x) = a {
+            g(x);
+        }
+    }
+}
+"#,
+            r#"
+fn f(a: Option<i32>, b: bool, c: bool) {
+    if (b || c) && let Some(x) = a {
+        g(x);
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_with_other_statements_or_else() {
+        check_assist_not_applicable(
+            convert_nested_if_to_let_chain,
+            r#"
+fn f(a: Option<i32>, b: Option<i32>) {
+    if$0 let Some(x) = a {
+        g();
+        if let Some(y) = b {}
+    }
+}
+"#,
+        );
+        check_assist_not_applicable(
+            convert_nested_if_to_let_chain,
+            r#"
+fn f(a: Option<i32>, b: Option<i32>) {
+    if$0 let Some(x) = a {
+        // comment
+        if let Some(y) = b {}
+    }
+}
+"#,
+        );
+        check_assist_not_applicable(
+            convert_nested_if_to_let_chain,
+            r#"
+fn f(a: Option<i32>, b: Option<i32>) {
+    if$0 let Some(x) = a {
+        if let Some(y) = b {}
+    } else {
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_without_let() {
+        check_assist_not_applicable(
+            convert_nested_if_to_let_chain,
+            r#"
+fn f(a: bool, b: bool) {
+    if$0 a {
+        if b {}
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn splits_let_chain() {
+        check_assist(
+            convert_let_chain_to_nested_if,
+            r#"
+fn f(a: Option<i32>, b: Option<i32>) {
+    if$0 let Some(x) = a && (let Some(y) = b) && x > y {
+        g(x);
+    }
+}
+"#,
+            r#"
+fn f(a: Option<i32>, b: Option<i32>) {
+    if let Some(x) = a {
+        if let Some(y) = b {
+            if x > y {
+                g(x);
+            }
+        }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_split_with_else_or_without_let() {
+        check_assist_not_applicable(
+            convert_let_chain_to_nested_if,
+            r#"
+fn f(a: Option<i32>, b: Option<i32>) {
+    if$0 let Some(x) = a && let Some(y) = b {
+    } else {
+    }
+}
+"#,
+        );
+        check_assist_not_applicable(
+            convert_let_chain_to_nested_if,
+            r#"
+fn f(a: bool, b: bool) {
+    if$0 a && b {}
+}
+"#,
+        );
+    }
+}
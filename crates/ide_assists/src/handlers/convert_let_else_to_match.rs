@@ -0,0 +1,446 @@
+use hir::Edition;
+use ide_db::syntax_helpers::node_ext::single_let;
+use syntax::{
+    ast::{
+        self,
+        edit::{AstNodeEdit, IndentLevel},
+        make, HasName,
+    },
+    ted, AstNode, T,
+};
+
+use crate::{utils::unwrap_trivial_block, AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: convert_let_else_to_match
+//
+// Converts a `let ... else` statement into a `let` binding the result of a `match`.
+//
+// ```
+// //- minicore: option
+// fn main(opt: Option<i32>) {
+//     $0let Some(x) = opt else { return };
+// }
+// ```
+// ->
+// ```
+// fn main(opt: Option<i32>) {
+//     let x = match opt {
+//         Some(x) => x,
+//         _ => return,
+//     };
+// }
+// ```
+pub(crate) fn convert_let_else_to_match(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let let_kw = ctx.find_token_syntax_at_offset(T![let])?;
+    let let_stmt = let_kw.parent().and_then(ast::LetStmt::cast)?;
+    let else_block = let_stmt.let_else()?.block_expr()?;
+    let pat = let_stmt.pat()?;
+    let init = let_stmt.initializer()?;
+    // The type ascription applies to the whole pattern, not to the bindings.
+    if let_stmt.ty().is_some() {
+        return None;
+    }
+    let bindings = bindings(ctx, &pat);
+    if bindings.is_empty() {
+        return None;
+    }
+
+    let target = let_stmt.syntax().text_range();
+    acc.add(
+        AssistId("convert_let_else_to_match", AssistKind::RefactorRewrite),
+        "Convert let-else to match",
+        target,
+        |builder| {
+            let binding_name = |binding: &ast::IdentPat| binding.name().map(|it| it.to_string());
+            let names: Vec<String> = bindings.iter().filter_map(binding_name).collect();
+
+            // `mut` moves from the bindings of the arm to the `let`.
+            let arm_pat = pat.clone_for_update();
+            for binding in arm_pat.syntax().descendants().filter_map(ast::IdentPat::cast) {
+                if binding.ref_token().is_some() {
+                    continue;
+                }
+                if let Some(mut_token) = binding.mut_token() {
+                    if let Some(ws) = mut_token.next_token().filter(|it| it.kind().is_trivia()) {
+                        ted::remove(ws);
+                    }
+                    ted::remove(mut_token);
+                }
+            }
+
+            let is_mut = |binding: &ast::IdentPat| {
+                binding.ref_token().is_none() && binding.mut_token().is_some()
+            };
+            let let_pat = |binding: &ast::IdentPat, name: &str| -> ast::Pat {
+                make::ident_pat(false, is_mut(binding), make::name(name)).into()
+            };
+            let (new_pat, arm_expr) = match &*names {
+                [name] => {
+                    (let_pat(&bindings[0], name), make::expr_path(make::ext::ident_path(name)))
+                }
+                _ => (
+                    make::tuple_pat(bindings.iter().zip(&names).map(|(b, n)| let_pat(b, n))).into(),
+                    make::expr_tuple(
+                        names.iter().map(|name| make::expr_path(make::ext::ident_path(name))),
+                    ),
+                ),
+            };
+
+            let arms = [
+                make::match_arm([arm_pat], None, arm_expr),
+                make::match_arm(
+                    [make::wildcard_pat().into()],
+                    None,
+                    unwrap_trivial_block(else_block.reset_indent().indent(IndentLevel(1))),
+                ),
+            ];
+            let match_expr = make::expr_match(init, make::match_arm_list(arms))
+                .indent(IndentLevel::from_node(let_stmt.syntax()));
+            let new_stmt = make::let_stmt(new_pat, None, Some(match_expr));
+            builder.replace(target, new_stmt.to_string());
+        },
+    )
+}
+
+// Assist: convert_to_let_else
+//
+// Converts a `let` binding the result of a `match` or an `if let`, whose other branch diverges,
+// into a `let ... else` statement. Requires the 2021 edition.
+//
+// ```
+// //- minicore: option
+// //- /main.rs crate:main edition:2021
+// fn main(opt: Option<i32>) {
+//     $0let x = match opt {
+//         Some(x) => x,
+//         None => return,
+//     };
+// }
+// ```
+// ->
+// ```
+// fn main(opt: Option<i32>) {
+//     let Some(x) = opt else {
+//         return
+//     };
+// }
+// ```
+pub(crate) fn convert_to_let_else(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let let_kw = ctx.find_token_syntax_at_offset(T![let])?;
+    let let_stmt = let_kw.parent().and_then(ast::LetStmt::cast)?;
+    if let_stmt.let_else().is_some() || let_stmt.ty().is_some() {
+        return None;
+    }
+    let binding = match let_stmt.pat()? {
+        ast::Pat::IdentPat(it) if it.pat().is_none() => it,
+        _ => return None,
+    };
+    let refutable = match let_stmt.initializer()? {
+        ast::Expr::MatchExpr(it) => refutable_from_match(ctx, &it)?,
+        ast::Expr::IfExpr(it) => refutable_from_if_let(ctx, &it)?,
+        _ => return None,
+    };
+    let krate = ctx.sema.scope(let_stmt.syntax()).krate()?;
+    if krate.edition(ctx.db()) < Edition::Edition2021 {
+        cov_mark::hit!(let_else_needs_edition_2021);
+        return None;
+    }
+
+    let target = let_stmt.syntax().text_range();
+    acc.add(
+        AssistId("convert_to_let_else", AssistKind::RefactorRewrite),
+        "Convert to let-else",
+        target,
+        |builder| {
+            let Refutable { scrutinee, pat, bound, diverging } = refutable;
+
+            let index = pat
+                .syntax()
+                .descendants()
+                .filter_map(ast::IdentPat::cast)
+                .position(|it| it == bound);
+            let pat = pat.clone_for_update();
+            let new_bound = index.and_then(|idx| {
+                pat.syntax().descendants().filter_map(ast::IdentPat::cast).nth(idx)
+            });
+            if let (Some(new_bound), Some(name)) = (new_bound, binding.name()) {
+                let ref_ = bound.ref_token().is_some() || binding.ref_token().is_some();
+                let mut_ = bound.mut_token().is_some() || binding.mut_token().is_some();
+                let replacement = make::ident_pat(ref_, mut_, name).clone_for_update();
+                ted::replace(new_bound.syntax(), replacement.syntax());
+            }
+
+            // `let PAT = EXPR else` is ambiguous if `EXPR` ends with a `}` or is a lazy boolean.
+            let scrutinee = match scrutinee {
+                ast::Expr::BinExpr(_) => make::expr_paren(scrutinee),
+                it if it.is_block_like() => make::expr_paren(it),
+                it => it,
+            };
+
+            let indent = IndentLevel::from_node(let_stmt.syntax());
+            let else_block = match diverging {
+                ast::Expr::BlockExpr(block) if block.modifier().is_none() => {
+                    block.reset_indent().indent(indent)
+                }
+                expr => make::block_expr(None, Some(expr.reset_indent())).indent(indent),
+            };
+            builder.replace(
+                target,
+                format!("let {} = {} else {};", pat.syntax(), scrutinee, else_block),
+            );
+        },
+    )
+}
+
+/// The parts of a `match` or `if let` which can become a `let ... else`.
+struct Refutable {
+    scrutinee: ast::Expr,
+    /// The pattern of the branch which evaluates to one of its bindings.
+    pat: ast::Pat,
+    /// The binding of `pat` which its branch evaluates to.
+    bound: ast::IdentPat,
+    /// The expression of the other branch, which must not bind anything.
+    diverging: ast::Expr,
+}
+
+fn refutable_from_match(ctx: &AssistContext, match_expr: &ast::MatchExpr) -> Option<Refutable> {
+    let scrutinee = match_expr.expr()?;
+    let arms: Vec<_> = match_expr.match_arm_list()?.arms().collect();
+    let (first, second) = match &*arms {
+        [first, second] if first.guard().is_none() && second.guard().is_none() => (first, second),
+        _ => return None,
+    };
+    [(first, second), (second, first)].into_iter().find_map(|(happy, other)| {
+        let pat = happy.pat()?;
+        let bound = evaluated_binding(ctx, &pat, happy.expr()?)?;
+        let diverging = diverging_branch(ctx, &other.pat()?, other.expr()?)?;
+        Some(Refutable { scrutinee: scrutinee.clone(), pat, bound, diverging })
+    })
+}
+
+fn refutable_from_if_let(ctx: &AssistContext, if_expr: &ast::IfExpr) -> Option<Refutable> {
+    let let_expr = single_let(if_expr.condition()?)?;
+    let else_block = match if_expr.else_branch()? {
+        ast::ElseBranch::Block(it) => it,
+        ast::ElseBranch::IfExpr(_) => return None,
+    };
+    let pat = let_expr.pat()?;
+    let bound = evaluated_binding(ctx, &pat, if_expr.then_branch()?.into())?;
+    let diverging = diverging_branch(ctx, &make::wildcard_pat().into(), else_block.into())?;
+    Some(Refutable { scrutinee: let_expr.expr()?, pat, bound, diverging })
+}
+
+/// The binding of `pat` which `expr` consists of, if any.
+fn evaluated_binding(
+    ctx: &AssistContext,
+    pat: &ast::Pat,
+    expr: ast::Expr,
+) -> Option<ast::IdentPat> {
+    if matches!(pat, ast::Pat::IdentPat(it) if ctx.sema.to_def(it).is_some()) {
+        // An irrefutable binding, there's nothing for `else` to handle.
+        return None;
+    }
+    let expr = match expr {
+        ast::Expr::BlockExpr(block) => unwrap_trivial_block(block),
+        it => it,
+    };
+    let name_ref = match expr {
+        ast::Expr::PathExpr(it) => it.path()?.as_single_name_ref()?,
+        _ => return None,
+    };
+    bindings(ctx, pat)
+        .into_iter()
+        .find(|it| it.name().map_or(false, |name| name.text() == name_ref.text()))
+}
+
+fn diverging_branch(ctx: &AssistContext, pat: &ast::Pat, expr: ast::Expr) -> Option<ast::Expr> {
+    let diverges = ctx.sema.type_of_expr(&expr).map_or(false, |it| it.original.is_never());
+    if !diverges || !bindings(ctx, pat).is_empty() {
+        return None;
+    }
+    Some(expr)
+}
+
+/// The identifier patterns of `pat` which introduce a local, as opposed to naming a constant or a
+/// unit struct or variant.
+fn bindings(ctx: &AssistContext, pat: &ast::Pat) -> Vec<ast::IdentPat> {
+    pat.syntax()
+        .descendants()
+        .filter_map(ast::IdentPat::cast)
+        .filter(|it| ctx.sema.to_def(it).is_some())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn let_else_to_match_single_binding() {
+        check_assist(
+            convert_let_else_to_match,
+            r#"
+//- minicore: option
+fn f(opt: Option<i32>) -> i32 {
+    $0let Some(mut x) = opt else {
+        return 0;
+    };
+    x += 1;
+    x
+}
+"#,
+            r#"
+fn f(opt: Option<i32>) -> i32 {
+    let mut x = match opt {
+        Some(x) => x,
+        _ => {
+            return 0;
+        }
+    };
+    x += 1;
+    x
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn let_else_to_match_several_bindings() {
+        check_assist(
+            convert_let_else_to_match,
+            r#"
+//- minicore: option
+fn f(opt: Option<(i32, i32)>) {
+    $0let Some((a, b)) = opt else { panic!() };
+}
+"#,
+            r#"
+fn f(opt: Option<(i32, i32)>) {
+    let (a, b) = match opt {
+        Some((a, b)) => (a, b),
+        _ => panic!(),
+    };
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn let_else_to_match_not_applicable_without_bindings() {
+        check_assist_not_applicable(
+            convert_let_else_to_match,
+            r#"
+//- minicore: option
+fn f(opt: Option<i32>) {
+    $0let None = opt else { return };
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn match_to_let_else_renames_binding() {
+        check_assist(
+            convert_to_let_else,
+            r#"
+//- minicore: result
+//- /main.rs crate:main edition:2021
+fn f(res: Result<i32, ()>) -> i32 {
+    $0let mut value = match res {
+        Err(_) => {
+            return 0;
+        }
+        Ok(it) => it,
+    };
+    value
+}
+"#,
+            r#"
+fn f(res: Result<i32, ()>) -> i32 {
+    let Ok(mut value) = res else {
+        return 0;
+    };
+    value
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn if_let_to_let_else() {
+        check_assist(
+            convert_to_let_else,
+            r#"
+//- minicore: option
+//- /main.rs crate:main edition:2021
+fn f(opt: Option<i32>) -> i32 {
+    $0let x = if let Some(x) = opt { x } else { return 0 };
+    x
+}
+"#,
+            r#"
+fn f(opt: Option<i32>) -> i32 {
+    let Some(x) = opt else { return 0 };
+    x
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn to_let_else_not_applicable_when_other_branch_binds() {
+        check_assist_not_applicable(
+            convert_to_let_else,
+            r#"
+//- minicore: result
+//- /main.rs crate:main edition:2021
+fn f(res: Result<i32, i32>) -> Result<i32, i32> {
+    $0let x = match res {
+        Ok(x) => x,
+        Err(e) => return Err(e),
+    };
+    Ok(x)
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn to_let_else_not_applicable_when_not_diverging() {
+        check_assist_not_applicable(
+            convert_to_let_else,
+            r#"
+//- minicore: option
+//- /main.rs crate:main edition:2021
+fn f(opt: Option<i32>) -> i32 {
+    $0let x = match opt {
+        Some(x) => x,
+        None => 0,
+    };
+    x
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn to_let_else_not_applicable_before_edition_2021() {
+        cov_mark::check!(let_else_needs_edition_2021);
+        check_assist_not_applicable(
+            convert_to_let_else,
+            r#"
+//- minicore: option
+//- /main.rs crate:main edition:2018
+fn f(opt: Option<i32>) -> i32 {
+    $0let x = match opt {
+        Some(x) => x,
+        None => return 0,
+    };
+    x
+}
+"#,
+        );
+    }
+}
@@ -0,0 +1,544 @@
+use hir::{Access, AsAssocItem, AssocItemContainer, ItemInNs, ModuleDef};
+use ide_db::{
+    base_db::FileId,
+    defs::Definition,
+    helpers::mod_path_to_ast,
+    imports::import_assets::item_for_path_search,
+    rename::source_edit_from_references,
+    search::{FileReference, UsageSearchResult},
+};
+use rustc_hash::FxHashMap;
+use syntax::{
+    algo::find_node_at_range,
+    ast::{self, HasArgList},
+    AstNode, TextRange,
+};
+
+use crate::{assist_context::AssistBuilder, AssistContext, AssistId, AssistKind, Assists};
+
+const SELF_PARAM_REPLACEMENT: &str = "this";
+
+// Assist: convert_method_to_assoc_fn
+//
+// Turns the `self` parameter of a method into a regular parameter and rewrites method calls into
+// associated function calls.
+//
+// ```
+// struct Foo;
+// impl Foo {
+//     fn frobnicate(&$0self, x: u32) {}
+// }
+// fn main() {
+//     Foo.frobnicate(92);
+// }
+// ```
+// ->
+// ```
+// struct Foo;
+// impl Foo {
+//     fn frobnicate(this: &Self, x: u32) {}
+// }
+// fn main() {
+//     Foo::frobnicate(&Foo, 92);
+// }
+// ```
+pub(crate) fn convert_method_to_assoc_fn(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let self_param: ast::SelfParam = ctx.find_node_at_offset()?;
+    let fn_ast = self_param.syntax().ancestors().find_map(ast::Fn::cast)?;
+    let func = ctx.sema.to_def(&fn_ast)?;
+    if !is_inherent_method(ctx, func) {
+        cov_mark::hit!(method_to_assoc_fn_not_inherent);
+        return None;
+    }
+    let access = func.self_param(ctx.db())?.access(ctx.db());
+    let self_local = ctx.sema.to_def(&self_param)?;
+
+    let mut_prefix = if self_param.amp_token().is_none() && self_param.mut_token().is_some() {
+        "mut "
+    } else {
+        ""
+    };
+    let new_param = match self_param.ty() {
+        Some(ty) => format!("{}{}: {}", mut_prefix, SELF_PARAM_REPLACEMENT, ty),
+        None => {
+            let lifetime = self_param.lifetime().map_or(String::new(), |it| format!("{} ", it));
+            match self_param.kind() {
+                ast::SelfParamKind::Owned => {
+                    format!("{}{}: Self", mut_prefix, SELF_PARAM_REPLACEMENT)
+                }
+                ast::SelfParamKind::Ref => format!("{}: &{}Self", SELF_PARAM_REPLACEMENT, lifetime),
+                ast::SelfParamKind::MutRef => {
+                    format!("{}: &{}mut Self", SELF_PARAM_REPLACEMENT, lifetime)
+                }
+            }
+        }
+    };
+
+    acc.add(
+        AssistId("convert_method_to_assoc_fn", AssistKind::RefactorRewrite),
+        "Convert method to associated function",
+        self_param.syntax().text_range(),
+        |builder| {
+            builder.replace(self_param.syntax().text_range(), new_param);
+
+            let self_def = Definition::Local(self_local);
+            let self_usages = self_def.usages(&ctx.sema).all();
+            let call_edits = Definition::Function(func)
+                .usages(&ctx.sema)
+                .all()
+                .into_iter()
+                .map(|(file_id, references)| {
+                    let source_file = ctx.sema.parse(file_id);
+                    let edits = references
+                        .into_iter()
+                        .filter_map(|FileReference { range, .. }| {
+                            let call = find_node_at_range::<ast::MethodCallExpr>(
+                                source_file.syntax(),
+                                range,
+                            )?;
+                            method_call_to_assoc_fn_call(ctx, func, access, &call, range)
+                        })
+                        .flatten()
+                        .collect();
+                    (file_id, edits)
+                })
+                .collect();
+            apply_usage_edits(builder, self_def, SELF_PARAM_REPLACEMENT, self_usages, call_edits);
+        },
+    )
+}
+
+// Assist: convert_assoc_fn_to_method
+//
+// Turns the first parameter of an associated function into a `self` parameter and rewrites
+// associated function calls into method calls.
+//
+// ```
+// struct Foo;
+// impl Foo {
+//     fn frobnicate($0this: &Foo, x: u32) {}
+// }
+// fn main() {
+//     Foo::frobnicate(&Foo, 92);
+// }
+// ```
+// ->
+// ```
+// struct Foo;
+// impl Foo {
+//     fn frobnicate(&self, x: u32) {}
+// }
+// fn main() {
+//     Foo.frobnicate(92);
+// }
+// ```
+pub(crate) fn convert_assoc_fn_to_method(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let param: ast::Param = ctx.find_node_at_offset()?;
+    let ident_pat = match param.pat()? {
+        ast::Pat::IdentPat(it) => it,
+        _ => return None,
+    };
+    let fn_ast = param.syntax().ancestors().find_map(ast::Fn::cast)?;
+    let func = ctx.sema.to_def(&fn_ast)?;
+    if func.self_param(ctx.db()).is_some() {
+        return None;
+    }
+    if !is_inherent_method(ctx, func) {
+        cov_mark::hit!(assoc_fn_to_method_not_inherent);
+        return None;
+    }
+    let local = ctx.sema.to_def(&ident_pat)?;
+    let first_param = func.assoc_fn_params(ctx.db()).into_iter().next()?;
+    if first_param.as_local(ctx.db()) != local {
+        return None;
+    }
+
+    // Same check as when renaming a parameter to `self`.
+    let impl_ty = match func.as_assoc_item(ctx.db())?.container(ctx.db()) {
+        AssocItemContainer::Impl(impl_) => impl_.self_ty(ctx.db()),
+        AssocItemContainer::Trait(_) => return None,
+    };
+    let first_param_ty = first_param.ty();
+    let (ty, access) = match first_param_ty.as_reference() {
+        Some((ty, hir::Mutability::Shared)) => (ty, Access::Shared),
+        Some((ty, hir::Mutability::Mut)) => (ty, Access::Exclusive),
+        None => (first_param_ty.clone(), Access::Owned),
+    };
+    if ty != impl_ty {
+        cov_mark::hit!(assoc_fn_to_method_type_mismatch);
+        return None;
+    }
+
+    let lifetime = match param.ty() {
+        Some(ast::Type::RefType(ref_ty)) => {
+            ref_ty.lifetime().map_or(String::new(), |it| format!("{} ", it))
+        }
+        _ => String::new(),
+    };
+    let new_param = match access {
+        Access::Shared => format!("&{}self", lifetime),
+        Access::Exclusive => format!("&{}mut self", lifetime),
+        Access::Owned if ident_pat.mut_token().is_some() => "mut self".to_string(),
+        Access::Owned => "self".to_string(),
+    };
+
+    acc.add(
+        AssistId("convert_assoc_fn_to_method", AssistKind::RefactorRewrite),
+        "Convert associated function to method",
+        param.syntax().text_range(),
+        |builder| {
+            builder.replace(param.syntax().text_range(), new_param);
+
+            let param_def = Definition::Local(local);
+            let param_usages = param_def.usages(&ctx.sema).all();
+            let call_edits = Definition::Function(func)
+                .usages(&ctx.sema)
+                .all()
+                .into_iter()
+                .map(|(file_id, references)| {
+                    let source_file = ctx.sema.parse(file_id);
+                    let edits = references
+                        .into_iter()
+                        .filter_map(|FileReference { range, .. }| {
+                            let call =
+                                find_node_at_range::<ast::CallExpr>(source_file.syntax(), range)?;
+                            assoc_fn_call_to_method_call(access, &call, range)
+                        })
+                        .flatten()
+                        .collect();
+                    (file_id, edits)
+                })
+                .collect();
+            apply_usage_edits(builder, param_def, "self", param_usages, call_edits);
+        },
+    )
+}
+
+fn is_inherent_method(ctx: &AssistContext, func: hir::Function) -> bool {
+    match func.as_assoc_item(ctx.db()).map(|it| it.container(ctx.db())) {
+        Some(AssocItemContainer::Impl(impl_)) => impl_.trait_(ctx.db()).is_none(),
+        _ => false,
+    }
+}
+
+/// Renames the usages of the converted parameter and rewrites the call sites, one `TextEdit` per
+/// file, as a recursive call can both rename and rewrite at the same offset.
+fn apply_usage_edits(
+    builder: &mut AssistBuilder,
+    param_def: Definition,
+    new_name: &str,
+    param_usages: UsageSearchResult,
+    call_edits: Vec<(FileId, Vec<(TextRange, String)>)>,
+) {
+    let mut edits: FxHashMap<FileId, Vec<(TextRange, String)>> = FxHashMap::default();
+    for (file_id, references) in param_usages {
+        let edit = source_edit_from_references(&references, param_def, new_name);
+        edits.entry(file_id).or_default().extend(edit.into_iter().map(|it| (it.delete, it.insert)));
+    }
+    for (file_id, call_edits) in call_edits {
+        edits.entry(file_id).or_default().extend(call_edits);
+    }
+    for (file_id, edits) in edits {
+        builder.edit_file(file_id);
+        for (range, text) in edits {
+            builder.replace(range, text);
+        }
+    }
+}
+
+/// Rewrites `recv.f(args)` into `Type::f(&recv, args)`.
+fn method_call_to_assoc_fn_call(
+    ctx: &AssistContext,
+    func: hir::Function,
+    access: Access,
+    call: &ast::MethodCallExpr,
+    range: TextRange,
+) -> Option<[(TextRange, String); 2]> {
+    let name_ref = call.name_ref()?;
+    if name_ref.syntax().text_range() != range {
+        return None;
+    }
+    let receiver = call.receiver()?;
+    let arg_list = call.arg_list()?;
+
+    let module = ctx.sema.scope(call.syntax()).module()?;
+    let item = item_for_path_search(ctx.db(), ItemInNs::from(ModuleDef::from(func)))?;
+    let path = mod_path_to_ast(&module.find_use_path(ctx.db(), item)?);
+
+    let receiver_ty = ctx.sema.type_of_expr(&receiver)?.original;
+    let receiver_prefix = match access {
+        Access::Shared if !receiver_ty.is_reference() => "&",
+        Access::Exclusive if !receiver_ty.is_mutable_reference() => "&mut ",
+        _ => "",
+    };
+    let generics = call.generic_arg_list().map_or_else(String::new, |it| it.to_string());
+
+    let prefix = (
+        TextRange::empty(receiver.syntax().text_range().start()),
+        format!("{}::{}{}({}", path, name_ref, generics, receiver_prefix),
+    );
+    let infix = (
+        TextRange::new(
+            receiver.syntax().text_range().end(),
+            arg_list.l_paren_token()?.text_range().end(),
+        ),
+        if arg_list.args().next().is_some() { ", ".to_string() } else { String::new() },
+    );
+    Some([prefix, infix])
+}
+
+/// Rewrites `Type::f(&recv, args)` into `recv.f(args)`.
+fn assoc_fn_call_to_method_call(
+    access: Access,
+    call: &ast::CallExpr,
+    range: TextRange,
+) -> Option<[(TextRange, String); 2]> {
+    let path = match call.expr()? {
+        ast::Expr::PathExpr(it) => it.path()?,
+        _ => return None,
+    };
+    let segment = path.segment()?;
+    let name_ref = segment.name_ref()?;
+    if name_ref.syntax().text_range() != range {
+        return None;
+    }
+    let arg_list = call.arg_list()?;
+    let mut args = arg_list.args();
+    let first_arg = args.next()?;
+    let rest_start = match args.next() {
+        Some(it) => it.syntax().text_range().start(),
+        None => arg_list.r_paren_token()?.text_range().start(),
+    };
+
+    let receiver = match &first_arg {
+        ast::Expr::RefExpr(ref_expr)
+            if match access {
+                Access::Shared => ref_expr.mut_token().is_none(),
+                Access::Exclusive => ref_expr.mut_token().is_some(),
+                Access::Owned => false,
+            } =>
+        {
+            ref_expr.expr()?
+        }
+        _ => first_arg,
+    };
+    let parenthesize = matches!(
+        receiver,
+        ast::Expr::BinExpr(_)
+            | ast::Expr::BoxExpr(_)
+            | ast::Expr::BreakExpr(_)
+            | ast::Expr::CastExpr(_)
+            | ast::Expr::ClosureExpr(_)
+            | ast::Expr::ContinueExpr(_)
+            | ast::Expr::RangeExpr(_)
+            | ast::Expr::RefExpr(_)
+            | ast::Expr::PrefixExpr(_)
+            | ast::Expr::ReturnExpr(_)
+            | ast::Expr::YieldExpr(_)
+    );
+    let generics = segment.generic_arg_list().map_or_else(String::new, |it| it.to_string());
+
+    let prefix = (
+        TextRange::new(call.syntax().text_range().start(), receiver.syntax().text_range().start()),
+        if parenthesize { "(".to_string() } else { String::new() },
+    );
+    let infix = (
+        TextRange::new(receiver.syntax().text_range().end(), rest_start),
+        format!("{}.{}{}(", if parenthesize { ")" } else { "" }, name_ref, generics),
+    );
+    Some([prefix, infix])
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn method_to_assoc_fn_ref_self() {
+        check_assist(
+            convert_method_to_assoc_fn,
+            r#"
+struct Foo { x: u32 }
+impl Foo {
+    fn get(&self$0, y: u32) -> u32 { self.x + y }
+    fn other(&self) -> u32 { self.get(1) }
+}
+fn main() {
+    let foo = Foo { x: 0 };
+    foo.get(2);
+    let r = &foo;
+    r.get(3);
+}
+"#,
+            r#"
+struct Foo { x: u32 }
+impl Foo {
+    fn get(this: &Self, y: u32) -> u32 { this.x + y }
+    fn other(&self) -> u32 { Foo::get(self, 1) }
+}
+fn main() {
+    let foo = Foo { x: 0 };
+    Foo::get(&foo, 2);
+    let r = &foo;
+    Foo::get(r, 3);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn method_to_assoc_fn_mut_self() {
+        check_assist(
+            convert_method_to_assoc_fn,
+            r#"
+struct Foo;
+impl Foo {
+    fn bump<'a>(&'a mut $0self) {}
+}
+fn main() {
+    let mut foo = Foo;
+    foo.bump();
+}
+"#,
+            r#"
+struct Foo;
+impl Foo {
+    fn bump<'a>(this: &'a mut Self) {}
+}
+fn main() {
+    let mut foo = Foo;
+    Foo::bump(&mut foo);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn method_to_assoc_fn_owned_self() {
+        check_assist(
+            convert_method_to_assoc_fn,
+            r#"
+struct Foo;
+impl Foo {
+    fn consume(mut $0self) -> Self { self }
+}
+fn main() {
+    Foo.consume();
+}
+"#,
+            r#"
+struct Foo;
+impl Foo {
+    fn consume(mut this: Self) -> Self { this }
+}
+fn main() {
+    Foo::consume(Foo);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn method_to_assoc_fn_not_applicable_in_trait_impl() {
+        cov_mark::check!(method_to_assoc_fn_not_inherent);
+        check_assist_not_applicable(
+            convert_method_to_assoc_fn,
+            r#"
+trait Tr { fn f(&self); }
+struct Foo;
+impl Tr for Foo {
+    fn f(&$0self) {}
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn assoc_fn_to_method_ref() {
+        check_assist(
+            convert_assoc_fn_to_method,
+            r#"
+struct Foo { x: u32 }
+impl Foo {
+    fn get($0this: &Self, y: u32) -> u32 { this.x + y }
+}
+fn main() {
+    let foo = Foo { x: 0 };
+    Foo::get(&foo, 2);
+    let r = &foo;
+    Foo::get(r, 3);
+}
+"#,
+            r#"
+struct Foo { x: u32 }
+impl Foo {
+    fn get(&self, y: u32) -> u32 { self.x + y }
+}
+fn main() {
+    let foo = Foo { x: 0 };
+    foo.get(2);
+    let r = &foo;
+    r.get(3);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn assoc_fn_to_method_parenthesizes_receiver() {
+        check_assist(
+            convert_assoc_fn_to_method,
+            r#"
+struct Foo;
+impl Foo {
+    fn take(foo$0: Foo) {}
+}
+fn main() {
+    let foo = Foo;
+    Foo::take(*&foo);
+}
+"#,
+            r#"
+struct Foo;
+impl Foo {
+    fn take(self) {}
+}
+fn main() {
+    let foo = Foo;
+    (*&foo).take();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn assoc_fn_to_method_not_applicable_in_trait_impl() {
+        cov_mark::check!(assoc_fn_to_method_not_inherent);
+        check_assist_not_applicable(
+            convert_assoc_fn_to_method,
+            r#"
+trait Tr { fn f(this: Self); }
+struct Foo;
+impl Tr for Foo {
+    fn f($0this: Self) {}
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn assoc_fn_to_method_type_mismatch() {
+        cov_mark::check!(assoc_fn_to_method_type_mismatch);
+        check_assist_not_applicable(
+            convert_assoc_fn_to_method,
+            r#"
+struct Foo;
+impl Foo {
+    fn f($0x: u32) {}
+}
+"#,
+        );
+    }
+}
@@ -0,0 +1,481 @@
+use either::Either;
+use ide_db::{base_db::FileId, defs::Definition};
+use rustc_hash::FxHashMap;
+use syntax::{
+    ast::{self, make, AstNode, HasGenericParams, HasVisibility},
+    ted, SyntaxKind, SyntaxNode, SyntaxToken, TextRange,
+};
+
+use crate::{assist_context::AssistBuilder, AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: convert_named_struct_to_tuple_struct
+//
+// Converts struct with named fields to tuple struct, and analogously for enum variants with named
+// fields.
+//
+// ```
+// struct Point$0 { x: f32, y: f32 }
+//
+// impl Point {
+//     pub fn new(x: f32, y: f32) -> Self {
+//         Point { x, y }
+//     }
+//
+//     pub fn x(&self) -> f32 {
+//         self.x
+//     }
+//
+//     pub fn y(&self) -> f32 {
+//         self.y
+//     }
+// }
+// ```
+// ->
+// ```
+// struct Point(f32, f32);
+//
+// impl Point {
+//     pub fn new(x: f32, y: f32) -> Self {
+//         Point(x, y)
+//     }
+//
+//     pub fn x(&self) -> f32 {
+//         self.0
+//     }
+//
+//     pub fn y(&self) -> f32 {
+//         self.1
+//     }
+// }
+// ```
+pub(crate) fn convert_named_struct_to_tuple_struct(
+    acc: &mut Assists,
+    ctx: &AssistContext,
+) -> Option<()> {
+    let strukt = ctx
+        .find_node_at_offset::<ast::Struct>()
+        .map(Either::Left)
+        .or_else(|| ctx.find_node_at_offset::<ast::Variant>().map(Either::Right))?;
+    let field_list = strukt.as_ref().either(|s| s.field_list(), |v| v.field_list())?;
+    let record_fields = match field_list {
+        ast::FieldList::RecordFieldList(it) => it,
+        ast::FieldList::TupleFieldList(_) => return None,
+    };
+    let strukt_def = match &strukt {
+        Either::Left(s) => Either::Left(ctx.sema.to_def(s)?),
+        Either::Right(v) => Either::Right(ctx.sema.to_def(v)?),
+    };
+    let target = strukt.as_ref().either(|s| s.syntax(), |v| v.syntax()).text_range();
+
+    acc.add(
+        AssistId("convert_named_struct_to_tuple_struct", AssistKind::RefactorRewrite),
+        "Convert to tuple struct",
+        target,
+        |edit| {
+            edit_struct_def(ctx, edit, &strukt, record_fields);
+            edit_references(ctx, edit, strukt_def);
+        },
+    )
+}
+
+fn edit_struct_def(
+    ctx: &AssistContext,
+    edit: &mut AssistBuilder,
+    strukt: &Either<ast::Struct, ast::Variant>,
+    record_fields: ast::RecordFieldList,
+) {
+    let tuple_fields =
+        record_fields.fields().filter_map(|f| Some(make::tuple_field(f.visibility(), f.ty()?)));
+    let tuple_fields = make::tuple_field_list(tuple_fields);
+    let record_fields_range = with_leading_whitespace(record_fields.syntax());
+
+    edit.edit_file(ctx.file_id());
+
+    match strukt {
+        Either::Left(strukt) => match strukt.where_clause() {
+            // The where clause has to come after the fields of a tuple struct.
+            Some(w) => {
+                let leading_ws = leading_whitespace(w.syntax());
+                let start = leading_ws
+                    .as_ref()
+                    .map_or(w.syntax().text_range(), |it| it.text_range())
+                    .start();
+                let leading_ws = leading_ws.map(|it| it.to_string()).unwrap_or_default();
+                let w = w.syntax().to_string();
+                edit.replace(
+                    TextRange::new(start, record_fields_range.end()),
+                    format!("{}{}{};", tuple_fields, leading_ws, w.trim_end_matches(',')),
+                );
+            }
+            None => edit.replace(record_fields_range, format!("{};", tuple_fields)),
+        },
+        Either::Right(_) => edit.replace(record_fields_range, tuple_fields.to_string()),
+    }
+}
+
+fn leading_whitespace(node: &SyntaxNode) -> Option<SyntaxToken> {
+    node.prev_sibling_or_token()?.into_token().filter(|it| it.kind() == SyntaxKind::WHITESPACE)
+}
+
+/// The range of `node`, extended to the whitespace before it.
+fn with_leading_whitespace(node: &SyntaxNode) -> TextRange {
+    match leading_whitespace(node) {
+        Some(ws) => TextRange::new(ws.text_range().start(), node.text_range().end()),
+        None => node.text_range(),
+    }
+}
+
+#[derive(Default)]
+struct FileEdits {
+    field_exprs: Vec<(ast::FieldExpr, usize)>,
+    records: Vec<Either<ast::RecordExpr, ast::RecordPat>>,
+}
+
+fn edit_references(
+    ctx: &AssistContext,
+    edit: &mut AssistBuilder,
+    strukt: Either<hir::Struct, hir::Variant>,
+) {
+    let db = ctx.db();
+    let (strukt_def, fields) = match strukt {
+        Either::Left(s) => (Definition::Adt(hir::Adt::Struct(s)), s.fields(db)),
+        Either::Right(v) => (Definition::Variant(v), v.fields(db)),
+    };
+    let names: Vec<String> = fields.iter().map(|f| f.name(db).to_string()).collect();
+    let mut edits: FxHashMap<FileId, FileEdits> = FxHashMap::default();
+
+    // Field accesses like `x.name`. The other references to the fields are in record expressions
+    // and patterns, which are rewritten as a whole.
+    for (index, field) in fields.into_iter().enumerate() {
+        for (file_id, refs) in Definition::Field(field).usages(&ctx.sema).all() {
+            let field_exprs = refs
+                .iter()
+                .filter_map(|r| r.name.as_name_ref()?.syntax().parent())
+                .filter_map(ast::FieldExpr::cast)
+                .map(|it| (it, index));
+            edits.entry(file_id).or_default().field_exprs.extend(field_exprs);
+        }
+    }
+
+    for (file_id, refs) in strukt_def.usages(&ctx.sema).include_self_refs().all() {
+        let records = refs.iter().filter_map(|r| {
+            let in_path = |path: Option<ast::Path>| {
+                path.map_or(false, |it| it.syntax().text_range().contains_range(r.range))
+            };
+            r.name.syntax().ancestors().find_map(|node| {
+                if let Some(expr) = ast::RecordExpr::cast(node.clone()) {
+                    return in_path(expr.path()).then(|| Either::Left(expr));
+                }
+                let pat = ast::RecordPat::cast(node)?;
+                in_path(pat.path()).then(|| Either::Right(pat))
+            })
+        });
+        edits.entry(file_id).or_default().records.extend(records);
+    }
+
+    for (file_id, FileEdits { field_exprs, records }) in edits {
+        edit.edit_file(file_id);
+        let field_exprs: Vec<_> =
+            field_exprs.into_iter().map(|(it, index)| (edit.make_mut(it), index)).collect();
+        let mut records: Vec<_> = records
+            .into_iter()
+            .map(|it| match it {
+                Either::Left(expr) => Either::Left(edit.make_mut(expr)),
+                Either::Right(pat) => Either::Right(edit.make_mut(pat)),
+            })
+            .collect();
+        // Rewrite the nested records first, the outer ones are built from the text of their
+        // fields.
+        records.sort_by_key(|it| {
+            let range = it.as_ref().either(|e| e.syntax(), |p| p.syntax()).text_range();
+            (range.end(), std::cmp::Reverse(range.start()))
+        });
+
+        for (field_expr, index) in field_exprs {
+            if let Some(name_ref) = field_expr.name_ref() {
+                ted::replace(name_ref.syntax(), tuple_index(index).clone_for_update().syntax());
+            }
+        }
+        for record in records {
+            match record {
+                Either::Left(expr) => {
+                    if let Some(call) = record_expr_to_call(&expr, &names) {
+                        ted::replace(expr.syntax(), call.clone_for_update().syntax());
+                    }
+                }
+                Either::Right(pat) => {
+                    if let Some(tuple_pat) = record_pat_to_tuple_pat(&pat, &names) {
+                        ted::replace(pat.syntax(), tuple_pat.clone_for_update().syntax());
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn tuple_index(index: usize) -> ast::NameRef {
+    let field_expr =
+        make::expr_field(make::expr_path(make::ext::ident_path("x")), &index.to_string());
+    match field_expr {
+        ast::Expr::FieldExpr(it) => it.name_ref().unwrap(),
+        _ => unreachable!(),
+    }
+}
+
+/// `Foo { b: 2, a: 1 }` to `Foo(1, 2)`. Fields left out because of a `..base` spread are taken
+/// from `base`, if that is a path which can be evaluated once per field.
+fn record_expr_to_call(expr: &ast::RecordExpr, names: &[String]) -> Option<ast::Expr> {
+    let path = expr.path()?;
+    let field_list = expr.record_expr_field_list()?;
+    let spread = match field_list.spread() {
+        Some(ast::Expr::PathExpr(it)) => Some(it),
+        Some(_) => return None,
+        None => None,
+    };
+    let args = names
+        .iter()
+        .enumerate()
+        .map(|(index, name)| {
+            let field = field_list
+                .fields()
+                .find(|f| f.field_name().map_or(false, |it| it.to_string() == *name));
+            match field {
+                Some(field) => field.expr(),
+                None => {
+                    Some(make::expr_field(ast::Expr::PathExpr(spread.clone()?), &index.to_string()))
+                }
+            }
+        })
+        .collect::<Option<Vec<_>>>()?;
+    Some(make::expr_call(make::expr_path(path), make::arg_list(args)))
+}
+
+/// `Foo { b, .. }` to `Foo(_, b, ..)`.
+fn record_pat_to_tuple_pat(pat: &ast::RecordPat, names: &[String]) -> Option<ast::TupleStructPat> {
+    let path = pat.path()?;
+    let field_list = pat.record_pat_field_list()?;
+    let mut pats: Vec<Option<ast::Pat>> = names
+        .iter()
+        .map(|name| {
+            field_list
+                .fields()
+                .find(|f| f.field_name().map_or(false, |it| it.to_string() == *name))
+                .and_then(|f| f.pat())
+        })
+        .collect();
+    if let Some(rest) = field_list.rest_pat() {
+        while let Some(None) = pats.last() {
+            pats.pop();
+        }
+        if pats.len() < names.len() {
+            pats.push(Some(ast::Pat::RestPat(rest)));
+        }
+    }
+    let pats = pats.into_iter().map(|it| it.unwrap_or_else(|| make::wildcard_pat().into()));
+    Some(make::tuple_struct_pat(path, pats))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn not_applicable_other_than_record_struct() {
+        check_assist_not_applicable(convert_named_struct_to_tuple_struct, r#"struct Foo$0(u32)"#);
+        check_assist_not_applicable(convert_named_struct_to_tuple_struct, r#"struct Foo$0;"#);
+    }
+
+    #[test]
+    fn convert_simple_struct() {
+        check_assist(
+            convert_named_struct_to_tuple_struct,
+            r#"
+struct Inner;
+struct A$0 { inner: Inner }
+
+impl A {
+    fn new(inner: Inner) -> A {
+        A { inner }
+    }
+
+    fn new_with_default() -> A {
+        A::new(Inner)
+    }
+
+    fn into_inner(self) -> Inner {
+        self.inner
+    }
+}"#,
+            r#"
+struct Inner;
+struct A(Inner);
+
+impl A {
+    fn new(inner: Inner) -> A {
+        A(inner)
+    }
+
+    fn new_with_default() -> A {
+        A::new(Inner)
+    }
+
+    fn into_inner(self) -> Inner {
+        self.0
+    }
+}"#,
+        );
+    }
+
+    #[test]
+    fn convert_fields_out_of_order_and_nested() {
+        check_assist(
+            convert_named_struct_to_tuple_struct,
+            r#"
+struct P$0 { x: u32, y: u32 }
+
+fn f(p: P) -> P {
+    P { y: p.x, x: P { x: 1, y: 2 }.y }
+}"#,
+            r#"
+struct P(u32, u32);
+
+fn f(p: P) -> P {
+    P(P(1, 2).1, p.0)
+}"#,
+        );
+    }
+
+    #[test]
+    fn convert_struct_with_spread() {
+        check_assist(
+            convert_named_struct_to_tuple_struct,
+            r#"
+struct P$0 { x: u32, y: u32, z: u32 }
+
+fn f(base: P) -> P {
+    P { y: 1, ..base }
+}"#,
+            r#"
+struct P(u32, u32, u32);
+
+fn f(base: P) -> P {
+    P(base.0, 1, base.2)
+}"#,
+        );
+    }
+
+    #[test]
+    fn convert_destructured_struct() {
+        check_assist(
+            convert_named_struct_to_tuple_struct,
+            r#"
+struct A$0 { a: u32, b: u32, c: u32 }
+
+impl A {
+    fn sum(self) -> u32 {
+        let A { b: second, a } = self;
+        let Self { b, .. } = self;
+        let A { c, .. } = self;
+        a + second + b + c
+    }
+}"#,
+            r#"
+struct A(u32, u32, u32);
+
+impl A {
+    fn sum(self) -> u32 {
+        let A(a, second, _) = self;
+        let Self(_, b, ..) = self;
+        let A(_, _, c) = self;
+        a + second + b + c
+    }
+}"#,
+        );
+    }
+
+    #[test]
+    fn convert_struct_with_visibility() {
+        check_assist(
+            convert_named_struct_to_tuple_struct,
+            r#"
+struct A$0 { pub first: u32, pub(crate) second: u64 }
+
+impl A {
+    fn new() -> A {
+        A { first: 42, second: 42 }
+    }
+}"#,
+            r#"
+struct A(pub u32, pub(crate) u64);
+
+impl A {
+    fn new() -> A {
+        A(42, 42)
+    }
+}"#,
+        );
+    }
+
+    #[test]
+    fn convert_struct_with_where_clause() {
+        check_assist(
+            convert_named_struct_to_tuple_struct,
+            r#"
+struct Wrap$0<T>
+where
+    T: Display,
+{ field1: T }
+"#,
+            r#"
+struct Wrap<T>(T)
+where
+    T: Display;
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_variant_with_multi_file_references() {
+        check_assist(
+            convert_named_struct_to_tuple_struct,
+            r#"
+//- /main.rs
+struct Inner;
+enum A {
+    $0Variant { inner: Inner },
+}
+
+mod foo;
+
+//- /foo.rs
+use crate::{A, Inner};
+fn f(a: A) -> Inner {
+    let _ = A::Variant { inner: Inner };
+    match a {
+        A::Variant { inner } => inner,
+    }
+}
+"#,
+            r#"
+//- /main.rs
+struct Inner;
+enum A {
+    Variant(Inner),
+}
+
+mod foo;
+
+//- /foo.rs
+use crate::{A, Inner};
+fn f(a: A) -> Inner {
+    let _ = A::Variant(Inner);
+    match a {
+        A::Variant(inner) => inner,
+    }
+}
+"#,
+        );
+    }
+}
@@ -0,0 +1,365 @@
+use hir::{Module, ModuleSource};
+use ide_db::base_db::FileId;
+use itertools::Itertools;
+use syntax::{
+    ast::{self, edit::IndentLevel, AstToken},
+    AstNode, SyntaxNode, TextRange,
+};
+
+use crate::{AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: extract_string_to_const
+//
+// Extracts a string literal which appears several times in the crate into a constant, and
+// replaces all its occurrences with the constant.
+//
+// ```
+// fn greet() -> &'static str {
+//     "Hello, $0World!"
+// }
+// fn main() {
+//     let greeting = "Hello, World!";
+// }
+// ```
+// ->
+// ```
+// const HELLO_WORLD: &str = "Hello, World!";
+//
+// fn greet() -> &'static str {
+//     HELLO_WORLD
+// }
+// fn main() {
+//     let greeting = HELLO_WORLD;
+// }
+// ```
+pub(crate) fn extract_string_to_const(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let token = ctx.find_token_at_offset::<ast::String>()?;
+    let literal = extractable_literal(&token)?;
+    let value = token.value()?.into_owned();
+    let module = ctx.sema.scope(literal.syntax()).module()?;
+    let root_module = module.krate().root_module(ctx.db());
+
+    let occurrences = find_occurrences(ctx, root_module, &value);
+    if occurrences.len() < 2 {
+        cov_mark::hit!(extract_string_to_const_single_occurrence);
+        return None;
+    }
+
+    // The constant goes next to the literal if all occurrences are in its module, and to the
+    // crate root otherwise, where all modules can name it with `crate::`.
+    let in_one_module = occurrences.iter().all(|it| it.module == module);
+    let target_module = if in_one_module { module } else { root_module };
+    let (insert_file, insert_offset, indent) = if in_one_module {
+        let item = top_level_item(literal.syntax())?;
+        (ctx.file_id(), item.text_range().start(), IndentLevel::from_node(&item))
+    } else {
+        let file_id = root_module.definition_source(ctx.db()).file_id.original_file(ctx.db());
+        let root_file = ctx.sema.parse(file_id);
+        let offset = root_file
+            .items()
+            .find(|item| match item {
+                ast::Item::Use(_) | ast::Item::ExternCrate(_) => false,
+                ast::Item::Module(it) => it.item_list().is_some(),
+                _ => true,
+            })
+            .map_or_else(
+                || root_file.syntax().text_range().end(),
+                |it| it.syntax().text_range().start(),
+            );
+        (file_id, offset, IndentLevel(0))
+    };
+
+    let name = const_name(ctx, target_module, &value);
+    let target = token.syntax().text_range();
+    acc.add(
+        AssistId("extract_string_to_const", AssistKind::RefactorExtract),
+        "Extract string into constant",
+        target,
+        |builder| {
+            let qualified_name = format!("crate::{}", name);
+            let occurrences = occurrences.into_iter().into_group_map_by(|it| it.file_id);
+            let mut files =
+                occurrences.keys().copied().chain(Some(insert_file)).collect::<Vec<_>>();
+            files.sort();
+            files.dedup();
+            for file_id in files {
+                builder.edit_file(file_id);
+                if file_id == insert_file {
+                    let text =
+                        format!("const {}: &str = {};\n\n{}", name, token.syntax().text(), indent);
+                    builder.insert(insert_offset, text);
+                }
+                for occurrence in occurrences.get(&file_id).into_iter().flatten() {
+                    let replacement =
+                        if occurrence.module == target_module { &name } else { &qualified_name };
+                    builder.replace(occurrence.range, replacement);
+                }
+            }
+        },
+    )
+}
+
+struct Occurrence {
+    file_id: FileId,
+    range: TextRange,
+    module: Module,
+}
+
+/// The literal expression of `token`, if it can be replaced by a constant. Strings in macro
+/// calls and attributes are left alone, `format!` and `#[doc]` need literals.
+fn extractable_literal(token: &ast::String) -> Option<ast::Literal> {
+    let literal = token.syntax().parent().and_then(ast::Literal::cast)?;
+    if literal.syntax().ancestors().any(|it| ast::Attr::can_cast(it.kind())) {
+        return None;
+    }
+    let parent = literal.syntax().parent()?;
+    if ast::Const::can_cast(parent.kind()) || ast::Static::can_cast(parent.kind()) {
+        return None;
+    }
+    Some(literal)
+}
+
+/// All string literals with the value `value` in the files of the crate.
+fn find_occurrences(ctx: &AssistContext, root_module: Module, value: &str) -> Vec<Occurrence> {
+    let db = ctx.db();
+    let mut files = Vec::new();
+    let mut worklist = vec![root_module];
+    while let Some(module) = worklist.pop() {
+        worklist.extend(module.children(db));
+        let source = module.definition_source(db);
+        if let ModuleSource::SourceFile(_) = source.value {
+            files.push(source.file_id.original_file(db));
+        }
+    }
+    files.sort();
+    files.dedup();
+
+    let mut res = Vec::new();
+    for file_id in files {
+        let source_file = ctx.sema.parse(file_id);
+        for token in source_file.syntax().descendants_with_tokens().filter_map(|it| it.into_token())
+        {
+            let string = match ast::String::cast(token) {
+                Some(it) => it,
+                None => continue,
+            };
+            let literal = match extractable_literal(&string) {
+                Some(it) => it,
+                None => continue,
+            };
+            if string.value().as_deref() != Some(value) {
+                continue;
+            }
+            if let Some(module) = ctx.sema.scope(literal.syntax()).module() {
+                res.push(Occurrence { file_id, range: literal.syntax().text_range(), module });
+            }
+        }
+    }
+    res
+}
+
+/// The item of the module `node` is in which contains `node`.
+fn top_level_item(node: &SyntaxNode) -> Option<SyntaxNode> {
+    node.ancestors().find(|it| {
+        ast::Item::can_cast(it.kind())
+            && it.parent().map_or(false, |parent| {
+                ast::SourceFile::can_cast(parent.kind()) || ast::ItemList::can_cast(parent.kind())
+            })
+    })
+}
+
+/// A `SCREAMING_SNAKE_CASE` name made of the first words of `value`, which isn't used in
+/// `module` yet.
+fn const_name(ctx: &AssistContext, module: Module, value: &str) -> String {
+    let words = value
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|it| !it.is_empty())
+        .take(4)
+        .map(|it| it.to_ascii_uppercase())
+        .join("_");
+    let base = match words.chars().next() {
+        None => "VALUE".to_string(),
+        Some(c) if c.is_ascii_digit() => format!("STR_{}", words),
+        Some(_) => words,
+    };
+
+    let taken =
+        module.scope(ctx.db(), None).into_iter().map(|(name, _)| name.to_string()).collect_vec();
+    let mut name = base.clone();
+    let mut counter = 1;
+    while taken.contains(&name) {
+        cov_mark::hit!(extract_string_to_const_name_taken);
+        name = format!("{}_{}", base, counter);
+        counter += 1;
+    }
+    name
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn extracts_within_module() {
+        check_assist(
+            extract_string_to_const,
+            r#"
+mod m {
+    fn f() -> &'static str {
+        "some $0text"
+    }
+    fn g() {
+        let s = "some text";
+        let t = r"some text";
+        let u = "other text";
+    }
+}
+fn h() {
+    let s = "some texts";
+}
+"#,
+            r#"
+mod m {
+    const SOME_TEXT: &str = "some text";
+
+    fn f() -> &'static str {
+        SOME_TEXT
+    }
+    fn g() {
+        let s = SOME_TEXT;
+        let t = SOME_TEXT;
+        let u = "other text";
+    }
+}
+fn h() {
+    let s = "some texts";
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn extracts_across_files() {
+        check_assist(
+            extract_string_to_const,
+            r#"
+//- /main.rs
+mod foo;
+use foo::f;
+
+fn main() {
+    let key = "key";
+    match f() {
+        "key" => {}
+        _ => {}
+    }
+}
+//- /foo.rs
+pub fn f() -> &'static str {
+    "key$0"
+}
+"#,
+            r#"
+//- /main.rs
+mod foo;
+use foo::f;
+
+const KEY: &str = "key";
+
+fn main() {
+    let key = KEY;
+    match f() {
+        KEY => {}
+        _ => {}
+    }
+}
+//- /foo.rs
+pub fn f() -> &'static str {
+    crate::KEY
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn skips_macro_calls_and_attributes() {
+        check_assist(
+            extract_string_to_const,
+            r#"
+#[doc = "hello"]
+fn f() {
+    println!("hello");
+    let a = "hello$0";
+    let b = "hello";
+}
+"#,
+            r#"
+const HELLO: &str = "hello";
+
+#[doc = "hello"]
+fn f() {
+    println!("hello");
+    let a = HELLO;
+    let b = HELLO;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn avoids_taken_names() {
+        cov_mark::check!(extract_string_to_const_name_taken);
+        check_assist(
+            extract_string_to_const,
+            r#"
+struct A;
+impl A {
+    fn f() {
+        let a = "a$0";
+        let b = "a";
+    }
+}
+"#,
+            r#"
+struct A;
+const A_1: &str = "a";
+
+impl A {
+    fn f() {
+        let a = A_1;
+        let b = A_1;
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_single_occurrence() {
+        cov_mark::check!(extract_string_to_const_single_occurrence);
+        check_assist_not_applicable(
+            extract_string_to_const,
+            r#"
+fn f() {
+    let a = "a$0";
+    let b = "b";
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_const_initializers() {
+        check_assist_not_applicable(
+            extract_string_to_const,
+            r#"
+const A: &str = "a$0";
+fn f() {
+    let a = "a";
+}
+"#,
+        );
+    }
+}
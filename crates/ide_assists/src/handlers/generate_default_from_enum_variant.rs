@@ -1,11 +1,16 @@
 use ide_db::{famous_defs::FamousDefs, RootDatabase};
-use syntax::ast::{self, AstNode, HasName};
+use itertools::Itertools;
+use syntax::ast::{self, AstNode, HasAttrs, HasName};
 
-use crate::{AssistContext, AssistId, AssistKind, Assists};
+use crate::{
+    utils::{delete_attr, find_derive},
+    AssistContext, AssistId, AssistKind, Assists,
+};
 
 // Assist: generate_default_from_enum_variant
 //
-// Adds a Default impl for an enum using a variant.
+// Adds a Default impl for an enum using a variant. If the enum derives `Default`, the derive is
+// replaced by the impl.
 //
 // ```
 // enum Version {
@@ -40,18 +45,47 @@ pub(crate) fn generate_default_from_enum_variant(
         return None;
     }
 
-    if existing_default_impl(&ctx.sema, &variant).is_some() {
+    let enum_ = variant.parent_enum();
+    let derive = find_derive(&enum_, "Default");
+    if derive.is_none() && existing_default_impl(&ctx.sema, &variant).is_some() {
         cov_mark::hit!(test_gen_default_impl_already_exists);
         return None;
     }
 
+    let label = match derive {
+        Some(_) => "Replace `#[derive(Default)]` with an impl using this enum variant",
+        None => "Generate `Default` impl from this enum variant",
+    };
     let target = variant.syntax().text_range();
     acc.add(
         AssistId("generate_default_from_enum_variant", AssistKind::Generate),
-        "Generate `Default` impl from this enum variant",
+        label,
         target,
         |edit| {
-            let start_offset = variant.parent_enum().syntax().text_range().end();
+            if let Some((attr, derives)) = &derive {
+                let other_derives = derives
+                    .iter()
+                    .filter(|it| it.segment().map_or(true, |it| it.to_string() != "Default"))
+                    .collect::<Vec<_>>();
+                match (other_derives.is_empty(), attr.token_tree()) {
+                    (false, Some(tt)) => edit.replace(
+                        tt.syntax().text_range(),
+                        format!("({})", other_derives.iter().format(", ")),
+                    ),
+                    _ => delete_attr(edit, attr),
+                }
+                // `#[default]` is only allowed with the derive.
+                let default_attrs = enum_
+                    .variant_list()
+                    .into_iter()
+                    .flat_map(|it| it.variants())
+                    .flat_map(|it| it.attrs())
+                    .filter(|it| it.simple_name().as_deref() == Some("default"));
+                for attr in default_attrs {
+                    delete_attr(edit, &attr);
+                }
+            }
+            let start_offset = enum_.syntax().text_range().end();
             let buf = format!(
                 r#"
 
@@ -168,6 +202,58 @@ enum Variant { Undefi$0ned }
             r#"
 enum Variant { Undefined }
 
+impl Default for Variant {
+    fn default() -> Self {
+        Self::Undefined
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_replace_derive() {
+        check_assist(
+            generate_default_from_enum_variant,
+            r#"
+//- minicore: default, derive
+#[derive(Clone, Default)]
+enum Variant {
+    Undefined,
+    #[default]
+    Minor,
+    Major$0,
+}
+"#,
+            r#"
+#[derive(Clone)]
+enum Variant {
+    Undefined,
+    Minor,
+    Major,
+}
+
+impl Default for Variant {
+    fn default() -> Self {
+        Self::Major
+    }
+}
+"#,
+        );
+        check_assist(
+            generate_default_from_enum_variant,
+            r#"
+//- minicore: default, derive
+#[derive(Default)]
+enum Variant {
+    Undefined$0,
+}
+"#,
+            r#"
+enum Variant {
+    Undefined,
+}
+
 impl Default for Variant {
     fn default() -> Self {
         Self::Undefined
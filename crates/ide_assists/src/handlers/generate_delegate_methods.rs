@@ -1,5 +1,12 @@
-use hir::{self, HasCrate, HasSource, HasVisibility};
-use syntax::ast::{self, make, AstNode, HasGenericParams, HasName, HasVisibility as _};
+use hir::{self, AsAssocItem, HasCrate, HasVisibility};
+use ide_db::path_transform::PathTransform;
+use syntax::{
+    ast::{
+        self, edit_in_place::GenericParamsOwnerEdit, make, AstNode, HasGenericParams, HasName,
+        HasVisibility as _,
+    },
+    TextRange,
+};
 
 use crate::{
     utils::{convert_param_list_to_arg_list, find_struct_impl, render_snippet, Cursor},
@@ -11,6 +18,9 @@ use syntax::ast::edit::AstNodeEdit;
 //
 // Generate delegate methods.
 //
+// The generic parameters of the field's type are substituted in the signatures, and methods with
+// receivers which can't be forwarded, like `self: Rc<Self>`, are skipped.
+//
 // ```
 // struct Age(u8);
 // impl Age {
@@ -47,27 +57,14 @@ pub(crate) fn generate_delegate_methods(acc: &mut Assists, ctx: &AssistContext)
     let strukt_name = strukt.name()?;
     let current_module = ctx.sema.scope(strukt.syntax()).module()?;
 
-    let (field_name, field_ty, target) = match ctx.find_node_at_offset::<ast::RecordField>() {
-        Some(field) => {
-            let field_name = field.name()?;
-            let field_ty = field.ty()?;
-            (format!("{}", field_name), field_ty, field.syntax().text_range())
-        }
-        None => {
-            let field = ctx.find_node_at_offset::<ast::TupleField>()?;
-            let field_list = ctx.find_node_at_offset::<ast::TupleFieldList>()?;
-            let field_list_index = field_list.fields().position(|it| it == field)?;
-            let field_ty = field.ty()?;
-            (format!("{}", field_list_index), field_ty, field.syntax().text_range())
-        }
-    };
+    let (field_name, field_ty, target) = field_at_offset(ctx)?;
 
     let sema_field_ty = ctx.sema.resolve_type(&field_ty)?;
     let krate = sema_field_ty.krate(ctx.db());
     let mut methods = vec![];
     sema_field_ty.iterate_assoc_items(ctx.db(), krate, |item| {
         if let hir::AssocItem::Function(f) = item {
-            if f.self_param(ctx.db()).is_some() && f.is_visible_from(ctx.db(), current_module) {
+            if f.is_visible_from(ctx.db(), current_module) && has_forwardable_receiver(ctx, f) {
                 methods.push(f)
             }
         }
@@ -85,10 +82,27 @@ pub(crate) fn generate_delegate_methods(acc: &mut Assists, ctx: &AssistContext)
             target,
             |builder| {
                 // Create the function
-                let method_source = match method.source(ctx.db()) {
+                let method_source = match ctx.sema.source(method) {
                     Some(source) => source.value,
                     None => return,
                 };
+                let impl_ = match method.as_assoc_item(ctx.db()).map(|it| it.container(ctx.db())) {
+                    Some(hir::AssocItemContainer::Impl(it)) => it,
+                    _ => return,
+                };
+
+                // Substitute the generic parameters of the impl with the arguments of the field's
+                // type, and qualify the paths for the struct's module.
+                let source_scope = ctx.sema.scope(method_source.syntax());
+                let target_scope = ctx.sema.scope(strukt.syntax());
+                let transform = PathTransform::impl_transformation(
+                    &target_scope,
+                    &source_scope,
+                    impl_,
+                    field_generic_args(ctx, impl_, &field_ty),
+                );
+                let method_source = method_source.clone_for_update();
+                transform.apply(method_source.syntax());
                 let method_name = method.name(ctx.db());
                 let vis = method_source.visibility();
                 let name = make::name(&method.name(ctx.db()).to_string());
@@ -110,6 +124,12 @@ pub(crate) fn generate_delegate_methods(acc: &mut Assists, ctx: &AssistContext)
                 let f = make::fn_(vis, name, type_params, params, body, ret_type, is_async)
                     .indent(ast::edit::IndentLevel(1))
                     .clone_for_update();
+                if let Some(where_clause) = method_source.where_clause() {
+                    let new_where_clause = f.get_or_create_where_clause();
+                    for pred in where_clause.predicates() {
+                        new_where_clause.add_predicate(pred.clone_subtree().clone_for_update());
+                    }
+                }
 
                 let cursor = Cursor::Before(f.syntax());
 
@@ -168,6 +188,74 @@ pub(crate) fn generate_delegate_methods(acc: &mut Assists, ctx: &AssistContext)
     Some(())
 }
 
+/// The name or index, type and range of the struct field at the cursor.
+pub(crate) fn field_at_offset(ctx: &AssistContext) -> Option<(String, ast::Type, TextRange)> {
+    match ctx.find_node_at_offset::<ast::RecordField>() {
+        Some(field) => {
+            let field_name = field.name()?;
+            let field_ty = field.ty()?;
+            Some((format!("{}", field_name), field_ty, field.syntax().text_range()))
+        }
+        None => {
+            let field = ctx.find_node_at_offset::<ast::TupleField>()?;
+            let field_list = ctx.find_node_at_offset::<ast::TupleFieldList>()?;
+            let field_list_index = field_list.fields().position(|it| it == field)?;
+            let field_ty = field.ty()?;
+            Some((format!("{}", field_list_index), field_ty, field.syntax().text_range()))
+        }
+    }
+}
+
+/// Whether the method takes `self`, `&self` or `&mut self`, which can be forwarded to the field.
+/// Arbitrary self types like `self: Box<Self>` can't.
+pub(crate) fn has_forwardable_receiver(ctx: &AssistContext, method: hir::Function) -> bool {
+    let self_param = match method.self_param(ctx.db()).and_then(|it| it.source(ctx.db())) {
+        Some(it) => it.value,
+        None => return false,
+    };
+    let is_self = |ty: Option<ast::Type>| match ty {
+        Some(ast::Type::PathType(it)) => it.syntax().text() == "Self",
+        _ => false,
+    };
+    match self_param.ty() {
+        None => true,
+        Some(ast::Type::RefType(it)) => is_self(it.ty()),
+        ty => is_self(ty),
+    }
+}
+
+/// The generic arguments of the field's type, if they line up with the generic parameters of
+/// `impl_`. That is the case for the usual `impl<T> Age<T>`, but not for something like
+/// `impl<T> Pair<T, T>`, whose parameters are then left as they are.
+fn field_generic_args(
+    ctx: &AssistContext,
+    impl_: hir::Impl,
+    field_ty: &ast::Type,
+) -> Option<ast::GenericArgList> {
+    let impl_src = ctx.sema.source(impl_)?.value;
+    let params: Vec<String> = impl_src
+        .generic_param_list()?
+        .type_or_const_params()
+        .filter_map(|it| it.name())
+        .map(|it| it.to_string())
+        .collect();
+    let generic_args = |ty: ast::Type| match ty {
+        ast::Type::PathType(it) => it.path()?.segment()?.generic_arg_list(),
+        _ => None,
+    };
+    let self_args: Vec<String> = generic_args(impl_src.self_ty()?)?
+        .generic_args()
+        .filter_map(|arg| match arg {
+            ast::GenericArg::TypeArg(it) => Some(it.to_string()),
+            _ => None,
+        })
+        .collect();
+    if params != self_args {
+        return None;
+    }
+    generic_args(field_ty.clone())
+}
+
 #[cfg(test)]
 mod tests {
     use crate::tests::{check_assist, check_assist_not_applicable};
@@ -312,6 +400,98 @@ impl<T> Person<T> {
         );
     }
 
+    #[test]
+    fn test_generate_delegate_substitutes_generics() {
+        check_assist(
+            generate_delegate_methods,
+            r#"
+struct Age<T>(T);
+impl<T> Age<T> {
+    fn get(&self) -> &T {
+        &self.0
+    }
+}
+
+struct Person {
+    ag$0e: Age<u8>,
+}"#,
+            r#"
+struct Age<T>(T);
+impl<T> Age<T> {
+    fn get(&self) -> &T {
+        &self.0
+    }
+}
+
+struct Person {
+    age: Age<u8>,
+}
+
+impl Person {
+    $0fn get(&self) -> &u8 {
+        self.age.get()
+    }
+}"#,
+        );
+    }
+
+    #[test]
+    fn test_generate_delegate_keeps_where_clause() {
+        check_assist(
+            generate_delegate_methods,
+            r#"
+struct Age(u8);
+impl Age {
+    fn with<F>(self, f: F) -> u8 where F: Fn(u8) -> u8 {
+        f(self.0)
+    }
+}
+
+struct Person(A$0ge);"#,
+            r#"
+struct Age(u8);
+impl Age {
+    fn with<F>(self, f: F) -> u8 where F: Fn(u8) -> u8 {
+        f(self.0)
+    }
+}
+
+struct Person(Age);
+
+impl Person {
+    $0fn with<F>(self, f: F) -> u8 where F: Fn(u8) -> u8 {
+        self.0.with(f)
+    }
+}"#,
+        );
+    }
+
+    #[test]
+    fn test_generate_delegate_skips_arbitrary_self_types() {
+        check_assist_not_applicable(
+            generate_delegate_methods,
+            r#"
+//- minicore: deref
+struct Rc<T>(T);
+impl<T> core::ops::Deref for Rc<T> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+struct Age(u8);
+impl Age {
+    fn age(self: Rc<Self>) -> u8 {
+        0
+    }
+}
+
+struct Person {
+    ag$0e: Age,
+}"#,
+        )
+    }
+
     #[test]
     fn test_generate_delegate_visibility() {
         check_assist_not_applicable(
@@ -0,0 +1,516 @@
+use hir::{HasVisibility, ModuleDef};
+use ide_db::helpers::mod_path_to_ast;
+use syntax::{
+    ast::{
+        self,
+        edit::{AstNodeEdit, IndentLevel},
+        edit_in_place::GenericParamsOwnerEdit,
+        make, AstNode, HasGenericParams, HasName,
+    },
+    ted, SyntaxNode,
+};
+
+use crate::{
+    utils::{
+        add_trait_assoc_items_to_impl, convert_param_list_to_arg_list, generate_trait_impl_text,
+        render_snippet, Cursor,
+    },
+    AssistContext, AssistId, AssistKind, Assists, GroupLabel,
+};
+
+use super::generate_delegate_methods::{field_at_offset, has_forwardable_receiver};
+
+// Assist: generate_delegate_trait
+//
+// Generate an impl of a trait implemented by the field's type, which forwards to the field.
+//
+// The required items of the trait are delegated, as well as the provided methods overridden by
+// the field's impl. Traits with required methods which can't be forwarded, like ones returning
+// `Self`, are not offered.
+//
+// ```
+// trait Speak {
+//     fn speak(&self, times: u32) -> u32;
+// }
+// struct Dog;
+// impl Speak for Dog {
+//     fn speak(&self, times: u32) -> u32 {
+//         times
+//     }
+// }
+//
+// struct Loud {
+//     do$0g: Dog,
+// }
+// ```
+// ->
+// ```
+// trait Speak {
+//     fn speak(&self, times: u32) -> u32;
+// }
+// struct Dog;
+// impl Speak for Dog {
+//     fn speak(&self, times: u32) -> u32 {
+//         times
+//     }
+// }
+//
+// struct Loud {
+//     dog: Dog,
+// }
+//
+// impl Speak for Loud {
+//     $0fn speak(&self, times: u32) -> u32 {
+//         self.dog.speak(times)
+//     }
+// }
+// ```
+pub(crate) fn generate_delegate_trait(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let strukt = ctx.find_node_at_offset::<ast::Struct>()?;
+    let strukt_name = strukt.name()?;
+    let (field_name, field_ty, target) = field_at_offset(ctx)?;
+    let db = ctx.db();
+    let current_module = ctx.sema.scope(strukt.syntax()).module()?;
+    let strukt_ty = ctx.sema.to_def(&strukt)?.ty(db);
+    let sema_field_ty = ctx.sema.resolve_type(&field_ty)?;
+    // The impls for a generic field's type may only apply for some parameters, those get a where
+    // bound instead.
+    let is_generic = strukt.generic_param_list().is_some();
+
+    let mut traits: Vec<(hir::Trait, hir::Impl)> = Vec::new();
+    for impl_ in hir::Impl::all_for_type(db, sema_field_ty.clone()) {
+        let trait_ = match impl_.trait_(db) {
+            Some(it) => it,
+            None => continue,
+        };
+        if traits.iter().any(|(it, _)| *it == trait_)
+            || !trait_.is_visible_from(db, current_module)
+            || trait_.is_unsafe(db)
+            || trait_.type_or_const_param_count(db) > 0
+            || (!is_generic && !sema_field_ty.impls_trait(db, trait_, &[]))
+            || strukt_ty.impls_trait(db, trait_, &[])
+        {
+            continue;
+        }
+        traits.push((trait_, impl_));
+    }
+    traits.sort_by_cached_key(|(trait_, _)| trait_.name(db).to_smol_str());
+
+    for (trait_, impl_) in traits {
+        let items = match delegated_items(ctx, trait_, impl_) {
+            Some(it) => it,
+            None => continue,
+        };
+        let trait_path = match current_module.find_use_path(db, ModuleDef::Trait(trait_)) {
+            Some(it) => mod_path_to_ast(&it),
+            None => continue,
+        };
+        acc.add_group(
+            &GroupLabel("Generate delegate trait impls…".to_owned()),
+            AssistId("generate_delegate_trait", AssistKind::Generate),
+            format!("Generate `{}` impl delegating to `{}`", trait_path, field_name),
+            target,
+            |builder| {
+                let adt = ast::Adt::Struct(strukt.clone());
+                let text = generate_trait_impl_text(&adt, &trait_path.to_string(), "");
+                let parse = syntax::SourceFile::parse(&text);
+                let impl_def = match parse.tree().syntax().descendants().find_map(ast::Impl::cast) {
+                    Some(it) => it.clone_subtree(),
+                    None => return,
+                };
+                let target_scope = ctx.sema.scope(strukt_name.syntax());
+                let items = items.into_iter().map(|it| it.clone_for_update()).collect();
+                let (impl_def, _) =
+                    add_trait_assoc_items_to_impl(&ctx.sema, items, trait_, impl_def, target_scope);
+
+                if is_generic {
+                    if let Some(path) = match &field_ty {
+                        ast::Type::PathType(it) => it.path(),
+                        _ => None,
+                    } {
+                        let pred =
+                            make::where_pred(path, [make::type_bound(&trait_path.to_string())]);
+                        impl_def
+                            .get_or_create_where_clause()
+                            .add_predicate(pred.clone_for_update());
+                    }
+                }
+
+                let assoc_items: Vec<_> =
+                    impl_def.get_or_create_assoc_item_list().assoc_items().collect();
+                for item in assoc_items {
+                    delegate_item(&item, &field_name, &field_ty, &trait_path);
+                }
+
+                let insert_pos = strukt.syntax().text_range().end();
+                let first_item = impl_def.assoc_item_list().and_then(|it| it.assoc_items().next());
+                match (ctx.config.snippet_cap, first_item) {
+                    (Some(cap), Some(first_item)) => {
+                        let cursor = Cursor::Before(first_item.syntax());
+                        let snippet = render_snippet(cap, impl_def.syntax(), cursor);
+                        builder.insert_snippet(cap, insert_pos, format!("\n\n{}", snippet));
+                    }
+                    _ => builder.insert(insert_pos, format!("\n\n{}", impl_def.syntax())),
+                }
+            },
+        )?;
+    }
+    Some(())
+}
+
+/// The items of `trait_` to delegate: all required ones, and the provided methods which the
+/// field's `impl_` overrides. Returns `None` if a required item can't be delegated.
+fn delegated_items(
+    ctx: &AssistContext,
+    trait_: hir::Trait,
+    impl_: hir::Impl,
+) -> Option<Vec<ast::AssocItem>> {
+    let db = ctx.db();
+    let overridden: Vec<_> = impl_.items(db).into_iter().filter_map(|it| it.name(db)).collect();
+    let mut res = Vec::new();
+    for item in trait_.items(db) {
+        match item {
+            hir::AssocItem::Function(f) => {
+                let src = ctx.sema.source(f)?.value;
+                let is_required = src.body().is_none();
+                if !is_required && !overridden.contains(&f.name(db)) {
+                    continue;
+                }
+                if can_delegate_fn(ctx, f, &src) {
+                    res.push(ast::AssocItem::Fn(src));
+                } else if is_required {
+                    return None;
+                }
+            }
+            hir::AssocItem::TypeAlias(ty) => {
+                let src = ctx.sema.source(ty)?.value;
+                if src.generic_param_list().is_some() {
+                    return None;
+                }
+                res.push(ast::AssocItem::TypeAlias(src));
+            }
+            hir::AssocItem::Const(c) => {
+                let src = ctx.sema.source(c)?.value;
+                if mentions_self_type(src.ty()?.syntax()) {
+                    return None;
+                }
+                res.push(ast::AssocItem::Const(src));
+            }
+        }
+    }
+    if res.is_empty() {
+        return None;
+    }
+    Some(res)
+}
+
+fn can_delegate_fn(ctx: &AssistContext, f: hir::Function, src: &ast::Fn) -> bool {
+    if f.self_param(ctx.db()).is_some() && !has_forwardable_receiver(ctx, f) {
+        return false;
+    }
+    if src.unsafe_token().is_some() {
+        return false;
+    }
+    let param_list = match src.param_list() {
+        Some(it) => it,
+        None => return false,
+    };
+    // The arguments are forwarded by name, and the field's `Self` is a different type.
+    param_list.params().all(|param| {
+        matches!(param.pat(), Some(ast::Pat::IdentPat(_)))
+            && !param.ty().map_or(false, |ty| mentions_self_type(ty.syntax()))
+    }) && !src.ret_type().map_or(false, |ty| mentions_self_type(ty.syntax()))
+}
+
+/// Whether `node` contains `Self` on its own, rather than as a prefix like in `Self::Item`.
+fn mentions_self_type(node: &SyntaxNode) -> bool {
+    node.descendants().filter_map(ast::Path::cast).any(|path| {
+        path.qualifier().is_none()
+            && path.parent_path().is_none()
+            && path.segment().and_then(|it| it.kind()) == Some(ast::PathSegmentKind::SelfTypeKw)
+    })
+}
+
+/// Replaces the body or value of `item` with one forwarding to the field.
+fn delegate_item(
+    item: &ast::AssocItem,
+    field_name: &str,
+    field_ty: &ast::Type,
+    trait_path: &ast::Path,
+) -> Option<()> {
+    let qualified = |name: &ast::Name| {
+        make::path_from_text(&format!("<{} as {}>::{}", field_ty, trait_path, name))
+    };
+    match item {
+        ast::AssocItem::Fn(f) => {
+            let name = f.name()?;
+            let param_list = f.param_list()?;
+            let args = convert_param_list_to_arg_list(param_list.clone());
+            let call = match param_list.self_param() {
+                Some(_) => make::expr_method_call(
+                    make::ext::field_from_idents(["self", field_name])?,
+                    make::name_ref(&name.to_string()),
+                    args,
+                ),
+                None => make::expr_call(make::expr_path(qualified(&name)), args),
+            };
+            let body = make::block_expr(None, Some(call)).indent(IndentLevel(1));
+            ted::replace(f.get_or_create_body().syntax(), body.clone_for_update().syntax());
+        }
+        ast::AssocItem::TypeAlias(ty) => {
+            let name = ty.name()?;
+            let value = make::ty_path(qualified(&name));
+            let new = make::ty_alias(name, value).clone_for_update();
+            ted::replace(ty.syntax(), new.syntax());
+        }
+        ast::AssocItem::Const(c) => {
+            let name = c.name()?;
+            let value = make::expr_path(qualified(&name));
+            let new = make::item_const(None, name, c.ty()?, value).clone_for_update();
+            ted::replace(c.syntax(), new.syntax());
+        }
+        ast::AssocItem::MacroCall(_) => (),
+    }
+    Some(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn delegates_required_items() {
+        check_assist(
+            generate_delegate_trait,
+            r#"
+trait Container {
+    type Item;
+    const CAPACITY: usize;
+    fn get(&self, index: usize) -> Option<&Self::Item>;
+    fn clear(&mut self);
+    fn new_empty() -> u32;
+    fn len(&self) -> usize {
+        0
+    }
+}
+struct Stack;
+impl Container for Stack {
+    type Item = u8;
+    const CAPACITY: usize = 8;
+    fn get(&self, index: usize) -> Option<&u8> {
+        None
+    }
+    fn clear(&mut self) {}
+    fn new_empty() -> u32 {
+        0
+    }
+}
+
+struct Wrapper(St$0ack);
+"#,
+            r#"
+trait Container {
+    type Item;
+    const CAPACITY: usize;
+    fn get(&self, index: usize) -> Option<&Self::Item>;
+    fn clear(&mut self);
+    fn new_empty() -> u32;
+    fn len(&self) -> usize {
+        0
+    }
+}
+struct Stack;
+impl Container for Stack {
+    type Item = u8;
+    const CAPACITY: usize = 8;
+    fn get(&self, index: usize) -> Option<&u8> {
+        None
+    }
+    fn clear(&mut self) {}
+    fn new_empty() -> u32 {
+        0
+    }
+}
+
+struct Wrapper(Stack);
+
+impl Container for Wrapper {
+    $0type Item = <Stack as Container>::Item;
+
+    const CAPACITY: usize = <Stack as Container>::CAPACITY;
+
+    fn get(&self, index: usize) -> Option<&Self::Item> {
+        self.0.get(index)
+    }
+
+    fn clear(&mut self) {
+        self.0.clear()
+    }
+
+    fn new_empty() -> u32 {
+        <Stack as Container>::new_empty()
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn delegates_overridden_provided_methods() {
+        check_assist(
+            generate_delegate_trait,
+            r#"
+trait Shape {
+    fn area(&self) -> u32;
+    fn name(&self) -> u32 {
+        0
+    }
+    fn sides(&self) -> u32 {
+        0
+    }
+}
+struct Square;
+impl Shape for Square {
+    fn area(&self) -> u32 {
+        1
+    }
+    fn sides(&self) -> u32 {
+        4
+    }
+}
+
+struct Tile {
+    sha$0pe: Square,
+}
+"#,
+            r#"
+trait Shape {
+    fn area(&self) -> u32;
+    fn name(&self) -> u32 {
+        0
+    }
+    fn sides(&self) -> u32 {
+        0
+    }
+}
+struct Square;
+impl Shape for Square {
+    fn area(&self) -> u32 {
+        1
+    }
+    fn sides(&self) -> u32 {
+        4
+    }
+}
+
+struct Tile {
+    shape: Square,
+}
+
+impl Shape for Tile {
+    $0fn area(&self) -> u32 {
+        self.shape.area()
+    }
+
+    fn sides(&self) -> u32 {
+        self.shape.sides()
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn generic_struct_gets_where_bound() {
+        check_assist(
+            generate_delegate_trait,
+            r#"
+trait Describe {
+    fn describe(&self) -> u32;
+}
+struct List<T>(T);
+impl<T: Describe> Describe for List<T> {
+    fn describe(&self) -> u32 {
+        self.0.describe()
+    }
+}
+
+struct Named<T> {
+    li$0st: List<T>,
+}
+"#,
+            r#"
+trait Describe {
+    fn describe(&self) -> u32;
+}
+struct List<T>(T);
+impl<T: Describe> Describe for List<T> {
+    fn describe(&self) -> u32 {
+        self.0.describe()
+    }
+}
+
+struct Named<T> {
+    list: List<T>,
+}
+
+impl<T> Describe for Named<T> where List<T>: Describe {
+    $0fn describe(&self) -> u32 {
+        self.list.describe()
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_when_returning_self() {
+        check_assist_not_applicable(
+            generate_delegate_trait,
+            r#"
+trait Duplicate {
+    fn duplicate(&self) -> Self;
+}
+struct Sheep;
+impl Duplicate for Sheep {
+    fn duplicate(&self) -> Self {
+        Sheep
+    }
+}
+
+struct Flock {
+    sh$0eep: Sheep,
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_when_already_implemented() {
+        check_assist_not_applicable(
+            generate_delegate_trait,
+            r#"
+trait Speak {
+    fn speak(&self) -> u32;
+}
+struct Dog;
+impl Speak for Dog {
+    fn speak(&self) -> u32 {
+        1
+    }
+}
+
+struct Loud {
+    do$0g: Dog,
+}
+impl Speak for Loud {
+    fn speak(&self) -> u32 {
+        2
+    }
+}
+"#,
+        );
+    }
+}
@@ -0,0 +1,389 @@
+use hir::{Access, HirDisplay};
+use ide_db::famous_defs::FamousDefs;
+use itertools::Itertools;
+use rustc_hash::FxHashSet;
+use stdx::{format_to, to_lower_snake_case};
+use syntax::{
+    ast::{self, edit::IndentLevel, HasModuleItem, HasName, HasVisibility},
+    AstNode, SyntaxNode,
+};
+
+use crate::{
+    assist_context::{AssistContext, Assists},
+    AssistId, AssistKind,
+};
+
+// Assist: generate_test_module
+//
+// Adds a `tests` module with a test stub for each public function of an impl block or a module.
+// Arguments are initialized with `Default::default()` where the type allows it.
+//
+// ```
+// struct Counter(u32);
+//
+// impl$0 Counter {
+//     pub fn new(start: u32) -> Self { Counter(start) }
+//     pub fn bump(&mut self) { self.0 += 1 }
+// }
+// ```
+// ->
+// ```
+// struct Counter(u32);
+//
+// impl Counter {
+//     pub fn new(start: u32) -> Self { Counter(start) }
+//     pub fn bump(&mut self) { self.0 += 1 }
+// }
+//
+// #[cfg(test)]
+// mod tests {
+//     use super::*;
+//
+//     #[test]
+//     fn test_new() {
+//         let start: u32 = todo!();
+//         assert_eq!(Counter::new(start), );
+//     }
+//
+//     #[test]
+//     fn test_bump() {
+//         let mut counter: Counter = todo!();
+//         counter.bump();
+//     }
+// }
+// ```
+pub(crate) fn generate_test_module(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let (container, target, impls, fns) = if let Some(impl_) = impl_at_header(ctx) {
+        let container = impl_.syntax().parent()?;
+        let target = impl_.syntax().text_range();
+        (container, target, vec![impl_], Vec::new())
+    } else {
+        let module = ctx.find_node_at_offset::<ast::Module>()?;
+        let item_list = module.item_list()?;
+        if ctx.offset() > item_list.syntax().text_range().start() {
+            return None;
+        }
+        let (impls, fns) = item_list.items().fold((Vec::new(), Vec::new()), |mut acc, item| {
+            match item {
+                ast::Item::Impl(it) if it.trait_().is_none() => acc.0.push(it),
+                ast::Item::Fn(it) if it.visibility().is_some() => acc.1.push(it),
+                _ => (),
+            }
+            acc
+        });
+        let target = module.syntax().text_range();
+        (item_list.syntax().clone(), target, impls, fns)
+    };
+    if has_tests_module(&container) {
+        cov_mark::hit!(generate_test_module_exists);
+        return None;
+    }
+
+    let mut stubs = Vec::new();
+    for func in fns {
+        stubs.push((func.name()?.text().to_string(), None, func));
+    }
+    for impl_ in &impls {
+        let self_ty = impl_.self_ty()?;
+        for item in impl_.assoc_item_list().into_iter().flat_map(|it| it.assoc_items()) {
+            match item {
+                ast::AssocItem::Fn(func) if func.visibility().is_some() => {
+                    stubs.push((func.name()?.text().to_string(), Some(self_ty.clone()), func))
+                }
+                _ => (),
+            }
+        }
+    }
+    if stubs.is_empty() {
+        return None;
+    }
+
+    let last_item = container.children().filter(|it| ast::Item::can_cast(it.kind())).last()?;
+    let offset = last_item.text_range().end();
+    let indent = IndentLevel::from_node(&last_item);
+
+    acc.add(
+        AssistId("generate_test_module", AssistKind::Generate),
+        "Generate a test module",
+        target,
+        |builder| {
+            let mut seen = FxHashSet::default();
+            let mut buf = String::from("#[cfg(test)]\nmod tests {\n    use super::*;\n");
+            for (name, self_ty, func) in &stubs {
+                let mut test_name = format!("test_{}", name);
+                if !seen.insert(test_name.clone()) {
+                    let ty_name = self_ty.as_ref().map_or(String::new(), type_name);
+                    test_name = format!("test_{}_{}", to_lower_snake_case(&ty_name), name);
+                }
+                format_to!(
+                    buf,
+                    "\n    #[test]\n    fn {}() {{\n{}    }}\n",
+                    test_name,
+                    stub_body(ctx, func, self_ty.as_ref())
+                );
+            }
+            buf.push('}');
+            builder.insert(offset, format!("\n\n{}{}", indent, indent_text(&buf, indent)));
+        },
+    )
+}
+
+fn impl_at_header(ctx: &AssistContext) -> Option<ast::Impl> {
+    let impl_ = ctx.find_node_at_offset::<ast::Impl>()?;
+    if impl_.trait_().is_some() {
+        return None;
+    }
+    let body_start = impl_.assoc_item_list()?.syntax().text_range().start();
+    if ctx.offset() > body_start {
+        return None;
+    }
+    Some(impl_)
+}
+
+fn has_tests_module(container: &SyntaxNode) -> bool {
+    container
+        .children()
+        .filter_map(ast::Module::cast)
+        .any(|it| it.name().map_or(false, |name| name.text() == "tests"))
+}
+
+/// The body of the test for `func`: a binding for each argument, initialized with
+/// `Default::default()` when the type allows it, and the call of the function.
+fn stub_body(ctx: &AssistContext, func: &ast::Fn, self_ty: Option<&ast::Type>) -> String {
+    let db = ctx.db();
+    let mut buf = String::new();
+    let func_def = match ctx.sema.to_def(func) {
+        Some(it) => it,
+        None => return buf,
+    };
+    let default_trait =
+        FamousDefs(&ctx.sema, Some(func_def.module(db).krate())).core_default_Default();
+    let placeholder = |ty: &hir::Type| match default_trait {
+        Some(default_trait) if ty.impls_trait(db, default_trait, &[]) => "Default::default()",
+        _ => "todo!()",
+    };
+
+    let mut receiver = None;
+    if let (Some(self_param), Some(self_ty)) = (func_def.self_param(db), self_ty) {
+        let name = to_lower_snake_case(&type_name(self_ty));
+        let mutability =
+            if matches!(self_param.access(db), Access::Exclusive) { "mut " } else { "" };
+        let ty = ctx.sema.resolve_type(self_ty);
+        format_to!(
+            buf,
+            "        let {}{}: {} = {};\n",
+            mutability,
+            name,
+            self_ty,
+            ty.as_ref().map_or("todo!()", placeholder)
+        );
+        receiver = Some(name);
+    }
+
+    let ast_params = func.param_list().into_iter().flat_map(|it| it.params());
+    let mut args = Vec::new();
+    for (idx, (ast_param, param)) in ast_params.zip(func_def.params_without_self(db)).enumerate() {
+        let name = match ast_param.pat() {
+            Some(ast::Pat::IdentPat(pat)) => pat.name().map(|it| it.text().to_string()),
+            _ => None,
+        }
+        .unwrap_or_else(|| format!("arg{}", idx));
+        let ty = match ast_param.ty() {
+            Some(it) => it.to_string(),
+            None => param.ty().display(db).to_string(),
+        };
+        format_to!(buf, "        let {}: {} = {};\n", name, ty, placeholder(param.ty()));
+        args.push(name);
+    }
+
+    let fn_name = func.name().map_or(String::new(), |it| it.text().to_string());
+    let mut call = match (receiver, self_ty) {
+        (Some(receiver), _) => format!("{}.{}({})", receiver, fn_name, args.iter().format(", ")),
+        (None, Some(self_ty)) => {
+            format!("{}::{}({})", type_name(self_ty), fn_name, args.iter().format(", "))
+        }
+        (None, None) => format!("{}({})", fn_name, args.iter().format(", ")),
+    };
+    if func_def.is_unsafe(db) {
+        call = format!("unsafe {{ {} }}", call);
+    }
+    if func_def.ret_type(db).is_unit() {
+        format_to!(buf, "        {};\n", call);
+    } else {
+        format_to!(buf, "        assert_eq!({}, );\n", call);
+    }
+    buf
+}
+
+/// The name of the type without generic arguments, `Foo` for `Foo<T>`.
+fn type_name(ty: &ast::Type) -> String {
+    match ty {
+        ast::Type::PathType(it) => it
+            .path()
+            .and_then(|it| it.segment())
+            .and_then(|it| it.name_ref())
+            .map_or_else(|| ty.to_string(), |it| it.text().to_string()),
+        _ => ty.to_string(),
+    }
+}
+
+fn indent_text(text: &str, indent: IndentLevel) -> String {
+    text.lines()
+        .enumerate()
+        .map(|(idx, line)| {
+            if idx == 0 || line.is_empty() {
+                line.to_string()
+            } else {
+                format!("{}{}", indent, line)
+            }
+        })
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn impl_methods() {
+        check_assist(
+            generate_test_module,
+            r#"
+//- minicore: default
+struct Counter(u32);
+impl Default for Counter {
+    fn default() -> Self { Counter(0) }
+}
+
+impl$0 Counter {
+    pub fn new(start: u32) -> Self { Counter(start) }
+    pub fn get(&self) -> u32 { self.0 }
+    pub fn bump(&mut self) { self.0 += 1 }
+    fn private(&self) {}
+}
+"#,
+            r#"
+struct Counter(u32);
+impl Default for Counter {
+    fn default() -> Self { Counter(0) }
+}
+
+impl Counter {
+    pub fn new(start: u32) -> Self { Counter(start) }
+    pub fn get(&self) -> u32 { self.0 }
+    pub fn bump(&mut self) { self.0 += 1 }
+    fn private(&self) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        let start: u32 = todo!();
+        assert_eq!(Counter::new(start), );
+    }
+
+    #[test]
+    fn test_get() {
+        let counter: Counter = Default::default();
+        assert_eq!(counter.get(), );
+    }
+
+    #[test]
+    fn test_bump() {
+        let mut counter: Counter = Default::default();
+        counter.bump();
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn module_functions() {
+        check_assist(
+            generate_test_module,
+            r#"
+mod $0geometry {
+    pub struct Point { x: i32 }
+
+    impl Point {
+        pub unsafe fn origin() -> Point { Point { x: 0 } }
+    }
+
+    pub fn shift(p: &mut Point, (dx, _): (i32, i32)) {}
+}
+"#,
+            r#"
+mod geometry {
+    pub struct Point { x: i32 }
+
+    impl Point {
+        pub unsafe fn origin() -> Point { Point { x: 0 } }
+    }
+
+    pub fn shift(p: &mut Point, (dx, _): (i32, i32)) {}
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_shift() {
+            let p: &mut Point = todo!();
+            let arg1: (i32, i32) = todo!();
+            shift(p, arg1);
+        }
+
+        #[test]
+        fn test_origin() {
+            assert_eq!(unsafe { Point::origin() }, );
+        }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_with_existing_tests() {
+        cov_mark::check!(generate_test_module_exists);
+        check_assist_not_applicable(
+            generate_test_module,
+            r#"
+struct S;
+impl$0 S {
+    pub fn f() {}
+}
+#[cfg(test)]
+mod tests {}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_inside_bodies_or_without_public_functions() {
+        check_assist_not_applicable(
+            generate_test_module,
+            r#"
+struct S;
+impl S {
+    pub fn f() { $0 }
+}
+"#,
+        );
+        check_assist_not_applicable(
+            generate_test_module,
+            r#"
+struct S;
+impl$0 S {
+    fn f() {}
+}
+"#,
+        );
+    }
+}
@@ -0,0 +1,704 @@
+use hir::{AsAssocItem, HasSource, HirDisplay, PathResolution, Semantics};
+use ide_db::{
+    base_db::FileId, defs::Definition, path_transform::PathTransform, search::FileReference,
+    syntax_helpers::node_ext::preorder_expr, RootDatabase,
+};
+use rustc_hash::{FxHashMap, FxHashSet};
+use syntax::{
+    algo::find_node_at_range,
+    ast::{self, edit::IndentLevel, HasArgList},
+    AstNode, SyntaxNode, SyntaxToken, TextRange, WalkEvent, T,
+};
+
+use crate::{utils::suggest_name, AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: introduce_parameter
+//
+// Replaces the selected expression with a new function parameter, passing the expression at every
+// call site instead.
+//
+// ```
+// fn connect() {
+//     open($0"localhost"$0);
+// }
+// fn open(host: &str) {}
+// fn main() {
+//     connect();
+// }
+// ```
+// ->
+// ```
+// fn connect(host: &str) {
+//     open(host);
+// }
+// fn open(host: &str) {}
+// fn main() {
+//     connect("localhost");
+// }
+// ```
+pub(crate) fn introduce_parameter(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let expr = selected_expr(ctx)?;
+    let fn_ast = expr.syntax().ancestors().find_map(ast::Fn::cast)?;
+    if !fn_ast.body()?.syntax().text_range().contains_range(expr.syntax().text_range()) {
+        return None;
+    }
+    if is_in_trait_impl(&fn_ast) {
+        cov_mark::hit!(introduce_parameter_trait_impl);
+        return None;
+    }
+    if !is_self_contained(&ctx.sema, &expr) {
+        cov_mark::hit!(introduce_parameter_uses_locals);
+        return None;
+    }
+    let func = ctx.sema.to_def(&fn_ast)?;
+    let module = ctx.sema.scope(fn_ast.syntax()).module()?;
+    let ty = ctx.sema.type_of_expr(&expr)?.original;
+    if ty.contains_unknown() || ty.is_unit() || ty.is_closure() {
+        return None;
+    }
+    let ty = ty.display_source_code(ctx.db(), module.into()).ok()?;
+    let param_list = fn_ast.param_list()?;
+
+    // Every other reference needs to be a call to pass the expression at.
+    let mut calls = Vec::new();
+    for (file_id, references) in Definition::Function(func).usages(&ctx.sema).all() {
+        let source_file = ctx.sema.parse(file_id);
+        for FileReference { range, name, .. } in references {
+            if file_id == ctx.file_id() && expr.syntax().text_range().contains_range(range) {
+                continue;
+            }
+            if name.syntax().ancestors().any(|it| ast::UseTree::can_cast(it.kind())) {
+                continue;
+            }
+            match call_arg_list(source_file.syntax(), range) {
+                Some(arg_list) => calls.push((file_id, arg_list)),
+                None => {
+                    cov_mark::hit!(introduce_parameter_non_call_reference);
+                    return None;
+                }
+            }
+        }
+    }
+
+    acc.add(
+        AssistId("introduce_parameter", AssistKind::RefactorExtract),
+        "Introduce parameter",
+        expr.syntax().text_range(),
+        |builder| {
+            // The parameter must not clash with the other ones, nor be shadowed by a local.
+            let taken = fn_ast
+                .syntax()
+                .descendants()
+                .filter_map(ast::IdentPat::cast)
+                .filter_map(|it| it.name())
+                .map(|it| it.text().to_string())
+                .collect();
+            let name = unique_name(suggest_name::for_variable(&expr, &ctx.sema), &taken);
+            let expr_range = expr.syntax().text_range();
+
+            let mut edits: FxHashMap<FileId, Vec<(TextRange, String)>> = FxHashMap::default();
+            let current_file_edits = edits.entry(ctx.file_id()).or_default();
+            current_file_edits.push((expr_range, name.clone()));
+            let last_param = param_list
+                .params()
+                .last()
+                .map(|it| it.syntax().clone())
+                .or_else(|| param_list.self_param().map(|it| it.syntax().clone()));
+            match (last_param, param_list.l_paren_token()) {
+                (Some(last_param), _) => current_file_edits.push((
+                    TextRange::empty(last_param.text_range().end()),
+                    format!(", {}: {}", name, ty),
+                )),
+                (None, Some(l_paren)) => current_file_edits.push((
+                    TextRange::empty(l_paren.text_range().end()),
+                    format!("{}: {}", name, ty),
+                )),
+                (None, None) => return,
+            }
+
+            for (file_id, arg_list) in &calls {
+                let arg = expr_at(ctx, &expr, arg_list.syntax());
+                if let Some(edit) = append_call_arg(arg_list, arg) {
+                    edits.entry(*file_id).or_default().push(edit);
+                }
+            }
+
+            for (file_id, edits) in edits {
+                builder.edit_file(file_id);
+                for (range, text) in edits {
+                    builder.replace(range, text);
+                }
+            }
+        },
+    )
+}
+
+// Assist: introduce_field
+//
+// Replaces the selected expression inside a method with a new field of `Self`, initializing it
+// with the expression wherever the struct is constructed.
+//
+// ```
+// struct Client { name: String }
+// impl Client {
+//     fn retries(&self) -> u32 {
+//         $03$0
+//     }
+// }
+// fn main() {
+//     let client = Client { name: String::new() };
+// }
+// ```
+// ->
+// ```
+// struct Client { name: String, var_name: u32 }
+// impl Client {
+//     fn retries(&self) -> u32 {
+//         self.var_name
+//     }
+// }
+// fn main() {
+//     let client = Client { name: String::new(), var_name: 3 };
+// }
+// ```
+pub(crate) fn introduce_field(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let expr = selected_expr(ctx)?;
+    let fn_ast = expr.syntax().ancestors().find_map(ast::Fn::cast)?;
+    if !fn_ast.body()?.syntax().text_range().contains_range(expr.syntax().text_range()) {
+        return None;
+    }
+    if fn_ast.param_list()?.self_param().is_none() {
+        return None;
+    }
+    if !is_self_contained(&ctx.sema, &expr) {
+        cov_mark::hit!(introduce_field_uses_locals);
+        return None;
+    }
+
+    let func = ctx.sema.to_def(&fn_ast)?;
+    let strukt = match func.as_assoc_item(ctx.db())?.container(ctx.db()) {
+        hir::AssocItemContainer::Impl(impl_) => match impl_.self_ty(ctx.db()).as_adt()? {
+            hir::Adt::Struct(it) => it,
+            _ => return None,
+        },
+        hir::AssocItemContainer::Trait(_) => return None,
+    };
+    let strukt_src = strukt.source(ctx.db())?;
+    if strukt_src.file_id.is_macro() {
+        return None;
+    }
+    let strukt_file_id = strukt_src.file_id.original_file(ctx.db());
+    let field_list = match strukt_src.value.field_list()? {
+        ast::FieldList::RecordFieldList(it) => it,
+        ast::FieldList::TupleFieldList(_) => return None,
+    };
+
+    let ty = ctx.sema.type_of_expr(&expr)?.original;
+    if ty.contains_unknown() || ty.is_unit() || ty.is_closure() {
+        return None;
+    }
+    let is_copy = ty.is_copy(ctx.db());
+    let ty = ty.display_source_code(ctx.db(), strukt.module(ctx.db()).into()).ok()?;
+
+    acc.add(
+        AssistId("introduce_field", AssistKind::RefactorExtract),
+        "Introduce field",
+        expr.syntax().text_range(),
+        |builder| {
+            let taken = field_list
+                .fields()
+                .filter_map(|it| it.name())
+                .map(|it| it.text().to_string())
+                .collect();
+            let name = unique_name(suggest_name::for_variable(&expr, &ctx.sema), &taken);
+
+            let mut edits: FxHashMap<FileId, Vec<(TextRange, String)>> = FxHashMap::default();
+            let access =
+                if is_copy { format!("self.{}", name) } else { format!("self.{}.clone()", name) };
+            edits.entry(ctx.file_id()).or_default().push((expr.syntax().text_range(), access));
+            if let Some(edit) = append_list_item(
+                field_list.syntax(),
+                field_list.fields().last().map(|it| it.syntax().clone()),
+                field_list.l_curly_token(),
+                field_list.r_curly_token(),
+                format!("{}: {}", name, ty),
+            ) {
+                edits.entry(strukt_file_id).or_default().push(edit);
+            }
+
+            let usages = Definition::Adt(strukt.into()).usages(&ctx.sema).include_self_refs().all();
+            for (file_id, references) in usages {
+                let source_file = ctx.sema.parse(file_id);
+                for FileReference { range, .. } in references {
+                    let record_expr =
+                        match find_node_at_range::<ast::RecordExpr>(source_file.syntax(), range) {
+                            Some(it) => it,
+                            None => continue,
+                        };
+                    let edit = record_expr_name_ref_range(&record_expr)
+                        .filter(|it| *it == range)
+                        .and_then(|_| record_expr.record_expr_field_list())
+                        .and_then(|list| {
+                            let value = expr_at(ctx, &expr, list.syntax());
+                            add_record_expr_field(&list, &name, value)
+                        });
+                    if let Some(edit) = edit {
+                        edits.entry(file_id).or_default().push(edit);
+                    }
+                }
+            }
+
+            for (file_id, edits) in edits {
+                builder.edit_file(file_id);
+                for (range, text) in edits {
+                    builder.replace(range, text);
+                }
+            }
+        },
+    )
+}
+
+fn selected_expr(ctx: &AssistContext) -> Option<ast::Expr> {
+    if ctx.has_empty_selection() {
+        return None;
+    }
+    let expr = ctx.find_node_at_range::<ast::Expr>()?;
+    if expr.syntax().text_range() != ctx.selection_trimmed() {
+        return None;
+    }
+    Some(expr)
+}
+
+/// Appends a counter to `name` if it is already `taken`, like `host_1` next to `host`.
+fn unique_name(name: String, taken: &FxHashSet<String>) -> String {
+    if !taken.contains(&name) {
+        return name;
+    }
+    (1..).map(|i| format!("{}_{}", name, i)).find(|it| !taken.contains(it)).unwrap()
+}
+
+fn is_in_trait_impl(fn_ast: &ast::Fn) -> bool {
+    fn_ast
+        .syntax()
+        .parent()
+        .and_then(|it| it.parent())
+        .and_then(ast::Impl::cast)
+        .map_or(false, |imp| imp.trait_().is_some())
+}
+
+/// Whether `expr` can be evaluated outside of the function it is in, i.e. does not refer to locals,
+/// generic parameters or `Self`, and does not affect the function's control flow.
+fn is_self_contained(sema: &Semantics<RootDatabase>, expr: &ast::Expr) -> bool {
+    let mut res = true;
+    preorder_expr(expr, &mut |ev| {
+        let expr = match ev {
+            WalkEvent::Enter(it) => it,
+            WalkEvent::Leave(_) => return false,
+        };
+        match &expr {
+            ast::Expr::ReturnExpr(_)
+            | ast::Expr::BreakExpr(_)
+            | ast::Expr::ContinueExpr(_)
+            | ast::Expr::TryExpr(_)
+            | ast::Expr::AwaitExpr(_)
+            | ast::Expr::YieldExpr(_) => res = false,
+            ast::Expr::PathExpr(path_expr) => {
+                let resolution = path_expr.path().and_then(|path| sema.resolve_path(&path));
+                if matches!(
+                    resolution,
+                    Some(
+                        PathResolution::Local(_)
+                            | PathResolution::TypeParam(_)
+                            | PathResolution::ConstParam(_)
+                            | PathResolution::SelfType(_)
+                    )
+                ) {
+                    res = false;
+                }
+            }
+            _ => (),
+        }
+        !res
+    });
+    res
+}
+
+/// The argument list of the call whose callee name is at `range`.
+fn call_arg_list(file: &SyntaxNode, range: TextRange) -> Option<ast::ArgList> {
+    let fn_call_args = find_node_at_range::<ast::CallExpr>(file, range).and_then(|call| {
+        let path = match call.expr()? {
+            ast::Expr::PathExpr(it) => it.path()?,
+            _ => return None,
+        };
+        if path.segment()?.name_ref()?.syntax().text_range() != range {
+            return None;
+        }
+        call.arg_list()
+    });
+    match fn_call_args {
+        Some(it) => Some(it),
+        None => {
+            let call = find_node_at_range::<ast::MethodCallExpr>(file, range)?;
+            if call.name_ref()?.syntax().text_range() != range {
+                return None;
+            }
+            call.arg_list()
+        }
+    }
+}
+
+/// Appends `arg` as the last argument of `arg_list`.
+fn append_call_arg(arg_list: &ast::ArgList, arg: String) -> Option<(TextRange, String)> {
+    match arg_list.args().last() {
+        Some(last_arg) => {
+            Some((TextRange::empty(last_arg.syntax().text_range().end()), format!(", {}", arg)))
+        }
+        None => Some((TextRange::empty(arg_list.l_paren_token()?.text_range().end()), arg)),
+    }
+}
+
+/// The text of `expr` moved to `target`, with its paths qualified for the scope there.
+fn expr_at(ctx: &AssistContext, expr: &ast::Expr, target: &SyntaxNode) -> String {
+    let moved = expr.clone_subtree().clone_for_update();
+    let target_scope = ctx.sema.scope(target);
+    let source_scope = ctx.sema.scope(expr.syntax());
+    PathTransform::qualify_paths(&target_scope, &source_scope).apply(moved.syntax());
+    moved.to_string()
+}
+
+fn record_expr_name_ref_range(record_expr: &ast::RecordExpr) -> Option<TextRange> {
+    Some(record_expr.path()?.segment()?.name_ref()?.syntax().text_range())
+}
+
+fn add_record_expr_field(
+    list: &ast::RecordExprFieldList,
+    name: &str,
+    value: String,
+) -> Option<(TextRange, String)> {
+    let item = format!("{}: {}", name, value);
+    if list.fields().next().is_none() {
+        if let Some(dotdot) = list.dotdot_token() {
+            return Some((TextRange::empty(dotdot.text_range().start()), format!("{}, ", item)));
+        }
+    }
+    append_list_item(
+        list.syntax(),
+        list.fields().last().map(|it| it.syntax().clone()),
+        list.l_curly_token(),
+        list.r_curly_token(),
+        item,
+    )
+}
+
+/// Appends `item` to a comma separated, curly braced list, keeping multi-line lists multi-line.
+fn append_list_item(
+    list: &syntax::SyntaxNode,
+    last_item: Option<syntax::SyntaxNode>,
+    l_curly: Option<SyntaxToken>,
+    r_curly: Option<SyntaxToken>,
+    item: String,
+) -> Option<(TextRange, String)> {
+    let last_item = match last_item {
+        Some(it) => it,
+        None => {
+            let range = TextRange::new(l_curly?.text_range().end(), r_curly?.text_range().start());
+            return Some((range, format!(" {} ", item)));
+        }
+    };
+    let comma = last_item
+        .siblings_with_tokens(syntax::Direction::Next)
+        .filter_map(|it| it.into_token())
+        .find(|it| it.kind() == T![,]);
+    if list.text().contains_char('\n') {
+        let indent = IndentLevel::from_node(&last_item);
+        Some(match comma {
+            Some(comma) => {
+                (TextRange::empty(comma.text_range().end()), format!("\n{}{},", indent, item))
+            }
+            None => {
+                (TextRange::empty(last_item.text_range().end()), format!(",\n{}{},", indent, item))
+            }
+        })
+    } else {
+        Some((TextRange::empty(last_item.text_range().end()), format!(", {}", item)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn introduce_parameter_updates_call_sites() {
+        check_assist(
+            introduce_parameter,
+            r#"
+struct S;
+impl S {
+    fn timeout(&self, retries: u32) -> u32 {
+        retries * $05000$0
+    }
+}
+fn main() {
+    S.timeout(3);
+    S::timeout(&S, 1,);
+}
+"#,
+            r#"
+struct S;
+impl S {
+    fn timeout(&self, retries: u32, var_name: u32) -> u32 {
+        retries * var_name
+    }
+}
+fn main() {
+    S.timeout(3, 5000);
+    S::timeout(&S, 1, 5000,);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn introduce_parameter_no_params() {
+        check_assist(
+            introduce_parameter,
+            r#"
+fn greeting() -> String {
+    $0String::from("hello")$0
+}
+fn main() {
+    greeting();
+}
+"#,
+            r#"
+fn greeting(from: String) -> String {
+    from
+}
+fn main() {
+    greeting(String::from("hello"));
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn introduce_parameter_qualifies_paths_at_call_sites() {
+        check_assist(
+            introduce_parameter,
+            r#"
+mod net {
+    pub fn default_port() -> u16 { 80 }
+    pub fn connect() -> u16 {
+        $0default_port()$0
+    }
+}
+use net::connect;
+fn main() {
+    connect();
+}
+"#,
+            r#"
+mod net {
+    pub fn default_port() -> u16 { 80 }
+    pub fn connect(default_port: u16) -> u16 {
+        default_port
+    }
+}
+use net::connect;
+fn main() {
+    connect(net::default_port());
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn introduce_parameter_avoids_existing_names() {
+        check_assist(
+            introduce_parameter,
+            r#"
+fn open(host: &str) {}
+fn connect(host: &str) {
+    open(host);
+    open($0"localhost"$0);
+}
+fn main() {
+    connect("example.com");
+}
+"#,
+            r#"
+fn open(host: &str) {}
+fn connect(host: &str, host_1: &str) {
+    open(host);
+    open(host_1);
+}
+fn main() {
+    connect("example.com", "localhost");
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn introduce_parameter_not_applicable_with_non_call_references() {
+        cov_mark::check!(introduce_parameter_non_call_reference);
+        check_assist_not_applicable(
+            introduce_parameter,
+            r#"
+fn port() -> u16 {
+    $080$0
+}
+fn main() {
+    let f = port;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn introduce_parameter_not_applicable_with_locals() {
+        cov_mark::check!(introduce_parameter_uses_locals);
+        check_assist_not_applicable(
+            introduce_parameter,
+            r#"
+fn f(x: u32) -> u32 {
+    $0x + 1$0
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn introduce_parameter_not_applicable_in_trait_impl() {
+        cov_mark::check!(introduce_parameter_trait_impl);
+        check_assist_not_applicable(
+            introduce_parameter,
+            r#"
+trait T { fn f(&self) -> u32; }
+impl T for () {
+    fn f(&self) -> u32 { $092$0 }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn introduce_field_multiline() {
+        check_assist(
+            introduce_field,
+            r#"
+struct Client {
+    name: String,
+}
+impl Client {
+    fn new() -> Self {
+        Self { name: String::new() }
+    }
+    fn greeting(&self) -> String {
+        $0String::from("hello")$0
+    }
+}
+fn main() {
+    let c = Client {
+        name: String::new()
+    };
+}
+"#,
+            r#"
+struct Client {
+    name: String,
+    from: String,
+}
+impl Client {
+    fn new() -> Self {
+        Self { name: String::new(), from: String::from("hello") }
+    }
+    fn greeting(&self) -> String {
+        self.from.clone()
+    }
+}
+fn main() {
+    let c = Client {
+        name: String::new(),
+        from: String::from("hello"),
+    };
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn introduce_field_empty_struct() {
+        check_assist(
+            introduce_field,
+            r#"
+struct Timer {}
+impl Timer {
+    fn interval(&self) -> u64 {
+        $01000$0
+    }
+}
+fn main() {
+    let t = Timer {};
+}
+"#,
+            r#"
+struct Timer { var_name: u64 }
+impl Timer {
+    fn interval(&self) -> u64 {
+        self.var_name
+    }
+}
+fn main() {
+    let t = Timer { var_name: 1000 };
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn introduce_field_avoids_existing_fields() {
+        check_assist(
+            introduce_field,
+            r#"
+struct Timer { var_name: u64 }
+impl Timer {
+    fn interval(&self) -> u64 {
+        $01000$0
+    }
+}
+fn main() {
+    let t = Timer { var_name: 1 };
+}
+"#,
+            r#"
+struct Timer { var_name: u64, var_name_1: u64 }
+impl Timer {
+    fn interval(&self) -> u64 {
+        self.var_name_1
+    }
+}
+fn main() {
+    let t = Timer { var_name: 1, var_name_1: 1000 };
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn introduce_field_not_applicable_with_self() {
+        cov_mark::check!(introduce_field_uses_locals);
+        check_assist_not_applicable(
+            introduce_field,
+            r#"
+struct S { x: u32 }
+impl S {
+    fn f(&self) -> u32 { $0self.x + 1$0 }
+}
+"#,
+        );
+    }
+}
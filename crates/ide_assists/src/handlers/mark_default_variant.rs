@@ -0,0 +1,247 @@
+use std::iter;
+
+use ide_db::famous_defs::FamousDefs;
+use syntax::{
+    ast::{self, edit::IndentLevel, HasAttrs},
+    AstNode, SyntaxKind, T,
+};
+
+use crate::{
+    utils::{find_derive, vis_offset},
+    AssistContext, AssistId, AssistKind, Assists,
+};
+
+// Assist: mark_default_variant
+//
+// Derives `Default` for an enum, marking the unit variant under the cursor as
+// the `#[default]` one.
+//
+// ```
+// # //- minicore: default, derive
+// #[derive(Clone)]
+// enum Version {
+//     Undefined,
+//     Minor$0,
+//     Major,
+// }
+// ```
+// ->
+// ```
+// #[derive(Clone, Default)]
+// enum Version {
+//     Undefined,
+//     #[default]
+//     Minor,
+//     Major,
+// }
+// ```
+pub(crate) fn mark_default_variant(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let variant = ctx.find_node_at_offset::<ast::Variant>()?;
+    variant.name()?;
+    if !matches!(variant.kind(), ast::StructKind::Unit) {
+        cov_mark::hit!(mark_default_on_non_unit_variant);
+        return None;
+    }
+    let enum_ = variant.parent_enum();
+    let has_default_variant = enum_
+        .variant_list()?
+        .variants()
+        .flat_map(|it| it.attrs())
+        .any(|it| it.simple_name().as_deref() == Some("default"));
+    if has_default_variant {
+        return None;
+    }
+
+    let krate = ctx.sema.scope(enum_.syntax()).krate()?;
+    let famous_defs = FamousDefs(&ctx.sema, Some(krate));
+    // `#[default]` on variants is only understood by newer toolchains, whose
+    // `Default` derive declares it as a helper attribute.
+    let supports_default_variant = famous_defs
+        .core_default_Default_derive()
+        .map_or(false, |it| it.has_builtin_derive_helper(ctx.db(), "default"));
+    if !supports_default_variant {
+        cov_mark::hit!(mark_default_unsupported);
+        return None;
+    }
+
+    let derive = find_derive(&enum_, "Default");
+    if derive.is_none() {
+        let default_trait = famous_defs.core_default_Default()?;
+        let enum_def = ctx.sema.to_def(&enum_)?;
+        if enum_def.ty(ctx.db()).impls_trait(ctx.db(), default_trait, &[]) {
+            cov_mark::hit!(mark_default_impl_already_exists);
+            return None;
+        }
+    }
+    let existing_derive = enum_
+        .attrs()
+        .find(|attr| attr.as_simple_call().map_or(false, |(name, _)| name == "derive"));
+
+    let target = variant.syntax().text_range();
+    acc.add(
+        AssistId("mark_default_variant", AssistKind::Generate),
+        "Mark as the `#[default]` variant",
+        target,
+        |builder| {
+            let indent = IndentLevel::from_node(variant.syntax());
+            builder.insert(target.start(), format!("#[default]\n{}", indent));
+            if derive.is_some() {
+                return;
+            }
+            let derive_list = existing_derive.as_ref().and_then(|it| it.token_tree());
+            match derive_list.and_then(|it| it.r_paren_token()) {
+                Some(r_paren) => {
+                    let last = iter::successors(r_paren.prev_token(), |it| it.prev_token())
+                        .find(|it| it.kind() != SyntaxKind::WHITESPACE);
+                    let text = match last.map(|it| it.kind()) {
+                        Some(T![,]) => " Default",
+                        Some(T!['(']) => "Default",
+                        _ => ", Default",
+                    };
+                    builder.insert(r_paren.text_range().start(), text);
+                }
+                None => {
+                    let indent = IndentLevel::from_node(enum_.syntax());
+                    builder.insert(
+                        vis_offset(enum_.syntax()),
+                        format!("#[derive(Default)]\n{}", indent),
+                    );
+                }
+            }
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn adds_derive() {
+        check_assist(
+            mark_default_variant,
+            r#"
+//- minicore: default, derive
+enum Variant {
+    Undefined,
+    Minor$0,
+}
+"#,
+            r#"
+#[derive(Default)]
+enum Variant {
+    Undefined,
+    #[default]
+    Minor,
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn extends_derive() {
+        check_assist(
+            mark_default_variant,
+            r#"
+//- minicore: default, derive
+mod m {
+    #[derive(Clone, Copy,)]
+    pub enum Variant {
+        Undefined$0,
+    }
+}
+"#,
+            r#"
+mod m {
+    #[derive(Clone, Copy, Default)]
+    pub enum Variant {
+        #[default]
+        Undefined,
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn keeps_existing_derive() {
+        check_assist(
+            mark_default_variant,
+            r#"
+//- minicore: default, derive
+#[derive(Default)]
+enum Variant {
+    Undefined,
+    Minor$0,
+}
+"#,
+            r#"
+#[derive(Default)]
+enum Variant {
+    Undefined,
+    #[default]
+    Minor,
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable() {
+        cov_mark::check!(mark_default_on_non_unit_variant);
+        check_assist_not_applicable(
+            mark_default_variant,
+            r#"
+//- minicore: default, derive
+enum Variant {
+    Minor(u32)$0,
+}
+"#,
+        );
+        check_assist_not_applicable(
+            mark_default_variant,
+            r#"
+//- minicore: default, derive
+#[derive(Default)]
+enum Variant {
+    #[default]
+    Undefined,
+    Minor$0,
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_with_impl() {
+        cov_mark::check!(mark_default_impl_already_exists);
+        check_assist_not_applicable(
+            mark_default_variant,
+            r#"
+//- minicore: default, derive
+enum Variant {
+    Minor$0,
+}
+impl Default for Variant {
+    fn default() -> Self { Variant::Minor }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_on_old_toolchain() {
+        cov_mark::check!(mark_default_unsupported);
+        check_assist_not_applicable(
+            mark_default_variant,
+            r#"
+//- minicore: default
+enum Variant {
+    Minor$0,
+}
+"#,
+        );
+    }
+}
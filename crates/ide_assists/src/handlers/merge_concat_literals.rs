@@ -0,0 +1,221 @@
+use itertools::Itertools;
+use syntax::{
+    ast::{self, AstToken},
+    AstNode, NodeOrToken, SyntaxKind, SyntaxToken, TextRange, T,
+};
+
+use crate::{AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: merge_concat_literals
+//
+// Merges adjacent literals in a `concat!` call into one string literal. If all
+// arguments are literals, the call is replaced by the string.
+//
+// ```
+// fn main() {
+//     let s = concat!$0("Hello, ", "World", '!', 1);
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     let s = "Hello, World!1";
+// }
+// ```
+pub(crate) fn merge_concat_literals(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let macro_call = ctx.find_node_at_offset::<ast::MacroCall>()?;
+    let tt = macro_call.token_tree()?;
+    if macro_call.path()?.segment()?.name_ref()?.text() != "concat"
+        || macro_call.excl_token().is_none()
+    {
+        return None;
+    }
+    let r_delim = NodeOrToken::Token(tt.right_delimiter_token()?);
+
+    let args = tt
+        .syntax()
+        .children_with_tokens()
+        .skip(1)
+        .take_while(|it| *it != r_delim)
+        .filter(|it| !it.kind().is_trivia())
+        .group_by(|it| it.kind() == T![,]);
+    // `None` for the arguments which aren't a single literal, like `-1` or `env!("VAR")`.
+    let args: Vec<Option<(TextRange, String)>> = args
+        .into_iter()
+        .filter_map(|(is_sep, group)| (!is_sep).then(|| group))
+        .map(|group| match group.collect::<Vec<_>>().as_slice() {
+            [NodeOrToken::Token(token)] => {
+                literal_text(token).map(|text| (token.text_range(), text))
+            }
+            _ => None,
+        })
+        .collect();
+
+    let edits: Vec<(TextRange, String)> = if !args.is_empty() && args.iter().all(Option::is_some) {
+        let range = TextRange::new(
+            macro_call.syntax().text_range().start(),
+            tt.syntax().text_range().end(),
+        );
+        let text = args.iter().flatten().map(|(_, text)| text.as_str()).collect::<String>();
+        vec![(range, format!("\"{}\"", text))]
+    } else {
+        let runs = args.iter().group_by(|it| it.is_some());
+        runs.into_iter()
+            .filter_map(|(is_literal, run)| {
+                let run = run.flatten().collect::<Vec<_>>();
+                if !is_literal || run.len() < 2 {
+                    return None;
+                }
+                let range = run[0].0.cover(run[run.len() - 1].0);
+                let text = run.iter().map(|(_, text)| text.as_str()).collect::<String>();
+                Some((range, format!("\"{}\"", text)))
+            })
+            .collect()
+    };
+    if edits.is_empty() {
+        return None;
+    }
+
+    acc.add(
+        AssistId("merge_concat_literals", AssistKind::RefactorRewrite),
+        "Merge literals in `concat!`",
+        macro_call.syntax().text_range(),
+        |builder| {
+            for (range, text) in edits {
+                builder.replace(range, text);
+            }
+        },
+    )
+}
+
+/// The text `token` contributes to the result of `concat!`, escaped to be put in a string
+/// literal.
+fn literal_text(token: &SyntaxToken) -> Option<String> {
+    match token.kind() {
+        SyntaxKind::STRING => {
+            let string = ast::String::cast(token.clone())?;
+            if string.is_raw() {
+                let value = string.value()?;
+                Some(value.replace('\\', "\\\\").replace('"', "\\\""))
+            } else {
+                // Escapes mean the same in the merged string, so keep them as written.
+                let text = token.text().strip_prefix('"')?.strip_suffix('"')?;
+                Some(text.to_string())
+            }
+        }
+        SyntaxKind::CHAR => {
+            let text = token.text().strip_prefix('\'')?.strip_suffix('\'')?;
+            let text = match text {
+                "\\'" => "'",
+                "\"" => "\\\"",
+                _ => text,
+            };
+            Some(text.to_string())
+        }
+        // `concat!` uses the value of integers, only merge the ones written as their value.
+        SyntaxKind::INT_NUMBER if token.text().bytes().all(|it| it.is_ascii_digit()) => {
+            Some(token.text().to_string())
+        }
+        T![true] | T![false] => Some(token.text().to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn merges_all_literals() {
+        check_assist(
+            merge_concat_literals,
+            r#"
+fn f() {
+    let s = $0concat!("a", 'b', 1, true);
+}
+"#,
+            r#"
+fn f() {
+    let s = "ab1true";
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn keeps_semicolon_of_statement() {
+        check_assist(
+            merge_concat_literals,
+            r#"
+fn f() {
+    concat!$0("a", "b");
+}
+"#,
+            r#"
+fn f() {
+    "ab";
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn merges_adjacent_literals() {
+        check_assist(
+            merge_concat_literals,
+            r#"
+fn f() {
+    let s = $0concat!("a", "b", env!("VAR"), 'c', 1, -1);
+}
+"#,
+            r#"
+fn f() {
+    let s = concat!("ab", env!("VAR"), "c1", -1);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn escapes_raw_strings_and_chars() {
+        check_assist(
+            merge_concat_literals,
+            r####"
+fn f() {
+    let s = $0concat!("a\n", r#"b"c\d"#, '"', '\'', '\u{41}');
+}
+"####,
+            r#"
+fn f() {
+    let s = "a\nb\"c\\d\"'\u{41}";
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_without_adjacent_literals() {
+        check_assist_not_applicable(
+            merge_concat_literals,
+            r#"
+fn f() {
+    let s = $0concat!("a", env!("VAR"), 1u8, 0x10);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_other_macros() {
+        check_assist_not_applicable(
+            merge_concat_literals,
+            r#"
+fn f() {
+    let s = $0format!("a", "b");
+}
+"#,
+        );
+    }
+}
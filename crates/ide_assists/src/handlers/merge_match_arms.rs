@@ -3,14 +3,15 @@ use std::{collections::HashMap, iter::successors};
 use syntax::{
     algo::neighbor,
     ast::{self, AstNode, HasName},
-    Direction,
+    Direction, SyntaxKind,
 };
 
 use crate::{AssistContext, AssistId, AssistKind, Assists, TextRange};
 
 // Assist: merge_match_arms
 //
-// Merges the current match arm with the following if their bodies are identical.
+// Merges the current match arm with the following ones whose bodies are identical. Arms further
+// down are only merged if no arm in between matches any of the same values.
 //
 // ```
 // enum Action { Move { distance: u32 }, Stop }
@@ -38,25 +39,41 @@ pub(crate) fn merge_match_arms(acc: &mut Assists, ctx: &AssistContext) -> Option
     if current_arm.guard().is_some() {
         return None;
     }
+    let match_expr = current_arm.syntax().ancestors().find_map(ast::MatchExpr::cast)?;
     let current_expr = current_arm.expr()?;
     let current_text_range = current_arm.syntax().text_range();
     let current_arm_types = get_arm_types(ctx, &current_arm);
 
     // We check if the following match arms match this one. We could, but don't,
     // compare to the previous match arm as well.
-    let arms_to_merge = successors(Some(current_arm), |it| neighbor(it, Direction::Next))
-        .take_while(|arm| match arm.expr() {
+    let mut arms_to_merge = vec![current_arm.clone()];
+    let mut skipped_arms: Vec<ast::MatchArm> = Vec::new();
+    for arm in
+        successors(neighbor(&current_arm, Direction::Next), |it| neighbor(it, Direction::Next))
+    {
+        let is_identical = match arm.expr() {
             Some(expr) if arm.guard().is_none() => {
-                let same_text = expr.syntax().text() == current_expr.syntax().text();
-                if !same_text {
-                    return false;
-                }
-
-                are_same_types(&current_arm_types, arm, ctx)
+                expr.syntax().text() == current_expr.syntax().text()
+                    && are_same_types(&current_arm_types, &arm, ctx)
             }
             _ => false,
-        })
-        .collect::<Vec<_>>();
+        };
+        // Merging moves the arm up, past the skipped ones, so none of them may match any of its
+        // values.
+        let can_move_up = || match arm.pat() {
+            Some(pat) => skipped_arms.iter().all(|skipped| {
+                skipped
+                    .pat()
+                    .map_or(false, |it| !ctx.sema.match_patterns_overlap(&match_expr, &it, &pat))
+            }),
+            None => false,
+        };
+        if is_identical && can_move_up() {
+            arms_to_merge.push(arm);
+        } else {
+            skipped_arms.push(arm);
+        }
+    }
 
     if arms_to_merge.len() <= 1 {
         return None;
@@ -79,12 +96,14 @@ pub(crate) fn merge_match_arms(acc: &mut Assists, ctx: &AssistContext) -> Option
             };
 
             let arm = format!("{} => {},", pats, current_expr.syntax().text());
-
-            if let [first, .., last] = &*arms_to_merge {
-                let start = first.syntax().text_range().start();
-                let end = last.syntax().text_range().end();
-
-                edit.replace(TextRange::new(start, end), arm);
+            edit.replace(current_text_range, arm);
+            for arm in &arms_to_merge[1..] {
+                let range = arm.syntax().text_range();
+                let start = match arm.syntax().prev_sibling_or_token() {
+                    Some(ws) if ws.kind() == SyntaxKind::WHITESPACE => ws.text_range().start(),
+                    _ => range.start(),
+                };
+                edit.delete(TextRange::new(start, range.end()));
             }
         },
     )
@@ -413,8 +432,8 @@ fn func(e: MyEnum) {
     }
 
     #[test]
-    fn merge_match_arms_same_type_skip_arm_with_different_type_in_between() {
-        check_assist_not_applicable(
+    fn merge_match_arms_same_type_past_arm_with_different_type_in_between() {
+        check_assist(
             merge_match_arms,
             r#"
 enum MyEnum {
@@ -430,6 +449,68 @@ fn func(e: MyEnum) {
         MyEnum::OptionC(x) => x.classify(),
     };
 }
+"#,
+            r#"
+enum MyEnum {
+    OptionA(f32),
+    OptionB(f64),
+    OptionC(f32)
+}
+
+fn func(e: MyEnum) {
+    match e {
+        MyEnum::OptionA(x) | MyEnum::OptionC(x) => x.classify(),
+        MyEnum::OptionB(x) => x.classify(),
+    };
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn merge_match_arms_past_disjoint_arms() {
+        check_assist(
+            merge_match_arms,
+            r#"
+enum E { A(bool), B, C }
+
+fn func(e: E) -> i32 {
+    match e {
+        E::A(true) => $01,
+        E::A(false) => 2,
+        E::B => 1,
+        E::C => 3,
+    }
+}
+"#,
+            r#"
+enum E { A(bool), B, C }
+
+fn func(e: E) -> i32 {
+    match e {
+        E::A(true) | E::B => 1,
+        E::A(false) => 2,
+        E::C => 3,
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn merge_match_arms_not_past_overlapping_arm() {
+        check_assist_not_applicable(
+            merge_match_arms,
+            r#"
+enum E { A(bool), B }
+
+fn func(e: E) -> i32 {
+    match e {
+        E::A(true) => $01,
+        E::A(_) => 2,
+        E::A(false) | E::B => 1,
+    }
+}
 "#,
         );
     }
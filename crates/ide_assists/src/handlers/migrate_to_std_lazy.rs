@@ -0,0 +1,552 @@
+use hir::{Adt, HasAttrs, ModuleDef, PathResolution, Struct};
+use ide_db::{
+    base_db::FileId,
+    defs::Definition,
+    famous_defs::FamousDefs,
+    helpers::mod_path_to_ast,
+    imports::insert_use::{insert_use, ImportScope},
+    search::UsageSearchResult,
+};
+use itertools::Itertools;
+use syntax::{
+    ast::{self, edit::IndentLevel, HasName},
+    AstNode, SyntaxKind, SyntaxNode, TextRange, T,
+};
+
+use crate::{assist_context::AssistBuilder, AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: migrate_to_std_lazy
+//
+// Replaces the statics of a `lazy_static!` block, or a `once_cell::sync::Lazy` or `OnceCell`
+// static, with their `std::sync` equivalents, `LazyLock` and `OnceLock`, if the standard library
+// has them and they are stable, or their feature is enabled. Calls to `lazy_static::initialize` and
+// `Lazy::force` become `LazyLock::force`.
+//
+// ```
+// # //- /main.rs crate:main deps:std,lazy_static
+// lazy_static::lazy_static! {
+//     static ref $0NAMES: Vec<u8> = Vec::new();
+// }
+// # //- /lazy_static.rs crate:lazy_static
+// # #[macro_export]
+// # macro_rules! lazy_static { ($($tt:tt)*) => {} }
+// # //- /std.rs crate:std
+// # pub mod sync { pub struct LazyLock<T>(T); }
+// ```
+// ->
+// ```
+// use std::sync::LazyLock;
+//
+// static NAMES: LazyLock<Vec<u8>> = LazyLock::new(|| Vec::new());
+// ```
+pub(crate) fn migrate_to_std_lazy(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let lazy_static = ctx.find_node_at_offset::<ast::MacroCall>().and_then(|macro_call| {
+        let mac = ctx.sema.resolve_macro_call(&macro_call)?;
+        let db = ctx.db();
+        let is_lazy_static = mac.name(db).to_smol_str() == "lazy_static"
+            && crate_name(mac.module(db).krate(), db).as_deref() == Some("lazy_static");
+        is_lazy_static.then(|| (macro_call, mac))
+    });
+    match lazy_static {
+        Some((macro_call, mac)) => migrate_lazy_static(acc, ctx, macro_call, mac),
+        None => migrate_once_cell(acc, ctx, ctx.find_node_at_offset::<ast::Static>()?),
+    }
+}
+
+fn migrate_lazy_static(
+    acc: &mut Assists,
+    ctx: &AssistContext,
+    macro_call: ast::MacroCall,
+    mac: hir::Macro,
+) -> Option<()> {
+    let famous_defs = FamousDefs(&ctx.sema, ctx.sema.scope(macro_call.syntax()).krate());
+    let lazy_lock = famous_defs.std_sync_LazyLock()?;
+    let import = Import::new(ctx, macro_call.syntax(), lazy_lock)?;
+
+    let (statics, names) = lazy_statics(&macro_call, &import.name)?;
+    let db = ctx.db();
+    let initialize = mac.module(db).krate().root_module(db).scope(db, None).into_iter().find_map(
+        |(name, def)| match def {
+            hir::ScopeDef::ModuleDef(ModuleDef::Function(it))
+                if name.to_smol_str() == "initialize" =>
+            {
+                Some(it)
+            }
+            _ => None,
+        },
+    );
+
+    let target = macro_call.syntax().text_range();
+    acc.add(
+        AssistId("migrate_to_std_lazy", AssistKind::RefactorRewrite),
+        format!("Migrate to `std::sync::{}`", import.name),
+        target,
+        |builder| {
+            builder.replace(target, statics);
+            import.insert(ctx, builder);
+            // `lazy_static::initialize(&NAME)`
+            let calls = initialize.map(|it| Definition::Function(it).usages(&ctx.sema).all());
+            let calls = calls.into_iter().flat_map(|usages| {
+                call_sites(usages).filter(|(_, call)| {
+                    let arg = call.arg_list().and_then(|it| it.args().next());
+                    match arg {
+                        Some(ast::Expr::RefExpr(it)) => match it.expr() {
+                            Some(ast::Expr::PathExpr(it)) => it
+                                .path()
+                                .and_then(|it| it.as_single_name_ref())
+                                .map_or(false, |it| names.contains(&it.text().to_string())),
+                            _ => false,
+                        },
+                        _ => false,
+                    }
+                })
+            });
+            replace_force_calls(ctx, builder, calls.collect(), lazy_lock);
+        },
+    )
+}
+
+fn migrate_once_cell(acc: &mut Assists, ctx: &AssistContext, static_: ast::Static) -> Option<()> {
+    let db = ctx.db();
+    let path = match static_.ty()? {
+        ast::Type::PathType(it) => it.path()?,
+        _ => return None,
+    };
+    let once_cell_ty = match ctx.sema.resolve_path(&path)? {
+        PathResolution::Def(ModuleDef::Adt(Adt::Struct(it))) => it,
+        _ => return None,
+    };
+    let module = once_cell_ty.module(db);
+    let is_once_cell_sync = module.name(db).map_or(false, |it| it.to_smol_str() == "sync")
+        && crate_name(module.krate(), db).as_deref() == Some("once_cell");
+    if !is_once_cell_sync {
+        return None;
+    }
+    let famous_defs = FamousDefs(&ctx.sema, ctx.sema.scope(static_.syntax()).krate());
+    let (std_ty, is_lazy) = match once_cell_ty.name(db).to_smol_str().as_str() {
+        "Lazy" => (famous_defs.std_sync_LazyLock()?, true),
+        "OnceCell" => (famous_defs.std_sync_OnceLock()?, false),
+        _ => return None,
+    };
+    let import = Import::new(ctx, static_.syntax(), std_ty)?;
+    let def = ctx.sema.to_def(&static_)?;
+
+    // `Lazy::new(|| ..)` and `OnceCell::new()`
+    let constructor = static_.body().and_then(|body| match body {
+        ast::Expr::CallExpr(call) => match call.expr()? {
+            ast::Expr::PathExpr(it) => {
+                let qualifier = it.path()?.qualifier()?;
+                resolves_to(ctx, &qualifier, once_cell_ty).then(|| qualifier)
+            }
+            _ => None,
+        },
+        _ => None,
+    });
+
+    let target = static_.syntax().text_range();
+    acc.add(
+        AssistId("migrate_to_std_lazy", AssistKind::RefactorRewrite),
+        format!("Migrate to `std::sync::{}`", import.name),
+        target,
+        |builder| {
+            builder.replace(without_generic_args(&path), import.name.clone());
+            if let Some(constructor) = &constructor {
+                builder.replace(without_generic_args(constructor), import.name.clone());
+            }
+            import.insert(ctx, builder);
+            if !is_lazy {
+                // `OnceLock` has the same API as `OnceCell`.
+                return;
+            }
+            // `Lazy::force(&NAME)`
+            let usages = Definition::Static(def).usages(&ctx.sema).all();
+            let calls = call_sites(usages).filter(|(_, call)| {
+                let path = match call.expr() {
+                    Some(ast::Expr::PathExpr(it)) => it.path(),
+                    _ => None,
+                };
+                let is_force = |path: &ast::Path| {
+                    path.segment().and_then(|it| it.name_ref()).map_or(false, |it| {
+                        it.text() == "force"
+                            && path
+                                .qualifier()
+                                .map_or(false, |it| resolves_to(ctx, &it, once_cell_ty))
+                    })
+                };
+                path.map_or(false, |it| is_force(&it))
+            });
+            replace_force_calls(ctx, builder, calls.collect(), std_ty);
+        },
+    )
+}
+
+/// The statics declared by a `lazy_static!` call, as `LazyLock`s, and their names.
+fn lazy_statics(macro_call: &ast::MacroCall, lazy_lock: &str) -> Option<(String, Vec<String>)> {
+    let tokens: Vec<_> = macro_call
+        .token_tree()?
+        .syntax()
+        .descendants_with_tokens()
+        .filter_map(|it| it.into_token())
+        .collect();
+    // Without the delimiters, and `static ref` turned into `static`, the body parses as items.
+    let mut text = String::new();
+    let mut tokens = tokens.get(1..tokens.len().checked_sub(1)?)?.iter().peekable();
+    let mut after_static = false;
+    while let Some(token) = tokens.next() {
+        if token.kind() == T![ref] && after_static {
+            tokens.next_if(|it| it.kind() == SyntaxKind::WHITESPACE);
+            after_static = false;
+            continue;
+        }
+        if !token.kind().is_trivia() {
+            after_static = token.kind() == T![static];
+        }
+        text.push_str(token.text());
+    }
+    let parse = ast::SourceFile::parse(&text);
+    if !parse.errors().is_empty() {
+        return None;
+    }
+    let statics = parse
+        .tree()
+        .items()
+        .map(|item| match item {
+            ast::Item::Static(it) => Some(it),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    let indent = IndentLevel::from_node(macro_call.syntax());
+    let inner_indent = text.trim_start_matches('\n').len() - text.trim_start().len();
+    let reindent = |text: &str| {
+        text.replace(&format!("\n{}", " ".repeat(inner_indent)), &format!("\n{}", indent))
+    };
+
+    let mut res = String::new();
+    let mut names = Vec::new();
+    let mut prev_end = None;
+    for static_ in &statics {
+        let range = static_.syntax().text_range();
+        if let Some(prev_end) = prev_end {
+            let separator = &text[TextRange::new(prev_end, range.start())];
+            let newlines = separator.matches('\n').count().max(1);
+            res.push_str(&format!("{}{}", "\n".repeat(newlines), indent));
+        }
+        prev_end = Some(range.end());
+
+        let name = static_.name()?;
+        let prefix =
+            &text[TextRange::new(range.start(), static_.static_token()?.text_range().start())];
+        let static_text = format!(
+            "{}static {}: {lazy}<{}> = {lazy}::new(|| {});",
+            prefix,
+            name,
+            static_.ty()?.syntax(),
+            static_.body()?.syntax(),
+            lazy = lazy_lock,
+        );
+        res.push_str(&reindent(&static_text));
+        names.push(name.text().to_string());
+    }
+    Some((res, names))
+}
+
+/// The `use` of the `std::sync` type, in the scope of the static.
+struct Import {
+    scope: ImportScope,
+    path: ast::Path,
+    name: String,
+}
+
+impl Import {
+    fn new(ctx: &AssistContext, node: &SyntaxNode, strukt: Struct) -> Option<Import> {
+        let db = ctx.db();
+        let module = ctx.sema.scope(node).module()?;
+        if let Some(feature) = strukt.attrs(db).unstable_feature() {
+            if !module.krate().enables_feature(db, &feature) {
+                return None;
+            }
+        }
+        let path = module.find_use_path_prefixed(
+            db,
+            ModuleDef::from(strukt),
+            ctx.config.insert_use.prefix_kind,
+        )?;
+        let scope = ImportScope::find_insert_use_container(node, &ctx.sema)?;
+        Some(Import { scope, path: mod_path_to_ast(&path), name: strukt.name(db).to_string() })
+    }
+
+    fn insert(&self, ctx: &AssistContext, builder: &mut AssistBuilder) {
+        let scope = match self.scope.clone() {
+            ImportScope::File(it) => ImportScope::File(builder.make_mut(it)),
+            ImportScope::Module(it) => ImportScope::Module(builder.make_mut(it)),
+            ImportScope::Block(it) => ImportScope::Block(builder.make_mut(it)),
+        };
+        insert_use(&scope, self.path.clone(), &ctx.config.insert_use);
+    }
+}
+
+/// The calls the usages are an argument of, like `Lazy::force(&NAME)`.
+fn call_sites(usages: UsageSearchResult) -> impl Iterator<Item = (FileId, ast::CallExpr)> {
+    usages.into_iter().flat_map(|(file_id, references)| {
+        references.into_iter().filter_map(move |reference| {
+            let name_ref = reference.name.as_name_ref()?.clone();
+            let call = name_ref.syntax().ancestors().find_map(ast::CallExpr::cast)?;
+            Some((file_id, call))
+        })
+    })
+}
+
+/// Replaces the callee of calls like `lazy_static::initialize(&NAME)` with `LazyLock::force`,
+/// spelled out in full as the import is only added to the file of the static.
+fn replace_force_calls(
+    ctx: &AssistContext,
+    builder: &mut AssistBuilder,
+    calls: Vec<(FileId, ast::CallExpr)>,
+    lazy_lock: Struct,
+) {
+    let db = ctx.db();
+    let calls = calls
+        .into_iter()
+        .sorted_by_key(|(file_id, call)| {
+            (*file_id != ctx.file_id(), *file_id, call.syntax().text_range().start())
+        })
+        .dedup_by(|(_, a), (_, b)| a == b);
+    for (file_id, call) in calls {
+        let callee = match call.expr() {
+            Some(it) => it,
+            None => continue,
+        };
+        let module = match ctx.sema.scope(call.syntax()).module() {
+            Some(it) => it,
+            None => continue,
+        };
+        let path = module.find_use_path_prefixed(
+            db,
+            ModuleDef::from(lazy_lock),
+            ctx.config.insert_use.prefix_kind,
+        );
+        if let Some(path) = path {
+            builder.edit_file(file_id);
+            builder.replace(
+                callee.syntax().text_range(),
+                format!("{}::force", mod_path_to_ast(&path)),
+            );
+        }
+    }
+}
+
+fn resolves_to(ctx: &AssistContext, path: &ast::Path, strukt: Struct) -> bool {
+    matches!(
+        ctx.sema.resolve_path(path),
+        Some(PathResolution::Def(ModuleDef::Adt(Adt::Struct(it)))) if it == strukt
+    )
+}
+
+/// The range of `once_cell::sync::Lazy` in `once_cell::sync::Lazy<T>`.
+fn without_generic_args(path: &ast::Path) -> TextRange {
+    let range = path.syntax().text_range();
+    match path.segment().and_then(|it| it.generic_arg_list()) {
+        Some(generic_args) => {
+            TextRange::new(range.start(), generic_args.syntax().text_range().start())
+        }
+        None => range,
+    }
+}
+
+fn crate_name(krate: hir::Crate, db: &ide_db::RootDatabase) -> Option<String> {
+    krate.display_name(db).map(|it| it.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    const LAZY_STATIC: &str = r#"//- /lazy_static.rs crate:lazy_static
+#[macro_export]
+macro_rules! lazy_static { ($($tt:tt)*) => {} }
+pub fn initialize<T>(_: &T) {}
+"#;
+
+    const ONCE_CELL: &str = r#"//- /once_cell.rs crate:once_cell
+pub mod sync {
+    pub struct Lazy<T, F = fn() -> T>(T, F);
+    impl<T, F> Lazy<T, F> {
+        pub const fn new(f: F) -> Lazy<T, F> { loop {} }
+        pub fn force(this: &Lazy<T, F>) -> &T { loop {} }
+    }
+    pub struct OnceCell<T>(T);
+    impl<T> OnceCell<T> {
+        pub const fn new() -> OnceCell<T> { loop {} }
+    }
+}
+"#;
+
+    const STD: &str = r#"//- /std.rs crate:std
+pub mod sync {
+    pub struct LazyLock<T, F = fn() -> T>(T, F);
+    pub struct OnceLock<T>(T);
+}
+"#;
+
+    fn fixture(main: &str, deps: &[&str]) -> String {
+        format!("{}{}", main, deps.concat())
+    }
+
+    #[test]
+    fn migrates_lazy_static() {
+        check_assist(
+            migrate_to_std_lazy,
+            &fixture(
+                r#"
+//- /main.rs crate:main deps:std,lazy_static
+use lazy_static::lazy_static;
+
+lazy_static! {
+    /// The names.
+    pub static ref $0NAMES: Vec<&'static str> = Vec::new();
+
+    static ref COUNT: usize = {
+        let x = 1;
+        x + 1
+    };
+}
+
+fn main() {
+    lazy_static::initialize(&NAMES);
+}
+"#,
+                &[LAZY_STATIC, STD],
+            ),
+            r#"
+use std::sync::LazyLock;
+
+use lazy_static::lazy_static;
+
+/// The names.
+pub static NAMES: LazyLock<Vec<&'static str>> = LazyLock::new(|| Vec::new());
+
+static COUNT: LazyLock<usize> = LazyLock::new(|| {
+    let x = 1;
+    x + 1
+});
+
+fn main() {
+    std::sync::LazyLock::force(&NAMES);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn migrates_once_cell_lazy() {
+        check_assist(
+            migrate_to_std_lazy,
+            &fixture(
+                r#"
+//- /main.rs crate:main deps:std,once_cell
+use once_cell::sync::Lazy;
+
+static $0CONFIG: Lazy<u32> = Lazy::new(|| 92);
+
+fn main() {
+    let config = Lazy::force(&CONFIG);
+}
+"#,
+                &[ONCE_CELL, STD],
+            ),
+            r#"
+use std::sync::LazyLock;
+
+use once_cell::sync::Lazy;
+
+static CONFIG: LazyLock<u32> = LazyLock::new(|| 92);
+
+fn main() {
+    let config = std::sync::LazyLock::force(&CONFIG);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn migrates_once_cell() {
+        check_assist(
+            migrate_to_std_lazy,
+            &fixture(
+                r#"
+//- /main.rs crate:main deps:std,once_cell
+static $0CELL: once_cell::sync::OnceCell<u32> = once_cell::sync::OnceCell::new();
+"#,
+                &[ONCE_CELL, STD],
+            ),
+            r#"
+use std::sync::OnceLock;
+
+static CELL: OnceLock<u32> = OnceLock::new();
+"#,
+        );
+    }
+
+    const UNSTABLE_STD: &str = r#"//- /std.rs crate:std
+pub mod sync {
+    #[unstable(feature = "lazy_cell", issue = "109736")]
+    pub struct LazyLock<T, F = fn() -> T>(T, F);
+}
+"#;
+
+    #[test]
+    fn migrates_to_unstable_std_with_feature() {
+        check_assist(
+            migrate_to_std_lazy,
+            &fixture(
+                r#"
+//- /main.rs crate:main deps:std,once_cell
+#![feature(lazy_cell)]
+
+static $0CONFIG: once_cell::sync::Lazy<u32> = once_cell::sync::Lazy::new(|| 92);
+"#,
+                &[ONCE_CELL, UNSTABLE_STD],
+            ),
+            r#"
+#![feature(lazy_cell)]
+
+use std::sync::LazyLock;
+
+static CONFIG: LazyLock<u32> = LazyLock::new(|| 92);
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_unstable_std() {
+        check_assist_not_applicable(
+            migrate_to_std_lazy,
+            &fixture(
+                r#"
+//- /main.rs crate:main deps:std,once_cell
+#![feature(once_cell)]
+
+static $0CONFIG: once_cell::sync::Lazy<u32> = once_cell::sync::Lazy::new(|| 92);
+"#,
+                &[ONCE_CELL, UNSTABLE_STD],
+            ),
+        );
+    }
+
+    #[test]
+    fn not_applicable_without_std_support() {
+        check_assist_not_applicable(
+            migrate_to_std_lazy,
+            &fixture(
+                r#"
+//- /main.rs crate:main deps:std,once_cell
+static $0CONFIG: once_cell::sync::Lazy<u32> = once_cell::sync::Lazy::new(|| 92);
+"#,
+                &[ONCE_CELL, "//- /std.rs crate:std\npub mod sync {}\n"],
+            ),
+        );
+    }
+}
@@ -0,0 +1,397 @@
+use hir::{HasSource, HasVisibility, InFile, Module, ModuleDef, Semantics, Visibility};
+use ide_db::{base_db::FileId, defs::Definition, RootDatabase};
+use syntax::{
+    ast::{self, HasVisibility as _},
+    match_ast, AstNode, SyntaxKind, SyntaxNode,
+};
+
+use crate::{AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: minimize_visibility
+//
+// Narrows the visibility of the items declared in a module to what their uses need.
+//
+// ```
+// mod $0m {
+//     pub fn helper() {}
+//     pub fn frobnicate() { helper() }
+// }
+// fn main() {
+//     m::frobnicate();
+// }
+// ```
+// ->
+// ```
+// mod m {
+//     fn helper() {}
+//     pub(crate) fn frobnicate() { helper() }
+// }
+// fn main() {
+//     m::frobnicate();
+// }
+// ```
+pub(crate) fn minimize_visibility(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let name: ast::Name = ctx.find_node_at_offset()?;
+    let module_ast = ast::Module::cast(name.syntax().parent()?)?;
+    let module = ctx.sema.to_def(&module_ast)?;
+
+    // Only the candidates are collected here, searching for their uses is left
+    // to when the assist is picked.
+    let mut candidates: Vec<(ModuleDef, FileId, ast::Visibility, Scope)> = Vec::new();
+    for def in module.declarations(ctx.db()) {
+        let (file_id, vis) = match source_visibility(ctx.db(), def) {
+            Some(it) => it,
+            None => continue,
+        };
+        let current = match Scope::from_visibility(&vis) {
+            Some(it) => it,
+            None => continue,
+        };
+        if current != Scope::Private && !is_exported(ctx.db(), module, def) {
+            candidates.push((def, file_id, vis, current));
+        }
+    }
+    // The declarations of a module all live in its own file.
+    let file_id = candidates.first()?.1;
+
+    acc.add(
+        AssistId("minimize_visibility", AssistKind::RefactorRewrite),
+        "Minimize visibility of module items",
+        name.syntax().text_range(),
+        |builder| {
+            builder.edit_file(file_id);
+            for (def, _, vis, current) in candidates {
+                let needed = match needed_scope(ctx, module, def) {
+                    Some(it) if it < current => it,
+                    _ => continue,
+                };
+                let range = match needed {
+                    // Also remove the space between the visibility and the keyword.
+                    Scope::Private => match vis.syntax().next_sibling_or_token() {
+                        Some(ws) if ws.kind() == SyntaxKind::WHITESPACE => {
+                            vis.syntax().text_range().cover(ws.text_range())
+                        }
+                        _ => vis.syntax().text_range(),
+                    },
+                    _ => vis.syntax().text_range(),
+                };
+                builder.replace(range, needed.text());
+            }
+        },
+    )
+}
+
+/// The visibilities the assist knows how to compare, narrowest first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Scope {
+    Private,
+    Super,
+    Crate,
+    Public,
+}
+
+impl Scope {
+    /// `pub(self)` and `pub(in path)` are left alone.
+    fn from_visibility(vis: &ast::Visibility) -> Option<Scope> {
+        let text = vis.syntax().text().to_string().replace(char::is_whitespace, "");
+        match text.as_str() {
+            "pub" => Some(Scope::Public),
+            "pub(crate)" => Some(Scope::Crate),
+            "pub(super)" => Some(Scope::Super),
+            _ => None,
+        }
+    }
+
+    fn text(self) -> &'static str {
+        match self {
+            Scope::Private => "",
+            Scope::Super => "pub(super)",
+            Scope::Crate => "pub(crate)",
+            Scope::Public => "pub",
+        }
+    }
+}
+
+/// The narrowest visibility `def`, declared in `module`, needs for all its references to
+/// resolve, and to be at least as visible as the items whose signatures mention it. Returns
+/// `None` if the item is reachable from other crates, which may use it without the workspace
+/// knowing.
+fn needed_scope(ctx: &AssistContext, module: Module, def: ModuleDef) -> Option<Scope> {
+    let db = ctx.db();
+    if is_exported(db, module, def) {
+        return None;
+    }
+    let parent = module.parent(db);
+
+    let mut defs = vec![Definition::from(def)];
+    if let ModuleDef::Trait(trait_) = def {
+        // Calling a method of the trait needs it in scope, without naming it.
+        defs.extend(trait_.items(db).into_iter().map(Definition::from));
+    }
+    let mut needed = Scope::Private;
+    for def in defs {
+        for (_, references) in def.usages(&ctx.sema).all() {
+            for reference in references {
+                let node = reference.name.syntax();
+                let from = ctx.sema.scope(node).module()?;
+                let mut scope = scope_to_reach(db, module, from);
+                // A private type can't be part of the interface of a more visible item.
+                match signature_visibility(&ctx.sema, node) {
+                    Some(Visibility::Public) => scope = Scope::Public,
+                    Some(Visibility::Module(it)) => {
+                        scope = scope.max(scope_to_reach(db, module, it.into()))
+                    }
+                    None => (),
+                }
+                // An item can't be re-exported further than it is visible.
+                if let Some(vis) =
+                    node.ancestors().find_map(ast::Use::cast).and_then(|it| it.visibility())
+                {
+                    let reexport = match Scope::from_visibility(&vis) {
+                        Some(Scope::Public) => Scope::Public,
+                        _ => Scope::Crate,
+                    };
+                    scope = scope.max(reexport);
+                }
+                needed = needed.max(scope);
+            }
+        }
+    }
+    // `pub(super)` in a child of the crate root reads better as `pub(crate)`.
+    if needed == Scope::Super && parent.map_or(true, |it| it.is_crate_root(db)) {
+        needed = Scope::Crate;
+    }
+    Some(needed)
+}
+
+/// The visibility an item of `module` needs to be visible in `from`.
+fn scope_to_reach(db: &RootDatabase, module: Module, from: Module) -> Scope {
+    if is_within(db, from, module) {
+        Scope::Private
+    } else if module.parent(db).map_or(false, |parent| is_within(db, from, parent)) {
+        Scope::Super
+    } else if from.krate() == module.krate() {
+        Scope::Crate
+    } else {
+        Scope::Public
+    }
+}
+
+/// The visibility of the item or field whose signature `node` is part of, if it isn't in a
+/// body.
+fn signature_visibility(sema: &Semantics<RootDatabase>, node: &SyntaxNode) -> Option<Visibility> {
+    let db = sema.db;
+    for node in node.ancestors() {
+        if ast::Expr::can_cast(node.kind())
+            || ast::Impl::can_cast(node.kind())
+            || ast::Module::can_cast(node.kind())
+        {
+            return None;
+        }
+        let vis = match_ast! {
+            match node {
+                // The fields of a variant are as visible as the enum.
+                ast::RecordField(it) => match it.syntax().ancestors().nth(2) {
+                    Some(owner) if ast::Variant::can_cast(owner.kind()) => continue,
+                    _ => sema.to_def(&it)?.visibility(db),
+                },
+                ast::TupleField(it) => match it.syntax().ancestors().nth(2) {
+                    Some(owner) if ast::Variant::can_cast(owner.kind()) => continue,
+                    _ => sema.to_def(&it)?.visibility(db),
+                },
+                ast::Fn(it) => sema.to_def(&it)?.visibility(db),
+                ast::Adt(it) => sema.to_def(&it)?.visibility(db),
+                ast::Trait(it) => sema.to_def(&it)?.visibility(db),
+                ast::TypeAlias(it) => sema.to_def(&it)?.visibility(db),
+                ast::Const(it) => sema.to_def(&it)?.visibility(db),
+                ast::Static(it) => sema.to_def(&it)?.visibility(db),
+                _ => continue,
+            }
+        };
+        return Some(vis);
+    }
+    None
+}
+
+fn is_within(db: &RootDatabase, module: Module, ancestor: Module) -> bool {
+    module.path_to_root(db).contains(&ancestor)
+}
+
+/// Whether `def` and all the modules leading to it from the crate root are `pub`.
+fn is_exported(db: &RootDatabase, module: Module, def: ModuleDef) -> bool {
+    def.visibility(db) == Visibility::Public
+        && module
+            .path_to_root(db)
+            .into_iter()
+            .filter(|it| !it.is_crate_root(db))
+            .all(|it| it.visibility(db) == Visibility::Public)
+}
+
+/// The explicit visibility of `def` as written in its file.
+fn source_visibility(db: &RootDatabase, def: ModuleDef) -> Option<(FileId, ast::Visibility)> {
+    fn visibility<N: AstNode + ast::HasVisibility>(
+        db: &RootDatabase,
+        source: InFile<N>,
+    ) -> Option<(FileId, ast::Visibility)> {
+        if source.file_id.is_macro() {
+            return None;
+        }
+        Some((source.file_id.original_file(db), source.value.visibility()?))
+    }
+
+    match def {
+        ModuleDef::Module(it) => visibility(db, it.declaration_source(db)?),
+        ModuleDef::Function(it) => visibility(db, it.source(db)?),
+        ModuleDef::Adt(it) => visibility(db, it.source(db)?),
+        ModuleDef::Const(it) => visibility(db, it.source(db)?),
+        ModuleDef::Static(it) => visibility(db, it.source(db)?),
+        ModuleDef::Trait(it) => visibility(db, it.source(db)?),
+        ModuleDef::TypeAlias(it) => visibility(db, it.source(db)?),
+        ModuleDef::Macro(_) | ModuleDef::Variant(_) | ModuleDef::BuiltinType(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn narrows_to_uses() {
+        check_assist(
+            minimize_visibility,
+            r#"
+mod outer {
+    mod $0inner {
+        pub fn unused() {}
+        pub(crate) fn in_parent() {}
+        pub fn in_root() {}
+        pub struct S;
+        pub(super) const C: u8 = 0;
+    }
+    fn f() -> inner::S {
+        inner::in_parent();
+        inner::S
+    }
+}
+fn main() {
+    outer::inner::in_root();
+}
+"#,
+            r#"
+mod outer {
+    mod inner {
+        fn unused() {}
+        pub(super) fn in_parent() {}
+        pub(crate) fn in_root() {}
+        pub(super) struct S;
+        const C: u8 = 0;
+    }
+    fn f() -> inner::S {
+        inner::in_parent();
+        inner::S
+    }
+}
+fn main() {
+    outer::inner::in_root();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn keeps_reexported_items() {
+        check_assist(
+            minimize_visibility,
+            r#"
+mod $0m {
+    pub fn reexported() {}
+    pub fn crate_reexported() {}
+    pub fn unused() {}
+}
+pub use m::reexported;
+pub(crate) use m::crate_reexported;
+"#,
+            r#"
+mod m {
+    pub fn reexported() {}
+    pub(crate) fn crate_reexported() {}
+    fn unused() {}
+}
+pub use m::reexported;
+pub(crate) use m::crate_reexported;
+"#,
+        );
+    }
+
+    #[test]
+    fn keeps_types_in_signatures_of_more_visible_items() {
+        check_assist(
+            minimize_visibility,
+            r#"
+mod $0m {
+    pub struct Foo;
+    pub struct Bar;
+    pub fn make() -> Foo { Foo }
+    pub(crate) struct Wrapper(pub(super) Bar, Foo);
+}
+pub use m::make;
+"#,
+            r#"
+mod m {
+    pub struct Foo;
+    pub(crate) struct Bar;
+    pub fn make() -> Foo { Foo }
+    struct Wrapper(pub(super) Bar, Foo);
+}
+pub use m::make;
+"#,
+        );
+    }
+
+    #[test]
+    fn keeps_traits_with_used_methods() {
+        check_assist(
+            minimize_visibility,
+            r#"
+mod $0m {
+    pub trait Tr {
+        fn method(&self) {}
+    }
+    impl Tr for () {}
+    pub fn unused() {}
+}
+use m::*;
+fn main() {
+    ().method();
+}
+"#,
+            r#"
+mod m {
+    pub(crate) trait Tr {
+        fn method(&self) {}
+    }
+    impl Tr for () {}
+    fn unused() {}
+}
+use m::*;
+fn main() {
+    ().method();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_exported_items() {
+        check_assist_not_applicable(
+            minimize_visibility,
+            r#"
+pub mod $0m {
+    pub fn f() {}
+}
+"#,
+        );
+    }
+}
@@ -0,0 +1,247 @@
+use hir::ModuleSource;
+use syntax::{
+    ast::{self, edit::AstNodeEdit, edit::IndentLevel, HasAttrs},
+    AstNode, SyntaxKind, TextRange,
+};
+
+use crate::{AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: move_module_from_file
+//
+// Moves the contents of a module's file into an inline module, deleting the file.
+//
+// ```
+// //- /main.rs
+// mod $0foo;
+// //- /foo.rs
+// fn t() {}
+// ```
+// ->
+// ```
+// mod foo {
+//     fn t() {}
+// }
+// ```
+pub(crate) fn move_module_from_file(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let module_ast = ctx.find_node_at_offset::<ast::Module>()?;
+    let semicolon = module_ast.semicolon_token()?;
+
+    let module_def = ctx.sema.to_def(&module_ast)?;
+    let src = module_def.definition_source(ctx.db());
+    let module_file_id = src.file_id.original_file(ctx.db());
+    let source_file = match src.value {
+        ModuleSource::SourceFile(it) => it,
+        _ => return None,
+    };
+
+    // A `#[path]` file resolves its own `mod foo;` declarations relative to its directory, which
+    // an inline module without the attribute would not do.
+    let path_attr = module_ast.attrs().find(|attr| attr.simple_name().as_deref() == Some("path"));
+    if path_attr.is_some()
+        && source_file
+            .syntax()
+            .descendants()
+            .filter_map(ast::Module::cast)
+            .any(|it| it.semicolon_token().is_some())
+    {
+        cov_mark::hit!(path_attr_with_outline_children);
+        return None;
+    }
+
+    let target =
+        TextRange::new(module_ast.syntax().text_range().start(), semicolon.text_range().end());
+    acc.add(
+        AssistId("move_module_from_file", AssistKind::RefactorInline),
+        "Inline module file contents",
+        target,
+        |builder| {
+            if let Some(attr) = path_attr {
+                let mut range = attr.syntax().text_range();
+                if let Some(ws) = attr
+                    .syntax()
+                    .next_sibling_or_token()
+                    .and_then(|it| it.into_token())
+                    .filter(|it| it.kind() == SyntaxKind::WHITESPACE)
+                {
+                    range = range.cover(ws.text_range());
+                }
+                builder.delete(range);
+            }
+
+            let indent = IndentLevel::from_node(module_ast.syntax());
+            let items = source_file.indent(indent + 1).to_string();
+            let items = items.trim();
+            let body = if items.is_empty() {
+                " {}".to_string()
+            } else {
+                format!(" {{\n{}{}\n{}}}", indent + 1, items, indent)
+            };
+            builder.replace(semicolon.text_range(), body);
+            builder.delete_file(module_file_id);
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn inline_from_root() {
+        check_assist(
+            move_module_from_file,
+            r#"
+//- /main.rs
+mod $0tests;
+//- /tests.rs
+#[test] fn t() {}
+"#,
+            r#"
+//- /main.rs
+mod tests {
+    #[test] fn t() {}
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn inline_from_mod_rs() {
+        check_assist(
+            move_module_from_file,
+            r#"
+//- /main.rs
+mod submodule;
+//- /submodule/mod.rs
+mod inner$0;
+fn g() {}
+//- /submodule/inner.rs
+fn f() {}
+
+fn h() {}
+"#,
+            r#"
+//- /submodule/mod.rs
+mod inner {
+    fn f() {}
+
+    fn h() {}
+}
+fn g() {}
+"#,
+        );
+    }
+
+    #[test]
+    fn inline_nested() {
+        check_assist(
+            move_module_from_file,
+            r#"
+//- /lib.rs
+mod foo;
+//- /foo.rs
+mod bar {
+    mod baz$0;
+}
+//- /foo/bar/baz.rs
+fn f() {
+    ()
+}
+"#,
+            r#"
+//- /foo.rs
+mod bar {
+    mod baz {
+        fn f() {
+            ()
+        }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn keep_inner_attributes() {
+        check_assist(
+            move_module_from_file,
+            r#"
+//- /main.rs
+pub(crate) mod $0tests;
+//- /tests.rs
+//! Tests.
+#![allow(dead_code)]
+
+fn t() {}
+"#,
+            r#"
+//- /main.rs
+pub(crate) mod tests {
+    //! Tests.
+    #![allow(dead_code)]
+
+    fn t() {}
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn inline_empty_file() {
+        check_assist(
+            move_module_from_file,
+            r#"
+//- /main.rs
+mod $0empty;
+//- /empty.rs
+"#,
+            r#"
+//- /main.rs
+mod empty {}
+"#,
+        );
+    }
+
+    #[test]
+    fn removes_path_attr() {
+        check_assist(
+            move_module_from_file,
+            r#"
+//- /main.rs
+#[path = "other.rs"]
+mod $0foo;
+//- /other.rs
+fn f() {}
+"#,
+            r#"
+//- /main.rs
+mod foo {
+    fn f() {}
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_with_path_attr_and_outline_children() {
+        cov_mark::check!(path_attr_with_outline_children);
+        check_assist_not_applicable(
+            move_module_from_file,
+            r#"
+//- /main.rs
+#[path = "other.rs"]
+mod $0foo;
+//- /other.rs
+mod child;
+//- /child.rs
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_inline_module() {
+        check_assist_not_applicable(move_module_from_file, r#"mod $0m { fn f() {} }"#);
+    }
+}
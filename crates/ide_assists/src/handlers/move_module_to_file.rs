@@ -2,10 +2,9 @@ use std::iter;
 
 use ast::edit::IndentLevel;
 use ide_db::base_db::AnchoredPathBuf;
-use itertools::Itertools;
 use stdx::format_to;
 use syntax::{
-    ast::{self, edit::AstNodeEdit, HasName},
+    ast::{self, edit::AstNodeEdit, HasAttrs, HasName},
     AstNode, TextRange,
 };
 
@@ -43,26 +42,44 @@ pub(crate) fn move_module_to_file(acc: &mut Assists, ctx: &AssistContext) -> Opt
     let module_def = ctx.sema.to_def(&outermost_mod_decl)?;
     let parent_module = module_def.parent(ctx.db())?;
 
+    let path = {
+        // Mirrors `ModDir` from name resolution: the directory of the current file's
+        // children, adjusted for each enclosing inline module and its `#[path]`.
+        let mut dir = String::new();
+        let mut relative_to_parent = false;
+        match parent_module.name(ctx.db()) {
+            Some(name) if !parent_module.is_mod_rs(ctx.db()) => {
+                format_to!(dir, "{}/", name);
+                relative_to_parent = true;
+            }
+            _ => (),
+        }
+        let mut ancestors =
+            iter::successors(module_ast.parent(), |module| module.parent()).collect::<Vec<_>>();
+        ancestors.reverse();
+        for ancestor in ancestors {
+            match path_attr(&ancestor) {
+                Some(attr_path) => {
+                    dir = join_attr(&dir, &attr_path, relative_to_parent);
+                    if !(dir.is_empty() || dir.ends_with('/')) {
+                        dir.push('/');
+                    }
+                }
+                None => format_to!(dir, "{}/", ancestor.name()?),
+            }
+            relative_to_parent = false;
+        }
+        let file = match path_attr(&module_ast) {
+            Some(attr_path) => join_attr(&dir, &attr_path, relative_to_parent),
+            None => format!("{}{}.rs", dir, module_name),
+        };
+        format!("./{}", file)
+    };
     acc.add(
         AssistId("move_module_to_file", AssistKind::RefactorExtract),
         "Extract module to file",
         target,
         |builder| {
-            let path = {
-                let mut buf = String::from("./");
-                match parent_module.name(ctx.db()) {
-                    Some(name) if !parent_module.is_mod_rs(ctx.db()) => {
-                        format_to!(buf, "{}/", name)
-                    }
-                    _ => (),
-                }
-                let segments = iter::successors(Some(module_ast.clone()), |module| module.parent())
-                    .filter_map(|it| it.name())
-                    .collect::<Vec<_>>();
-                format_to!(buf, "{}", segments.into_iter().rev().format("/"));
-                format_to!(buf, ".rs");
-                buf
-            };
             let contents = {
                 let items = module_items.dedent(IndentLevel(1)).to_string();
                 let mut items =
@@ -92,6 +109,27 @@ pub(crate) fn move_module_to_file(acc: &mut Assists, ctx: &AssistContext) -> Opt
     )
 }
 
+fn path_attr(module: &ast::Module) -> Option<String> {
+    let attr = module.attrs().find(|attr| attr.simple_name().as_deref() == Some("path"))?;
+    match attr.expr()? {
+        ast::Expr::Literal(lit) => match lit.kind() {
+            ast::LiteralKind::String(it) => Some(it.value()?.replace('\\', "/")),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn join_attr(dir: &str, attr_path: &str, relative_to_parent: bool) -> String {
+    let base = if relative_to_parent {
+        let dir = dir.trim_end_matches('/');
+        dir.rfind('/').map_or("", |idx| &dir[..idx + 1])
+    } else {
+        dir
+    };
+    format!("{}{}", base, attr_path.trim_start_matches("./"))
+}
+
 #[cfg(test)]
 mod tests {
     use crate::tests::{check_assist, check_assist_not_applicable};
@@ -248,6 +286,70 @@ mod bar {
     }
 }
 //- /foo/bar/baz/qux.rs
+"#,
+        );
+    }
+
+    #[test]
+    fn keep_inner_attributes() {
+        check_assist(
+            move_module_to_file,
+            r#"
+mod $0tests {
+    #![allow(dead_code)]
+    fn t() {}
+}
+"#,
+            r#"
+//- /main.rs
+mod tests;
+//- /tests.rs
+#![allow(dead_code)]
+fn t() {}
+"#,
+        );
+    }
+
+    #[test]
+    fn extract_with_path_attr() {
+        check_assist(
+            move_module_to_file,
+            r#"
+#[path = "other.rs"]
+mod $0tests {
+    fn t() {}
+}
+"#,
+            r#"
+//- /main.rs
+#[path = "other.rs"]
+mod tests;
+//- /other.rs
+fn t() {}
+"#,
+        );
+    }
+
+    #[test]
+    fn extract_nested_in_path_attr_module() {
+        check_assist(
+            move_module_to_file,
+            r#"
+//- /lib.rs
+mod foo;
+//- /foo.rs
+#[path = "elsewhere"]
+mod bar {
+    mod baz$0 {}
+}
+"#,
+            r#"
+//- /foo.rs
+#[path = "elsewhere"]
+mod bar {
+    mod baz;
+}
+//- /elsewhere/baz.rs
 "#,
         );
     }
@@ -0,0 +1,364 @@
+use hir::{ModuleDef, PathResolution};
+use syntax::{
+    ast::{
+        self, edit_in_place::GenericParamsOwnerEdit, make, AstNode, HasGenericParams, HasName,
+        HasTypeBounds,
+    },
+    match_ast,
+    ted::{self, Position},
+    SyntaxNode, T,
+};
+
+use crate::{AssistContext, AssistId, AssistKind, Assists, BoundsPlacement};
+
+// Assist: normalize_bounds
+//
+// Merges the bounds of each type parameter, drops the repeated ones and those implied by the
+// supertraits of the others, and puts them in the where clause, or next to the parameters
+// depending on the `assist.boundsPlacement` setting.
+//
+// ```
+// trait Shape {}
+// trait Circle: Shape {}
+// fn draw<$0T: Circle + Clone>(t: T) where T: Shape + Clone {}
+// ```
+// ->
+// ```
+// trait Shape {}
+// trait Circle: Shape {}
+// fn draw<T>(t: T) where T: Circle + Clone {}
+// ```
+pub(crate) fn normalize_bounds(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let owner = match ctx.find_node_at_offset::<ast::GenericParamList>() {
+        Some(it) => it.syntax().parent()?,
+        None => ctx.find_node_at_offset::<ast::WhereClause>()?.syntax().parent()?,
+    };
+    let (param_list, where_clause) = generics_of(&owner)?;
+    let placement = ctx.config.bounds_placement;
+
+    let type_params: Vec<ast::TypeParam> = param_list
+        .type_or_const_params()
+        .filter_map(|it| match it {
+            ast::TypeOrConstParam::Type(it) => Some(it),
+            ast::TypeOrConstParam::Const(_) => None,
+        })
+        .collect();
+    let names: Vec<String> =
+        type_params.iter().map(|it| it.name().map_or(String::new(), |it| it.to_string())).collect();
+    let preds: Vec<ast::WherePred> = where_clause.iter().flat_map(|it| it.predicates()).collect();
+    let param_pred_idx = |pred: &ast::WherePred| -> Option<usize> {
+        if pred.for_token().is_some() || pred.lifetime().is_some() {
+            return None;
+        }
+        let path = match pred.ty()? {
+            ast::Type::PathType(it) => it.path()?,
+            _ => return None,
+        };
+        if path.qualifier().is_some() || path.segment()?.generic_arg_list().is_some() {
+            return None;
+        }
+        let name = path.segment()?.name_ref()?.to_string();
+        names.iter().position(|it| *it == name)
+    };
+
+    let mut inline_bounds = Vec::new();
+    let mut where_bounds = Vec::new();
+    let mut normalized = Vec::new();
+    for (idx, param) in type_params.iter().enumerate() {
+        let inline: Vec<ast::TypeBound> =
+            param.type_bound_list().into_iter().flat_map(|it| it.bounds()).collect();
+        let in_where: Vec<ast::TypeBound> = preds
+            .iter()
+            .filter(|pred| param_pred_idx(pred) == Some(idx))
+            .flat_map(|pred| pred.type_bound_list().into_iter().flat_map(|it| it.bounds()))
+            .collect();
+        normalized.push(normalize(ctx, inline.iter().chain(&in_where).cloned().collect()));
+        inline_bounds.push(inline);
+        where_bounds.push(in_where);
+    }
+    let param_preds_count = preds.iter().filter(|it| param_pred_idx(it).is_some()).count();
+
+    let texts = |bounds: &[ast::TypeBound]| bounds.iter().map(bound_text).collect::<Vec<_>>();
+    let changed = match placement {
+        BoundsPlacement::WhereClause => {
+            inline_bounds.iter().any(|it| !it.is_empty())
+                || param_preds_count != normalized.iter().filter(|it| !it.is_empty()).count()
+                || where_bounds.iter().zip(&normalized).any(|(a, b)| texts(a) != texts(b))
+        }
+        BoundsPlacement::Inline => {
+            param_preds_count != 0
+                || inline_bounds.iter().zip(&normalized).any(|(a, b)| texts(a) != texts(b))
+        }
+    };
+    if !changed {
+        return None;
+    }
+
+    let other_preds: Vec<ast::WherePred> =
+        preds.iter().filter(|it| param_pred_idx(it).is_none()).cloned().collect();
+    let target = owner.text_range();
+    acc.add(
+        AssistId("normalize_bounds", AssistKind::RefactorRewrite),
+        "Normalize bounds",
+        target,
+        |edit| {
+            let owner = edit.make_syntax_mut(owner);
+            let (param_list, where_clause) = match generics_of(&owner) {
+                Some(it) => it,
+                None => return,
+            };
+            let type_params = param_list.type_or_const_params().filter_map(|it| match it {
+                ast::TypeOrConstParam::Type(it) => Some(it),
+                ast::TypeOrConstParam::Const(_) => None,
+            });
+            let mut new_preds = Vec::new();
+            for (param, bounds) in type_params.zip(normalized) {
+                if let Some(list) = param.type_bound_list() {
+                    list.remove();
+                }
+                if bounds.is_empty() {
+                    continue;
+                }
+                let name = match param.name() {
+                    Some(it) => it,
+                    None => continue,
+                };
+                let pred = make::where_pred(make::ext::ident_path(&name.to_string()), bounds);
+                match placement {
+                    BoundsPlacement::WhereClause => new_preds.push(pred),
+                    BoundsPlacement::Inline => {
+                        let list = match pred.type_bound_list() {
+                            Some(it) => it.syntax().clone_subtree().clone_for_update(),
+                            None => continue,
+                        };
+                        ted::insert_all_raw(
+                            Position::after(name.syntax()),
+                            vec![
+                                make::token(T![:]).into(),
+                                make::tokens::single_space().into(),
+                                list.into(),
+                            ],
+                        );
+                    }
+                }
+            }
+            new_preds.extend(other_preds);
+
+            match where_clause {
+                Some(where_clause) if new_preds.is_empty() => where_clause.remove(),
+                Some(where_clause) => {
+                    if param_preds_count != 0 || placement == BoundsPlacement::WhereClause {
+                        let new_clause = make::where_clause(new_preds).clone_for_update();
+                        ted::replace(where_clause.syntax(), new_clause.syntax());
+                    }
+                }
+                None if new_preds.is_empty() => (),
+                None => {
+                    let where_clause = match_ast! {
+                        match owner {
+                            ast::Fn(it) => it.get_or_create_where_clause(),
+                            ast::Trait(it) => it.get_or_create_where_clause(),
+                            ast::Impl(it) => it.get_or_create_where_clause(),
+                            ast::Enum(it) => it.get_or_create_where_clause(),
+                            ast::Struct(it) => it.get_or_create_where_clause(),
+                            _ => return,
+                        }
+                    };
+                    for pred in new_preds {
+                        where_clause.add_predicate(pred.clone_for_update());
+                    }
+                }
+            }
+        },
+    )
+}
+
+fn generics_of(owner: &SyntaxNode) -> Option<(ast::GenericParamList, Option<ast::WhereClause>)> {
+    match_ast! {
+        match owner {
+            ast::Fn(it) => Some((it.generic_param_list()?, it.where_clause())),
+            ast::Trait(it) => Some((it.generic_param_list()?, it.where_clause())),
+            ast::Impl(it) => Some((it.generic_param_list()?, it.where_clause())),
+            ast::Enum(it) => Some((it.generic_param_list()?, it.where_clause())),
+            ast::Struct(it) => Some((it.generic_param_list()?, it.where_clause())),
+            _ => None,
+        }
+    }
+}
+
+fn bound_text(bound: &ast::TypeBound) -> String {
+    bound.syntax().text().to_string().chars().filter(|it| !it.is_whitespace()).collect()
+}
+
+/// Drops the repeated bounds, and the bounds on traits which are supertraits of other bounds.
+/// Bounds naming the same trait by different paths count as repeated.
+fn normalize(ctx: &AssistContext, bounds: Vec<ast::TypeBound>) -> Vec<ast::TypeBound> {
+    let db = ctx.db();
+    let resolve = |bound: &ast::TypeBound| {
+        if bound.question_mark_token().is_some() {
+            return None;
+        }
+        let path = match bound.ty()? {
+            ast::Type::PathType(it) => it.path()?,
+            _ => return None,
+        };
+        match ctx.sema.resolve_path(&path)? {
+            PathResolution::Def(ModuleDef::Trait(it)) => {
+                let segment = path.segment()?;
+                let is_plain = segment.generic_arg_list().is_none()
+                    && segment.param_list().is_none()
+                    && it.type_or_const_param_count(db) == 0;
+                Some((it, is_plain))
+            }
+            _ => None,
+        }
+    };
+
+    let mut res: Vec<(ast::TypeBound, Option<(hir::Trait, bool)>)> = Vec::new();
+    for bound in bounds {
+        let resolved = resolve(&bound);
+        let is_repeated =
+            res.iter().any(|(other, other_resolved)| match (resolved, other_resolved) {
+                (Some((it, true)), Some((other, true))) => it == *other,
+                _ => bound_text(&bound) == bound_text(other),
+            });
+        if !is_repeated {
+            res.push((bound, resolved));
+        }
+    }
+
+    let traits: Vec<hir::Trait> = res.iter().filter_map(|(_, it)| Some(it.as_ref()?.0)).collect();
+    res.iter()
+        .filter(|(_, resolved)| {
+            let (trait_, is_plain) = match resolved {
+                Some(it) => *it,
+                None => return true,
+            };
+            // With `trait A: B` and `trait B: A`, either one implies the other, so both stay.
+            !is_plain
+                || !traits.iter().any(|&other| {
+                    other != trait_
+                        && other.all_supertraits(db).contains(&trait_)
+                        && !trait_.all_supertraits(db).contains(&other)
+                })
+        })
+        .map(|(bound, _)| bound.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::tests::{
+        check_assist, check_assist_not_applicable, check_assist_with_config, TEST_CONFIG,
+    };
+    use crate::AssistConfig;
+
+    fn check_inline(ra_fixture_before: &str, ra_fixture_after: &str) {
+        let config = AssistConfig { bounds_placement: BoundsPlacement::Inline, ..TEST_CONFIG };
+        check_assist_with_config(normalize_bounds, config, ra_fixture_before, ra_fixture_after);
+    }
+
+    #[test]
+    fn moves_bounds_to_where_clause() {
+        check_assist(
+            normalize_bounds,
+            r#"fn f<$0T: Clone, U: Copy>(t: T, u: U) {}"#,
+            r#"fn f<T, U>(t: T, u: U) where T: Clone, U: Copy {}"#,
+        );
+        check_assist(
+            normalize_bounds,
+            r#"struct S<$0T: Clone>(T);"#,
+            r#"struct S<T>(T) where T: Clone;"#,
+        );
+    }
+
+    #[test]
+    fn merges_and_dedupes_bounds() {
+        check_assist(
+            normalize_bounds,
+            r#"
+mod m { pub trait Tr {} }
+use m::Tr;
+fn f<T: Tr, U>(t: T, u: U) where T: m::Tr + Tr, $0U: Sized, Vec<T>: Sized, U: Sized {}
+"#,
+            r#"
+mod m { pub trait Tr {} }
+use m::Tr;
+fn f<T, U>(t: T, u: U) where T: Tr, U: Sized, Vec<T>: Sized {}
+"#,
+        );
+    }
+
+    #[test]
+    fn removes_bounds_implied_by_supertraits() {
+        check_assist(
+            normalize_bounds,
+            r#"
+trait A {}
+trait B: A {}
+trait C: B {}
+trait Gen<T> {}
+trait D: Gen<u32> {}
+fn f<$0T: A + C, U: Gen<u32> + D>() {}
+"#,
+            r#"
+trait A {}
+trait B: A {}
+trait C: B {}
+trait Gen<T> {}
+trait D: Gen<u32> {}
+fn f<T, U>() where T: C, U: Gen<u32> + D {}
+"#,
+        );
+        check_assist(
+            normalize_bounds,
+            r#"
+trait A: B {}
+trait B: A {}
+fn f<$0T: A + B>() {}
+"#,
+            r#"
+trait A: B {}
+trait B: A {}
+fn f<T>() where T: A + B {}
+"#,
+        );
+    }
+
+    #[test]
+    fn moves_bounds_inline() {
+        check_inline(
+            r#"
+fn f<T: Clone, U = ()>(t: T) where$0 T: Copy, U: Default, for<'a> &'a T: Sized {}
+"#,
+            r#"
+fn f<T: Clone + Copy, U: Default = ()>(t: T) where for<'a> &'a T: Sized {}
+"#,
+        );
+        check_inline(
+            r#"
+impl<T> Foo for T
+where$0
+    T: Clone,
+{
+}
+"#,
+            r#"
+impl<T: Clone> Foo for T {
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_when_normalized() {
+        check_assist_not_applicable(
+            normalize_bounds,
+            r#"fn f<$0T, U>() where T: Clone, U: Copy {}"#,
+        );
+        check_assist_not_applicable(normalize_bounds, r#"fn f<$0T>() {}"#);
+        check_assist_not_applicable(normalize_bounds, r#"fn f<$0T>() where Vec<T>: Clone {}"#);
+    }
+}
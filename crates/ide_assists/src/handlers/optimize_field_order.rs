@@ -0,0 +1,325 @@
+use std::cmp::Reverse;
+
+use hir::Layout;
+use syntax::{
+    ast::{self, edit::IndentLevel, AstNode, HasName},
+    SyntaxKind,
+};
+
+use crate::{AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: optimize_field_order
+//
+// Reorders the fields of a struct by decreasing alignment, so that declaring them in order
+// leaves no padding between them. Only offered for structs without a `#[repr]`, whose fields
+// can be moved freely.
+//
+// ```
+// struct $0Foo {
+//     a: u8,
+//     b: u64,
+//     c: u16,
+// }
+// ```
+// ->
+// ```
+// struct Foo {
+//     b: u64,
+//     c: u16,
+//     a: u8,
+// }
+// ```
+pub(crate) fn optimize_field_order(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let strukt = ctx.find_node_at_offset::<ast::Struct>()?;
+    let def = ctx.sema.to_def(&strukt)?;
+    if def.repr(ctx.db()).is_some() {
+        return None;
+    }
+    let fields = record_fields(&strukt)?;
+    let layouts = field_layouts(ctx, &fields)?;
+    if Layout::sequential(&layouts)?.size == Layout::reordered(&layouts)?.size {
+        return None;
+    }
+
+    let mut order: Vec<usize> = (0..fields.len()).collect();
+    order.sort_by_key(|&idx| Reverse(layouts[idx].align));
+
+    let target = strukt.name()?.syntax().text_range();
+    acc.add(
+        AssistId("optimize_field_order", AssistKind::RefactorRewrite),
+        "Reorder fields to remove padding",
+        target,
+        |builder| {
+            for (field, &idx) in fields.iter().zip(&order) {
+                builder.replace(field.syntax().text_range(), fields[idx].syntax().to_string());
+            }
+        },
+    )
+}
+
+// Assist: add_padding_comments
+//
+// Annotates the fields of a `#[repr(C)]` struct with their offset, size and the padding
+// following them.
+//
+// ```
+// #[repr(C)]
+// struct $0Foo {
+//     a: u8,
+//     b: u32,
+// }
+// ```
+// ->
+// ```
+// #[repr(C)]
+// struct Foo {
+//     // offset 0, size 1, followed by 3 bytes of padding
+//     a: u8,
+//     // offset 4, size 4
+//     b: u32,
+// }
+// ```
+pub(crate) fn add_padding_comments(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let strukt = ctx.find_node_at_offset::<ast::Struct>()?;
+    let def = ctx.sema.to_def(&strukt)?;
+    if !def.is_repr_c(ctx.db()) {
+        return None;
+    }
+    let fields = record_fields(&strukt)?;
+    if fields.is_empty() {
+        return None;
+    }
+    // Line comments would comment out the rest of a struct written on one line.
+    let on_own_lines = fields.iter().all(|field| {
+        field.syntax().prev_sibling_or_token().map_or(false, |it| {
+            it.kind() == SyntaxKind::WHITESPACE && it.to_string().contains('\n')
+        })
+    });
+    if !on_own_lines {
+        return None;
+    }
+    let layouts = field_layouts(ctx, &fields)?;
+    let size = Layout::sequential(&layouts)?.size;
+    let offsets = Layout::sequential_offsets(&layouts)?;
+
+    let target = strukt.name()?.syntax().text_range();
+    acc.add(
+        AssistId("add_padding_comments", AssistKind::RefactorRewrite),
+        "Add padding comments",
+        target,
+        |builder| {
+            for (idx, field) in fields.iter().enumerate() {
+                let next = offsets.get(idx + 1).copied().unwrap_or(size);
+                let padding = next - offsets[idx] - layouts[idx].size;
+                let mut comment = format!("// offset {}, size {}", offsets[idx], layouts[idx].size);
+                match padding {
+                    0 => (),
+                    1 => comment.push_str(", followed by 1 byte of padding"),
+                    _ => comment.push_str(&format!(", followed by {} bytes of padding", padding)),
+                }
+                let indent = IndentLevel::from_node(field.syntax());
+                builder.insert(
+                    field.syntax().text_range().start(),
+                    format!("{}\n{}", comment, indent),
+                );
+            }
+        },
+    )
+}
+
+fn record_fields(strukt: &ast::Struct) -> Option<Vec<ast::RecordField>> {
+    match strukt.field_list()? {
+        ast::FieldList::RecordFieldList(it) => Some(it.fields().collect()),
+        ast::FieldList::TupleFieldList(_) => None,
+    }
+}
+
+fn field_layouts(ctx: &AssistContext, fields: &[ast::RecordField]) -> Option<Vec<Layout>> {
+    fields.iter().map(|field| ctx.sema.to_def(field)?.ty(ctx.db()).layout(ctx.db())).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn reorders_by_alignment() {
+        check_assist(
+            optimize_field_order,
+            r#"
+struct $0Foo {
+    /// Docs stay with their field.
+    a: bool,
+    b: (u16, u8),
+    c: [u32; 2],
+    d: &'static str,
+    e: u8,
+}
+"#,
+            r#"
+struct Foo {
+    d: &'static str,
+    c: [u32; 2],
+    b: (u16, u8),
+    /// Docs stay with their field.
+    a: bool,
+    e: u8,
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_without_padding() {
+        check_assist_not_applicable(
+            optimize_field_order,
+            r#"
+struct $0Foo {
+    a: u32,
+    b: u8,
+    c: u8,
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_with_repr() {
+        check_assist_not_applicable(
+            optimize_field_order,
+            r#"
+#[repr(C)]
+struct $0Foo {
+    a: u8,
+    b: u64,
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_generic_fields() {
+        check_assist_not_applicable(
+            optimize_field_order,
+            r#"
+struct $0Foo<T> {
+    a: u8,
+    b: T,
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn adds_padding_comments() {
+        check_assist(
+            add_padding_comments,
+            r#"
+struct Inner {
+    a: u64,
+    b: u8,
+}
+#[repr(C)]
+struct $0Foo {
+    a: u8,
+    b: Inner,
+    c: u16,
+}
+"#,
+            r#"
+struct Inner {
+    a: u64,
+    b: u8,
+}
+#[repr(C)]
+struct Foo {
+    // offset 0, size 1, followed by 7 bytes of padding
+    a: u8,
+    // offset 8, size 16
+    b: Inner,
+    // offset 24, size 2, followed by 6 bytes of padding
+    c: u16,
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn padding_comments_use_target_alignment() {
+        check_assist(
+            add_padding_comments,
+            r#"
+//- /lib.rs crate:foo cfg:target_arch=x86,target_os=linux,target_pointer_width=32
+#[repr(C)]
+struct $0Foo {
+    a: u8,
+    b: u64,
+    c: f64,
+    d: usize,
+}
+"#,
+            r#"
+#[repr(C)]
+struct Foo {
+    // offset 0, size 1, followed by 3 bytes of padding
+    a: u8,
+    // offset 4, size 8
+    b: u64,
+    // offset 12, size 8
+    c: f64,
+    // offset 20, size 4
+    d: usize,
+}
+"#,
+        );
+        check_assist(
+            add_padding_comments,
+            r#"
+//- /lib.rs crate:foo cfg:target_arch=aarch64,target_pointer_width=64
+#[repr(C)]
+struct $0Foo {
+    a: u8,
+    b: u128,
+}
+"#,
+            r#"
+#[repr(C)]
+struct Foo {
+    // offset 0, size 1, followed by 15 bytes of padding
+    a: u8,
+    // offset 16, size 16
+    b: u128,
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn padding_comments_not_applicable_on_overflow() {
+        check_assist_not_applicable(
+            add_padding_comments,
+            r#"
+#[repr(C)]
+struct $0Foo {
+    a: [u8; 18446744073709551615],
+    b: u16,
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn padding_comments_need_repr_c() {
+        check_assist_not_applicable(
+            add_padding_comments,
+            r#"
+struct $0Foo {
+    a: u8,
+    b: u32,
+}
+"#,
+        );
+    }
+}
@@ -1,6 +1,6 @@
 use std::iter;
 
-use hir::AsAssocItem;
+use hir::{AsAssocItem, PrefixKind};
 use ide_db::RootDatabase;
 use ide_db::{
     helpers::mod_path_to_ast,
@@ -20,7 +20,8 @@ use crate::{
 
 // Assist: qualify_path
 //
-// If the name is unresolved, provides all possible qualified paths for it.
+// If the name is unresolved, provides all possible qualified paths for it, both
+// relative to the current module and starting at the crate root.
 //
 // ```
 // fn main() {
@@ -37,10 +38,18 @@ use crate::{
 // ```
 pub(crate) fn qualify_path(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
     let (import_assets, syntax_under_caret) = find_importable_node(ctx)?;
-    let mut proposed_imports = import_assets.search_for_relative_paths(&ctx.sema);
-    if proposed_imports.is_empty() {
+    let relative_paths = import_assets.search_for_relative_paths(&ctx.sema);
+    if relative_paths.is_empty() {
         return None;
     }
+    // Also offer the full path from the crate root (or the extern crate), which
+    // reads the same from anywhere in the crate.
+    let mut proposed_imports = import_assets
+        .search_for_imports(&ctx.sema, PrefixKind::ByCrate)
+        .into_iter()
+        .filter(|import| relative_paths.iter().all(|it| it.import_path != import.import_path))
+        .chain(relative_paths)
+        .collect::<Vec<_>>();
 
     let range = match &syntax_under_caret {
         NodeOrToken::Node(node) => ctx.sema.original_range(node).range,
@@ -231,7 +240,9 @@ fn label(candidate: &ImportCandidate, import: &LocatedImport) -> String {
 
 #[cfg(test)]
 mod tests {
-    use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target};
+    use crate::tests::{
+        check_assist, check_assist_by_label, check_assist_not_applicable, check_assist_target,
+    };
 
     use super::*;
 
@@ -1270,4 +1281,32 @@ struct Foo;
 "#,
         );
     }
+
+    #[test]
+    fn qualify_from_crate_root() {
+        check_assist_by_label(
+            qualify_path,
+            r#"
+mod util {
+    pub fn helper() {}
+}
+mod foo {
+    fn f() {
+        helper$0();
+    }
+}
+"#,
+            r#"
+mod util {
+    pub fn helper() {}
+}
+mod foo {
+    fn f() {
+        crate::util::helper();
+    }
+}
+"#,
+            "Qualify as `crate::util::helper`",
+        );
+    }
 }
@@ -1,18 +1,18 @@
 use itertools::Itertools;
 use rustc_hash::FxHashMap;
 
-use hir::{PathResolution, Semantics};
+use hir::{HasSource, PathResolution, Semantics};
 use ide_db::RootDatabase;
 use syntax::{
-    ast::{self, HasName},
-    ted, AstNode,
+    ast::{self, edit::IndentLevel, HasName},
+    ted, AstNode, SyntaxKind, TextRange,
 };
 
-use crate::{utils::get_methods, AssistContext, AssistId, AssistKind, Assists};
+use crate::{AssistContext, AssistId, AssistKind, Assists};
 
 // Assist: reorder_impl
 //
-// Reorder the methods of an `impl Trait`. The methods will be ordered
+// Reorder the items of an `impl Trait`. The items will be ordered
 // in the same order as in the trait definition.
 //
 // ```
@@ -47,27 +47,14 @@ use crate::{utils::get_methods, AssistContext, AssistId, AssistKind, Assists};
 pub(crate) fn reorder_impl(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
     let impl_ast = ctx.find_node_at_offset::<ast::Impl>()?;
     let items = impl_ast.assoc_item_list()?;
-    let methods = get_methods(&items);
+    let assoc_items = items.assoc_items().collect::<Vec<_>>();
 
-    let path = impl_ast
-        .trait_()
-        .and_then(|t| match t {
-            ast::Type::PathType(path) => Some(path),
-            _ => None,
-        })?
-        .path()?;
+    let trait_ = trait_definition(&impl_ast, &ctx.sema)?;
+    let ranks = compute_item_ranks(trait_, ctx);
+    let sorted = sort_by_ranks(&assoc_items, &ranks);
 
-    let ranks = compute_method_ranks(&path, ctx)?;
-    let sorted: Vec<_> = methods
-        .iter()
-        .cloned()
-        .sorted_by_key(|f| {
-            f.name().and_then(|n| ranks.get(&n.to_string()).copied()).unwrap_or(usize::max_value())
-        })
-        .collect();
-
-    // Don't edit already sorted methods:
-    if methods == sorted {
+    // Don't edit already sorted items:
+    if assoc_items == sorted {
         cov_mark::hit!(not_applicable_if_sorted);
         return None;
     }
@@ -75,11 +62,12 @@ pub(crate) fn reorder_impl(acc: &mut Assists, ctx: &AssistContext) -> Option<()>
     let target = items.syntax().text_range();
     acc.add(
         AssistId("reorder_impl", AssistKind::RefactorRewrite),
-        "Sort methods by trait definition",
+        "Sort items by trait definition",
         target,
         |builder| {
-            let methods = methods.into_iter().map(|fn_| builder.make_mut(fn_)).collect::<Vec<_>>();
-            methods
+            let assoc_items =
+                assoc_items.into_iter().map(|item| builder.make_mut(item)).collect::<Vec<_>>();
+            assoc_items
                 .into_iter()
                 .zip(sorted)
                 .for_each(|(old, new)| ted::replace(old.syntax(), new.clone_for_update().syntax()));
@@ -87,24 +75,174 @@ pub(crate) fn reorder_impl(acc: &mut Assists, ctx: &AssistContext) -> Option<()>
     )
 }
 
-fn compute_method_ranks(path: &ast::Path, ctx: &AssistContext) -> Option<FxHashMap<String, usize>> {
-    let td = trait_definition(path, &ctx.sema)?;
+// Assist: reorder_impl_with_default_stubs
+//
+// Reorder the items of an `impl Trait` in the same order as in the trait
+// definition, and list the members which keep their default implementation
+// as comments in their place.
+//
+// ```
+// trait Foo {
+//     fn a() {}
+//     fn b(&self) -> u32 { 0 }
+//     fn c() {}
+// }
+//
+// struct Bar;
+// $0impl Foo for Bar {
+//     fn c() {}
+//     fn a() {}
+// }
+// ```
+// ->
+// ```
+// trait Foo {
+//     fn a() {}
+//     fn b(&self) -> u32 { 0 }
+//     fn c() {}
+// }
+//
+// struct Bar;
+// impl Foo for Bar {
+//     fn a() {}
+//     // fn b(&self) -> u32 { .. }
+//     fn c() {}
+// }
+// ```
+pub(crate) fn reorder_impl_with_default_stubs(
+    acc: &mut Assists,
+    ctx: &AssistContext,
+) -> Option<()> {
+    let impl_ast = ctx.find_node_at_offset::<ast::Impl>()?;
+    let items = impl_ast.assoc_item_list()?;
+    let assoc_items = items.assoc_items().collect::<Vec<_>>();
+    let (first, last) = (assoc_items.first()?, assoc_items.last()?);
+
+    let trait_ = trait_definition(&impl_ast, &ctx.sema)?;
+    let implemented = assoc_items.iter().filter_map(item_name).collect::<Vec<_>>();
+    let stubs = trait_
+        .items(ctx.db())
+        .into_iter()
+        .filter(|item| {
+            item.name(ctx.db()).map_or(false, |name| !implemented.contains(&name.to_string()))
+        })
+        .filter_map(|item| Some((item.name(ctx.db())?.to_string(), default_stub(ctx, item)?)))
+        .collect::<FxHashMap<_, _>>();
+    if stubs.is_empty() {
+        return None;
+    }
 
-    Some(
-        td.items(ctx.db())
-            .iter()
-            .flat_map(|i| match i {
-                hir::AssocItem::Function(f) => Some(f),
-                _ => None,
-            })
-            .enumerate()
-            .map(|(idx, func)| (func.name(ctx.db()).to_string(), idx))
-            .collect(),
+    let ranks = compute_item_ranks(trait_, ctx);
+    let mut sorted = sort_by_ranks(&assoc_items, &ranks)
+        .into_iter()
+        .map(|item| {
+            let rank = item_name(&item).and_then(|name| ranks.get(&name).copied());
+            (rank.unwrap_or(usize::max_value()), item.syntax().text().to_string())
+        })
+        .collect::<Vec<_>>();
+    sorted.extend(stubs.into_iter().map(|(name, stub)| (ranks[&name], stub)));
+    sorted.sort_by_key(|(rank, _)| *rank);
+
+    let separator = match first.syntax().next_sibling_or_token() {
+        Some(ws) if ws.kind() == SyntaxKind::WHITESPACE && assoc_items.len() > 1 => ws.to_string(),
+        _ => format!("\n{}", IndentLevel::from_node(first.syntax())),
+    };
+    let range =
+        TextRange::new(first.syntax().text_range().start(), last.syntax().text_range().end());
+    acc.add(
+        AssistId("reorder_impl_with_default_stubs", AssistKind::RefactorRewrite),
+        "Sort items by trait definition, listing default members",
+        items.syntax().text_range(),
+        |builder| {
+            let text = sorted.into_iter().map(|(_, text)| text).join(&separator);
+            builder.replace(range, text);
+        },
     )
 }
 
-fn trait_definition(path: &ast::Path, sema: &Semantics<RootDatabase>) -> Option<hir::Trait> {
-    match sema.resolve_path(path)? {
+fn compute_item_ranks(trait_: hir::Trait, ctx: &AssistContext) -> FxHashMap<String, usize> {
+    trait_
+        .items(ctx.db())
+        .iter()
+        .flat_map(|i| i.name(ctx.db()))
+        .enumerate()
+        .map(|(idx, name)| (name.to_string(), idx))
+        .collect()
+}
+
+fn sort_by_ranks(
+    items: &[ast::AssocItem],
+    ranks: &FxHashMap<String, usize>,
+) -> Vec<ast::AssocItem> {
+    items
+        .iter()
+        .cloned()
+        .sorted_by_key(|item| {
+            item_name(item).and_then(|name| ranks.get(&name).copied()).unwrap_or(usize::max_value())
+        })
+        .collect()
+}
+
+fn item_name(item: &ast::AssocItem) -> Option<String> {
+    let name = match item {
+        ast::AssocItem::Fn(it) => it.name(),
+        ast::AssocItem::Const(it) => it.name(),
+        ast::AssocItem::TypeAlias(it) => it.name(),
+        ast::AssocItem::MacroCall(_) => None,
+    };
+    name.map(|it| it.to_string())
+}
+
+/// Renders the signature of a trait item with a default implementation as a
+/// comment, or `None` if the item has to be implemented.
+fn default_stub(ctx: &AssistContext, item: hir::AssocItem) -> Option<String> {
+    let (syntax, default) = match item {
+        hir::AssocItem::Function(it) => {
+            let src = it.source(ctx.db())?.value;
+            (src.syntax().clone(), src.body()?.syntax().text_range().start())
+        }
+        hir::AssocItem::Const(it) => {
+            let src = it.source(ctx.db())?.value;
+            src.body()?;
+            (src.syntax().clone(), src.eq_token()?.text_range().start())
+        }
+        hir::AssocItem::TypeAlias(it) => {
+            let src = it.source(ctx.db())?.value;
+            src.ty()?;
+            (src.syntax().clone(), src.eq_token()?.text_range().start())
+        }
+    };
+    // Skip attributes and doc comments.
+    let start = syntax
+        .children_with_tokens()
+        .find(|it| {
+            !matches!(it.kind(), SyntaxKind::ATTR | SyntaxKind::COMMENT | SyntaxKind::WHITESPACE)
+        })?
+        .text_range()
+        .start();
+    let signature =
+        syntax.text().slice(TextRange::new(start, default) - syntax.text_range().start());
+    // Signatures spanning several lines are collapsed into one.
+    let signature = signature
+        .to_string()
+        .split_whitespace()
+        .join(" ")
+        .replace("( ", "(")
+        .replace(", )", ")")
+        .replace(" )", ")");
+    let stub = match item {
+        hir::AssocItem::Function(_) => format!("// {} {{ .. }}", signature),
+        _ => format!("// {} = ..;", signature),
+    };
+    Some(stub)
+}
+
+fn trait_definition(impl_ast: &ast::Impl, sema: &Semantics<RootDatabase>) -> Option<hir::Trait> {
+    let path = match impl_ast.trait_()? {
+        ast::Type::PathType(path) => path.path()?,
+        _ => return None,
+    };
+    match sema.resolve_path(&path)? {
         PathResolution::Def(hir::ModuleDef::Trait(trait_)) => Some(trait_),
         _ => None,
     }
@@ -216,4 +354,111 @@ impl Bar for Foo {
 }"#,
         )
     }
+
+    #[test]
+    fn reorder_impl_assoc_consts_and_types() {
+        check_assist(
+            reorder_impl,
+            r#"
+trait Bar {
+    type T;
+    const C: u8;
+    fn f();
+}
+
+struct Foo;
+$0impl Bar for Foo {
+    fn f() {}
+    const C: u8 = 0;
+    type T = ();
+}
+"#,
+            r#"
+trait Bar {
+    type T;
+    const C: u8;
+    fn f();
+}
+
+struct Foo;
+impl Bar for Foo {
+    type T = ();
+    const C: u8 = 0;
+    fn f() {}
+}
+"#,
+        )
+    }
+
+    #[test]
+    fn reorder_impl_with_stubs() {
+        check_assist(
+            reorder_impl_with_default_stubs,
+            r#"
+trait Bar {
+    /// Docs.
+    type T = ();
+    const C: u8 = 0;
+    fn required(&self);
+    #[inline]
+    fn provided<U>(
+        &self,
+        u: U,
+    ) -> u8 {
+        0
+    }
+    fn last();
+}
+
+struct Foo;
+$0impl Bar for Foo {
+    fn last() {}
+    fn required(&self) {}
+}
+"#,
+            r#"
+trait Bar {
+    /// Docs.
+    type T = ();
+    const C: u8 = 0;
+    fn required(&self);
+    #[inline]
+    fn provided<U>(
+        &self,
+        u: U,
+    ) -> u8 {
+        0
+    }
+    fn last();
+}
+
+struct Foo;
+impl Bar for Foo {
+    // type T = ..;
+    // const C: u8 = ..;
+    fn required(&self) {}
+    // fn provided<U>(&self, u: U) -> u8 { .. }
+    fn last() {}
+}
+"#,
+        )
+    }
+
+    #[test]
+    fn reorder_impl_with_stubs_not_applicable_without_defaults() {
+        check_assist_not_applicable(
+            reorder_impl_with_default_stubs,
+            r#"
+trait Bar {
+    fn a();
+    fn b() {}
+}
+struct Foo;
+$0impl Bar for Foo {
+    fn b() {}
+    fn a() {}
+}
+"#,
+        )
+    }
 }
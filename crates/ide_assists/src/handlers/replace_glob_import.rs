@@ -0,0 +1,508 @@
+use hir::{Module, ModuleDef, Name, PathResolution, ScopeDef};
+use ide_db::{
+    base_db::FileRange,
+    defs::Definition,
+    search::{FileReference, SearchScope},
+};
+use itertools::Itertools;
+use rustc_hash::FxHashSet;
+use syntax::{
+    ast::{self, make},
+    ted, AstNode, SyntaxKind, SyntaxNode, T,
+};
+
+use crate::{
+    assist_context::{AssistContext, Assists},
+    AssistId, AssistKind,
+};
+
+// Assist: replace_glob_import
+//
+// Replaces a glob import with the names the module actually uses from it. Names which are
+// also imported by another glob before this one are left to that glob, and a glob nothing is
+// used from is removed.
+//
+// ```
+// mod foo {
+//     pub struct Bar;
+//     pub struct Baz;
+//     pub fn qux() {}
+// }
+//
+// use foo::*$0;
+//
+// fn f(bar: Bar) { qux() }
+// ```
+// ->
+// ```
+// mod foo {
+//     pub struct Bar;
+//     pub struct Baz;
+//     pub fn qux() {}
+// }
+//
+// use foo::{Bar, qux};
+//
+// fn f(bar: Bar) { qux() }
+// ```
+pub(crate) fn replace_glob_import(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let star = ctx.find_token_syntax_at_offset(T![*])?;
+    let use_tree = star.parent().and_then(ast::UseTree::cast)?;
+    let use_item = use_tree.syntax().ancestors().find_map(ast::Use::cast)?;
+    // Globs only import into the module they are in, which has to be an item list to search.
+    let module_syntax = use_item.syntax().parent()?;
+    if !matches!(module_syntax.kind(), SyntaxKind::SOURCE_FILE | SyntaxKind::ITEM_LIST) {
+        return None;
+    }
+    let current_module = ctx.sema.scope(use_item.syntax()).module()?;
+    let provided = glob_names(ctx, &use_tree, current_module)?;
+
+    let db = ctx.db();
+    let in_scope: FxHashSet<(Name, ScopeDef)> =
+        current_module.scope(db, None).into_iter().collect();
+    let mut explicit = FxHashSet::default();
+    let mut earlier_globs = FxHashSet::default();
+    for tree in module_syntax
+        .children()
+        .filter_map(ast::Use::cast)
+        .flat_map(|it| it.syntax().descendants().filter_map(ast::UseTree::cast).collect_vec())
+    {
+        if tree.star_token().is_some() {
+            if tree.syntax().text_range().start() < use_tree.syntax().text_range().start() {
+                earlier_globs.extend(glob_names(ctx, &tree, current_module).unwrap_or_default());
+            }
+        } else if tree.use_tree_list().is_none() {
+            if let Some(name) = imported_name(&tree) {
+                explicit.insert(name);
+            }
+        }
+    }
+
+    let search_scope = SearchScope::file_range(FileRange {
+        file_id: ctx.file_id(),
+        range: module_syntax.text_range(),
+    });
+    let mut names = provided
+        .into_iter()
+        .filter(|(name, def)| {
+            // Named imports shadow globs, and a name can come from this glob only if the module
+            // resolves it to the same thing.
+            in_scope.contains(&(name.clone(), ScopeDef::ModuleDef(*def)))
+                && !explicit.contains(&name.to_string())
+                && !earlier_globs.contains(&(name.clone(), *def))
+        })
+        .filter(|(_, def)| is_used_in(ctx, *def, current_module, &search_scope))
+        .map(|(name, _)| name.to_string())
+        .collect_vec();
+    names.sort();
+    names.dedup();
+
+    acc.add(
+        AssistId("replace_glob_import", AssistKind::RefactorRewrite),
+        "Replace glob import with explicit imports",
+        use_tree.syntax().text_range(),
+        |builder| {
+            if names.is_empty() {
+                if use_item.use_tree().as_ref() == Some(&use_tree) {
+                    builder.make_mut(use_item).remove();
+                } else {
+                    builder.make_mut(use_tree).remove();
+                }
+                return;
+            }
+            let use_tree = builder.make_mut(use_tree);
+            let star = match use_tree.star_token() {
+                Some(it) => it,
+                None => return,
+            };
+            let list = make::use_tree_list(
+                names
+                    .iter()
+                    .map(|name| make::use_tree(make::ext::ident_path(name), None, None, false)),
+            )
+            .clone_for_update();
+            if use_tree.path().is_some() && names.len() != 1 {
+                ted::replace(star, list.syntax());
+            } else {
+                let without_braces = list
+                    .syntax()
+                    .children_with_tokens()
+                    .filter(|child| !matches!(child.kind(), T!['{'] | T!['}']))
+                    .collect();
+                ted::replace_with_many(star, without_braces);
+            }
+        },
+    )
+}
+
+/// The names and definitions a glob use tree imports into `module`.
+fn glob_names(
+    ctx: &AssistContext,
+    use_tree: &ast::UseTree,
+    module: Module,
+) -> Option<Vec<(Name, ModuleDef)>> {
+    let path = match use_tree.path() {
+        Some(it) => it,
+        None => {
+            use_tree.syntax().parent().and_then(ast::UseTreeList::cast)?.parent_use_tree().path()?
+        }
+    };
+    let db = ctx.db();
+    let res = match ctx.sema.resolve_path(&path)? {
+        PathResolution::Def(ModuleDef::Module(target)) => target
+            .scope(db, Some(module))
+            .into_iter()
+            .filter_map(|(name, def)| match def {
+                ScopeDef::ModuleDef(def) => Some((name, def)),
+                _ => None,
+            })
+            .collect(),
+        PathResolution::Def(ModuleDef::Adt(hir::Adt::Enum(target))) => target
+            .variants(db)
+            .into_iter()
+            .map(|variant| (variant.name(db), ModuleDef::Variant(variant)))
+            .collect(),
+        _ => return None,
+    };
+    Some(res)
+}
+
+/// The name a use tree without a glob or a list brings into scope, if any.
+fn imported_name(use_tree: &ast::UseTree) -> Option<String> {
+    if let Some(rename) = use_tree.rename() {
+        return rename.name().map(|it| it.text().to_string());
+    }
+    let segment = use_tree.path()?.segment()?;
+    if segment.self_token().is_some() {
+        let parent_path = use_tree
+            .syntax()
+            .ancestors()
+            .skip(1)
+            .filter_map(ast::UseTree::cast)
+            .find_map(|it| it.path())?;
+        return Some(parent_path.segment()?.name_ref()?.text().to_string());
+    }
+    Some(segment.name_ref()?.text().to_string())
+}
+
+/// Whether `def` is referred to from `module` outside of imports. A trait is used when any of
+/// its items is, as it needs to be in scope for them to resolve.
+fn is_used_in(ctx: &AssistContext, def: ModuleDef, module: Module, scope: &SearchScope) -> bool {
+    let mut defs = vec![Definition::from(def)];
+    if let ModuleDef::Trait(trait_) = def {
+        defs.extend(trait_.items(ctx.db()).into_iter().map(Definition::from));
+    }
+    defs.into_iter().any(|def| {
+        def.usages(&ctx.sema).in_scope(scope.clone()).all().iter().any(|(_, refs)| {
+            refs.iter().any(|FileReference { name, .. }| {
+                let syntax: &SyntaxNode = name.syntax();
+                !syntax.ancestors().any(|it| ast::Use::can_cast(it.kind()))
+                    && ctx.sema.scope(syntax).module() == Some(module)
+            })
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn replaces_glob_with_used_names() {
+        check_assist(
+            replace_glob_import,
+            r#"
+mod foo {
+    pub struct Bar;
+    pub struct Baz;
+    pub fn qux() {}
+}
+
+use foo::*$0;
+
+fn f(bar: Bar) {
+    qux();
+}
+"#,
+            r#"
+mod foo {
+    pub struct Bar;
+    pub struct Baz;
+    pub fn qux() {}
+}
+
+use foo::{Bar, qux};
+
+fn f(bar: Bar) {
+    qux();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn single_name_without_braces() {
+        check_assist(
+            replace_glob_import,
+            r#"
+mod foo {
+    pub struct Bar;
+    pub struct Baz;
+}
+
+use foo::*$0;
+
+fn f(bar: Bar) {}
+"#,
+            r#"
+mod foo {
+    pub struct Bar;
+    pub struct Baz;
+}
+
+use foo::Bar;
+
+fn f(bar: Bar) {}
+"#,
+        );
+    }
+
+    #[test]
+    fn removes_unused_glob() {
+        check_assist(
+            replace_glob_import,
+            r#"
+mod foo {
+    pub struct Bar;
+}
+
+use foo::*$0;
+use std::fmt;
+
+fn f() {}
+"#,
+            r#"
+mod foo {
+    pub struct Bar;
+}
+
+use std::fmt;
+
+fn f() {}
+"#,
+        );
+        check_assist(
+            replace_glob_import,
+            r#"
+mod foo {
+    pub mod bar {
+        pub struct Baz;
+    }
+    pub struct Qux;
+}
+
+use foo::{bar::*$0, Qux};
+
+fn f(qux: Qux) {}
+"#,
+            r#"
+mod foo {
+    pub mod bar {
+        pub struct Baz;
+    }
+    pub struct Qux;
+}
+
+use foo::{Qux};
+
+fn f(qux: Qux) {}
+"#,
+        );
+    }
+
+    #[test]
+    fn skips_shadowed_and_explicit_names() {
+        check_assist(
+            replace_glob_import,
+            r#"
+mod foo {
+    pub struct Bar;
+    pub struct Baz;
+    pub struct Qux;
+}
+
+use foo::*$0;
+use foo::Baz;
+
+struct Qux;
+
+fn f(bar: Bar, baz: Baz, qux: Qux) {}
+"#,
+            r#"
+mod foo {
+    pub struct Bar;
+    pub struct Baz;
+    pub struct Qux;
+}
+
+use foo::Bar;
+use foo::Baz;
+
+struct Qux;
+
+fn f(bar: Bar, baz: Baz, qux: Qux) {}
+"#,
+        );
+    }
+
+    #[test]
+    fn leaves_names_to_earlier_globs() {
+        check_assist(
+            replace_glob_import,
+            r#"
+mod foo {
+    pub struct Bar;
+}
+mod baz {
+    pub use super::foo::Bar;
+    pub struct Baz;
+}
+
+use foo::*;
+use baz::*$0;
+
+fn f(bar: Bar, baz: Baz) {}
+"#,
+            r#"
+mod foo {
+    pub struct Bar;
+}
+mod baz {
+    pub use super::foo::Bar;
+    pub struct Baz;
+}
+
+use foo::*;
+use baz::Baz;
+
+fn f(bar: Bar, baz: Baz) {}
+"#,
+        );
+    }
+
+    #[test]
+    fn only_counts_uses_in_the_module() {
+        check_assist(
+            replace_glob_import,
+            r#"
+mod foo {
+    pub struct Bar;
+    pub struct Baz;
+}
+
+mod inner {
+    use super::foo::*$0;
+
+    fn f(bar: Bar) {}
+
+    mod innermost {
+        use super::super::foo::Baz;
+
+        fn g(baz: Baz) {}
+    }
+}
+"#,
+            r#"
+mod foo {
+    pub struct Bar;
+    pub struct Baz;
+}
+
+mod inner {
+    use super::foo::Bar;
+
+    fn f(bar: Bar) {}
+
+    mod innermost {
+        use super::super::foo::Baz;
+
+        fn g(baz: Baz) {}
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn enum_variants_and_traits() {
+        check_assist(
+            replace_glob_import,
+            r#"
+mod foo {
+    pub enum E { A, B }
+    pub trait T { fn t(&self) {} }
+    impl T for () {}
+}
+
+use foo::E::*$0;
+
+fn f() -> foo::E { A }
+"#,
+            r#"
+mod foo {
+    pub enum E { A, B }
+    pub trait T { fn t(&self) {} }
+    impl T for () {}
+}
+
+use foo::E::A;
+
+fn f() -> foo::E { A }
+"#,
+        );
+        check_assist(
+            replace_glob_import,
+            r#"
+mod foo {
+    pub enum E { A, B }
+    pub trait T { fn t(&self) {} }
+    impl T for () {}
+}
+
+use foo::*$0;
+
+fn f() { ().t() }
+"#,
+            r#"
+mod foo {
+    pub enum E { A, B }
+    pub trait T { fn t(&self) {} }
+    impl T for () {}
+}
+
+use foo::T;
+
+fn f() { ().t() }
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_in_blocks() {
+        check_assist_not_applicable(
+            replace_glob_import,
+            r#"
+mod foo {
+    pub struct Bar;
+}
+
+fn f() {
+    use foo::*$0;
+    let _ = Bar;
+}
+"#,
+        );
+    }
+}
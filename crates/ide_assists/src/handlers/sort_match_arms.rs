@@ -0,0 +1,264 @@
+use hir::{ModuleDef, PathResolution};
+use syntax::{
+    ast::{self, AstNode},
+    T,
+};
+
+use crate::{AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: sort_match_arms
+//
+// Sorts the arms of a `match` by the declaration order of the enum variants they match. Arms not
+// tied to a variant, like `_`, stay where they are, and an arm is never moved past one matching
+// some of the same values.
+//
+// ```
+// enum Color { Red, Green, Blue }
+//
+// fn name(color: Color) -> &'static str {
+//     $0match color {
+//         Color::Blue => "blue",
+//         Color::Red => "red",
+//         Color::Green => "green",
+//     }
+// }
+// ```
+// ->
+// ```
+// enum Color { Red, Green, Blue }
+//
+// fn name(color: Color) -> &'static str {
+//     match color {
+//         Color::Red => "red",
+//         Color::Green => "green",
+//         Color::Blue => "blue",
+//     }
+// }
+// ```
+pub(crate) fn sort_match_arms(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let match_keyword = ctx.find_token_syntax_at_offset(T![match])?;
+    let match_expr = ast::MatchExpr::cast(match_keyword.parent()?)?;
+    let arms = match_expr.match_arm_list()?.arms().collect::<Vec<_>>();
+    let pats = arms.iter().map(|arm| arm.pat()).collect::<Option<Vec<_>>>()?;
+    let keys = pats.iter().map(|pat| variant_index(ctx, pat)).collect::<Vec<_>>();
+
+    // Arms without a variant split the others into runs which are sorted separately.
+    let mut order = (0..arms.len()).collect::<Vec<_>>();
+    let mut start = 0;
+    while start < order.len() {
+        let len = keys[start..].iter().take_while(|key| key.is_some()).count();
+        order[start..start + len].sort_by_key(|&idx| keys[idx]);
+        start += len + 1;
+    }
+    if order.iter().enumerate().all(|(pos, &idx)| pos == idx) {
+        return None;
+    }
+
+    // Swapping two arms only preserves the behavior when no value matches both of them.
+    for (pos, &idx) in order.iter().enumerate() {
+        for &other in &order[pos + 1..] {
+            if other < idx && ctx.sema.match_patterns_overlap(&match_expr, &pats[idx], &pats[other])
+            {
+                cov_mark::hit!(sort_match_arms_overlapping_arms);
+                return None;
+            }
+        }
+    }
+    // Which also means every arm stays as reachable as it was.
+    let reachability = |order: &[usize]| {
+        let reordered = order
+            .iter()
+            .map(|&idx| (pats[idx].clone(), arms[idx].guard().is_some()))
+            .collect::<Vec<_>>();
+        ctx.sema.match_arms_reachability(&match_expr, &reordered)
+    };
+    let identity = (0..arms.len()).collect::<Vec<_>>();
+    if let (Some(before), Some(after)) = (reachability(&identity), reachability(&order)) {
+        if order.iter().zip(after).any(|(&idx, reachable)| before[idx] != reachable) {
+            return None;
+        }
+    }
+
+    acc.add(
+        AssistId("sort_match_arms", AssistKind::RefactorRewrite),
+        "Sort match arms by variant",
+        match_expr.syntax().text_range(),
+        |edit| {
+            for (pos, &idx) in order.iter().enumerate() {
+                if pos == idx {
+                    continue;
+                }
+                let arm = &arms[idx];
+                let mut text = arm.syntax().to_string();
+                // Arms moved up from the end may be missing the comma the others need.
+                let needs_comma = arm.comma_token().is_none()
+                    && pos + 1 != arms.len()
+                    && !arm.expr().map_or(false, |it| it.is_block_like());
+                if needs_comma {
+                    text.push(',');
+                }
+                edit.replace(arms[pos].syntax().text_range(), text);
+            }
+        },
+    )
+}
+
+/// The position of the variant `pat` matches in the declaration of its enum, or the first of
+/// them for or-patterns.
+fn variant_index(ctx: &AssistContext, pat: &ast::Pat) -> Option<usize> {
+    let resolve = |path: ast::Path| match ctx.sema.resolve_path(&path)? {
+        PathResolution::Def(ModuleDef::Variant(it)) => Some(it),
+        _ => None,
+    };
+    let variant = match pat {
+        ast::Pat::TupleStructPat(it) => resolve(it.path()?)?,
+        ast::Pat::RecordPat(it) => resolve(it.path()?)?,
+        ast::Pat::PathPat(it) => resolve(it.path()?)?,
+        ast::Pat::IdentPat(it) => match ctx.sema.resolve_bind_pat_to_const(it)? {
+            ModuleDef::Variant(it) => it,
+            _ => return None,
+        },
+        ast::Pat::RefPat(it) => return variant_index(ctx, &it.pat()?),
+        ast::Pat::ParenPat(it) => return variant_index(ctx, &it.pat()?),
+        ast::Pat::OrPat(it) => {
+            return it
+                .pats()
+                .map(|it| variant_index(ctx, &it))
+                .collect::<Option<Vec<_>>>()?
+                .into_iter()
+                .min()
+        }
+        _ => return None,
+    };
+    let db = ctx.db();
+    variant.parent_enum(db).variants(db).into_iter().position(|it| it == variant)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn sorts_by_variant_order() {
+        check_assist(
+            sort_match_arms,
+            r#"
+enum X { A, B(i32), C { c: i32 } }
+
+fn f(x: &X) -> i32 {
+    $0match x {
+        X::C { c } => *c,
+        &X::B(b) => b,
+        X::A => 0
+    }
+}
+"#,
+            r#"
+enum X { A, B(i32), C { c: i32 } }
+
+fn f(x: &X) -> i32 {
+    match x {
+        X::A => 0,
+        &X::B(b) => b,
+        X::C { c } => *c,
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn keeps_catch_all_arms_in_place() {
+        check_assist(
+            sort_match_arms,
+            r#"
+enum X { A, B, C, D }
+
+fn f(x: X) {
+    $0match x {
+        X::B => {}
+        X::A => {}
+        _ => {}
+    }
+}
+"#,
+            r#"
+enum X { A, B, C, D }
+
+fn f(x: X) {
+    match x {
+        X::A => {}
+        X::B => {}
+        _ => {}
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn keeps_order_of_arms_for_the_same_variant() {
+        check_assist(
+            sort_match_arms,
+            r#"
+enum X { A(bool), B }
+
+fn f(x: X) {
+    $0match x {
+        X::B => {}
+        X::A(true) => {}
+        X::A(_) => {}
+    }
+}
+"#,
+            r#"
+enum X { A(bool), B }
+
+fn f(x: X) {
+    match x {
+        X::A(true) => {}
+        X::A(_) => {}
+        X::B => {}
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_when_moving_past_overlapping_arm() {
+        cov_mark::check!(sort_match_arms_overlapping_arms);
+        check_assist_not_applicable(
+            sort_match_arms,
+            r#"
+enum X { A, B(bool), C }
+
+fn f(x: X) {
+    $0match x {
+        X::B(false) | X::C => {}
+        X::A | X::B(_) => {}
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_when_sorted() {
+        check_assist_not_applicable(
+            sort_match_arms,
+            r#"
+enum X { A, B }
+
+fn f(x: X) {
+    $0match x {
+        X::A => {}
+        X::B => {}
+    }
+}
+"#,
+        );
+    }
+}
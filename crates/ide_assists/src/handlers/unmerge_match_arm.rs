@@ -0,0 +1,196 @@
+use itertools::Itertools;
+use syntax::{
+    ast::{self, edit::IndentLevel, AstNode},
+    TextRange, T,
+};
+
+use crate::{AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: unmerge_match_arm
+//
+// Splits the current match arm with a `|` pattern into two arms with identical bodies. Not offered
+// when either arm would be unreachable.
+//
+// ```
+// enum Action { Move { distance: u32 }, Stop }
+//
+// fn handle(action: Action) {
+//     match action {
+//         Action::Move { .. } $0| Action::Stop => foo(),
+//     }
+// }
+// ```
+// ->
+// ```
+// enum Action { Move { distance: u32 }, Stop }
+//
+// fn handle(action: Action) {
+//     match action {
+//         Action::Move { .. } => foo(),
+//         Action::Stop => foo(),
+//     }
+// }
+// ```
+pub(crate) fn unmerge_match_arm(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let pipe_token = ctx.find_token_syntax_at_offset(T![|])?;
+    let or_pat = ast::OrPat::cast(pipe_token.parent()?)?;
+    let match_arm = ast::MatchArm::cast(or_pat.syntax().parent()?)?;
+    let match_expr = match_arm.syntax().ancestors().find_map(ast::MatchExpr::cast)?;
+    let expr = match_arm.expr()?;
+
+    let pats = or_pat.pats().collect::<Vec<_>>();
+    let split = pats
+        .iter()
+        .position(|pat| pat.syntax().text_range().start() > pipe_token.text_range().start())?;
+    if split == 0 {
+        // The leading `|` of the arm.
+        return None;
+    }
+
+    // Each half becomes an arm of its own, which mustn't be covered by the arms above it already.
+    let has_guard = match_arm.guard().is_some();
+    let mut arms = match_expr
+        .match_arm_list()?
+        .arms()
+        .take_while(|arm| arm != &match_arm)
+        .map(|arm| Some((arm.pat()?, arm.guard().is_some())))
+        .collect::<Option<Vec<_>>>()?;
+    let arms_above = arms.len();
+    arms.extend(pats.iter().map(|pat| (pat.clone(), has_guard)));
+    let reachability = ctx.sema.match_arms_reachability(&match_expr, &arms)?;
+    let (first_reachable, second_reachable) = reachability[arms_above..].split_at(split);
+    if !first_reachable.contains(&true) || !second_reachable.contains(&true) {
+        cov_mark::hit!(unmerge_match_arm_unreachable_half);
+        return None;
+    }
+
+    let arm_range = match_arm.syntax().text_range();
+    let pat_range = or_pat.syntax().text_range();
+    let text = match_arm.syntax().to_string();
+    let slice = |range: TextRange| text[range - arm_range.start()].to_string();
+    // Attributes before the pattern, and the guard and body after it, are repeated on both arms.
+    let prefix = slice(TextRange::new(arm_range.start(), pat_range.start()));
+    let suffix = slice(TextRange::new(pat_range.end(), expr.syntax().text_range().end()));
+    let comma = if match_arm.comma_token().is_some() { "," } else { "" };
+    let (first, second) = pats.split_at(split);
+
+    acc.add(
+        AssistId("unmerge_match_arm", AssistKind::RefactorRewrite),
+        "Unmerge match arm",
+        pipe_token.text_range(),
+        |edit| {
+            let indent = IndentLevel::from_node(match_arm.syntax());
+            let arms = format!(
+                "{}{}{},\n{}{}{}{}{}",
+                prefix,
+                first.iter().format(" | "),
+                suffix,
+                indent,
+                prefix,
+                second.iter().format(" | "),
+                suffix,
+                comma,
+            );
+            edit.replace(arm_range, arms);
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn unmerge_match_arm_two_patterns() {
+        check_assist(
+            unmerge_match_arm,
+            r#"
+enum X { A, B, C }
+
+fn main() {
+    let x = X::A;
+    let y = match x {
+        X::A $0| X::B => 1i32,
+        X::C => 2i32,
+    };
+}
+"#,
+            r#"
+enum X { A, B, C }
+
+fn main() {
+    let x = X::A;
+    let y = match x {
+        X::A => 1i32,
+        X::B => 1i32,
+        X::C => 2i32,
+    };
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn unmerge_match_arm_splits_at_cursor_and_keeps_guard() {
+        check_assist(
+            unmerge_match_arm,
+            r#"
+enum X { A(i32), B(i32), C(i32) }
+
+fn f(x: X, c: bool) {
+    match x {
+        X::A(a) | X::B(a) $0| X::C(a) if c => { g(a) }
+        _ => {}
+    }
+}
+"#,
+            r#"
+enum X { A(i32), B(i32), C(i32) }
+
+fn f(x: X, c: bool) {
+    match x {
+        X::A(a) | X::B(a) if c => { g(a) },
+        X::C(a) if c => { g(a) }
+        _ => {}
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn unmerge_match_arm_not_applicable_for_unreachable_half() {
+        cov_mark::check!(unmerge_match_arm_unreachable_half);
+        check_assist_not_applicable(
+            unmerge_match_arm,
+            r#"
+enum X { A, B, C }
+
+fn f(x: X) {
+    match x {
+        X::B => {}
+        X::A $0| X::B => {}
+        X::C => {}
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn unmerge_match_arm_not_applicable_in_nested_or_pattern() {
+        check_assist_not_applicable(
+            unmerge_match_arm,
+            r#"
+fn f(x: Option<bool>) {
+    match x {
+        Some(true $0| false) => {}
+        None => {}
+    }
+}
+"#,
+        );
+    }
+}
@@ -1,20 +1,30 @@
 use std::iter;
 
+use hir::HirDisplay;
 use ide_db::{
+    base_db::FileId,
+    defs::Definition,
     famous_defs::FamousDefs,
     syntax_helpers::node_ext::{for_each_tail_expr, walk_expr},
 };
+use rustc_hash::FxHashMap;
 use syntax::{
     ast::{self, make, Expr},
-    match_ast, AstNode,
+    match_ast, ted, AstNode,
 };
 
-use crate::{AssistContext, AssistId, AssistKind, Assists};
+use crate::{
+    assist_context::AssistBuilder, AssistContext, AssistId, AssistKind, Assists, GroupLabel,
+};
 
 // Assist: wrap_return_type_in_result
 //
 // Wrap the function's return type into Result.
 //
+// The error type is taken from the first `?` on a `Result` in the body. Other `?`s which don't
+// fit get a `.map_err()` or `.ok_or_else()` stub. The call sites can be updated to propagate the
+// error with `?`, or to `.unwrap()` the result.
+//
 // ```
 // # //- minicore: result
 // fn foo() -> i32$0 { 42i32 }
@@ -24,13 +34,72 @@ use crate::{AssistContext, AssistId, AssistKind, Assists};
 // fn foo() -> Result<i32, ${0:_}> { Ok(42i32) }
 // ```
 pub(crate) fn wrap_return_type_in_result(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    wrap_return_type(acc, ctx, WrapperKind::Result)
+}
+
+// Assist: wrap_return_type_in_option
+//
+// Wrap the function's return type into Option.
+//
+// `?`s on a `Result` in the body are converted with `.ok()`. The call sites can be updated to
+// propagate `None` with `?`, or to `.unwrap()` the option.
+//
+// ```
+// # //- minicore: option
+// fn foo() -> i32$0 { 42i32 }
+// ```
+// ->
+// ```
+// fn foo() -> Option<i32> { Some(42i32) }
+// ```
+pub(crate) fn wrap_return_type_in_option(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    wrap_return_type(acc, ctx, WrapperKind::Option)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum WrapperKind {
+    Result,
+    Option,
+}
+
+impl WrapperKind {
+    fn name(self) -> &'static str {
+        match self {
+            WrapperKind::Result => "Result",
+            WrapperKind::Option => "Option",
+        }
+    }
+
+    fn ctor(self) -> &'static str {
+        match self {
+            WrapperKind::Result => "Ok",
+            WrapperKind::Option => "Some",
+        }
+    }
+}
+
+/// How the call sites of the function are updated.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum CallSiteEdit {
+    None,
+    Try,
+    Unwrap,
+}
+
+struct CallSite {
+    call: ast::Expr,
+    /// Whether the caller returns the same wrapper, so that `?` works.
+    can_try: bool,
+}
+
+fn wrap_return_type(acc: &mut Assists, ctx: &AssistContext, kind: WrapperKind) -> Option<()> {
     let ret_type = ctx.find_node_at_offset::<ast::RetType>()?;
     let parent = ret_type.syntax().parent()?;
-    let body = match_ast! {
+    let (body, func) = match_ast! {
         match parent {
-            ast::Fn(func) => func.body()?,
+            ast::Fn(func) => (func.body()?, Some(func)),
             ast::ClosureExpr(closure) => match closure.body()? {
-                Expr::BlockExpr(block) => block,
+                Expr::BlockExpr(block) => (block, None),
                 // closures require a block when a return type is specified
                 _ => return None,
             },
@@ -40,50 +109,320 @@ pub(crate) fn wrap_return_type_in_result(acc: &mut Assists, ctx: &AssistContext)
 
     let type_ref = &ret_type.ty()?;
     let ty = ctx.sema.resolve_type(type_ref).and_then(|ty| ty.as_adt());
-    let result_enum =
-        FamousDefs(&ctx.sema, ctx.sema.scope(type_ref.syntax()).krate()).core_result_Result()?;
+    let scope = ctx.sema.scope(type_ref.syntax());
+    let famous_defs = FamousDefs(&ctx.sema, scope.krate());
+    let (option_enum, result_enum) =
+        (famous_defs.core_option_Option(), famous_defs.core_result_Result());
+    let wrapper_enum = match kind {
+        WrapperKind::Result => result_enum?,
+        WrapperKind::Option => option_enum?,
+    };
 
-    if matches!(ty, Some(hir::Adt::Enum(ret_type)) if ret_type == result_enum) {
-        cov_mark::hit!(wrap_return_type_in_result_simple_return_type_already_result);
+    if matches!(ty, Some(hir::Adt::Enum(ret_type)) if ret_type == wrapper_enum) {
+        if kind == WrapperKind::Result {
+            cov_mark::hit!(wrap_return_type_in_result_simple_return_type_already_result);
+        }
         return None;
     }
 
-    acc.add(
-        AssistId("wrap_return_type_in_result", AssistKind::RefactorRewrite),
-        "Wrap return type in Result",
-        type_ref.syntax().text_range(),
-        |builder| {
-            let body = ast::Expr::BlockExpr(body);
+    let body = ast::Expr::BlockExpr(body);
+
+    let mut exprs_to_wrap = Vec::new();
+    let tail_cb = &mut |e: &_| tail_cb_impl(&mut exprs_to_wrap, e);
+    let mut try_exprs = Vec::new();
+    walk_expr(&body, &mut |expr| match expr {
+        Expr::ReturnExpr(ret_expr) => {
+            if let Some(ret_expr_arg) = &ret_expr.expr() {
+                for_each_tail_expr(ret_expr_arg, tail_cb);
+            }
+        }
+        Expr::TryExpr(try_expr) => try_exprs.push(try_expr),
+        _ => (),
+    });
+    for_each_tail_expr(&body, tail_cb);
 
-            let mut exprs_to_wrap = Vec::new();
-            let tail_cb = &mut |e: &_| tail_cb_impl(&mut exprs_to_wrap, e);
-            walk_expr(&body, &mut |expr| {
-                if let Expr::ReturnExpr(ret_expr) = expr {
-                    if let Some(ret_expr_arg) = &ret_expr.expr() {
-                        for_each_tail_expr(ret_expr_arg, tail_cb);
+    // `?`s in the body which don't fit the new return type get a conversion stub.
+    let mut err_ty = None;
+    let mut try_fixes = Vec::new();
+    for try_expr in try_exprs {
+        let operand = match try_expr.expr() {
+            Some(it) => it,
+            None => continue,
+        };
+        let operand_ty = match ctx.sema.type_of_expr(&operand) {
+            Some(it) => it.original,
+            None => continue,
+        };
+        let operand_enum = match operand_ty.as_adt() {
+            Some(hir::Adt::Enum(it)) => Some(it),
+            _ => continue,
+        };
+        let fix = match kind {
+            WrapperKind::Result if operand_enum == option_enum => Some(TryFix::OkOrElse),
+            WrapperKind::Result if operand_enum == result_enum => {
+                match (&err_ty, operand_ty.type_arguments().nth(1)) {
+                    (None, Some(operand_err)) => {
+                        err_ty = Some(operand_err);
+                        None
                     }
+                    (Some(err_ty), Some(operand_err)) if *err_ty != operand_err => {
+                        Some(TryFix::MapErr)
+                    }
+                    _ => None,
                 }
-            });
-            for_each_tail_expr(&body, tail_cb);
-
-            for ret_expr_arg in exprs_to_wrap {
-                let ok_wrapped = make::expr_call(
-                    make::expr_path(make::ext::ident_path("Ok")),
-                    make::arg_list(iter::once(ret_expr_arg.clone())),
-                );
-                builder.replace_ast(ret_expr_arg, ok_wrapped);
             }
+            WrapperKind::Option if operand_enum == result_enum => Some(TryFix::Ok),
+            _ => None,
+        };
+        if let Some(fix) = fix {
+            try_fixes.push((operand, fix));
+        }
+    }
+    let err_ty = err_ty
+        .zip(scope.module())
+        .and_then(|(ty, module)| ty.display_source_code(ctx.db(), module.into()).ok());
+
+    let call_sites = match &func {
+        Some(func) => call_sites(ctx, func, wrapper_enum),
+        None => FxHashMap::default(),
+    };
+    let mut call_site_edits = Vec::new();
+    if call_sites.values().flatten().any(|it| it.can_try) {
+        call_site_edits.push(CallSiteEdit::Try);
+    }
+    if !call_sites.is_empty() {
+        call_site_edits.push(CallSiteEdit::Unwrap);
+    }
+    // The plain variant comes last, it's the one which is picked by default.
+    call_site_edits.push(CallSiteEdit::None);
 
-            match ctx.config.snippet_cap {
-                Some(cap) => {
-                    let snippet = format!("Result<{}, ${{0:_}}>", type_ref);
-                    builder.replace_snippet(cap, type_ref.syntax().text_range(), snippet)
+    let (assist_id, group) = match kind {
+        WrapperKind::Result => ("wrap_return_type_in_result", "Wrap return type in Result…"),
+        WrapperKind::Option => ("wrap_return_type_in_option", "Wrap return type in Option…"),
+    };
+    for call_site_edit in call_site_edits {
+        let label = match (call_site_edit, kind) {
+            (CallSiteEdit::None, _) => format!("Wrap return type in {}", kind.name()),
+            (CallSiteEdit::Try, WrapperKind::Result) => {
+                "Wrap return type in Result, propagating errors with `?` at call sites".to_owned()
+            }
+            (CallSiteEdit::Try, WrapperKind::Option) => {
+                "Wrap return type in Option, propagating `None` with `?` at call sites".to_owned()
+            }
+            (CallSiteEdit::Unwrap, _) => {
+                format!("Wrap return type in {}, unwrapping it at call sites", kind.name())
+            }
+        };
+        acc.add_group(
+            &GroupLabel(group.to_owned()),
+            AssistId(assist_id, AssistKind::RefactorRewrite),
+            label,
+            type_ref.syntax().text_range(),
+            |builder| {
+                let empty = Vec::new();
+                let local_call_sites = match call_site_edit {
+                    CallSiteEdit::None => &empty,
+                    _ => call_sites.get(&ctx.file_id()).unwrap_or(&empty),
+                };
+
+                // All nodes have to be made mutable before the first edit.
+                let try_fixes: Vec<_> = try_fixes
+                    .iter()
+                    .map(|(operand, fix)| (builder.make_mut(operand.clone()), *fix))
+                    .collect();
+                let local_call_sites = make_mut_sorted(builder, local_call_sites);
+                let exprs_to_wrap: Vec<_> =
+                    exprs_to_wrap.iter().map(|it| builder.make_mut(it.clone())).collect();
+
+                for (operand, fix) in try_fixes {
+                    ted::replace(operand.syntax(), fix.apply(operand.clone()).syntax());
+                }
+                // Inner calls before outer ones, as the outer ones are rebuilt from their text. A
+                // call which is a tail expression of the function itself is edited and wrapped at
+                // once.
+                for call_site in &local_call_sites {
+                    if !exprs_to_wrap.contains(&call_site.call) {
+                        replace_call_site(call_site, call_site_edit);
+                    }
                 }
-                None => builder
-                    .replace(type_ref.syntax().text_range(), format!("Result<{}, _>", type_ref)),
+                for ret_expr_arg in exprs_to_wrap {
+                    let expr = local_call_sites
+                        .iter()
+                        .find(|it| it.call == ret_expr_arg)
+                        .and_then(|call_site| edit_call_site(call_site, call_site_edit))
+                        .unwrap_or_else(|| ret_expr_arg.clone());
+                    let wrapped = make::expr_call(
+                        make::expr_path(make::ext::ident_path(kind.ctor())),
+                        make::arg_list(iter::once(expr)),
+                    );
+                    ted::replace(ret_expr_arg.syntax(), wrapped.clone_for_update().syntax());
+                }
+
+                match (kind, ctx.config.snippet_cap) {
+                    (WrapperKind::Result, Some(cap)) => {
+                        let snippet = format!(
+                            "Result<{}, ${{0:{}}}>",
+                            type_ref,
+                            err_ty.as_deref().unwrap_or("_")
+                        );
+                        builder.replace_snippet(cap, type_ref.syntax().text_range(), snippet)
+                    }
+                    (WrapperKind::Result, None) => builder.replace(
+                        type_ref.syntax().text_range(),
+                        format!("Result<{}, {}>", type_ref, err_ty.as_deref().unwrap_or("_")),
+                    ),
+                    (WrapperKind::Option, _) => builder
+                        .replace(type_ref.syntax().text_range(), format!("Option<{}>", type_ref)),
+                }
+
+                if call_site_edit != CallSiteEdit::None {
+                    edit_other_call_sites(ctx, builder, &call_sites, call_site_edit);
+                }
+            },
+        )?;
+    }
+    Some(())
+}
+
+#[derive(Clone, Copy)]
+enum TryFix {
+    /// `option?` to `option.ok_or_else(|| todo!())?`.
+    OkOrElse,
+    /// `result?` to `result.map_err(|err| todo!())?`.
+    MapErr,
+    /// `result?` to `result.ok()?`.
+    Ok,
+}
+
+impl TryFix {
+    fn apply(self, operand: ast::Expr) -> ast::Expr {
+        let (method, arg) = match self {
+            TryFix::OkOrElse => {
+                ("ok_or_else", Some(make::expr_closure(None, make::ext::expr_todo())))
             }
-        },
-    )
+            TryFix::MapErr => {
+                let err = make::ident_pat(false, false, make::name("err"));
+                let param = make::untyped_param(err.into());
+                ("map_err", Some(make::expr_closure(Some(param), make::ext::expr_todo())))
+            }
+            TryFix::Ok => ("ok", None),
+        };
+        make::expr_method_call(operand, make::name_ref(method), make::arg_list(arg))
+            .clone_for_update()
+    }
+}
+
+/// The calls of `func`, by file.
+fn call_sites(
+    ctx: &AssistContext,
+    func: &ast::Fn,
+    wrapper_enum: hir::Enum,
+) -> FxHashMap<FileId, Vec<CallSite>> {
+    let mut res: FxHashMap<FileId, Vec<CallSite>> = FxHashMap::default();
+    let def = match ctx.sema.to_def(func) {
+        Some(it) => it,
+        None => return res,
+    };
+    for (file_id, refs) in Definition::Function(def).usages(&ctx.sema).all() {
+        for r in refs {
+            let call = match r.name.as_name_ref().and_then(call_for_name_ref) {
+                Some(it) => it,
+                None => continue,
+            };
+            // Recursive calls are in a function which returns the wrapper now.
+            let in_func = file_id == ctx.file_id()
+                && func.syntax().text_range().contains_range(call.syntax().text_range());
+            let can_try = in_func
+                || call
+                    .syntax()
+                    .ancestors()
+                    .find_map(|node| {
+                        match_ast! {
+                            match node {
+                                ast::Fn(it) => Some(ctx.sema.to_def(&it)),
+                                ast::ClosureExpr(_) => Some(None),
+                                _ => None,
+                            }
+                        }
+                    })
+                    .flatten()
+                    .map_or(false, |caller| {
+                        caller.ret_type(ctx.db()).as_adt() == Some(hir::Adt::Enum(wrapper_enum))
+                    });
+            res.entry(file_id).or_default().push(CallSite { call, can_try });
+        }
+    }
+    res
+}
+
+/// The call expression `name_ref` is the callee of, like `foo` in `foo(1)` or `x.foo()`.
+fn call_for_name_ref(name_ref: &ast::NameRef) -> Option<ast::Expr> {
+    let call = match name_ref.syntax().parent().and_then(ast::MethodCallExpr::cast) {
+        Some(method_call) => ast::Expr::MethodCallExpr(method_call),
+        None => {
+            let path_expr = name_ref.syntax().ancestors().find_map(ast::PathExpr::cast)?;
+            if path_expr.path()?.segment()?.name_ref()? != *name_ref {
+                return None;
+            }
+            let call = ast::CallExpr::cast(path_expr.syntax().parent()?)?;
+            if call.expr()?.syntax() != path_expr.syntax() {
+                return None;
+            }
+            ast::Expr::CallExpr(call)
+        }
+    };
+    // Already handled, the code was broken before.
+    if call.syntax().parent().map_or(false, |it| ast::TryExpr::can_cast(it.kind())) {
+        return None;
+    }
+    Some(call)
+}
+
+fn edit_call_site(call_site: &CallSite, edit: CallSiteEdit) -> Option<ast::Expr> {
+    let call = call_site.call.clone();
+    match edit {
+        CallSiteEdit::Try if call_site.can_try => Some(make::expr_try(call)),
+        CallSiteEdit::Unwrap => {
+            Some(make::expr_method_call(call, make::name_ref("unwrap"), make::arg_list(None)))
+        }
+        _ => None,
+    }
+}
+
+fn replace_call_site(call_site: &CallSite, edit: CallSiteEdit) {
+    if let Some(new) = edit_call_site(call_site, edit) {
+        ted::replace(call_site.call.syntax(), new.clone_for_update().syntax());
+    }
+}
+
+fn edit_other_call_sites(
+    ctx: &AssistContext,
+    builder: &mut AssistBuilder,
+    call_sites: &FxHashMap<FileId, Vec<CallSite>>,
+    edit: CallSiteEdit,
+) {
+    for (&file_id, call_sites) in call_sites {
+        if file_id == ctx.file_id() {
+            continue;
+        }
+        builder.edit_file(file_id);
+        for call_site in &make_mut_sorted(builder, call_sites) {
+            replace_call_site(call_site, edit);
+        }
+    }
+}
+
+/// Mutable copies of the call sites, the inner ones like `a` in `b(a())` first.
+fn make_mut_sorted(builder: &mut AssistBuilder, call_sites: &[CallSite]) -> Vec<CallSite> {
+    let mut res: Vec<_> = call_sites
+        .iter()
+        .map(|it| CallSite { call: builder.make_mut(it.call.clone()), ..*it })
+        .collect();
+    res.sort_by_key(|it| {
+        let range = it.call.syntax().text_range();
+        (range.end(), std::cmp::Reverse(range.start()))
+    });
+    res
 }
 
 fn tail_cb_impl(acc: &mut Vec<ast::Expr>, e: &ast::Expr) {
@@ -104,7 +443,7 @@ fn tail_cb_impl(acc: &mut Vec<ast::Expr>, e: &ast::Expr) {
 
 #[cfg(test)]
 mod tests {
-    use crate::tests::{check_assist, check_assist_not_applicable};
+    use crate::tests::{check_assist, check_assist_by_label, check_assist_not_applicable};
 
     use super::*;
 
@@ -977,4 +1316,168 @@ fn foo(the_field: u32) -> Result<u32, ${0:_}> {
 "#,
         );
     }
+
+    #[test]
+    fn wrap_return_type_in_option_simple() {
+        check_assist(
+            wrap_return_type_in_option,
+            r#"
+//- minicore: option
+fn foo() -> i3$02 {
+    if true {
+        return 1;
+    }
+    2
+}
+"#,
+            r#"
+fn foo() -> Option<i32> {
+    if true {
+        return Some(1);
+    }
+    Some(2)
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn wrap_return_type_in_option_already_option() {
+        check_assist_not_applicable(
+            wrap_return_type_in_option,
+            r#"
+//- minicore: option
+fn foo() -> Option<i32$0> {
+    None
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn wrap_return_type_in_result_stubs_incompatible_try() {
+        check_assist(
+            wrap_return_type_in_result,
+            r#"
+//- minicore: option, result
+fn parse() -> Result<u32, u8> { Ok(1) }
+fn other() -> Result<u32, u16> { Ok(2) }
+fn first() -> Option<u32> { None }
+fn foo() -> u3$02 {
+    let a = parse()?;
+    let b = other()?;
+    let c = first()?;
+    let f = || first()?;
+    a + b + c
+}
+"#,
+            r#"
+fn parse() -> Result<u32, u8> { Ok(1) }
+fn other() -> Result<u32, u16> { Ok(2) }
+fn first() -> Option<u32> { None }
+fn foo() -> Result<u32, ${0:u8}> {
+    let a = parse()?;
+    let b = other().map_err(|err| todo!())?;
+    let c = first().ok_or_else(|| todo!())?;
+    let f = || first()?;
+    Ok(a + b + c)
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn wrap_return_type_in_option_converts_result_try() {
+        check_assist(
+            wrap_return_type_in_option,
+            r#"
+//- minicore: option, result
+fn parse() -> Result<u32, u8> { Ok(1) }
+fn foo() -> u3$02 {
+    parse()? + 1
+}
+"#,
+            r#"
+fn parse() -> Result<u32, u8> { Ok(1) }
+fn foo() -> Option<u32> {
+    Some(parse().ok()? + 1)
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn wrap_return_type_in_result_propagates_at_call_sites() {
+        check_assist_by_label(
+            wrap_return_type_in_result,
+            r#"
+//- minicore: result
+fn foo() -> i3$02 { 42 }
+fn caller() -> Result<i32, ()> {
+    let x = foo();
+    Ok(x)
+}
+fn other() -> i32 { foo() + 1 }
+"#,
+            r#"
+fn foo() -> Result<i32, ${0:_}> { Ok(42) }
+fn caller() -> Result<i32, ()> {
+    let x = foo()?;
+    Ok(x)
+}
+fn other() -> i32 { foo() + 1 }
+"#,
+            "Wrap return type in Result, propagating errors with `?` at call sites",
+        );
+    }
+
+    #[test]
+    fn wrap_return_type_in_result_unwraps_at_call_sites() {
+        check_assist_by_label(
+            wrap_return_type_in_result,
+            r#"
+//- minicore: result
+//- /main.rs
+mod other;
+fn foo() -> i3$02 { 42 }
+fn caller() -> Result<i32, ()> {
+    let x = foo();
+    Ok(x)
+}
+//- /other.rs
+fn other() -> i32 { crate::foo() + 1 }
+"#,
+            r#"
+//- /main.rs
+mod other;
+fn foo() -> Result<i32, ${0:_}> { Ok(42) }
+fn caller() -> Result<i32, ()> {
+    let x = foo().unwrap();
+    Ok(x)
+}
+//- /other.rs
+fn other() -> i32 { crate::foo().unwrap() + 1 }
+"#,
+            "Wrap return type in Result, unwrapping it at call sites",
+        );
+    }
+
+    #[test]
+    fn wrap_return_type_in_option_recursive_call() {
+        check_assist_by_label(
+            wrap_return_type_in_option,
+            r#"
+//- minicore: option
+fn fact(n: u32) -> u3$02 {
+    if n == 0 { 1 } else { n * fact(n - 1) }
+}
+"#,
+            r#"
+fn fact(n: u32) -> Option<u32> {
+    if n == 0 { Some(1) } else { Some(n * fact(n - 1)?) }
+}
+"#,
+            "Wrap return type in Option, propagating `None` with `?` at call sites",
+        );
+    }
 }
@@ -75,7 +75,7 @@ use syntax::TextRange;
 
 pub(crate) use crate::assist_context::{AssistContext, Assists};
 
-pub use assist_config::AssistConfig;
+pub use assist_config::{AssistConfig, BoundsPlacement, QualifyItemKind};
 pub use ide_db::assists::{
     Assist, AssistId, AssistKind, AssistResolveStrategy, GroupLabel, SingleResolve,
 };
@@ -116,6 +116,10 @@ mod handlers {
     mod convert_integer_literal;
     mod convert_into_to_from;
     mod convert_iter_for_each_to_for;
+    mod convert_let_chain;
+    mod convert_let_else_to_match;
+    mod convert_method_to_assoc_fn;
+    mod convert_named_struct_to_tuple_struct;
     mod convert_tuple_struct_to_named_struct;
     mod convert_to_guarded_return;
     mod convert_while_to_loop;
@@ -123,6 +127,7 @@ mod handlers {
     mod expand_glob_import;
     mod extract_function;
     mod extract_module;
+    mod extract_string_to_const;
     mod extract_struct_from_enum_variant;
     mod extract_type_alias;
     mod extract_variable;
@@ -146,21 +151,31 @@ mod handlers {
     mod generate_is_empty_from_len;
     mod generate_new;
     mod generate_setter;
+    mod generate_test_module;
     mod generate_delegate_methods;
+    mod generate_delegate_trait;
     mod add_return_type;
     mod inline_call;
     mod inline_local_variable;
     mod inline_type_alias;
     mod introduce_named_lifetime;
+    mod introduce_parameter;
     mod invert_if;
+    mod mark_default_variant;
     mod merge_imports;
+    mod merge_concat_literals;
     mod merge_match_arms;
+    mod migrate_to_std_lazy;
+    mod minimize_visibility;
     mod move_bounds;
     mod move_guard;
+    mod normalize_bounds;
+    mod move_module_from_file;
     mod move_module_to_file;
     mod move_to_mod_rs;
     mod move_from_mod_rs;
     mod number_representation;
+    mod optimize_field_order;
     mod promote_local_to_const;
     mod pull_assignment_up;
     mod qualify_path;
@@ -173,6 +188,7 @@ mod handlers {
     mod reorder_impl;
     mod replace_try_expr_with_match;
     mod replace_derive_with_manual_impl;
+    mod replace_glob_import;
     mod replace_if_let_with_match;
     mod introduce_named_generic;
     mod replace_let_with_if_let;
@@ -181,7 +197,9 @@ mod handlers {
     mod replace_turbofish_with_explicit_type;
     mod split_import;
     mod sort_items;
+    mod sort_match_arms;
     mod toggle_ignore;
+    mod unmerge_match_arm;
     mod unmerge_use;
     mod unwrap_block;
     mod unwrap_result_return_type;
@@ -205,11 +223,19 @@ mod handlers {
             convert_into_to_from::convert_into_to_from,
             convert_iter_for_each_to_for::convert_iter_for_each_to_for,
             convert_iter_for_each_to_for::convert_for_loop_with_for_each,
+            convert_let_chain::convert_nested_if_to_let_chain,
+            convert_let_chain::convert_let_chain_to_nested_if,
+            convert_let_else_to_match::convert_let_else_to_match,
+            convert_let_else_to_match::convert_to_let_else,
+            convert_method_to_assoc_fn::convert_method_to_assoc_fn,
+            convert_method_to_assoc_fn::convert_assoc_fn_to_method,
+            convert_named_struct_to_tuple_struct::convert_named_struct_to_tuple_struct,
             convert_to_guarded_return::convert_to_guarded_return,
             convert_tuple_struct_to_named_struct::convert_tuple_struct_to_named_struct,
             convert_while_to_loop::convert_while_to_loop,
             destructure_tuple_binding::destructure_tuple_binding,
             expand_glob_import::expand_glob_import,
+            extract_string_to_const::extract_string_to_const,
             extract_struct_from_enum_variant::extract_struct_from_enum_variant,
             extract_type_alias::extract_type_alias,
             fix_visibility::fix_visibility,
@@ -235,16 +261,26 @@ mod handlers {
             inline_type_alias::inline_type_alias,
             introduce_named_generic::introduce_named_generic,
             introduce_named_lifetime::introduce_named_lifetime,
+            introduce_parameter::introduce_field,
+            introduce_parameter::introduce_parameter,
             invert_if::invert_if,
+            mark_default_variant::mark_default_variant,
+            merge_concat_literals::merge_concat_literals,
             merge_imports::merge_imports,
             merge_match_arms::merge_match_arms,
+            migrate_to_std_lazy::migrate_to_std_lazy,
+            minimize_visibility::minimize_visibility,
             move_bounds::move_bounds_to_where_clause,
+            normalize_bounds::normalize_bounds,
             move_guard::move_arm_cond_to_match_guard,
             move_guard::move_guard_to_arm_body,
+            move_module_from_file::move_module_from_file,
             move_module_to_file::move_module_to_file,
             move_to_mod_rs::move_to_mod_rs,
             move_from_mod_rs::move_from_mod_rs,
             number_representation::reformat_number_literal,
+            optimize_field_order::optimize_field_order,
+            optimize_field_order::add_padding_comments,
             pull_assignment_up::pull_assignment_up,
             promote_local_to_const::promote_local_to_const,
             qualify_path::qualify_path,
@@ -257,19 +293,24 @@ mod handlers {
             remove_unused_param::remove_unused_param,
             reorder_fields::reorder_fields,
             reorder_impl::reorder_impl,
+            reorder_impl::reorder_impl_with_default_stubs,
             replace_try_expr_with_match::replace_try_expr_with_match,
             replace_derive_with_manual_impl::replace_derive_with_manual_impl,
+            replace_glob_import::replace_glob_import,
             replace_if_let_with_match::replace_if_let_with_match,
             replace_if_let_with_match::replace_match_with_if_let,
             replace_let_with_if_let::replace_let_with_if_let,
             replace_turbofish_with_explicit_type::replace_turbofish_with_explicit_type,
             replace_qualified_name_with_use::replace_qualified_name_with_use,
             sort_items::sort_items,
+            sort_match_arms::sort_match_arms,
             split_import::split_import,
             toggle_ignore::toggle_ignore,
+            unmerge_match_arm::unmerge_match_arm,
             unmerge_use::unmerge_use,
             unwrap_block::unwrap_block,
             unwrap_result_return_type::unwrap_result_return_type,
+            wrap_return_type_in_result::wrap_return_type_in_option,
             wrap_return_type_in_result::wrap_return_type_in_result,
             // These are manually sorted for better priorities. By default,
             // priority is determined by the size of the target range (smaller
@@ -289,7 +330,9 @@ mod handlers {
             generate_getter::generate_getter,
             generate_getter::generate_getter_mut,
             generate_setter::generate_setter,
+            generate_test_module::generate_test_module,
             generate_delegate_methods::generate_delegate_methods,
+            generate_delegate_trait::generate_delegate_trait,
             generate_deref::generate_deref,
             // Are you sure you want to add new assist here, and not to the
             // sorted list above?
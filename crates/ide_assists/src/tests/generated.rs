@@ -147,6 +147,29 @@ fn handle(action: Action) {
     )
 }
 
+#[test]
+fn doctest_add_padding_comments() {
+    check_doc_test(
+        "add_padding_comments",
+        r#####"
+#[repr(C)]
+struct $0Foo {
+    a: u8,
+    b: u32,
+}
+"#####,
+        r#####"
+#[repr(C)]
+struct Foo {
+    // offset 0, size 1, followed by 3 bytes of padding
+    a: u8,
+    // offset 4, size 4
+    b: u32,
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_add_return_type() {
     check_doc_test(
@@ -230,6 +253,31 @@ pub(crate) fn frobnicate() {}
     )
 }
 
+#[test]
+fn doctest_convert_assoc_fn_to_method() {
+    check_doc_test(
+        "convert_assoc_fn_to_method",
+        r#####"
+struct Foo;
+impl Foo {
+    fn frobnicate($0this: &Foo, x: u32) {}
+}
+fn main() {
+    Foo::frobnicate(&Foo, 92);
+}
+"#####,
+        r#####"
+struct Foo;
+impl Foo {
+    fn frobnicate(&self, x: u32) {}
+}
+fn main() {
+    Foo.frobnicate(92);
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_convert_bool_then_to_if() {
     check_doc_test(
@@ -364,6 +412,139 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_convert_let_chain_to_nested_if() {
+    check_doc_test(
+        "convert_let_chain_to_nested_if",
+        r#####"
+fn main() {
+    if$0 let Some(a) = foo() && let Some(b) = bar(a) {
+        baz(b);
+    }
+}
+"#####,
+        r#####"
+fn main() {
+    if let Some(a) = foo() {
+        if let Some(b) = bar(a) {
+            baz(b);
+        }
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_convert_let_else_to_match() {
+    check_doc_test(
+        "convert_let_else_to_match",
+        r#####"
+//- minicore: option
+fn main(opt: Option<i32>) {
+    $0let Some(x) = opt else { return };
+}
+"#####,
+        r#####"
+fn main(opt: Option<i32>) {
+    let x = match opt {
+        Some(x) => x,
+        _ => return,
+    };
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_convert_method_to_assoc_fn() {
+    check_doc_test(
+        "convert_method_to_assoc_fn",
+        r#####"
+struct Foo;
+impl Foo {
+    fn frobnicate(&$0self, x: u32) {}
+}
+fn main() {
+    Foo.frobnicate(92);
+}
+"#####,
+        r#####"
+struct Foo;
+impl Foo {
+    fn frobnicate(this: &Self, x: u32) {}
+}
+fn main() {
+    Foo::frobnicate(&Foo, 92);
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_convert_named_struct_to_tuple_struct() {
+    check_doc_test(
+        "convert_named_struct_to_tuple_struct",
+        r#####"
+struct Point$0 { x: f32, y: f32 }
+
+impl Point {
+    pub fn new(x: f32, y: f32) -> Self {
+        Point { x, y }
+    }
+
+    pub fn x(&self) -> f32 {
+        self.x
+    }
+
+    pub fn y(&self) -> f32 {
+        self.y
+    }
+}
+"#####,
+        r#####"
+struct Point(f32, f32);
+
+impl Point {
+    pub fn new(x: f32, y: f32) -> Self {
+        Point(x, y)
+    }
+
+    pub fn x(&self) -> f32 {
+        self.0
+    }
+
+    pub fn y(&self) -> f32 {
+        self.1
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_convert_nested_if_to_let_chain() {
+    check_doc_test(
+        "convert_nested_if_to_let_chain",
+        r#####"
+fn main() {
+    if$0 let Some(a) = foo() {
+        if let Some(b) = bar(a) {
+            baz(b);
+        }
+    }
+}
+"#####,
+        r#####"
+fn main() {
+    if let Some(a) = foo() && let Some(b) = bar(a) {
+        baz(b);
+    }
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_convert_to_guarded_return() {
     check_doc_test(
@@ -388,6 +569,30 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_convert_to_let_else() {
+    check_doc_test(
+        "convert_to_let_else",
+        r#####"
+//- minicore: option
+//- /main.rs crate:main edition:2021
+fn main(opt: Option<i32>) {
+    $0let x = match opt {
+        Some(x) => x,
+        None => return,
+    };
+}
+"#####,
+        r#####"
+fn main(opt: Option<i32>) {
+    let Some(x) = opt else {
+        return
+    };
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_convert_tuple_struct_to_named_struct() {
     check_doc_test(
@@ -555,6 +760,31 @@ fn bar(name: i32) -> i32 {
     )
 }
 
+#[test]
+fn doctest_extract_string_to_const() {
+    check_doc_test(
+        "extract_string_to_const",
+        r#####"
+fn greet() -> &'static str {
+    "Hello, $0World!"
+}
+fn main() {
+    let greeting = "Hello, World!";
+}
+"#####,
+        r#####"
+const HELLO_WORLD: &str = "Hello, World!";
+
+fn greet() -> &'static str {
+    HELLO_WORLD
+}
+fn main() {
+    let greeting = HELLO_WORLD;
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_extract_struct_from_enum_variant() {
     check_doc_test(
@@ -794,6 +1024,49 @@ impl Person {
     )
 }
 
+#[test]
+fn doctest_generate_delegate_trait() {
+    check_doc_test(
+        "generate_delegate_trait",
+        r#####"
+trait Speak {
+    fn speak(&self, times: u32) -> u32;
+}
+struct Dog;
+impl Speak for Dog {
+    fn speak(&self, times: u32) -> u32 {
+        times
+    }
+}
+
+struct Loud {
+    do$0g: Dog,
+}
+"#####,
+        r#####"
+trait Speak {
+    fn speak(&self, times: u32) -> u32;
+}
+struct Dog;
+impl Speak for Dog {
+    fn speak(&self, times: u32) -> u32 {
+        times
+    }
+}
+
+struct Loud {
+    dog: Dog,
+}
+
+impl Speak for Loud {
+    $0fn speak(&self, times: u32) -> u32 {
+        self.dog.speak(times)
+    }
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_generate_deref() {
     check_doc_test(
@@ -1169,6 +1442,46 @@ impl Person {
     )
 }
 
+#[test]
+fn doctest_generate_test_module() {
+    check_doc_test(
+        "generate_test_module",
+        r#####"
+struct Counter(u32);
+
+impl$0 Counter {
+    pub fn new(start: u32) -> Self { Counter(start) }
+    pub fn bump(&mut self) { self.0 += 1 }
+}
+"#####,
+        r#####"
+struct Counter(u32);
+
+impl Counter {
+    pub fn new(start: u32) -> Self { Counter(start) }
+    pub fn bump(&mut self) { self.0 += 1 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        let start: u32 = todo!();
+        assert_eq!(Counter::new(start), );
+    }
+
+    #[test]
+    fn test_bump() {
+        let mut counter: Counter = todo!();
+        counter.bump();
+    }
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_inline_call() {
     check_doc_test(
@@ -1266,6 +1579,35 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_introduce_field() {
+    check_doc_test(
+        "introduce_field",
+        r#####"
+struct Client { name: String }
+impl Client {
+    fn retries(&self) -> u32 {
+        $03$0
+    }
+}
+fn main() {
+    let client = Client { name: String::new() };
+}
+"#####,
+        r#####"
+struct Client { name: String, var_name: u32 }
+impl Client {
+    fn retries(&self) -> u32 {
+        self.var_name
+    }
+}
+fn main() {
+    let client = Client { name: String::new(), var_name: 3 };
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_introduce_named_generic() {
     check_doc_test(
@@ -1304,6 +1646,31 @@ impl<'a> Cursor<'a> {
     )
 }
 
+#[test]
+fn doctest_introduce_parameter() {
+    check_doc_test(
+        "introduce_parameter",
+        r#####"
+fn connect() {
+    open($0"localhost"$0);
+}
+fn open(host: &str) {}
+fn main() {
+    connect();
+}
+"#####,
+        r#####"
+fn connect(host: &str) {
+    open(host);
+}
+fn open(host: &str) {}
+fn main() {
+    connect("localhost");
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_invert_if() {
     check_doc_test(
@@ -1372,6 +1739,48 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_mark_default_variant() {
+    check_doc_test(
+        "mark_default_variant",
+        r#####"
+//- minicore: default, derive
+#[derive(Clone)]
+enum Version {
+    Undefined,
+    Minor$0,
+    Major,
+}
+"#####,
+        r#####"
+#[derive(Clone, Default)]
+enum Version {
+    Undefined,
+    #[default]
+    Minor,
+    Major,
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_merge_concat_literals() {
+    check_doc_test(
+        "merge_concat_literals",
+        r#####"
+fn main() {
+    let s = concat!$0("Hello, ", "World", '!', 1);
+}
+"#####,
+        r#####"
+fn main() {
+    let s = "Hello, World!1";
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_merge_imports() {
     check_doc_test(
@@ -1412,6 +1821,54 @@ fn handle(action: Action) {
     )
 }
 
+#[test]
+fn doctest_migrate_to_std_lazy() {
+    check_doc_test(
+        "migrate_to_std_lazy",
+        r#####"
+//- /main.rs crate:main deps:std,lazy_static
+lazy_static::lazy_static! {
+    static ref $0NAMES: Vec<u8> = Vec::new();
+}
+//- /lazy_static.rs crate:lazy_static
+#[macro_export]
+macro_rules! lazy_static { ($($tt:tt)*) => {} }
+//- /std.rs crate:std
+pub mod sync { pub struct LazyLock<T>(T); }
+"#####,
+        r#####"
+use std::sync::LazyLock;
+
+static NAMES: LazyLock<Vec<u8>> = LazyLock::new(|| Vec::new());
+"#####,
+    )
+}
+
+#[test]
+fn doctest_minimize_visibility() {
+    check_doc_test(
+        "minimize_visibility",
+        r#####"
+mod $0m {
+    pub fn helper() {}
+    pub fn frobnicate() { helper() }
+}
+fn main() {
+    m::frobnicate();
+}
+"#####,
+        r#####"
+mod m {
+    fn helper() {}
+    pub(crate) fn frobnicate() { helper() }
+}
+fn main() {
+    m::frobnicate();
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_move_arm_cond_to_match_guard() {
     check_doc_test(
@@ -1501,6 +1958,24 @@ fn handle(action: Action) {
     )
 }
 
+#[test]
+fn doctest_move_module_from_file() {
+    check_doc_test(
+        "move_module_from_file",
+        r#####"
+//- /main.rs
+mod $0foo;
+//- /foo.rs
+fn t() {}
+"#####,
+        r#####"
+mod foo {
+    fn t() {}
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_move_module_to_file() {
     check_doc_test(
@@ -1532,6 +2007,44 @@ fn t() {}
     )
 }
 
+#[test]
+fn doctest_normalize_bounds() {
+    check_doc_test(
+        "normalize_bounds",
+        r#####"
+trait Shape {}
+trait Circle: Shape {}
+fn draw<$0T: Circle + Clone>(t: T) where T: Shape + Clone {}
+"#####,
+        r#####"
+trait Shape {}
+trait Circle: Shape {}
+fn draw<T>(t: T) where T: Circle + Clone {}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_optimize_field_order() {
+    check_doc_test(
+        "optimize_field_order",
+        r#####"
+struct $0Foo {
+    a: u8,
+    b: u64,
+    c: u16,
+}
+"#####,
+        r#####"
+struct Foo {
+    b: u64,
+    c: u16,
+    a: u8,
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_promote_local_to_const() {
     check_doc_test(
@@ -1771,6 +2284,40 @@ impl Foo for Bar {
     )
 }
 
+#[test]
+fn doctest_reorder_impl_with_default_stubs() {
+    check_doc_test(
+        "reorder_impl_with_default_stubs",
+        r#####"
+trait Foo {
+    fn a() {}
+    fn b(&self) -> u32 { 0 }
+    fn c() {}
+}
+
+struct Bar;
+$0impl Foo for Bar {
+    fn c() {}
+    fn a() {}
+}
+"#####,
+        r#####"
+trait Foo {
+    fn a() {}
+    fn b(&self) -> u32 { 0 }
+    fn c() {}
+}
+
+struct Bar;
+impl Foo for Bar {
+    fn a() {}
+    // fn b(&self) -> u32 { .. }
+    fn c() {}
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_replace_char_with_string() {
     check_doc_test(
@@ -1812,6 +2359,35 @@ impl Debug for S {
     )
 }
 
+#[test]
+fn doctest_replace_glob_import() {
+    check_doc_test(
+        "replace_glob_import",
+        r#####"
+mod foo {
+    pub struct Bar;
+    pub struct Baz;
+    pub fn qux() {}
+}
+
+use foo::*$0;
+
+fn f(bar: Bar) { qux() }
+"#####,
+        r#####"
+mod foo {
+    pub struct Bar;
+    pub struct Baz;
+    pub fn qux() {}
+}
+
+use foo::{Bar, qux};
+
+fn f(bar: Bar) { qux() }
+"#####,
+    )
+}
+
 #[test]
 fn doctest_replace_if_let_with_match() {
     check_doc_test(
@@ -2059,6 +2635,35 @@ enum Animal {
     )
 }
 
+#[test]
+fn doctest_sort_match_arms() {
+    check_doc_test(
+        "sort_match_arms",
+        r#####"
+enum Color { Red, Green, Blue }
+
+fn name(color: Color) -> &'static str {
+    $0match color {
+        Color::Blue => "blue",
+        Color::Red => "red",
+        Color::Green => "green",
+    }
+}
+"#####,
+        r#####"
+enum Color { Red, Green, Blue }
+
+fn name(color: Color) -> &'static str {
+    match color {
+        Color::Red => "red",
+        Color::Green => "green",
+        Color::Blue => "blue",
+    }
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_split_import() {
     check_doc_test(
@@ -2092,6 +2697,32 @@ fn arithmetics {
     )
 }
 
+#[test]
+fn doctest_unmerge_match_arm() {
+    check_doc_test(
+        "unmerge_match_arm",
+        r#####"
+enum Action { Move { distance: u32 }, Stop }
+
+fn handle(action: Action) {
+    match action {
+        Action::Move { .. } $0| Action::Stop => foo(),
+    }
+}
+"#####,
+        r#####"
+enum Action { Move { distance: u32 }, Stop }
+
+fn handle(action: Action) {
+    match action {
+        Action::Move { .. } => foo(),
+        Action::Stop => foo(),
+    }
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_unmerge_use() {
     check_doc_test(
@@ -2139,6 +2770,20 @@ fn foo() -> i32 { 42i32 }
     )
 }
 
+#[test]
+fn doctest_wrap_return_type_in_option() {
+    check_doc_test(
+        "wrap_return_type_in_option",
+        r#####"
+//- minicore: option
+fn foo() -> i32$0 { 42i32 }
+"#####,
+        r#####"
+fn foo() -> Option<i32> { Some(42i32) }
+"#####,
+    )
+}
+
 #[test]
 fn doctest_wrap_return_type_in_result() {
     check_doc_test(
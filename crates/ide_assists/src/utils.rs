@@ -6,7 +6,10 @@ use itertools::Itertools;
 
 pub(crate) use gen_trait_fn_body::gen_trait_fn_body;
 use hir::{db::HirDatabase, HirDisplay, Semantics};
-use ide_db::{famous_defs::FamousDefs, path_transform::PathTransform, RootDatabase, SnippetCap};
+use ide_db::{
+    famous_defs::FamousDefs, path_transform::PathTransform,
+    syntax_helpers::node_ext::parse_tt_as_comma_sep_paths, RootDatabase, SnippetCap,
+};
 use stdx::format_to;
 use syntax::{
     ast::{
@@ -206,6 +209,33 @@ pub(crate) fn render_snippet(_cap: SnippetCap, node: &SyntaxNode, cursor: Cursor
     }
 }
 
+/// Finds the `#[derive(...)]` attribute of `item` which lists `trait_name`,
+/// returning it with all the derives it lists.
+pub(crate) fn find_derive(
+    item: &impl HasAttrs,
+    trait_name: &str,
+) -> Option<(ast::Attr, Vec<ast::Path>)> {
+    item.attrs().find_map(|attr| {
+        let (name, tt) = attr.as_simple_call()?;
+        if name != "derive" {
+            return None;
+        }
+        let derives = parse_tt_as_comma_sep_paths(tt)?;
+        let lists_trait = derives.iter().any(|path| {
+            path.segment().and_then(|it| it.name_ref()).map_or(false, |it| it.text() == trait_name)
+        });
+        lists_trait.then(|| (attr, derives))
+    })
+}
+
+/// Deletes `attr` along with the whitespace following it.
+pub(crate) fn delete_attr(builder: &mut AssistBuilder, attr: &ast::Attr) {
+    builder.delete(attr.syntax().text_range());
+    if let Some(ws) = attr.syntax().next_sibling_or_token().filter(|it| it.kind() == WHITESPACE) {
+        builder.delete(ws.text_range());
+    }
+}
+
 pub(crate) fn vis_offset(node: &SyntaxNode) -> TextSize {
     node.children_with_tokens()
         .find(|it| !matches!(it.kind(), WHITESPACE | COMMENT | ATTR))
@@ -2,6 +2,7 @@
 
 pub(crate) mod attribute;
 pub(crate) mod dot;
+pub(crate) mod env_vars;
 pub(crate) mod extern_abi;
 pub(crate) mod flyimport;
 pub(crate) mod fn_param;
@@ -14,6 +15,7 @@ pub(crate) mod postfix;
 pub(crate) mod qualified_path;
 pub(crate) mod record;
 pub(crate) mod snippet;
+pub(crate) mod term_search;
 pub(crate) mod trait_impl;
 pub(crate) mod unqualified_path;
 pub(crate) mod use_;
@@ -23,16 +25,18 @@ use std::iter;
 
 use hir::{db::HirDatabase, known, ScopeDef};
 use ide_db::SymbolKind;
+use syntax::ast;
+use text_edit::Indel;
 
 use crate::{
     context::Visible,
     item::Builder,
     render::{
         const_::render_const,
-        function::{render_fn, render_method},
+        function::{render_fn, render_method, render_method_qualified},
         literal::{render_struct_literal, render_variant_lit},
         macro_::render_macro,
-        pattern::{render_struct_pat, render_variant_pat},
+        pattern::{render_slice_pats, render_struct_pat, render_variant_pat},
         render_field, render_resolution, render_resolution_simple, render_tuple_field,
         type_alias::{render_type_alias, render_type_alias_with_eq},
         union_literal::render_union_literal,
@@ -195,6 +199,47 @@ impl Completions {
         ));
     }
 
+    /// Adds a method several traits in scope have, to be called with fully qualified syntax:
+    /// `Trait::method(&receiver)`.
+    pub(crate) fn add_method_qualified(
+        &mut self,
+        ctx: &CompletionContext,
+        func: hir::Function,
+        receiver: &ast::Expr,
+    ) {
+        let is_private_editable = match ctx.is_visible(&func) {
+            Visible::Yes => false,
+            Visible::Editable => true,
+            Visible::No => return,
+        };
+        self.add(render_method_qualified(
+            RenderContext::new(ctx).private_editable(is_private_editable),
+            receiver,
+            func,
+        ));
+    }
+
+    /// Adds a method the receiver only has once `where_bound` is inserted into the
+    /// enclosing function.
+    pub(crate) fn add_method_with_where_bound(
+        &mut self,
+        ctx: &CompletionContext,
+        func: hir::Function,
+        where_bound: Indel,
+    ) {
+        let is_private_editable = match ctx.is_visible(&func) {
+            Visible::Yes => false,
+            Visible::Editable => true,
+            Visible::No => return,
+        };
+        self.add(render_method(
+            RenderContext::new(ctx).private_editable(is_private_editable).where_bound(where_bound),
+            None,
+            None,
+            func,
+        ));
+    }
+
     pub(crate) fn add_const(&mut self, ctx: &CompletionContext, konst: hir::Const) {
         let is_private_editable = match ctx.is_visible(&konst) {
             Visible::Yes => false,
@@ -338,7 +383,28 @@ impl Completions {
         strukt: hir::Struct,
         local_name: Option<hir::Name>,
     ) {
-        self.add_opt(render_struct_pat(RenderContext::new(ctx), strukt, local_name));
+        self.add_opt(render_struct_pat(RenderContext::new(ctx), strukt, local_name, None));
+    }
+
+    pub(crate) fn add_qualified_struct_pat(
+        &mut self,
+        ctx: &CompletionContext,
+        strukt: hir::Struct,
+        path: hir::ModPath,
+    ) {
+        let path = Some(&path);
+        self.add_opt(render_struct_pat(RenderContext::new(ctx), strukt, None, path));
+    }
+
+    pub(crate) fn add_slice_pats(
+        &mut self,
+        ctx: &CompletionContext,
+        len: Option<u64>,
+        refutable: bool,
+    ) {
+        render_slice_pats(RenderContext::new(ctx), len, refutable)
+            .into_iter()
+            .for_each(|item| self.add(item));
     }
 }
 
@@ -64,6 +64,18 @@ pub(crate) fn complete_known_attribute_input(
             lint::complete_lint(acc, ctx, &existing_lints, &lints);
         }
         "cfg" => cfg::complete_cfg(acc, ctx),
+        "cfg_attr" => {
+            // Only the predicate is a cfg, the rest are the attributes it applies.
+            let offset = ctx.original_token.text_range().start();
+            let in_predicate = tt
+                .syntax()
+                .children_with_tokens()
+                .take_while(|it| it.text_range().end() <= offset)
+                .all(|it| it.kind() != T![,]);
+            if in_predicate {
+                cfg::complete_cfg(acc, ctx);
+            }
+        }
         _ => (),
     }
     Some(())
@@ -3,9 +3,12 @@
 use std::iter;
 
 use ide_db::SymbolKind;
+use itertools::Itertools;
 use syntax::SyntaxKind;
 
-use crate::{completions::Completions, context::CompletionContext, CompletionItem};
+use crate::{
+    completions::Completions, context::CompletionContext, CompletionItem, CompletionItemKind,
+};
 
 pub(crate) fn complete_cfg(acc: &mut Completions, ctx: &CompletionContext) {
     let add_completion = |item: &str| {
@@ -21,35 +24,69 @@ pub(crate) fn complete_cfg(acc: &mut Completions, ctx: &CompletionContext) {
     })
     .find(|t| matches!(t.kind(), SyntaxKind::IDENT));
 
+    // Known values come first, followed by the ones the crate is built with
+    // (features and cfgs set by build scripts included).
+    let potential_cfg = ctx.krate.map(|krate| krate.potential_cfg(ctx.db));
     match previous.as_ref().map(|p| p.text()) {
-        Some("target_arch") => KNOWN_ARCH.iter().copied().for_each(add_completion),
-        Some("target_env") => KNOWN_ENV.iter().copied().for_each(add_completion),
-        Some("target_os") => KNOWN_OS.iter().copied().for_each(add_completion),
-        Some("target_vendor") => KNOWN_VENDOR.iter().copied().for_each(add_completion),
-        Some("target_endian") => ["little", "big"].into_iter().for_each(add_completion),
         Some(name) => {
-            if let Some(krate) = ctx.krate {
-                krate.potential_cfg(ctx.db).get_cfg_values(name).cloned().for_each(|s| {
-                    let insert_text = format!(r#""{}""#, s);
-                    let mut item =
-                        CompletionItem::new(SymbolKind::BuiltinAttr, ctx.source_range(), s);
-                    item.insert_text(insert_text);
-
-                    acc.add(item.build());
-                })
+            let known: &[&str] = match name {
+                "target_arch" => &KNOWN_ARCH,
+                "target_env" => &KNOWN_ENV,
+                "target_os" => &KNOWN_OS,
+                "target_vendor" => &KNOWN_VENDOR,
+                "target_endian" => &["little", "big"],
+                "target_family" => &["unix", "wasm", "windows"],
+                "target_pointer_width" => &["16", "32", "64"],
+                "panic" => &["abort", "unwind"],
+                _ => &[],
             };
+            let from_crate = potential_cfg
+                .iter()
+                .flat_map(|cfg| cfg.get_cfg_values(name))
+                .map(|it| it.as_str())
+                .sorted();
+            known.iter().copied().chain(from_crate).unique().for_each(add_completion);
         }
         None => {
-            if let Some(krate) = ctx.krate {
-                krate.potential_cfg(ctx.db).get_cfg_keys().cloned().for_each(|s| {
-                    let item = CompletionItem::new(SymbolKind::BuiltinAttr, ctx.source_range(), s);
-                    acc.add(item.build());
-                })
+            for predicate in ["all", "any", "not"] {
+                let mut item =
+                    CompletionItem::new(CompletionItemKind::Keyword, ctx.source_range(), predicate);
+                if let Some(cap) = ctx.config.snippet_cap {
+                    item.insert_snippet(cap, format!("{}($0)", predicate));
+                }
+                acc.add(item.build());
             }
+            let from_crate = potential_cfg
+                .iter()
+                .flat_map(|cfg| cfg.get_cfg_keys())
+                .map(|it| it.as_str())
+                .sorted();
+            KNOWN_KEYS.iter().copied().chain(from_crate).unique().for_each(|key| {
+                let item = CompletionItem::new(SymbolKind::BuiltinAttr, ctx.source_range(), key);
+                acc.add(item.build());
+            });
         }
     };
 }
 
+const KNOWN_KEYS: [&str; 15] = [
+    "debug_assertions",
+    "doc",
+    "doctest",
+    "feature",
+    "panic",
+    "target_arch",
+    "target_endian",
+    "target_env",
+    "target_family",
+    "target_os",
+    "target_pointer_width",
+    "target_vendor",
+    "test",
+    "unix",
+    "windows",
+];
+
 const KNOWN_ARCH: [&str; 19] = [
     "aarch64",
     "arm",
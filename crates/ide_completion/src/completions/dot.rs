@@ -1,7 +1,10 @@
 //! Completes references after dot (fields and method calls).
 
 use either::Either;
+use ide_db::{helpers::mod_path_to_ast, traits::group_method_candidates};
 use rustc_hash::FxHashSet;
+use syntax::{ast, AstNode};
+use text_edit::Indel;
 
 use crate::{context::CompletionContext, patterns::ImmediateLocation, Completions};
 
@@ -25,7 +28,91 @@ pub(crate) fn complete_dot(acc: &mut Completions, ctx: &CompletionContext) {
             Either::Right(tuple_idx) => acc.add_tuple_field(ctx, None, tuple_idx, &ty),
         });
     }
-    complete_methods(ctx, &receiver_ty, |func| acc.add_method(ctx, func, None, None));
+    // `Trait::method(&receiver)` can't be spelled out once the parentheses are there.
+    let can_qualify = !matches!(
+        ctx.completion_location,
+        Some(ImmediateLocation::MethodCall { has_parens: true, .. })
+    );
+    let mut seen_methods = FxHashSet::default();
+    complete_methods(ctx, &receiver_ty, |func, is_ambiguous| {
+        seen_methods.insert(func.name(ctx.db));
+        if is_ambiguous && can_qualify {
+            acc.add_method_qualified(ctx, func, dot_receiver)
+        } else {
+            acc.add_method(ctx, func, None, None)
+        }
+    });
+    complete_methods_with_where_bound(acc, ctx, &receiver_ty, &seen_methods);
+}
+
+/// Completes the methods of the traits in scope which a generic receiver doesn't
+/// implement yet, adding the missing bound to the enclosing function.
+fn complete_methods_with_where_bound(
+    acc: &mut Completions,
+    ctx: &CompletionContext,
+    receiver: &hir::Type,
+    seen_methods: &FxHashSet<hir::Name>,
+) {
+    let param = match receiver.strip_references().as_type_param(ctx.db) {
+        Some(it) if !it.is_implicit(ctx.db) => it,
+        _ => return,
+    };
+    let fn_ = match &ctx.function_def {
+        Some(it) => it,
+        None => return,
+    };
+    // The bounds of methods in traits and trait impls are fixed by the trait.
+    let in_trait = fn_.syntax().ancestors().nth(2).map_or(false, |it| {
+        ast::Trait::can_cast(it.kind())
+            || ast::Impl::cast(it).map_or(false, |it| it.trait_().is_some())
+    });
+    if in_trait {
+        return;
+    }
+
+    let mut traits: Vec<hir::Trait> =
+        ctx.scope.visible_traits().into_iter().map(hir::Trait::from).collect();
+    traits.sort_by_key(|it| it.name(ctx.db).to_smol_str());
+    for trait_ in traits {
+        if trait_.type_or_const_param_count(ctx.db) > 0
+            || Some(trait_) == ctx.famous_defs().core_ops_Drop()
+            || receiver.impls_trait(ctx.db, trait_, &[])
+        {
+            continue;
+        }
+        let path =
+            ctx.module.and_then(|it| it.find_use_path(ctx.db, hir::ModuleDef::Trait(trait_)));
+        let path = match path {
+            Some(it) => mod_path_to_ast(&it),
+            None => continue,
+        };
+        let bound = format!("{}: {}", param.name(ctx.db), path);
+        let edit = match where_bound_edit(fn_, &bound) {
+            Some(it) => it,
+            None => return,
+        };
+        for item in trait_.items(ctx.db) {
+            if let hir::AssocItem::Function(func) = item {
+                if func.self_param(ctx.db).is_some() && !seen_methods.contains(&func.name(ctx.db)) {
+                    acc.add_method_with_where_bound(ctx, func, edit.clone());
+                }
+            }
+        }
+    }
+}
+
+fn where_bound_edit(fn_: &ast::Fn, bound: &str) -> Option<Indel> {
+    let edit = match fn_.where_clause() {
+        Some(where_clause) => match where_clause.predicates().last() {
+            Some(pred) => Indel::insert(pred.syntax().text_range().end(), format!(", {}", bound)),
+            None => Indel::insert(where_clause.syntax().text_range().end(), format!(" {}", bound)),
+        },
+        None => {
+            let body = fn_.body()?;
+            Indel::insert(body.syntax().text_range().start(), format!("where {} ", bound))
+        }
+    };
+    Some(edit)
 }
 
 fn complete_undotted_self(acc: &mut Completions, ctx: &CompletionContext) {
@@ -46,7 +133,7 @@ fn complete_undotted_self(acc: &mut Completions, ctx: &CompletionContext) {
                     acc.add_tuple_field(ctx, Some(hir::known::SELF_PARAM), tuple_idx, &ty)
                 }
             });
-            complete_methods(ctx, &ty, |func| {
+            complete_methods(ctx, &ty, |func, _| {
                 acc.add_method(ctx, func, Some(hir::known::SELF_PARAM), None)
             });
         }
@@ -69,12 +156,13 @@ fn complete_fields(
     }
 }
 
+/// Calls `f` with the methods of `receiver`, and whether calling them is ambiguous as several
+/// traits in scope have a method of that name.
 fn complete_methods(
     ctx: &CompletionContext,
     receiver: &hir::Type,
-    mut f: impl FnMut(hir::Function),
+    mut f: impl FnMut(hir::Function, bool),
 ) {
-    let mut seen_methods = FxHashSet::default();
     let mut traits_in_scope = ctx.scope.visible_traits();
 
     // Remove drop from the environment as calling `Drop::drop` is not allowed
@@ -83,6 +171,7 @@ fn complete_methods(
         traits_in_scope.remove(&drop_trait.into());
     }
 
+    let mut candidates = Vec::new();
     receiver.iterate_method_candidates(
         ctx.db,
         &ctx.scope,
@@ -90,12 +179,18 @@ fn complete_methods(
         ctx.module,
         None,
         |func| {
-            if func.self_param(ctx.db).is_some() && seen_methods.insert(func.name(ctx.db)) {
-                f(func);
+            if func.self_param(ctx.db).is_some() {
+                candidates.push(func);
             }
             None::<()>
         },
     );
+    for group in group_method_candidates(ctx.db, receiver, candidates) {
+        let is_ambiguous = group.len() > 1;
+        for func in group {
+            f(func, is_ambiguous);
+        }
+    }
 }
 
 #[cfg(test)]
@@ -753,6 +848,50 @@ fn main() {
         )
     }
 
+    #[test]
+    fn completes_arbitrary_self_type_methods() {
+        check(
+            r#"
+//- minicore: deref, pin
+use core::pin::Pin;
+struct Rc<T>(T);
+impl<T> core::ops::Deref for Rc<T> {
+    type Target = T;
+    fn deref(&self) -> &T { &self.0 }
+}
+struct S;
+impl S {
+    fn by_rc(self: Rc<Self>) {}
+    fn by_pin(self: Pin<&mut Self>) {}
+    fn by_ref(&self) {}
+}
+fn foo(s: Rc<S>) { s.$0 }
+"#,
+            expect![[r#"
+                fd 0        S
+                me by_rc()  fn(self: Rc<Self>)
+                me by_ref() fn(&self)
+            "#]],
+        );
+    }
+
+    #[test]
+    fn completes_method_with_doc_alias() {
+        check(
+            r#"
+struct Buf;
+impl Buf {
+    #[doc(alias("len", "size"))]
+    fn byte_count(&self) -> usize { 0 }
+}
+fn foo(b: Buf) { b.$0 }
+"#,
+            expect![[r#"
+                me byte_count() (alias len, size) fn(&self) -> usize
+            "#]],
+        );
+    }
+
     #[test]
     fn postfix_drop_completion() {
         cov_mark::check!(dot_remove_drop_trait);
@@ -782,4 +921,108 @@ fn main() {
 ",
         )
     }
+
+    #[test]
+    fn completes_methods_of_unsatisfied_bounds() {
+        check(
+            r#"
+trait Named { fn name(&self) -> u32; }
+trait Sized2 { fn size(&self) -> u32; }
+fn foo<T: Sized2>(t: T) { t.$0 }
+"#,
+            expect![[r#"
+                me size() (as Sized2) fn(&self) -> u32
+                me name() (as Named)  fn(&self) -> u32
+            "#]],
+        );
+    }
+
+    #[test]
+    fn adds_where_clause_for_method() {
+        check_edit(
+            "name",
+            r#"
+trait Named { fn name(&self) -> u32; }
+fn foo<T>(t: T) -> u32 { t.$0 }
+"#,
+            r#"
+trait Named { fn name(&self) -> u32; }
+fn foo<T>(t: T) -> u32 where T: Named { t.name()$0 }
+"#,
+        );
+    }
+
+    #[test]
+    fn extends_existing_where_clause() {
+        check_edit(
+            "name",
+            r#"
+trait Named { fn name(&self) -> u32; }
+fn foo<T, U>(t: &T, u: U) -> u32
+where
+    U: Copy,
+{
+    t.$0
+}
+"#,
+            r#"
+trait Named { fn name(&self) -> u32; }
+fn foo<T, U>(t: &T, u: U) -> u32
+where
+    U: Copy, T: Named,
+{
+    t.name()$0
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn no_where_bound_in_trait_impls() {
+        check(
+            r#"
+trait Named { fn name(&self) -> u32; }
+trait Tr { fn f<T>(t: T); }
+struct S;
+impl Tr for S {
+    fn f<T>(t: T) { t.$0 }
+}
+"#,
+            expect![[r#""#]],
+        );
+    }
+
+    #[test]
+    fn ambiguous_trait_methods_are_qualified() {
+        check(
+            r#"
+trait A { fn name(&self) -> u8; }
+trait B { fn name(&self, x: u8) -> u16; }
+impl A for u32 { fn name(&self) -> u8 { 0 } }
+impl B for u32 { fn name(&self, x: u8) -> u16 { 0 } }
+fn foo(x: u32) { x.$0 }
+"#,
+            expect![[r#"
+                me name() (as A)  fn(&self) -> u8
+                me name(…) (as B) fn(&self, u8) -> u16
+            "#]],
+        );
+
+        let (db, position) = crate::tests::position(
+            r#"
+trait A { fn name(&self) -> u8; }
+trait B { fn name(&self, x: u8) -> u16; }
+impl A for u32 { fn name(&self) -> u8 { 0 } }
+impl B for u32 { fn name(&self, x: u8) -> u16 { 0 } }
+fn foo(x: u32) { x.na$0 }
+"#,
+        );
+        let completions: Vec<_> =
+            crate::completions(&db, &crate::tests::TEST_CONFIG, position).unwrap().into();
+        let mut actual =
+            ide_db::base_db::SourceDatabaseExt::file_text(&db, position.file_id).to_string();
+        let completion = completions.iter().find(|it| it.label() == "name(…) (as B)").unwrap();
+        completion.text_edit().apply(&mut actual);
+        assert_eq!(actual.lines().last(), Some("fn foo(x: u32) { B::name(&x, $0) }"));
+    }
 }
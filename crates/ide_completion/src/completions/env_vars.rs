@@ -0,0 +1,145 @@
+//! Completes environment variables in `env!` and `option_env!`.
+
+use hir::Documentation;
+use ide_db::syntax_helpers::node_ext::macro_name_of_input;
+use itertools::Itertools;
+use syntax::{
+    ast::{self, IsString},
+    AstToken,
+};
+
+use crate::{context::CompletionContext, CompletionItem, CompletionItemKind, Completions};
+
+/// The variables Cargo sets when compiling a crate, see
+/// <https://doc.rust-lang.org/cargo/reference/environment-variables.html#environment-variables-cargo-sets-for-crates>.
+const CARGO_DEFINED_VARS: &[(&str, &str)] = &[
+    ("CARGO", "Path to the `cargo` binary performing the build"),
+    ("CARGO_MANIFEST_DIR", "The directory containing the manifest of your package"),
+    ("CARGO_PKG_VERSION", "The full version of your package"),
+    ("CARGO_PKG_VERSION_MAJOR", "The major version of your package"),
+    ("CARGO_PKG_VERSION_MINOR", "The minor version of your package"),
+    ("CARGO_PKG_VERSION_PATCH", "The patch version of your package"),
+    ("CARGO_PKG_VERSION_PRE", "The pre-release version of your package"),
+    ("CARGO_PKG_AUTHORS", "Colon separated list of authors from the manifest of your package"),
+    ("CARGO_PKG_NAME", "The name of your package"),
+    ("CARGO_PKG_DESCRIPTION", "The description from the manifest of your package"),
+    ("CARGO_PKG_HOMEPAGE", "The home page from the manifest of your package"),
+    ("CARGO_PKG_REPOSITORY", "The repository from the manifest of your package"),
+    ("CARGO_PKG_LICENSE", "The license from the manifest of your package"),
+    ("CARGO_PKG_LICENSE_FILE", "The license file from the manifest of your package"),
+    ("CARGO_CRATE_NAME", "The name of the crate that is currently being compiled"),
+    ("CARGO_BIN_NAME", "The name of the binary that is currently being compiled"),
+    ("CARGO_PRIMARY_PACKAGE", "Set if the package being built is primary"),
+    ("CARGO_TARGET_TMPDIR", "Only set when building integration test or benchmark code"),
+    ("OUT_DIR", "The output directory of the build script of your package"),
+];
+
+/// Completes the names of the variables known to be set for the crate, like
+/// those set by Cargo and its build scripts, inside of `env!("...")` and
+/// `option_env!("...")`.
+pub(crate) fn complete_env_vars(acc: &mut Completions, ctx: &CompletionContext) -> Option<()> {
+    let string = ast::String::cast(ctx.original_token.clone())?;
+    let input = string.syntax().parent().and_then(ast::TokenTree::cast)?;
+    if !matches!(macro_name_of_input(&input)?.as_str(), "env" | "option_env") {
+        return None;
+    }
+    let source_range = string.text_range_between_quotes()?;
+    let env = ctx.krate?.env(ctx.db);
+
+    let known = CARGO_DEFINED_VARS.iter().map(|&(name, _)| name);
+    let set = env.iter().map(|(name, _)| name);
+    for name in known.chain(set).sorted().dedup() {
+        let mut item = CompletionItem::new(CompletionItemKind::Keyword, source_range, name);
+        if let Some(value) = env.get(name) {
+            item.detail(format!("{:?}", value));
+        }
+        if let Some((_, docs)) = CARGO_DEFINED_VARS.iter().find(|&&(it, _)| it == name) {
+            item.documentation(Documentation::new(docs.to_string()));
+        }
+        item.add_to(acc);
+    }
+    Some(())
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{expect, Expect};
+
+    use crate::tests::{check_edit, completion_list};
+
+    fn check(ra_fixture: &str, expect: Expect) {
+        let actual = completion_list(ra_fixture);
+        expect.assert_eq(&actual);
+    }
+
+    #[test]
+    fn completes_env_vars() {
+        check(
+            r#"
+//- /main.rs env:OUT_DIR=/out,GIT_HASH=abc
+#[rustc_builtin_macro]
+macro_rules! env { () => {} }
+fn main() {
+    let _ = env!("$0");
+}
+"#,
+            expect![[r#"
+                kw CARGO
+                kw CARGO_BIN_NAME
+                kw CARGO_CRATE_NAME
+                kw CARGO_MANIFEST_DIR
+                kw CARGO_PKG_AUTHORS
+                kw CARGO_PKG_DESCRIPTION
+                kw CARGO_PKG_HOMEPAGE
+                kw CARGO_PKG_LICENSE
+                kw CARGO_PKG_LICENSE_FILE
+                kw CARGO_PKG_NAME
+                kw CARGO_PKG_REPOSITORY
+                kw CARGO_PKG_VERSION
+                kw CARGO_PKG_VERSION_MAJOR
+                kw CARGO_PKG_VERSION_MINOR
+                kw CARGO_PKG_VERSION_PATCH
+                kw CARGO_PKG_VERSION_PRE
+                kw CARGO_PRIMARY_PACKAGE
+                kw CARGO_TARGET_TMPDIR
+                kw GIT_HASH               "abc"
+                kw OUT_DIR                "/out"
+            "#]],
+        );
+    }
+
+    #[test]
+    fn completes_in_nested_calls() {
+        check_edit(
+            "GIT_HASH",
+            r#"
+//- /main.rs env:GIT_HASH=abc
+#[rustc_builtin_macro]
+macro_rules! option_env { () => {} }
+#[rustc_builtin_macro]
+macro_rules! concat { () => {} }
+const VERSION: &str = concat!("v", option_env!("GIT_$0"));
+"#,
+            r#"
+#[rustc_builtin_macro]
+macro_rules! option_env { () => {} }
+#[rustc_builtin_macro]
+macro_rules! concat { () => {} }
+const VERSION: &str = concat!("v", option_env!("GIT_HASH"));
+"#,
+        );
+    }
+
+    #[test]
+    fn no_completions_in_other_strings() {
+        check(
+            r#"
+//- /main.rs env:GIT_HASH=abc
+fn main() {
+    let _ = "$0";
+}
+"#,
+            expect![[""]],
+        );
+    }
+}
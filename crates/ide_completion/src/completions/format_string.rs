@@ -1,12 +1,12 @@
 //! Completes identifiers in format string literals.
 
-use ide_db::syntax_helpers::format_string::is_format_string;
+use ide_db::{format_args::named_args, syntax_helpers::format_string::is_format_string};
 use itertools::Itertools;
 use syntax::{ast, AstToken, TextRange, TextSize};
 
 use crate::{context::CompletionContext, CompletionItem, CompletionItemKind, Completions};
 
-/// Complete identifiers in format strings.
+/// Complete named arguments and captured identifiers in format strings.
 pub(crate) fn format_string(acc: &mut Completions, ctx: &CompletionContext) {
     let string = match ast::String::cast(ctx.token.clone())
         .zip(ast::String::cast(ctx.original_token.clone()))
@@ -28,6 +28,9 @@ pub(crate) fn format_string(acc: &mut Completions, ctx: &CompletionContext) {
     };
 
     let source_range = TextRange::new(brace_offset, cursor);
+    named_args(&string).into_iter().for_each(|name| {
+        CompletionItem::new(CompletionItemKind::Binding, source_range, name.text()).add_to(acc);
+    });
     ctx.locals.iter().for_each(|(name, _)| {
         CompletionItem::new(CompletionItemKind::Binding, source_range, name.to_smol_str())
             .add_to(acc);
@@ -125,4 +128,24 @@ fn main() {
 "#,
         );
     }
+
+    #[test]
+    fn completes_named_args() {
+        check(
+            r#"
+macro_rules! format_args {
+    ($lit:literal $(tt:tt)*) => { 0 },
+}
+fn main() {
+    let foobar = 1;
+    format_args!("{$0}", named = 2, other = foobar);
+}
+"#,
+            expect![[r#"
+                bn foobar
+                bn named
+                bn other
+            "#]],
+        );
+    }
 }
@@ -49,13 +49,30 @@ pub(crate) fn complete_pattern(acc: &mut Completions, ctx: &CompletionContext) {
 
     let single_variant_enum = |enum_: hir::Enum| ctx.db.enum_data(enum_.into()).variants.len() == 1;
 
-    if let Some(hir::Adt::Enum(e)) =
-        ctx.expected_type.as_ref().and_then(|ty| ty.strip_references().as_adt())
-    {
-        if refutable || single_variant_enum(e) {
-            super::enum_variants_with_paths(acc, ctx, e, |acc, ctx, variant, path| {
-                acc.add_qualified_variant_pat(ctx, variant, path);
-            });
+    if let Some(ty) = ctx.expected_type.as_ref().map(|ty| ty.strip_references()) {
+        match ty.as_adt() {
+            Some(hir::Adt::Enum(e)) => {
+                if refutable || single_variant_enum(e) {
+                    super::enum_variants_with_paths(acc, ctx, e, |acc, ctx, variant, path| {
+                        acc.add_qualified_variant_pat(ctx, variant, path);
+                    });
+                }
+            }
+            Some(hir::Adt::Struct(strukt)) => {
+                // Structs in scope are added below, with their local name.
+                let path = ctx
+                    .module
+                    .and_then(|it| it.find_use_path(ctx.db, hir::ModuleDef::from(strukt)));
+                if let Some(path) = path.filter(|it| it.segments().len() > 1) {
+                    acc.add_qualified_struct_pat(ctx, strukt, path);
+                }
+            }
+            _ if ty.is_slice() => acc.add_slice_pats(ctx, None, refutable),
+            _ => {
+                if let Some((_, len)) = ty.as_array() {
+                    acc.add_slice_pats(ctx, Some(len), refutable);
+                }
+            }
         }
     }
 
@@ -265,7 +265,7 @@ fn add_custom_postfix_completions(
                 Some(imports) => imports,
                 None => return,
             };
-            let body = snippet.postfix_snippet(receiver_text);
+            let body = snippet.postfix_snippet(ctx, receiver_text);
             let mut builder =
                 postfix_snippet(trigger, snippet.description.as_deref().unwrap_or_default(), &body);
             builder.documentation(Documentation::new(format!("```rust\n{}\n```", body)));
@@ -111,7 +111,7 @@ fn add_custom_completions(
                 Some(imports) => imports,
                 None => return,
             };
-            let body = snip.snippet();
+            let body = snip.snippet(ctx);
             let mut builder = snippet(ctx, cap, trigger, &body);
             builder.documentation(Documentation::new(format!("```rust\n{}\n```", body)));
             for import in imports.into_iter() {
@@ -155,6 +155,38 @@ fn main() { $0 }
 use core::ops::ControlFlow;
 
 fn main() { ControlFlow::Break(()) }
+"#,
+        );
+    }
+
+    #[test]
+    fn item_template_with_module_and_crate_names() {
+        check_edit_with_config(
+            CompletionConfig {
+                snippets: vec![Snippet::new(
+                    &["errenum".into()],
+                    &[],
+                    &[
+                        "/// Errors of `${crate}::${module}`.".into(),
+                        "pub enum ${1:Error} {$0}".into(),
+                    ],
+                    "",
+                    &[],
+                    crate::SnippetScope::Item,
+                )
+                .unwrap()],
+                ..TEST_CONFIG
+            },
+            "errenum",
+            r#"
+//- /lib.rs crate:my_crate
+mod parser;
+//- /parser.rs
+$0
+"#,
+            r#"
+/// Errors of `my_crate::parser`.
+pub enum ${1:Error} {$0}
 "#,
         );
     }
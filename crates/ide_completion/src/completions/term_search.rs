@@ -0,0 +1,195 @@
+//! Completes small expressions of the expected type, built out of what is in scope.
+//!
+//! This does not search deeper than one step, the idea is to save typing on the usual
+//! wrapping and conversions, not to write the code for the user.
+
+use hir::{HirDisplay, ModuleDef, ScopeDef, Type};
+use ide_db::helpers::mod_path_to_ast;
+
+use crate::{
+    context::Visible,
+    item::{CompletionRelevance, CompletionRelevanceTypeMatch},
+    CompletionContext, CompletionItem, CompletionItemKind, Completions,
+};
+
+/// Completes expressions like `Some(value)`, `value.into()`, `Config::new()` or
+/// `Config::default()` when their type is the expected one.
+pub(crate) fn complete_term_search(acc: &mut Completions, ctx: &CompletionContext) -> Option<()> {
+    if !ctx.config.enable_term_search || !ctx.expects_expression() || ctx.is_non_trivial_path() {
+        return None;
+    }
+    let expected = ctx.expected_type.as_ref()?;
+    if expected.is_unit() || expected.is_unknown() {
+        return None;
+    }
+    let _p = profile::span("complete_term_search");
+
+    let db = ctx.db;
+    let famous_defs = ctx.famous_defs();
+    let mut locals = Vec::new();
+    ctx.process_all_names(&mut |name, def| {
+        if let ScopeDef::Local(local) = def {
+            locals.push((name, local.ty(db)));
+        }
+    });
+
+    let mut terms = Vec::new();
+
+    let wrapper = match expected.as_adt() {
+        Some(hir::Adt::Enum(it)) if Some(it) == famous_defs.core_option_Option() => Some("Some"),
+        Some(hir::Adt::Enum(it)) if Some(it) == famous_defs.core_result_Result() => Some("Ok"),
+        _ => None,
+    };
+    if let (Some(wrapper), Some(inner)) = (wrapper, expected.type_arguments().next()) {
+        for (name, ty) in &locals {
+            if ty.could_unify_with(db, &inner) {
+                terms.push(format!("{}({})", wrapper, name));
+            }
+        }
+    }
+
+    if let Some(into_trait) = famous_defs.core_convert_Into() {
+        for (name, ty) in &locals {
+            // The local itself is already completed.
+            if !ty.could_unify_with(db, expected)
+                && ty.impls_trait(db, into_trait, &[expected.clone()])
+            {
+                terms.push(format!("{}.into()", name));
+            }
+        }
+    }
+
+    let implements_default =
+        famous_defs.core_default_Default().map_or(false, |it| expected.impls_trait(db, it, &[]));
+    match (expected.as_adt(), ctx.module) {
+        (Some(adt), Some(module)) => {
+            if let Some(path) = module.find_use_path(db, ModuleDef::from(adt)) {
+                let path = mod_path_to_ast(&path);
+                for ctor in constructors(ctx, expected) {
+                    terms.push(format!("{}::{}()", path, ctor));
+                }
+                if implements_default {
+                    terms.push(format!("{}::default()", path));
+                }
+            }
+        }
+        _ if implements_default => terms.push("Default::default()".to_owned()),
+        _ => (),
+    }
+
+    let detail = expected.display(db).to_string();
+    for term in terms {
+        let mut item =
+            CompletionItem::new(CompletionItemKind::Expression, ctx.source_range(), term);
+        item.detail(detail.clone()).set_relevance(CompletionRelevance {
+            type_match: Some(CompletionRelevanceTypeMatch::Exact),
+            ..CompletionRelevance::default()
+        });
+        item.add_to(acc);
+    }
+    Some(())
+}
+
+/// The names of the visible inherent associated functions of `ty` which take no arguments and
+/// return a `ty`, like `new`.
+fn constructors(ctx: &CompletionContext, ty: &Type) -> Vec<hir::Name> {
+    let db = ctx.db;
+    let mut res = Vec::new();
+    for impl_ in hir::Impl::all_for_type(db, ty.clone()) {
+        if impl_.trait_(db).is_some() {
+            continue;
+        }
+        for item in impl_.items(db) {
+            let func = match item {
+                hir::AssocItem::Function(it) => it,
+                _ => continue,
+            };
+            if func.self_param(db).is_none()
+                && func.assoc_fn_params(db).is_empty()
+                && func.ret_type(db).could_unify_with(db, ty)
+                && matches!(ctx.is_visible(&func), Visible::Yes)
+            {
+                res.push(func.name(db));
+            }
+        }
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{expect, Expect};
+
+    use crate::{
+        tests::{do_completion_with_config, TEST_CONFIG},
+        CompletionConfig, CompletionItemKind,
+    };
+
+    fn check(ra_fixture: &str, expect: Expect) {
+        let config = CompletionConfig { enable_term_search: true, ..TEST_CONFIG };
+        let actual = do_completion_with_config(config, ra_fixture, CompletionItemKind::Expression)
+            .into_iter()
+            .map(|it| format!("{}\n", it.label()))
+            .collect::<String>();
+        expect.assert_eq(&actual);
+    }
+
+    const FIXTURE: &str = r#"
+//- minicore: option, default, from
+struct Config;
+impl Config {
+    pub fn new() -> Config { Config }
+    fn with_port(port: u32) -> Config { Config }
+}
+impl Default for Config {
+    fn default() -> Self { Config }
+}
+struct Id(u32);
+impl From<u32> for Id {
+    fn from(it: u32) -> Id { Id(it) }
+}
+"#;
+
+    #[test]
+    fn wraps_locals_in_some() {
+        check(
+            &format!(
+                "{}\nfn f(value: u32, config: Config) {{ let _: Option<u32> = $0; }}",
+                FIXTURE
+            ),
+            expect![[r#"
+                Some(value)
+            "#]],
+        );
+    }
+
+    #[test]
+    fn converts_locals_with_into() {
+        check(
+            &format!("{}\nfn f(value: u32) -> Id {{ $0 }}", FIXTURE),
+            expect![[r#"
+                value.into()
+            "#]],
+        );
+    }
+
+    #[test]
+    fn calls_constructors_and_default() {
+        check(
+            &format!("{}\nfn f(value: u32) -> Config {{ $0 }}", FIXTURE),
+            expect![[r#"
+                Config::default()
+                Config::new()
+            "#]],
+        );
+    }
+
+    #[test]
+    fn disabled_by_default() {
+        let actual = crate::tests::do_completion(
+            &format!("{}\nfn f(value: u32) -> Config {{ $0 }}", FIXTURE),
+            CompletionItemKind::Expression,
+        );
+        assert!(actual.is_empty());
+    }
+}
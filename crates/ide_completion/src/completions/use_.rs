@@ -5,9 +5,12 @@ use syntax::{ast, AstNode};
 
 use crate::{
     context::{CompletionContext, PathCompletionCtx, PathKind, PathQualifierCtx},
-    Completions,
+    CompletionItem, CompletionItemKind, Completions,
 };
 
+/// The number of names a module has to export for `*` to be offered in a use tree.
+const GLOB_HINT_THRESHOLD: usize = 8;
+
 pub(crate) fn complete_use_tree(acc: &mut Completions, ctx: &CompletionContext) {
     let (is_absolute_path, qualifier) = match ctx.path_context {
         Some(PathCompletionCtx {
@@ -49,6 +52,7 @@ pub(crate) fn complete_use_tree(acc: &mut Completions, ctx: &CompletionContext)
                                 if name_ref.syntax().text() == name.to_smol_str().as_str()
                         )
                     };
+                    let mut exported = 0;
                     for (name, def) in module_scope {
                         let add_resolution = match def {
                             ScopeDef::Unknown if unknown_is_current(&name) => {
@@ -61,9 +65,14 @@ pub(crate) fn complete_use_tree(acc: &mut Completions, ctx: &CompletionContext)
                         };
 
                         if add_resolution {
-                            acc.add_resolution(ctx, name, def);
+                            exported += 1;
+                            add_resolution_in_use_tree(acc, ctx, name, def);
                         }
                     }
+                    if exported >= GLOB_HINT_THRESHOLD && use_tree_is_open(ctx) {
+                        cov_mark::hit!(use_tree_glob_hint);
+                        acc.add_keyword(ctx, "*");
+                    }
                 }
                 hir::PathResolution::Def(hir::ModuleDef::Adt(hir::Adt::Enum(e))) => {
                     cov_mark::hit!(enum_plain_qualified_use_tree);
@@ -84,10 +93,60 @@ pub(crate) fn complete_use_tree(acc: &mut Completions, ctx: &CompletionContext)
             cov_mark::hit!(unqualified_path_only_modules_in_import);
             ctx.process_all_names(&mut |name, res| {
                 if let ScopeDef::ModuleDef(hir::ModuleDef::Module(_)) = res {
-                    acc.add_resolution(ctx, name, res);
+                    add_resolution_in_use_tree(acc, ctx, name, res);
                 }
             });
             acc.add_nameref_keywords(ctx);
         }
     }
 }
+
+/// Adds `def`, and for modules also a `module::{self, …}` group importing the module together
+/// with some of its items.
+fn add_resolution_in_use_tree(
+    acc: &mut Completions,
+    ctx: &CompletionContext,
+    name: hir::Name,
+    def: ScopeDef,
+) {
+    acc.add_resolution(ctx, name.clone(), def);
+    if let (ScopeDef::ModuleDef(hir::ModuleDef::Module(_)), Some(cap)) =
+        (def, ctx.config.snippet_cap)
+    {
+        if use_tree_is_open(ctx) && !ctx.is_scope_def_hidden(def) {
+            let mut item = CompletionItem::new(
+                CompletionItemKind::Snippet,
+                ctx.source_range(),
+                format!("{}::{{self, …}}", name),
+            );
+            item.insert_snippet(cap, format!("{}::{{self, $0}}", name));
+            item.add_to(acc);
+        }
+    }
+}
+
+/// Whether the path being completed ends its use tree, so that nothing like `::{...}` or
+/// `as name` follows it yet.
+fn use_tree_is_open(ctx: &CompletionContext) -> bool {
+    let name_ref = match &ctx.name_syntax {
+        Some(ast::NameLike::NameRef(it)) => it,
+        _ => return true,
+    };
+    let path = match name_ref.syntax().ancestors().filter_map(ast::Path::cast).last() {
+        Some(it) => it,
+        None => return true,
+    };
+    if path.segment().and_then(|it| it.name_ref()).as_ref() != Some(name_ref) {
+        return false;
+    }
+    let use_tree = path.syntax().parent().and_then(ast::UseTree::cast);
+    match use_tree {
+        Some(use_tree) => {
+            use_tree.use_tree_list().is_none()
+                && use_tree.star_token().is_none()
+                && use_tree.rename().is_none()
+                && use_tree.coloncolon_token().is_none()
+        }
+        None => true,
+    }
+}
@@ -402,7 +402,7 @@ impl<'a> CompletionContext<'a> {
         let file_with_fake_ident = {
             let parse = db.parse(file_id);
             let edit = Indel::insert(offset, COMPLETION_MARKER.to_string());
-            parse.reparse(&edit).tree()
+            parse.reparse(&edit, db.file_edition(file_id)).tree()
         };
         let fake_ident_token =
             file_with_fake_ident.syntax().token_at_offset(offset).right_biased()?;
@@ -2,7 +2,7 @@
 
 use std::fmt;
 
-use hir::{Documentation, Mutability};
+use hir::{Deprecation, Documentation, Mutability};
 use ide_db::{
     helpers::mod_path_to_ast,
     imports::{
@@ -11,10 +11,11 @@ use ide_db::{
     },
     SnippetCap, SymbolKind,
 };
+use itertools::Itertools;
 use smallvec::SmallVec;
 use stdx::{impl_from, never};
 use syntax::{algo, SmolStr, TextRange};
-use text_edit::TextEdit;
+use text_edit::{Indel, TextEdit};
 
 /// `CompletionItem` describes a single completion variant in the editor pop-up.
 /// It is basically a POD with various properties. To construct a
@@ -143,6 +144,9 @@ pub struct CompletionRelevance {
     pub is_op_method: bool,
     /// Set for item completions that are private but in the workspace.
     pub is_private_editable: bool,
+    /// Set for method completions which need a bound to be added to the
+    /// enclosing function, because the generic receiver doesn't satisfy it yet.
+    pub requires_where_bound: bool,
     /// This is set in cases like these:
     ///
     /// ```
@@ -179,7 +183,7 @@ pub enum CompletionRelevanceTypeMatch {
 }
 
 impl CompletionRelevance {
-    const BASE_LINE: u32 = 2;
+    const BASE_LINE: u32 = 3;
     /// Provides a relevance score. Higher values are more relevant.
     ///
     /// The absolute value of the relevance score is not meaningful, for
@@ -199,6 +203,9 @@ impl CompletionRelevance {
         if self.is_private_editable {
             score -= 1;
         }
+        if self.requires_where_bound {
+            score -= 1;
+        }
 
         // score increases
         if self.exact_name_match {
@@ -235,6 +242,8 @@ pub enum CompletionItemKind {
     Keyword,
     Method,
     Snippet,
+    /// A synthesized expression of the expected type.
+    Expression,
     UnresolvedReference,
 }
 
@@ -276,6 +285,7 @@ impl CompletionItemKind {
             CompletionItemKind::Keyword => "kw",
             CompletionItemKind::Method => "me",
             CompletionItemKind::Snippet => "sn",
+            CompletionItemKind::Expression => "ex",
             CompletionItemKind::UnresolvedReference => "??",
         }
     }
@@ -299,11 +309,13 @@ impl CompletionItem {
             lookup: None,
             kind: kind.into(),
             text_edit: None,
-            deprecated: false,
+            deprecation: None,
             trigger_call_info: None,
             relevance: CompletionRelevance::default(),
             ref_match: None,
             imports_to_add: Default::default(),
+            doc_aliases: Vec::new(),
+            additional_edits: Vec::new(),
         }
     }
 
@@ -405,10 +417,12 @@ pub(crate) struct Builder {
     lookup: Option<SmolStr>,
     kind: CompletionItemKind,
     text_edit: Option<TextEdit>,
-    deprecated: bool,
+    deprecation: Option<Deprecation>,
     trigger_call_info: Option<bool>,
     relevance: CompletionRelevance,
     ref_match: Option<Mutability>,
+    doc_aliases: Vec<SmolStr>,
+    additional_edits: Vec<Indel>,
 }
 
 impl Builder {
@@ -419,6 +433,20 @@ impl Builder {
         let mut lookup = self.lookup;
         let insert_text = self.insert_text.unwrap_or_else(|| label.to_string());
 
+        if !self.doc_aliases.is_empty() {
+            // Let clients filter by the aliases as well, like rustdoc's search does.
+            lookup = Some(SmolStr::from(format!(
+                "{} {}",
+                lookup.as_deref().unwrap_or(&label),
+                self.doc_aliases.iter().format(" ")
+            )));
+            label = SmolStr::from(format!(
+                "{} (alias {})",
+                label,
+                self.doc_aliases.iter().format(", ")
+            ));
+        }
+
         if let [import_edit] = &*self.imports_to_add {
             // snippets can have multiple imports, but normal completions only have up to one
             if let Some(original_path) = import_edit.import.original_path.as_ref() {
@@ -429,10 +457,27 @@ impl Builder {
             label = SmolStr::from(format!("{} (as {})", label, trait_name));
         }
 
-        let text_edit = match self.text_edit {
+        let mut text_edit = match self.text_edit {
             Some(it) => it,
             None => TextEdit::replace(self.source_range, insert_text),
         };
+        if !self.additional_edits.is_empty() {
+            let mut builder = TextEdit::builder();
+            for indel in text_edit.iter().chain(&self.additional_edits) {
+                builder.replace(indel.delete, indel.insert.clone());
+            }
+            text_edit = builder.finish();
+        }
+
+        let mut documentation = self.documentation;
+        if let Some(deprecation) = &self.deprecation {
+            // Clients only strike the label through, so tell the user why.
+            let docs = match documentation {
+                Some(docs) => format!("*{}*\n\n{}", deprecation, docs.as_str()),
+                None => format!("*{}*", deprecation),
+            };
+            documentation = Some(Documentation::new(docs));
+        }
 
         CompletionItem {
             source_range: self.source_range,
@@ -440,10 +485,10 @@ impl Builder {
             text_edit,
             is_snippet: self.is_snippet,
             detail: self.detail,
-            documentation: self.documentation,
+            documentation,
             lookup,
             kind: self.kind,
-            deprecated: self.deprecated,
+            deprecated: self.deprecation.is_some(),
             trigger_call_info: self.trigger_call_info.unwrap_or(false),
             relevance: self.relevance,
             ref_match: self.ref_match,
@@ -458,6 +503,10 @@ impl Builder {
         self.label = label.into();
         self
     }
+    pub(crate) fn doc_aliases(&mut self, doc_aliases: Vec<SmolStr>) -> &mut Builder {
+        self.doc_aliases = doc_aliases;
+        self
+    }
     pub(crate) fn trait_name(&mut self, trait_name: SmolStr) -> &mut Builder {
         self.trait_name = Some(trait_name);
         self
@@ -503,8 +552,8 @@ impl Builder {
         self.documentation = docs.map(Into::into);
         self
     }
-    pub(crate) fn set_deprecated(&mut self, deprecated: bool) -> &mut Builder {
-        self.deprecated = deprecated;
+    pub(crate) fn set_deprecation(&mut self, deprecation: Option<Deprecation>) -> &mut Builder {
+        self.deprecation = deprecation;
         self
     }
     pub(crate) fn set_relevance(&mut self, relevance: CompletionRelevance) -> &mut Builder {
@@ -523,6 +572,11 @@ impl Builder {
         self.ref_match = Some(mutability);
         self
     }
+    /// Adds an edit outside of the completed range, applied along with the completion.
+    pub(crate) fn additional_edit(&mut self, indel: Indel) -> &mut Builder {
+        self.additional_edits.push(indel);
+        self
+    }
 }
 
 #[cfg(test)]
@@ -576,11 +630,28 @@ mod tests {
             vec![CompletionRelevance {
                 is_op_method: true,
                 is_private_editable: true,
+                requires_where_bound: true,
                 ..CompletionRelevance::default()
             }],
+            vec![
+                CompletionRelevance {
+                    is_op_method: true,
+                    is_private_editable: true,
+                    ..CompletionRelevance::default()
+                },
+                CompletionRelevance {
+                    is_op_method: true,
+                    requires_where_bound: true,
+                    ..CompletionRelevance::default()
+                },
+            ],
             vec![
                 CompletionRelevance { is_private_editable: true, ..CompletionRelevance::default() },
                 CompletionRelevance { is_op_method: true, ..CompletionRelevance::default() },
+                CompletionRelevance {
+                    requires_where_bound: true,
+                    ..CompletionRelevance::default()
+                },
             ],
             vec![CompletionRelevance::default()],
             vec![
@@ -13,6 +13,7 @@ pub(crate) mod literal;
 use hir::{AsAssocItem, HasAttrs, HirDisplay, ScopeDef};
 use ide_db::{helpers::item_name, RootDatabase, SnippetCap, SymbolKind};
 use syntax::{SmolStr, SyntaxKind, TextRange};
+use text_edit::Indel;
 
 use crate::{
     context::{PathCompletionCtx, PathKind},
@@ -26,11 +27,18 @@ pub(crate) struct RenderContext<'a> {
     completion: &'a CompletionContext<'a>,
     is_private_editable: bool,
     import_to_add: Option<ImportEdit>,
+    /// The bound to insert for the item to apply, see [`CompletionRelevance::requires_where_bound`].
+    where_bound: Option<Indel>,
 }
 
 impl<'a> RenderContext<'a> {
     pub(crate) fn new(completion: &'a CompletionContext<'a>) -> RenderContext<'a> {
-        RenderContext { completion, is_private_editable: false, import_to_add: None }
+        RenderContext {
+            completion,
+            is_private_editable: false,
+            import_to_add: None,
+            where_bound: None,
+        }
     }
 
     pub(crate) fn private_editable(mut self, private_editable: bool) -> Self {
@@ -43,6 +51,11 @@ impl<'a> RenderContext<'a> {
         self
     }
 
+    pub(crate) fn where_bound(mut self, where_bound: Indel) -> Self {
+        self.where_bound = Some(where_bound);
+        self
+    }
+
     fn snippet_cap(&self) -> Option<SnippetCap> {
         self.completion.config.snippet_cap
     }
@@ -56,37 +69,40 @@ impl<'a> RenderContext<'a> {
     }
 
     fn completion_relevance(&self) -> CompletionRelevance {
-        CompletionRelevance { is_private_editable: self.is_private_editable, ..Default::default() }
+        CompletionRelevance {
+            is_private_editable: self.is_private_editable,
+            requires_where_bound: self.where_bound.is_some(),
+            ..Default::default()
+        }
     }
 
-    fn is_deprecated(&self, def: impl HasAttrs) -> bool {
-        let attrs = def.attrs(self.db());
-        attrs.by_key("deprecated").exists() || attrs.by_key("rustc_deprecated").exists()
+    fn deprecation(&self, def: impl HasAttrs) -> Option<hir::Deprecation> {
+        def.attrs(self.db()).deprecation()
     }
 
-    fn is_deprecated_assoc_item(&self, as_assoc_item: impl AsAssocItem) -> bool {
+    /// The deprecation of an associated item, or of the trait it belongs to.
+    fn assoc_item_deprecation(&self, as_assoc_item: impl AsAssocItem) -> Option<hir::Deprecation> {
         let db = self.db();
-        let assoc = match as_assoc_item.as_assoc_item(db) {
-            Some(assoc) => assoc,
-            None => return false,
-        };
+        let assoc = as_assoc_item.as_assoc_item(db)?;
 
-        let is_assoc_deprecated = match assoc {
-            hir::AssocItem::Function(it) => self.is_deprecated(it),
-            hir::AssocItem::Const(it) => self.is_deprecated(it),
-            hir::AssocItem::TypeAlias(it) => self.is_deprecated(it),
+        let assoc_deprecation = match assoc {
+            hir::AssocItem::Function(it) => self.deprecation(it),
+            hir::AssocItem::Const(it) => self.deprecation(it),
+            hir::AssocItem::TypeAlias(it) => self.deprecation(it),
         };
-        is_assoc_deprecated
-            || assoc
-                .containing_trait_or_trait_impl(db)
-                .map(|trait_| self.is_deprecated(trait_))
-                .unwrap_or(false)
+        assoc_deprecation.or_else(|| {
+            assoc.containing_trait_or_trait_impl(db).and_then(|trait_| self.deprecation(trait_))
+        })
     }
 
     // FIXME: remove this
     fn docs(&self, def: impl HasAttrs) -> Option<hir::Documentation> {
         def.docs(self.db())
     }
+
+    fn doc_aliases(&self, def: impl HasAttrs) -> Vec<SmolStr> {
+        def.attrs(self.db()).doc_aliases()
+    }
 }
 
 pub(crate) fn render_field(
@@ -95,7 +111,7 @@ pub(crate) fn render_field(
     field: hir::Field,
     ty: &hir::Type,
 ) -> CompletionItem {
-    let is_deprecated = ctx.is_deprecated(field);
+    let deprecation = ctx.deprecation(field);
     let name = field.name(ctx.db()).to_smol_str();
     let mut item = CompletionItem::new(
         SymbolKind::Field,
@@ -109,7 +125,7 @@ pub(crate) fn render_field(
     });
     item.detail(ty.display(ctx.db()).to_string())
         .set_documentation(field.docs(ctx.db()))
-        .set_deprecated(is_deprecated)
+        .set_deprecation(deprecation)
         .lookup_by(name.clone());
     let is_keyword = SyntaxKind::from_keyword(name.as_str()).is_some();
     if is_keyword && !matches!(name.as_str(), "self" | "crate" | "super" | "Self") {
@@ -212,7 +228,7 @@ fn render_resolution_simple_(
         ScopeDef::Unknown => CompletionItemKind::UnresolvedReference,
         ScopeDef::ModuleDef(Function(_)) => CompletionItemKind::SymbolKind(SymbolKind::Function),
         ScopeDef::ModuleDef(Variant(_)) => CompletionItemKind::SymbolKind(SymbolKind::Variant),
-        ScopeDef::ModuleDef(Macro(_)) => CompletionItemKind::SymbolKind(SymbolKind::Macro),
+        ScopeDef::ModuleDef(Macro(mac)) => CompletionItemKind::SymbolKind(mac.kind(db).into()),
         ScopeDef::ModuleDef(Module(..)) => CompletionItemKind::SymbolKind(SymbolKind::Module),
         ScopeDef::ModuleDef(Adt(adt)) => CompletionItemKind::SymbolKind(match adt {
             hir::Adt::Struct(_) => SymbolKind::Struct,
@@ -255,6 +271,18 @@ fn render_resolution_simple_(
             item.ref_match(ref_match);
         }
     };
+    let value_ty = match resolution {
+        ScopeDef::ModuleDef(Const(it)) => Some(it.ty(db)),
+        ScopeDef::ModuleDef(Static(it)) => Some(it.ty(db)),
+        _ => None,
+    };
+    if let Some(ty) = value_ty {
+        item.set_relevance(CompletionRelevance {
+            type_match: compute_type_match(ctx.completion, &ty),
+            exact_name_match: compute_exact_name_match(ctx.completion, &local_name),
+            ..ctx.completion_relevance()
+        });
+    }
 
     // Add `<>` for generic types
     let type_path_no_ty_args = matches!(
@@ -278,7 +306,10 @@ fn render_resolution_simple_(
         }
     }
     item.set_documentation(scope_def_docs(db, resolution))
-        .set_deprecated(scope_def_is_deprecated(&ctx, resolution));
+        .set_deprecation(scope_def_deprecation(&ctx, resolution));
+    if let Some(attrs) = resolution.attrs(db) {
+        item.doc_aliases(attrs.doc_aliases());
+    }
 
     if let Some(import_to_add) = ctx.import_to_add {
         item.add_import(import_to_add);
@@ -300,12 +331,17 @@ fn scope_def_docs(db: &RootDatabase, resolution: ScopeDef) -> Option<hir::Docume
     }
 }
 
-fn scope_def_is_deprecated(ctx: &RenderContext<'_>, resolution: ScopeDef) -> bool {
+fn scope_def_deprecation(
+    ctx: &RenderContext<'_>,
+    resolution: ScopeDef,
+) -> Option<hir::Deprecation> {
     match resolution {
-        ScopeDef::ModuleDef(it) => ctx.is_deprecated_assoc_item(it),
-        ScopeDef::GenericParam(it) => ctx.is_deprecated(it),
-        ScopeDef::AdtSelfType(it) => ctx.is_deprecated(it),
-        _ => false,
+        ScopeDef::ModuleDef(it) => {
+            ctx.assoc_item_deprecation(it).or_else(|| it.attrs(ctx.db())?.deprecation())
+        }
+        ScopeDef::GenericParam(it) => ctx.deprecation(it),
+        ScopeDef::AdtSelfType(it) => ctx.deprecation(it),
+        _ => None,
     }
 }
 
@@ -434,6 +470,7 @@ mod tests {
                 (relevance.is_local, "local"),
                 (relevance.exact_postfix_snippet_match, "snippet"),
                 (relevance.is_op_method, "op_method"),
+                (relevance.requires_where_bound, "where_bound"),
             ]
             .into_iter()
             .filter_map(|(cond, desc)| if cond { Some(desc) } else { None })
@@ -614,6 +651,7 @@ fn main() { let _: m::Spam = S$0 }
                             is_local: false,
                             is_op_method: false,
                             is_private_editable: false,
+                            requires_where_bound: false,
                             exact_postfix_snippet_match: false,
                         },
                     },
@@ -635,6 +673,7 @@ fn main() { let _: m::Spam = S$0 }
                             is_local: false,
                             is_op_method: false,
                             is_private_editable: false,
+                            requires_where_bound: false,
                             exact_postfix_snippet_match: false,
                         },
                     },
@@ -678,6 +717,9 @@ fn main() { som$0 }
                         ),
                         lookup: "something_deprecated",
                         detail: "fn()",
+                        documentation: Documentation(
+                            "*Deprecated*",
+                        ),
                         deprecated: true,
                     },
                     CompletionItem {
@@ -690,6 +732,9 @@ fn main() { som$0 }
                         ),
                         lookup: "something_else_deprecated",
                         detail: "fn()",
+                        documentation: Documentation(
+                            "*Deprecated since 1.0.0*",
+                        ),
                         deprecated: true,
                     },
                 ]
@@ -713,6 +758,9 @@ fn foo() { A { the$0 } }
                             Field,
                         ),
                         detail: "u32",
+                        documentation: Documentation(
+                            "*Deprecated*",
+                        ),
                         deprecated: true,
                         relevance: CompletionRelevance {
                             exact_name_match: false,
@@ -722,6 +770,7 @@ fn foo() { A { the$0 } }
                             is_local: false,
                             is_op_method: false,
                             is_private_editable: false,
+                            requires_where_bound: false,
                             exact_postfix_snippet_match: false,
                         },
                     },
@@ -1015,6 +1064,26 @@ fn foo(s: S) { test(s.$0) }
         );
     }
 
+    #[test]
+    fn const_and_static_relevances() {
+        check_relevance_for_kinds(
+            r#"
+const C: u32 = 0;
+static S: i32 = 0;
+fn test(bar: u32) {}
+fn foo() { test($0) }
+"#,
+            &[
+                CompletionItemKind::SymbolKind(SymbolKind::Const),
+                CompletionItemKind::SymbolKind(SymbolKind::Static),
+            ],
+            expect![[r#"
+                ct C [type]
+                sc S []
+            "#]],
+        );
+    }
+
     #[test]
     fn record_field_relevances() {
         check_relevance(
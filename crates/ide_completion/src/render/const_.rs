@@ -3,7 +3,10 @@
 use hir::{AsAssocItem, HirDisplay};
 use ide_db::SymbolKind;
 
-use crate::{item::CompletionItem, render::RenderContext};
+use crate::{
+    item::{CompletionItem, CompletionRelevance},
+    render::{compute_exact_name_match, compute_type_match, RenderContext},
+};
 
 pub(crate) fn render_const(ctx: RenderContext<'_>, const_: hir::Const) -> Option<CompletionItem> {
     let _p = profile::span("render_const");
@@ -17,9 +20,13 @@ fn render(ctx: RenderContext<'_>, const_: hir::Const) -> Option<CompletionItem>
 
     let mut item = CompletionItem::new(SymbolKind::Const, ctx.source_range(), name.clone());
     item.set_documentation(ctx.docs(const_))
-        .set_deprecated(ctx.is_deprecated(const_) || ctx.is_deprecated_assoc_item(const_))
+        .set_deprecation(ctx.deprecation(const_).or_else(|| ctx.assoc_item_deprecation(const_)))
         .detail(detail)
-        .set_relevance(ctx.completion_relevance());
+        .set_relevance(CompletionRelevance {
+            type_match: compute_type_match(ctx.completion, &const_.ty(db)),
+            exact_name_match: compute_exact_name_match(ctx.completion, &name),
+            ..ctx.completion_relevance()
+        });
 
     if let Some(actm) = const_.as_assoc_item(db) {
         if let Some(trt) = actm.containing_trait_or_trait_impl(db) {
@@ -1,10 +1,11 @@
 //! Renderer for function calls.
 
 use hir::{db::HirDatabase, AsAssocItem, HirDisplay};
-use ide_db::{SnippetCap, SymbolKind};
+use ide_db::{helpers::mod_path_to_ast, SnippetCap, SymbolKind};
 use itertools::Itertools;
 use stdx::format_to;
-use syntax::SmolStr;
+use syntax::{ast, AstNode, SmolStr, TextRange};
+use text_edit::TextEdit;
 
 use crate::{
     context::{CompletionContext, PathCompletionCtx, PathKind},
@@ -16,6 +17,8 @@ use crate::{
 enum FuncKind {
     Function,
     Method(Option<hir::Name>),
+    /// A method called with fully qualified syntax on the receiver: `Trait::method(&receiver)`.
+    QualifiedMethod(ast::Expr),
 }
 
 pub(crate) fn render_fn(
@@ -37,6 +40,15 @@ pub(crate) fn render_method(
     render(ctx, local_name, func, FuncKind::Method(receiver))
 }
 
+pub(crate) fn render_method_qualified(
+    ctx: RenderContext<'_>,
+    receiver: &ast::Expr,
+    func: hir::Function,
+) -> CompletionItem {
+    let _p = profile::span("render_method_qualified");
+    render(ctx, None, func, FuncKind::QualifiedMethod(receiver.clone()))
+}
+
 fn render(
     ctx @ RenderContext { completion, .. }: RenderContext<'_>,
     local_name: Option<hir::Name>,
@@ -83,18 +95,29 @@ fn render(
     }
 
     item.set_documentation(ctx.docs(func))
-        .set_deprecated(ctx.is_deprecated(func) || ctx.is_deprecated_assoc_item(func))
+        .set_deprecation(ctx.deprecation(func).or_else(|| ctx.assoc_item_deprecation(func)))
         .detail(detail(db, func))
-        .lookup_by(name.to_smol_str());
+        .lookup_by(name.to_smol_str())
+        .doc_aliases(ctx.doc_aliases(func));
 
+    let is_qualified = match &func_kind {
+        FuncKind::QualifiedMethod(receiver) => {
+            add_qualified_call(&mut item, completion, func, receiver).is_some()
+        }
+        _ => false,
+    };
     match completion.config.snippet_cap {
-        Some(cap) if should_add_parens(completion) => {
+        Some(cap) if !is_qualified && should_add_parens(completion) => {
             let (self_param, params) = params(completion, func, &func_kind);
             add_call_parens(&mut item, completion, cap, call, self_param, params);
         }
         _ => (),
     }
 
+    if let Some(where_bound) = ctx.where_bound.clone() {
+        item.additional_edit(where_bound);
+    }
+
     match ctx.import_to_add {
         Some(import_to_add) => {
             item.add_import(import_to_add);
@@ -162,6 +185,47 @@ pub(super) fn add_call_parens<'b>(
     builder.label(SmolStr::from_iter([&name, label_suffix])).insert_snippet(cap, snippet)
 }
 
+/// Replaces `receiver.method` with `Trait::method(&receiver)`.
+fn add_qualified_call(
+    builder: &mut Builder,
+    ctx: &CompletionContext,
+    func: hir::Function,
+    receiver: &ast::Expr,
+) -> Option<()> {
+    let db = ctx.db;
+    let trait_ = func.as_assoc_item(db)?.containing_trait(db)?;
+    let trait_path = ctx
+        .module
+        .and_then(|it| it.find_use_path(db, hir::ModuleDef::Trait(trait_)))
+        .map_or_else(|| trait_.name(db).to_string(), |it| mod_path_to_ast(&it).to_string());
+    let receiver_range = ctx.sema.original_range_opt(receiver.syntax())?.range;
+    if ctx.source_range().end() < receiver_range.start() {
+        return None;
+    }
+    let borrow = match func.self_param(db)?.access(db) {
+        hir::Access::Shared => "&",
+        hir::Access::Exclusive => "&mut ",
+        hir::Access::Owned => "",
+    };
+
+    let name = func.name(db).to_smol_str();
+    let call = format!("{}::{}({}{}", trait_path, name, borrow, receiver.syntax().text());
+    let has_params = !func.params_without_self(db).is_empty();
+    let range = TextRange::new(receiver_range.start(), ctx.source_range().end());
+    match ctx.config.snippet_cap {
+        Some(cap) => {
+            let snippet = match has_params {
+                true => format!("{}, $0)", call),
+                false => format!("{})$0", call),
+            };
+            builder.snippet_edit(cap, TextEdit::replace(range, snippet))
+        }
+        None => builder.text_edit(TextEdit::replace(range, format!("{})", call))),
+    };
+    builder.label(SmolStr::from_iter([&name, if has_params { "(…)" } else { "()" }]));
+    Some(())
+}
+
 fn ref_of_param(ctx: &CompletionContext, arg: &str, ty: &hir::Type) -> &'static str {
     if let Some(derefed_ty) = ty.remove_ref() {
         for (name, local) in ctx.locals.iter() {
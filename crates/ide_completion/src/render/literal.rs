@@ -98,7 +98,7 @@ fn render(
     if qualified {
         item.lookup_by(format_literal_label(&short_qualified_name.to_string(), kind));
     }
-    item.set_documentation(thing.docs(db)).set_deprecated(thing.is_deprecated(&ctx));
+    item.set_documentation(thing.docs(db)).set_deprecation(thing.deprecation(&ctx));
 
     let ty = thing.ty(db);
     item.set_relevance(CompletionRelevance {
@@ -159,10 +159,10 @@ impl Variant {
         }
     }
 
-    fn is_deprecated(self, ctx: &RenderContext<'_>) -> bool {
+    fn deprecation(self, ctx: &RenderContext<'_>) -> Option<hir::Deprecation> {
         match self {
-            Variant::Struct(it) => ctx.is_deprecated(it),
-            Variant::EnumVariant(it) => ctx.is_deprecated(it),
+            Variant::Struct(it) => ctx.deprecation(it),
+            Variant::EnumVariant(it) => ctx.deprecation(it),
         }
     }
 
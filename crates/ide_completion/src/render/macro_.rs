@@ -41,7 +41,7 @@ fn render(
         source_range,
         label(&ctx, needs_bang, bra, ket, &name),
     );
-    item.set_deprecated(ctx.is_deprecated(macro_))
+    item.set_deprecation(ctx.deprecation(macro_))
         .detail(macro_.display(completion.db).to_string())
         .set_documentation(docs)
         .set_relevance(ctx.completion_relevance());
@@ -15,6 +15,7 @@ pub(crate) fn render_struct_pat(
     ctx: RenderContext<'_>,
     strukt: hir::Struct,
     local_name: Option<Name>,
+    path: Option<&hir::ModPath>,
 ) -> Option<CompletionItem> {
     let _p = profile::span("render_struct_pat");
 
@@ -26,7 +27,10 @@ pub(crate) fn render_struct_pat(
         return None;
     }
 
-    let name = local_name.unwrap_or_else(|| strukt.name(ctx.db())).to_smol_str();
+    let name = match path {
+        Some(path) => path.to_string().into(),
+        None => local_name.unwrap_or_else(|| strukt.name(ctx.db())).to_smol_str(),
+    };
     let pat = render_pat(&ctx, &name, strukt.kind(ctx.db()), &visible_fields, fields_omitted)?;
 
     Some(build_completion(ctx, name, pat, strukt))
@@ -52,6 +56,39 @@ pub(crate) fn render_variant_pat(
     Some(build_completion(ctx, name, pat, variant))
 }
 
+/// Renders `[first, rest @ ..]`, and `[]` if it can be matched, for a slice or array of
+/// length `len`.
+pub(crate) fn render_slice_pats(
+    ctx: RenderContext<'_>,
+    len: Option<u64>,
+    refutable: bool,
+) -> Vec<CompletionItem> {
+    let _p = profile::span("render_slice_pats");
+
+    let mut res = Vec::new();
+    let mut add = |label: &str, snippet: &str| {
+        let mut item = CompletionItem::new(CompletionItemKind::Binding, ctx.source_range(), label);
+        item.set_relevance(ctx.completion_relevance());
+        match ctx.snippet_cap() {
+            Some(snippet_cap) => item.detail(snippet).insert_snippet(snippet_cap, snippet),
+            None => item.detail(label).insert_text(label),
+        };
+        res.push(item.build());
+    };
+    // A slice can always be empty, an array has a fixed length.
+    let (can_be_empty, can_be_nonempty) = match len {
+        Some(len) => (len == 0, len > 0),
+        None => (refutable, refutable),
+    };
+    if can_be_nonempty {
+        add("[first, rest @ ..]", "[${1:first}, ${2:rest} @ ..]$0");
+    }
+    if can_be_empty {
+        add("[]", "[]$0");
+    }
+    res
+}
+
 fn build_completion(
     ctx: RenderContext<'_>,
     name: SmolStr,
@@ -60,7 +97,7 @@ fn build_completion(
 ) -> CompletionItem {
     let mut item = CompletionItem::new(CompletionItemKind::Binding, ctx.source_range(), name);
     item.set_documentation(ctx.docs(def))
-        .set_deprecated(ctx.is_deprecated(def))
+        .set_deprecation(ctx.deprecation(def))
         .detail(&pat)
         .set_relevance(ctx.completion_relevance());
     match ctx.snippet_cap() {
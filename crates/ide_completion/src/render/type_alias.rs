@@ -38,7 +38,9 @@ fn render(
 
     let mut item = CompletionItem::new(SymbolKind::TypeAlias, ctx.source_range(), name.clone());
     item.set_documentation(ctx.docs(type_alias))
-        .set_deprecated(ctx.is_deprecated(type_alias) || ctx.is_deprecated_assoc_item(type_alias))
+        .set_deprecation(
+            ctx.deprecation(type_alias).or_else(|| ctx.assoc_item_deprecation(type_alias)),
+        )
         .detail(detail)
         .set_relevance(ctx.completion_relevance());
 
@@ -64,7 +64,7 @@ pub(crate) fn render_union_literal(
     );
 
     item.set_documentation(ctx.docs(un))
-        .set_deprecated(ctx.is_deprecated(un))
+        .set_deprecation(ctx.deprecation(un))
         .detail(&detail)
         .set_relevance(ctx.completion_relevance());
 
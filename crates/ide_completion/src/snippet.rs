@@ -55,8 +55,11 @@ use std::ops::Deref;
 // There is also a special placeholder, `${receiver}`, which will be replaced by the receiver expression for postfix snippets, or a `$0` tabstop in case of normal snippets.
 // This replacement for normal snippets allows you to reuse a snippet for both post- and prefix in a single definition.
 //
-// For the VSCode editor, rust-analyzer also ships with a small set of defaults which can be removed
-// by overwriting the settings object mentioned above, the defaults are:
+// The `${module}` and `${crate}` placeholders are replaced by the names of the module and the crate the snippet is inserted in,
+// which lets item templates refer to where they are used.
+//
+// For the VSCode editor, rust-analyzer also ships with a small set of defaults, including templates for common items, which
+// can be removed by overwriting the settings object mentioned above, the defaults are:
 // [source,json]
 // ----
 // {
@@ -98,6 +101,67 @@ use std::ops::Deref;
 //         "body": "Some(${receiver})",
 //         "description": "Wrap the expression in an `Option::Some`",
 //         "scope": "expr"
+//     },
+//     "Error enum": {
+//         "prefix": "errenum",
+//         "body": [
+//             "/// Errors of the `${module}` module.",
+//             "#[derive(Debug, Error)]",
+//             "pub enum ${1:Error} {",
+//             "\t#[error(\"${2:message}\")]",
+//             "\t${3:Variant},$0",
+//             "}",
+//             "",
+//             "pub type Result<T, E = $1> = std::result::Result<T, E>;"
+//         ],
+//         "requires": "thiserror::Error",
+//         "description": "Define an error enum deriving `thiserror::Error`",
+//         "scope": "item"
+//     },
+//     "Builder": {
+//         "prefix": "builder",
+//         "body": [
+//             "#[derive(Debug, Default)]",
+//             "pub struct ${1:Config}Builder {",
+//             "\t${2:field}: Option<${3:String}>,",
+//             "}",
+//             "",
+//             "impl $1Builder {",
+//             "\tpub fn $2(mut self, $2: $3) -> Self {",
+//             "\t\tself.$2 = Some($2);",
+//             "\t\tself",
+//             "\t}",
+//             "",
+//             "\tpub fn build(self) -> $1 {",
+//             "\t\t$1 { $2: self.$2.unwrap_or_default() }$0",
+//             "\t}",
+//             "}"
+//         ],
+//         "description": "Define a builder for a struct",
+//         "scope": "item"
+//     },
+//     "Typestate": {
+//         "prefix": "typestate",
+//         "body": [
+//             "pub struct ${1:Connection}<S> {",
+//             "\tstate: S,",
+//             "}",
+//             "",
+//             "pub struct ${2:Closed};",
+//             "pub struct ${3:Open};",
+//             "",
+//             "impl $1<$2> {",
+//             "\tpub fn ${4:open}(self) -> $1<$3> {",
+//             "\t\t$1 { state: $3 }",
+//             "\t}",
+//             "}",
+//             "",
+//             "impl $1<$3> {",
+//             "\t$0",
+//             "}"
+//         ],
+//         "description": "Define a type whose methods depend on a state type parameter",
+//         "scope": "item"
 //     }
 // }
 // ----
@@ -164,15 +228,27 @@ impl Snippet {
         import_edits(ctx, import_scope, &self.requires)
     }
 
-    pub fn snippet(&self) -> String {
-        self.snippet.replace("${receiver}", "$0")
+    pub(crate) fn snippet(&self, ctx: &CompletionContext) -> String {
+        expand_names(ctx, &self.snippet).replace("${receiver}", "$0")
     }
 
-    pub fn postfix_snippet(&self, receiver: &str) -> String {
-        self.snippet.replace("${receiver}", receiver)
+    pub(crate) fn postfix_snippet(&self, ctx: &CompletionContext, receiver: &str) -> String {
+        expand_names(ctx, &self.snippet).replace("${receiver}", receiver)
     }
 }
 
+/// Replaces the `${module}` and `${crate}` placeholders with the names of the module and the
+/// crate the snippet is completed in. The crate root is named after its crate.
+fn expand_names(ctx: &CompletionContext, snippet: &str) -> String {
+    let module = match ctx.module {
+        Some(it) => it,
+        None => return snippet.to_owned(),
+    };
+    let krate = module.krate().display_name(ctx.db).map(|it| it.to_string()).unwrap_or_default();
+    let module = module.name(ctx.db).map_or_else(|| krate.clone(), |it| it.to_string());
+    snippet.replace("${module}", &module).replace("${crate}", &krate)
+}
+
 fn import_edits(
     ctx: &CompletionContext,
     import_scope: &ImportScope,
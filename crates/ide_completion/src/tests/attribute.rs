@@ -659,6 +659,33 @@ mod cfg {
             "#]],
         );
     }
+
+    #[test]
+    fn cfg_values_from_crate() {
+        check(
+            r#"
+//- /main.rs crate:main cfg:feature=foo,feature=bar
+#[cfg(feature = $0)]
+fn f() {}
+"#,
+            expect![[r#"
+                ba bar
+                ba foo
+            "#]],
+        );
+    }
+
+    #[test]
+    fn cfg_attr_predicate() {
+        check(
+            r#"#[cfg_attr(target_pointer_width = $0, derive(Debug))] struct S;"#,
+            expect![[r#"
+                ba 16
+                ba 32
+                ba 64
+            "#]],
+        );
+    }
 }
 
 mod derive {
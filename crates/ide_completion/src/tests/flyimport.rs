@@ -1170,3 +1170,21 @@ struct Foo;
 "#,
     );
 }
+
+#[test]
+fn flyimport_doc_alias() {
+    check(
+        r#"
+mod sys {
+    #[doc(alias = "mmap")]
+    pub fn map_memory() {}
+}
+fn main() {
+    mma$0
+}
+"#,
+        expect![[r#"
+            fn map_memory() (alias mmap) (use sys::map_memory) fn()
+        "#]],
+    );
+}
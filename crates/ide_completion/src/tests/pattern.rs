@@ -538,3 +538,56 @@ fn f(v: u32) {
         "#]],
     );
 }
+
+#[test]
+fn completes_qualified_struct_pat_from_expected_type() {
+    check_empty(
+        r#"
+mod geo {
+    pub struct Point { pub x: i32, pub y: i32 }
+}
+fn f(p: geo::Point) {
+    match p {
+        a$0
+    }
+}
+"#,
+        expect![[r#"
+            kw ref
+            kw mut
+            bn geo::Point geo::Point { x$1, y$2 }$0
+            md geo
+        "#]],
+    );
+}
+
+#[test]
+fn completes_slice_pats() {
+    check_empty(
+        r#"
+fn f(s: &[u8]) {
+    match s {
+        a$0
+    }
+}
+"#,
+        expect![[r#"
+            kw ref
+            kw mut
+            bn [first, rest @ ..] [${1:first}, ${2:rest} @ ..]$0
+            bn []                 []$0
+        "#]],
+    );
+    check_empty(
+        r#"
+fn f(a: [u8; 2]) {
+    let b$0 = a;
+}
+"#,
+        expect![[r#"
+            kw ref
+            kw mut
+            bn [first, rest @ ..] [${1:first}, ${2:rest} @ ..]$0
+        "#]],
+    );
+}
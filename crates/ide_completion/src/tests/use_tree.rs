@@ -1,7 +1,7 @@
 //! Completion tests for use trees.
 use expect_test::{expect, Expect};
 
-use crate::tests::completion_list;
+use crate::tests::{check_edit, completion_list};
 
 fn check(ra_fixture: &str, expect: Expect) {
     let actual = completion_list(ra_fixture);
@@ -23,7 +23,9 @@ mod foo {}
 "#,
         expect![[r#"
             md foo
+            sn foo::{self, …}
             md other_crate
+            sn other_crate::{self, …}
             kw self::
             kw super::
             kw crate::
@@ -62,6 +64,7 @@ use self::{foo::*, bar$0};
         expect![[r#"
             st S
             md foo
+            sn foo::{self, …}
         "#]],
     );
 }
@@ -93,6 +96,7 @@ use foo::{$0}
         expect![[r#"
             kw self
             md bar
+            sn bar::{self, …}
         "#]],
     );
 }
@@ -128,6 +132,7 @@ use foo::{bar::{$0}}
         expect![[r#"
             kw self
             md baz
+            sn baz::{self, …}
         "#]],
     );
 }
@@ -183,6 +188,7 @@ struct Bar;
 "#,
         expect![[r#"
             md foo
+            sn foo::{self, …}
             st Bar
         "#]],
     );
@@ -203,7 +209,9 @@ struct Bar;
             kw super::
             st Bar
             md bar
+            sn bar::{self, …}
             md foo
+            sn foo::{self, …}
         "#]],
     );
 }
@@ -223,6 +231,7 @@ mod a {
         expect![[r#"
             kw super::
             md b
+            sn b::{self, …}
             ct A
         "#]],
     );
@@ -239,6 +248,7 @@ struct Bar;
 "#,
         expect![[r#"
             md foo
+            sn foo::{self, …}
             st Bar
         "#]],
     );
@@ -257,6 +267,7 @@ pub mod foo {}
         expect![[r#"
             st Foo
             md foo
+            sn foo::{self, …}
         "#]],
     );
 }
@@ -271,6 +282,7 @@ pub use $0;
 "#,
         expect![[r#"
             md bar
+            sn bar::{self, …}
             kw self::
             kw super::
             kw crate::
@@ -288,6 +300,7 @@ use {$0};
 "#,
         expect![[r#"
             md bar
+            sn bar::{self, …}
             kw self::
             kw super::
             kw crate::
@@ -310,3 +323,109 @@ use self::foo::impl$0
         "#]],
     );
 }
+
+#[test]
+fn module_with_self_group() {
+    check_edit(
+        "foo::{self, …}",
+        r#"
+mod foo { pub struct Bar; }
+use self::f$0
+"#,
+        r#"
+mod foo { pub struct Bar; }
+use self::foo::{self, $0}
+"#,
+    );
+    check(
+        r#"
+mod foo { pub mod bar {} }
+use self::f$0::bar;
+"#,
+        expect![[r#"
+            md foo
+        "#]],
+    );
+}
+
+#[test]
+fn macros_in_use_tree() {
+    check(
+        r#"
+//- proc_macros: derive_identity
+use proc_macros::$0
+"#,
+        expect![[r#"
+            de DeriveIdentity proc_macro DeriveIdentity
+        "#]],
+    );
+    check(
+        r#"
+//- proc_macros: identity
+use proc_macros::$0
+"#,
+        expect![[r#"
+            at identity proc_macro identity
+        "#]],
+    );
+    check(
+        r#"
+//- proc_macros: mirror
+use proc_macros::$0
+"#,
+        expect![[r#"
+            ma mirror proc_macro mirror
+        "#]],
+    );
+    check_edit(
+        "rules",
+        r#"
+//- /main.rs crate:main deps:dep
+use dep::r$0
+//- /dep.rs crate:dep
+#[macro_export]
+macro_rules! rules { () => {} }
+"#,
+        r#"
+use dep::rules
+"#,
+    );
+    check_edit(
+        "two",
+        r#"
+//- /main.rs crate:main deps:dep
+use dep::t$0
+//- /dep.rs crate:dep
+pub macro two() {}
+"#,
+        r#"
+use dep::two
+"#,
+    );
+}
+
+#[test]
+fn glob_hint_for_large_modules() {
+    cov_mark::check!(use_tree_glob_hint);
+    let actual = completion_list(
+        r#"
+mod prelude {
+    pub struct A; pub struct B; pub struct C; pub struct D;
+    pub struct E; pub struct F; pub struct G; pub struct H;
+}
+use prelude::$0
+"#,
+    );
+    assert!(actual.ends_with("kw *\n"), "{}", actual);
+    check(
+        r#"
+mod prelude {
+    pub struct A;
+}
+use prelude::$0
+"#,
+        expect![[r#"
+            st A
+        "#]],
+    );
+}
@@ -64,6 +64,7 @@ impl RootDatabase {
             // SourceDatabase
             base_db::ParseQuery
             base_db::CrateGraphQuery
+            base_db::FileEditionQuery
 
             // SourceDatabaseExt
             base_db::FileTextQuery
@@ -77,6 +78,7 @@ impl RootDatabase {
             hir::db::MacroDefQuery
             hir::db::ParseMacroExpansionQuery
             hir::db::MacroExpandQuery
+            hir::db::SpeculativeExpansionQuery
             hir::db::HygieneFrameQuery
             hir::db::InternMacroCallQuery
 
@@ -128,10 +130,13 @@ impl RootDatabase {
             hir::db::ImplDatumQuery
             hir::db::FnDefDatumQuery
             hir::db::ReturnTypeImplTraitsQuery
+            hir::db::TypeAliasImplTraitsQuery
+            hir::db::TraitAliasBoundsQuery
             hir::db::InternCallableDefQuery
             hir::db::InternTypeOrConstParamIdQuery
             hir::db::InternImplTraitIdQuery
             hir::db::InternClosureQuery
+            hir::db::InternGeneratorQuery
             hir::db::AssociatedTyValueQuery
             hir::db::TraitSolveQueryQuery
             hir::db::InternTypeOrConstParamIdQuery
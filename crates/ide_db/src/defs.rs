@@ -7,14 +7,14 @@
 
 use arrayvec::ArrayVec;
 use hir::{
-    Adt, AsAssocItem, AssocItem, BuiltinAttr, BuiltinType, Const, Field, Function, GenericParam,
-    HasVisibility, Impl, ItemInNs, Label, Local, Macro, Module, ModuleDef, Name, PathResolution,
-    Semantics, Static, ToolModule, Trait, TypeAlias, Variant, Visibility,
+    Adt, AsAssocItem, AssocItem, BuiltinAttr, BuiltinType, Const, Deprecation, Field, Function,
+    GenericParam, HasAttrs, HasVisibility, Impl, ItemInNs, Label, Local, Macro, Module, ModuleDef,
+    Name, PathResolution, Semantics, Static, ToolModule, Trait, TypeAlias, Variant, Visibility,
 };
 use stdx::impl_from;
 use syntax::{
     ast::{self, AstNode},
-    match_ast, SyntaxKind, SyntaxNode, SyntaxToken,
+    match_ast, SyntaxKind, SyntaxNode, SyntaxToken, T,
 };
 
 use crate::RootDatabase;
@@ -114,12 +114,37 @@ impl Definition {
         };
         Some(name)
     }
+
+    /// The contents of the `#[deprecated]` attribute of this definition, if any.
+    pub fn deprecation(&self, db: &RootDatabase) -> Option<Deprecation> {
+        let attrs = match self {
+            Definition::Macro(it) => it.attrs(db),
+            Definition::Field(it) => it.attrs(db),
+            Definition::Module(it) => it.attrs(db),
+            Definition::Function(it) => it.attrs(db),
+            Definition::Adt(it) => it.attrs(db),
+            Definition::Variant(it) => it.attrs(db),
+            Definition::Const(it) => it.attrs(db),
+            Definition::Static(it) => it.attrs(db),
+            Definition::Trait(it) => it.attrs(db),
+            Definition::TypeAlias(it) => it.attrs(db),
+            Definition::BuiltinType(_)
+            | Definition::SelfType(_)
+            | Definition::Local(_)
+            | Definition::GenericParam(_)
+            | Definition::Label(_)
+            | Definition::BuiltinAttr(_)
+            | Definition::ToolModule(_) => return None,
+        };
+        attrs.deprecation()
+    }
 }
 
 #[derive(Debug)]
 pub enum IdentClass {
     NameClass(NameClass),
     NameRefClass(NameRefClass),
+    Operator(OperatorClass),
 }
 
 impl IdentClass {
@@ -142,6 +167,9 @@ impl IdentClass {
         sema: &Semantics<RootDatabase>,
         token: &SyntaxToken,
     ) -> Option<IdentClass> {
+        if let Some(operator) = OperatorClass::classify_token(sema, token) {
+            return Some(IdentClass::Operator(operator));
+        }
         let parent = token.parent()?;
         Self::classify_node(sema, &parent)
     }
@@ -170,11 +198,58 @@ impl IdentClass {
                 res.push(Definition::Local(local_ref));
                 res.push(Definition::Field(field_ref));
             }
+            IdentClass::Operator(
+                OperatorClass::Bin(func) | OperatorClass::Index(func) | OperatorClass::Try(func),
+            ) => res.push(Definition::Function(func)),
         }
         res
     }
 }
 
+/// An overloadable operator, resolved to the trait method it calls. This is the impl method if
+/// the impl is known, the trait method otherwise.
+#[derive(Debug)]
+pub enum OperatorClass {
+    /// `+`, `==`, `+=` and the like in a binary expression.
+    Bin(Function),
+    /// The brackets of an index expression.
+    Index(Function),
+    /// The `?` of a try expression.
+    Try(Function),
+}
+
+impl OperatorClass {
+    pub fn classify_token(
+        sema: &Semantics<RootDatabase>,
+        token: &SyntaxToken,
+    ) -> Option<OperatorClass> {
+        let parent = token.parent()?;
+        match_ast! {
+            match parent {
+                ast::BinExpr(bin_expr) => {
+                    if bin_expr.op_token()? != *token {
+                        return None;
+                    }
+                    sema.resolve_bin_expr(&bin_expr).map(OperatorClass::Bin)
+                },
+                ast::IndexExpr(index_expr) => {
+                    if !matches!(token.kind(), T!['['] | T![']']) {
+                        return None;
+                    }
+                    sema.resolve_index_expr(&index_expr).map(OperatorClass::Index)
+                },
+                ast::TryExpr(try_expr) => {
+                    if token.kind() != T![?] {
+                        return None;
+                    }
+                    sema.resolve_try_expr(&try_expr).map(OperatorClass::Try)
+                },
+                _ => None,
+            }
+        }
+    }
+}
+
 /// On a first blush, a single `ast::Name` defines a single definition at some
 /// scope. That is, that, by just looking at the syntactical category, we can
 /// unambiguously define the semantic category.
@@ -1,5 +1,5 @@
 //! See [`FamousDefs`].
-use hir::{Crate, Enum, Macro, Module, ScopeDef, Semantics, Trait};
+use hir::{Crate, Enum, Macro, Module, ScopeDef, Semantics, Struct, Trait};
 
 use crate::RootDatabase;
 
@@ -32,6 +32,26 @@ impl FamousDefs<'_, '_> {
         self.find_trait("core:cmp:Ord")
     }
 
+    pub fn core_cmp_PartialEq(&self) -> Option<Trait> {
+        self.find_trait("core:cmp:PartialEq")
+    }
+
+    pub fn core_cmp_Eq(&self) -> Option<Trait> {
+        self.find_trait("core:cmp:Eq")
+    }
+
+    pub fn core_clone_Clone(&self) -> Option<Trait> {
+        self.find_trait("core:clone:Clone")
+    }
+
+    pub fn core_fmt_Debug(&self) -> Option<Trait> {
+        self.find_trait("core:fmt:Debug")
+    }
+
+    pub fn core_hash_Hash(&self) -> Option<Trait> {
+        self.find_trait("core:hash:Hash")
+    }
+
     pub fn core_convert_From(&self) -> Option<Trait> {
         self.find_trait("core:convert:From")
     }
@@ -84,10 +104,30 @@ impl FamousDefs<'_, '_> {
         self.find_trait("core:marker:Copy")
     }
 
+    pub fn core_marker_Send(&self) -> Option<Trait> {
+        self.find_trait("core:marker:Send")
+    }
+
+    pub fn core_marker_Sync(&self) -> Option<Trait> {
+        self.find_trait("core:marker:Sync")
+    }
+
     pub fn core_macros_builtin_derive(&self) -> Option<Macro> {
         self.find_macro("core:macros:builtin:derive")
     }
 
+    pub fn core_default_Default_derive(&self) -> Option<Macro> {
+        self.find_macro("core:default:Default")
+    }
+
+    pub fn std_sync_LazyLock(&self) -> Option<Struct> {
+        self.find_struct("std:sync:LazyLock")
+    }
+
+    pub fn std_sync_OnceLock(&self) -> Option<Struct> {
+        self.find_struct("std:sync:OnceLock")
+    }
+
     pub fn alloc(&self) -> Option<Crate> {
         self.find_crate("alloc")
     }
@@ -119,9 +159,17 @@ impl FamousDefs<'_, '_> {
     }
 
     fn find_macro(&self, path: &str) -> Option<Macro> {
-        match self.find_def(path)? {
+        // Macros often share their name with a trait, like `Default`.
+        self.find_defs(path).into_iter().find_map(|def| match def {
             hir::ScopeDef::ModuleDef(hir::ModuleDef::Macro(it)) => Some(it),
             _ => None,
+        })
+    }
+
+    fn find_struct(&self, path: &str) -> Option<Struct> {
+        match self.find_def(path)? {
+            hir::ScopeDef::ModuleDef(hir::ModuleDef::Adt(hir::Adt::Struct(it))) => Some(it),
+            _ => None,
         }
     }
 
@@ -148,6 +196,14 @@ impl FamousDefs<'_, '_> {
     }
 
     fn find_def(&self, path: &str) -> Option<ScopeDef> {
+        self.find_defs(path).into_iter().next()
+    }
+
+    fn find_defs(&self, path: &str) -> Vec<ScopeDef> {
+        self.find_defs_inner(path).unwrap_or_default()
+    }
+
+    fn find_defs_inner(&self, path: &str) -> Option<Vec<ScopeDef>> {
         let db = self.0.db;
         let mut path = path.split(':');
         let trait_ = path.next_back()?;
@@ -164,8 +220,12 @@ impl FamousDefs<'_, '_> {
                 }
             })?;
         }
-        let def =
-            module.scope(db, None).into_iter().find(|(name, _def)| name.to_smol_str() == trait_)?.1;
-        Some(def)
+        let defs = module
+            .scope(db, None)
+            .into_iter()
+            .filter(|(name, _def)| name.to_smol_str() == trait_)
+            .map(|(_name, def)| def)
+            .collect();
+        Some(defs)
     }
 }
@@ -0,0 +1,267 @@
+//! How Rust types cross the FFI boundary: whether they have a C equivalent, and how it is
+//! spelled.
+//!
+//! The rules follow rustc's `improper_ctypes` lints: primitives, pointers and types with a
+//! `#[repr(C)]`, `#[repr(transparent)]` or integer representation are fine, types whose layout
+//! rustc is free to choose are not.
+
+use hir::{Adt, BuiltinType, HasAttrs, HirDisplay, Mutability, ReprKind, Type};
+
+use crate::{famous_defs::FamousDefs, RootDatabase};
+
+#[derive(Debug)]
+pub enum CTypeError {
+    /// The type has no C equivalent, like `String`.
+    NotFfiSafe { ty: Type, reason: &'static str },
+    /// The type isn't known well enough to tell, like a generic parameter.
+    Unknown,
+}
+
+impl CTypeError {
+    /// The message rustc's `improper_ctypes` would give.
+    pub fn message(&self, db: &RootDatabase) -> Option<String> {
+        match self {
+            CTypeError::NotFfiSafe { ty, reason } => {
+                Some(format!("`{}` is not FFI-safe: {}", ty.display(db), reason))
+            }
+            CTypeError::Unknown => None,
+        }
+    }
+}
+
+/// Spells out the declaration of `declarator`, like a variable name, with the C equivalent of
+/// `ty`: `int32_t x` or `const uint8_t *data`. An empty declarator gives an abstract one, as in
+/// casts and unnamed parameters.
+pub fn c_declaration(
+    famous_defs: &FamousDefs,
+    ty: &Type,
+    declarator: &str,
+) -> Result<String, CTypeError> {
+    declaration(famous_defs, ty, declarator, false, 0)
+}
+
+/// The return type of a function: like [`c_declaration`], with `()` and `!` becoming `void`.
+pub fn c_return_declaration(
+    famous_defs: &FamousDefs,
+    ty: &Type,
+    declarator: &str,
+) -> Result<String, CTypeError> {
+    if ty.is_unit() || ty.is_never() {
+        return Ok(format!("void {}", declarator));
+    }
+    c_declaration(famous_defs, ty, declarator)
+}
+
+/// `#[repr(transparent)]` structs and `Option`s are declared as their payload, which would never
+/// end for a struct that (erroneously) wraps itself.
+const MAX_DEPTH: usize = 16;
+
+fn declaration(
+    famous_defs: &FamousDefs,
+    ty: &Type,
+    declarator: &str,
+    is_const: bool,
+    depth: usize,
+) -> Result<String, CTypeError> {
+    if depth > MAX_DEPTH {
+        return Err(CTypeError::Unknown);
+    }
+    let db = famous_defs.0.db;
+    let not_ffi_safe = |reason| Err(CTypeError::NotFfiSafe { ty: ty.clone(), reason });
+    let base = |name: &str| {
+        let qualifier = if is_const { "const " } else { "" };
+        Ok(format!("{}{} {}", qualifier, name, declarator).trim_end().to_string())
+    };
+
+    if let Some((pointee, mutability)) = ty.as_reference().or_else(|| ty.as_raw_ptr()) {
+        return pointer(famous_defs, ty, &pointee, mutability, declarator, is_const, depth);
+    }
+    if ty.is_str() {
+        return not_ffi_safe("string slices have no C equivalent");
+    }
+    if let Some(builtin) = ty.as_builtin() {
+        return match c_builtin(builtin) {
+            Ok(name) => base(name),
+            Err(reason) => not_ffi_safe(reason),
+        };
+    }
+    if let Some((elem, len)) = ty.as_array() {
+        let declarator = match declarator.starts_with('*') {
+            true => format!("({})[{}]", declarator, len),
+            false => format!("{}[{}]", declarator, len),
+        };
+        return declaration(famous_defs, &elem, &declarator, is_const, depth + 1);
+    }
+    if ty.is_fn_ptr() {
+        let callable = ty.as_callable(db).ok_or(CTypeError::Unknown)?;
+        let params = callable
+            .params(db)
+            .iter()
+            .map(|(_, param)| declaration(famous_defs, param, "", false, depth + 1))
+            .collect::<Result<Vec<_>, _>>()?;
+        let params = if params.is_empty() { "void".to_string() } else { params.join(", ") };
+        let qualifier = if is_const { "const " } else { "" };
+        let declarator = format!("(*{}{})({})", qualifier, declarator, params);
+        let ret = callable.return_type();
+        return match ret.is_unit() || ret.is_never() {
+            true => Ok(format!("void {}", declarator)),
+            false => declaration(famous_defs, &ret, &declarator, false, depth + 1),
+        };
+    }
+    if ty.is_slice() {
+        return not_ffi_safe("slices have no C equivalent");
+    }
+    if ty.as_dyn_trait().is_some() {
+        return not_ffi_safe("trait objects have no C equivalent");
+    }
+    if ty.is_tuple() {
+        return not_ffi_safe("tuples have unspecified layout");
+    }
+
+    let adt = ty.as_adt().ok_or(CTypeError::Unknown)?;
+    // `Option<&T>`, `Option<fn()>`, `Option<Box<T>>` and `Option<NonNull<T>>` are nullable
+    // pointers.
+    if let Some(inner) = option_payload(famous_defs, ty) {
+        let is_transparent = matches!(
+            inner.as_adt(),
+            Some(Adt::Struct(it)) if it.repr(db) == Some(ReprKind::Transparent)
+        );
+        if inner.is_reference() || inner.is_fn_ptr() || is_box(db, &inner) || is_transparent {
+            return declaration(famous_defs, &inner, declarator, is_const, depth + 1);
+        }
+        return not_ffi_safe("enum has no representation hint");
+    }
+    if is_box(db, ty) {
+        let pointee = ty.type_arguments().next().ok_or(CTypeError::Unknown)?;
+        return pointer(famous_defs, ty, &pointee, Mutability::Mut, declarator, is_const, depth);
+    }
+    let name = adt.name(db).to_smol_str();
+    match adt {
+        Adt::Struct(strukt) => match strukt.repr(db) {
+            Some(ReprKind::C) => base(&format!("struct {}", name)),
+            Some(ReprKind::Transparent) => {
+                let field = ty
+                    .fields(db)
+                    .into_iter()
+                    .map(|(_, ty)| ty)
+                    .find(|ty| ty.layout(db).map_or(true, |it| it.size != 0))
+                    .ok_or(CTypeError::Unknown)?;
+                declaration(famous_defs, &field, declarator, is_const, depth + 1)
+            }
+            None => not_ffi_safe("this struct has unspecified layout"),
+            Some(_) => Err(CTypeError::Unknown),
+        },
+        Adt::Union(union) => match union.repr(db) {
+            Some(ReprKind::C) => base(&format!("union {}", name)),
+            None => not_ffi_safe("this union has unspecified layout"),
+            Some(_) => Err(CTypeError::Unknown),
+        },
+        Adt::Enum(enum_) => {
+            if enum_.is_data_carrying(db) {
+                return match enum_.repr(db) {
+                    None => not_ffi_safe("enum has no representation hint"),
+                    Some(_) => Err(CTypeError::Unknown),
+                };
+            }
+            match enum_.repr(db) {
+                Some(ReprKind::C) => base(&format!("enum {}", name)),
+                // Declared as a `typedef` of the discriminant type.
+                Some(ReprKind::Int(_)) => base(&name),
+                None => not_ffi_safe("enum has no representation hint"),
+                Some(_) => Err(CTypeError::Unknown),
+            }
+        }
+    }
+}
+
+fn pointer(
+    famous_defs: &FamousDefs,
+    ty: &Type,
+    pointee: &Type,
+    mutability: Mutability,
+    declarator: &str,
+    is_const: bool,
+    depth: usize,
+) -> Result<String, CTypeError> {
+    // Pointers to unsized types are fat, they have no C equivalent either.
+    if pointee.is_str() {
+        return Err(CTypeError::NotFfiSafe {
+            ty: ty.clone(),
+            reason: "string slices have no C equivalent",
+        });
+    }
+    if pointee.is_slice() {
+        return Err(CTypeError::NotFfiSafe {
+            ty: ty.clone(),
+            reason: "slices have no C equivalent",
+        });
+    }
+    if pointee.as_dyn_trait().is_some() {
+        return Err(CTypeError::NotFfiSafe {
+            ty: ty.clone(),
+            reason: "trait objects have no C equivalent",
+        });
+    }
+    let qualifier = if is_const { "const " } else { "" };
+    let declarator = format!("*{}{}", qualifier, declarator);
+    // `*const c_void` and friends are the usual opaque pointers.
+    if pointee.is_unit() {
+        let qualifier = if mutability == Mutability::Shared { "const " } else { "" };
+        return Ok(format!("{}void {}", qualifier, declarator));
+    }
+    match declaration(
+        famous_defs,
+        pointee,
+        &declarator,
+        mutability == Mutability::Shared,
+        depth + 1,
+    ) {
+        // Pointers to anything are FFI-safe, the pointee is opaque to C.
+        Err(CTypeError::NotFfiSafe { .. }) => {
+            let qualifier = if mutability == Mutability::Shared { "const " } else { "" };
+            Ok(format!("{}void {}", qualifier, declarator))
+        }
+        res => res,
+    }
+}
+
+fn c_builtin(builtin: BuiltinType) -> Result<&'static str, &'static str> {
+    let name = builtin.name().to_smol_str();
+    let res = match name.as_str() {
+        "bool" => "bool",
+        "i8" => "int8_t",
+        "i16" => "int16_t",
+        "i32" => "int32_t",
+        "i64" => "int64_t",
+        "isize" => "intptr_t",
+        "u8" => "uint8_t",
+        "u16" => "uint16_t",
+        "u32" => "uint32_t",
+        "u64" => "uint64_t",
+        "usize" => "uintptr_t",
+        "f32" => "float",
+        "f64" => "double",
+        "char" => return Err("the `char` type has no C equivalent"),
+        "i128" | "u128" => return Err("128-bit integers don't currently have a known stable ABI"),
+        // Only `str` is left.
+        _ => return Err("string slices have no C equivalent"),
+    };
+    Ok(res)
+}
+
+fn option_payload(famous_defs: &FamousDefs, ty: &Type) -> Option<Type> {
+    let option = famous_defs.core_option_Option()?;
+    if ty.as_adt()? != Adt::Enum(option) {
+        return None;
+    }
+    ty.type_arguments().next()
+}
+
+fn is_box(db: &RootDatabase, ty: &Type) -> bool {
+    match ty.as_adt() {
+        Some(adt @ Adt::Struct(_)) => {
+            adt.attrs(db).by_key("lang").string_value().map_or(false, |it| it == "owned_box")
+        }
+        _ => false,
+    }
+}
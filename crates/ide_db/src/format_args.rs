@@ -0,0 +1,127 @@
+//! What the identifiers in the format strings of `format_args!`-family macro calls refer to.
+//!
+//! `{name}` names either a `name = value` argument following the format string, or, since
+//! Rust 2021, a binding or constant captured from the surrounding scope.
+
+use hir::{ModuleDef, PathResolution, Semantics};
+use syntax::{
+    ast::{self, make},
+    AstNode, AstToken, NodeOrToken, SyntaxKind, SyntaxToken, TextRange, T,
+};
+
+use crate::{
+    defs::Definition,
+    syntax_helpers::format_string::{is_format_string, lex_format_specifiers, FormatSpecifier},
+    RootDatabase,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FormatArgTarget {
+    /// A `name = value` argument of the macro call, the token being `name`.
+    Named(SyntaxToken),
+    /// A binding, const or static captured from the surrounding scope.
+    Captured(Definition),
+}
+
+/// Returns the string literal `token` of the original file if it is the format string of a
+/// format macro call.
+pub fn format_string(sema: &Semantics<RootDatabase>, token: &SyntaxToken) -> Option<ast::String> {
+    let string = ast::String::cast(token.clone())?;
+    sema.descend_into_macros(token.clone())
+        .into_iter()
+        .filter_map(ast::String::cast)
+        .any(|it| is_format_string(&it))
+        .then(|| string)
+}
+
+/// The `{name}` and `{:name$}` identifiers of a format string, with their range in the file.
+pub fn identifiers(string: &ast::String) -> Vec<(TextRange, String)> {
+    let start = string.syntax().text_range().start();
+    let text = string.text();
+    let mut pieces = Vec::new();
+    lex_format_specifiers(string, &mut |range, kind| pieces.push((range, kind)));
+    pieces
+        .iter()
+        .enumerate()
+        .filter(|(idx, (_, kind))| {
+            // Other identifiers are the trait to format with, like `x` in `{:x}`.
+            matches!(kind, FormatSpecifier::Identifier)
+                && (matches!(
+                    idx.checked_sub(1).map(|it| &pieces[it].1),
+                    Some(FormatSpecifier::Open)
+                ) || matches!(pieces.get(idx + 1), Some((_, FormatSpecifier::DollarSign))))
+        })
+        .map(|(_, (range, _))| (*range + start, text[*range].to_string()))
+        .collect()
+}
+
+/// Whether the format string has placeholders taking arguments by position, like `{}`, `{0}`
+/// or `{:.*}`. Those can use named arguments too.
+pub fn has_positional_placeholders(string: &ast::String) -> bool {
+    let mut pieces = Vec::new();
+    lex_format_specifiers(string, &mut |_, kind| pieces.push(kind));
+    pieces.iter().any(|it| matches!(it, FormatSpecifier::Asterisk))
+        || pieces.windows(2).any(|pair| {
+            matches!(
+                pair,
+                [FormatSpecifier::Open, FormatSpecifier::Close | FormatSpecifier::Colon]
+                    | [FormatSpecifier::Open, FormatSpecifier::Integer]
+                    | [FormatSpecifier::Integer, FormatSpecifier::DollarSign]
+            )
+        })
+}
+
+/// The `name` tokens of the `name = value` arguments following the format string in the
+/// macro call.
+pub fn named_args(string: &ast::String) -> Vec<SyntaxToken> {
+    let tt = match string.syntax().parent().and_then(ast::TokenTree::cast) {
+        Some(it) => it,
+        None => return Vec::new(),
+    };
+    let tokens: Vec<_> =
+        tt.syntax().children_with_tokens().filter(|it| !it.kind().is_trivia()).collect();
+    let mut res = Vec::new();
+    let mut after_string = false;
+    let mut at_arg_start = false;
+    for (idx, element) in tokens.iter().enumerate() {
+        match element {
+            NodeOrToken::Token(token) if token == string.syntax() => after_string = true,
+            NodeOrToken::Token(token) if token.kind() == T![,] => {
+                at_arg_start = after_string;
+                continue;
+            }
+            NodeOrToken::Token(token)
+                if at_arg_start
+                    && token.kind() == SyntaxKind::IDENT
+                    && tokens.get(idx + 1).map(|it| it.kind()) == Some(T![=]) =>
+            {
+                res.push(token.clone())
+            }
+            _ => (),
+        }
+        at_arg_start = false;
+    }
+    res
+}
+
+/// Resolves the identifier `name` of the format string `string`.
+pub fn resolve(
+    sema: &Semantics<RootDatabase>,
+    string: &ast::String,
+    name: &str,
+) -> Option<FormatArgTarget> {
+    if let Some(token) = named_args(string).into_iter().find(|it| it.text() == name) {
+        return Some(FormatArgTarget::Named(token));
+    }
+    let expanded = sema
+        .descend_into_macros(string.syntax().clone())
+        .into_iter()
+        .find(|it| ast::String::cast(it.clone()).map_or(false, |it| is_format_string(&it)))?;
+    let scope = sema.scope(&expanded.parent()?);
+    let def = match scope.speculative_resolve(&make::ext::ident_path(name))? {
+        PathResolution::Local(it) => Definition::Local(it),
+        PathResolution::Def(def @ (ModuleDef::Const(_) | ModuleDef::Static(_))) => def.into(),
+        _ => return None,
+    };
+    Some(FormatArgTarget::Captured(def))
+}
@@ -8,6 +8,8 @@ pub mod active_parameter;
 pub mod assists;
 pub mod defs;
 pub mod famous_defs;
+pub mod ffi;
+pub mod format_args;
 pub mod helpers;
 pub mod items_locator;
 pub mod label;
@@ -135,6 +137,7 @@ impl RootDatabase {
         db.set_local_roots_with_durability(Default::default(), Durability::HIGH);
         db.set_library_roots_with_durability(Default::default(), Durability::HIGH);
         db.set_enable_proc_attr_macros(false);
+        db.set_trait_solver_fuel(hir::DEFAULT_TRAIT_SOLVER_FUEL);
         db.update_lru_capacity(lru_capacity);
         db
     }
@@ -144,6 +147,7 @@ impl RootDatabase {
         base_db::ParseQuery.in_db_mut(self).set_lru_capacity(lru_capacity);
         hir::db::ParseMacroExpansionQuery.in_db_mut(self).set_lru_capacity(lru_capacity);
         hir::db::MacroExpandQuery.in_db_mut(self).set_lru_capacity(lru_capacity);
+        hir::db::SpeculativeExpansionQuery.in_db_mut(self).set_lru_capacity(lru_capacity);
     }
 }
 
@@ -238,5 +242,6 @@ impl SnippetCap {
 
 #[cfg(test)]
 mod tests {
+    mod hir_visitors;
     mod sourcegen_lints;
 }
@@ -27,7 +27,7 @@ use either::Either;
 use hir::{AsAssocItem, FieldSource, HasSource, InFile, ModuleSource, Semantics};
 use stdx::never;
 use syntax::{
-    ast::{self, HasName},
+    ast::{self, HasAttrs, HasName},
     AstNode, SyntaxKind, TextRange, T,
 };
 use text_edit::{TextEdit, TextEditBuilder};
@@ -175,23 +175,45 @@ fn rename_mod(
         bail!("Invalid name `{0}`: cannot rename module to {0}", new_name);
     }
 
+    if module.is_crate_root(sema.db) {
+        bail!("Cannot rename a crate root module");
+    }
+
     let mut source_change = SourceChange::default();
 
+    let declaration = module.declaration_source(sema.db);
     let InFile { file_id, value: def_source } = module.definition_source(sema.db);
-    let file_id = file_id.original_file(sema.db);
-    if let ModuleSource::SourceFile(..) = def_source {
-        // mod is defined in path/to/dir/mod.rs
-        let path = if module.is_mod_rs(sema.db) {
-            format!("../{}/mod.rs", new_name)
-        } else {
-            format!("{}.rs", new_name)
+    let anchor = file_id.original_file(sema.db);
+    // With a `#[path]` attribute, the file isn't named after the module.
+    let has_path_attr = declaration.as_ref().map_or(false, |it| path_attr(&it.value).is_some());
+    if let (ModuleSource::SourceFile(..), false) = (def_source, has_path_attr) {
+        let is_mod_rs = module.is_mod_rs(sema.db);
+        let has_detached_child = module.children(sema.db).any(|child| {
+            matches!(child.definition_source(sema.db).value, ModuleSource::SourceFile(..))
+        });
+
+        if !is_mod_rs {
+            let dst = AnchoredPathBuf { anchor, path: format!("{}.rs", new_name) };
+            source_change.push_file_system_edit(FileSystemEdit::MoveFile { src: anchor, dst });
+        }
+
+        // The directory holds the module itself if it's a `mod.rs`, and its
+        // submodules otherwise. In the former case, the anchor is inside it.
+        let dir_paths = match (is_mod_rs, has_detached_child, module.name(sema.db)) {
+            (true, _, Some(name)) => Some((format!("../{}", name), format!("../{}", new_name))),
+            (false, true, Some(name)) => Some((name.to_string(), new_name.to_string())),
+            _ => None,
         };
-        let dst = AnchoredPathBuf { anchor: file_id, path };
-        let move_file = FileSystemEdit::MoveFile { src: file_id, dst };
-        source_change.push_file_system_edit(move_file);
+        if let Some((src, dst)) = dir_paths {
+            source_change.push_file_system_edit(FileSystemEdit::MoveDir {
+                src: AnchoredPathBuf { anchor, path: src },
+                src_id: anchor,
+                dst: AnchoredPathBuf { anchor, path: dst },
+            });
+        }
     }
 
-    if let Some(src) = module.declaration_source(sema.db) {
+    if let Some(src) = declaration {
         let file_id = src.file_id.original_file(sema.db);
         match src.value.name() {
             Some(name) => {
@@ -218,6 +240,23 @@ fn rename_mod(
     Ok(source_change)
 }
 
+/// The string literal of the `#[path = "..."]` attribute of a module
+/// declaration, if it has one.
+pub fn path_attr(module: &ast::Module) -> Option<ast::String> {
+    module.attrs().find_map(|attr| {
+        if attr.simple_name()? != "path" {
+            return None;
+        }
+        match attr.meta()?.expr()? {
+            ast::Expr::Literal(lit) => match lit.kind() {
+                ast::LiteralKind::String(it) => Some(it),
+                _ => None,
+            },
+            _ => None,
+        }
+    })
+}
+
 fn rename_reference(
     sema: &Semantics<RootDatabase>,
     mut def: Definition,
@@ -292,6 +331,14 @@ fn rename_reference(
     source_change.extend(usages.iter().map(|(&file_id, references)| {
         (file_id, source_edit_from_references(references, def, new_name))
     }));
+    // `{name}` in format strings capturing the binding
+    for (file_id, ranges) in def.usages(sema).format_string_captures() {
+        let mut edit = TextEdit::builder();
+        for range in ranges {
+            edit.replace(range, new_name.to_string());
+        }
+        source_change.insert_source_edit(file_id, edit.finish());
+    }
 
     let mut insert_def_edit = |def| {
         let (file_id, edit) = source_edit_from_def(sema, def, new_name)?;
@@ -6,16 +6,17 @@
 
 use std::{convert::TryInto, mem, sync::Arc};
 
-use base_db::{FileId, FileRange, SourceDatabase, SourceDatabaseExt};
+use base_db::{CrateId, FileId, FileLoader, FileRange, SourceDatabase, SourceDatabaseExt};
 use hir::{
     AsAssocItem, DefWithBody, HasAttrs, HasSource, InFile, ModuleSource, Semantics, Visibility,
 };
 use once_cell::unsync::Lazy;
 use rustc_hash::FxHashMap;
-use syntax::{ast, match_ast, AstNode, TextRange, TextSize};
+use syntax::{ast, match_ast, AstNode, SyntaxKind, TextRange, TextSize};
 
 use crate::{
-    defs::{Definition, NameClass, NameRefClass},
+    defs::{Definition, NameClass, NameRefClass, OperatorClass},
+    format_args::{self, FormatArgTarget},
     RootDatabase,
 };
 
@@ -171,6 +172,19 @@ impl SearchScope {
         SearchScope::new(files.iter().map(|f| (*f, None)).collect())
     }
 
+    /// Splits the scope into one scope per crate, in crate order.
+    fn split_by_crate(self, db: &RootDatabase) -> Vec<SearchScope> {
+        let mut by_crate: FxHashMap<Option<CrateId>, FxHashMap<FileId, Option<TextRange>>> =
+            FxHashMap::default();
+        for (file_id, range) in self.entries {
+            let krate = db.relevant_crates(file_id).iter().min().copied();
+            by_crate.entry(krate).or_default().insert(file_id, range);
+        }
+        let mut by_crate: Vec<_> = by_crate.into_iter().collect();
+        by_crate.sort_by_key(|&(krate, _)| krate);
+        by_crate.into_iter().map(|(_, entries)| SearchScope::new(entries)).collect()
+    }
+
     pub fn intersection(&self, other: &SearchScope) -> SearchScope {
         let (mut small, mut large) = (&self.entries, &other.entries);
         if small.len() > large.len() {
@@ -240,6 +254,7 @@ impl Definition {
                 DefWithBody::Function(f) => f.source(db).map(|src| src.syntax().cloned()),
                 DefWithBody::Const(c) => c.source(db).map(|src| src.syntax().cloned()),
                 DefWithBody::Static(s) => s.source(db).map(|src| src.syntax().cloned()),
+                DefWithBody::Variant(v) => v.source(db).map(|src| src.syntax().cloned()),
             };
             return match def {
                 Some(def) => SearchScope::file_range(def.as_ref().original_file_range(db)),
@@ -369,17 +384,150 @@ impl<'a> FindUsages<'a> {
         res
     }
 
-    fn search(&self, sink: &mut dyn FnMut(FileId, FileReference) -> bool) {
-        let _p = profile::span("FindUsages:search");
+    /// Like [`FindUsages::all`], but searches one crate at a time and hands the
+    /// usages found in each crate to `sink` right away.
+    ///
+    /// Crates are visited in a fixed order, and the first `skip` of them are
+    /// left out. The search stops once `sink` returns `true`. Returns the number
+    /// of crates the search spans, including the skipped ones.
+    pub fn all_by_crate(
+        self,
+        skip: usize,
+        sink: &mut dyn FnMut(UsageSearchResult) -> bool,
+    ) -> usize {
+        let scopes = self.search_scope().split_by_crate(self.sema.db);
+        let len = scopes.len();
+        for scope in scopes.into_iter().skip(skip) {
+            let mut res = UsageSearchResult::default();
+            self.search_in(&scope, &mut |file_id, reference| {
+                res.references.entry(file_id).or_default().push(reference);
+                false
+            });
+            if sink(res) {
+                break;
+            }
+        }
+        len
+    }
+
+    /// Finds the operator expressions that call this function, e.g. the `+` in `a + b` for an
+    /// `Add::add` impl. These are not part of [`FindUsages::all`] as they can't be renamed.
+    pub fn operator_usages(&self) -> FxHashMap<FileId, Vec<TextRange>> {
+        let _p = profile::span("FindUsages:operator_usages");
+        let mut res: FxHashMap<FileId, Vec<TextRange>> = FxHashMap::default();
+        let func = match self.def {
+            Definition::Function(func) => func,
+            _ => return res,
+        };
+        let op = match operator_for_method(&func.name(self.sema.db).to_smol_str()) {
+            Some(op) => op,
+            None => return res,
+        };
         let sema = self.sema;
+        for (file_id, search_range) in self.search_scope().entries {
+            let text = sema.db.file_text(file_id);
+            let search_range =
+                search_range.unwrap_or_else(|| TextRange::up_to(TextSize::of(text.as_str())));
+            let tree = Lazy::new(|| sema.parse(file_id).syntax().clone());
 
-        let search_scope = {
-            let base = self.def.search_scope(sema.db);
-            match &self.scope {
-                None => base,
-                Some(scope) => base.intersection(scope),
+            for (idx, _) in text.match_indices(op) {
+                let offset: TextSize = idx.try_into().unwrap();
+                if !search_range.contains_inclusive(offset) {
+                    continue;
+                }
+                let token = match tree.token_at_offset(offset).right_biased() {
+                    Some(token) if token.text() == op => token,
+                    _ => continue,
+                };
+                let range = token.text_range();
+                let is_usage = sema.descend_into_macros(token).into_iter().any(|token| {
+                    let resolved = match OperatorClass::classify_token(sema, &token) {
+                        Some(
+                            OperatorClass::Bin(it)
+                            | OperatorClass::Index(it)
+                            | OperatorClass::Try(it),
+                        ) => it,
+                        None => return false,
+                    };
+                    resolved == func
+                });
+                if is_usage {
+                    res.entry(file_id).or_default().push(range);
+                }
+            }
+        }
+        res
+    }
+
+    /// Finds the `{name}` identifiers of format strings which capture this binding or constant.
+    /// These are not part of [`FindUsages::all`] as they are not name references.
+    pub fn format_string_captures(&self) -> FxHashMap<FileId, Vec<TextRange>> {
+        let _p = profile::span("FindUsages:format_string_captures");
+        let mut res: FxHashMap<FileId, Vec<TextRange>> = FxHashMap::default();
+        let sema = self.sema;
+        let name = match self.def {
+            Definition::Local(_) | Definition::Const(_) | Definition::Static(_) => {
+                match self.def.name(sema.db) {
+                    Some(it) => it.to_smol_str(),
+                    None => return res,
+                }
             }
+            _ => return res,
         };
+        for (file_id, search_range) in self.search_scope().entries {
+            let text = sema.db.file_text(file_id);
+            let search_range =
+                search_range.unwrap_or_else(|| TextRange::up_to(TextSize::of(text.as_str())));
+            let tree = Lazy::new(|| sema.parse(file_id).syntax().clone());
+
+            for (idx, _) in text.match_indices(name.as_str()) {
+                let offset: TextSize = idx.try_into().unwrap();
+                if !search_range.contains_inclusive(offset) {
+                    continue;
+                }
+                let token = match tree.token_at_offset(offset).right_biased() {
+                    Some(token) if token.kind() == SyntaxKind::STRING => token,
+                    _ => continue,
+                };
+                let string = match format_args::format_string(sema, &token) {
+                    Some(it) => it,
+                    None => continue,
+                };
+                let is_capture =
+                    format_args::identifiers(&string).into_iter().any(|(range, ident)| {
+                        range.start() == offset
+                            && ident == name.as_str()
+                            && format_args::resolve(sema, &string, &ident)
+                                == Some(FormatArgTarget::Captured(self.def))
+                    });
+                if is_capture {
+                    let range = TextRange::at(offset, TextSize::of(name.as_str()));
+                    res.entry(file_id).or_default().push(range);
+                }
+            }
+        }
+        res
+    }
+
+    fn search_scope(&self) -> SearchScope {
+        let base = self.def.search_scope(self.sema.db);
+        match &self.scope {
+            None => base,
+            Some(scope) => base.intersection(scope),
+        }
+    }
+
+    fn search(&self, sink: &mut dyn FnMut(FileId, FileReference) -> bool) {
+        self.search_in(&self.search_scope(), sink)
+    }
+
+    fn search_in(
+        &self,
+        search_scope: &SearchScope,
+        sink: &mut dyn FnMut(FileId, FileReference) -> bool,
+    ) {
+        let _p = profile::span("FindUsages:search");
+        let sema = self.sema;
 
         let name = match self.def {
             // special case crate modules as these do not have a proper name
@@ -433,7 +581,7 @@ impl<'a> FindUsages<'a> {
             })
         }
 
-        for (text, file_id, search_range) in scope_files(sema, &search_scope) {
+        for (text, file_id, search_range) in scope_files(sema, search_scope) {
             let tree = Lazy::new(move || sema.parse(file_id).syntax().clone());
 
             // Search for occurrences of the items name
@@ -777,3 +925,39 @@ impl ReferenceCategory {
         mode.or(Some(ReferenceCategory::Read))
     }
 }
+
+/// The operator whose overload is implemented by a trait method called `name`.
+fn operator_for_method(name: &str) -> Option<&'static str> {
+    let op = match name {
+        "add" => "+",
+        "sub" => "-",
+        "mul" => "*",
+        "div" => "/",
+        "rem" => "%",
+        "bitand" => "&",
+        "bitor" => "|",
+        "bitxor" => "^",
+        "shl" => "<<",
+        "shr" => ">>",
+        "add_assign" => "+=",
+        "sub_assign" => "-=",
+        "mul_assign" => "*=",
+        "div_assign" => "/=",
+        "rem_assign" => "%=",
+        "bitand_assign" => "&=",
+        "bitor_assign" => "|=",
+        "bitxor_assign" => "^=",
+        "shl_assign" => "<<=",
+        "shr_assign" => ">>=",
+        "eq" => "==",
+        "ne" => "!=",
+        "lt" => "<",
+        "le" => "<=",
+        "gt" => ">",
+        "ge" => ">=",
+        "index" => "[",
+        "branch" => "?",
+        _ => return None,
+    };
+    Some(op)
+}
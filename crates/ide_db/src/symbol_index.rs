@@ -43,7 +43,7 @@ use rustc_hash::FxHashSet;
 
 use crate::RootDatabase;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Query {
     query: String,
     lowercased: String,
@@ -205,6 +205,51 @@ pub fn world_symbols(db: &RootDatabase, query: Query) -> Vec<FileSymbol> {
     query.search(&indices)
 }
 
+/// Like [`world_symbols`], but searches one crate (or library source root) at a
+/// time and hands the symbols found in each to `sink`, until it returns `true`
+/// or the query's limit is reached.
+pub fn world_symbols_by_crate(
+    db: &RootDatabase,
+    mut query: Query,
+    sink: &mut dyn FnMut(Vec<FileSymbol>) -> bool,
+) {
+    let _p = profile::span("world_symbols_by_crate").detail(|| query.query.clone());
+
+    let batches: Box<dyn Iterator<Item = Vec<Arc<SymbolIndex>>> + '_> = if query.libs {
+        let mut roots: Vec<_> = db.library_roots().iter().copied().collect();
+        roots.sort();
+        Box::new(roots.into_iter().map(move |root| vec![db.library_symbols(root)]))
+    } else {
+        let mut crates: Vec<_> = db
+            .local_roots()
+            .iter()
+            .flat_map(|&root| db.source_root_crates(root).iter().copied().collect::<Vec<_>>())
+            .collect();
+        crates.sort();
+        crates.dedup();
+        Box::new(crates.into_iter().map(move |krate| {
+            Crate::from(krate)
+                .modules(db)
+                .par_iter()
+                .map_with(Snap::new(db), |snap, &module| snap.module_symbols(module))
+                .collect()
+        }))
+    };
+
+    let mut seen = FxHashSet::default();
+    for indices in batches {
+        if query.limit == 0 {
+            break;
+        }
+        let mut symbols = query.clone().search(&indices);
+        symbols.retain(|symbol| seen.insert(symbol.loc.clone()));
+        query.limit = query.limit.saturating_sub(symbols.len());
+        if sink(symbols) {
+            break;
+        }
+    }
+}
+
 pub fn crate_symbols(db: &RootDatabase, krate: Crate, query: Query) -> Vec<FileSymbol> {
     let _p = profile::span("crate_symbols").detail(|| format!("{:?}", query));
 
@@ -317,6 +362,8 @@ impl Query {
         }
         let mut stream = op.union();
         let mut res = Vec::new();
+        // Doc aliases are indexed as separate symbols, don't report the same declaration twice.
+        let mut seen = FxHashSet::default();
         while let Some((_, indexed_values)) = stream.next() {
             for indexed_value in indexed_values {
                 let symbol_index = &indices[indexed_value.index];
@@ -336,6 +383,9 @@ impl Query {
                         }
                     }
 
+                    if !seen.insert(&symbol.loc) {
+                        continue;
+                    }
                     res.push(symbol.clone());
                     if res.len() >= self.limit {
                         return res;
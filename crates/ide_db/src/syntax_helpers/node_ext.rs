@@ -3,7 +3,7 @@ use itertools::Itertools;
 use parser::T;
 use syntax::{
     ast::{self, HasLoopBody, PathSegmentKind, VisibilityKind},
-    AstNode, Preorder, RustLanguage, WalkEvent,
+    AstNode, Direction, Preorder, RustLanguage, SyntaxKind, WalkEvent,
 };
 
 pub fn expr_as_name_ref(expr: &ast::Expr) -> Option<ast::NameRef> {
@@ -463,3 +463,20 @@ pub fn parse_tt_as_comma_sep_paths(input: ast::TokenTree) -> Option<Vec<ast::Pat
         .collect();
     Some(paths)
 }
+
+/// Returns the name of the macro `input` is passed to. This also works for
+/// calls nested in the input of other macros, like `env!` in
+/// `include_str!(concat!(env!("OUT_DIR"), "/x"))`, which aren't parsed as calls.
+pub fn macro_name_of_input(input: &ast::TokenTree) -> Option<String> {
+    if let Some(call) = input.syntax().parent().and_then(ast::MacroCall::cast) {
+        return Some(call.path()?.segment()?.name_ref()?.text().into());
+    }
+    let mut prev = input
+        .syntax()
+        .siblings_with_tokens(Direction::Prev)
+        .skip(1)
+        .filter(|it| !it.kind().is_trivia());
+    prev.next()?.into_token().filter(|it| it.kind() == T![!])?;
+    let name = prev.next()?.into_token().filter(|it| it.kind() == SyntaxKind::IDENT)?;
+    Some(name.text().to_string())
+}
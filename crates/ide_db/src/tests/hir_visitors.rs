@@ -0,0 +1,109 @@
+//! Tests for the item and body visitors of `hir`, which need a full database.
+use base_db::fixture::WithFixture;
+use hir::{
+    db::HirDatabase,
+    visit::{walk_body, walk_crate, BodyExpr, BodyVisitor, ItemVisitor},
+    AssocItem, Crate, DefWithBody, HirDisplay, Impl, Module, ModuleDef,
+};
+use syntax::AstNode;
+
+use crate::RootDatabase;
+
+#[derive(Default)]
+struct Items {
+    modules: Vec<String>,
+    items: Vec<String>,
+}
+
+impl ItemVisitor for Items {
+    fn visit_module(&mut self, db: &dyn HirDatabase, module: Module) -> bool {
+        let name = module.name(db).map_or_else(|| "crate".to_string(), |it| it.to_string());
+        let skip = name == "skipped";
+        self.modules.push(name);
+        !skip
+    }
+
+    fn visit_item(&mut self, db: &dyn HirDatabase, item: ModuleDef) {
+        self.items.push(format!("item {}", item.name(db).unwrap()));
+    }
+
+    fn visit_impl(&mut self, _db: &dyn HirDatabase, _impl: Impl) {
+        self.items.push("impl".to_string());
+    }
+
+    fn visit_assoc_item(&mut self, db: &dyn HirDatabase, item: AssocItem) {
+        self.items.push(format!("assoc {}", item.name(db).unwrap()));
+    }
+}
+
+#[derive(Default)]
+struct Exprs(Vec<String>);
+
+impl BodyVisitor for Exprs {
+    fn visit_expr(&mut self, db: &dyn HirDatabase, expr: BodyExpr) -> bool {
+        let text = expr.source.value.syntax().text().to_string();
+        self.0.push(format!("{}: {}", text, expr.ty.display(db)));
+        !text.starts_with("skipped")
+    }
+}
+
+#[test]
+fn walks_modules_and_items() {
+    let (db, _) = RootDatabase::with_single_file(
+        r#"
+mod a {
+    pub struct S;
+    impl S { fn f() {} }
+    pub trait T { fn g(); }
+    mod skipped { fn h() {} }
+}
+fn main() {}
+"#,
+    );
+    let db: &dyn HirDatabase = &db;
+    let mut visitor = Items::default();
+    walk_crate(db, Crate::all(db)[0], &mut visitor);
+
+    assert_eq!(visitor.modules, ["crate", "a", "skipped"]);
+    visitor.items.sort();
+    assert_eq!(
+        visitor.items,
+        ["assoc f", "assoc g", "impl", "item S", "item T", "item a", "item main", "item skipped"]
+    );
+}
+
+#[test]
+fn walks_body_exprs_with_types() {
+    let (db, _) = RootDatabase::with_single_file(
+        r#"
+fn skipped() -> u8 { 0 }
+fn main() {
+    let x = (1, 2u8);
+    skipped();
+}
+"#,
+    );
+    let db: &dyn HirDatabase = &db;
+    let main = Crate::all(db)[0]
+        .root_module(db)
+        .declarations(db)
+        .into_iter()
+        .find_map(|it| match it {
+            ModuleDef::Function(it) if it.name(db).to_string() == "main" => Some(it),
+            _ => None,
+        })
+        .unwrap();
+    let mut visitor = Exprs::default();
+    walk_body(db, DefWithBody::from(main), &mut visitor);
+
+    assert_eq!(
+        visitor.0,
+        [
+            "{\n    let x = (1, 2u8);\n    skipped();\n}: ()",
+            "(1, 2u8): (i32, u8)",
+            "1: i32",
+            "2u8: u8",
+            "skipped(): u8",
+        ]
+    );
+}
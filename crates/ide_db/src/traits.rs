@@ -1,8 +1,8 @@
 //! Functionality for obtaining data related to traits from the DB.
 
 use crate::RootDatabase;
-use hir::Semantics;
-use rustc_hash::FxHashSet;
+use hir::{AsAssocItem, Semantics};
+use rustc_hash::{FxHashMap, FxHashSet};
 use syntax::{
     ast::{self, HasName},
     AstNode,
@@ -76,6 +76,57 @@ pub fn get_missing_assoc_items(
     })
 }
 
+/// Groups the method candidates of `receiver`, in method resolution order, by name.
+///
+/// A group has several methods if calling one is ambiguous, like rustc's "multiple applicable
+/// items in scope" error: when no inherent method takes precedence, and several traits with a
+/// method of that name are implemented at the same autoderef step.
+pub fn group_method_candidates(
+    db: &RootDatabase,
+    receiver: &hir::Type,
+    candidates: impl IntoIterator<Item = hir::Function>,
+) -> Vec<Vec<hir::Function>> {
+    let steps: Vec<hir::Type> = receiver.autoderef(db).collect();
+    let trait_and_step = |func: hir::Function| {
+        let trait_ = func.as_assoc_item(db)?.containing_trait(db)?;
+        // `impls_trait` needs the generic arguments of the trait.
+        if trait_.type_or_const_param_count(db) > 0 {
+            return None;
+        }
+        let step = steps.iter().position(|ty| ty.impls_trait(db, trait_, &[]))?;
+        Some((trait_, step))
+    };
+
+    let mut groups: Vec<Vec<hir::Function>> = Vec::new();
+    let mut group_by_name = FxHashMap::default();
+    for func in candidates {
+        let idx = *group_by_name.entry(func.name(db)).or_insert_with(|| {
+            groups.push(Vec::new());
+            groups.len() - 1
+        });
+        let group = &mut groups[idx];
+        let first = match group.first() {
+            Some(&it) => it,
+            None => {
+                group.push(func);
+                continue;
+            }
+        };
+        let ambiguous = match (trait_and_step(first), trait_and_step(func)) {
+            (Some((_, first_step)), Some((trait_, step))) => {
+                let containing_trait =
+                    |it: hir::Function| it.as_assoc_item(db)?.containing_trait(db);
+                step == first_step && group.iter().all(|&it| containing_trait(it) != Some(trait_))
+            }
+            _ => false,
+        };
+        if ambiguous {
+            group.push(func);
+        }
+    }
+    groups
+}
+
 #[cfg(test)]
 mod tests {
     use base_db::{fixture::ChangeFixture, FilePosition};
@@ -0,0 +1,69 @@
+use hir::MacroKind;
+use syntax::{ast, AstNode, SyntaxNode};
+
+use crate::{
+    handlers::unreachable_branch::{eval, Value},
+    Diagnostic, DiagnosticsContext, Severity,
+};
+
+// Diagnostic: always-true-assert
+//
+// This diagnostic is triggered if the condition of an `assert!` is trivially
+// constant and always holds, like `assert!(!cfg!(miri))` outside of Miri.
+pub(crate) fn always_true_assert(
+    ctx: &DiagnosticsContext<'_>,
+    acc: &mut Vec<Diagnostic>,
+    node: &SyntaxNode,
+) -> Option<()> {
+    let macro_call = ast::MacroCall::cast(node.clone())?;
+    let mac = ctx.sema.resolve_macro_call(&macro_call)?;
+    if mac.kind(ctx.sema.db) != MacroKind::BuiltIn
+        || mac.name(ctx.sema.db).to_smol_str() != "assert"
+    {
+        return None;
+    }
+    // `assert!(cond)` expands to `if !cond { panic!() }`.
+    let expansion = ctx.sema.expand(&macro_call)?;
+    let if_expr = expansion.descendants().find_map(ast::IfExpr::cast)?;
+    if eval(&ctx.sema, &if_expr.condition()?, 0)? != Value::Bool(false) {
+        return None;
+    }
+    acc.push(
+        Diagnostic::new(
+            "always-true-assert",
+            "this assertion is always true",
+            macro_call.syntax().text_range(),
+        )
+        .severity(Severity::WeakWarning),
+    );
+    Some(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::check_diagnostics;
+
+    #[test]
+    fn always_true_assert() {
+        check_diagnostics(
+            r#"
+#[rustc_builtin_macro]
+macro_rules! assert { () => {} }
+#[rustc_builtin_macro]
+macro_rules! cfg { () => {} }
+#[macro_export]
+macro_rules! panic { ($($tt:tt)*) => {} }
+const CHECKED: bool = true;
+
+fn f(x: bool) {
+    assert!(CHECKED);
+  //^^^^^^^^^^^^^^^^ weak: this assertion is always true
+    assert!(!cfg!(miri), "not supported on Miri");
+  //^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ weak: this assertion is always true
+    assert!(x);
+    assert!(!CHECKED);
+}
+"#,
+        );
+    }
+}
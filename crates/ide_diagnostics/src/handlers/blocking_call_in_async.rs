@@ -0,0 +1,220 @@
+use hir::{Adt, AssocItem, Crate, Function, ModuleDef, PathResolution, ScopeDef};
+use ide_db::{base_db::FileId, RootDatabase};
+use syntax::{
+    ast::{self, AstNode},
+    SyntaxNode, TextRange,
+};
+
+use crate::{Diagnostic, DiagnosticsContext, Severity};
+
+/// The functions flagged by default, see [`crate::DiagnosticsConfig::blocking_functions`].
+pub const DEFAULT_BLOCKING_FUNCTIONS: &[&str] = &[
+    "std::thread::sleep",
+    "std::fs::read",
+    "std::fs::read_to_string",
+    "std::fs::write",
+    "std::fs::File::open",
+    "std::fs::File::create",
+    "std::sync::Mutex::lock",
+    "std::sync::RwLock::read",
+    "std::sync::RwLock::write",
+];
+
+// Diagnostic: blocking-call-in-async
+//
+// This diagnostic is triggered if a function that blocks the current thread, like
+// `std::thread::sleep` or `Mutex::lock`, is called inside an async function or block. Such a
+// call stalls the executor instead of yielding to it. The list of flagged functions is taken
+// from the `diagnostics.blockingFunctions` setting.
+pub(crate) fn blocking_call_in_async(
+    ctx: &DiagnosticsContext<'_>,
+    acc: &mut Vec<Diagnostic>,
+    file_id: FileId,
+) -> Option<()> {
+    let source_file = ctx.sema.parse(file_id);
+    let calls: Vec<_> = source_file
+        .syntax()
+        .descendants()
+        .filter(|node| {
+            matches!(
+                ast::Expr::cast(node.clone()),
+                Some(ast::Expr::CallExpr(_) | ast::Expr::MethodCallExpr(_))
+            )
+        })
+        .filter(|node| in_async_context(node))
+        .collect();
+    if calls.is_empty() {
+        return None;
+    }
+
+    let db = ctx.sema.db;
+    let krate = ctx.sema.to_module_def(file_id)?.krate();
+    let blocking: Vec<(Function, &str)> = ctx
+        .config
+        .blocking_functions
+        .iter()
+        .filter_map(|path| Some((resolve_function(db, krate, path)?, path.as_str())))
+        .collect();
+    if blocking.is_empty() {
+        return None;
+    }
+
+    for call in calls {
+        let (func, range) = match callee(ctx, &call) {
+            Some(it) => it,
+            None => continue,
+        };
+        if let Some((_, path)) = blocking.iter().find(|(it, _)| *it == func) {
+            acc.push(
+                Diagnostic::new(
+                    "blocking-call-in-async",
+                    format!("blocking call to `{}` in async context", path),
+                    range,
+                )
+                .severity(Severity::WeakWarning),
+            );
+        }
+    }
+    Some(())
+}
+
+/// Whether `node` runs as part of an async body. Closures stop the search, their bodies usually
+/// run elsewhere, e.g. on a blocking thread pool.
+fn in_async_context(node: &SyntaxNode) -> bool {
+    for ancestor in node.ancestors().skip(1) {
+        if let Some(block) = ast::BlockExpr::cast(ancestor.clone()) {
+            if block.async_token().is_some() {
+                return true;
+            }
+        } else if let Some(func) = ast::Fn::cast(ancestor.clone()) {
+            return func.async_token().is_some();
+        } else if ast::ClosureExpr::can_cast(ancestor.kind())
+            || ast::Item::can_cast(ancestor.kind())
+        {
+            return false;
+        }
+    }
+    false
+}
+
+/// The function called by `call`, and the range naming it.
+fn callee(ctx: &DiagnosticsContext<'_>, call: &SyntaxNode) -> Option<(Function, TextRange)> {
+    match ast::Expr::cast(call.clone())? {
+        ast::Expr::CallExpr(call) => match call.expr()? {
+            ast::Expr::PathExpr(path) => match ctx.sema.resolve_path(&path.path()?)? {
+                PathResolution::Def(ModuleDef::Function(it)) => {
+                    Some((it, path.syntax().text_range()))
+                }
+                _ => None,
+            },
+            _ => None,
+        },
+        ast::Expr::MethodCallExpr(call) => {
+            let func = ctx.sema.resolve_method_call(&call)?;
+            Some((func, call.name_ref()?.syntax().text_range()))
+        }
+        _ => None,
+    }
+}
+
+/// Resolves a path like `std::fs::File::open`, as seen from `krate`, to a free function or an
+/// inherent method.
+fn resolve_function(db: &RootDatabase, krate: Crate, path: &str) -> Option<Function> {
+    let mut segments = path.split("::");
+    let crate_name = segments.next()?;
+    let dep = krate.dependencies(db).into_iter().find(|dep| dep.name.to_smol_str() == crate_name);
+    let krate = match dep {
+        Some(dep) => dep.krate,
+        None if krate.display_name(db).map_or(false, |name| name.to_string() == crate_name) => {
+            krate
+        }
+        None => return None,
+    };
+
+    let mut module = krate.root_module(db);
+    while let Some(segment) = segments.next() {
+        let def = module
+            .scope(db, None)
+            .into_iter()
+            .find_map(|(name, def)| (name.to_smol_str() == segment).then(|| def))?;
+        match def {
+            ScopeDef::ModuleDef(ModuleDef::Module(it)) => module = it,
+            ScopeDef::ModuleDef(ModuleDef::Function(it)) => {
+                return segments.next().is_none().then(|| it)
+            }
+            ScopeDef::ModuleDef(ModuleDef::Adt(adt)) => {
+                let method = segments.next()?;
+                if segments.next().is_some() {
+                    return None;
+                }
+                return inherent_method(db, adt, method);
+            }
+            _ => return None,
+        }
+    }
+    None
+}
+
+fn inherent_method(db: &RootDatabase, adt: Adt, name: &str) -> Option<Function> {
+    hir::Impl::all_for_type(db, adt.ty(db))
+        .into_iter()
+        .filter(|impl_| impl_.trait_(db).is_none())
+        .flat_map(|impl_| impl_.items(db))
+        .find_map(|item| match item {
+            AssocItem::Function(it) if it.name(db).to_smol_str() == name => Some(it),
+            _ => None,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::check_diagnostics;
+
+    #[test]
+    fn blocking_calls_in_async_fn() {
+        check_diagnostics(
+            r#"
+//- /main.rs crate:main deps:std edition:2021
+use std::{fs::File, sync::Mutex, thread};
+
+async fn run(m: &Mutex) {
+    thread::sleep(1);
+  //^^^^^^^^^^^^^ weak: blocking call to `std::thread::sleep` in async context
+    File::open("");
+  //^^^^^^^^^^ weak: blocking call to `std::fs::File::open` in async context
+    m.lock();
+    //^^^^ weak: blocking call to `std::sync::Mutex::lock` in async context
+    let _ = async {
+        std::thread::sleep(1);
+      //^^^^^^^^^^^^^^^^^^ weak: blocking call to `std::thread::sleep` in async context
+    };
+    let _ = || thread::sleep(1);
+}
+
+fn sync(m: &Mutex) {
+    thread::sleep(1);
+    m.lock();
+}
+//- /std.rs crate:std
+pub mod thread {
+    pub fn sleep(_: u64) {}
+}
+pub mod fs {
+    pub struct File;
+    impl File {
+        pub fn open(_: &str) {}
+    }
+}
+pub mod sync {
+    mod mutex {
+        pub struct Mutex;
+        impl Mutex {
+            pub fn lock(&self) {}
+        }
+    }
+    pub use self::mutex::Mutex;
+}
+"#,
+        );
+    }
+}
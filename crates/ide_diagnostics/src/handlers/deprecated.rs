@@ -0,0 +1,102 @@
+use hir::Adt;
+use ide_db::defs::{Definition, NameRefClass};
+use syntax::{
+    ast::{self, HasAttrs},
+    AstNode, SyntaxNode,
+};
+
+use crate::{Diagnostic, DiagnosticsContext, Severity};
+
+// Diagnostic: deprecated
+//
+// This diagnostic is triggered if an item marked `#[deprecated]` is used.
+// Like rustc, uses inside items which are deprecated themselves are fine.
+pub(crate) fn deprecated(
+    ctx: &DiagnosticsContext<'_>,
+    acc: &mut Vec<Diagnostic>,
+    node: &SyntaxNode,
+) -> Option<()> {
+    let name_ref = ast::NameRef::cast(node.clone())?;
+    let def = match NameRefClass::classify(&ctx.sema, &name_ref)? {
+        NameRefClass::Definition(it) => it,
+        NameRefClass::FieldShorthand { field_ref, .. } => Definition::Field(field_ref),
+    };
+    let db = ctx.sema.db;
+    let deprecation = def.deprecation(db)?;
+    let in_deprecated_item =
+        name_ref.syntax().ancestors().filter_map(ast::AnyHasAttrs::cast).any(|owner| {
+            owner.attrs().any(|attr| {
+                matches!(attr.simple_name().as_deref(), Some("deprecated" | "rustc_deprecated"))
+            })
+        });
+    if in_deprecated_item {
+        return None;
+    }
+
+    let kind = match def {
+        Definition::Macro(_) => "macro",
+        Definition::Field(_) => "field",
+        Definition::Module(_) => "module",
+        Definition::Function(_) => "function",
+        Definition::Adt(Adt::Struct(_)) => "struct",
+        Definition::Adt(Adt::Enum(_)) => "enum",
+        Definition::Adt(Adt::Union(_)) => "union",
+        Definition::Variant(_) => "variant",
+        Definition::Const(_) => "constant",
+        Definition::Static(_) => "static",
+        Definition::Trait(_) => "trait",
+        Definition::TypeAlias(_) => "type alias",
+        _ => "item",
+    };
+    let mut message = format!("use of deprecated {} `{}`", kind, def.name(db)?);
+    if let Some(note) = &deprecation.note {
+        message.push_str(": ");
+        message.push_str(note);
+    }
+    acc.push(
+        Diagnostic::new("deprecated", message, name_ref.syntax().text_range())
+            .severity(Severity::WeakWarning)
+            .with_deprecated(true),
+    );
+    Some(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::check_diagnostics;
+
+    #[test]
+    fn deprecated() {
+        check_diagnostics(
+            r#"
+#[deprecated(since = "1.0.0", note = "use `bar` instead")]
+fn foo() {}
+#[deprecated]
+struct S {
+    #[deprecated = "gone"]
+    f: u32,
+}
+struct T {
+    #[deprecated = "gone"]
+    f: u32,
+}
+
+fn main() {
+    foo();
+  //^^^ weak: use of deprecated function `foo`: use `bar` instead
+    let _: S;
+         //^ weak: use of deprecated struct `S`
+    let t = T { f: 0 };
+              //^ weak: use of deprecated field `f`: gone
+    t.f;
+    //^ weak: use of deprecated field `f`: gone
+}
+
+#[deprecated]
+fn still_uses_foo() {
+    foo();
+}
+"#,
+        );
+    }
+}
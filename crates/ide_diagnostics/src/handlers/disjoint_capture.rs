@@ -0,0 +1,319 @@
+use hir::{db::HirDatabase, PathResolution, Type};
+use ide_db::{
+    base_db::{Edition, FileId},
+    famous_defs::FamousDefs,
+    source_change::SourceChange,
+};
+use itertools::Itertools;
+use rustc_hash::FxHashMap;
+use syntax::{
+    ast::{self, edit::IndentLevel},
+    AstNode, SyntaxNode, TextRange,
+};
+use text_edit::TextEdit;
+
+use crate::{fix, Assist, Diagnostic, DiagnosticsContext, Severity};
+
+// Diagnostic: disjoint-capture
+//
+// This diagnostic is triggered on closures which take ownership of some fields of a variable
+// but not of the whole of it, when the rest of the variable has a destructor. Since Rust 2021
+// such closures only capture the fields they use, so the rest of the variable is dropped at
+// the end of its scope rather than together with the closure.
+//
+// The fix forces the whole variable to be captured, like it is in earlier editions.
+pub(crate) fn disjoint_capture(
+    ctx: &DiagnosticsContext<'_>,
+    acc: &mut Vec<Diagnostic>,
+    file_id: FileId,
+    node: &SyntaxNode,
+) -> Option<()> {
+    let closure = ast::ClosureExpr::cast(node.clone())?;
+    let body = closure.body()?;
+    let db = ctx.sema.db;
+    let is_move = closure.move_token().is_some();
+    let closure_range = closure.syntax().text_range();
+
+    // The captured places of each variable, as paths of field names. An empty path means that
+    // the variable is used as a whole.
+    let mut places: FxHashMap<hir::Local, Vec<Vec<String>>> = FxHashMap::default();
+    let mut moves_out = false;
+    for path_expr in body.syntax().descendants().filter_map(ast::PathExpr::cast) {
+        let local = match path_expr.path().and_then(|it| ctx.sema.resolve_path(&it)) {
+            Some(PathResolution::Local(it)) => it,
+            _ => continue,
+        };
+        let source = local.source(db);
+        if source.file_id != file_id.into()
+            || closure_range.contains_range(source.value.syntax().text_range())
+        {
+            continue;
+        }
+        let mut place = ast::Expr::from(path_expr);
+        let mut fields = Vec::new();
+        while let Some(field_expr) = place.syntax().parent().and_then(ast::FieldExpr::cast) {
+            match field_expr.name_ref() {
+                Some(name) => fields.push(name.text().to_string()),
+                None => break,
+            }
+            place = field_expr.into();
+        }
+        if !fields.is_empty() && is_moved(ctx, &place) {
+            moves_out = true;
+        }
+        places.entry(local).or_default().push(fields);
+    }
+    if !is_move && !moves_out {
+        // Everything is captured by reference, which doesn't change when anything is dropped.
+        return None;
+    }
+
+    let krate = ctx.sema.to_module_def(file_id)?.krate();
+    let drop_trait = FamousDefs(&ctx.sema, Some(krate)).core_ops_Drop()?;
+    let mut affected = places
+        .into_iter()
+        .filter(|(local, places)| {
+            let ty = local.ty(db);
+            !ty.is_reference() && dropped_later(db, drop_trait, &ty, places, 0)
+        })
+        .map(|(local, _)| local.name(db).to_string())
+        .collect::<Vec<_>>();
+    if affected.is_empty() {
+        return None;
+    }
+    affected.sort();
+
+    let (message, severity) = if krate.edition(db) >= Edition::Edition2021 {
+        ("this closure captures only some fields of", Severity::WeakWarning)
+    } else {
+        ("in Rust 2021, this closure will capture only some fields of", Severity::Warning)
+    };
+    let message = format!(
+        "{} {}, the rest is dropped at the end of the scope",
+        message,
+        affected.iter().map(|it| format!("`{}`", it)).join(", ")
+    );
+    let head_end = closure.param_list()?.syntax().text_range().end();
+    let range = TextRange::new(closure_range.start(), head_end);
+    acc.push(
+        Diagnostic::new("disjoint-capture", message, range)
+            .severity(severity)
+            .experimental()
+            .with_fixes(Some(vec![capture_whole(file_id, &body, &affected, range)])),
+    );
+    Some(())
+}
+
+/// Whether the place is moved out of, rather than borrowed or copied. This only recognizes the
+/// usual ways of moving, like passing to a function or binding to a variable.
+fn is_moved(ctx: &DiagnosticsContext<'_>, place: &ast::Expr) -> bool {
+    let is_copy = ctx.sema.type_of_expr(place).map_or(true, |it| it.original.is_copy(ctx.sema.db));
+    if is_copy {
+        return false;
+    }
+    match place.syntax().parent() {
+        Some(parent) => {
+            ast::ArgList::can_cast(parent.kind())
+                || ast::ReturnExpr::can_cast(parent.kind())
+                || ast::ClosureExpr::can_cast(parent.kind())
+                || ast::LetStmt::cast(parent)
+                    .and_then(|it| it.initializer())
+                    .map_or(false, |it| it.syntax() == place.syntax())
+        }
+        None => false,
+    }
+}
+
+/// Whether some part of `ty`, which isn't among the captured `places`, runs code when dropped.
+fn dropped_later(
+    db: &dyn HirDatabase,
+    drop_trait: hir::Trait,
+    ty: &Type,
+    places: &[Vec<String>],
+    depth: usize,
+) -> bool {
+    if places.iter().any(|it| it.len() <= depth) || depth > 4 {
+        return false;
+    }
+    // Fields can't be moved out of a type with a destructor, so the whole of it is captured.
+    if ty.impls_trait(db, drop_trait, &[]) {
+        return false;
+    }
+    let fields: Vec<(String, Type)> = if ty.is_tuple() {
+        ty.tuple_fields(db).into_iter().enumerate().map(|(idx, ty)| (idx.to_string(), ty)).collect()
+    } else {
+        ty.fields(db).into_iter().map(|(field, ty)| (field.name(db).to_string(), ty)).collect()
+    };
+    fields.iter().any(|(name, field_ty)| {
+        let captured = places.iter().filter(|it| &it[depth] == name).cloned().collect::<Vec<_>>();
+        if captured.is_empty() {
+            needs_drop(db, drop_trait, field_ty, 0)
+        } else {
+            dropped_later(db, drop_trait, field_ty, &captured, depth + 1)
+        }
+    })
+}
+
+fn needs_drop(db: &dyn HirDatabase, drop_trait: hir::Trait, ty: &Type, depth: usize) -> bool {
+    if depth > 4 || ty.is_reference() {
+        return false;
+    }
+    if ty.impls_trait(db, drop_trait, &[]) {
+        return true;
+    }
+    let fields = if ty.is_tuple() {
+        ty.tuple_fields(db)
+    } else if let Some((elem, _)) = ty.as_array() {
+        vec![elem]
+    } else {
+        ty.fields(db).into_iter().map(|(_, ty)| ty).collect()
+    };
+    fields.iter().any(|it| needs_drop(db, drop_trait, it, depth + 1))
+}
+
+fn capture_whole(file_id: FileId, body: &ast::Expr, locals: &[String], range: TextRange) -> Assist {
+    let captures = match locals {
+        [local] => format!("let _ = &{};", local),
+        _ => format!("let _ = ({});", locals.iter().map(|it| format!("&{}", it)).join(", ")),
+    };
+    let edit = match body {
+        ast::Expr::BlockExpr(block) => {
+            let l_curly = block.stmt_list().and_then(|it| it.l_curly_token());
+            let offset =
+                l_curly.map_or(body.syntax().text_range().start(), |it| it.text_range().end());
+            if block.syntax().text().contains_char('\n') {
+                let indent = IndentLevel::from_node(block.syntax()) + 1;
+                TextEdit::insert(offset, format!("\n{}{}", indent, captures))
+            } else {
+                TextEdit::insert(offset, format!(" {}", captures))
+            }
+        }
+        _ => {
+            let mut builder = TextEdit::builder();
+            builder.insert(body.syntax().text_range().start(), format!("{{ {} ", captures));
+            builder.insert(body.syntax().text_range().end(), " }".to_string());
+            builder.finish()
+        }
+    };
+    fix(
+        "capture_whole_variable",
+        "Capture the whole variable",
+        SourceChange::from_text_edit(file_id, edit),
+        range,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_diagnostics, check_fix};
+
+    #[test]
+    fn disjoint_capture() {
+        check_diagnostics(
+            r#"
+//- minicore: drop, copy
+struct Guard;
+impl Drop for Guard {
+    fn drop(&mut self) {}
+}
+struct S { a: u32, guard: Guard }
+struct NoDrop { a: u32, b: u32 }
+fn consume(_: Guard) {}
+
+fn f() {
+    let s = S { a: 0, guard: Guard };
+    let c = move || s.a;
+          //^^^^^^^ 💡 weak: this closure captures only some fields of `s`, the rest is dropped at the end of the scope
+    let s = S { a: 0, guard: Guard };
+    let c = move || { let _ = &s; s.a };
+    let s = S { a: 0, guard: Guard };
+    let c = || s.a + 1;
+    let n = NoDrop { a: 0, b: 0 };
+    let c = move || n.a;
+    let t = (Guard, Guard);
+    let c = || consume(t.0);
+          //^^ 💡 weak: this closure captures only some fields of `t`, the rest is dropped at the end of the scope
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn migration_warning_in_2018() {
+        check_diagnostics(
+            r#"
+//- minicore: drop
+//- /main.rs crate:main edition:2018
+struct S { a: u32, b: Vec }
+struct Vec;
+impl Drop for Vec {
+    fn drop(&mut self) {}
+}
+
+fn f(s: S) {
+    let c = move |x: u32| x + s.a;
+          //^^^^^^^^^^^^^ 💡 warn: in Rust 2021, this closure will capture only some fields of `s`, the rest is dropped at the end of the scope
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn capture_whole_variable() {
+        check_fix(
+            r#"
+//- minicore: drop
+struct Guard;
+impl Drop for Guard {
+    fn drop(&mut self) {}
+}
+struct S { a: u32, guard: Guard }
+
+fn f(s: S) {
+    let c = move$0 || s.a;
+}
+"#,
+            r#"
+struct Guard;
+impl Drop for Guard {
+    fn drop(&mut self) {}
+}
+struct S { a: u32, guard: Guard }
+
+fn f(s: S) {
+    let c = move || { let _ = &s; s.a };
+}
+"#,
+        );
+        check_fix(
+            r#"
+//- minicore: drop
+struct Guard;
+impl Drop for Guard {
+    fn drop(&mut self) {}
+}
+struct S { a: u32, guard: Guard }
+
+fn f(s: S, t: S) {
+    let c = move$0 || {
+        t.a + s.a
+    };
+}
+"#,
+            r#"
+struct Guard;
+impl Drop for Guard {
+    fn drop(&mut self) {}
+}
+struct S { a: u32, guard: Guard }
+
+fn f(s: S, t: S) {
+    let c = move || {
+        let _ = (&s, &t);
+        t.a + s.a
+    };
+}
+"#,
+        );
+    }
+}
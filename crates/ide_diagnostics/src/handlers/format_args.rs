@@ -0,0 +1,82 @@
+use ide_db::format_args;
+use syntax::{ast, AstNode, SyntaxNode};
+
+use crate::{handlers::unused_asm_operand::is_asm_call, Diagnostic, DiagnosticsContext};
+
+// Diagnostic: unresolved-format-arg
+//
+// This diagnostic is triggered if an identifier in a format string names neither an argument of
+// the macro call nor a value in scope.
+
+// Diagnostic: unused-format-arg
+//
+// This diagnostic is triggered if a named argument of a format macro call is not used by the
+// format string.
+pub(crate) fn format_args(
+    ctx: &DiagnosticsContext<'_>,
+    acc: &mut Vec<Diagnostic>,
+    node: &SyntaxNode,
+) -> Option<()> {
+    let macro_call = ast::MacroCall::cast(node.clone())?;
+    let is_asm = is_asm_call(&ctx.sema, &macro_call);
+    let strings: Vec<_> = macro_call
+        .token_tree()?
+        .syntax()
+        .children_with_tokens()
+        .filter_map(|it| it.into_token())
+        .filter_map(|token| format_args::format_string(&ctx.sema, &token))
+        // `asm!` takes several template strings.
+        .take(if is_asm { usize::MAX } else { 1 })
+        .collect();
+
+    for string in &strings {
+        for (range, name) in format_args::identifiers(string) {
+            if format_args::resolve(&ctx.sema, string, &name).is_none() {
+                acc.push(Diagnostic::new(
+                    "unresolved-format-arg",
+                    format!("cannot find value `{}` in this scope", name),
+                    range,
+                ));
+            }
+        }
+    }
+    // Unused operands of `asm!` are reported by `unused-asm-operand`.
+    let string = strings.first().filter(|_| !is_asm)?;
+    // Positional placeholders may refer to named arguments as well.
+    if !format_args::has_positional_placeholders(string) {
+        let identifiers = format_args::identifiers(string);
+        for arg in format_args::named_args(string) {
+            if identifiers.iter().all(|(_, name)| name != arg.text()) {
+                acc.push(Diagnostic::new(
+                    "unused-format-arg",
+                    "named argument never used",
+                    arg.text_range(),
+                ));
+            }
+        }
+    }
+    Some(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::check_diagnostics;
+
+    #[test]
+    fn format_args() {
+        check_diagnostics(
+            r#"
+macro_rules! format_args {
+    ($lit:literal $($tt:tt)*) => { 0 };
+}
+const C: u8 = 0;
+fn f(local: u8) {
+    format_args!("{local} {C:>width$} {missing:?}", width = 1, unused = 2);
+                                     //^^^^^^^ error: cannot find value `missing` in this scope
+                                                             //^^^^^^ error: named argument never used
+    format_args!("{} {{nothing}}", unused = 2);
+}
+"#,
+        );
+    }
+}
@@ -0,0 +1,124 @@
+use hir::HirDisplay;
+use ide_db::{
+    famous_defs::FamousDefs,
+    ffi::{c_declaration, c_return_declaration, CTypeError},
+};
+use syntax::{ast, AstNode, SyntaxKind, SyntaxNode};
+
+use crate::{Diagnostic, DiagnosticsContext, Severity};
+
+// Diagnostic: improper-ctypes
+//
+// This diagnostic is triggered if a function or static declared in an `extern` block uses a type
+// which has no C equivalent, like `String` or `&str`. Mirrors rustc's `improper_ctypes` lint.
+
+// Diagnostic: improper-ctypes-definitions
+//
+// This diagnostic is triggered if the signature of an `extern "C"` function defined in Rust uses
+// a type which has no C equivalent. Mirrors rustc's `improper_ctypes_definitions` lint.
+pub(crate) fn improper_ctypes(
+    ctx: &DiagnosticsContext<'_>,
+    acc: &mut Vec<Diagnostic>,
+    node: &SyntaxNode,
+) -> Option<()> {
+    if !matches!(node.kind(), SyntaxKind::FN | SyntaxKind::STATIC) {
+        return None;
+    }
+    let in_extern_block = node.parent()?.parent().and_then(ast::ExternBlock::cast).is_some();
+    let (code, what) = match in_extern_block {
+        true => ("improper-ctypes", "`extern` block"),
+        false => ("improper-ctypes-definitions", "`extern` fn"),
+    };
+    let krate = ctx.sema.scope(node).krate();
+    let famous_defs = FamousDefs(&ctx.sema, krate);
+    let mut check = |ty: Option<ast::Type>, is_return: bool| {
+        let ty_ast = match ty {
+            Some(it) => it,
+            None => return,
+        };
+        let ty = match ctx.sema.resolve_type(&ty_ast) {
+            Some(it) => it,
+            None => return,
+        };
+        let res = match is_return {
+            true => c_return_declaration(&famous_defs, &ty, ""),
+            false => c_declaration(&famous_defs, &ty, ""),
+        };
+        if let Err(CTypeError::NotFfiSafe { ty: unsafe_ty, reason }) = res {
+            let db = ctx.sema.db;
+            let message = format!(
+                "{} uses type `{}`, which is not FFI-safe: {}",
+                what,
+                unsafe_ty.display(db),
+                reason
+            );
+            acc.push(
+                Diagnostic::new(code, message, ty_ast.syntax().text_range())
+                    .severity(Severity::Warning),
+            );
+        }
+    };
+
+    if let Some(func) = ast::Fn::cast(node.clone()) {
+        let def = ctx.sema.to_def(&func)?;
+        let abi = def.abi(ctx.sema.db)?;
+        // Only the ABIs with C calling conventions, not `"Rust"` or `"rust-call"`.
+        if matches!(abi.as_str(), "Rust" | "rust-call" | "rust-intrinsic" | "platform-intrinsic") {
+            return None;
+        }
+        if func.generic_param_list().is_some() {
+            return None;
+        }
+        for param in func.param_list()?.params() {
+            check(param.ty(), false);
+        }
+        check(func.ret_type().and_then(|it| it.ty()), true);
+    } else if let Some(static_) = ast::Static::cast(node.clone()) {
+        if in_extern_block {
+            check(static_.ty(), false);
+        }
+    }
+    Some(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::check_diagnostics;
+
+    #[test]
+    fn improper_ctypes() {
+        check_diagnostics(
+            r#"
+//- minicore: option
+struct Opaque;
+#[repr(C)]
+struct Point(i32, i32);
+#[repr(u8)]
+enum Small { A, B }
+enum Plain { A, B }
+trait Tr {}
+
+extern "C" {
+    fn takes_string(s: Opaque);
+                     //^^^^^^ warn: `extern` block uses type `Opaque`, which is not FFI-safe: this struct has unspecified layout
+    fn fine(p: Point, q: *const Point, r: Option<&Point>, s: Small, f: extern "C" fn(u8)) -> *mut u8;
+    fn slices(s: &[u8], t: &str) -> char;
+               //^^^^^ warn: `extern` block uses type `&[u8]`, which is not FFI-safe: slices have no C equivalent
+                         //^^^^ warn: `extern` block uses type `&str`, which is not FFI-safe: string slices have no C equivalent
+                                  //^^^^ warn: `extern` block uses type `char`, which is not FFI-safe: the `char` type has no C equivalent
+    fn objects(o: *const dyn Tr, p: Plain, t: (u8, u8));
+                //^^^^^^^^^^^^^ warn: `extern` block uses type `*const dyn Tr`, which is not FFI-safe: trait objects have no C equivalent
+                                  //^^^^^ warn: `extern` block uses type `Plain`, which is not FFI-safe: enum has no representation hint
+                                            //^^^^^^^^ warn: `extern` block uses type `(u8, u8)`, which is not FFI-safe: tuples have unspecified layout
+    static GLOBAL: Opaque;
+                 //^^^^^^ warn: `extern` block uses type `Opaque`, which is not FFI-safe: this struct has unspecified layout
+}
+
+extern "C" fn callback(x: u128) -> Point { loop {} }
+                        //^^^^ warn: `extern` fn uses type `u128`, which is not FFI-safe: 128-bit integers don't currently have a known stable ABI
+fn rust_abi(s: &str) {}
+extern "Rust" fn explicit_rust_abi(s: &str) {}
+"#,
+        );
+    }
+}
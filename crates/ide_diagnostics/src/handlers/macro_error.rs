@@ -175,6 +175,24 @@ fn main() {
         );
     }
 
+    #[test]
+    fn env_var_not_set_by_cargo() {
+        check_diagnostics(
+            r#"
+//- /main.rs env:CARGO_PKG_NAME=foo,CARGO_PKG_VERSION=0.1.0
+#[rustc_builtin_macro]
+macro_rules! env {}
+
+fn main() {
+    env!("CARGO_PKG_VERSION");
+    env!("CARGO_PKG_VERISON");
+  //^^^^^^^^^^^^^^^^^^^^^^^^^ error: environment variable `CARGO_PKG_VERISON` not defined at compile time
+    env!("HOME");
+}
+"#,
+        );
+    }
+
     #[test]
     fn macro_rules_diag() {
         check_diagnostics(
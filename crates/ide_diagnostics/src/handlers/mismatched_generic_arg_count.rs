@@ -0,0 +1,343 @@
+use either::Either;
+use hir::{GenericDef, GenericParam, HasSource, HirDisplay, ModuleDef, PathResolution};
+use ide_db::{assists::Assist, base_db::FileId, source_change::SourceChange};
+use itertools::Itertools;
+use syntax::{ast, match_ast, AstNode, SyntaxNode};
+use text_edit::TextEdit;
+
+use crate::{fix, Diagnostic, DiagnosticsContext};
+
+// Diagnostic: mismatched-generic-arg-count
+//
+// This diagnostic is triggered if a path or a method call supplies too few or
+// too many generic arguments, or lifetime arguments, for the item it refers to.
+pub(crate) fn mismatched_generic_arg_count(
+    ctx: &DiagnosticsContext<'_>,
+    acc: &mut Vec<Diagnostic>,
+    file_id: FileId,
+    node: &SyntaxNode,
+) -> Option<()> {
+    let (def, arg_list, name_range) = match_ast! {
+        match node {
+            ast::PathSegment(segment) => {
+                let name_ref = segment.name_ref()?;
+                if segment.param_list().is_some() {
+                    return None;
+                }
+                let path = segment.parent_path();
+                let arg_list = segment.generic_arg_list();
+                // Omitting all generic arguments is only an error in types, elsewhere they are
+                // inferred.
+                let is_type = path.syntax().parent().and_then(ast::PathType::cast).is_some();
+                if arg_list.is_none() && !is_type {
+                    return None;
+                }
+                let def = match ctx.sema.resolve_path(&path)? {
+                    PathResolution::Def(def) => generic_def(ctx, def)?,
+                    _ => return None,
+                };
+                (def, arg_list, name_ref.syntax().text_range())
+            },
+            ast::MethodCallExpr(call) => {
+                let arg_list = call.generic_arg_list()?;
+                let def = GenericDef::Function(ctx.sema.resolve_method_call(&call)?);
+                (def, Some(arg_list), call.name_ref()?.syntax().text_range())
+            },
+            _ => return None,
+        }
+    };
+
+    let db = ctx.sema.db;
+    let module = ctx.sema.scope(node).module()?;
+    let mut lifetime_params = 0;
+    // The rendered default of every explicit type or const parameter, if it has one.
+    let mut type_params = Vec::new();
+    for param in def.params(db) {
+        let default = match param {
+            GenericParam::LifetimeParam(_) => {
+                lifetime_params += 1;
+                continue;
+            }
+            GenericParam::TypeParam(it) if it.is_implicit(db) => continue,
+            GenericParam::TypeParam(it) => {
+                let source = it.merge().source(db).and_then(|it| it.value.left());
+                match source {
+                    Some(ast::TypeOrConstParam::Type(src)) if src.default_type().is_some() => {
+                        let rendered = it
+                            .default(db)
+                            .and_then(|ty| ty.display_source_code(db, module.into()).ok());
+                        Some(rendered.unwrap_or_else(|| src.default_type().unwrap().to_string()))
+                    }
+                    _ => None,
+                }
+            }
+            GenericParam::ConstParam(it) => match it.merge().source(db).map(|it| it.value) {
+                Some(Either::Left(ast::TypeOrConstParam::Const(src))) => {
+                    src.default_val().map(|it| it.to_string())
+                }
+                _ => None,
+            },
+        };
+        type_params.push(default);
+    }
+    let required = type_params.iter().take_while(|it| it.is_none()).count();
+
+    let args = Args::new(arg_list.as_ref());
+    let range = match &arg_list {
+        Some(it) => it.syntax().text_range(),
+        None => name_range,
+    };
+    let replace = |args: Args, label: &str, id: &'static str| -> Assist {
+        let edit = match &arg_list {
+            Some(it) => TextEdit::replace(
+                it.syntax().text_range(),
+                args.render(it.coloncolon_token().is_some()),
+            ),
+            None => TextEdit::insert(name_range.end(), args.render(false)),
+        };
+        fix(id, label, SourceChange::from_text_edit(file_id, edit), range)
+    };
+
+    let found = args.lifetimes.len();
+    if found != 0 && found != lifetime_params {
+        let mut fixed = args.clone();
+        let label = if found < lifetime_params {
+            fixed.lifetimes.resize(lifetime_params, "'_".to_string());
+            "Add missing lifetime arguments"
+        } else {
+            fixed.lifetimes.truncate(lifetime_params);
+            "Remove extra lifetime arguments"
+        };
+        acc.push(
+            Diagnostic::new(
+                "mismatched-generic-arg-count",
+                format!(
+                    "expected {}, found {}",
+                    plural(lifetime_params, "lifetime argument"),
+                    found
+                ),
+                range,
+            )
+            .with_fixes(Some(vec![replace(fixed, label, "fix_lifetime_arg_count")])),
+        );
+    }
+
+    let found = args.types.len();
+    if found < required {
+        let expected = if required == type_params.len() { "" } else { "at least " };
+        let mut fixes = Vec::new();
+        let mut fixed = args.clone();
+        fixed.types.resize(required, "_".to_string());
+        fixes.push(replace(fixed, "Add missing generic arguments", "add_missing_generic_args"));
+        if required < type_params.len() {
+            let mut fixed = args.clone();
+            fixed.types.extend(
+                type_params[found..].iter().map(|it| it.clone().unwrap_or_else(|| "_".to_string())),
+            );
+            fixes.push(replace(
+                fixed,
+                "Add all generic arguments, using defaults",
+                "add_all_generic_args",
+            ));
+        }
+        acc.push(
+            Diagnostic::new(
+                "mismatched-generic-arg-count",
+                format!(
+                    "expected {}{}, found {}",
+                    expected,
+                    plural(required, "generic argument"),
+                    found
+                ),
+                range,
+            )
+            .with_fixes(Some(fixes)),
+        );
+    } else if found > type_params.len() {
+        let expected = if required == type_params.len() { "" } else { "at most " };
+        let mut fixed = args;
+        fixed.types.truncate(type_params.len());
+        acc.push(
+            Diagnostic::new(
+                "mismatched-generic-arg-count",
+                format!(
+                    "expected {}{}, found {}",
+                    expected,
+                    plural(type_params.len(), "generic argument"),
+                    found
+                ),
+                range,
+            )
+            .with_fixes(Some(vec![replace(
+                fixed,
+                "Remove extra generic arguments",
+                "remove_extra_generic_args",
+            )])),
+        );
+    }
+
+    Some(())
+}
+
+fn generic_def(ctx: &DiagnosticsContext<'_>, def: ModuleDef) -> Option<GenericDef> {
+    let def = match def {
+        ModuleDef::Adt(it) => GenericDef::Adt(it),
+        ModuleDef::Variant(it) => GenericDef::Adt(it.parent_enum(ctx.sema.db).into()),
+        ModuleDef::Function(it) => GenericDef::Function(it),
+        ModuleDef::Trait(it) => GenericDef::Trait(it),
+        ModuleDef::TypeAlias(it) => GenericDef::TypeAlias(it),
+        _ => return None,
+    };
+    Some(def)
+}
+
+fn plural(count: usize, what: &str) -> String {
+    format!("{} {}{}", count, what, if count == 1 { "" } else { "s" })
+}
+
+/// The arguments of a generic argument list, as written.
+#[derive(Clone)]
+struct Args {
+    lifetimes: Vec<String>,
+    /// Type and const arguments.
+    types: Vec<String>,
+    assoc_types: Vec<String>,
+}
+
+impl Args {
+    fn new(arg_list: Option<&ast::GenericArgList>) -> Args {
+        let mut res = Args { lifetimes: Vec::new(), types: Vec::new(), assoc_types: Vec::new() };
+        for arg in arg_list.into_iter().flat_map(|it| it.generic_args()) {
+            let text = arg.syntax().text().to_string();
+            match arg {
+                ast::GenericArg::LifetimeArg(_) => res.lifetimes.push(text),
+                ast::GenericArg::TypeArg(_) | ast::GenericArg::ConstArg(_) => res.types.push(text),
+                ast::GenericArg::AssocTypeArg(_) => res.assoc_types.push(text),
+            }
+        }
+        res
+    }
+
+    fn render(&self, turbofish: bool) -> String {
+        let args = self.lifetimes.iter().chain(&self.types).chain(&self.assoc_types);
+        if self.lifetimes.is_empty() && self.types.is_empty() && self.assoc_types.is_empty() {
+            return String::new();
+        }
+        format!("{}<{}>", if turbofish { "::" } else { "" }, args.format(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_diagnostics, check_fix, check_fixes};
+
+    #[test]
+    fn generic_arg_count() {
+        check_diagnostics(
+            r#"
+struct S<T, U = i32>(T, U);
+struct R<'a, T>(&'a T);
+fn f<T>() {}
+
+fn main() {
+    let _: S<u8, u8, u8>;
+          //^^^^^^^^^^^^ 💡 error: expected at most 2 generic arguments, found 3
+    let _: S<u8>;
+    let _: S<u8, u8>;
+    let _: R<'static, 'static, u8>;
+          //^^^^^^^^^^^^^^^^^^^^^^ 💡 error: expected 1 lifetime argument, found 2
+    let _: R<u8>;
+    f::<u8>();
+    f::<u8, u8>();
+   //^^^^^^^^^^ 💡 error: expected 1 generic argument, found 2
+    f();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn missing_generic_args_in_type() {
+        check_fixes(
+            r#"
+struct S<T, U = i32>(T, U);
+
+fn f(_: S$0) {}
+"#,
+            vec![
+                r#"
+struct S<T, U = i32>(T, U);
+
+fn f(_: S<_>) {}
+"#,
+                r#"
+struct S<T, U = i32>(T, U);
+
+fn f(_: S<_, i32>) {}
+"#,
+            ],
+        );
+    }
+
+    #[test]
+    fn add_missing_lifetime_args() {
+        check_fix(
+            r#"
+struct R<'a, 'b, T>(&'a T, &'b T);
+
+fn f(_: R<'static, u8>$0) {}
+"#,
+            r#"
+struct R<'a, 'b, T>(&'a T, &'b T);
+
+fn f(_: R<'static, '_, u8>) {}
+"#,
+        );
+    }
+
+    #[test]
+    fn remove_extra_generic_args() {
+        check_fix(
+            r#"
+struct S;
+
+fn main() {
+    let s = S::<u8, i32$0>;
+}
+"#,
+            r#"
+struct S;
+
+fn main() {
+    let s = S;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn method_call_generic_args() {
+        check_fix(
+            r#"
+struct S;
+impl S {
+    fn m<T>(&self) {}
+}
+
+fn main() {
+    S.m::<u8, $0u16>();
+}
+"#,
+            r#"
+struct S;
+impl S {
+    fn m<T>(&self) {}
+}
+
+fn main() {
+    S.m::<u8>();
+}
+"#,
+        );
+    }
+}
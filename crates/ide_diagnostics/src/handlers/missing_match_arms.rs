@@ -820,6 +820,26 @@ fn main() {
         );
     }
 
+    #[test]
+    fn match_guard_let_chain() {
+        check_diagnostics_no_bails(
+            r#"
+fn main() {
+    match true {
+        //^^^^ error: missing match arm
+        true if let true = false && true => {}
+        false => {}
+    }
+    match true {
+        true if let true = false && true => {}
+        true => {}
+        false => {}
+    }
+}
+"#,
+        );
+    }
+
     #[test]
     fn pattern_type_is_of_substitution() {
         check_diagnostics_no_bails(
@@ -0,0 +1,154 @@
+use hir::{Access, AsAssocItem, ModuleDef};
+use ide_db::{
+    base_db::FileId, helpers::mod_path_to_ast, source_change::SourceChange,
+    traits::group_method_candidates,
+};
+use itertools::Itertools;
+use syntax::{ast, AstNode, SyntaxNode};
+use text_edit::TextEdit;
+
+use crate::{fix, Diagnostic, DiagnosticsContext};
+
+// Diagnostic: multiple-applicable-items
+//
+// This diagnostic is triggered if a method call is ambiguous because several traits in scope,
+// implemented at the same autoderef step, have a method of that name. Mirrors rustc's E0034.
+pub(crate) fn multiple_applicable_items(
+    ctx: &DiagnosticsContext<'_>,
+    acc: &mut Vec<Diagnostic>,
+    file_id: FileId,
+    node: &SyntaxNode,
+) -> Option<()> {
+    let call = ast::MethodCallExpr::cast(node.clone())?;
+    let name_ref = call.name_ref()?;
+    let receiver = call.receiver()?;
+    let db = ctx.sema.db;
+    let ty = ctx.sema.type_of_expr(&receiver)?.original;
+    let scope = ctx.sema.scope(call.syntax());
+    let module = scope.module()?;
+
+    let mut candidates = Vec::new();
+    ty.iterate_method_candidates(db, &scope, &scope.visible_traits(), Some(module), None, |func| {
+        if func.self_param(db).is_some() && func.name(db).to_smol_str() == name_ref.text() {
+            candidates.push(func);
+        }
+        None::<()>
+    });
+    let group = group_method_candidates(db, &ty, candidates).into_iter().next()?;
+    if group.len() < 2 {
+        return None;
+    }
+
+    let call_range = call.syntax().text_range();
+    let args = call.arg_list()?.args().map(|it| it.syntax().to_string()).collect::<Vec<_>>();
+    let fixes = group
+        .into_iter()
+        .filter_map(|func| {
+            let trait_ = func.as_assoc_item(db)?.containing_trait(db)?;
+            let path = module.find_use_path(db, ModuleDef::Trait(trait_))?;
+            let qualified = format!("{}::{}", mod_path_to_ast(&path), func.name(db));
+            let borrow = match func.self_param(db)?.access(db) {
+                Access::Shared => "&",
+                Access::Exclusive => "&mut ",
+                Access::Owned => "",
+            };
+            let receiver = format!("{}{}", borrow, receiver.syntax());
+            let replacement = format!(
+                "{}({})",
+                qualified,
+                std::iter::once(receiver).chain(args.iter().cloned()).join(", ")
+            );
+            let edit = TextEdit::replace(call_range, replacement);
+            Some(fix(
+                "use_fully_qualified_syntax",
+                &format!("Use fully qualified syntax: `{}`", qualified),
+                SourceChange::from_text_edit(file_id, edit),
+                call_range,
+            ))
+        })
+        .collect::<Vec<_>>();
+    acc.push(
+        Diagnostic::new(
+            "multiple-applicable-items",
+            "multiple applicable items in scope",
+            name_ref.syntax().text_range(),
+        )
+        .experimental()
+        .with_fixes(Some(fixes).filter(|it| !it.is_empty())),
+    );
+    Some(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_diagnostics, check_fixes};
+
+    #[test]
+    fn multiple_applicable_items() {
+        check_diagnostics(
+            r#"
+mod m {
+    pub trait A { fn name(&self) -> u8 { 0 } }
+    pub trait B { fn name(&self) -> u8 { 1 } }
+    pub trait C { fn other(&self) {} }
+    impl A for u32 {}
+    impl B for u32 {}
+    impl C for u32 {}
+}
+use m::{A, B, C};
+struct S;
+impl S { fn name(&self) {} }
+impl A for S {}
+impl B for S {}
+
+fn main() {
+    0u32.name();
+       //^^^^ 💡 error: multiple applicable items in scope
+    0u32.other();
+    S.name();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn qualifies_call() {
+        check_fixes(
+            r#"
+trait A { fn name(&mut self, x: u8) {} }
+trait B { fn name(&mut self, x: u8) {} }
+impl A for u32 {}
+impl B for u32 {}
+
+fn main() {
+    let mut x = 0u32;
+    x.na$0me(92);
+}
+"#,
+            vec![
+                r#"
+trait A { fn name(&mut self, x: u8) {} }
+trait B { fn name(&mut self, x: u8) {} }
+impl A for u32 {}
+impl B for u32 {}
+
+fn main() {
+    let mut x = 0u32;
+    A::name(&mut x, 92);
+}
+"#,
+                r#"
+trait A { fn name(&mut self, x: u8) {} }
+trait B { fn name(&mut self, x: u8) {} }
+impl A for u32 {}
+impl B for u32 {}
+
+fn main() {
+    let mut x = 0u32;
+    B::name(&mut x, 92);
+}
+"#,
+            ],
+        );
+    }
+}
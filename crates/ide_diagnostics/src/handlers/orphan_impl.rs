@@ -0,0 +1,60 @@
+use crate::{handlers::overlapping_impls::impl_header_range, Diagnostic, DiagnosticsContext};
+
+// Diagnostic: orphan-impl
+//
+// This diagnostic is triggered if an impl implements a trait from another crate for types
+// which are all from other crates too. Types we can't tell the crate of, like trait
+// objects or associated types, are assumed to be local.
+pub(crate) fn orphan_impl(ctx: &DiagnosticsContext<'_>, d: &hir::OrphanImpl) -> Diagnostic {
+    Diagnostic::new(
+        "orphan-impl",
+        "only traits defined in the current crate can be implemented for types defined outside of the crate",
+        impl_header_range(ctx, &d.impl_).range,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::check_diagnostics;
+
+    #[test]
+    fn foreign_trait_for_foreign_types() {
+        check_diagnostics(
+            r#"
+//- /lib.rs crate:lib
+pub trait Foreign<T> {}
+pub struct Ext;
+//- /main.rs crate:main deps:lib
+struct Local;
+  impl lib::Foreign<u8> for lib::Ext {}
+//^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ error: only traits defined in the current crate can be implemented for types defined outside of the crate
+  impl lib::Foreign<u16> for u32 {}
+//^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ error: only traits defined in the current crate can be implemented for types defined outside of the crate
+  impl lib::Foreign<u32> for (Local, Local) {}
+//^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ error: only traits defined in the current crate can be implemented for types defined outside of the crate
+  impl<T> lib::Foreign<u64> for T {}
+//^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ error: only traits defined in the current crate can be implemented for types defined outside of the crate
+"#,
+        );
+    }
+
+    #[test]
+    fn local_trait_or_type() {
+        check_diagnostics(
+            r#"
+//- /lib.rs crate:lib
+pub trait Foreign<T> {}
+pub struct Ext;
+//- /main.rs crate:main deps:lib
+trait Mine {}
+struct Local;
+impl Mine for lib::Ext {}
+impl Mine for u32 {}
+impl lib::Foreign<u8> for Local {}
+impl lib::Foreign<Local> for u32 {}
+impl lib::Foreign<u16> for &Local {}
+impl lib::Foreign<u32> for dyn Mine {}
+"#,
+        );
+    }
+}
@@ -0,0 +1,162 @@
+use hir::InFile;
+use ide_db::base_db::FileRange;
+use syntax::{
+    ast::{self, HasGenericParams},
+    AstNode, AstPtr, TextRange,
+};
+
+use crate::{Diagnostic, DiagnosticsContext};
+
+// Diagnostic: conflicting-impls
+//
+// This diagnostic is triggered if two impls of a trait apply to the same type, in the
+// current crate or in one of its dependencies. Where clauses are only taken into account
+// when the trait solver can tell they never hold, and crates using specialization are
+// not checked.
+pub(crate) fn overlapping_impls(
+    ctx: &DiagnosticsContext<'_>,
+    d: &hir::OverlappingImpls,
+) -> Diagnostic {
+    Diagnostic::new(
+        "conflicting-impls",
+        format!(
+            "conflicting implementations of trait `{}` for type `{}`",
+            d.trait_name,
+            d.self_ty.display(ctx.sema.db)
+        ),
+        impl_header_range(ctx, &d.impl_).range,
+    )
+    .with_related(impl_header_range(ctx, &d.other), "conflicting implementation here")
+}
+
+/// The range of the `impl Trait for Type` part of an impl, leaving out the where clause and
+/// the items.
+pub(crate) fn impl_header_range(
+    ctx: &DiagnosticsContext<'_>,
+    impl_: &InFile<AstPtr<ast::Impl>>,
+) -> FileRange {
+    let full_range = ctx.sema.diagnostics_display_range(impl_.clone().map(|it| it.into()));
+    if impl_.file_id.is_macro() {
+        return full_range;
+    }
+    let node = match ctx.sema.parse_or_expand(impl_.file_id) {
+        Some(root) => impl_.value.to_node(&root),
+        None => return full_range,
+    };
+    let end = node
+        .self_ty()
+        .map(|it| it.syntax().text_range().end())
+        .or_else(|| node.generic_param_list().map(|it| it.syntax().text_range().end()));
+    match end {
+        Some(end) => FileRange {
+            file_id: full_range.file_id,
+            range: TextRange::new(node.syntax().text_range().start(), end),
+        },
+        None => full_range,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::check_diagnostics;
+
+    #[test]
+    fn duplicate_impls() {
+        check_diagnostics(
+            r#"
+trait Tr {}
+struct S;
+  impl Tr for S {}
+//^^^^^^^^^^^^^ error: conflicting implementations of trait `Tr` for type `S`
+  impl Tr for S {}
+//^^^^^^^^^^^^^ error: conflicting implementations of trait `Tr` for type `S`
+"#,
+        );
+    }
+
+    #[test]
+    fn blanket_impl_overlaps() {
+        check_diagnostics(
+            r#"
+//- minicore: copy
+trait Tr {}
+struct S;
+struct C;
+impl Clone for C {
+    fn clone(&self) -> C { C }
+}
+impl Copy for C {}
+
+  impl<T: Copy> Tr for T {}
+//^^^^^^^^^^^^^^^^^^^^^^ error: conflicting implementations of trait `Tr` for type `T`
+impl Tr for S {}
+  impl Tr for C {}
+//^^^^^^^^^^^^^ error: conflicting implementations of trait `Tr` for type `C`
+"#,
+        );
+    }
+
+    #[test]
+    fn generic_impls_overlap() {
+        check_diagnostics(
+            r#"
+//- minicore: sized
+trait Tr<U> {}
+struct W<T>(T);
+  impl<T> Tr<u32> for W<T> {}
+//^^^^^^^^^^^^^^^^^^^^^^^^ error: conflicting implementations of trait `Tr` for type `W<T>`
+  impl Tr<u32> for W<i32> {}
+//^^^^^^^^^^^^^^^^^^^^^^^ error: conflicting implementations of trait `Tr` for type `W<i32>`
+impl Tr<u64> for W<i32> {}
+"#,
+        );
+    }
+
+    #[test]
+    fn impl_in_dependency() {
+        check_diagnostics(
+            r#"
+//- /lib.rs crate:lib
+pub trait Tr {}
+impl<T> Tr for &T {}
+//- /main.rs crate:main deps:lib
+struct S;
+  impl<'a> lib::Tr for &'a S {}
+//^^^^^^^^^^^^^^^^^^^^^^^^^^ error: conflicting implementations of trait `Tr` for type `&S`
+"#,
+        );
+    }
+
+    #[test]
+    fn no_overlap() {
+        check_diagnostics(
+            r#"
+//- minicore: copy
+trait Tr {}
+trait Marker {}
+struct S;
+struct M;
+impl Marker for M {}
+impl<T: Marker> Tr for T {}
+impl Tr for S {}
+impl<T> Tr for [T] {}
+impl<T: Copy> Tr for (T, S) {}
+impl Tr for (S, S) {}
+impl !Tr for u32 {}
+"#,
+        );
+    }
+
+    #[test]
+    fn no_overlap_with_specialization() {
+        check_diagnostics(
+            r#"
+#![feature(specialization)]
+trait Tr {}
+struct S;
+impl<T> Tr for T {}
+impl Tr for S {}
+"#,
+        );
+    }
+}
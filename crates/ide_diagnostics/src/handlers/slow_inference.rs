@@ -0,0 +1,66 @@
+use hir::DefWithBody;
+use syntax::{
+    ast::{self, HasName},
+    AstNode, SyntaxNode,
+};
+
+use crate::{Diagnostic, DiagnosticsContext, Severity};
+
+// Diagnostic: slow-inference
+//
+// This diagnostic is triggered if inference profiling is enabled and type inference of a function,
+// constant or static took longer than `rust-analyzer.inferenceProfiling.slowThreshold`, so that
+// pathological code, like deeply recursive trait bounds, can be restructured.
+pub(crate) fn slow_inference(
+    ctx: &DiagnosticsContext<'_>,
+    acc: &mut Vec<Diagnostic>,
+    node: &SyntaxNode,
+) -> Option<()> {
+    let (def, what, name): (DefWithBody, _, _) = if let Some(it) = ast::Fn::cast(node.clone()) {
+        (ctx.sema.to_def(&it)?.into(), "function", it.name()?)
+    } else if let Some(it) = ast::Const::cast(node.clone()) {
+        (ctx.sema.to_def(&it)?.into(), "constant", it.name()?)
+    } else if let Some(it) = ast::Static::cast(node.clone()) {
+        (ctx.sema.to_def(&it)?.into(), "static", it.name()?)
+    } else {
+        return None;
+    };
+    let timing = def.inference_timing()?;
+    if timing.total < ctx.config.slow_inference_threshold {
+        return None;
+    }
+    let mut message = format!("analysis of this {} took {:.1}s", what, timing.total.as_secs_f64());
+    if let Some(cause) = timing.likely_cause() {
+        message = format!("{}, {}", message, cause);
+    }
+    acc.push(
+        Diagnostic::new("slow-inference", message, name.syntax().text_range())
+            .severity(Severity::WeakWarning),
+    );
+    Some(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use ide_db::{assists::AssistResolveStrategy, base_db::fixture::WithFixture, RootDatabase};
+
+    use crate::DiagnosticsConfig;
+
+    #[test]
+    fn slow_inference() {
+        hir::set_inference_profiling(true);
+        let (db, file_id) = RootDatabase::with_single_file(
+            r#"
+fn slow() -> u32 { 92 }
+"#,
+        );
+        let config =
+            DiagnosticsConfig { slow_inference_threshold: Duration::ZERO, ..Default::default() };
+        let diagnostics = crate::diagnostics(&db, &config, &AssistResolveStrategy::All, file_id);
+        let diagnostic =
+            diagnostics.iter().find(|it| it.code.as_str() == "slow-inference").unwrap();
+        assert!(diagnostic.message.starts_with("analysis of this function took "));
+    }
+}
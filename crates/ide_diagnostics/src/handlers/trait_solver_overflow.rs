@@ -0,0 +1,72 @@
+use crate::{Diagnostic, DiagnosticsContext, Severity};
+
+// Diagnostic: trait-solver-overflow
+//
+// This diagnostic is triggered if the trait solver gave up on a goal while inferring the type of
+// an expression, or if autoderef reached its recursion limit, which usually results in `{unknown}`
+// types. The solver's budget can be raised via `#![recursion_limit]` and the
+// `rust-analyzer.traitSolver.fuel` setting.
+pub(crate) fn trait_solver_overflow(
+    ctx: &DiagnosticsContext<'_>,
+    d: &hir::TraitSolverOverflow,
+) -> Diagnostic {
+    Diagnostic::new(
+        "trait-solver-overflow",
+        "trait solving overflowed, some types may be unknown",
+        ctx.sema.diagnostics_display_range(d.expr.clone().map(|it| it.into())).range,
+    )
+    .severity(Severity::WeakWarning)
+}
+
+#[cfg(test)]
+mod tests {
+    use hir::db::HirDatabase;
+    use ide_db::{assists::AssistResolveStrategy, base_db::fixture::WithFixture, RootDatabase};
+
+    use crate::{tests::check_diagnostics, DiagnosticsConfig};
+
+    #[test]
+    fn out_of_fuel() {
+        let (mut db, file_id) = RootDatabase::with_single_file(
+            r#"
+trait Foo {}
+struct S;
+impl Foo for S {}
+fn foo<T: Foo>(_: T) {}
+fn main() {
+    foo(S);
+}
+"#,
+        );
+        db.set_trait_solver_fuel(0);
+        let diagnostics = crate::diagnostics(
+            &db,
+            &DiagnosticsConfig::default(),
+            &AssistResolveStrategy::All,
+            file_id,
+        );
+        assert!(
+            diagnostics.iter().any(|d| d.code.as_str() == "trait-solver-overflow"),
+            "{:?}",
+            diagnostics
+        );
+    }
+
+    #[test]
+    fn autoderef_recursion_limit() {
+        check_diagnostics(
+            r#"
+//- minicore: deref
+struct S;
+impl core::ops::Deref for S {
+    type Target = S;
+    fn deref(&self) -> &S { self }
+}
+fn main() {
+    S.foo;
+  //^^^^^ weak: trait solving overflowed, some types may be unknown
+}
+"#,
+        );
+    }
+}
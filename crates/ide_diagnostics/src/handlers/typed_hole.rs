@@ -0,0 +1,257 @@
+use hir::{HirDisplay, ModuleDef, ScopeDef, StructKind, Type};
+use ide_db::{
+    base_db::FileId, famous_defs::FamousDefs, helpers::mod_path_to_ast, source_change::SourceChange,
+};
+use itertools::Itertools;
+use syntax::{ast, AstNode, SyntaxNode, TextRange};
+use text_edit::TextEdit;
+
+use crate::{fix, Assist, Diagnostic, DiagnosticsContext, Severity};
+
+// Diagnostic: typed-hole
+//
+// This diagnostic is triggered on a `_` expression outside of a destructuring assignment, and on
+// `todo!()` and `unimplemented!()` calls, when the type expected at that position is known. It
+// reports that type and offers to fill the hole with a local variable, a constructor or
+// `Default::default()` that fits.
+pub(crate) fn typed_hole(
+    ctx: &DiagnosticsContext<'_>,
+    acc: &mut Vec<Diagnostic>,
+    file_id: FileId,
+    node: &SyntaxNode,
+) -> Option<()> {
+    let expr = ast::Expr::cast(node.clone())?;
+    let db = ctx.sema.db;
+    let (ty, is_placeholder) = match &expr {
+        ast::Expr::UnderscoreExpr(_) => {
+            if is_assignee(&expr) {
+                return None;
+            }
+            (ctx.sema.type_of_expr(&expr)?.original, false)
+        }
+        ast::Expr::MacroCall(call) => {
+            let mac = ctx.sema.resolve_macro_call(call)?;
+            if !matches!(mac.name(db).to_smol_str().as_str(), "todo" | "unimplemented") {
+                return None;
+            }
+            // The call itself diverges, the type it stands in for is the one it's coerced to.
+            let ty = ctx.sema.type_of_expr(&expr)?.adjusted();
+            if ty.is_unit() {
+                return None;
+            }
+            (ty, true)
+        }
+        _ => return None,
+    };
+    if ty.is_unknown() || ty.is_never() {
+        return None;
+    }
+
+    let range = expr.syntax().text_range();
+    let ty_text = ty.display(db);
+    let diag = if is_placeholder {
+        Diagnostic::new("typed-hole", format!("placeholder of type {}", ty_text), range)
+            .severity(Severity::WeakWarning)
+    } else {
+        Diagnostic::new("typed-hole", format!("typed hole of type {}", ty_text), range)
+    };
+    acc.push(diag.with_fixes(fixes(ctx, file_id, &expr, &ty, range)));
+    Some(())
+}
+
+/// `_` is allowed on the left-hand side of a destructuring assignment, like `(a, _) = (1, 2)`.
+fn is_assignee(expr: &ast::Expr) -> bool {
+    expr.syntax().ancestors().filter_map(ast::BinExpr::cast).any(|bin| {
+        bin.op_kind() == Some(ast::BinaryOp::Assignment { op: None })
+            && bin.lhs().map_or(false, |lhs| {
+                lhs.syntax().text_range().contains_range(expr.syntax().text_range())
+            })
+    })
+}
+
+fn fixes(
+    ctx: &DiagnosticsContext<'_>,
+    file_id: FileId,
+    expr: &ast::Expr,
+    ty: &Type,
+    range: TextRange,
+) -> Option<Vec<Assist>> {
+    let db = ctx.sema.db;
+    let scope = ctx.sema.scope(expr.syntax());
+    let mut terms = Vec::new();
+
+    scope.process_all_names(&mut |name, def| {
+        if let ScopeDef::Local(local) = def {
+            if local.ty(db).could_coerce_to(db, ty) {
+                terms.push(name.to_string());
+            }
+        }
+    });
+
+    if let (Some(adt), Some(module)) = (ty.as_adt(), scope.module()) {
+        let constructors: Vec<(ModuleDef, StructKind, Vec<hir::Field>)> = match adt {
+            hir::Adt::Struct(it) => vec![(it.into(), it.kind(db), it.fields(db))],
+            hir::Adt::Enum(it) => it
+                .variants(db)
+                .into_iter()
+                .map(|variant| (variant.into(), variant.kind(db), variant.fields(db)))
+                .collect(),
+            hir::Adt::Union(_) => Vec::new(),
+        };
+        for (def, kind, fields) in constructors {
+            if fields.iter().any(|field| !field.is_visible_from(db, module)) {
+                continue;
+            }
+            let path = match module.find_use_path(db, def) {
+                Some(it) => mod_path_to_ast(&it),
+                None => continue,
+            };
+            terms.push(match kind {
+                StructKind::Unit => path.to_string(),
+                StructKind::Tuple => {
+                    format!("{}({})", path, fields.iter().map(|_| "todo!()").join(", "))
+                }
+                StructKind::Record => format!(
+                    "{} {{ {} }}",
+                    path,
+                    fields.iter().map(|field| format!("{}: todo!()", field.name(db))).join(", ")
+                ),
+            });
+        }
+    }
+
+    if let Some(default_trait) = FamousDefs(&ctx.sema, scope.krate()).core_default_Default() {
+        if ty.impls_trait(db, default_trait, &[]) {
+            terms.push("Default::default()".to_string());
+        }
+    }
+
+    let fixes = terms
+        .into_iter()
+        .unique()
+        .map(|term| {
+            let label = format!("Replace with `{}`", term);
+            let edit = TextEdit::replace(range, term);
+            fix("fill_typed_hole", &label, SourceChange::from_text_edit(file_id, edit), range)
+        })
+        .collect::<Vec<_>>();
+    Some(fixes).filter(|it| !it.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_diagnostics, check_fixes};
+
+    #[test]
+    fn reports_typed_holes() {
+        check_diagnostics(
+            r#"
+macro_rules! todo { () => { loop {} } }
+macro_rules! unimplemented { () => { loop {} } }
+struct S { a: u32 }
+
+fn f(x: u32) {
+    let y: u32 = _;
+               //^ 💡 error: typed hole of type u32
+    let z;
+    (z, _) = (1, 2);
+    _ = x;
+    todo!();
+}
+fn g() -> S {
+    let s: S = todo!();
+             //^^^^^^^ 💡 weak: placeholder of type S
+    s
+}
+fn h() -> S {
+    unimplemented!()
+  //^^^^^^^^^^^^^^^^ 💡 weak: placeholder of type S
+}
+fn i() {
+    todo!()
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn fills_typed_hole() {
+        check_fixes(
+            r#"
+//- minicore: default
+struct S;
+impl Default for S { fn default() -> Self { S } }
+
+fn f(x: S, y: i32) {
+    let _: S = $0_;
+}
+"#,
+            vec![
+                r#"
+struct S;
+impl Default for S { fn default() -> Self { S } }
+
+fn f(x: S, y: i32) {
+    let _: S = x;
+}
+"#,
+                r#"
+struct S;
+impl Default for S { fn default() -> Self { S } }
+
+fn f(x: S, y: i32) {
+    let _: S = S;
+}
+"#,
+                r#"
+struct S;
+impl Default for S { fn default() -> Self { S } }
+
+fn f(x: S, y: i32) {
+    let _: S = Default::default();
+}
+"#,
+            ],
+        );
+    }
+
+    #[test]
+    fn fills_placeholder_with_constructors() {
+        check_fixes(
+            r#"
+macro_rules! todo { () => { loop {} } }
+enum E { A, B(u8), C { c: bool } }
+
+fn f() -> E {
+    todo!$0()
+}
+"#,
+            vec![
+                r#"
+macro_rules! todo { () => { loop {} } }
+enum E { A, B(u8), C { c: bool } }
+
+fn f() -> E {
+    E::A
+}
+"#,
+                r#"
+macro_rules! todo { () => { loop {} } }
+enum E { A, B(u8), C { c: bool } }
+
+fn f() -> E {
+    E::B(todo!())
+}
+"#,
+                r#"
+macro_rules! todo { () => { loop {} } }
+enum E { A, B(u8), C { c: bool } }
+
+fn f() -> E {
+    E::C { c: todo!() }
+}
+"#,
+            ],
+        );
+    }
+}
@@ -0,0 +1,310 @@
+use hir::{AsAssocItem, ModuleDef, PathResolution, Semantics, StructKind, Variant};
+use ide_db::RootDatabase;
+use syntax::{
+    ast::{self, BinaryOp, ElseBranch, LiteralKind, LogicOp, UnaryOp},
+    AstNode, SyntaxNode,
+};
+
+use crate::{Diagnostic, DiagnosticsContext, Severity};
+
+// Diagnostic: unreachable-branch
+//
+// This diagnostic is shown for `if` branches and `match` arms which are never
+// taken, because the condition or the matched value is trivially constant, like
+// `cfg!(test)`, a `bool` constant or a unit enum variant.
+pub(crate) fn unreachable_branch(
+    ctx: &DiagnosticsContext<'_>,
+    acc: &mut Vec<Diagnostic>,
+    node: &SyntaxNode,
+) -> Option<()> {
+    // Literal conditions like `if false` are obviously constant already.
+    let is_literal = |expr: &ast::Expr| matches!(expr, ast::Expr::Literal(_));
+    if let Some(if_expr) = ast::IfExpr::cast(node.clone()) {
+        let condition = if_expr.condition().filter(|it| !is_literal(it))?;
+        let value = match eval(&ctx.sema, &condition, 0)? {
+            Value::Bool(it) => it,
+            Value::Variant(_) => return None,
+        };
+        let branch = if value {
+            match if_expr.else_branch()? {
+                ElseBranch::Block(it) => it.syntax().clone(),
+                ElseBranch::IfExpr(it) => it.syntax().clone(),
+            }
+        } else {
+            if_expr.then_branch()?.syntax().clone()
+        };
+        acc.push(unreachable(
+            format!("unreachable branch: the condition is always {}", value),
+            &branch,
+        ));
+    } else if let Some(match_expr) = ast::MatchExpr::cast(node.clone()) {
+        let scrutinee = match_expr.expr().filter(|it| !is_literal(it))?;
+        let value = eval(&ctx.sema, &scrutinee, 0)?;
+        let arms = match_expr.match_arm_list()?.arms();
+        let (_, unreachable_arms) = taken_arm(&ctx.sema, value, arms);
+        for arm in unreachable_arms {
+            acc.push(unreachable(
+                format!(
+                    "unreachable match arm: the matched value is always `{}`",
+                    value.display(ctx.sema.db)
+                ),
+                arm.syntax(),
+            ));
+        }
+    }
+    Some(())
+}
+
+fn unreachable(message: String, node: &SyntaxNode) -> Diagnostic {
+    Diagnostic::new("unreachable-branch", message, node.text_range())
+        .severity(Severity::WeakWarning)
+        .with_unused(true)
+}
+
+/// A value known without running the program.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Value {
+    Bool(bool),
+    /// A unit enum variant.
+    Variant(Variant),
+}
+
+impl Value {
+    fn display(self, db: &RootDatabase) -> String {
+        match self {
+            Value::Bool(it) => it.to_string(),
+            Value::Variant(it) => format!("{}::{}", it.parent_enum(db).name(db), it.name(db)),
+        }
+    }
+}
+
+/// Constants referring to other constants are followed this deep.
+const MAX_DEPTH: usize = 8;
+
+/// Evaluates trivially constant expressions: `bool` literals and constants,
+/// unit variants, `cfg!`, `matches!` and the logic operators.
+pub(super) fn eval(
+    sema: &Semantics<RootDatabase>,
+    expr: &ast::Expr,
+    depth: usize,
+) -> Option<Value> {
+    if depth > MAX_DEPTH {
+        return None;
+    }
+    let eval_bool = |expr: Option<ast::Expr>| match eval(sema, &expr?, depth)? {
+        Value::Bool(it) => Some(it),
+        Value::Variant(_) => None,
+    };
+    let res = match expr {
+        ast::Expr::Literal(lit) => match lit.kind() {
+            LiteralKind::Bool(it) => Value::Bool(it),
+            _ => return None,
+        },
+        ast::Expr::ParenExpr(it) => return eval(sema, &it.expr()?, depth),
+        ast::Expr::PrefixExpr(it) if it.op_kind() == Some(UnaryOp::Not) => {
+            Value::Bool(!eval_bool(it.expr())?)
+        }
+        ast::Expr::BinExpr(it) => {
+            // Like at runtime, the right hand side doesn't matter if the left
+            // hand side decides the result.
+            let (lhs, rhs) = (it.lhs(), it.rhs());
+            match it.op_kind()? {
+                BinaryOp::LogicOp(LogicOp::And) => match eval_bool(lhs)? {
+                    false => Value::Bool(false),
+                    true => Value::Bool(eval_bool(rhs)?),
+                },
+                BinaryOp::LogicOp(LogicOp::Or) => match eval_bool(lhs)? {
+                    true => Value::Bool(true),
+                    false => Value::Bool(eval_bool(rhs)?),
+                },
+                _ => return None,
+            }
+        }
+        ast::Expr::PathExpr(it) => match sema.resolve_path(&it.path()?)? {
+            PathResolution::Def(ModuleDef::Const(konst)) => {
+                // The default of a trait's const, like `T::FLAG`, can be overridden by impls.
+                if konst
+                    .as_assoc_item(sema.db)
+                    .and_then(|it| it.containing_trait(sema.db))
+                    .is_some()
+                {
+                    return None;
+                }
+                if let Some(it) = konst.eval_bool(sema.db) {
+                    return Some(Value::Bool(it));
+                }
+                let body = sema.source(konst)?.value.body()?;
+                return eval(sema, &body, depth + 1);
+            }
+            PathResolution::Def(ModuleDef::Variant(it)) if it.kind(sema.db) == StructKind::Unit => {
+                Value::Variant(it)
+            }
+            _ => return None,
+        },
+        ast::Expr::MacroCall(it) => {
+            let expansion = sema.expand(it)?;
+            let expr = expansion.descendants().find_map(ast::Expr::cast)?;
+            return eval(sema, &expr, depth + 1);
+        }
+        ast::Expr::MatchExpr(it) => {
+            let value = eval(sema, &it.expr()?, depth)?;
+            let (arm, _) = taken_arm(sema, value, it.match_arm_list()?.arms());
+            return eval(sema, &arm?.expr()?, depth);
+        }
+        _ => return None,
+    };
+    Some(res)
+}
+
+/// Matches `value` against `arms`, returning the arm which is definitely taken,
+/// if known, and the arms which definitely aren't.
+fn taken_arm(
+    sema: &Semantics<RootDatabase>,
+    value: Value,
+    arms: impl Iterator<Item = ast::MatchArm>,
+) -> (Option<ast::MatchArm>, Vec<ast::MatchArm>) {
+    let mut taken = None;
+    let mut not_taken = Vec::new();
+    for arm in arms {
+        if taken.is_some() {
+            not_taken.push(arm);
+            continue;
+        }
+        match arm.pat().and_then(|pat| pat_matches(sema, &pat, value)) {
+            Some(false) => not_taken.push(arm),
+            Some(true) if arm.guard().is_none() => taken = Some(arm),
+            _ => (),
+        }
+    }
+    (taken, not_taken)
+}
+
+/// Whether `pat` matches `value`, or `None` if we can't tell.
+fn pat_matches(sema: &Semantics<RootDatabase>, pat: &ast::Pat, value: Value) -> Option<bool> {
+    let path_matches = |path: &ast::Path| match sema.resolve_path(path)? {
+        PathResolution::Def(ModuleDef::Variant(it)) => Some(value == Value::Variant(it)),
+        _ => None,
+    };
+    match pat {
+        ast::Pat::WildcardPat(_) => Some(true),
+        ast::Pat::ParenPat(it) => pat_matches(sema, &it.pat()?, value),
+        ast::Pat::OrPat(it) => {
+            let mut res = Some(false);
+            for pat in it.pats() {
+                match pat_matches(sema, &pat, value) {
+                    Some(true) => return Some(true),
+                    Some(false) => (),
+                    None => res = None,
+                }
+            }
+            res
+        }
+        ast::Pat::LiteralPat(it) => match it.literal()?.kind() {
+            LiteralKind::Bool(it) => Some(value == Value::Bool(it)),
+            _ => None,
+        },
+        ast::Pat::IdentPat(it) => match sema.resolve_bind_pat_to_const(it) {
+            Some(ModuleDef::Variant(variant)) => Some(value == Value::Variant(variant)),
+            Some(_) => None,
+            None if it.pat().is_none() => Some(true),
+            None => None,
+        },
+        ast::Pat::PathPat(it) => path_matches(&it.path()?),
+        // These can't match a unit variant, but could still be the right one.
+        ast::Pat::TupleStructPat(it) => path_matches(&it.path()?).filter(|it| !it),
+        ast::Pat::RecordPat(it) => path_matches(&it.path()?).filter(|it| !it),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::check_diagnostics;
+
+    #[test]
+    fn if_branches() {
+        check_diagnostics(
+            r#"
+//- /main.rs cfg:test
+#[rustc_builtin_macro]
+macro_rules! cfg { () => {} }
+const VERBOSE: bool = false;
+const ALSO_VERBOSE: bool = VERBOSE;
+
+fn f(x: bool) {
+    if cfg!(test) {} else { f(x) }
+                        //^^^^^^^^ weak: unreachable branch: the condition is always true
+    if VERBOSE { f(x) }
+             //^^^^^^^^ weak: unreachable branch: the condition is always false
+    if !ALSO_VERBOSE && cfg!(test) {} else if x {}
+                                         //^^^^^^^ weak: unreachable branch: the condition is always true
+    if x || VERBOSE {}
+    if VERBOSE || x {}
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn match_arms() {
+        check_diagnostics(
+            r#"
+enum Mode { Fast, Slow(u32), Other }
+use Mode::*;
+const MODE: Mode = Mode::Fast;
+
+fn f(x: bool) -> u32 {
+    match MODE {
+        Mode::Slow(n) => n,
+      //^^^^^^^^^^^^^^^^^^^ weak: unreachable match arm: the matched value is always `Mode::Fast`
+        Other => 1,
+      //^^^^^^^^^^^ weak: unreachable match arm: the matched value is always `Mode::Fast`
+        Fast if x => 2,
+        Mode::Fast => 3,
+        _ => 4,
+      //^^^^^^^ weak: unreachable match arm: the matched value is always `Mode::Fast`
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn trait_consts_are_unknown() {
+        check_diagnostics(
+            r#"
+trait Flag {
+    const FLAG: bool = false;
+    fn f() {
+        if Self::FLAG {}
+    }
+}
+struct S;
+impl Flag for S {
+    const FLAG: bool = true;
+}
+fn g<T: Flag>() {
+    if T::FLAG {}
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn unknown_values() {
+        check_diagnostics(
+            r#"
+fn g() -> bool { true }
+fn f(x: Option<u32>) {
+    if false {}
+    if g() {}
+    if let Some(_) = x {}
+    match g() {
+        true => (),
+        false => (),
+    }
+}
+"#,
+        );
+    }
+}
@@ -89,6 +89,7 @@ mod baz {}
                         range: 0..8,
                         severity: Error,
                         unused: false,
+                        deprecated: false,
                         experimental: false,
                         fixes: Some(
                             [
@@ -146,6 +147,7 @@ mod baz {}
                                 },
                             ],
                         ),
+                        related: [],
                     },
                 ]
             "#]],
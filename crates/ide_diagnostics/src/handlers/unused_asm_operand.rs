@@ -0,0 +1,158 @@
+use hir::{MacroKind, Semantics};
+use ide_db::{
+    syntax_helpers::format_string::{lex_format_specifiers, FormatSpecifier},
+    RootDatabase,
+};
+use itertools::Itertools;
+use rustc_hash::FxHashSet;
+use syntax::{
+    ast, AstNode, AstToken, NodeOrToken, SyntaxKind, SyntaxNode, SyntaxToken, TextRange, T,
+};
+
+use crate::{Diagnostic, DiagnosticsContext};
+
+// Diagnostic: unused-asm-operand
+//
+// This diagnostic is triggered if an operand of an `asm!` or `global_asm!` call is not used by
+// its template strings. Operands bound to an explicit register, like `in("eax") x`, don't need
+// to be.
+pub(crate) fn unused_asm_operand(
+    ctx: &DiagnosticsContext<'_>,
+    acc: &mut Vec<Diagnostic>,
+    node: &SyntaxNode,
+) -> Option<()> {
+    let macro_call = ast::MacroCall::cast(node.clone())?;
+    if !is_asm_call(&ctx.sema, &macro_call) {
+        return None;
+    }
+    let tt = macro_call.token_tree()?;
+    let elements: Vec<_> = tt
+        .syntax()
+        .children_with_tokens()
+        .filter(|it| !it.kind().is_trivia() && !matches!(it.kind(), T!['('] | T![')']))
+        .collect();
+
+    let mut templates = Vec::new();
+    let mut operands = Vec::new();
+    for arg in elements.split(|it| it.kind() == T![,]) {
+        match arg {
+            [NodeOrToken::Token(token)] => templates.extend(ast::String::cast(token.clone())),
+            [NodeOrToken::Token(name), NodeOrToken::Token(eq), rest @ ..]
+                if name.kind() == SyntaxKind::IDENT && eq.kind() == T![=] =>
+            {
+                operands.push(Operand {
+                    name: Some(name.clone()),
+                    range: name.text_range(),
+                    explicit: is_explicit(rest),
+                })
+            }
+            [NodeOrToken::Token(kind), ..] if is_operand_kind(kind) => operands.push(Operand {
+                name: None,
+                range: kind.text_range(),
+                explicit: is_explicit(arg),
+            }),
+            _ => (),
+        }
+    }
+
+    let mut used_indices = FxHashSet::default();
+    let mut used_names = FxHashSet::default();
+    let mut next_index = 0;
+    for template in &templates {
+        let text = template.text();
+        let mut pieces = Vec::new();
+        lex_format_specifiers(template, &mut |range, kind| pieces.push((range, kind)));
+        for ((_, first), (range, second)) in pieces.iter().tuple_windows() {
+            match (first, second) {
+                (FormatSpecifier::Open, FormatSpecifier::Close | FormatSpecifier::Colon) => {
+                    used_indices.insert(next_index);
+                    next_index += 1;
+                }
+                (FormatSpecifier::Open, FormatSpecifier::Integer) => {
+                    used_indices.extend(text[*range].parse::<usize>().ok());
+                }
+                (FormatSpecifier::Open, FormatSpecifier::Identifier) => {
+                    used_names.insert(text[*range].to_string());
+                }
+                _ => (),
+            }
+        }
+    }
+
+    for (idx, operand) in operands.iter().enumerate() {
+        if operand.explicit || used_indices.contains(&idx) {
+            continue;
+        }
+        let message = match &operand.name {
+            Some(name) if used_names.contains(name.text()) => continue,
+            Some(_) => "named argument never used",
+            None => "argument never used",
+        };
+        acc.push(Diagnostic::new("unused-asm-operand", message, operand.range));
+    }
+    Some(())
+}
+
+struct Operand {
+    name: Option<SyntaxToken>,
+    /// The name of named operands, the kind of the others, like `in`.
+    range: TextRange,
+    /// Bound to a register like `"eax"`, rather than a register class like `reg`.
+    explicit: bool,
+}
+
+pub(crate) fn is_asm_call(sema: &Semantics<RootDatabase>, macro_call: &ast::MacroCall) -> bool {
+    sema.resolve_macro_call(macro_call).map_or(false, |mac| {
+        mac.kind(sema.db) == MacroKind::BuiltIn
+            && matches!(mac.name(sema.db).to_smol_str().as_str(), "asm" | "global_asm")
+    })
+}
+
+fn is_operand_kind(token: &SyntaxToken) -> bool {
+    matches!(token.text(), "in" | "out" | "lateout" | "inout" | "inlateout" | "const" | "sym")
+}
+
+fn is_explicit(operand: &[NodeOrToken<SyntaxNode, SyntaxToken>]) -> bool {
+    match operand {
+        [_, NodeOrToken::Node(reg), ..] => {
+            reg.children_with_tokens().any(|it| it.kind() == SyntaxKind::STRING)
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::check_diagnostics;
+
+    #[test]
+    fn unused_asm_operand() {
+        check_diagnostics(
+            r#"
+#[rustc_builtin_macro]
+#[macro_export]
+macro_rules! format_args {}
+#[rustc_builtin_macro]
+macro_rules! asm {}
+
+fn f(x: u64, y: u64) {
+    unsafe {
+        asm!(
+            "mov {0}, {out}",
+            "add {out}, {1}",
+            in(reg) x,
+            in(reg) y,
+            out = out(reg) _,
+            in(reg) 1u8,
+          //^^ error: argument never used
+            unused = in(reg) x,
+          //^^^^^^ error: named argument never used
+            in("eax") y,
+            options(nostack),
+        );
+    }
+}
+"#,
+        );
+    }
+}
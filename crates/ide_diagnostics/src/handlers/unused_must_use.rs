@@ -0,0 +1,194 @@
+use hir::{HasAttrs, ModuleDef, PathResolution};
+use ide_db::{base_db::FileId, source_change::SourceChange, RootDatabase};
+use syntax::{ast, AstNode, SyntaxNode};
+use text_edit::TextEdit;
+
+use crate::{fix, Diagnostic, DiagnosticsContext, Severity};
+
+// Diagnostic: unused-must-use
+//
+// This diagnostic is triggered if the result of a call is discarded although
+// the called function, or the type of the result, is marked `#[must_use]`.
+// Results are checked after `?` and `.await`, and for the last call of a
+// method chain.
+pub(crate) fn unused_must_use(
+    ctx: &DiagnosticsContext<'_>,
+    acc: &mut Vec<Diagnostic>,
+    file_id: FileId,
+    node: &SyntaxNode,
+) -> Option<()> {
+    let stmt = ast::ExprStmt::cast(node.clone())?;
+    stmt.semicolon_token()?;
+    let expr = stmt.expr()?;
+    let mut inner = expr.clone();
+    while let ast::Expr::ParenExpr(it) = &inner {
+        inner = it.expr()?;
+    }
+    if !matches!(
+        inner,
+        ast::Expr::CallExpr(_)
+            | ast::Expr::MethodCallExpr(_)
+            | ast::Expr::TryExpr(_)
+            | ast::Expr::AwaitExpr(_)
+    ) {
+        return None;
+    }
+
+    let db = ctx.sema.db;
+    let ty = ctx.sema.type_of_expr(&expr)?.original;
+    if ty.is_unit() || ty.is_never() {
+        return None;
+    }
+    let (message, note) = if let Some(adt) = ty.as_adt().filter(|it| is_must_use(db, *it)) {
+        let attrs = adt.attrs(db);
+        let note = attrs.by_key("must_use").string_value().map(|it| it.to_string());
+        (format!("unused `{}` that must be used", adt.name(db)), note)
+    } else if let Some(trait_) =
+        ty.as_impl_traits(db).and_then(|mut traits| traits.find(|it| is_must_use(db, *it)))
+    {
+        let attrs = trait_.attrs(db);
+        let note = attrs.by_key("must_use").string_value().map(|it| it.to_string());
+        (format!("unused implementer of `{}` that must be used", trait_.name(db)), note)
+    } else {
+        let func = match &inner {
+            ast::Expr::CallExpr(call) => match call.expr()? {
+                ast::Expr::PathExpr(path) => match ctx.sema.resolve_path(&path.path()?)? {
+                    PathResolution::Def(ModuleDef::Function(it)) => it,
+                    _ => return None,
+                },
+                _ => return None,
+            },
+            ast::Expr::MethodCallExpr(call) => ctx.sema.resolve_method_call(call)?,
+            _ => return None,
+        };
+        if !is_must_use(db, func) {
+            return None;
+        }
+        let attrs = func.attrs(db);
+        let note = attrs.by_key("must_use").string_value().map(|it| it.to_string());
+        (format!("unused return value of `{}` that must be used", func.name(db)), note)
+    };
+    let message = match note {
+        Some(note) => format!("{}: {}", message, note),
+        None => message,
+    };
+
+    let range = expr.syntax().text_range();
+    let let_underscore = TextEdit::insert(range.start(), "let _ = ".to_string());
+    let mut drop = TextEdit::builder();
+    drop.insert(range.start(), "drop(".to_string());
+    drop.insert(range.end(), ")".to_string());
+    let fixes = vec![
+        fix(
+            "ignore_with_let_underscore",
+            "Ignore the value with `let _ = `",
+            SourceChange::from_text_edit(file_id, let_underscore),
+            range,
+        ),
+        fix(
+            "wrap_in_drop",
+            "Wrap in `drop(...)`",
+            SourceChange::from_text_edit(file_id, drop.finish()),
+            range,
+        ),
+    ];
+    acc.push(
+        Diagnostic::new("unused-must-use", message, range)
+            .severity(Severity::WeakWarning)
+            .with_fixes(Some(fixes)),
+    );
+    Some(())
+}
+
+fn is_must_use(db: &RootDatabase, def: impl HasAttrs) -> bool {
+    def.attrs(db).by_key("must_use").exists()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_diagnostics, check_fixes};
+
+    #[test]
+    fn unused_must_use() {
+        check_diagnostics(
+            r#"
+#[must_use]
+fn f() -> i32 { 0 }
+#[must_use = "check the status"]
+struct Status;
+fn status() -> Status { Status }
+struct S;
+impl S {
+    #[must_use]
+    fn get(&self) -> i32 { 0 }
+    fn chain(&self) -> &S { self }
+    fn status(&self) -> Status { Status }
+}
+
+fn main() {
+    f();
+  //^^^ 💡 weak: unused return value of `f` that must be used
+    status();
+  //^^^^^^^^ 💡 weak: unused `Status` that must be used: check the status
+    S.chain().chain().get();
+  //^^^^^^^^^^^^^^^^^^^^^^^ 💡 weak: unused return value of `get` that must be used
+    (S.status());
+  //^^^^^^^^^^^^ 💡 weak: unused `Status` that must be used: check the status
+    let _ = f();
+    let _x = status();
+    S.chain();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn through_try() {
+        check_diagnostics(
+            r#"
+//- minicore: option, try
+#[must_use]
+struct Status;
+fn status() -> Option<Status> { None }
+#[must_use]
+fn checked() -> Option<i32> { None }
+
+fn f() -> Option<()> {
+    status()?;
+  //^^^^^^^^^ 💡 weak: unused `Status` that must be used
+    checked()?;
+    None
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn fixes() {
+        check_fixes(
+            r#"
+#[must_use]
+fn f() -> i32 { 0 }
+fn main() {
+    f()$0;
+}
+"#,
+            vec![
+                r#"
+#[must_use]
+fn f() -> i32 { 0 }
+fn main() {
+    let _ = f();
+}
+"#,
+                r#"
+#[must_use]
+fn f() -> i32 { 0 }
+fn main() {
+    drop(f());
+}
+"#,
+            ],
+        );
+    }
+}
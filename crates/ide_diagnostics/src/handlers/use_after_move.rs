@@ -0,0 +1,172 @@
+use crate::{Diagnostic, DiagnosticsContext};
+
+// Diagnostic: use-after-move
+//
+// This diagnostic is triggered if a local variable is used after its value was
+// moved out of it. The check is deliberately conservative and only looks at
+// moves which happen unconditionally within the same function.
+pub(crate) fn use_after_move(ctx: &DiagnosticsContext<'_>, d: &hir::UseAfterMove) -> Diagnostic {
+    let moved_at = ctx.sema.diagnostics_display_range(d.moved_at.clone().map(|it| it.into()));
+    Diagnostic::new(
+        "use-after-move",
+        format!("use of moved value: `{}`", d.name),
+        ctx.sema.diagnostics_display_range(d.expr.clone().map(|it| it.into())).range,
+    )
+    .with_related(moved_at, "value moved here")
+    .experimental()
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+
+    use crate::tests::{check_diagnostics, check_expect};
+
+    #[test]
+    fn use_after_move() {
+        check_diagnostics(
+            r#"
+//- minicore: copy
+struct S;
+impl S {
+    fn by_ref(&self) {}
+    fn by_value(self) {}
+}
+fn consume(_: S) {}
+
+fn f() {
+    let s = S;
+    consume(s);
+    s.by_ref();
+  //^ error: use of moved value: `s`
+
+    let t = S;
+    t.by_value();
+    let u = t;
+          //^ error: use of moved value: `t`
+
+    let v = S;
+    let w = (v, 0);
+    consume(v);
+          //^ error: use of moved value: `v`
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn no_diagnostic_without_move() {
+        check_diagnostics(
+            r#"
+//- minicore: copy, derive
+#[derive(Clone, Copy)]
+struct C;
+struct S;
+impl S {
+    fn by_ref(&self) {}
+}
+fn consume<T>(_: T) {}
+
+fn f() {
+    let c = C;
+    consume(c);
+    consume(c);
+
+    let s = S;
+    s.by_ref();
+    consume(&s);
+    consume(s);
+
+    let mut s = S;
+    consume(s);
+    s = S;
+    consume(s);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn conditional_moves() {
+        check_diagnostics(
+            r#"
+//- minicore: copy
+struct S;
+fn consume(_: S) {}
+
+fn f(c: bool) {
+    let mut s = S;
+    if c {
+        consume(s);
+        return;
+    }
+    consume(s);
+
+    let t = S;
+    consume(t);
+    if c {
+        consume(t);
+              //^ error: use of moved value: `t`
+    }
+
+    let mut u = S;
+    consume(u);
+    if c {
+        u = S;
+    } else {
+        u = S;
+    }
+    consume(u);
+
+    loop {
+        let w = S;
+        consume(w);
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn points_at_moving_expression() {
+        check_expect(
+            r#"
+//- minicore: copy
+struct S;
+fn consume(_: S) {}
+fn f() {
+    let s = S;
+    consume(s);
+    consume(s);
+}
+"#,
+            expect![[r#"
+                [
+                    Diagnostic {
+                        code: DiagnosticCode(
+                            "use-after-move",
+                        ),
+                        message: "use of moved value: `s`",
+                        range: 82..83,
+                        severity: Error,
+                        unused: false,
+                        deprecated: false,
+                        experimental: true,
+                        fixes: None,
+                        related: [
+                            (
+                                FileRange {
+                                    file_id: FileId(
+                                        0,
+                                    ),
+                                    range: 66..67,
+                                },
+                                "value moved here",
+                            ),
+                        ],
+                    },
+                ]
+            "#]],
+        );
+    }
+}
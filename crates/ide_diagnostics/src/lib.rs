@@ -35,7 +35,10 @@ mod handlers {
     pub(crate) mod missing_match_arms;
     pub(crate) mod missing_unsafe;
     pub(crate) mod no_such_field;
+    pub(crate) mod orphan_impl;
+    pub(crate) mod overlapping_impls;
     pub(crate) mod replace_filter_map_next_with_find_map;
+    pub(crate) mod trait_solver_overflow;
     pub(crate) mod type_mismatch;
     pub(crate) mod unimplemented_builtin_macro;
     pub(crate) mod unresolved_extern_crate;
@@ -43,26 +46,49 @@ mod handlers {
     pub(crate) mod unresolved_macro_call;
     pub(crate) mod unresolved_module;
     pub(crate) mod unresolved_proc_macro;
+    pub(crate) mod use_after_move;
 
     // The handlers below are unusual, the implement the diagnostics as well.
+    pub(crate) mod always_true_assert;
+    pub(crate) mod blocking_call_in_async;
+    pub(crate) mod deprecated;
+    pub(crate) mod disjoint_capture;
     pub(crate) mod field_shorthand;
+    pub(crate) mod format_args;
+    pub(crate) mod improper_ctypes;
+    pub(crate) mod mismatched_generic_arg_count;
+    pub(crate) mod multiple_applicable_items;
+    pub(crate) mod slow_inference;
+    pub(crate) mod typed_hole;
+    pub(crate) mod unreachable_branch;
+    pub(crate) mod unused_asm_operand;
+    pub(crate) mod unused_must_use;
     pub(crate) mod useless_braces;
     pub(crate) mod unlinked_file;
 }
 
+mod lint_levels;
+
+pub use crate::handlers::blocking_call_in_async::DEFAULT_BLOCKING_FUNCTIONS;
+
 #[cfg(test)]
 mod tests;
 
-use hir::{diagnostics::AnyDiagnostic, Semantics};
+use std::time::Duration;
+
+use hir::{diagnostics::AnyDiagnostic, HirFileId, InFile, Semantics};
 use ide_db::{
     assists::{Assist, AssistId, AssistKind, AssistResolveStrategy},
-    base_db::{FileId, SourceDatabase},
+    base_db::{FileId, FileRange, SourceDatabase},
     label::Label,
     source_change::SourceChange,
     RootDatabase,
 };
 use rustc_hash::FxHashSet;
-use syntax::{ast::AstNode, TextRange};
+use syntax::{
+    ast::{self, AstNode},
+    SyntaxNode, TextRange,
+};
 
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub struct DiagnosticCode(pub &'static str);
@@ -80,8 +106,11 @@ pub struct Diagnostic {
     pub range: TextRange,
     pub severity: Severity,
     pub unused: bool,
+    pub deprecated: bool,
     pub experimental: bool,
     pub fixes: Option<Vec<Assist>>,
+    /// Secondary locations explaining the diagnostic, like where a value was moved.
+    pub related: Vec<(FileRange, String)>,
 }
 
 impl Diagnostic {
@@ -93,8 +122,10 @@ impl Diagnostic {
             range,
             severity: Severity::Error,
             unused: false,
+            deprecated: false,
             experimental: false,
             fixes: None,
+            related: Vec::new(),
         }
     }
 
@@ -113,17 +144,27 @@ impl Diagnostic {
         self
     }
 
+    fn with_related(mut self, range: FileRange, message: impl Into<String>) -> Diagnostic {
+        self.related.push((range, message.into()));
+        self
+    }
+
     fn with_unused(mut self, unused: bool) -> Diagnostic {
         self.unused = unused;
         self
     }
+
+    fn with_deprecated(mut self, deprecated: bool) -> Diagnostic {
+        self.deprecated = deprecated;
+        self
+    }
 }
 
 #[derive(Debug, Copy, Clone)]
 pub enum Severity {
     Error,
-    // We don't actually emit this one yet, but we should at some point.
-    // Warning,
+    /// Only emitted when the user asks for it with `#[warn(...)]`.
+    Warning,
     WeakWarning,
 }
 
@@ -138,11 +179,31 @@ impl Default for ExprFillDefaultMode {
     }
 }
 
-#[derive(Default, Debug, Clone)]
+#[derive(Debug, Clone)]
 pub struct DiagnosticsConfig {
     pub disable_experimental: bool,
     pub disabled: FxHashSet<String>,
     pub expr_fill_default: ExprFillDefaultMode,
+    /// Paths of the functions which must not be called in async code, like `std::thread::sleep`.
+    pub blocking_functions: Vec<String>,
+    /// How long type inference of a body can take before it is reported as slow, when inference
+    /// profiling is enabled.
+    pub slow_inference_threshold: Duration,
+}
+
+impl Default for DiagnosticsConfig {
+    fn default() -> Self {
+        DiagnosticsConfig {
+            disable_experimental: false,
+            disabled: FxHashSet::default(),
+            expr_fill_default: ExprFillDefaultMode::default(),
+            blocking_functions: DEFAULT_BLOCKING_FUNCTIONS
+                .iter()
+                .map(|it| it.to_string())
+                .collect(),
+            slow_inference_threshold: Duration::from_secs(1),
+        }
+    }
 }
 
 struct DiagnosticsContext<'a> {
@@ -180,13 +241,37 @@ pub fn diagnostics(
     if module.is_none() {
         handlers::unlinked_file::unlinked_file(&ctx, &mut res, file_id);
     }
+    handlers::blocking_call_in_async::blocking_call_in_async(&ctx, &mut res, file_id);
+
+    for node in parse.tree().syntax().descendants() {
+        handlers::mismatched_generic_arg_count::mismatched_generic_arg_count(
+            &ctx, &mut res, file_id, &node,
+        );
+        handlers::unused_must_use::unused_must_use(&ctx, &mut res, file_id, &node);
+        handlers::multiple_applicable_items::multiple_applicable_items(
+            &ctx, &mut res, file_id, &node,
+        );
+        handlers::deprecated::deprecated(&ctx, &mut res, &node);
+        handlers::unreachable_branch::unreachable_branch(&ctx, &mut res, &node);
+        handlers::always_true_assert::always_true_assert(&ctx, &mut res, &node);
+        handlers::format_args::format_args(&ctx, &mut res, &node);
+        handlers::unused_asm_operand::unused_asm_operand(&ctx, &mut res, &node);
+        handlers::improper_ctypes::improper_ctypes(&ctx, &mut res, &node);
+        handlers::typed_hole::typed_hole(&ctx, &mut res, file_id, &node);
+        handlers::disjoint_capture::disjoint_capture(&ctx, &mut res, file_id, &node);
+    }
 
     let mut diags = Vec::new();
     if let Some(m) = module {
         m.diagnostics(db, &mut diags)
     }
+    // Bodies have all been inferred by now, so their timings are known.
+    for node in parse.tree().syntax().descendants() {
+        handlers::slow_inference::slow_inference(&ctx, &mut res, &node);
+    }
 
     for diag in diags {
+        let backtrace = expansion_backtrace(&ctx.sema, diag.file_id());
         #[rustfmt::skip]
         let d = match diag {
             AnyDiagnostic::BreakOutsideOfLoop(d) => handlers::break_outside_of_loop::break_outside_of_loop(&ctx, &d),
@@ -198,7 +283,10 @@ pub fn diagnostics(
             AnyDiagnostic::MissingMatchArms(d) => handlers::missing_match_arms::missing_match_arms(&ctx, &d),
             AnyDiagnostic::MissingUnsafe(d) => handlers::missing_unsafe::missing_unsafe(&ctx, &d),
             AnyDiagnostic::NoSuchField(d) => handlers::no_such_field::no_such_field(&ctx, &d),
+            AnyDiagnostic::OrphanImpl(d) => handlers::orphan_impl::orphan_impl(&ctx, &d),
+            AnyDiagnostic::OverlappingImpls(d) => handlers::overlapping_impls::overlapping_impls(&ctx, &d),
             AnyDiagnostic::ReplaceFilterMapNextWithFindMap(d) => handlers::replace_filter_map_next_with_find_map::replace_filter_map_next_with_find_map(&ctx, &d),
+            AnyDiagnostic::TraitSolverOverflow(d) => handlers::trait_solver_overflow::trait_solver_overflow(&ctx, &d),
             AnyDiagnostic::TypeMismatch(d) => handlers::type_mismatch::type_mismatch(&ctx, &d),
             AnyDiagnostic::UnimplementedBuiltinMacro(d) => handlers::unimplemented_builtin_macro::unimplemented_builtin_macro(&ctx, &d),
             AnyDiagnostic::UnresolvedExternCrate(d) => handlers::unresolved_extern_crate::unresolved_extern_crate(&ctx, &d),
@@ -206,6 +294,7 @@ pub fn diagnostics(
             AnyDiagnostic::UnresolvedMacroCall(d) => handlers::unresolved_macro_call::unresolved_macro_call(&ctx, &d),
             AnyDiagnostic::UnresolvedModule(d) => handlers::unresolved_module::unresolved_module(&ctx, &d),
             AnyDiagnostic::UnresolvedProcMacro(d) => handlers::unresolved_proc_macro::unresolved_proc_macro(&ctx, &d),
+            AnyDiagnostic::UseAfterMove(d) => handlers::use_after_move::use_after_move(&ctx, &d),
             AnyDiagnostic::InvalidDeriveTarget(d) => handlers::invalid_derive_target::invalid_derive_target(&ctx, &d),
 
             AnyDiagnostic::InactiveCode(d) => match handlers::inactive_code::inactive_code(&ctx, &d) {
@@ -213,9 +302,10 @@ pub fn diagnostics(
                 None => continue,
             }
         };
-        res.push(d)
+        res.push(backtrace.into_iter().fold(d, |d, (range, label)| d.with_related(range, label)))
     }
 
+    res = lint_levels::apply(&ctx.sema, file_id, module, res);
     res.retain(|d| {
         !ctx.config.disabled.contains(d.code.as_str())
             && !(ctx.config.disable_experimental && d.experimental)
@@ -224,6 +314,54 @@ pub fn diagnostics(
     res
 }
 
+/// The macro calls `file_id` was expanded from, innermost first, like the "in this macro
+/// invocation" notes of rustc.
+fn expansion_backtrace(
+    sema: &Semantics<RootDatabase>,
+    mut file_id: HirFileId,
+) -> Vec<(FileRange, String)> {
+    let mut frames: Vec<(FileRange, String)> = Vec::new();
+    while let Some(call) = file_id.call_node(sema.db) {
+        let label = if let Some(macro_call) = ast::MacroCall::cast(call.value.clone()) {
+            match macro_call.path() {
+                Some(path) => format!("in this expansion of `{}!`", path),
+                None => "in this macro expansion".to_string(),
+            }
+        } else if ast::Attr::can_cast(call.value.kind()) {
+            // Derives are the only expansions whose call is an attribute.
+            "in this derive".to_string()
+        } else {
+            "in this attribute macro expansion".to_string()
+        };
+        let range = written_range(sema.db, call.as_ref())
+            .unwrap_or_else(|| call.as_ref().original_file_range(sema.db));
+        // Calls made by a macro can fall back to the range of its own call.
+        if frames.last().map_or(true, |(last, _)| *last != range) {
+            frames.push((range, label));
+        }
+        file_id = call.file_id;
+    }
+    frames
+}
+
+/// Where the text of `node` was written, which is in the definition of a `macro_rules!` for
+/// calls made by another macro.
+fn written_range(db: &RootDatabase, node: InFile<&SyntaxNode>) -> Option<FileRange> {
+    let mut first = InFile::new(node.file_id, node.value.first_token()?);
+    let mut last = InFile::new(node.file_id, node.value.last_token()?);
+    while first.file_id.is_macro() {
+        first = first.upmap(db)?;
+    }
+    while last.file_id.is_macro() {
+        last = last.upmap(db)?;
+    }
+    if first.file_id != last.file_id {
+        return None;
+    }
+    let range = first.value.text_range().cover(last.value.text_range());
+    Some(FileRange { file_id: first.file_id.original_file(db), range })
+}
+
 fn fix(id: &'static str, label: &str, source_change: SourceChange, target: TextRange) -> Assist {
     let mut res = unresolved_fix(id, label, target);
     res.source_change = Some(source_change);
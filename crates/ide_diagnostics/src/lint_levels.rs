@@ -0,0 +1,136 @@
+//! Lint level attributes for our own diagnostics.
+//!
+//! Every diagnostic can be named as `rust_analyzer::<code>`, with dashes
+//! replaced by underscores, so `#[allow(rust_analyzer::unresolved_macro_call)]`
+//! silences that diagnostic for the annotated item. Diagnostics which mirror a
+//! rustc or clippy lint also respond to that lint and its groups, so existing
+//! `#![allow(non_snake_case)]`s keep working.
+//!
+//! The innermost attribute wins: we first look at the syntactic ancestors of
+//! the diagnostic, then at the enclosing modules up to the crate root, which
+//! includes `cfg_attr`-expanded attributes and `#[allow]`s on `mod foo;`.
+
+use hir::{HasAttrs, Module, Semantics};
+use ide_db::{base_db::FileId, RootDatabase};
+use syntax::{
+    ast::{self, HasAttrs as _},
+    AstNode,
+};
+
+use crate::{Diagnostic, Severity};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LintLevel {
+    Allow,
+    Warn,
+    Deny,
+}
+
+impl LintLevel {
+    fn from_attr(name: &str) -> Option<LintLevel> {
+        match name {
+            "allow" | "expect" => Some(LintLevel::Allow),
+            "warn" => Some(LintLevel::Warn),
+            "deny" | "forbid" => Some(LintLevel::Deny),
+            _ => None,
+        }
+    }
+}
+
+/// Drops allowed diagnostics and adjusts the severity of the ones explicitly
+/// warned about or denied.
+pub(crate) fn apply(
+    sema: &Semantics<RootDatabase>,
+    file_id: FileId,
+    module: Option<Module>,
+    diagnostics: Vec<Diagnostic>,
+) -> Vec<Diagnostic> {
+    let module_levels = module.map(|it| module_levels(sema.db, it)).unwrap_or_default();
+    let root = sema.db.parse(file_id).tree();
+    diagnostics
+        .into_iter()
+        .filter_map(|mut diagnostic| {
+            let names = lint_names(&diagnostic);
+            let matches = |lints: &[String]| lints.iter().any(|lint| names.contains(lint));
+
+            let syntactic = if root.syntax().text_range().contains_range(diagnostic.range) {
+                root.syntax().covering_element(diagnostic.range).ancestors().find_map(|node| {
+                    let owner = ast::AnyHasAttrs::cast(node)?;
+                    owner
+                        .attrs()
+                        .filter_map(|attr| {
+                            let (name, tt) = attr.as_simple_call()?;
+                            let level = LintLevel::from_attr(&name)?;
+                            matches(&parse_lints(&tt.syntax().text().to_string())).then(|| level)
+                        })
+                        .last()
+                })
+            } else {
+                None
+            };
+            let level = syntactic.or_else(|| {
+                module_levels.iter().find_map(|(level, lints)| matches(lints).then(|| *level))
+            });
+
+            match level {
+                Some(LintLevel::Allow) => return None,
+                Some(LintLevel::Warn) => diagnostic.severity = Severity::Warning,
+                Some(LintLevel::Deny) => diagnostic.severity = Severity::Error,
+                None => (),
+            }
+            Some(diagnostic)
+        })
+        .collect()
+}
+
+/// Lint attributes of `module` and its ancestors, innermost module first and,
+/// within a module, the last attribute first.
+fn module_levels(db: &RootDatabase, module: Module) -> Vec<(LintLevel, Vec<String>)> {
+    let mut res = Vec::new();
+    for module in std::iter::successors(Some(module), |it| it.parent(db)) {
+        let attrs = module.attrs(db);
+        let levels = attrs.iter().rev().filter_map(|attr| {
+            let level = LintLevel::from_attr(&attr.path().as_ident()?.to_smol_str())?;
+            Some((level, parse_lints(&attr.token_tree_value()?.to_string())))
+        });
+        res.extend(levels);
+    }
+    res
+}
+
+/// Splits `(clippy :: foo, bar)` into `["clippy::foo", "bar"]`.
+fn parse_lints(tt: &str) -> Vec<String> {
+    tt.trim_start_matches('(')
+        .trim_end_matches(')')
+        .split(',')
+        .map(|lint| lint.split_whitespace().collect::<String>())
+        .filter(|lint| !lint.is_empty())
+        .collect()
+}
+
+/// All the lint names `diagnostic` can be referred to by.
+fn lint_names(diagnostic: &Diagnostic) -> Vec<String> {
+    let code = diagnostic.code.as_str();
+    let mut res = vec![format!("rust_analyzer::{}", code.replace('-', "_"))];
+    let rustc_lints: &[&str] = match code {
+        "incorrect-ident-case" => &[
+            "non_snake_case",
+            "non_camel_case_types",
+            "non_upper_case_globals",
+            "nonstandard_style",
+        ],
+        "unnecessary-braces" => &["unused_import_braces", "unused"],
+        "unused-must-use" => &["unused_must_use", "unused"],
+        "deprecated" => &["deprecated"],
+        "improper-ctypes" => &["improper_ctypes"],
+        "improper-ctypes-definitions" => &["improper_ctypes_definitions"],
+        "use-field-shorthand" => &["clippy::redundant_field_names", "clippy::style", "clippy::all"],
+        "replace-filter-map-next-with-find-map" => &["clippy::filter_map_next", "clippy::pedantic"],
+        _ => &[],
+    };
+    res.extend(rustc_lints.iter().map(|it| it.to_string()));
+    if !rustc_lints.is_empty() {
+        res.push("warnings".to_string());
+    }
+    res
+}
@@ -112,6 +112,7 @@ pub(crate) fn check_diagnostics_with_config(config: DiagnosticsConfig, ra_fixtur
                 }
                 annotation.push_str(match d.severity {
                     Severity::Error => "error",
+                    Severity::Warning => "warn",
                     Severity::WeakWarning => "weak",
                 });
                 annotation.push_str(": ");
@@ -142,3 +143,77 @@ fn test_disabled_diagnostics() {
     );
     assert!(!diagnostics.is_empty());
 }
+
+#[test]
+fn macro_expansion_backtrace() {
+    let (db, file_id) = RootDatabase::with_single_file(
+        r#"
+unsafe fn unsafe_fn() {}
+macro_rules! inner {
+    () => { unsafe_fn() };
+}
+macro_rules! outer {
+    () => { inner!() };
+}
+fn main() {
+    outer!();
+}
+"#,
+    );
+    let diagnostics = super::diagnostics(
+        &db,
+        &DiagnosticsConfig::default(),
+        &AssistResolveStrategy::All,
+        file_id,
+    );
+    let text = db.file_text(file_id);
+    let related: Vec<_> = diagnostics
+        .iter()
+        .flat_map(|d| &d.related)
+        .map(|(range, label)| (&text[range.range], label.as_str()))
+        .collect();
+    assert_eq!(
+        related,
+        [
+            ("inner!()", "in this expansion of `inner!`"),
+            ("outer!()", "in this expansion of `outer!`")
+        ]
+    );
+}
+
+#[test]
+fn lint_attributes() {
+    check_diagnostics(
+        r#"
+//- /lib.rs
+#![allow(rust_analyzer::unresolved_module)]
+#![warn(unused)]
+
+mod missing;
+mod foo;
+mod bar;
+
+use a::{c};
+     //^^^ 💡 warn: Unnecessary braces in use statement
+
+#[allow(unused_import_braces)]
+use a::{d};
+
+mod a {
+    pub mod c {}
+    pub mod d {}
+}
+//- /foo.rs
+#[expect(clippy::redundant_field_names)]
+fn f() {
+    let x = 92;
+    S { x: x };
+}
+struct S { x: i32 }
+//- /bar.rs
+#![deny(warnings)]
+use crate::a::{c};
+            //^^^ 💡 error: Unnecessary braces in use statement
+"#,
+    );
+}
@@ -69,6 +69,15 @@ pub struct SyntheticToken {
 pub fn token_tree_to_syntax_node(
     tt: &tt::Subtree,
     entry_point: parser::TopEntryPoint,
+) -> (Parse<SyntaxNode>, TokenMap) {
+    token_tree_to_syntax_node_with_edition(tt, entry_point, parser::Edition::CURRENT)
+}
+
+/// Like [`token_tree_to_syntax_node`], treating only the keywords of `edition` as keywords.
+pub fn token_tree_to_syntax_node_with_edition(
+    tt: &tt::Subtree,
+    entry_point: parser::TopEntryPoint,
+    edition: parser::Edition,
 ) -> (Parse<SyntaxNode>, TokenMap) {
     let buffer = match tt {
         tt::Subtree { delimiter: None, token_trees } => {
@@ -76,7 +85,7 @@ pub fn token_tree_to_syntax_node(
         }
         _ => TokenBuffer::from_subtree(tt),
     };
-    let parser_input = to_parser_input(&buffer);
+    let parser_input = to_parser_input(&buffer, edition);
     let parser_output = entry_point.parse(&parser_input);
     let mut tree_sink = TtTreeSink::new(buffer.begin());
     for event in parser_output.iter() {
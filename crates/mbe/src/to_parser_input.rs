@@ -4,7 +4,7 @@
 use syntax::{SyntaxKind, SyntaxKind::*, T};
 use tt::buffer::TokenBuffer;
 
-pub(crate) fn to_parser_input(buffer: &TokenBuffer) -> parser::Input {
+pub(crate) fn to_parser_input(buffer: &TokenBuffer, edition: parser::Edition) -> parser::Input {
     let mut res = parser::Input::default();
 
     let mut current = buffer.begin();
@@ -48,12 +48,15 @@ pub(crate) fn to_parser_input(buffer: &TokenBuffer) -> parser::Input {
                     tt::Leaf::Ident(ident) => match ident.text.as_ref() {
                         "_" => res.push(T![_]),
                         i if i.starts_with('\'') => res.push(LIFETIME_IDENT),
-                        _ => match SyntaxKind::from_keyword(&ident.text) {
+                        _ => match SyntaxKind::from_keyword_in_edition(&ident.text, edition) {
                             Some(kind) => res.push(kind),
                             None => {
                                 let contextual_keyword =
-                                    SyntaxKind::from_contextual_keyword(&ident.text)
-                                        .unwrap_or(SyntaxKind::IDENT);
+                                    SyntaxKind::from_contextual_keyword_in_edition(
+                                        &ident.text,
+                                        edition,
+                                    )
+                                    .unwrap_or(SyntaxKind::IDENT);
                                 res.push_ident(contextual_keyword);
                             }
                         },
@@ -85,7 +85,7 @@ impl<'a> TtIter<'a> {
         entry_point: parser::PrefixEntryPoint,
     ) -> ExpandResult<Option<tt::TokenTree>> {
         let buffer = TokenBuffer::from_tokens(self.inner.as_slice());
-        let parser_input = to_parser_input(&buffer);
+        let parser_input = to_parser_input(&buffer, parser::Edition::CURRENT);
         let tree_traversal = entry_point.parse(&parser_input);
 
         let mut cursor = buffer.begin();
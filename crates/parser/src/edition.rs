@@ -0,0 +1,43 @@
+//! The edition of the Rust language, which determines the set of keywords.
+
+use crate::SyntaxKind::{self, *};
+
+/// The edition of the Rust language used to lex and parse a file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Edition {
+    Edition2015,
+    Edition2018,
+    Edition2021,
+}
+
+impl Edition {
+    /// The edition of files not belonging to any crate, like `base_db::Edition::CURRENT`.
+    pub const CURRENT: Edition = Edition::Edition2018;
+}
+
+impl Default for Edition {
+    fn default() -> Edition {
+        Edition::CURRENT
+    }
+}
+
+impl SyntaxKind {
+    /// Like [`SyntaxKind::from_keyword`], but only returns keywords that are
+    /// strict keywords in the given `edition`.
+    pub fn from_keyword_in_edition(ident: &str, edition: Edition) -> Option<SyntaxKind> {
+        let kw = SyntaxKind::from_keyword(ident)?;
+        match kw {
+            ASYNC_KW | AWAIT_KW | DYN_KW | TRY_KW if edition < Edition::Edition2018 => None,
+            _ => Some(kw),
+        }
+    }
+
+    /// Like [`SyntaxKind::from_contextual_keyword`], but also returns keywords
+    /// that are only weak keywords in the given `edition`, like `dyn` in 2015.
+    pub fn from_contextual_keyword_in_edition(ident: &str, edition: Edition) -> Option<SyntaxKind> {
+        match ident {
+            "dyn" if edition < Edition::Edition2018 => Some(DYN_KW),
+            _ => SyntaxKind::from_contextual_keyword(ident),
+        }
+    }
+}
@@ -45,6 +45,13 @@ fn type_with_bounds_cond(p: &mut Parser, allow_bounds: bool) {
         T![for] => for_type(p, allow_bounds),
         T![impl] => impl_trait_type(p),
         T![dyn] => dyn_trait_type(p),
+        // `dyn` is only a weak keyword in the 2015 edition.
+        IDENT
+            if p.at_contextual_kw(T![dyn])
+                && matches!(p.nth(1), IDENT | LIFETIME_IDENT | T![?] | T![for] | T!['(']) =>
+        {
+            dyn_trait_type(p)
+        }
         // Some path types are not allowed to have bounds (no plus)
         T![<] => path_type_(p, allow_bounds),
         _ if paths::is_path_start(p) => path_or_macro_type_(p, allow_bounds),
@@ -261,9 +268,9 @@ fn impl_trait_type(p: &mut Parser) {
 // test dyn_trait_type
 // type A = dyn Iterator<Item=Foo<'a>> + 'a;
 fn dyn_trait_type(p: &mut Parser) {
-    assert!(p.at(T![dyn]));
+    assert!(p.at(T![dyn]) || p.at_contextual_kw(T![dyn]));
     let m = p.start();
-    p.bump(T![dyn]);
+    p.bump_remap(T![dyn]);
     generic_params::bounds_without_colon(p);
     m.complete(p, DYN_TRAIT_TYPE);
 }
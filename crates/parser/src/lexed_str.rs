@@ -11,12 +11,14 @@
 use std::ops;
 
 use crate::{
+    Edition,
     SyntaxKind::{self, *},
     T,
 };
 
 pub struct LexedStr<'a> {
     text: &'a str,
+    edition: Edition,
     kind: Vec<SyntaxKind>,
     start: Vec<u32>,
     error: Vec<LexError>,
@@ -29,7 +31,13 @@ struct LexError {
 
 impl<'a> LexedStr<'a> {
     pub fn new(text: &'a str) -> LexedStr<'a> {
-        let mut res = LexedStr { text, kind: Vec::new(), start: Vec::new(), error: Vec::new() };
+        LexedStr::new_with_edition(text, Edition::CURRENT)
+    }
+
+    /// Lexes `text`, treating only the keywords of `edition` as keywords.
+    pub fn new_with_edition(text: &'a str, edition: Edition) -> LexedStr<'a> {
+        let mut res =
+            LexedStr { text, edition, kind: Vec::new(), start: Vec::new(), error: Vec::new() };
 
         let mut offset = 0;
         if let Some(shebang_len) = rustc_lexer::strip_shebang(text) {
@@ -39,7 +47,7 @@ impl<'a> LexedStr<'a> {
         for token in rustc_lexer::tokenize(&text[offset..]) {
             let token_text = &text[offset..][..token.len];
 
-            let (kind, err) = from_rustc(&token.kind, token_text);
+            let (kind, err) = from_rustc(&token.kind, token_text, edition);
             res.push(kind, offset);
             offset += token.len;
 
@@ -55,6 +63,14 @@ impl<'a> LexedStr<'a> {
     }
 
     pub fn single_token(text: &'a str) -> Option<(SyntaxKind, Option<String>)> {
+        LexedStr::single_token_with_edition(text, Edition::CURRENT)
+    }
+
+    /// Like [`LexedStr::single_token`], treating only the keywords of `edition` as keywords.
+    pub fn single_token_with_edition(
+        text: &'a str,
+        edition: Edition,
+    ) -> Option<(SyntaxKind, Option<String>)> {
         if text.is_empty() {
             return None;
         }
@@ -64,7 +80,7 @@ impl<'a> LexedStr<'a> {
             return None;
         }
 
-        let (kind, err) = from_rustc(&token.kind, text);
+        let (kind, err) = from_rustc(&token.kind, text, edition);
         Some((kind, err.map(|it| it.to_owned())))
     }
 
@@ -72,6 +88,10 @@ impl<'a> LexedStr<'a> {
         self.text
     }
 
+    pub fn edition(&self) -> Edition {
+        self.edition
+    }
+
     pub fn len(&self) -> usize {
         self.kind.len() - 1
     }
@@ -132,6 +152,7 @@ impl<'a> LexedStr<'a> {
 fn from_rustc(
     kind: &rustc_lexer::TokenKind,
     token_text: &str,
+    edition: Edition,
 ) -> (SyntaxKind, Option<&'static str>) {
     // A note on an intended tradeoff:
     // We drop some useful information here (see patterns with double dots `..`)
@@ -152,7 +173,9 @@ fn from_rustc(
             rustc_lexer::TokenKind::Whitespace => WHITESPACE,
 
             rustc_lexer::TokenKind::Ident if token_text == "_" => UNDERSCORE,
-            rustc_lexer::TokenKind::Ident => SyntaxKind::from_keyword(token_text).unwrap_or(IDENT),
+            rustc_lexer::TokenKind::Ident => {
+                SyntaxKind::from_keyword_in_edition(token_text, edition).unwrap_or(IDENT)
+            }
 
             rustc_lexer::TokenKind::RawIdent => IDENT,
             rustc_lexer::TokenKind::Literal { kind, .. } => return from_rustc_literal(kind),
@@ -35,8 +35,9 @@ impl<'a> LexedStr<'a> {
             } else {
                 if kind == SyntaxKind::IDENT {
                     let token_text = self.text(i);
-                    let contextual_kw = SyntaxKind::from_contextual_keyword(token_text)
-                        .unwrap_or(SyntaxKind::IDENT);
+                    let contextual_kw =
+                        SyntaxKind::from_contextual_keyword_in_edition(token_text, self.edition())
+                            .unwrap_or(SyntaxKind::IDENT);
                     res.push_ident(contextual_kw);
                 } else {
                     if was_joint {
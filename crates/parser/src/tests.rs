@@ -10,7 +10,7 @@ use std::{
 
 use expect_test::expect_file;
 
-use crate::{LexedStr, TopEntryPoint};
+use crate::{Edition, LexedStr, SyntaxKind, TopEntryPoint};
 
 #[test]
 fn lex_ok() {
@@ -79,8 +79,33 @@ fn parse_inline_err() {
     }
 }
 
+#[test]
+fn edition_dependent_keywords() {
+    let kinds = |text, edition| {
+        let lexed = LexedStr::new_with_edition(text, edition);
+        (0..lexed.len()).map(|i| lexed.kind(i)).collect::<Vec<_>>()
+    };
+    assert_eq!(kinds("async", Edition::Edition2015), [SyntaxKind::IDENT]);
+    assert_eq!(kinds("async", Edition::Edition2018), [SyntaxKind::ASYNC_KW]);
+    assert_eq!(kinds("try", Edition::Edition2015), [SyntaxKind::IDENT]);
+    assert_eq!(kinds("dyn", Edition::Edition2021), [SyntaxKind::DYN_KW]);
+
+    // `dyn` is a weak keyword in 2015, so both of these are fine.
+    let (actual, errors) = parse_with_edition(
+        TopEntryPoint::SourceFile,
+        "fn dyn(x: Box<dyn Tr>) {}",
+        Edition::Edition2015,
+    );
+    assert!(!errors, "{}", actual);
+    assert!(actual.contains("DYN_TRAIT_TYPE"), "{}", actual);
+}
+
 fn parse(entry: TopEntryPoint, text: &str) -> (String, bool) {
-    let lexed = LexedStr::new(text);
+    parse_with_edition(entry, text, Edition::CURRENT)
+}
+
+fn parse_with_edition(entry: TopEntryPoint, text: &str, edition: Edition) -> (String, bool) {
+    let lexed = LexedStr::new_with_edition(text, edition);
     let input = lexed.to_input();
     let output = entry.parse(&input);
 
@@ -11,7 +11,8 @@ mod version;
 
 use paths::AbsPathBuf;
 use std::{
-    ffi::OsStr,
+    collections::HashMap,
+    ffi::{OsStr, OsString},
     fmt, io,
     sync::{Arc, Mutex},
 };
@@ -24,7 +25,7 @@ use crate::{
     process::ProcMacroProcessSrv,
 };
 
-pub use version::{read_dylib_info, RustCInfo};
+pub use version::{read_dylib_info, ProcMacroAbi, RustCInfo};
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
 pub enum ProcMacroKind {
@@ -33,21 +34,64 @@ pub enum ProcMacroKind {
     Attr,
 }
 
-/// A handle to an external process which load dylibs with macros (.so or .dll)
-/// and runs actual macro expansion functions.
+/// A handle to the external processes which load dylibs with macros (.so or .dll)
+/// and run actual macro expansion functions.
+///
+/// Every process can expand macros of all the ABIs it supports, but dylibs of
+/// different ABIs are loaded into separate ones, up to a few of them, so
+/// that a crash in one ABI doesn't take down the macros of the others.
 #[derive(Debug)]
 pub struct ProcMacroServer {
-    /// Currently, the proc macro process expands all procedural macros sequentially.
+    process_path: AbsPathBuf,
+    args: Vec<OsString>,
+    /// Currently, each proc macro process expands its procedural macros sequentially.
     ///
     /// That means that concurrent salsa requests may block each other when expanding proc macros,
     /// which is unfortunate, but simple and good enough for the time being.
     ///
     /// Therefore, we just wrap the `ProcMacroProcessSrv` in a mutex here.
-    process: Arc<Mutex<ProcMacroProcessSrv>>,
+    processes: Mutex<ProcessPool<Mutex<ProcMacroProcessSrv>>>,
+}
+
+const MAX_PROCESSES: usize = 3;
+
+/// The process serving each ABI.
+#[derive(Debug)]
+struct ProcessPool<P> {
+    by_abi: HashMap<ProcMacroAbi, Arc<P>>,
+    /// Spawned eagerly to report a broken server right away. It serves the
+    /// first ABI, and the ones past the limit of processes.
+    primary: Arc<P>,
+}
+
+impl<P> ProcessPool<P> {
+    fn new(primary: P) -> ProcessPool<P> {
+        ProcessPool { by_abi: HashMap::new(), primary: Arc::new(primary) }
+    }
+
+    fn get_or_spawn(
+        &mut self,
+        abi: ProcMacroAbi,
+        spawn: impl FnOnce() -> io::Result<P>,
+    ) -> io::Result<Arc<P>> {
+        if let Some(process) = self.by_abi.get(&abi) {
+            return Ok(process.clone());
+        }
+        let n_processes =
+            self.by_abi.values().filter(|it| !Arc::ptr_eq(it, &self.primary)).count() + 1;
+        let process = if self.by_abi.is_empty() || n_processes >= MAX_PROCESSES {
+            self.primary.clone()
+        } else {
+            Arc::new(spawn()?)
+        };
+        self.by_abi.insert(abi, process.clone());
+        Ok(process)
+    }
 }
 
 pub struct MacroDylib {
     path: AbsPathBuf,
+    abi: ProcMacroAbi,
 }
 
 impl MacroDylib {
@@ -57,19 +101,22 @@ impl MacroDylib {
         let _p = profile::span("MacroDylib::new");
 
         let info = version::read_dylib_info(&path)?;
-        if info.version.0 < 1 || info.version.1 < 47 {
-            let msg = format!("proc-macro {} built by {:#?} is not supported by Rust Analyzer, please update your rust version.", path.display(), info);
-            return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
-        }
+        let abi = match info.abi() {
+            Some(it) => it,
+            None => {
+                let msg = format!("proc-macro {} built by {:#?} is not supported by Rust Analyzer, please update your rust version.", path.display(), info);
+                return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+            }
+        };
 
-        Ok(MacroDylib { path })
+        Ok(MacroDylib { path, abi })
     }
 }
 
 /// A handle to a specific macro (a `#[proc_macro]` annotated function).
 ///
-/// It exists withing a context of a specific [`ProcMacroProcess`] -- currently
-/// we share a single expander process for all macros.
+/// It exists withing a context of a specific [`ProcMacroProcess`] -- the one
+/// serving the ABI of its dylib.
 #[derive(Debug, Clone)]
 pub struct ProcMacro {
     process: Arc<Mutex<ProcMacroProcessSrv>>,
@@ -110,12 +157,16 @@ pub struct MacroPanic {
 
 impl ProcMacroServer {
     /// Spawns an external process as the proc macro server and returns a client connected to it.
+    ///
+    /// More processes are spawned the same way when dylibs of other ABIs are loaded.
     pub fn spawn(
         process_path: AbsPathBuf,
         args: impl IntoIterator<Item = impl AsRef<OsStr>>,
     ) -> io::Result<ProcMacroServer> {
-        let process = ProcMacroProcessSrv::run(process_path, args)?;
-        Ok(ProcMacroServer { process: Arc::new(Mutex::new(process)) })
+        let args: Vec<OsString> = args.into_iter().map(|it| it.as_ref().into()).collect();
+        let process = ProcMacroProcessSrv::run(process_path.clone(), &args)?;
+        let processes = Mutex::new(ProcessPool::new(Mutex::new(process)));
+        Ok(ProcMacroServer { process_path, args, processes })
     }
 
     pub fn load_dylib(
@@ -123,14 +174,15 @@ impl ProcMacroServer {
         dylib: MacroDylib,
     ) -> Result<Result<Vec<ProcMacro>, String>, ServerError> {
         let _p = profile::span("ProcMacroClient::by_dylib_path");
+        let process = self.process_for(dylib.abi)?;
         let macros =
-            self.process.lock().unwrap_or_else(|e| e.into_inner()).find_proc_macros(&dylib.path)?;
+            process.lock().unwrap_or_else(|e| e.into_inner()).find_proc_macros(&dylib.path)?;
 
         let res = macros.map(|macros| {
             macros
                 .into_iter()
                 .map(|(name, kind)| ProcMacro {
-                    process: self.process.clone(),
+                    process: process.clone(),
                     name,
                     kind,
                     dylib_path: dylib.path.clone(),
@@ -140,6 +192,22 @@ impl ProcMacroServer {
 
         Ok(res)
     }
+
+    fn process_for(
+        &self,
+        abi: ProcMacroAbi,
+    ) -> Result<Arc<Mutex<ProcMacroProcessSrv>>, ServerError> {
+        let mut processes = self.processes.lock().unwrap_or_else(|e| e.into_inner());
+        processes
+            .get_or_spawn(abi, || {
+                tracing::info!("spawning a proc-macro server for {:?}", abi);
+                ProcMacroProcessSrv::run(self.process_path.clone(), &self.args).map(Mutex::new)
+            })
+            .map_err(|err| ServerError {
+                message: "failed to spawn proc-macro server".into(),
+                io: Some(err),
+            })
+    }
 }
 
 impl ProcMacro {
@@ -181,3 +249,62 @@ impl ProcMacro {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+
+    #[test]
+    fn process_pool_spawns_one_process_per_abi() {
+        let spawned = Cell::new(0);
+        let spawn = || {
+            spawned.set(spawned.get() + 1);
+            Ok(spawned.get())
+        };
+        let mut pool = ProcessPool::new(0);
+
+        let first = pool.get_or_spawn(ProcMacroAbi::Abi1_58, spawn).unwrap();
+        assert_eq!(*first, 0);
+        let second = pool.get_or_spawn(ProcMacroAbi::Abi1_56, spawn).unwrap();
+        assert_eq!(*second, 1);
+        assert!(Arc::ptr_eq(&first, &pool.get_or_spawn(ProcMacroAbi::Abi1_58, spawn).unwrap()));
+        assert!(Arc::ptr_eq(&second, &pool.get_or_spawn(ProcMacroAbi::Abi1_56, spawn).unwrap()));
+        assert_eq!(spawned.get(), 1);
+    }
+
+    #[test]
+    fn process_pool_is_bounded() {
+        let spawned = Cell::new(0usize);
+        let spawn = || {
+            spawned.set(spawned.get() + 1);
+            Ok(spawned.get())
+        };
+        let mut pool = ProcessPool::new(0);
+        let abis = [
+            ProcMacroAbi::Abi1_48,
+            ProcMacroAbi::Abi1_54,
+            ProcMacroAbi::Abi1_56,
+            ProcMacroAbi::Abi1_57,
+            ProcMacroAbi::Abi1_58,
+        ];
+        for abi in abis {
+            pool.get_or_spawn(abi, spawn).unwrap();
+        }
+
+        assert_eq!(spawned.get(), MAX_PROCESSES - 1);
+        let overflow = pool.get_or_spawn(ProcMacroAbi::Abi1_58, spawn).unwrap();
+        assert!(Arc::ptr_eq(&overflow, &pool.primary));
+    }
+
+    #[test]
+    fn abi_of_compiler_version() {
+        let info =
+            |version| RustCInfo { version, channel: String::new(), commit: None, date: None };
+        assert_eq!(info((1, 47, 0)).abi(), None);
+        assert_eq!(info((1, 53, 0)).abi(), Some(ProcMacroAbi::Abi1_48));
+        assert_eq!(info((1, 57, 1)).abi(), Some(ProcMacroAbi::Abi1_57));
+        assert_eq!(info((1, 62, 0)).abi(), Some(ProcMacroAbi::Abi1_58));
+    }
+}
@@ -18,6 +18,35 @@ pub struct RustCInfo {
     pub date: Option<String>,
 }
 
+/// The versions of the proc-macro bridge ABI the server implements, named
+/// after the first release using them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ProcMacroAbi {
+    Abi1_48,
+    Abi1_54,
+    Abi1_56,
+    Abi1_57,
+    Abi1_58,
+}
+
+impl RustCInfo {
+    /// The ABI the server uses for dylibs built by this compiler, `None` if
+    /// it is too old to be supported.
+    pub fn abi(&self) -> Option<ProcMacroAbi> {
+        // FIXME: this should use exclusive ranges when they're stable
+        // https://github.com/rust-lang/rust/issues/37854
+        let abi = match (self.version.0, self.version.1) {
+            (1, 48..=53) => ProcMacroAbi::Abi1_48,
+            (1, 54..=55) => ProcMacroAbi::Abi1_54,
+            (1, 56) => ProcMacroAbi::Abi1_56,
+            (1, 57) => ProcMacroAbi::Abi1_57,
+            (1, 58..) => ProcMacroAbi::Abi1_58,
+            _ => return None,
+        };
+        Some(abi)
+    }
+}
+
 /// Read rustc dylib information
 pub fn read_dylib_info(dylib_path: &AbsPath) -> io::Result<RustCInfo> {
     macro_rules! err {
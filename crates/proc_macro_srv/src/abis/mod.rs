@@ -37,7 +37,7 @@ pub(crate) use abi_1_56::Abi as Abi_1_56;
 pub(crate) use abi_1_57::Abi as Abi_1_57;
 pub(crate) use abi_1_58::Abi as Abi_1_58;
 use libloading::Library;
-use proc_macro_api::{ProcMacroKind, RustCInfo};
+use proc_macro_api::{ProcMacroAbi, ProcMacroKind, RustCInfo};
 
 pub struct PanicMessage {
     message: Option<String>,
@@ -72,30 +72,28 @@ impl Abi {
         symbol_name: String,
         info: RustCInfo,
     ) -> Result<Abi, LoadProcMacroDylibError> {
-        // FIXME: this should use exclusive ranges when they're stable
-        // https://github.com/rust-lang/rust/issues/37854
-        match (info.version.0, info.version.1) {
-            (1, 48..=53) => {
+        match info.abi() {
+            Some(ProcMacroAbi::Abi1_48) => {
                 let inner = unsafe { Abi_1_48::from_lib(lib, symbol_name) }?;
                 Ok(Abi::Abi1_48(inner))
             }
-            (1, 54..=55) => {
+            Some(ProcMacroAbi::Abi1_54) => {
                 let inner = unsafe { Abi_1_54::from_lib(lib, symbol_name) }?;
                 Ok(Abi::Abi1_54(inner))
             }
-            (1, 56) => {
+            Some(ProcMacroAbi::Abi1_56) => {
                 let inner = unsafe { Abi_1_56::from_lib(lib, symbol_name) }?;
                 Ok(Abi::Abi1_56(inner))
             }
-            (1, 57) => {
+            Some(ProcMacroAbi::Abi1_57) => {
                 let inner = unsafe { Abi_1_57::from_lib(lib, symbol_name) }?;
                 Ok(Abi::Abi1_57(inner))
             }
-            (1, 58..) => {
+            Some(ProcMacroAbi::Abi1_58) => {
                 let inner = unsafe { Abi_1_58::from_lib(lib, symbol_name) }?;
                 Ok(Abi::Abi1_58(inner))
             }
-            _ => Err(LoadProcMacroDylibError::UnsupportedABI),
+            None => Err(LoadProcMacroDylibError::UnsupportedABI),
         }
     }
 
@@ -6,14 +6,17 @@ use std::{
     io::{stderr, Write},
     sync::{
         atomic::{AtomicBool, Ordering},
-        RwLock,
+        Mutex, RwLock,
     },
     time::{Duration, Instant},
 };
 
 use once_cell::sync::Lazy;
 
-use crate::tree::{Idx, Tree};
+use crate::{
+    recording::{self, Recording},
+    tree::{Idx, Tree},
+};
 
 /// Filtering syntax
 /// env RA_PROFILE=*             // dump everything
@@ -30,6 +33,23 @@ pub fn init_from(spec: &str) {
     filter.install();
 }
 
+/// Starts recording all spans on all threads, see [`Recording`]. Nothing is
+/// printed until [`finish_recording`] is called.
+pub fn start_recording() {
+    recording::clear();
+    let previous = FILTER.read().unwrap().clone();
+    *PREVIOUS_FILTER.lock().unwrap() = Some(previous);
+    Filter { depth: 999, record: true, ..Filter::disabled() }.install();
+}
+
+/// Stops recording spans, restoring the previous filter, and returns the
+/// spans recorded since [`start_recording`].
+pub fn finish_recording() -> Recording {
+    let previous = PREVIOUS_FILTER.lock().unwrap().take();
+    previous.unwrap_or_else(Filter::disabled).install();
+    recording::take()
+}
+
 type Label = &'static str;
 
 /// This function starts a profiling scope in the current execution stack with a given description.
@@ -130,6 +150,7 @@ impl Drop for HeartbeatSpan {
 
 static PROFILING_ENABLED: AtomicBool = AtomicBool::new(false);
 static FILTER: Lazy<RwLock<Filter>> = Lazy::new(Default::default);
+static PREVIOUS_FILTER: Lazy<Mutex<Option<Filter>>> = Lazy::new(Default::default);
 
 fn with_profile_stack<T>(f: impl FnOnce(&mut ProfileStack) -> T) -> T {
     thread_local!(static STACK: RefCell<ProfileStack> = RefCell::new(ProfileStack::new()));
@@ -142,6 +163,8 @@ struct Filter {
     allowed: HashSet<String>,
     longer_than: Duration,
     heartbeat_longer_than: Duration,
+    /// Whether to record spans rather than print them.
+    record: bool,
     version: usize,
 }
 
@@ -169,7 +192,7 @@ impl Filter {
         };
         let allowed =
             if spec == "*" { HashSet::new() } else { spec.split('|').map(String::from).collect() };
-        Filter { depth, allowed, longer_than, heartbeat_longer_than, version: 0 }
+        Filter { depth, allowed, longer_than, heartbeat_longer_than, record: false, version: 0 }
     }
 
     fn install(mut self) {
@@ -190,6 +213,9 @@ struct ProfileStack {
 struct Frame {
     t: Instant,
     heartbeats: u32,
+    label: Label,
+    /// The time spent in nested spans.
+    children: Duration,
 }
 
 #[derive(Default)]
@@ -225,8 +251,15 @@ impl ProfileStack {
             return false;
         }
 
-        self.frames.push(Frame { t: Instant::now(), heartbeats: 0 });
-        self.messages.start();
+        self.frames.push(Frame {
+            t: Instant::now(),
+            heartbeats: 0,
+            label,
+            children: Duration::ZERO,
+        });
+        if !self.filter.record {
+            self.messages.start();
+        }
         true
     }
 
@@ -242,6 +275,15 @@ impl ProfileStack {
             }
         }
 
+        if let Some(parent) = self.frames.last_mut() {
+            parent.children += duration;
+        }
+        if self.filter.record {
+            let parents = self.frames.iter().map(|it| it.label);
+            recording::record(parents, label, duration, duration.saturating_sub(frame.children));
+            return;
+        }
+
         self.messages.finish(Message { duration, label, detail });
         if self.frames.is_empty() {
             let longer_than = self.filter.longer_than;
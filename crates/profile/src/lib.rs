@@ -5,13 +5,15 @@ mod memory_usage;
 #[cfg(feature = "cpu_profiler")]
 mod google_cpu_profiler;
 mod hprof;
+mod recording;
 mod tree;
 
 use std::cell::RefCell;
 
 pub use crate::{
-    hprof::{heartbeat, heartbeat_span, init, init_from, span},
+    hprof::{finish_recording, heartbeat, heartbeat_span, init, init_from, span, start_recording},
     memory_usage::{Bytes, MemoryUsage},
+    recording::{Recording, SpanStats},
     stop_watch::{StopWatch, StopWatchSpan},
 };
 
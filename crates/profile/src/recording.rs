@@ -0,0 +1,74 @@
+//! Records every profiling span instead of printing the slow ones, to break
+//! the time down by span label or to render it as a flamegraph.
+use std::{
+    collections::BTreeMap,
+    io::{self, Write},
+    sync::Mutex,
+    time::Duration,
+};
+
+use once_cell::sync::Lazy;
+
+static RECORDING: Lazy<Mutex<Recording>> = Lazy::new(Default::default);
+
+/// The spans recorded between [`crate::start_recording`] and
+/// [`crate::finish_recording`], on all threads.
+#[derive(Default, Debug, Clone)]
+pub struct Recording {
+    /// The time spent in each stack of span labels, not counting the time
+    /// spent in nested spans.
+    pub stacks: BTreeMap<Vec<&'static str>, Duration>,
+    /// Statistics by span label.
+    pub spans: BTreeMap<&'static str, SpanStats>,
+}
+
+#[derive(Default, Debug, Clone, Copy)]
+pub struct SpanStats {
+    /// How often a span with this label was entered. For query spans, this
+    /// is the number of times the query was (re)computed.
+    pub count: u64,
+    /// The total time spent in these spans. Spans nested in a span with the
+    /// same label are not counted twice.
+    pub total: Duration,
+}
+
+impl Recording {
+    /// Writes the stacks in the "folded" format of `inferno` and
+    /// `flamegraph.pl`, weighted by microseconds.
+    pub fn write_folded(&self, out: &mut impl Write) -> io::Result<()> {
+        for (stack, duration) in &self.stacks {
+            let micros = duration.as_micros();
+            if micros > 0 {
+                writeln!(out, "{} {}", stack.join(";"), micros)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn clear() {
+    *RECORDING.lock().unwrap() = Recording::default();
+}
+
+pub(crate) fn take() -> Recording {
+    std::mem::take(&mut *RECORDING.lock().unwrap())
+}
+
+/// Records a finished span, given the labels of the spans it is nested in.
+pub(crate) fn record(
+    parents: impl Iterator<Item = &'static str> + Clone,
+    label: &'static str,
+    duration: Duration,
+    self_time: Duration,
+) {
+    let is_nested_in_itself = parents.clone().any(|it| it == label);
+    let stack = parents.chain(Some(label)).collect::<Vec<_>>();
+
+    let mut recording = RECORDING.lock().unwrap();
+    *recording.stacks.entry(stack).or_default() += self_time;
+    let stats = recording.spans.entry(label).or_default();
+    stats.count += 1;
+    if !is_nested_in_itself {
+        stats.total += duration;
+    }
+}
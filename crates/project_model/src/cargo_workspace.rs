@@ -89,6 +89,14 @@ pub struct CargoConfig {
     /// when debugging isolated issues.
     pub no_sysroot: bool,
 
+    /// Load sysroot crates from this directory instead of the one discovered
+    /// via `rustc --print sysroot`.
+    pub sysroot_src: Option<AbsPathBuf>,
+
+    /// Only load `core` and `alloc` from the sysroot, for `#![no_std]`
+    /// projects.
+    pub no_std: bool,
+
     /// rustc private crate source
     pub rustc_source: Option<RustcSource>,
 
@@ -136,6 +144,10 @@ pub struct PackageData {
     pub manifest: ManifestPath,
     /// Targets provided by the crate (lib, bin, example, test, ...)
     pub targets: Vec<Target>,
+    /// Where the package was fetched from, as reported by `cargo metadata`,
+    /// like `registry+https://...` or `git+https://...#rev`. `None` for path
+    /// dependencies and workspace members.
+    pub source: Option<String>,
     /// Does this package come from the local filesystem (and is editable)?
     pub is_local: bool,
     // Whether this package is a member of the workspace
@@ -331,6 +343,7 @@ impl CargoWorkspace {
                 version: version.clone(),
                 manifest: AbsPathBuf::assert(PathBuf::from(&manifest_path)).try_into().unwrap(),
                 targets: Vec::new(),
+                source: meta_pkg.source.as_ref().map(|it| it.repr.clone()),
                 is_local,
                 is_member,
                 edition,
@@ -418,6 +431,10 @@ impl CargoWorkspace {
         }
     }
 
+    pub fn package_for_manifest(&self, manifest_path: &ManifestPath) -> Option<Package> {
+        self.packages().find(|&pkg| &self[pkg].manifest == manifest_path)
+    }
+
     pub fn parent_manifests(&self, manifest_path: &ManifestPath) -> Option<Vec<ManifestPath>> {
         let mut found = false;
         let parent_manifests = self
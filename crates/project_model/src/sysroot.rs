@@ -4,7 +4,11 @@
 //! but we can't process `.rlib` and need source code instead. The source code
 //! is typically installed with `rustup component add rust-src` command.
 
-use std::{env, fs, iter, ops, path::PathBuf, process::Command};
+use std::{
+    env, fs, iter, ops,
+    path::PathBuf,
+    process::{self, Command},
+};
 
 use anyhow::{format_err, Result};
 use la_arena::{Arena, Idx};
@@ -16,6 +20,8 @@ use crate::{utf8_stdout, ManifestPath};
 pub struct Sysroot {
     root: AbsPathBuf,
     crates: Arena<SysrootCrateData>,
+    /// Whether this is the bundled stand-in for a missing `rust-src`.
+    is_stub: bool,
 }
 
 pub(crate) type SysrootCrate = Idx<SysrootCrateData>;
@@ -39,6 +45,10 @@ impl Sysroot {
         &self.root
     }
 
+    pub fn is_stub(&self) -> bool {
+        self.is_stub
+    }
+
     pub fn public_deps(&self) -> impl Iterator<Item = (&'static str, SysrootCrate, bool)> + '_ {
         // core is added as a dependency before std in order to
         // mimic rustcs dependency order
@@ -58,13 +68,44 @@ impl Sysroot {
     }
 
     pub fn discover(dir: &AbsPath) -> Result<Sysroot> {
-        tracing::debug!("Discovering sysroot for {}", dir.display());
-        let sysroot_dir = discover_sysroot_dir(dir)?;
-        let sysroot_src_dir = discover_sysroot_src_dir(&sysroot_dir, dir)?;
+        let sysroot_src_dir = Sysroot::discover_src_dir(dir)?;
         let res = Sysroot::load(sysroot_src_dir)?;
         Ok(res)
     }
 
+    pub fn discover_src_dir(dir: &AbsPath) -> Result<AbsPathBuf> {
+        tracing::debug!("Discovering sysroot for {}", dir.display());
+        let sysroot_dir = discover_sysroot_dir(dir)?;
+        discover_sysroot_src_dir(&sysroot_dir)
+    }
+
+    /// Degraded mode for when the `rust-src` component is missing: loads a
+    /// minimal, bundled interface of `core`, `alloc` and, unless `no_std`,
+    /// `std`, so that the prelude at least resolves.
+    pub fn stub(no_std: bool) -> Result<Sysroot> {
+        // Per process, as other servers may be writing theirs at the same time.
+        let root = env::temp_dir().join(format!("rust-analyzer-sysroot-stub-{}", process::id()));
+        for (name, text) in SYSROOT_STUBS {
+            let dir = root.join(name).join("src");
+            fs::create_dir_all(&dir)?;
+            fs::write(dir.join("lib.rs"), text)?;
+        }
+        let root = AbsPathBuf::try_from(root)
+            .map_err(|path| format_err!("temp dir is not absolute: {}", path.display()))?;
+        let crates = if no_std { NO_STD_SYSROOT_CRATES } else { STUB_SYSROOT_CRATES };
+        let mut sysroot = Sysroot::load_crates(root, crates)?;
+        sysroot.is_stub = true;
+        Ok(sysroot)
+    }
+
+    /// Runs `rustup component add rust-src` for the toolchain used in `dir`.
+    pub fn install_rust_src(dir: &AbsPath) -> Result<()> {
+        let mut rustup = Command::new(toolchain::rustup());
+        rustup.current_dir(dir).args(&["component", "add", "rust-src"]);
+        utf8_stdout(rustup)?;
+        Ok(())
+    }
+
     pub fn discover_rustc(cargo_toml: &ManifestPath) -> Option<ManifestPath> {
         tracing::debug!("Discovering rustc source for {}", cargo_toml.display());
         let current_dir = cargo_toml.parent();
@@ -72,9 +113,20 @@ impl Sysroot {
     }
 
     pub fn load(sysroot_src_dir: AbsPathBuf) -> Result<Sysroot> {
-        let mut sysroot = Sysroot { root: sysroot_src_dir, crates: Arena::default() };
+        Sysroot::load_crates(sysroot_src_dir, SYSROOT_CRATES)
+    }
+
+    /// Loads only `core` and `alloc`, for `#![no_std]` projects that must not
+    /// see `std` (or its lang items) at all.
+    pub fn load_no_std(sysroot_src_dir: AbsPathBuf) -> Result<Sysroot> {
+        Sysroot::load_crates(sysroot_src_dir, NO_STD_SYSROOT_CRATES)
+    }
+
+    fn load_crates(sysroot_src_dir: AbsPathBuf, crates: &str) -> Result<Sysroot> {
+        let mut sysroot =
+            Sysroot { root: sysroot_src_dir, crates: Arena::default(), is_stub: false };
 
-        for path in SYSROOT_CRATES.trim().lines() {
+        for path in crates.trim().lines() {
             let name = path.split('/').last().unwrap();
             let root = [format!("{}/src/lib.rs", path), format!("lib{}/lib.rs", path)]
                 .into_iter()
@@ -141,10 +193,7 @@ fn discover_sysroot_dir(current_dir: &AbsPath) -> Result<AbsPathBuf> {
     Ok(AbsPathBuf::assert(PathBuf::from(stdout)))
 }
 
-fn discover_sysroot_src_dir(
-    sysroot_path: &AbsPathBuf,
-    current_dir: &AbsPath,
-) -> Result<AbsPathBuf> {
+fn discover_sysroot_src_dir(sysroot_path: &AbsPathBuf) -> Result<AbsPathBuf> {
     if let Ok(path) = env::var("RUST_SRC_PATH") {
         let path = AbsPathBuf::try_from(path.as_str())
             .map_err(|path| format_err!("RUST_SRC_PATH must be absolute: {}", path.display()))?;
@@ -156,23 +205,17 @@ fn discover_sysroot_src_dir(
         tracing::debug!("RUST_SRC_PATH is set, but is invalid (no core: {:?}), ignoring", core);
     }
 
-    get_rust_src(sysroot_path)
-        .or_else(|| {
-            let mut rustup = Command::new(toolchain::rustup());
-            rustup.current_dir(current_dir).args(&["component", "add", "rust-src"]);
-            utf8_stdout(rustup).ok()?;
-            get_rust_src(sysroot_path)
-        })
-        .ok_or_else(|| {
-            format_err!(
-                "\
+    // Installing `rust-src` is left to the client, which asks the user first.
+    get_rust_src(sysroot_path).ok_or_else(|| {
+        format_err!(
+            "\
 can't load standard library from sysroot
 {}
 (discovered via `rustc --print sysroot`)
 try installing the Rust source the same way you installed rustc",
-                sysroot_path.display(),
-            )
-        })
+            sysroot_path.display(),
+        )
+    })
 }
 
 fn get_rustc_src(sysroot_path: &AbsPath) -> Option<ManifestPath> {
@@ -209,6 +252,21 @@ term
 test
 unwind";
 
+const SYSROOT_STUBS: [(&str, &str); 3] = [
+    ("core", include_str!("../sysroot_stub/core.rs")),
+    ("alloc", include_str!("../sysroot_stub/alloc.rs")),
+    ("std", include_str!("../sysroot_stub/std.rs")),
+];
+
+const STUB_SYSROOT_CRATES: &str = "
+alloc
+core
+std";
+
+const NO_STD_SYSROOT_CRATES: &str = "
+alloc
+core";
+
 const STD_DEPS: &str = "
 alloc
 core
@@ -10,8 +10,8 @@ use paths::{AbsPath, AbsPathBuf};
 use serde::de::DeserializeOwned;
 
 use crate::{
-    CargoWorkspace, CfgOverrides, ProjectJson, ProjectJsonData, ProjectWorkspace, Sysroot,
-    WorkspaceBuildScripts,
+    build_scripts::BuildScriptOutput, CargoWorkspace, CfgOverrides, ProjectJson, ProjectJsonData,
+    ProjectWorkspace, Sysroot, WorkspaceBuildScripts,
 };
 
 fn load_cargo(file: &str) -> CrateGraph {
@@ -1741,3 +1741,86 @@ fn rust_project_is_proc_macro_has_proc_macro_dep() {
     // on the proc_macro sysroot crate.
     crate_data.dependencies.iter().find(|&dep| dep.name.deref() == "proc_macro").unwrap();
 }
+
+#[test]
+fn rust_project_proc_macro_dylib_paths() {
+    let data: ProjectJsonData = serde_json::from_value(serde_json::json!({
+        "crates": [
+            { "root_module": "src/lib.rs", "edition": "2018", "deps": [] },
+            {
+                "root_module": "macros/lib.rs",
+                "edition": "2018",
+                "deps": [],
+                "is_proc_macro": true,
+                "proc_macro_dylib_path": "target/libmacros.so"
+            }
+        ]
+    }))
+    .unwrap();
+    let project = rooted_project_json(data);
+    let base = project.path().to_path_buf();
+    let workspace = ProjectWorkspace::Json { project, sysroot: None, rustc_cfg: Vec::new() };
+
+    let dylib_paths = workspace.proc_macro_dylib_paths();
+    assert_eq!(dylib_paths.len(), 1);
+    assert_eq!(
+        dylib_paths[&base.join("macros/lib.rs").normalize()],
+        base.join("target/libmacros.so")
+    );
+}
+
+#[test]
+fn no_std_sysroot_only_has_core_and_alloc() {
+    let sysroot_src_dir = AbsPathBuf::assert(get_test_path("fake-sysroot"));
+    let sysroot = Sysroot::load_no_std(sysroot_src_dir).unwrap();
+    let crates: Vec<_> = sysroot.crates().map(|krate| sysroot[krate].name.as_str()).collect();
+    assert_eq!(crates, ["alloc", "core"]);
+    let public_deps: Vec<_> = sysroot.public_deps().map(|(name, _, _)| name).collect();
+    assert_eq!(public_deps, ["core", "alloc"]);
+}
+
+#[test]
+fn stub_sysroot_has_std() {
+    let sysroot = Sysroot::stub(false).unwrap();
+    assert!(sysroot.is_stub());
+    let public_deps: Vec<_> = sysroot.public_deps().map(|(name, _, _)| name).collect();
+    assert_eq!(public_deps, ["core", "alloc", "std"]);
+}
+
+#[test]
+fn no_std_stub_sysroot_has_no_std() {
+    let sysroot = Sysroot::stub(true).unwrap();
+    assert!(sysroot.is_stub());
+    let public_deps: Vec<_> = sysroot.public_deps().map(|(name, _, _)| name).collect();
+    assert_eq!(public_deps, ["core", "alloc"]);
+}
+
+#[test]
+fn eq_ignore_build_data_ignores_build_scripts_only() {
+    let cargo_workspace = |rustc_cfg| ProjectWorkspace::Cargo {
+        cargo: CargoWorkspace::new(get_test_json_file("hello-world-metadata.json")),
+        build_scripts: WorkspaceBuildScripts::default(),
+        sysroot: None,
+        rustc: None,
+        rustc_cfg,
+        cfg_overrides: CfgOverrides::default(),
+    };
+    let workspace = cargo_workspace(Vec::new());
+
+    let mut built = cargo_workspace(Vec::new());
+    if let ProjectWorkspace::Cargo { cargo, build_scripts, .. } = &mut built {
+        let package = cargo.packages().next().unwrap();
+        build_scripts.outputs.insert(
+            package,
+            BuildScriptOutput {
+                cfgs: vec!["has_build_script".parse().unwrap()],
+                ..Default::default()
+            },
+        );
+    }
+    assert!(built != workspace);
+    assert!(built.eq_ignore_build_data(&workspace));
+
+    let other_target = cargo_workspace(vec!["unix".parse().unwrap()]);
+    assert!(!other_target.eq_ignore_build_data(&workspace));
+}
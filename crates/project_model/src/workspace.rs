@@ -62,6 +62,8 @@ pub struct PackageRoot {
     pub exclude: Vec<AbsPathBuf>,
 }
 
+const TOOLCHAIN_FILES: &[&str] = &["rust-toolchain", "rust-toolchain.toml"];
+
 #[derive(Clone, Eq, PartialEq)]
 pub enum ProjectWorkspace {
     /// Project workspace was discovered by running `cargo metadata` and `rustc --print sysroot`.
@@ -179,7 +181,26 @@ impl ProjectWorkspace {
                 let sysroot = if config.no_sysroot {
                     None
                 } else {
-                    Some(Sysroot::discover(cargo_toml.parent()).with_context(|| {
+                    let load = |sysroot_src| {
+                        if config.no_std {
+                            Sysroot::load_no_std(sysroot_src)
+                        } else {
+                            Sysroot::load(sysroot_src)
+                        }
+                    };
+                    let sysroot = match &config.sysroot_src {
+                        Some(path) => load(path.clone()),
+                        None => Sysroot::discover_src_dir(cargo_toml.parent())
+                            .and_then(load)
+                            .or_else(|err| {
+                                tracing::error!(
+                                    "failed to discover sysroot, falling back to a stub: {:#}",
+                                    err
+                                );
+                                Sysroot::stub(config.no_std)
+                            }),
+                    };
+                    Some(sysroot.with_context(|| {
                         format!(
                             "Failed to find sysroot for Cargo.toml file {}. Is rust-src installed?",
                             cargo_toml.display()
@@ -244,11 +265,79 @@ impl ProjectWorkspace {
                 .first()
                 .and_then(|it| it.parent())
                 .ok_or_else(|| format_err!("No detached files to load"))?,
-        )?;
+        )
+        .or_else(|err| {
+            tracing::error!("failed to discover sysroot, falling back to a stub: {:#}", err);
+            Sysroot::stub(false)
+        })?;
         let rustc_cfg = rustc_cfg::get(None, None);
         Ok(ProjectWorkspace::DetachedFiles { files: detached_files, sysroot, rustc_cfg })
     }
 
+    /// Whether this workspace is analyzed against the bundled sysroot stub
+    /// because `rust-src` is missing.
+    pub fn has_stub_sysroot(&self) -> bool {
+        match self {
+            ProjectWorkspace::Cargo { sysroot, .. } | ProjectWorkspace::Json { sysroot, .. } => {
+                sysroot.as_ref().map_or(false, Sysroot::is_stub)
+            }
+            ProjectWorkspace::DetachedFiles { sysroot, .. } => sysroot.is_stub(),
+        }
+    }
+
+    /// The directory of the workspace's manifest, if it has one.
+    pub fn root(&self) -> Option<&AbsPath> {
+        match self {
+            ProjectWorkspace::Cargo { cargo, .. } => Some(cargo.workspace_root()),
+            ProjectWorkspace::Json { project, .. } => Some(project.path()),
+            ProjectWorkspace::DetachedFiles { .. } => None,
+        }
+    }
+
+    pub fn sysroot(&self) -> Option<&Sysroot> {
+        match self {
+            ProjectWorkspace::Cargo { sysroot, .. } | ProjectWorkspace::Json { sysroot, .. } => {
+                sysroot.as_ref()
+            }
+            ProjectWorkspace::DetachedFiles { sysroot, .. } => Some(sysroot),
+        }
+    }
+
+    /// The files rustup picks the toolchain of this workspace from: the
+    /// `rust-toolchain` files next to it, any existing ones further up, and
+    /// rustup's settings holding overrides and the default toolchain.
+    pub fn toolchain_files(&self) -> Vec<AbsPathBuf> {
+        let dirs = match self {
+            ProjectWorkspace::DetachedFiles { files, .. } => {
+                files.iter().filter_map(|file| file.parent()).collect::<Vec<_>>()
+            }
+            _ => self.root().into_iter().collect(),
+        };
+
+        let mut res = Vec::new();
+        for dir in dirs {
+            for name in TOOLCHAIN_FILES {
+                res.push(dir.join(name));
+            }
+            let mut ancestor = dir.parent();
+            while let Some(dir) = ancestor {
+                res.extend(
+                    TOOLCHAIN_FILES
+                        .iter()
+                        .map(|name| dir.join(name))
+                        .filter(|it| fs::metadata(it).is_ok()),
+                );
+                ancestor = dir.parent();
+            }
+        }
+        let settings = toolchain::rustup_home()
+            .and_then(|home| AbsPathBuf::try_from(home.join("settings.toml")).ok());
+        res.extend(settings.filter(|it| fs::metadata(it).is_ok()));
+        res.sort();
+        res.dedup();
+        res
+    }
+
     pub fn run_build_scripts(
         &self,
         config: &CargoConfig,
@@ -264,6 +353,27 @@ impl ProjectWorkspace {
         }
     }
 
+    /// Compares the workspaces as discovered, ignoring the results of their
+    /// build scripts, which are only filled in later.
+    pub fn eq_ignore_build_data(&self, other: &Self) -> bool {
+        let key = |p: &'_ ProjectWorkspace| match p {
+            ProjectWorkspace::Cargo {
+                cargo,
+                sysroot,
+                rustc,
+                rustc_cfg,
+                cfg_overrides,
+
+                build_scripts: _,
+            } => Some((cargo, sysroot, rustc, rustc_cfg, cfg_overrides)),
+            _ => None,
+        };
+        match (key(self), key(other)) {
+            (Some(lk), Some(rk)) => lk == rk,
+            _ => self == other,
+        }
+    }
+
     pub fn set_build_scripts(&mut self, bs: WorkspaceBuildScripts) {
         match self {
             ProjectWorkspace::Cargo { build_scripts, .. } => *build_scripts = bs,
@@ -273,6 +383,34 @@ impl ProjectWorkspace {
         }
     }
 
+    /// The proc-macro dylibs of the workspace, keyed by the root module of the crate they are
+    /// built from. For Cargo workspaces, these are only known after running the build scripts.
+    pub fn proc_macro_dylib_paths(&self) -> FxHashMap<AbsPathBuf, AbsPathBuf> {
+        match self {
+            ProjectWorkspace::Cargo { cargo, build_scripts, .. } => cargo
+                .packages()
+                .filter_map(|pkg| {
+                    let dylib = build_scripts.outputs.get(pkg)?.proc_macro_dylib_path.clone()?;
+                    let root = cargo[pkg]
+                        .targets
+                        .iter()
+                        .map(|&tgt| &cargo[tgt])
+                        .find(|tgt| tgt.is_proc_macro)?
+                        .root
+                        .clone();
+                    Some((root, dylib))
+                })
+                .collect(),
+            ProjectWorkspace::Json { project, .. } => project
+                .crates()
+                .filter_map(|(_, krate)| {
+                    Some((krate.root_module.clone(), krate.proc_macro_dylib_path.clone()?))
+                })
+                .collect(),
+            ProjectWorkspace::DetachedFiles { .. } => FxHashMap::default(),
+        }
+    }
+
     /// Returns the roots for the current `ProjectWorkspace`
     /// The return type contains the path and whether or not
     /// the root is a member of the current workspace
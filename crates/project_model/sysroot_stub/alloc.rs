@@ -0,0 +1,81 @@
+//! Minimal interface of `alloc`, used when the `rust-src` component is not
+//! installed.
+#![no_std]
+
+pub mod boxed {
+    #[lang = "owned_box"]
+    pub struct Box<T: ?Sized>(*mut T);
+
+    impl<T> Box<T> {
+        pub fn new(x: T) -> Box<T> {
+            loop {}
+        }
+    }
+
+    impl<T: ?Sized> core::ops::Deref for Box<T> {
+        type Target = T;
+        fn deref(&self) -> &T {
+            loop {}
+        }
+    }
+}
+
+pub mod vec {
+    pub struct Vec<T> {
+        ptr: *mut T,
+        len: usize,
+    }
+
+    impl<T> Vec<T> {
+        pub fn new() -> Vec<T> {
+            loop {}
+        }
+        pub fn push(&mut self, value: T) {}
+        pub fn pop(&mut self) -> Option<T> {
+            loop {}
+        }
+        pub fn len(&self) -> usize {
+            self.len
+        }
+        pub fn is_empty(&self) -> bool {
+            self.len == 0
+        }
+    }
+
+    impl<T> core::ops::Deref for Vec<T> {
+        type Target = [T];
+        fn deref(&self) -> &[T] {
+            loop {}
+        }
+    }
+}
+
+pub mod string {
+    pub struct String {
+        vec: crate::vec::Vec<u8>,
+    }
+
+    impl String {
+        pub fn new() -> String {
+            loop {}
+        }
+        pub fn push_str(&mut self, string: &str) {}
+        pub fn len(&self) -> usize {
+            self.vec.len()
+        }
+        pub fn is_empty(&self) -> bool {
+            self.vec.is_empty()
+        }
+    }
+
+    impl core::ops::Deref for String {
+        type Target = str;
+        fn deref(&self) -> &str {
+            loop {}
+        }
+    }
+
+    pub trait ToString {
+        fn to_string(&self) -> String;
+    }
+}
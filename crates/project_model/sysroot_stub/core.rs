@@ -0,0 +1,201 @@
+//! Minimal interface of `core`, used when the `rust-src` component is not
+//! installed. Only covers the items that most code touches through the prelude.
+#![no_core]
+#![feature(no_core, lang_items)]
+
+pub mod marker {
+    #[lang = "sized"]
+    pub trait Sized {}
+    #[lang = "copy"]
+    pub trait Copy: Clone {}
+    #[lang = "sync"]
+    pub unsafe auto trait Sync {}
+    pub unsafe auto trait Send {}
+    #[lang = "unpin"]
+    pub auto trait Unpin {}
+    #[lang = "phantom_data"]
+    pub struct PhantomData<T: ?Sized>;
+}
+
+pub mod clone {
+    #[lang = "clone"]
+    pub trait Clone: Sized {
+        fn clone(&self) -> Self;
+    }
+}
+
+pub mod default {
+    pub trait Default: Sized {
+        fn default() -> Self;
+    }
+}
+
+pub mod cmp {
+    #[lang = "eq"]
+    pub trait PartialEq<Rhs: ?Sized = Self> {
+        fn eq(&self, other: &Rhs) -> bool;
+    }
+    pub trait Eq: PartialEq<Self> {}
+    #[lang = "partial_ord"]
+    pub trait PartialOrd<Rhs: ?Sized = Self>: PartialEq<Rhs> {}
+    pub trait Ord: Eq + PartialOrd<Self> {}
+}
+
+pub mod convert {
+    pub trait From<T>: Sized {
+        fn from(value: T) -> Self;
+    }
+    pub trait Into<T>: Sized {
+        fn into(self) -> T;
+    }
+    pub trait AsRef<T: ?Sized> {
+        fn as_ref(&self) -> &T;
+    }
+    pub trait AsMut<T: ?Sized> {
+        fn as_mut(&mut self) -> &mut T;
+    }
+}
+
+pub mod ops {
+    #[lang = "drop"]
+    pub trait Drop {
+        fn drop(&mut self);
+    }
+    #[lang = "deref"]
+    pub trait Deref {
+        #[lang = "deref_target"]
+        type Target: ?Sized;
+        fn deref(&self) -> &Self::Target;
+    }
+    #[lang = "deref_mut"]
+    pub trait DerefMut: Deref {
+        fn deref_mut(&mut self) -> &mut Self::Target;
+    }
+    #[lang = "index"]
+    pub trait Index<Idx: ?Sized> {
+        type Output: ?Sized;
+        fn index(&self, index: Idx) -> &Self::Output;
+    }
+    #[lang = "fn_once"]
+    pub trait FnOnce<Args> {
+        #[lang = "fn_once_output"]
+        type Output;
+    }
+    #[lang = "fn_mut"]
+    pub trait FnMut<Args>: FnOnce<Args> {}
+    #[lang = "fn"]
+    pub trait Fn<Args>: FnMut<Args> {}
+    #[lang = "add"]
+    pub trait Add<Rhs = Self> {
+        type Output;
+        fn add(self, rhs: Rhs) -> Self::Output;
+    }
+    #[lang = "sub"]
+    pub trait Sub<Rhs = Self> {
+        type Output;
+        fn sub(self, rhs: Rhs) -> Self::Output;
+    }
+    #[lang = "mul"]
+    pub trait Mul<Rhs = Self> {
+        type Output;
+        fn mul(self, rhs: Rhs) -> Self::Output;
+    }
+    #[lang = "div"]
+    pub trait Div<Rhs = Self> {
+        type Output;
+        fn div(self, rhs: Rhs) -> Self::Output;
+    }
+    #[lang = "neg"]
+    pub trait Neg {
+        type Output;
+        fn neg(self) -> Self::Output;
+    }
+    #[lang = "not"]
+    pub trait Not {
+        type Output;
+        fn not(self) -> Self::Output;
+    }
+    #[lang = "Range"]
+    pub struct Range<Idx> {
+        pub start: Idx,
+        pub end: Idx,
+    }
+}
+
+pub mod option {
+    #[lang = "Option"]
+    pub enum Option<T> {
+        #[lang = "None"]
+        None,
+        #[lang = "Some"]
+        Some(T),
+    }
+    pub use self::Option::*;
+}
+
+pub mod result {
+    pub enum Result<T, E> {
+        #[lang = "Ok"]
+        Ok(T),
+        #[lang = "Err"]
+        Err(E),
+    }
+    pub use self::Result::*;
+}
+
+pub mod iter {
+    #[lang = "iterator"]
+    pub trait Iterator {
+        type Item;
+        #[lang = "next"]
+        fn next(&mut self) -> crate::option::Option<Self::Item>;
+    }
+    pub trait IntoIterator {
+        type Item;
+        type IntoIter: Iterator<Item = Self::Item>;
+        #[lang = "into_iter"]
+        fn into_iter(self) -> Self::IntoIter;
+    }
+    impl<I: Iterator> IntoIterator for I {
+        type Item = I::Item;
+        type IntoIter = I;
+        fn into_iter(self) -> I {
+            self
+        }
+    }
+    pub trait Extend<A> {}
+    pub trait FromIterator<A>: Sized {}
+    pub trait DoubleEndedIterator: Iterator {}
+    pub trait ExactSizeIterator: Iterator {}
+}
+
+pub mod mem {
+    pub fn drop<T>(_x: T) {}
+}
+
+pub mod prelude {
+    pub mod v1 {
+        pub use crate::{
+            clone::Clone,
+            cmp::{Eq, Ord, PartialEq, PartialOrd},
+            convert::{AsMut, AsRef, From, Into},
+            default::Default,
+            iter::{DoubleEndedIterator, ExactSizeIterator, Extend, IntoIterator, Iterator},
+            marker::{Copy, Send, Sized, Sync, Unpin},
+            mem::drop,
+            ops::{Drop, Fn, FnMut, FnOnce},
+            option::Option::{self, None, Some},
+            result::Result::{self, Err, Ok},
+        };
+    }
+    pub mod rust_2015 {
+        pub use super::v1::*;
+    }
+    pub mod rust_2018 {
+        pub use super::v1::*;
+    }
+    pub mod rust_2021 {
+        pub use super::v1::*;
+        pub use crate::iter::FromIterator;
+    }
+}
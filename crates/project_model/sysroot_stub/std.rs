@@ -0,0 +1,23 @@
+//! Minimal interface of `std`, used when the `rust-src` component is not
+//! installed.
+#![no_std]
+
+pub use alloc::{boxed, string, vec};
+pub use core::{clone, cmp, convert, default, iter, marker, mem, ops, option, result};
+
+pub mod prelude {
+    pub mod v1 {
+        pub use alloc::{boxed::Box, string::String, string::ToString, vec::Vec};
+        pub use core::prelude::v1::*;
+    }
+    pub mod rust_2015 {
+        pub use super::v1::*;
+    }
+    pub mod rust_2018 {
+        pub use super::v1::*;
+    }
+    pub mod rust_2021 {
+        pub use super::v1::*;
+        pub use core::prelude::rust_2021::*;
+    }
+}
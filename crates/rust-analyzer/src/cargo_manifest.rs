@@ -0,0 +1,250 @@
+//! Navigation in `Cargo.toml` files: going from a dependency entry to the crate
+//! it resolves to, and describing that crate on hover.
+//!
+//! We don't parse TOML here, the entries we are interested in are plain keys at
+//! the start of a line in one of the dependency tables, or the last segment of a
+//! `[dependencies.name]` header. The crate itself is looked up in the resolved
+//! `cargo metadata`, so `[patch]`, `[replace]` and renames are already taken
+//! into account.
+
+use lsp_types::{Position, Range};
+use project_model::{CargoWorkspace, ManifestPath, Package, ProjectWorkspace, TargetKind};
+use stdx::format_to;
+use vfs::AbsPathBuf;
+
+use crate::global_state::GlobalStateSnapshot;
+
+const DEPENDENCY_TABLES: &[&str] = &["dependencies", "dev-dependencies", "build-dependencies"];
+
+/// A dependency of a package, as it's written in the manifest of that package.
+pub(crate) struct ManifestDependency<'a> {
+    pub(crate) cargo: &'a CargoWorkspace,
+    pub(crate) pkg: Package,
+    /// The range of the dependency name in the manifest.
+    pub(crate) range: Range,
+}
+
+impl<'a> ManifestDependency<'a> {
+    /// The root module of the library target of the dependency.
+    pub(crate) fn lib_root(&self) -> Option<&'a AbsPathBuf> {
+        let cargo = self.cargo;
+        cargo[self.pkg]
+            .targets
+            .iter()
+            .find(|&&tgt| matches!(cargo[tgt].kind, TargetKind::Lib))
+            .map(|&tgt| &cargo[tgt].root)
+    }
+
+    pub(crate) fn hover_markup(&self) -> String {
+        let data = &self.cargo[self.pkg];
+        let mut res = format!("**{}** {}", data.name, data.version);
+        let source = match data.source.as_deref() {
+            Some(source) => match source.split_once('+') {
+                Some(("registry", url)) => format!("registry `{}`", url),
+                Some(("git", url)) => format!("git `{}`", url),
+                _ => format!("`{}`", source),
+            },
+            None => format!("path `{}`", data.manifest.parent().display()),
+        };
+        format_to!(res, "\n\nSource: {}", source);
+        let mut features = data.active_features.clone();
+        features.sort();
+        if features.is_empty() {
+            res.push_str("\n\nFeatures: none");
+        } else {
+            let features = features.iter().map(|it| format!("`{}`", it)).collect::<Vec<_>>();
+            format_to!(res, "\n\nFeatures: {}", features.join(", "));
+        }
+        if let Some(repository) = &data.repository {
+            format_to!(res, "\n\nRepository: {}", repository);
+        }
+        res
+    }
+}
+
+/// Finds the dependency whose name is at `position` in the `Cargo.toml` at `path`.
+pub(crate) fn dependency_at<'a>(
+    snap: &'a GlobalStateSnapshot,
+    path: &ManifestPath,
+    position: Position,
+) -> Option<ManifestDependency<'a>> {
+    let text = std::fs::read_to_string(path).ok()?;
+    let (name, range) = dependency_name_at(&text, position)?;
+    // Cargo reports dependencies by the name of the extern crate.
+    let crate_name = name.replace('-', "_");
+    snap.workspaces.iter().find_map(|ws| {
+        let cargo = match ws {
+            ProjectWorkspace::Cargo { cargo, .. } => cargo,
+            _ => return None,
+        };
+        let package = cargo.package_for_manifest(path)?;
+        let dep = cargo[package].dependencies.iter().find(|dep| dep.name == crate_name)?;
+        Some(ManifestDependency { cargo, pkg: dep.pkg, range })
+    })
+}
+
+/// Returns the dependency name at `position` and its range, if the position is on
+/// the key of an entry in a dependency table, or on the name in a
+/// `[dependencies.name]` header.
+fn dependency_name_at(text: &str, position: Position) -> Option<(String, Range)> {
+    let mut in_dependency_table = false;
+    for (line_idx, line) in text.lines().enumerate() {
+        let trimmed = line.trim_start();
+        let indent = line.len() - trimmed.len();
+        let on_line = line_idx as u32 == position.line;
+
+        if trimmed.starts_with('[') {
+            let header = trimmed.trim_start_matches('[');
+            let offset = indent + (trimmed.len() - header.len());
+            let header = header.split(']').next().unwrap_or_default();
+            let segments = split_dotted_key(header);
+            let table = |nth_back: usize| {
+                segments
+                    .len()
+                    .checked_sub(nth_back + 1)
+                    .map(|idx| segments[idx].0.as_str())
+                    .filter(|it| DEPENDENCY_TABLES.contains(it))
+            };
+            in_dependency_table = table(0).is_some();
+            if on_line && table(1).is_some() {
+                let (name, start, end) = segments.last()?;
+                return name_in_range(line, name, offset + start, offset + end, position);
+            }
+        } else if in_dependency_table && on_line {
+            let key = trimmed.split('=').next()?;
+            let (name, start, end) = split_dotted_key(key).into_iter().next()?;
+            return name_in_range(line, &name, indent + start, indent + end, position);
+        }
+        if on_line {
+            return None;
+        }
+    }
+    None
+}
+
+fn name_in_range(
+    line: &str,
+    name: &str,
+    start: usize,
+    end: usize,
+    position: Position,
+) -> Option<(String, Range)> {
+    let utf16_col = |offset: usize| line[..offset].encode_utf16().count() as u32;
+    let (start, end) = (utf16_col(start), utf16_col(end));
+    if !(start..=end).contains(&position.character) {
+        return None;
+    }
+    let range = Range::new(Position::new(position.line, start), Position::new(position.line, end));
+    Some((name.to_string(), range))
+}
+
+/// Splits `a."b.c".d` into its segments, with the byte ranges of their names
+/// (without quotes) in `key`.
+fn split_dotted_key(key: &str) -> Vec<(String, usize, usize)> {
+    let mut res = Vec::new();
+    let mut rest = key;
+    loop {
+        let offset = key.len() - rest.trim_start().len();
+        rest = rest.trim_start();
+        let (segment, len) = match rest.chars().next() {
+            Some(quote @ ('"' | '\'')) => match rest[1..].find(quote) {
+                Some(end) => ((offset + 1, offset + 1 + end), end + 2),
+                None => return res,
+            },
+            Some(_) => {
+                let len = rest
+                    .find(|c: char| !(c.is_alphanumeric() || c == '-' || c == '_'))
+                    .unwrap_or(rest.len());
+                ((offset, offset + len), len)
+            }
+            None => return res,
+        };
+        if segment.0 == segment.1 {
+            return res;
+        }
+        res.push((key[segment.0..segment.1].to_string(), segment.0, segment.1));
+        rest = rest[len..].trim_start();
+        match rest.strip_prefix('.') {
+            Some(it) => rest = it,
+            None => return res,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(text: &str, expected: Option<&str>) {
+        let (line, col) = text
+            .lines()
+            .enumerate()
+            .find_map(|(idx, line)| Some((idx, line.find("$0")?)))
+            .expect("no cursor");
+        let text = text.replace("$0", "");
+        let actual =
+            dependency_name_at(&text, Position::new(line as u32, col as u32)).map(|(name, _)| name);
+        assert_eq!(actual.as_deref(), expected);
+    }
+
+    #[test]
+    fn dependency_keys() {
+        check(
+            r#"
+[package]
+name = "foo"
+
+[dependencies]
+serde = "1.0"
+se$0rde_json = { version = "1.0", features = ["std"] }
+"#,
+            Some("serde_json"),
+        );
+        check(
+            r#"
+[package]
+name$0 = "foo"
+"#,
+            None,
+        );
+        check(
+            r#"
+[dev-dependencies]
+expect-test$0.workspace = true
+"#,
+            Some("expect-test"),
+        );
+        check(
+            r#"
+[target.'cfg(unix)'.dependencies]
+"lib$0c" = "0.2"
+"#,
+            Some("libc"),
+        );
+        check(
+            r#"
+[dependencies]
+serde = { version = "1.0", features = ["der$0ive"] }
+"#,
+            None,
+        );
+    }
+
+    #[test]
+    fn dependency_table_headers() {
+        check(
+            r#"
+[dependencies.rowan$0]
+version = "0.15"
+"#,
+            Some("rowan"),
+        );
+        check(
+            r#"
+[dependencies.rowan]
+vers$0ion = "0.15"
+"#,
+            None,
+        );
+    }
+}
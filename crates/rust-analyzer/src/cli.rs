@@ -7,8 +7,13 @@ mod symbols;
 mod highlight;
 mod analysis_stats;
 mod diagnostics;
+mod dead_code;
+mod unsafe_report;
+mod public_api;
+mod crate_graph;
 mod ssr;
 mod lsif;
+mod scip;
 
 mod progress_report;
 
@@ -3,6 +3,8 @@
 
 use std::{
     env,
+    fs::File,
+    io::{BufWriter, Write},
     time::{SystemTime, UNIX_EPOCH},
 };
 
@@ -89,6 +91,11 @@ impl flags::AnalysisStats {
         }
         eprintln!(")");
 
+        let record_spans = self.query_stats || self.folded_stacks.is_some();
+        if record_spans {
+            profile::start_recording();
+        }
+
         let mut analysis_sw = self.stop_watch();
         let mut num_crates = 0;
         let mut visited_modules = FxHashSet::default();
@@ -159,6 +166,19 @@ impl flags::AnalysisStats {
             report_metric("total memory", memory.allocated.megabytes() as u64, "MB");
         }
 
+        if record_spans {
+            let recording = profile::finish_recording();
+            if self.query_stats {
+                print_query_stats(&recording, verbosity);
+            }
+            if let Some(path) = &self.folded_stacks {
+                let mut out = BufWriter::new(File::create(path)?);
+                recording.write_folded(&mut out)?;
+                out.flush()?;
+                eprintln!("Folded stacks written to {}", path.display());
+            }
+        }
+
         if env::var("RA_COUNT").is_ok() {
             eprintln!("{}", profile::countme::get_all());
         }
@@ -424,6 +444,32 @@ fn expr_syntax_range(
     }
 }
 
+/// The profiling spans of the queries doing most of the work, by what they do.
+const QUERY_SPANS: &[(&str, &str)] = &[
+    ("ItemTree lowering", "file_item_tree_query"),
+    ("Def maps", "crate_def_map_query"),
+    ("Macro expansion", "macro_expand"),
+    ("Macro parsing", "parse_macro_expansion"),
+    ("Body lowering", "body_with_source_map_query"),
+    ("Type inference", "infer_query"),
+];
+
+fn print_query_stats(recording: &profile::Recording, verbosity: Verbosity) {
+    eprintln!("Query breakdown:");
+    for &(name, label) in QUERY_SPANS {
+        let stats = recording.spans.get(label).copied().unwrap_or_default();
+        eprintln!("  {:<20} {:>8} runs {:>10.2?}", name, stats.count, stats.total);
+    }
+    if verbosity.is_verbose() {
+        let mut spans = recording.spans.iter().collect::<Vec<_>>();
+        spans.sort_by_key(|(_, stats)| std::cmp::Reverse(stats.total));
+        eprintln!("Slowest spans:");
+        for (label, stats) in spans.into_iter().take(20) {
+            eprintln!("  {:<40} {:>8} runs {:>10.2?}", label, stats.count, stats.total);
+        }
+    }
+}
+
 fn shuffle<T>(rng: &mut Rand32, slice: &mut [T]) {
     for i in 0..slice.len() {
         randomize_first(rng, &mut slice[i..]);
@@ -0,0 +1,110 @@
+//! Prints the crate graph the server builds for a workspace as JSON, for build
+//! systems which generate `rust-project.json` files and want to check the result.
+//!
+//! The output only depends on the loaded crates: crates are ordered by their id,
+//! and cfg options, env vars and dependencies are sorted.
+
+use std::env;
+
+use ide_db::base_db::{CrateOrigin, SourceDatabase};
+use project_model::{ProjectManifest, ProjectWorkspace};
+use serde_json::{json, Map, Value};
+use vfs::{AbsPathBuf, VfsPath};
+
+use crate::cli::{
+    flags,
+    load_cargo::{load_workspace, LoadCargoConfig},
+};
+
+impl flags::CrateGraph {
+    pub fn run(self) -> anyhow::Result<()> {
+        let cargo_config = Default::default();
+        let root = AbsPathBuf::assert(env::current_dir()?.join(&self.path));
+        let manifest = ProjectManifest::discover_single(&root)?;
+        let mut workspace = ProjectWorkspace::load(manifest, &cargo_config, &|_| {})?;
+        if !self.disable_build_scripts {
+            let build_scripts = workspace.run_build_scripts(&cargo_config, &|_| {})?;
+            workspace.set_build_scripts(build_scripts);
+        }
+        let dylib_paths = workspace.proc_macro_dylib_paths();
+
+        // Proc macros don't need to be expanded, the dylib paths come from the workspace.
+        let load_cargo_config = LoadCargoConfig {
+            load_out_dirs_from_check: false,
+            with_proc_macro: false,
+            prefill_caches: false,
+        };
+        let (host, vfs, _proc_macro) = load_workspace(workspace, &load_cargo_config)?;
+        let db = host.raw_database();
+        let crate_graph = db.crate_graph();
+
+        let mut crate_ids = crate_graph.iter().collect::<Vec<_>>();
+        crate_ids.sort_by_key(|it| it.0);
+        let mut crates = Vec::with_capacity(crate_ids.len());
+        for crate_id in crate_ids {
+            let data = &crate_graph[crate_id];
+            let root_module = vfs.file_path(data.root_file_id);
+            let dylib_path = match root_module.as_path() {
+                Some(path) => dylib_paths.get(path).map(|it| it.display().to_string()),
+                None => None,
+            };
+
+            let mut cfg = data
+                .cfg_options
+                .iter()
+                .map(|atom| match atom {
+                    hir::CfgAtom::Flag(name) => name.to_string(),
+                    hir::CfgAtom::KeyValue { key, value } => format!("{}=\"{}\"", key, value),
+                })
+                .collect::<Vec<_>>();
+            cfg.sort();
+
+            let mut env = data.env.iter().collect::<Vec<_>>();
+            env.sort();
+            let env = env
+                .into_iter()
+                .map(|(key, value)| (key.to_owned(), Value::from(value)))
+                .collect::<Map<_, _>>();
+
+            let mut deps = data
+                .dependencies
+                .iter()
+                .map(|dep| (dep.crate_id.0, dep.name.to_string()))
+                .collect::<Vec<_>>();
+            deps.sort();
+            let deps = deps
+                .into_iter()
+                .map(|(crate_id, name)| json!({ "crate": crate_id, "name": name }))
+                .collect::<Vec<_>>();
+
+            let origin = match &data.origin {
+                CrateOrigin::CratesIo { .. } => "crates_io",
+                CrateOrigin::Lang => "lang",
+                CrateOrigin::Unknown => "unknown",
+            };
+            crates.push(json!({
+                "id": crate_id.0,
+                "display_name": data.display_name.as_ref().map(|it| it.canonical_name()),
+                "root_module": path_to_string(&root_module),
+                "edition": data.edition.to_string(),
+                "version": data.version,
+                "origin": origin,
+                "is_proc_macro": data.is_proc_macro,
+                "proc_macro_dylib_path": dylib_path,
+                "deps": deps,
+                "cfg": cfg,
+                "env": env,
+            }));
+        }
+
+        println!("{}", serde_json::to_string_pretty(&json!({ "crates": crates }))?);
+        Ok(())
+    }
+}
+
+fn path_to_string(path: &VfsPath) -> String {
+    match path.as_path() {
+        Some(it) => it.display().to_string(),
+        None => path.to_string(),
+    }
+}
@@ -0,0 +1,59 @@
+//! Reports crate-private items of the workspace which are never used, either
+//! as JSON or as one warning per line.
+
+use serde_json::json;
+
+use crate::cli::{
+    flags,
+    load_cargo::{load_workspace_at, LoadCargoConfig},
+};
+
+impl flags::DeadCode {
+    pub fn run(self) -> anyhow::Result<()> {
+        let cargo_config = Default::default();
+        let load_cargo_config = LoadCargoConfig {
+            load_out_dirs_from_check: !self.disable_build_scripts,
+            with_proc_macro: !self.disable_proc_macros,
+            prefill_caches: false,
+        };
+        let (host, vfs, _proc_macro) =
+            load_workspace_at(&self.path, &cargo_config, &load_cargo_config, &|_| {})?;
+        let analysis = host.analysis();
+
+        let threads =
+            self.threads.unwrap_or_else(|| num_cpus::get_physical().try_into().unwrap_or(u8::MAX));
+        let items = analysis.dead_code(threads).unwrap();
+
+        let mut report = Vec::new();
+        for item in &items {
+            let line_index = analysis.file_line_index(item.nav.file_id).unwrap();
+            let pos = line_index.line_col(item.nav.focus_or_full_range().start());
+            let path = vfs.file_path(item.nav.file_id);
+            if self.json {
+                report.push(json!({
+                    "crate": item.crate_name,
+                    "kind": item.kind,
+                    "name": item.nav.name.as_str(),
+                    "file": path.to_string(),
+                    "line": pos.line + 1,
+                    "column": pos.col + 1,
+                }));
+            } else {
+                println!(
+                    "{}:{}:{}: warning: {} `{}` is never used",
+                    path,
+                    pos.line + 1,
+                    pos.col + 1,
+                    item.kind,
+                    item.nav.name
+                );
+            }
+        }
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        } else {
+            eprintln!("{} unused items found", items.len());
+        }
+        Ok(())
+    }
+}
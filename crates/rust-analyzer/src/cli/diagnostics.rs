@@ -1,15 +1,20 @@
 //! Analyze all modules in a project for diagnostics. Exits with a non-zero
-//! status code if any errors are found.
+//! status code if any diagnostics at least as severe as `--fail-on` are found.
 
 use rustc_hash::FxHashSet;
+use serde_json::{json, Value};
 
 use hir::{db::HirDatabase, Crate, Module};
-use ide::{AssistResolveStrategy, DiagnosticsConfig, Severity};
-use ide_db::base_db::SourceDatabaseExt;
+use ide::{AssistResolveStrategy, Diagnostic, DiagnosticsConfig, LineCol, Severity};
+use ide_db::base_db::{FileId, SourceDatabaseExt};
+use vfs::VfsPath;
 
-use crate::cli::{
-    flags,
-    load_cargo::{load_workspace_at, LoadCargoConfig},
+use crate::{
+    cli::{
+        flags::{self, DiagnosticsFormat, SeverityLevel},
+        load_cargo::{load_workspace_at, LoadCargoConfig},
+    },
+    to_proto::url_from_abs_path,
 };
 
 impl flags::Diagnostics {
@@ -20,12 +25,21 @@ impl flags::Diagnostics {
             with_proc_macro: !self.disable_proc_macros,
             prefill_caches: false,
         };
-        let (host, _vfs, _proc_macro) =
+        let (host, vfs, _proc_macro) =
             load_workspace_at(&self.path, &cargo_config, &load_cargo_config, &|_| {})?;
         let db = host.raw_database();
         let analysis = host.analysis();
 
-        let mut found_error = false;
+        let config = DiagnosticsConfig {
+            disable_experimental: !self.experimental,
+            disabled: self.disable.iter().cloned().collect(),
+            ..DiagnosticsConfig::default()
+        };
+        let enabled: FxHashSet<&str> = self.enable.iter().map(|it| it.as_str()).collect();
+        let min_severity = self.severity.unwrap_or(SeverityLevel::Weak);
+        let fail_on = self.fail_on.unwrap_or(SeverityLevel::Error);
+
+        let mut reported = Vec::new();
         let mut visited_files = FxHashSet::default();
 
         let work = all_modules(db).into_iter().filter(|module| {
@@ -37,41 +51,156 @@ impl flags::Diagnostics {
 
         for module in work {
             let file_id = module.definition_source(db).file_id.original_file(db);
-            if !visited_files.contains(&file_id) {
-                let crate_name =
-                    module.krate().display_name(db).as_deref().unwrap_or("unknown").to_string();
-                println!("processing crate: {}, module: {}", crate_name, _vfs.file_path(file_id));
-                for diagnostic in analysis
-                    .diagnostics(
-                        &DiagnosticsConfig::default(),
-                        AssistResolveStrategy::None,
-                        file_id,
-                    )
-                    .unwrap()
+            if !visited_files.insert(file_id) {
+                continue;
+            }
+            let diagnostics =
+                analysis.diagnostics(&config, AssistResolveStrategy::None, file_id).unwrap();
+            let line_index = analysis.file_line_index(file_id).unwrap();
+            for diagnostic in diagnostics {
+                if severity_level(diagnostic.severity) < min_severity
+                    || (!enabled.is_empty() && !enabled.contains(diagnostic.code.as_str()))
                 {
-                    if matches!(diagnostic.severity, Severity::Error) {
-                        found_error = true;
-                    }
-
-                    println!("{:?}", diagnostic);
+                    continue;
                 }
-
-                visited_files.insert(file_id);
+                let start = line_index.line_col(diagnostic.range.start());
+                let end = line_index.line_col(diagnostic.range.end());
+                reported.push(Reported { file_id, start, end, diagnostic });
             }
         }
 
-        println!();
-        println!("diagnostic scan complete");
+        let path = |file_id: FileId| vfs.file_path(file_id).to_string();
+        match self.format.unwrap_or(DiagnosticsFormat::Text) {
+            DiagnosticsFormat::Text => {
+                for it in &reported {
+                    println!(
+                        "{}:{}:{}: {}[{}]: {}",
+                        path(it.file_id),
+                        it.start.line + 1,
+                        it.start.col + 1,
+                        severity_name(it.diagnostic.severity),
+                        it.diagnostic.code.as_str(),
+                        it.diagnostic.message
+                    );
+                }
+            }
+            DiagnosticsFormat::Json => {
+                let json: Vec<Value> = reported
+                    .iter()
+                    .map(|it| {
+                        json!({
+                            "file": path(it.file_id),
+                            "code": it.diagnostic.code.as_str(),
+                            "severity": severity_name(it.diagnostic.severity),
+                            "message": it.diagnostic.message,
+                            "experimental": it.diagnostic.experimental,
+                            "start": { "line": it.start.line + 1, "column": it.start.col + 1 },
+                            "end": { "line": it.end.line + 1, "column": it.end.col + 1 },
+                        })
+                    })
+                    .collect();
+                println!("{}", serde_json::to_string_pretty(&json)?);
+            }
+            DiagnosticsFormat::Sarif => {
+                let log = sarif(&reported, &|file_id| vfs.file_path(file_id));
+                println!("{}", serde_json::to_string_pretty(&log)?);
+            }
+        }
 
-        if found_error {
-            println!();
-            anyhow::bail!("diagnostic error detected")
+        let failing =
+            reported.iter().filter(|it| severity_level(it.diagnostic.severity) >= fail_on).count();
+        eprintln!("{} diagnostics found in {} files", reported.len(), visited_files.len());
+        if failing > 0 {
+            anyhow::bail!("{} diagnostics at or above the `--fail-on` severity", failing)
         }
 
         Ok(())
     }
 }
 
+struct Reported {
+    file_id: FileId,
+    start: LineCol,
+    end: LineCol,
+    diagnostic: Diagnostic,
+}
+
+fn severity_level(severity: Severity) -> SeverityLevel {
+    match severity {
+        Severity::Error => SeverityLevel::Error,
+        Severity::Warning => SeverityLevel::Warning,
+        Severity::WeakWarning => SeverityLevel::Weak,
+    }
+}
+
+fn severity_name(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::WeakWarning => "weak",
+    }
+}
+
+/// Renders the diagnostics as a SARIF 2.1.0 log, for code scanning tools.
+fn sarif(reported: &[Reported], path: &dyn Fn(FileId) -> VfsPath) -> Value {
+    let mut rules: Vec<&str> = reported.iter().map(|it| it.diagnostic.code.as_str()).collect();
+    rules.sort_unstable();
+    rules.dedup();
+    let results: Vec<Value> = reported
+        .iter()
+        .map(|it| {
+            let level = match it.diagnostic.severity {
+                Severity::Error => "error",
+                Severity::Warning => "warning",
+                Severity::WeakWarning => "note",
+            };
+            json!({
+                "ruleId": it.diagnostic.code.as_str(),
+                "level": level,
+                "message": { "text": it.diagnostic.message },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": artifact_location(&path(it.file_id)),
+                        "region": {
+                            "startLine": it.start.line + 1,
+                            "startColumn": it.start.col + 1,
+                            "endLine": it.end.line + 1,
+                            "endColumn": it.end.col + 1,
+                        },
+                    },
+                }],
+            })
+        })
+        .collect();
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "rust-analyzer",
+                    "informationUri": "https://rust-analyzer.github.io/",
+                    "version": env!("REV"),
+                    "rules": rules.iter().map(|id| json!({ "id": id })).collect::<Vec<_>>(),
+                },
+            },
+            "results": results,
+        }],
+    })
+}
+
+/// SARIF wants the location of a file as a URI: a `file://` one for files on disk, or one
+/// relative to the `%SRCROOT%` base for the rest.
+fn artifact_location(path: &VfsPath) -> Value {
+    match path.as_path() {
+        Some(path) => json!({ "uri": url_from_abs_path(path).to_string() }),
+        None => json!({
+            "uri": path.to_string().trim_start_matches('/'),
+            "uriBaseId": "%SRCROOT%",
+        }),
+    }
+}
+
 fn all_modules(db: &dyn HirDatabase) -> Vec<Module> {
     let mut worklist: Vec<_> =
         Crate::all(db).into_iter().map(|krate| krate.root_module(db)).collect();
@@ -84,3 +213,57 @@ fn all_modules(db: &dyn HirDatabase) -> Vec<Module> {
 
     modules
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use ide::{DiagnosticCode, TextRange};
+
+    use super::*;
+
+    #[test]
+    fn sarif_locations_are_uris() {
+        let (root, uri) = if cfg!(windows) {
+            ("C:\\my crate", "file:///c:/my%20crate/src/lib.rs")
+        } else {
+            ("/my crate", "file:///my%20crate/src/lib.rs")
+        };
+        let paths = [
+            VfsPath::new_real_path(
+                Path::new(root).join("src").join("lib.rs").display().to_string(),
+            ),
+            VfsPath::new_virtual_path("/src/generated.rs".to_string()),
+        ];
+        let reported: Vec<Reported> = (0..paths.len() as u32)
+            .map(|idx| Reported {
+                file_id: FileId(idx),
+                start: LineCol { line: 0, col: 4 },
+                end: LineCol { line: 0, col: 7 },
+                diagnostic: Diagnostic {
+                    code: DiagnosticCode("unresolved-module"),
+                    message: "unresolved module".to_string(),
+                    range: TextRange::default(),
+                    severity: Severity::Error,
+                    unused: false,
+                    deprecated: false,
+                    experimental: false,
+                    fixes: None,
+                    related: Vec::new(),
+                },
+            })
+            .collect();
+
+        let log = sarif(&reported, &|file_id| paths[file_id.0 as usize].clone());
+
+        let results = &log["runs"][0]["results"];
+        assert_eq!(
+            results[0]["locations"][0]["physicalLocation"]["artifactLocation"],
+            json!({ "uri": uri })
+        );
+        assert_eq!(
+            results[1]["locations"][0]["physicalLocation"]["artifactLocation"],
+            json!({ "uri": "src/generated.rs", "uriBaseId": "%SRCROOT%" })
+        );
+    }
+}
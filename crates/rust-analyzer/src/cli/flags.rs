@@ -64,6 +64,10 @@ xflags::xflags! {
             optional --memory-usage
             /// Print the total length of all source and macro files (whitespace is not counted).
             optional --source-stats
+            /// Print the time spent in, and the number of (re)computations of, the main queries.
+            optional --query-stats
+            /// Write the time spent in each profiling span to this file, as folded stacks for flamegraph tools.
+            optional --folded-stacks path: PathBuf
 
             /// Only analyze items matching this path.
             optional -o, --only path: String
@@ -80,6 +84,8 @@ xflags::xflags! {
             optional --skip-inference
         }
 
+        /// Run all native diagnostics on the workspace crates. Exits with an error status if
+        /// any diagnostic is at least as severe as `--fail-on`.
         cmd diagnostics
             /// Directory with Cargo.toml.
             required path: PathBuf
@@ -88,6 +94,79 @@ xflags::xflags! {
             optional --disable-build-scripts
             /// Don't use expand proc macros.
             optional --disable-proc-macros
+
+            /// Output format: `text` (the default), `json` or `sarif`.
+            optional --format format: DiagnosticsFormat
+            /// Only report diagnostics at least this severe: `error`, `warning` or `weak` (the default).
+            optional --severity severity: SeverityLevel
+            /// Fail if a reported diagnostic is at least this severe, defaults to `error`.
+            optional --fail-on severity: SeverityLevel
+            /// Only report diagnostics with this code, can be repeated.
+            repeated --enable code: String
+            /// Don't report diagnostics with this code, can be repeated.
+            repeated --disable code: String
+            /// Also report experimental diagnostics.
+            optional --experimental
+        }
+
+        /// Report crate-private items of the workspace which are never used.
+        cmd dead-code
+            /// Directory with Cargo.toml.
+            required path: PathBuf
+        {
+            /// Print the report as JSON.
+            optional --json
+            /// Number of crates to search at once, defaults to the number of cores.
+            optional --threads count: u8
+
+            /// Don't run build scripts or load `OUT_DIR` values by running `cargo check` before analysis.
+            optional --disable-build-scripts
+            /// Don't use expand proc macros.
+            optional --disable-proc-macros
+        }
+
+        /// List the unsafe code of the workspace, with the operations each `unsafe` block allows.
+        cmd unsafe-report
+            /// Directory with Cargo.toml.
+            required path: PathBuf
+        {
+            /// Print the report as JSON.
+            optional --json
+
+            /// Don't run build scripts or load `OUT_DIR` values by running `cargo check` before analysis.
+            optional --disable-build-scripts
+            /// Don't use expand proc macros.
+            optional --disable-proc-macros
+        }
+
+        /// List the public items of a crate, or compare them with an earlier version.
+        cmd public-api
+            /// Directory with Cargo.toml.
+            required path: PathBuf
+        {
+            /// The crate to list, defaults to the only workspace member.
+            optional --crate-name name: String
+            /// Write the public items to this file as JSON, to diff against later.
+            optional --save file: PathBuf
+            /// Report the changes since a file written by `--save`.
+            optional --baseline file: PathBuf
+            /// Report the changes since this git revision of the workspace.
+            optional --baseline-rev rev: String
+
+            /// Don't run build scripts or load `OUT_DIR` values by running `cargo check` before analysis.
+            optional --disable-build-scripts
+            /// Don't use expand proc macros.
+            optional --disable-proc-macros
+        }
+
+        /// Print the crate graph of the workspace as JSON: crate roots, editions, dependencies,
+        /// cfg options, env vars and proc-macro dylibs.
+        cmd crate-graph
+            /// Directory with Cargo.toml or rust-project.json.
+            required path: PathBuf
+        {
+            /// Don't run build scripts, so no `OUT_DIR`, build script cfgs or proc-macro dylibs are reported.
+            optional --disable-build-scripts
         }
 
         cmd ssr
@@ -108,6 +187,14 @@ xflags::xflags! {
         cmd lsif
             required path: PathBuf
         {}
+
+        /// Write a SCIP index of the workspace, for code browsers.
+        cmd scip
+            required path: PathBuf
+        {
+            /// Where to write the index, defaults to `index.scip`.
+            optional --output path: PathBuf
+        }
     }
 }
 
@@ -132,10 +219,15 @@ pub enum RustAnalyzerCmd {
     Highlight(Highlight),
     AnalysisStats(AnalysisStats),
     Diagnostics(Diagnostics),
+    DeadCode(DeadCode),
+    UnsafeReport(UnsafeReport),
+    PublicApi(PublicApi),
+    CrateGraph(CrateGraph),
     Ssr(Ssr),
     Search(Search),
     ProcMacro(ProcMacro),
     Lsif(Lsif),
+    Scip(Scip),
 }
 
 #[derive(Debug)]
@@ -167,6 +259,8 @@ pub struct AnalysisStats {
     pub parallel: bool,
     pub memory_usage: bool,
     pub source_stats: bool,
+    pub query_stats: bool,
+    pub folded_stacks: Option<PathBuf>,
     pub only: Option<String>,
     pub with_deps: bool,
     pub no_sysroot: bool,
@@ -181,6 +275,50 @@ pub struct Diagnostics {
 
     pub disable_build_scripts: bool,
     pub disable_proc_macros: bool,
+    pub format: Option<DiagnosticsFormat>,
+    pub severity: Option<SeverityLevel>,
+    pub fail_on: Option<SeverityLevel>,
+    pub enable: Vec<String>,
+    pub disable: Vec<String>,
+    pub experimental: bool,
+}
+
+#[derive(Debug)]
+pub struct DeadCode {
+    pub path: PathBuf,
+
+    pub json: bool,
+    pub threads: Option<u8>,
+    pub disable_build_scripts: bool,
+    pub disable_proc_macros: bool,
+}
+
+#[derive(Debug)]
+pub struct UnsafeReport {
+    pub path: PathBuf,
+
+    pub json: bool,
+    pub disable_build_scripts: bool,
+    pub disable_proc_macros: bool,
+}
+
+#[derive(Debug)]
+pub struct PublicApi {
+    pub path: PathBuf,
+
+    pub crate_name: Option<String>,
+    pub save: Option<PathBuf>,
+    pub baseline: Option<PathBuf>,
+    pub baseline_rev: Option<String>,
+    pub disable_build_scripts: bool,
+    pub disable_proc_macros: bool,
+}
+
+#[derive(Debug)]
+pub struct CrateGraph {
+    pub path: PathBuf,
+
+    pub disable_build_scripts: bool,
 }
 
 #[derive(Debug)]
@@ -203,6 +341,13 @@ pub struct Lsif {
     pub path: PathBuf,
 }
 
+#[derive(Debug)]
+pub struct Scip {
+    pub path: PathBuf,
+
+    pub output: Option<PathBuf>,
+}
+
 impl RustAnalyzer {
     pub const HELP: &'static str = Self::HELP_;
 
@@ -223,6 +368,21 @@ pub enum OutputFormat {
     Csv,
 }
 
+#[derive(Debug, PartialEq, Eq)]
+pub enum DiagnosticsFormat {
+    Text,
+    Json,
+    Sarif,
+}
+
+/// Diagnostic severities, from the least to the most severe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SeverityLevel {
+    Weak,
+    Warning,
+    Error,
+}
+
 impl RustAnalyzer {
     pub fn verbosity(&self) -> Verbosity {
         if self.quiet {
@@ -246,3 +406,29 @@ impl FromStr for OutputFormat {
         }
     }
 }
+
+impl FromStr for DiagnosticsFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            "sarif" => Ok(Self::Sarif),
+            _ => Err(format!("unknown diagnostics format `{}`", s)),
+        }
+    }
+}
+
+impl FromStr for SeverityLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "weak" => Ok(Self::Weak),
+            "warning" => Ok(Self::Warning),
+            "error" => Ok(Self::Error),
+            _ => Err(format!("unknown severity `{}`", s)),
+        }
+    }
+}
@@ -1,6 +1,6 @@
 //! Loads a Cargo project into a static instance of analysis, without support
 //! for incorporating changes.
-use std::{path::Path, sync::Arc};
+use std::{ops::ControlFlow, path::Path, sync::Arc};
 
 use anyhow::Result;
 use crossbeam_channel::{unbounded, Receiver};
@@ -11,7 +11,10 @@ use proc_macro_api::ProcMacroServer;
 use project_model::{CargoConfig, ProjectManifest, ProjectWorkspace};
 use vfs::{loader::Handle, AbsPath, AbsPathBuf};
 
-use crate::reload::{load_proc_macro, ProjectFolders, SourceRootConfig};
+use crate::{
+    config::{FilesConfig, FilesWatcher},
+    reload::{load_proc_macro, ProjectFolders, SourceRootConfig},
+};
 
 // Note: Since this type is used by external tools that use rust-analyzer as a library
 // what otherwise would be `pub(crate)` has to be `pub` here instead.
@@ -76,7 +79,14 @@ pub fn load_workspace(
         },
     );
 
-    let project_folders = ProjectFolders::new(&[ws], &[]);
+    let files_config = FilesConfig {
+        watcher: FilesWatcher::Notify,
+        exclude: Vec::new(),
+        exclude_globs: Vec::new(),
+        root_exclude_globs: Vec::new(),
+        respect_ignore_files: false,
+    };
+    let project_folders = ProjectFolders::new(&[ws], &files_config);
     loader.set_config(vfs::loader::Config {
         load: project_folders.load,
         watch: vec![],
@@ -88,7 +98,7 @@ pub fn load_workspace(
         load_crate_graph(crate_graph, project_folders.source_root_config, &mut vfs, &receiver);
 
     if load_config.prefill_caches {
-        host.analysis().parallel_prime_caches(1, |_| {})?;
+        host.analysis().parallel_prime_caches(1, |_| ControlFlow::Continue(()))?;
     }
     Ok((host, vfs, proc_macro_client))
 }
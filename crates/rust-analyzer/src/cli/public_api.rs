@@ -0,0 +1,164 @@
+//! Lists the public items of a crate, and reports how they changed since a
+//! saved snapshot or an earlier git revision.
+
+use std::{fs, path::Path, process::Command};
+
+use anyhow::{bail, format_err, Context};
+use hir::Crate;
+use ide::{diff_public_api, AnalysisHost, ApiItem};
+use ide_db::base_db::SourceDatabaseExt;
+use serde_json::{json, Value};
+
+use crate::cli::{
+    flags,
+    load_cargo::{load_workspace_at, LoadCargoConfig},
+};
+
+impl flags::PublicApi {
+    pub fn run(self) -> anyhow::Result<()> {
+        let items = self.public_api(&self.path)?;
+
+        if let Some(save) = &self.save {
+            let json: Vec<Value> = items
+                .iter()
+                .map(|it| json!({ "path": it.path, "signature": it.signature }))
+                .collect();
+            fs::write(save, serde_json::to_string_pretty(&json)?)
+                .with_context(|| format!("failed to write {}", save.display()))?;
+        }
+
+        let baseline = match (&self.baseline, &self.baseline_rev) {
+            (Some(_), Some(_)) => bail!("`--baseline` and `--baseline-rev` can't be used together"),
+            (Some(file), None) => Some(read_snapshot(file)?),
+            (None, Some(rev)) => Some(self.public_api_at_rev(rev)?),
+            (None, None) => None,
+        };
+
+        let baseline = match baseline {
+            Some(it) => it,
+            None => {
+                if self.save.is_none() {
+                    for item in &items {
+                        println!("{}", item.signature);
+                    }
+                }
+                return Ok(());
+            }
+        };
+
+        let diff = diff_public_api(&baseline, &items);
+        for item in &diff.removed {
+            println!("- {}: {}", item.path, item.signature);
+        }
+        for item in &diff.added {
+            println!("+ {}: {}", item.path, item.signature);
+        }
+        for (old, new) in &diff.changed {
+            println!("~ {}: {}", old.path, old.signature);
+            println!("  {}: {}", new.path, new.signature);
+        }
+        eprintln!(
+            "{} added, {} removed, {} changed",
+            diff.added.len(),
+            diff.removed.len(),
+            diff.changed.len()
+        );
+        Ok(())
+    }
+
+    fn public_api(&self, path: &Path) -> anyhow::Result<Vec<ApiItem>> {
+        let cargo_config = Default::default();
+        let load_cargo_config = LoadCargoConfig {
+            load_out_dirs_from_check: !self.disable_build_scripts,
+            with_proc_macro: !self.disable_proc_macros,
+            prefill_caches: false,
+        };
+        let (host, _vfs, _proc_macro) =
+            load_workspace_at(path, &cargo_config, &load_cargo_config, &|_| {})?;
+        let krate = self.find_crate(&host)?;
+        Ok(host.analysis().public_api(krate.into()).unwrap())
+    }
+
+    fn find_crate(&self, host: &AnalysisHost) -> anyhow::Result<Crate> {
+        let db = host.raw_database();
+        let members: Vec<Crate> = Crate::all(db)
+            .into_iter()
+            .filter(|krate| {
+                let source_root = db.file_source_root(krate.root_file(db));
+                !db.source_root(source_root).is_library
+            })
+            .collect();
+        let name = |krate: &Crate| krate.display_name(db).map(|it| it.to_string());
+
+        match &self.crate_name {
+            Some(crate_name) => {
+                let candidates: Vec<Crate> = members
+                    .iter()
+                    .copied()
+                    .filter(|krate| name(krate).as_deref() == Some(crate_name.as_str()))
+                    .collect();
+                // The library and the binaries of a package share its name. Prefer the
+                // library, which the binaries depend on.
+                let is_dependency = |krate: &Crate| {
+                    candidates
+                        .iter()
+                        .any(|other| other.dependencies(db).iter().any(|dep| dep.krate == *krate))
+                };
+                candidates
+                    .iter()
+                    .find(|krate| is_dependency(krate))
+                    .or_else(|| candidates.first())
+                    .copied()
+                    .ok_or_else(|| format_err!("no crate named `{}`", crate_name))
+            }
+            None => match members.as_slice() {
+                [krate] => Ok(*krate),
+                _ => {
+                    let mut names: Vec<String> = members.iter().filter_map(name).collect();
+                    names.sort();
+                    names.dedup();
+                    bail!("pick a crate with `--crate-name`, one of: {}", names.join(", "))
+                }
+            },
+        }
+    }
+
+    /// Computes the public API at another revision, in a temporary worktree.
+    fn public_api_at_rev(&self, rev: &str) -> anyhow::Result<Vec<ApiItem>> {
+        let path = fs::canonicalize(&self.path)?;
+        let toplevel = git(&path, &["rev-parse", "--show-toplevel"])?;
+        let relative = path.strip_prefix(toplevel.trim())?.to_path_buf();
+
+        let worktree =
+            std::env::temp_dir().join(format!("rust-analyzer-public-api-{}", std::process::id()));
+        let worktree_str = worktree.to_str().context("non UTF-8 temporary directory")?;
+        git(&path, &["worktree", "add", "--detach", worktree_str, rev])?;
+        let res = self.public_api(&worktree.join(relative));
+        git(&path, &["worktree", "remove", "--force", worktree_str])?;
+        res
+    }
+}
+
+fn git(dir: &Path, args: &[&str]) -> anyhow::Result<String> {
+    let output = Command::new("git").current_dir(dir).args(args).output()?;
+    if !output.status.success() {
+        bail!("`git {}` failed: {}", args.join(" "), String::from_utf8_lossy(&output.stderr));
+    }
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+fn read_snapshot(file: &Path) -> anyhow::Result<Vec<ApiItem>> {
+    let text =
+        fs::read_to_string(file).with_context(|| format!("failed to read {}", file.display()))?;
+    let json: Vec<Value> = serde_json::from_str(&text)?;
+    json.iter()
+        .map(|it| {
+            let field = |name: &str| {
+                it[name].as_str().map(ToString::to_string).ok_or_else(|| {
+                    format_err!("invalid snapshot {}: missing `{}`", file.display(), name)
+                })
+            };
+            Ok(ApiItem { path: field("path")?, signature: field("signature")? })
+        })
+        .collect()
+}
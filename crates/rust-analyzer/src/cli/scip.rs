@@ -0,0 +1,200 @@
+//! SCIP (SCIP Code Intelligence Protocol) index generator, the protobuf based
+//! successor of LSIF.
+
+use std::{collections::HashMap, env, time::Instant};
+
+use ide::{LineCol, MonikerResult, StaticIndex, StaticIndexedFile, TextRange, TokenId};
+use ide_db::LineIndexDatabase;
+use project_model::{CargoConfig, ProjectManifest, ProjectWorkspace};
+use rustc_hash::FxHashSet;
+use scip::types as scip_types;
+use vfs::AbsPathBuf;
+
+use crate::cli::{
+    flags,
+    load_cargo::{load_workspace, LoadCargoConfig},
+    Result,
+};
+
+impl flags::Scip {
+    pub fn run(self) -> Result<()> {
+        eprintln!("Generating SCIP start...");
+        let now = Instant::now();
+        let cargo_config = CargoConfig::default();
+        let no_progress = &|_| ();
+        let load_cargo_config = LoadCargoConfig {
+            load_out_dirs_from_check: true,
+            with_proc_macro: true,
+            prefill_caches: true,
+        };
+        let path = AbsPathBuf::assert(env::current_dir()?.join(&self.path));
+        let rootpath = path.normalize();
+        let manifest = ProjectManifest::discover_single(&path)?;
+
+        let workspace = ProjectWorkspace::load(manifest, &cargo_config, no_progress)?;
+
+        let (host, vfs, _) = load_workspace(workspace, &load_cargo_config)?;
+        let db = host.raw_database();
+        let analysis = host.analysis();
+
+        let si = StaticIndex::compute(&analysis);
+
+        let mut index = scip_types::Index {
+            metadata: Some(scip_types::Metadata {
+                version: scip_types::ProtocolVersion::UnspecifiedProtocolVersion.into(),
+                tool_info: Some(scip_types::ToolInfo {
+                    name: "rust-analyzer".to_owned(),
+                    version: env!("REV").to_owned(),
+                    arguments: vec![],
+                    ..Default::default()
+                })
+                .into(),
+                project_root: lsp_types::Url::from_file_path(&rootpath).unwrap().to_string(),
+                text_document_encoding: scip_types::TextEncoding::UTF8.into(),
+                ..Default::default()
+            })
+            .into(),
+            ..Default::default()
+        };
+
+        let mut symbols_emitted: FxHashSet<TokenId> = FxHashSet::default();
+        let mut tokens_to_symbol: HashMap<TokenId, String> = HashMap::new();
+
+        for file in si.files {
+            let StaticIndexedFile { file_id, tokens, .. } = file;
+            let relative_path = match get_relative_filepath(&vfs, &rootpath, file_id) {
+                Some(relative_path) => relative_path,
+                None => continue,
+            };
+
+            let line_index = db.line_index(file_id);
+            let mut doc = scip_types::Document {
+                relative_path,
+                language: "rust".to_string(),
+                ..Default::default()
+            };
+
+            tokens.into_iter().for_each(|(range, id)| {
+                let token = si.tokens.get(id).unwrap();
+
+                let mut occurrence = scip_types::Occurrence::default();
+                occurrence.range = text_range_to_scip_range(&line_index, range);
+                occurrence.symbol = tokens_to_symbol
+                    .entry(id)
+                    .or_insert_with(|| match &token.moniker {
+                        Some(moniker) => scip::symbol::format_symbol(moniker_to_symbol(moniker)),
+                        // Symbols without a moniker can't be referenced from other documents.
+                        None => format!("local {}", id.raw()),
+                    })
+                    .clone();
+
+                if let Some(def) = token.definition {
+                    if def.file_id == file_id && def.range == range {
+                        occurrence.symbol_roles |= scip_types::SymbolRole::Definition as i32;
+                    }
+
+                    if symbols_emitted.insert(id) {
+                        let mut symbol_info = scip_types::SymbolInformation::default();
+                        symbol_info.symbol = occurrence.symbol.clone();
+                        if let Some(hover) = &token.hover {
+                            if !hover.markup.as_str().is_empty() {
+                                symbol_info.documentation = vec![hover.markup.as_str().to_string()];
+                            }
+                        }
+
+                        doc.symbols.push(symbol_info)
+                    }
+                }
+
+                doc.occurrences.push(occurrence);
+            });
+
+            if doc.occurrences.is_empty() {
+                continue;
+            }
+
+            index.documents.push(doc);
+        }
+
+        let output = self.output.unwrap_or_else(|| "index.scip".into());
+        scip::write_message_to_file(&output, index)
+            .map_err(|err| anyhow::anyhow!("Failed to write scip to file: {}", err))?;
+
+        eprintln!("Generating SCIP finished {:?}", now.elapsed());
+        Ok(())
+    }
+}
+
+fn get_relative_filepath(
+    vfs: &vfs::Vfs,
+    rootpath: &vfs::AbsPathBuf,
+    file_id: ide::FileId,
+) -> Option<String> {
+    Some(vfs.file_path(file_id).as_path()?.strip_prefix(rootpath)?.as_ref().to_str()?.to_string())
+}
+
+// SCIP Ranges have a (very large) optimization that ranges if they are on the same line
+// only encode as a vector of [start_line, start_col, end_col].
+//
+// This transforms a line index into the optimized SCIP Range.
+fn text_range_to_scip_range(line_index: &ide::LineIndex, range: TextRange) -> Vec<i32> {
+    let LineCol { line: start_line, col: start_col } = line_index.line_col(range.start());
+    let LineCol { line: end_line, col: end_col } = line_index.line_col(range.end());
+
+    if start_line == end_line {
+        vec![start_line as i32, start_col as i32, end_col as i32]
+    } else {
+        vec![start_line as i32, start_col as i32, end_line as i32, end_col as i32]
+    }
+}
+
+fn moniker_to_symbol(moniker: &MonikerResult) -> scip_types::Symbol {
+    let package = &moniker.package_information;
+    let path = &moniker.identifier.path;
+    // We don't know which kind of item a path segment names, so everything
+    // but the item itself is treated as a namespace.
+    let descriptors = path
+        .iter()
+        .enumerate()
+        .map(|(idx, name)| scip_types::Descriptor {
+            name: name.to_string(),
+            suffix: if idx + 1 == path.len() {
+                scip_types::descriptor::Suffix::Term
+            } else {
+                scip_types::descriptor::Suffix::Namespace
+            }
+            .into(),
+            ..Default::default()
+        })
+        .collect();
+
+    scip_types::Symbol {
+        scheme: "rust-analyzer".into(),
+        package: Some(scip_types::Package {
+            manager: "cargo".to_string(),
+            name: package.name.clone(),
+            version: package.version.clone(),
+            ..Default::default()
+        })
+        .into(),
+        descriptors,
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ide::{LineIndex, TextSize};
+
+    use super::*;
+
+    #[test]
+    fn scip_ranges() {
+        let line_index = LineIndex::new("fn foo() {\n    bar\n}\n");
+        let range =
+            |start: u32, end: u32| TextRange::new(TextSize::from(start), TextSize::from(end));
+        assert_eq!(text_range_to_scip_range(&line_index, range(3, 6)), vec![0, 3, 6]);
+        assert_eq!(text_range_to_scip_range(&line_index, range(15, 18)), vec![1, 4, 7]);
+        assert_eq!(text_range_to_scip_range(&line_index, range(9, 20)), vec![0, 9, 2, 1]);
+    }
+}
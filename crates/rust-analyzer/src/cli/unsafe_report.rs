@@ -0,0 +1,85 @@
+//! Lists the unsafe code of the workspace for audits, either as JSON or as one
+//! line per item followed by the operations it allows.
+
+use ide::{Analysis, FileRange, UnsafeItemKind};
+use serde_json::{json, Value};
+use vfs::Vfs;
+
+use crate::cli::{
+    flags,
+    load_cargo::{load_workspace_at, LoadCargoConfig},
+};
+
+impl flags::UnsafeReport {
+    pub fn run(self) -> anyhow::Result<()> {
+        let cargo_config = Default::default();
+        let load_cargo_config = LoadCargoConfig {
+            load_out_dirs_from_check: !self.disable_build_scripts,
+            with_proc_macro: !self.disable_proc_macros,
+            prefill_caches: false,
+        };
+        let (host, vfs, _proc_macro) =
+            load_workspace_at(&self.path, &cargo_config, &load_cargo_config, &|_| {})?;
+        let analysis = host.analysis();
+
+        let items = analysis.unsafe_report().unwrap();
+
+        let mut report = Vec::new();
+        for item in &items {
+            if self.json {
+                let operations = item
+                    .operations
+                    .iter()
+                    .map(|op| {
+                        let mut location = location_json(&analysis, &vfs, op.range);
+                        location["description"] = op.description().into();
+                        location
+                    })
+                    .collect::<Vec<_>>();
+                let mut entry = location_json(&analysis, &vfs, item.range);
+                entry["crate"] = item.crate_name.as_str().into();
+                entry["kind"] = item.kind.as_str().into();
+                entry["name"] = item.name.as_str().into();
+                entry["operations"] = operations.into();
+                report.push(entry);
+            } else {
+                let item_location = location(&analysis, &vfs, item.range);
+                println!("{}: {} `{}`", item_location, item.kind.as_str(), item.name);
+                for op in &item.operations {
+                    let op_location = location(&analysis, &vfs, op.range);
+                    println!("    {}: {}", op_location, op.description());
+                }
+            }
+        }
+
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        } else {
+            let count = |kind| items.iter().filter(|it| it.kind == kind).count();
+            eprintln!(
+                "{} unsafe functions, {} unsafe blocks, {} unsafe traits, {} unsafe impls",
+                count(UnsafeItemKind::Function),
+                count(UnsafeItemKind::Block),
+                count(UnsafeItemKind::Trait),
+                count(UnsafeItemKind::Impl),
+            );
+        }
+        Ok(())
+    }
+}
+
+fn location(analysis: &Analysis, vfs: &Vfs, frange: FileRange) -> String {
+    let line_index = analysis.file_line_index(frange.file_id).unwrap();
+    let pos = line_index.line_col(frange.range.start());
+    format!("{}:{}:{}", vfs.file_path(frange.file_id), pos.line + 1, pos.col + 1)
+}
+
+fn location_json(analysis: &Analysis, vfs: &Vfs, frange: FileRange) -> Value {
+    let line_index = analysis.file_line_index(frange.file_id).unwrap();
+    let pos = line_index.line_col(frange.range.start());
+    json!({
+        "file": vfs.file_path(frange.file_id).to_string(),
+        "line": pos.line + 1,
+        "column": pos.col + 1,
+    })
+}
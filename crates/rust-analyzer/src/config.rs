@@ -7,12 +7,13 @@
 //! configure the server itself, feature flags are passed into analysis, and
 //! tweak things like automatic insertion of `()` in completions.
 
-use std::{ffi::OsString, iter, path::PathBuf};
+use std::{ffi::OsString, iter, path::PathBuf, time::Duration};
 
 use flycheck::FlycheckConfig;
 use ide::{
-    AssistConfig, CompletionConfig, DiagnosticsConfig, ExprFillDefaultMode, HighlightRelatedConfig,
-    HoverConfig, HoverDocFormat, InlayHintsConfig, JoinLinesConfig, LifetimeElisionHints, Snippet,
+    AssistConfig, BoundsPlacement, ColorConfig, CompletionConfig, DiagnosticsConfig,
+    DiscriminantHints, ExprFillDefaultMode, HighlightRelatedConfig, HoverConfig, HoverDocFormat,
+    InlayHintsConfig, JoinLinesConfig, LifetimeElisionHints, QualifyItemKind, Snippet,
     SnippetScope,
 };
 use ide_db::{
@@ -25,7 +26,7 @@ use project_model::{
 };
 use rustc_hash::{FxHashMap, FxHashSet};
 use serde::{de::DeserializeOwned, Deserialize};
-use vfs::AbsPathBuf;
+use vfs::{AbsPath, AbsPathBuf, Glob};
 
 use crate::{
     caps::completion_item_edit_resolve,
@@ -60,6 +61,10 @@ config_data! {
         assist_importGroup: bool                           = "true",
         /// Whether to allow import insertion to merge new imports into single path glob imports like `use std::fmt::*;`.
         assist_allowMergingIntoGlobImports: bool           = "true",
+        /// Kinds of items which are never imported, but qualified with their full path instead.
+        assist_qualifyInsteadOfImport: Vec<QualifyItemKindDef> = "[]",
+        /// Where the `Normalize bounds` assist puts the bounds of type parameters.
+        assist_boundsPlacement: BoundsPlacementDef = "\"where_clause\"",
 
         /// Warm up caches on project load.
         cache_warmup: bool = "true",
@@ -88,6 +93,13 @@ config_data! {
         cargo_target: Option<String>     = "null",
         /// Internal config for debugging, disables loading of sysroot crates.
         cargo_noSysroot: bool            = "false",
+        /// Path to the sysroot library sources (the `library` directory of
+        /// `rust-src`) to analyze against, instead of the one discovered via
+        /// `rustc --print sysroot`. Relative to the workspace root.
+        cargo_sysroot: Option<String>    = "null",
+        /// Only load `core` and `alloc` from the sysroot, so that `#![no_std]`
+        /// projects do not see `std` or its lang items.
+        cargo_noStd: bool                = "false",
 
         /// Run specified `cargo check` command for diagnostics on save.
         checkOnSave_enable: bool                         = "true",
@@ -113,6 +125,13 @@ config_data! {
         /// similar option.
         checkOnSave_overrideCommand: Option<Vec<String>> = "null",
 
+        /// Whether to show color swatches for color literals and constructors.
+        colorProvider_enable: bool = "true",
+        /// Types whose `rgb`/`rgba` constructors and `{ r, g, b, a }` literals
+        /// are shown as colors, given as a full path (`wgpu::Color`) or a bare
+        /// type name (`Color`).
+        colorProvider_typePaths: Vec<String> = "[\"Color\"]",
+
         /// Whether to add argument snippets when completing functions.
         /// Only applies when `#rust-analyzer.completion.addCallParenthesis#` is set.
         completion_addCallArgumentSnippets: bool = "true",
@@ -120,7 +139,7 @@ config_data! {
         completion_addCallParenthesis: bool      = "true",
         /// Custom completion snippets.
         // NOTE: Keep this list in sync with the feature docs of user snippets.
-        completion_snippets: FxHashMap<String, SnippetDef> = r#"{
+        completion_snippets: FxHashMap<String, SnippetDef> = r##"{
             "Arc::new": {
                 "postfix": "arc",
                 "body": "Arc::new(${receiver})",
@@ -159,8 +178,130 @@ config_data! {
                 "body": "Some(${receiver})",
                 "description": "Wrap the expression in an `Option::Some`",
                 "scope": "expr"
+            },
+            "Error enum": {
+                "prefix": "errenum",
+                "body": [
+                    "/// Errors of the `${module}` module.",
+                    "#[derive(Debug, Error)]",
+                    "pub enum ${1:Error} {",
+                    "\t#[error(\"${2:message}\")]",
+                    "\t${3:Variant},$0",
+                    "}",
+                    "",
+                    "pub type Result<T, E = $1> = std::result::Result<T, E>;"
+                ],
+                "requires": "thiserror::Error",
+                "description": "Define an error enum deriving `thiserror::Error`",
+                "scope": "item"
+            },
+            "Builder": {
+                "prefix": "builder",
+                "body": [
+                    "#[derive(Debug, Default)]",
+                    "pub struct ${1:Config}Builder {",
+                    "\t${2:field}: Option<${3:String}>,",
+                    "}",
+                    "",
+                    "impl $1Builder {",
+                    "\tpub fn $2(mut self, $2: $3) -> Self {",
+                    "\t\tself.$2 = Some($2);",
+                    "\t\tself",
+                    "\t}",
+                    "",
+                    "\tpub fn build(self) -> $1 {",
+                    "\t\t$1 { $2: self.$2.unwrap_or_default() }$0",
+                    "\t}",
+                    "}"
+                ],
+                "description": "Define a builder for a struct",
+                "scope": "item"
+            },
+            "Typestate": {
+                "prefix": "typestate",
+                "body": [
+                    "pub struct ${1:Connection}<S> {",
+                    "\tstate: S,",
+                    "}",
+                    "",
+                    "pub struct ${2:Closed};",
+                    "pub struct ${3:Open};",
+                    "",
+                    "impl $1<$2> {",
+                    "\tpub fn ${4:open}(self) -> $1<$3> {",
+                    "\t\t$1 { state: $3 }",
+                    "\t}",
+                    "}",
+                    "",
+                    "impl $1<$3> {",
+                    "\t$0",
+                    "}"
+                ],
+                "description": "Define a type whose methods depend on a state type parameter",
+                "scope": "item"
+            },
+            "Error enum": {
+                "prefix": "errenum",
+                "body": [
+                    "/// Errors of the `${module}` module.",
+                    "#[derive(Debug, Error)]",
+                    "pub enum ${1:Error} {",
+                    "\t#[error(\"${2:message}\")]",
+                    "\t${3:Variant},$0",
+                    "}",
+                    "",
+                    "pub type Result<T, E = $1> = std::result::Result<T, E>;"
+                ],
+                "requires": "thiserror::Error",
+                "description": "Define an error enum deriving `thiserror::Error`",
+                "scope": "item"
+            },
+            "Builder": {
+                "prefix": "builder",
+                "body": [
+                    "#[derive(Debug, Default)]",
+                    "pub struct ${1:Config}Builder {",
+                    "\t${2:field}: Option<${3:String}>,",
+                    "}",
+                    "",
+                    "impl $1Builder {",
+                    "\tpub fn $2(mut self, $2: $3) -> Self {",
+                    "\t\tself.$2 = Some($2);",
+                    "\t\tself",
+                    "\t}",
+                    "",
+                    "\tpub fn build(self) -> $1 {",
+                    "\t\t$1 { $2: self.$2.unwrap_or_default() }$0",
+                    "\t}",
+                    "}"
+                ],
+                "description": "Define a builder for a struct",
+                "scope": "item"
+            },
+            "Typestate": {
+                "prefix": "typestate",
+                "body": [
+                    "pub struct ${1:Connection}<S> {",
+                    "\tstate: S,",
+                    "}",
+                    "",
+                    "pub struct ${2:Closed};",
+                    "pub struct ${3:Open};",
+                    "",
+                    "impl $1<$2> {",
+                    "\tpub fn ${4:open}(self) -> $1<$3> {",
+                    "\t\t$1 { state: $3 }",
+                    "\t}",
+                    "}",
+                    "",
+                    "impl $1<$3> {",
+                    "\t$0",
+                    "}"
+                ],
+                "description": "Define a type whose methods depend on a state type parameter",
+                "scope": "item"
             }
-        }"#,
+        }"##,
         /// Whether to show postfix snippets like `dbg`, `if`, `not`, etc.
         completion_postfix_enable: bool         = "true",
         /// Toggles the additional completions that automatically add imports when completed.
@@ -171,6 +312,9 @@ config_data! {
         completion_autoself_enable: bool        = "true",
         /// Enables completions of private items and fields that are defined in the current workspace even if they are not visible at the current position.
         completion_privateEditable_enable: bool = "false",
+        /// Whether to add completions of small expressions of the expected type, built from the
+        /// locals, constructors and conversions in scope, like `Some(value)` or `x.into()`.
+        completion_termSearch_enable: bool      = "false",
 
         /// Whether to show native rust-analyzer diagnostics.
         diagnostics_enable: bool                = "true",
@@ -179,6 +323,20 @@ config_data! {
         diagnostics_enableExperimental: bool    = "false",
         /// List of rust-analyzer diagnostics to disable.
         diagnostics_disabled: FxHashSet<String> = "[]",
+        /// Paths of functions that block the current thread, like `std::thread::sleep`.
+        /// Calls to these inside async functions and blocks are flagged by the
+        /// `blocking-call-in-async` diagnostic.
+        diagnostics_blockingFunctions: Vec<String> = r#"[
+            "std::thread::sleep",
+            "std::fs::read",
+            "std::fs::read_to_string",
+            "std::fs::write",
+            "std::fs::File::open",
+            "std::fs::File::create",
+            "std::sync::Mutex::lock",
+            "std::sync::RwLock::read",
+            "std::sync::RwLock::write"
+        ]"#,
         /// Map of prefixes to be substituted when parsing diagnostic file paths.
         /// This should be the reverse mapping of what is passed to `rustc` as `--remap-path-prefix`.
         diagnostics_remapPrefix: FxHashMap<String, String> = "{}",
@@ -202,6 +360,18 @@ config_data! {
         /// relative to the workspace root, and globs are not supported. You may
         /// also need to add the folders to Code's `files.watcherExclude`.
         files_excludeDirs: Vec<PathBuf> = "[]",
+        /// Glob patterns of files and directories to skip when loading and
+        /// watching source roots, relative to each root. Like in `.gitignore`,
+        /// a pattern without a `/` matches at any depth, e.g. `node_modules`
+        /// or `**/fixtures/large/**`.
+        files_excludeGlobs: Vec<String> = "[]",
+        /// Additional exclude globs for specific source roots, keyed by the
+        /// root directory relative to the workspace root.
+        files_excludeGlobsPerRoot: FxHashMap<String, Vec<String>> = "{}",
+        /// Skip files and directories ignored by `.gitignore` and `.ignore`
+        /// files when loading and watching the workspace's source roots.
+        /// Dependencies and build script outputs are always loaded.
+        files_respectIgnoreFiles: bool = "true",
 
         /// Enables highlighting of related references while hovering your mouse above any identifier.
         highlightRelated_references: bool = "true",
@@ -211,6 +381,8 @@ config_data! {
         highlightRelated_breakPoints: bool = "true",
         /// Enables highlighting of all break points for a loop or block context while hovering your mouse above any `async` or `await` keywords.
         highlightRelated_yieldPoints: bool = "true",
+        /// Enables highlighting of all exit and yield points of the surrounding function while the cursor is anywhere in it, and not on an identifier or keyword with highlights of its own.
+        highlightRelated_functionPoints: bool = "false",
 
         /// Use semantic tokens for strings.
         ///
@@ -243,6 +415,15 @@ config_data! {
         /// `#rust-analyzer.hoverActions.enable#` is set.
         hoverActions_run: bool             = "true",
 
+        /// Whether to record how long type inference takes for each function, to find
+        /// the ones making analysis slow. The slowest are listed in the status report,
+        /// and the `slow-inference` diagnostic flags those over
+        /// `#rust-analyzer.inferenceProfiling.slowThreshold#`.
+        inferenceProfiling_enable: bool = "false",
+        /// Inference time in milliseconds above which a function is flagged by the
+        /// `slow-inference` diagnostic.
+        inferenceProfiling_slowThreshold: usize = "1000",
+
         /// Whether to render trailing colons for parameter hints, and trailing colons for parameter hints.
         inlayHints_renderColons: bool                      = "true",
         /// Maximum length for inlay hints. Set to null to have an unlimited length.
@@ -264,6 +445,10 @@ config_data! {
         inlayHints_lifetimeElisionHints_useParameterNames: bool  = "false",
         /// Whether to hide inlay hints for constructors.
         inlayHints_hideNamedConstructorHints: bool          = "false",
+        /// Whether to show enum variant discriminant hints.
+        inlayHints_discriminantHints_enable: DiscriminantHintsDef = "\"never\"",
+        /// Whether to show inlay hints at `.await` points, where async code may suspend.
+        inlayHints_awaitPointHints_enable: bool = "false",
 
         /// Join lines inserts else between consecutive ifs.
         joinLines_joinElseIf: bool = "true",
@@ -313,6 +498,10 @@ config_data! {
 
         /// How many worker threads to to handle priming caches. The default `0` means to pick automatically.
         primeCaches_numThreads: ParallelPrimeCachesNumThreads = "0",
+        /// Whether to infer the items of a file when it is opened, and the functions
+        /// they call, in the background, so that the first hover or go to definition
+        /// on it doesn't wait for their analysis.
+        primeCaches_openFiles: bool = "true",
 
         /// Enable support for procedural macros, implies `#rust-analyzer.cargo.runBuildScripts#`.
         procMacro_enable: bool                     = "true",
@@ -324,6 +513,10 @@ config_data! {
         /// This config takes a map of crate names with the exported proc-macro names to ignore as values.
         procMacro_ignored: FxHashMap<Box<str>, Box<[Box<str>]>>          = "{}",
 
+        /// Number of references after which a reference search stops and offers to
+        /// continue instead. Set to null to always search the whole workspace.
+        references_limit: Option<usize> = "10000",
+
         /// Command to be executed instead of 'cargo' for runnables.
         runnables_overrideCargo: Option<String> = "null",
         /// Additional arguments to be passed to cargo for runnables such as
@@ -347,9 +540,18 @@ config_data! {
         rustfmt_overrideCommand: Option<Vec<String>> = "null",
         /// Enables the use of rustfmt's unstable range formatting command for the
         /// `textDocument/rangeFormatting` request. The rustfmt option is unstable and only
-        /// available on a nightly build.
+        /// available on a nightly build. Without it, rust-analyzer only reindents the
+        /// selected lines.
         rustfmt_enableRangeFormatting: bool = "false",
 
+        /// Number of steps the trait solver may take on a single goal before giving up and
+        /// reporting an overflow. Defaults to 100.
+        traitSolver_fuel: Option<usize> = "null",
+
+        /// Whether goto definition and find references may lead from one workspace folder
+        /// into another. Files outside of all workspace folders, like the sysroot, can
+        /// always be reached.
+        workspace_crossFolderNavigation: bool = "false",
         /// Workspace symbol search scope.
         workspace_symbol_search_scope: WorkspaceSymbolSearchScopeDef = "\"workspace\"",
         /// Workspace symbol search kind.
@@ -370,6 +572,11 @@ pub struct Config {
     detached_files: Vec<AbsPathBuf>,
     pub discovered_projects: Option<Vec<ProjectManifest>>,
     pub root_path: AbsPathBuf,
+    /// The workspace folders opened in the client.
+    pub workspace_folders: Vec<AbsPathBuf>,
+    /// Settings scoped to a single workspace folder. Only the `cargo`,
+    /// `checkOnSave` and `rustfmt` settings are honored per folder.
+    folder_data: Vec<(AbsPathBuf, ConfigData)>,
     snippets: Vec<Snippet>,
 }
 
@@ -450,13 +657,17 @@ impl HoverActionsConfig {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FilesConfig {
     pub watcher: FilesWatcher,
     pub exclude: Vec<AbsPathBuf>,
+    pub exclude_globs: Vec<Glob>,
+    /// Extra exclude globs for the source roots at the given paths.
+    pub root_exclude_globs: Vec<(AbsPathBuf, Vec<Glob>)>,
+    pub respect_ignore_files: bool,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum FilesWatcher {
     Client,
     Notify,
@@ -506,7 +717,9 @@ impl Config {
             data: ConfigData::default(),
             detached_files: Vec::new(),
             discovered_projects: None,
+            workspace_folders: vec![root_path.clone()],
             root_path,
+            folder_data: Vec::new(),
             snippets: Default::default(),
         }
     }
@@ -554,6 +767,35 @@ impl Config {
         }
     }
 
+    /// Updates the settings scoped to the workspace folder `folder`. A null or
+    /// empty `json` falls back to the global settings.
+    pub fn update_folder(
+        &mut self,
+        folder: AbsPathBuf,
+        json: serde_json::Value,
+    ) -> Result<(), Vec<(String, serde_json::Error)>> {
+        tracing::info!("updating config of {} from JSON: {:#}", folder.display(), json);
+        self.folder_data.retain(|(it, _)| *it != folder);
+        if json.is_null() || json.as_object().map_or(false, |it| it.is_empty()) {
+            return Ok(());
+        }
+        let mut errors = Vec::new();
+        let data = ConfigData::from_json(json, &mut errors);
+        self.folder_data.push((folder, data));
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Sets the client's workspace folders, dropping the settings of removed
+    /// folders.
+    pub fn set_workspace_folders(&mut self, folders: Vec<AbsPathBuf>) {
+        self.folder_data.retain(|(it, _)| folders.contains(it));
+        self.workspace_folders = folders;
+    }
+
     pub fn json_schema() -> serde_json::Value {
         ConfigData::json_schema()
     }
@@ -719,6 +961,14 @@ impl Config {
         self.experimental("serverStatusNotification")
     }
 
+    pub fn loading_progress_notification(&self) -> bool {
+        self.experimental("loadingProgressNotification")
+    }
+
+    pub fn configuration_applied_notification(&self) -> bool {
+        self.experimental("configurationAppliedNotification")
+    }
+
     pub fn publish_diagnostics(&self) -> bool {
         self.data.diagnostics_enable
     }
@@ -726,6 +976,10 @@ impl Config {
         DiagnosticsConfig {
             disable_experimental: !self.data.diagnostics_enableExperimental,
             disabled: self.data.diagnostics_disabled.clone(),
+            blocking_functions: self.data.diagnostics_blockingFunctions.clone(),
+            slow_inference_threshold: Duration::from_millis(
+                self.data.inferenceProfiling_slowThreshold as u64,
+            ),
             expr_fill_default: match self.data.assist_exprFillDefault {
                 ExprFillDefaultDef::Todo => ExprFillDefaultMode::Todo,
                 ExprFillDefaultDef::Default => ExprFillDefaultMode::Default,
@@ -742,6 +996,22 @@ impl Config {
     pub fn lru_capacity(&self) -> Option<usize> {
         self.data.lruCapacity
     }
+    pub fn inference_profiling(&self) -> bool {
+        self.data.inferenceProfiling_enable
+    }
+    pub fn cross_folder_navigation(&self) -> bool {
+        self.data.workspace_crossFolderNavigation
+    }
+
+    pub fn references_limit(&self) -> Option<usize> {
+        self.data.references_limit
+    }
+
+    pub fn trait_solver_fuel(&self) -> u32 {
+        self.data
+            .traitSolver_fuel
+            .map_or(ide::DEFAULT_TRAIT_SOLVER_FUEL, |fuel| fuel.try_into().unwrap_or(u32::MAX))
+    }
     pub fn proc_macro_srv(&self) -> Option<(AbsPathBuf, Vec<OsString>)> {
         if !self.data.procMacro_enable {
             return None;
@@ -768,6 +1038,16 @@ impl Config {
                 _ => FilesWatcher::Notify,
             },
             exclude: self.data.files_excludeDirs.iter().map(|it| self.root_path.join(it)).collect(),
+            exclude_globs: self.data.files_excludeGlobs.iter().map(|it| Glob::new(it)).collect(),
+            root_exclude_globs: self
+                .data
+                .files_excludeGlobsPerRoot
+                .iter()
+                .map(|(root, globs)| {
+                    (self.root_path.join(root), globs.iter().map(|it| Glob::new(it)).collect())
+                })
+                .collect(),
+            respect_ignore_files: self.data.files_respectIgnoreFiles,
         }
     }
     pub fn notifications(&self) -> NotificationsConfig {
@@ -779,72 +1059,101 @@ impl Config {
     pub fn run_build_scripts(&self) -> bool {
         self.data.cargo_runBuildScripts || self.data.procMacro_enable
     }
+    /// Returns the innermost workspace folder containing `path`.
+    pub fn workspace_folder_for(&self, path: &AbsPath) -> Option<&AbsPath> {
+        self.workspace_folders
+            .iter()
+            .filter(|folder| path.starts_with(folder))
+            .max_by_key(|folder| folder.as_os_str().len())
+            .map(|folder| folder.as_path())
+    }
+
+    /// Returns the settings which apply to `path`, along with the directory
+    /// relative paths in them are resolved against.
+    fn scope_for(&self, path: &AbsPath) -> (&AbsPath, &ConfigData) {
+        self.workspace_folder_for(path)
+            .and_then(|folder| self.folder_data.iter().find(|(it, _)| it.as_path() == folder))
+            .map_or((self.root_path.as_path(), &self.data), |(folder, data)| {
+                (folder.as_path(), data)
+            })
+    }
+
     pub fn cargo(&self) -> CargoConfig {
-        let rustc_source = self.data.rustcSource.as_ref().map(|rustc_src| {
+        self.cargo_for(&self.root_path)
+    }
+
+    pub fn cargo_for(&self, path: &AbsPath) -> CargoConfig {
+        let (root_path, data) = self.scope_for(path);
+        let rustc_source = data.rustcSource.as_ref().map(|rustc_src| {
             if rustc_src == "discover" {
                 RustcSource::Discover
             } else {
-                RustcSource::Path(self.root_path.join(rustc_src))
+                RustcSource::Path(root_path.join(rustc_src))
             }
         });
 
         CargoConfig {
-            no_default_features: self.data.cargo_noDefaultFeatures,
-            all_features: self.data.cargo_allFeatures,
-            features: self.data.cargo_features.clone(),
-            target: self.data.cargo_target.clone(),
-            no_sysroot: self.data.cargo_noSysroot,
+            no_default_features: data.cargo_noDefaultFeatures,
+            all_features: data.cargo_allFeatures,
+            features: data.cargo_features.clone(),
+            target: data.cargo_target.clone(),
+            no_sysroot: data.cargo_noSysroot,
+            sysroot_src: data.cargo_sysroot.as_ref().map(|it| root_path.join(it)),
+            no_std: data.cargo_noStd,
             rustc_source,
-            unset_test_crates: UnsetTestCrates::Only(self.data.cargo_unsetTest.clone()),
-            wrap_rustc_in_build_scripts: self.data.cargo_useRustcWrapperForBuildScripts,
+            unset_test_crates: UnsetTestCrates::Only(data.cargo_unsetTest.clone()),
+            wrap_rustc_in_build_scripts: data.cargo_useRustcWrapperForBuildScripts,
         }
     }
 
     pub fn rustfmt(&self) -> RustfmtConfig {
-        match &self.data.rustfmt_overrideCommand {
+        self.rustfmt_for(&self.root_path)
+    }
+
+    pub fn rustfmt_for(&self, path: &AbsPath) -> RustfmtConfig {
+        let (_, data) = self.scope_for(path);
+        match &data.rustfmt_overrideCommand {
             Some(args) if !args.is_empty() => {
                 let mut args = args.clone();
                 let command = args.remove(0);
                 RustfmtConfig::CustomCommand { command, args }
             }
             Some(_) | None => RustfmtConfig::Rustfmt {
-                extra_args: self.data.rustfmt_extraArgs.clone(),
-                enable_range_formatting: self.data.rustfmt_enableRangeFormatting,
+                extra_args: data.rustfmt_extraArgs.clone(),
+                enable_range_formatting: data.rustfmt_enableRangeFormatting,
             },
         }
     }
     pub fn flycheck(&self) -> Option<FlycheckConfig> {
-        if !self.data.checkOnSave_enable {
+        self.flycheck_for(&self.root_path)
+    }
+    pub fn flycheck_for(&self, path: &AbsPath) -> Option<FlycheckConfig> {
+        let (_, data) = self.scope_for(path);
+        if !data.checkOnSave_enable {
             return None;
         }
-        let flycheck_config = match &self.data.checkOnSave_overrideCommand {
+        let flycheck_config = match &data.checkOnSave_overrideCommand {
             Some(args) if !args.is_empty() => {
                 let mut args = args.clone();
                 let command = args.remove(0);
                 FlycheckConfig::CustomCommand { command, args }
             }
             Some(_) | None => FlycheckConfig::CargoCommand {
-                command: self.data.checkOnSave_command.clone(),
-                target_triple: self
-                    .data
+                command: data.checkOnSave_command.clone(),
+                target_triple: data
                     .checkOnSave_target
                     .clone()
-                    .or_else(|| self.data.cargo_target.clone()),
-                all_targets: self.data.checkOnSave_allTargets,
-                no_default_features: self
-                    .data
+                    .or_else(|| data.cargo_target.clone()),
+                all_targets: data.checkOnSave_allTargets,
+                no_default_features: data
                     .checkOnSave_noDefaultFeatures
-                    .unwrap_or(self.data.cargo_noDefaultFeatures),
-                all_features: self
-                    .data
-                    .checkOnSave_allFeatures
-                    .unwrap_or(self.data.cargo_allFeatures),
-                features: self
-                    .data
+                    .unwrap_or(data.cargo_noDefaultFeatures),
+                all_features: data.checkOnSave_allFeatures.unwrap_or(data.cargo_allFeatures),
+                features: data
                     .checkOnSave_features
                     .clone()
-                    .unwrap_or_else(|| self.data.cargo_features.clone()),
-                extra_args: self.data.checkOnSave_extraArgs.clone(),
+                    .unwrap_or_else(|| data.cargo_features.clone()),
+                extra_args: data.checkOnSave_extraArgs.clone(),
             },
         };
         Some(flycheck_config)
@@ -872,6 +1181,12 @@ impl Config {
             param_names_for_lifetime_elision_hints: self
                 .data
                 .inlayHints_lifetimeElisionHints_useParameterNames,
+            discriminant_hints: match self.data.inlayHints_discriminantHints_enable {
+                DiscriminantHintsDef::Always => DiscriminantHints::Always,
+                DiscriminantHintsDef::Never => DiscriminantHints::Never,
+                DiscriminantHintsDef::Fieldless => DiscriminantHints::Fieldless,
+            },
+            await_point_hints: self.data.inlayHints_awaitPointHints_enable,
             max_length: self.data.inlayHints_maxLength,
         }
     }
@@ -900,6 +1215,7 @@ impl Config {
                 && completion_item_edit_resolve(&self.caps),
             enable_self_on_the_fly: self.data.completion_autoself_enable,
             enable_private_editable: self.data.completion_privateEditable_enable,
+            enable_term_search: self.data.completion_termSearch_enable,
             add_call_parenthesis: self.data.completion_addCallParenthesis,
             add_call_argument_snippets: self.data.completion_addCallArgumentSnippets,
             insert_use: self.insert_use_config(),
@@ -922,6 +1238,21 @@ impl Config {
             snippet_cap: SnippetCap::new(self.experimental("snippetTextEdit")),
             allowed: None,
             insert_use: self.insert_use_config(),
+            always_qualify: self
+                .data
+                .assist_qualifyInsteadOfImport
+                .iter()
+                .map(|kind| match kind {
+                    QualifyItemKindDef::Macro => QualifyItemKind::Macro,
+                    QualifyItemKindDef::Function => QualifyItemKind::Function,
+                    QualifyItemKindDef::Type => QualifyItemKind::Type,
+                    QualifyItemKindDef::Constant => QualifyItemKind::Constant,
+                })
+                .collect(),
+            bounds_placement: match self.data.assist_boundsPlacement {
+                BoundsPlacementDef::WhereClause => BoundsPlacement::WhereClause,
+                BoundsPlacementDef::Inline => BoundsPlacement::Inline,
+            },
         }
     }
     pub fn join_lines(&self) -> JoinLinesConfig {
@@ -932,6 +1263,12 @@ impl Config {
             join_assignments: self.data.joinLines_joinAssignments,
         }
     }
+    pub fn color_provider(&self) -> Option<ColorConfig> {
+        if !self.data.colorProvider_enable {
+            return None;
+        }
+        Some(ColorConfig { type_paths: self.data.colorProvider_typePaths.clone() })
+    }
     pub fn call_info_full(&self) -> bool {
         self.data.callInfo_full
     }
@@ -1042,6 +1379,7 @@ impl Config {
             break_points: self.data.highlightRelated_breakPoints,
             exit_points: self.data.highlightRelated_exitPoints,
             yield_points: self.data.highlightRelated_yieldPoints,
+            function_points: self.data.highlightRelated_functionPoints,
         }
     }
 
@@ -1051,6 +1389,10 @@ impl Config {
             n => n,
         }
     }
+
+    pub fn prime_caches_open_files(&self) -> bool {
+        self.data.primeCaches_openFiles
+    }
 }
 
 #[derive(Deserialize, Debug, Clone, Copy)]
@@ -1151,6 +1493,16 @@ enum LifetimeElisionDef {
     SkipTrivial,
 }
 
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "snake_case")]
+enum DiscriminantHintsDef {
+    #[serde(alias = "true")]
+    Always,
+    #[serde(alias = "false")]
+    Never,
+    Fieldless,
+}
+
 #[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "snake_case")]
 enum ImportPrefixDef {
@@ -1161,6 +1513,22 @@ enum ImportPrefixDef {
     ByCrate,
 }
 
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "snake_case")]
+enum QualifyItemKindDef {
+    Macro,
+    Function,
+    Type,
+    Constant,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "snake_case")]
+enum BoundsPlacementDef {
+    WhereClause,
+    Inline,
+}
+
 #[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "snake_case")]
 enum WorkspaceSymbolSearchScopeDef {
@@ -1303,6 +1671,20 @@ fn field_props(field: &str, ty: &str, doc: &[&str], default: &str) -> serde_json
             "type": "array",
             "items": { "type": "string" },
         },
+        "Vec<QualifyItemKindDef>" => set! {
+            "type": "array",
+            "items": {
+                "type": "string",
+                "enum": ["macro", "function", "type", "constant"],
+                "enumDescriptions": [
+                    "Macros.",
+                    "Functions.",
+                    "Structs, enums, unions, traits and type aliases.",
+                    "Constants and statics."
+                ],
+            },
+            "uniqueItems": true,
+        },
         "Vec<PathBuf>" => set! {
             "type": "array",
             "items": { "type": "string" },
@@ -1321,6 +1703,13 @@ fn field_props(field: &str, ty: &str, doc: &[&str], default: &str) -> serde_json
         "FxHashMap<String, String>" => set! {
             "type": "object",
         },
+        "FxHashMap<String, Vec<String>>" => set! {
+            "type": "object",
+        },
+        "usize" => set! {
+            "type": "integer",
+            "minimum": 0,
+        },
         "Option<usize>" => set! {
             "type": ["null", "integer"],
             "minimum": 0,
@@ -1347,6 +1736,14 @@ fn field_props(field: &str, ty: &str, doc: &[&str], default: &str) -> serde_json
                 "Merge imports from the same module into a single `use` statement."
             ],
         },
+        "BoundsPlacementDef" => set! {
+            "type": "string",
+            "enum": ["where_clause", "inline"],
+            "enumDescriptions": [
+                "Put all the bounds in the where clause.",
+                "Put the bounds of type parameters next to the parameters."
+            ],
+        },
         "ExprFillDefaultDef" => set! {
             "type": "string",
             "enum": ["todo", "default"],
@@ -1412,6 +1809,15 @@ fn field_props(field: &str, ty: &str, doc: &[&str], default: &str) -> serde_json
                 "Only show lifetime elision hints if a return type is involved."
             ],
         },
+        "DiscriminantHintsDef" => set! {
+            "type": "string",
+            "enum": ["always", "never", "fieldless"],
+            "enumDescriptions": [
+                "Always show all discriminant hints.",
+                "Never show discriminant hints.",
+                "Only show discriminant hints on fieldless enum variants."
+            ],
+        },
         _ => panic!("missing entry for {}: {}", ty, default),
     }
 
@@ -7,13 +7,16 @@ use crate::{
     global_state::{GlobalState, GlobalStateSnapshot},
     lsp_utils::is_cancelled,
     main_loop::Task,
+    thread_pool::Priority,
     LspError, Result,
 };
 
 /// A visitor for routing a raw JSON request to an appropriate handler function.
 ///
 /// Most requests are read-only and async and are handled on the threadpool
-/// (`on` method).
+/// (`on` method). Those the user waits on while typing skip ahead of the
+/// queue (`on_latency_sensitive`), while those which can take a long time let
+/// everything else go first (`on_long_running`).
 ///
 /// Some read-only requests are latency sensitive, and are immediately handled
 /// on the main loop thread (`on_sync`). These are typically typing-related
@@ -86,6 +89,45 @@ impl<'a> RequestDispatcher<'a> {
         &mut self,
         f: fn(GlobalStateSnapshot, R::Params) -> Result<R::Result>,
     ) -> &mut Self
+    where
+        R: lsp_types::request::Request + 'static,
+        R::Params: DeserializeOwned + panic::UnwindSafe + Send + fmt::Debug + 'static,
+        R::Result: Serialize + 'static,
+    {
+        self.on_with_priority::<R>(Priority::Normal, f)
+    }
+
+    /// Dispatches the request onto thread pool, ahead of other queued requests.
+    pub(crate) fn on_latency_sensitive<R>(
+        &mut self,
+        f: fn(GlobalStateSnapshot, R::Params) -> Result<R::Result>,
+    ) -> &mut Self
+    where
+        R: lsp_types::request::Request + 'static,
+        R::Params: DeserializeOwned + panic::UnwindSafe + Send + fmt::Debug + 'static,
+        R::Result: Serialize + 'static,
+    {
+        self.on_with_priority::<R>(Priority::High, f)
+    }
+
+    /// Dispatches the request onto thread pool, behind other queued requests.
+    pub(crate) fn on_long_running<R>(
+        &mut self,
+        f: fn(GlobalStateSnapshot, R::Params) -> Result<R::Result>,
+    ) -> &mut Self
+    where
+        R: lsp_types::request::Request + 'static,
+        R::Params: DeserializeOwned + panic::UnwindSafe + Send + fmt::Debug + 'static,
+        R::Result: Serialize + 'static,
+    {
+        self.on_with_priority::<R>(Priority::Low, f)
+    }
+
+    fn on_with_priority<R>(
+        &mut self,
+        priority: Priority,
+        f: fn(GlobalStateSnapshot, R::Params) -> Result<R::Result>,
+    ) -> &mut Self
     where
         R: lsp_types::request::Request + 'static,
         R::Params: DeserializeOwned + panic::UnwindSafe + Send + fmt::Debug + 'static,
@@ -96,7 +138,7 @@ impl<'a> RequestDispatcher<'a> {
             None => return self,
         };
 
-        self.global_state.task_pool.handle.spawn({
+        self.global_state.task_pool.handle.spawn(priority, {
             let world = self.global_state.snapshot();
             move || {
                 let result = panic::catch_unwind(move || {
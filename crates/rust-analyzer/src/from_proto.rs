@@ -1,6 +1,6 @@
 //! Conversion lsp_types types to rust-analyzer specific ones.
 use anyhow::format_err;
-use ide::{Annotation, AnnotationKind, AssistKind, LineCol, LineColUtf16};
+use ide::{Annotation, AnnotationKind, AssistKind, Color, ExpansionId, LineCol, LineColUtf16};
 use ide_db::base_db::{FileId, FilePosition, FileRange};
 use syntax::{TextRange, TextSize};
 use vfs::AbsPathBuf;
@@ -20,6 +20,11 @@ pub(crate) fn abs_path(url: &lsp_types::Url) -> Result<AbsPathBuf> {
 }
 
 pub(crate) fn vfs_path(url: &lsp_types::Url) -> Result<vfs::VfsPath> {
+    if url.scheme() != "file" {
+        // Virtual documents registered by the client are keyed by their url,
+        // see `to_proto::url_from_virtual_path` for the reverse.
+        return Ok(vfs::VfsPath::new_virtual_path(format!("/{}", url)));
+    }
     abs_path(url).map(vfs::VfsPath::from)
 }
 
@@ -50,6 +55,27 @@ pub(crate) fn file_id(snap: &GlobalStateSnapshot, url: &lsp_types::Url) -> Resul
     snap.url_to_file_id(url)
 }
 
+/// Returns the file and id of the macro expansion shown by a document created with
+/// `to_proto::expansion_url`.
+pub(crate) fn expansion(
+    snap: &GlobalStateSnapshot,
+    url: &lsp_types::Url,
+) -> Result<(FileId, ExpansionId)> {
+    let invalid = || invalid_params_error(format!("not a macro expansion: {}", url));
+    let query = |name: &str| {
+        url.query_pairs().find_map(|(key, value)| (key == name).then(|| value.into_owned()))
+    };
+    let source = query("source").and_then(|it| lsp_types::Url::parse(&it).ok());
+    let path = query("macro");
+    let occurrence = query("occurrence").and_then(|it| it.parse().ok());
+    match (source, path, occurrence) {
+        (Some(source), Some(path), Some(occurrence)) => {
+            Ok((file_id(snap, &source)?, ExpansionId { path, occurrence }))
+        }
+        _ => Err(invalid().into()),
+    }
+}
+
 pub(crate) fn file_position(
     snap: &GlobalStateSnapshot,
     tdpp: lsp_types::TextDocumentPositionParams,
@@ -71,6 +97,10 @@ pub(crate) fn file_range(
     Ok(FileRange { file_id, range })
 }
 
+pub(crate) fn color(color: lsp_types::Color) -> Color {
+    Color { red: color.red, green: color.green, blue: color.blue, alpha: color.alpha }
+}
+
 pub(crate) fn assist_kind(kind: lsp_types::CodeActionKind) -> Option<AssistKind> {
     let assist_kind = match &kind {
         k if k == &lsp_types::CodeActionKind::EMPTY => AssistKind::None,
@@ -3,18 +3,22 @@
 //!
 //! Each tick provides an immutable snapshot of the state as `WorldSnapshot`.
 
-use std::{sync::Arc, time::Instant};
+use std::{
+    sync::{atomic::AtomicBool, Arc},
+    time::Instant,
+};
 
 use crossbeam_channel::{unbounded, Receiver, Sender};
 use flycheck::FlycheckHandle;
 use ide::{Analysis, AnalysisHost, Cancellable, Change, FileId};
 use ide_db::base_db::{CrateId, FileLoader, SourceDatabase};
-use lsp_types::{SemanticTokens, Url};
+use lsp_types::{notification::Notification as _, Url};
 use parking_lot::{Mutex, RwLock};
 use proc_macro_api::ProcMacroServer;
 use project_model::{CargoWorkspace, ProjectWorkspace, Target, WorkspaceBuildScripts};
 use rustc_hash::FxHashMap;
-use vfs::AnchoredPathBuf;
+use serde::Serialize;
+use vfs::{AbsPath, AnchoredPathBuf, VfsPath};
 
 use crate::{
     config::Config,
@@ -26,8 +30,9 @@ use crate::{
     mem_docs::MemDocs,
     op_queue::OpQueue,
     reload::{self, SourceRootConfig},
+    semantic_tokens::SemanticTokensCache,
     thread_pool::TaskPool,
-    to_proto::url_from_abs_path,
+    to_proto::{url_from_abs_path, url_from_virtual_path},
     Result,
 };
 
@@ -56,9 +61,13 @@ pub(crate) struct GlobalState {
     pub(crate) analysis_host: AnalysisHost,
     pub(crate) diagnostics: DiagnosticCollection,
     pub(crate) mem_docs: MemDocs,
-    pub(crate) semantic_tokens_cache: Arc<Mutex<FxHashMap<Url, SemanticTokens>>>,
+    pub(crate) semantic_tokens_cache: Arc<Mutex<SemanticTokensCache>>,
+    /// Bumped whenever the crate graph is replaced, as it renumbers the crates.
+    pub(crate) crate_graph_generation: u64,
     pub(crate) shutdown_requested: bool,
     pub(crate) proc_macro_changed: bool,
+    /// Whether the user was already told that `rust-src` is missing.
+    pub(crate) rust_src_missing_reported: bool,
     pub(crate) last_reported_status: Option<lsp_ext::ServerStatusParams>,
     pub(crate) source_root_config: SourceRootConfig,
     pub(crate) proc_macro_client: Option<ProcMacroServer>,
@@ -104,6 +113,11 @@ pub(crate) struct GlobalState {
         OpQueue<(Arc<Vec<ProjectWorkspace>>, Vec<anyhow::Result<WorkspaceBuildScripts>>)>,
 
     pub(crate) prime_caches_queue: OpQueue<()>,
+    /// Set when the client cancels the current priming of the caches.
+    pub(crate) skip_prime_caches: Arc<AtomicBool>,
+    /// Files opened since the last loop turn, whose items are inferred in the
+    /// background once the change is applied.
+    pub(crate) opened_files_to_prime: Vec<VfsPath>,
 }
 
 /// An immutable snapshot of the world's state at a point in time.
@@ -112,7 +126,12 @@ pub(crate) struct GlobalStateSnapshot {
     pub(crate) analysis: Analysis,
     pub(crate) check_fixes: CheckFixes,
     mem_docs: MemDocs,
-    pub(crate) semantic_tokens_cache: Arc<Mutex<FxHashMap<Url, SemanticTokens>>>,
+    pub(crate) semantic_tokens_cache: Arc<Mutex<SemanticTokensCache>>,
+    /// The revision of `semantic_tokens_cache` this snapshot corresponds to.
+    pub(crate) semantic_tokens_revision: u64,
+    /// The generation of the crate graph of `analysis`.
+    pub(crate) crate_graph_generation: u64,
+    sender: Sender<lsp_server::Message>,
     vfs: Arc<RwLock<(vfs::Vfs, FxHashMap<FileId, LineEndings>)>>,
     pub(crate) workspaces: Arc<Vec<ProjectWorkspace>>,
 }
@@ -147,8 +166,10 @@ impl GlobalState {
             diagnostics: Default::default(),
             mem_docs: MemDocs::default(),
             semantic_tokens_cache: Arc::new(Default::default()),
+            crate_graph_generation: 0,
             shutdown_requested: false,
             proc_macro_changed: false,
+            rust_src_missing_reported: false,
             last_reported_status: None,
             source_root_config: SourceRootConfig::default(),
             proc_macro_client: None,
@@ -166,6 +187,8 @@ impl GlobalState {
             workspaces: Arc::new(Vec::new()),
             fetch_workspaces_queue: OpQueue::default(),
             prime_caches_queue: OpQueue::default(),
+            skip_prime_caches: Arc::default(),
+            opened_files_to_prime: Vec::new(),
 
             fetch_build_data_queue: OpQueue::default(),
         };
@@ -204,9 +227,9 @@ impl GlobalState {
                         self.fetch_workspaces_queue.request_op();
                     }
                     fs_changes.push((path, file.change_kind));
-                    if file.is_created_or_deleted() {
-                        has_structure_changes = true;
-                    }
+                }
+                if file.is_created_or_deleted() {
+                    has_structure_changes = true;
                 }
 
                 let text = if file.exists() {
@@ -231,6 +254,7 @@ impl GlobalState {
             change
         };
 
+        self.semantic_tokens_cache.lock().bump_revision();
         self.analysis_host.apply_change(change);
         true
     }
@@ -244,7 +268,21 @@ impl GlobalState {
             check_fixes: Arc::clone(&self.diagnostics.check_fixes),
             mem_docs: self.mem_docs.clone(),
             semantic_tokens_cache: Arc::clone(&self.semantic_tokens_cache),
+            semantic_tokens_revision: self.semantic_tokens_cache.lock().revision(),
+            crate_graph_generation: self.crate_graph_generation,
+            sender: self.sender.clone(),
+        }
+    }
+
+    /// Returns the path of a document the client wants us to track: either a
+    /// file, or a virtual document registered via
+    /// [`lsp_ext::RegisterVirtualDocument`].
+    pub(crate) fn doc_path(&self, url: &Url) -> Option<VfsPath> {
+        let path = from_proto::vfs_path(url).ok()?;
+        if path.as_path().is_none() && !self.source_root_config.virtual_docs.contains_key(&path) {
+            return None;
         }
+        Some(path)
     }
 
     pub(crate) fn send_request<R: lsp_types::request::Request>(
@@ -308,6 +346,28 @@ impl Drop for GlobalState {
 }
 
 impl GlobalStateSnapshot {
+    pub(crate) fn send_notification<N: lsp_types::notification::Notification>(
+        &self,
+        params: N::Params,
+    ) {
+        let not = lsp_server::Notification::new(N::METHOD.to_string(), params);
+        self.sender.send(not.into()).unwrap()
+    }
+
+    /// Reports a batch of results of a request which came with a
+    /// `partialResultToken`. They go out before the response to the request.
+    pub(crate) fn send_partial_result<T: Serialize>(
+        &self,
+        token: lsp_types::ProgressToken,
+        value: T,
+    ) {
+        let not = lsp_server::Notification::new(
+            lsp_types::notification::Progress::METHOD.to_string(),
+            serde_json::json!({ "token": token, "value": value }),
+        );
+        self.sender.send(not.into()).unwrap()
+    }
+
     pub(crate) fn url_to_file_id(&self, url: &Url) -> Result<FileId> {
         url_to_file_id(&self.vfs.read().0, url)
     }
@@ -316,6 +376,29 @@ impl GlobalStateSnapshot {
         file_id_to_url(&self.vfs.read().0, id)
     }
 
+    pub(crate) fn is_virtual_doc(&self, file_id: FileId) -> bool {
+        self.vfs.read().0.file_path(file_id).as_path().is_none()
+    }
+
+    /// Whether navigation from `from` may lead to `to`: unless enabled in the
+    /// config, it must not cross from one workspace folder into another.
+    pub(crate) fn is_reachable(&self, from: FileId, to: FileId) -> bool {
+        if self.config.cross_folder_navigation() {
+            return true;
+        }
+        let vfs = &self.vfs.read().0;
+        let folder = |file_id| {
+            vfs.file_path(file_id)
+                .as_path()
+                .and_then(|path| self.config.workspace_folder_for(path))
+                .map(AbsPath::to_path_buf)
+        };
+        match (folder(from), folder(to)) {
+            (Some(from), Some(to)) => from == to,
+            _ => true,
+        }
+    }
+
     pub(crate) fn file_line_index(&self, file_id: FileId) -> Cancellable<LineIndex> {
         let endings = self.vfs.read().1[&file_id];
         let index = self.analysis.file_line_index(file_id)?;
@@ -355,8 +438,10 @@ impl GlobalStateSnapshot {
 
 pub(crate) fn file_id_to_url(vfs: &vfs::Vfs, id: FileId) -> Url {
     let path = vfs.file_path(id);
-    let path = path.as_path().unwrap();
-    url_from_abs_path(path)
+    match path.as_path() {
+        Some(path) => url_from_abs_path(path),
+        None => url_from_virtual_path(&path).unwrap(),
+    }
 }
 
 pub(crate) fn url_to_file_id(vfs: &vfs::Vfs, url: &Url) -> Result<FileId> {
@@ -3,15 +3,17 @@
 //! `ide` crate.
 
 use std::{
-    io::Write as _,
+    io::{self, Write as _},
     process::{self, Stdio},
+    sync::Arc,
 };
 
 use anyhow::Context;
 use ide::{
-    AnnotationConfig, AssistKind, AssistResolveStrategy, FileId, FilePosition, FileRange,
-    HoverAction, HoverGotoTypeData, Query, RangeInfo, Runnable, RunnableKind, SingleResolve,
-    SourceChange, TextEdit,
+    AnnotationConfig, AssistKind, AssistResolveStrategy, BatchAssistScope, CallItem,
+    FeatureUsageKind, FileId, FilePosition, FileRange, HoverAction, HoverGotoTypeData,
+    NavigationTarget, Query, RangeInfo, ReferenceSearchResult, Runnable, RunnableKind,
+    SingleResolve, SourceChange, TextEdit,
 };
 use ide_db::SymbolKind;
 use lsp_server::ErrorCode;
@@ -20,30 +22,32 @@ use lsp_types::{
     CallHierarchyOutgoingCall, CallHierarchyOutgoingCallsParams, CallHierarchyPrepareParams,
     CodeLens, CompletionItem, Diagnostic, DiagnosticTag, DocumentFormattingParams, FoldingRange,
     FoldingRangeParams, HoverContents, Location, LocationLink, NumberOrString, Position,
-    PrepareRenameResponse, Range, RenameParams, SemanticTokensDeltaParams,
-    SemanticTokensFullDeltaResult, SemanticTokensParams, SemanticTokensRangeParams,
-    SemanticTokensRangeResult, SemanticTokensResult, SymbolInformation, SymbolTag,
-    TextDocumentIdentifier, Url, WorkspaceEdit,
+    PrepareRenameResponse, ProgressToken, Range, RenameParams, SemanticTokens, SemanticTokensDelta,
+    SemanticTokensDeltaParams, SemanticTokensFullDeltaResult, SemanticTokensParams,
+    SemanticTokensRangeParams, SemanticTokensRangeResult, SemanticTokensResult, SymbolInformation,
+    SymbolTag, TextDocumentIdentifier, Url, WorkspaceEdit,
 };
 use project_model::{ManifestPath, ProjectWorkspace, TargetKind};
+use serde::{Deserialize, Serialize};
 use serde_json::json;
 use stdx::{format_to, never};
 use syntax::{algo, ast, AstNode, TextRange, TextSize, T};
 use vfs::AbsPathBuf;
 
 use crate::{
+    cargo_manifest,
     cargo_target_spec::CargoTargetSpec,
     config::RustfmtConfig,
     diff::diff,
     from_proto,
     global_state::{GlobalState, GlobalStateSnapshot},
-    line_index::LineEndings,
+    line_index::{LineEndings, LineIndex},
     lsp_ext::{
         self, InlayHint, InlayHintsParams, PositionOrRange, ViewCrateGraphParams,
         WorkspaceSymbolParams,
     },
     lsp_utils::{all_edits_are_disjoint, invalid_params_error},
-    to_proto, LspError, Result,
+    semantic_tokens, to_proto, LspError, Result,
 };
 
 pub(crate) fn handle_analyzer_status(
@@ -98,6 +102,7 @@ pub(crate) fn handle_memory_usage(state: &mut GlobalState, _: ()) -> Result<Stri
 
 pub(crate) fn handle_shuffle_crate_graph(state: &mut GlobalState, _: ()) -> Result<()> {
     state.analysis_host.shuffle_crate_graph();
+    state.crate_graph_generation += 1;
     Ok(())
 }
 
@@ -123,6 +128,31 @@ pub(crate) fn handle_view_hir(
     Ok(res)
 }
 
+pub(crate) fn handle_view_desugared_body(
+    snap: GlobalStateSnapshot,
+    params: lsp_types::TextDocumentPositionParams,
+) -> Result<lsp_ext::DesugaredBody> {
+    let _p = profile::span("handle_view_desugared_body");
+    let position = from_proto::file_position(&snap, params)?;
+    let body = snap.analysis.view_desugared_body(position)?;
+    let line_index = LineIndex {
+        index: Arc::new(ide::LineIndex::new(&body.text)),
+        endings: LineEndings::Unix,
+        encoding: snap.config.offset_encoding(),
+    };
+    let mappings = body
+        .ranges
+        .into_iter()
+        .map(|(range, source)| {
+            Ok(lsp_ext::DesugaredMapping {
+                range: to_proto::range(&line_index, range),
+                location: to_proto::location(&snap, source)?,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(lsp_ext::DesugaredBody { text: body.text, mappings })
+}
+
 pub(crate) fn handle_view_item_tree(
     snap: GlobalStateSnapshot,
     params: lsp_ext::ViewItemTreeParams,
@@ -133,6 +163,26 @@ pub(crate) fn handle_view_item_tree(
     Ok(res)
 }
 
+pub(crate) fn handle_view_c_header(
+    snap: GlobalStateSnapshot,
+    params: lsp_ext::ViewCHeaderParams,
+) -> Result<String> {
+    let _p = profile::span("handle_view_c_header");
+    let file_id = from_proto::file_id(&snap, &params.text_document.uri)?;
+    let res = snap.analysis.view_c_header(file_id)?;
+    Ok(res)
+}
+
+pub(crate) fn handle_view_trait_solver_overflows(
+    snap: GlobalStateSnapshot,
+    params: lsp_types::TextDocumentPositionParams,
+) -> Result<String> {
+    let _p = profile::span("handle_view_trait_solver_overflows");
+    let position = from_proto::file_position(&snap, params)?;
+    let res = snap.analysis.view_trait_solver_overflows(position)?;
+    Ok(res)
+}
+
 pub(crate) fn handle_view_crate_graph(
     snap: GlobalStateSnapshot,
     params: ViewCrateGraphParams,
@@ -142,6 +192,140 @@ pub(crate) fn handle_view_crate_graph(
     Ok(dot)
 }
 
+pub(crate) fn handle_dead_code(
+    snap: GlobalStateSnapshot,
+    _: (),
+) -> Result<Vec<lsp_ext::DeadCodeItem>> {
+    let _p = profile::span("handle_dead_code");
+    let items = snap.analysis.dead_code(snap.config.prime_caches_num_threads())?;
+    items
+        .into_iter()
+        .map(|item| {
+            let frange =
+                FileRange { file_id: item.nav.file_id, range: item.nav.focus_or_full_range() };
+            Ok(lsp_ext::DeadCodeItem {
+                location: to_proto::location(&snap, frange)?,
+                name: item.nav.name.to_string(),
+                kind: item.kind.to_string(),
+                crate_name: item.crate_name,
+            })
+        })
+        .collect()
+}
+
+pub(crate) fn handle_unsafe_report(
+    snap: GlobalStateSnapshot,
+    _: (),
+) -> Result<Vec<lsp_ext::UnsafeReportItem>> {
+    let _p = profile::span("handle_unsafe_report");
+    let items = snap.analysis.unsafe_report()?;
+    items
+        .into_iter()
+        .map(|item| {
+            let operations = item
+                .operations
+                .iter()
+                .map(|op| {
+                    Ok(lsp_ext::UnsafeOperation {
+                        location: to_proto::location(&snap, op.range)?,
+                        description: op.description().to_string(),
+                    })
+                })
+                .collect::<Result<_>>()?;
+            Ok(lsp_ext::UnsafeReportItem {
+                location: to_proto::location(&snap, item.range)?,
+                kind: item.kind.as_str().to_string(),
+                name: item.name,
+                operations,
+                crate_name: item.crate_name,
+            })
+        })
+        .collect()
+}
+
+pub(crate) fn handle_feature_usages(
+    snap: GlobalStateSnapshot,
+    params: lsp_ext::FeatureUsagesParams,
+) -> Result<Vec<lsp_ext::FeatureUsage>> {
+    let _p = profile::span("handle_feature_usages");
+    let feature = params.feature.as_str();
+    let mut res = Vec::new();
+    for usage in snap.analysis.feature_usages(feature)? {
+        let kind = match usage.kind {
+            FeatureUsageKind::Cfg => "cfg",
+            FeatureUsageKind::CfgAttr => "cfg_attr",
+            FeatureUsageKind::CfgMacro => "cfg!",
+        };
+        let location = to_proto::location(&snap, usage.range)?;
+        res.push(lsp_ext::FeatureUsage { location, kind: kind.to_string() });
+    }
+
+    // Manifests aren't analyzed, but Cargo told us which features enable which.
+    for workspace in snap.workspaces.iter() {
+        let cargo = match workspace {
+            ProjectWorkspace::Cargo { cargo, .. } => cargo,
+            _ => continue,
+        };
+        for package in cargo.packages() {
+            let package = &cargo[package];
+            if !package.is_member {
+                continue;
+            }
+            let mut enabling: Vec<&str> = package
+                .features
+                .iter()
+                .filter(|(_, enables)| enables.iter().any(|it| activates(it, feature)))
+                .map(|(name, _)| name.as_str())
+                .collect();
+            if enabling.is_empty() {
+                continue;
+            }
+            enabling.sort_unstable();
+            let text = match std::fs::read_to_string(&package.manifest) {
+                Ok(it) => it,
+                Err(_) => continue,
+            };
+            let uri = to_proto::url_from_abs_path(&package.manifest);
+            for name in enabling {
+                if let Some((line, len)) = features_table_entry(&text, name) {
+                    let range = Range::new(Position::new(line, 0), Position::new(line, len));
+                    let location = Location::new(uri.clone(), range);
+                    res.push(lsp_ext::FeatureUsage { location, kind: "activation".to_string() });
+                }
+            }
+        }
+    }
+    return Ok(res);
+
+    /// Whether an entry of a `[features]` table, like `"dep:serde"` or
+    /// `"serde?/std"`, enables `feature`.
+    fn activates(entry: &str, feature: &str) -> bool {
+        let entry = entry.strip_prefix("dep:").unwrap_or(entry);
+        match entry.split_once('/') {
+            // `dep/feature` also enables the optional dependency `dep`, `dep?/feature` doesn't.
+            Some((dep, dep_feature)) => dep_feature == feature || dep == feature,
+            None => entry == feature,
+        }
+    }
+
+    /// The line of `name = [...]` in the `[features]` table, and its length.
+    fn features_table_entry(manifest: &str, name: &str) -> Option<(u32, u32)> {
+        let mut in_features = false;
+        for (idx, line) in manifest.lines().enumerate() {
+            let trimmed = line.trim();
+            if trimmed.starts_with('[') {
+                in_features = trimmed == "[features]";
+                continue;
+            }
+            let key = trimmed.split('=').next().map(|it| it.trim().trim_matches('"'));
+            if in_features && trimmed.contains('=') && key == Some(name) {
+                return Some((idx as u32, line.encode_utf16().count() as u32));
+            }
+        }
+        None
+    }
+}
+
 pub(crate) fn handle_expand_macro(
     snap: GlobalStateSnapshot,
     params: lsp_ext::ExpandMacroParams,
@@ -155,6 +339,116 @@ pub(crate) fn handle_expand_macro(
     Ok(res.map(|it| lsp_ext::ExpandedMacro { name: it.name, expansion: it.expansion }))
 }
 
+pub(crate) fn handle_expansion_uri(
+    snap: GlobalStateSnapshot,
+    params: lsp_types::TextDocumentPositionParams,
+) -> Result<Option<lsp_types::Url>> {
+    let _p = profile::span("handle_expansion_uri");
+    let position = from_proto::file_position(&snap, params)?;
+    let id = match snap.analysis.expansion_id(position)? {
+        Some(it) => it,
+        None => return Ok(None),
+    };
+    let document = snap.analysis.expansion_document(position.file_id, &id)?;
+    Ok(document.map(|it| to_proto::expansion_url(&snap, position.file_id, &id, &it.name)))
+}
+
+pub(crate) fn handle_view_expansion(
+    snap: GlobalStateSnapshot,
+    params: lsp_ext::ViewExpansionParams,
+) -> Result<Option<lsp_ext::ExpansionDocument>> {
+    let _p = profile::span("handle_view_expansion");
+    let (file_id, id) = from_proto::expansion(&snap, &params.uri)?;
+    let document = match snap.analysis.expansion_document(file_id, &id)? {
+        Some(it) => it,
+        None => return Ok(None),
+    };
+    let line_index = LineIndex {
+        index: Arc::new(ide::LineIndex::new(&document.text)),
+        endings: LineEndings::Unix,
+        encoding: snap.config.offset_encoding(),
+    };
+    let mappings = document
+        .ranges
+        .into_iter()
+        .map(|(range, source)| {
+            Ok(lsp_ext::ExpansionMapping {
+                range: to_proto::range(&line_index, range),
+                location: to_proto::location(&snap, source)?,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(Some(lsp_ext::ExpansionDocument { text: document.text, mappings }))
+}
+
+pub(crate) fn handle_type_of_range(
+    snap: GlobalStateSnapshot,
+    params: lsp_ext::TypeOfRangeParams,
+) -> Result<Option<lsp_ext::TypeOfRangeResult>> {
+    let _p = profile::span("handle_type_of_range");
+    let file_id = from_proto::file_id(&snap, &params.text_document.uri)?;
+    let line_index = snap.file_line_index(file_id)?;
+    let range = from_proto::text_range(&line_index, params.range)?;
+
+    let res = match snap.analysis.type_of_range(FileRange { file_id, range })? {
+        Some(it) => it,
+        None => return Ok(None),
+    };
+    Ok(Some(lsp_ext::TypeOfRangeResult {
+        range: to_proto::range(&line_index, res.range),
+        ty: res.info.original,
+        adjusted_type: res.info.adjusted,
+    }))
+}
+
+pub(crate) fn handle_explain_impl(
+    snap: GlobalStateSnapshot,
+    params: lsp_ext::ExplainImplParams,
+) -> Result<lsp_ext::ImplExplanation> {
+    let _p = profile::span("handle_explain_impl");
+    let file_id = from_proto::file_id(&snap, &params.text_document.uri)?;
+    let line_index = snap.file_line_index(file_id)?;
+    let range = from_proto::text_range(&line_index, params.range)?;
+
+    let explanation = snap
+        .analysis
+        .explain_impl(FileRange { file_id, range }, &params.trait_)?
+        .map_err(invalid_params_error)?;
+    let rendered = explanation.render();
+    let mut res = to_proto::impl_explanation(explanation);
+    res.rendered = Some(rendered);
+    Ok(res)
+}
+
+pub(crate) fn handle_trait_impl_matrix(
+    snap: GlobalStateSnapshot,
+    params: lsp_types::TextDocumentPositionParams,
+) -> Result<Option<lsp_ext::TraitImplMatrixResult>> {
+    let _p = profile::span("handle_trait_impl_matrix");
+    let position = from_proto::file_position(&snap, params)?;
+    let matrix = match snap.analysis.trait_impl_matrix(position)? {
+        Some(it) => it,
+        None => return Ok(None),
+    };
+    let derive_missing = match matrix.derive_missing {
+        Some(change) => Some(to_proto::workspace_edit(&snap, change)?),
+        None => None,
+    };
+    Ok(Some(lsp_ext::TraitImplMatrixResult {
+        ty: matrix.ty,
+        rows: matrix
+            .rows
+            .into_iter()
+            .map(|row| lsp_ext::TraitImplRow {
+                trait_: row.trait_,
+                implemented: row.implemented,
+                derivable: row.derivable,
+            })
+            .collect(),
+        derive_missing,
+    }))
+}
+
 pub(crate) fn handle_selection_range(
     snap: GlobalStateSnapshot,
     params: lsp_types::SelectionRangeParams,
@@ -409,11 +703,13 @@ pub(crate) fn handle_workspace_symbol(
         q.limit(128);
         q
     };
-    let mut res = exec_query(&snap, query)?;
-    if res.is_empty() && !all_symbols {
+    let partial_result_token = params.partial_result_params.partial_result_token.clone();
+    let mut res = Vec::new();
+    let found = exec_query(&snap, query, partial_result_token.as_ref(), &mut res)?;
+    if found == 0 && !all_symbols {
         let mut query = Query::new(params.query);
         query.limit(128);
-        res = exec_query(&snap, query)?;
+        exec_query(&snap, query, partial_result_token.as_ref(), &mut res)?;
     }
 
     return Ok(Some(res));
@@ -455,26 +751,65 @@ pub(crate) fn handle_workspace_symbol(
         (all_symbols, libs)
     }
 
-    fn exec_query(snap: &GlobalStateSnapshot, query: Query) -> Result<Vec<SymbolInformation>> {
-        let mut res = Vec::new();
-        for nav in snap.analysis.symbol_search(query)? {
-            let container_name = nav.container_name.as_ref().map(|v| v.to_string());
-
-            #[allow(deprecated)]
-            let info = SymbolInformation {
-                name: nav.name.to_string(),
-                kind: nav
-                    .kind
-                    .map(to_proto::symbol_kind)
-                    .unwrap_or(lsp_types::SymbolKind::VARIABLE),
-                tags: None,
-                location: to_proto::location_from_nav(snap, nav)?,
-                container_name,
-                deprecated: None,
+    /// Runs `query`, reporting the matches as partial results if there is a
+    /// token, and adding them to `res` otherwise. Returns the number of matches.
+    fn exec_query(
+        snap: &GlobalStateSnapshot,
+        query: Query,
+        partial_result_token: Option<&ProgressToken>,
+        res: &mut Vec<SymbolInformation>,
+    ) -> Result<usize> {
+        let token = match partial_result_token {
+            Some(it) => it,
+            None => {
+                let len = res.len();
+                for nav in snap.analysis.symbol_search(query)? {
+                    res.push(symbol_information(snap, nav)?);
+                }
+                return Ok(res.len() - len);
+            }
+        };
+
+        let mut found = 0;
+        let mut error = None;
+        snap.analysis.symbol_search_by_crate(query, &mut |navs| {
+            let symbols: Result<Vec<_>> =
+                navs.into_iter().map(|nav| symbol_information(snap, nav)).collect();
+            let symbols = match symbols {
+                Ok(it) => it,
+                Err(err) => {
+                    error = Some(err);
+                    return true;
+                }
             };
-            res.push(info);
+            found += symbols.len();
+            if !symbols.is_empty() {
+                snap.send_partial_result(token.clone(), symbols);
+            }
+            false
+        })?;
+        match error {
+            Some(err) => Err(err),
+            None => Ok(found),
         }
-        Ok(res)
+    }
+
+    fn symbol_information(
+        snap: &GlobalStateSnapshot,
+        nav: NavigationTarget,
+    ) -> Result<SymbolInformation> {
+        let container_name = nav.container_name.as_ref().map(|v| v.to_string());
+
+        #[allow(deprecated)]
+        let info = SymbolInformation {
+            name: nav.name.to_string(),
+            kind: nav.kind.map(to_proto::symbol_kind).unwrap_or(lsp_types::SymbolKind::VARIABLE),
+            tags: None,
+            location: to_proto::location_from_nav(snap, nav)?,
+            container_name,
+            deprecated: None,
+        };
+        Ok(info)
     }
 }
 
@@ -545,13 +880,34 @@ pub(crate) fn handle_goto_definition(
     params: lsp_types::GotoDefinitionParams,
 ) -> Result<Option<lsp_types::GotoDefinitionResponse>> {
     let _p = profile::span("handle_goto_definition");
+    if let Some(manifest_path) =
+        cargo_manifest_path(&params.text_document_position_params.text_document.uri)
+    {
+        let position = params.text_document_position_params.position;
+        let dep = match cargo_manifest::dependency_at(&snap, &manifest_path, position) {
+            Some(it) => it,
+            None => return Ok(None),
+        };
+        let links = dep
+            .lib_root()
+            .map(|root| LocationLink {
+                origin_selection_range: Some(dep.range),
+                target_uri: to_proto::url_from_abs_path(root),
+                target_range: Range::default(),
+                target_selection_range: Range::default(),
+            })
+            .into_iter()
+            .collect::<Vec<_>>();
+        return Ok(Some(links.into()));
+    }
     let position = from_proto::file_position(&snap, params.text_document_position_params)?;
     let nav_info = match snap.analysis.goto_definition(position)? {
         None => return Ok(None),
         Some(it) => it,
     };
     let src = FileRange { file_id: position.file_id, range: nav_info.range };
-    let res = to_proto::goto_definition_response(&snap, Some(src), nav_info.info)?;
+    let targets = reachable_targets(&snap, position.file_id, nav_info.info);
+    let res = to_proto::goto_definition_response(&snap, Some(src), targets)?;
     Ok(Some(res))
 }
 
@@ -566,7 +922,8 @@ pub(crate) fn handle_goto_declaration(
         Some(it) => it,
     };
     let src = FileRange { file_id: position.file_id, range: nav_info.range };
-    let res = to_proto::goto_definition_response(&snap, Some(src), nav_info.info)?;
+    let targets = reachable_targets(&snap, position.file_id, nav_info.info);
+    let res = to_proto::goto_definition_response(&snap, Some(src), targets)?;
     Ok(Some(res))
 }
 
@@ -581,7 +938,8 @@ pub(crate) fn handle_goto_implementation(
         Some(it) => it,
     };
     let src = FileRange { file_id: position.file_id, range: nav_info.range };
-    let res = to_proto::goto_definition_response(&snap, Some(src), nav_info.info)?;
+    let targets = reachable_targets(&snap, position.file_id, nav_info.info);
+    let res = to_proto::goto_definition_response(&snap, Some(src), targets)?;
     Ok(Some(res))
 }
 
@@ -596,10 +954,68 @@ pub(crate) fn handle_goto_type_definition(
         Some(it) => it,
     };
     let src = FileRange { file_id: position.file_id, range: nav_info.range };
-    let res = to_proto::goto_definition_response(&snap, Some(src), nav_info.info)?;
+    let targets = reachable_targets(&snap, position.file_id, nav_info.info);
+    let res = to_proto::goto_definition_response(&snap, Some(src), targets)?;
     Ok(Some(res))
 }
 
+pub(crate) fn handle_trait_implementations(
+    snap: GlobalStateSnapshot,
+    params: lsp_ext::TraitImplementationsParams,
+) -> Result<Vec<lsp_ext::TraitImplementationsGroup>> {
+    let _p = profile::span("handle_trait_implementations");
+    let position = from_proto::file_position(&snap, params.position)?;
+    let crates = match snap.analysis.implementing_crates(position)? {
+        None => return Ok(Vec::new()),
+        Some(it) => it,
+    };
+    crates
+        .into_iter()
+        .filter(|krate| params.crate_id.map_or(true, |id| krate.crate_id.0 == id))
+        .map(|krate| {
+            // Dependencies are only searched on demand, which saves resolving the names of
+            // the whole crate graph to show the impls of the workspace.
+            let implementations = if params.crate_id.is_some() || !krate.is_library {
+                let navs = snap
+                    .analysis
+                    .trait_impls_in_crate(position, krate.crate_id)?
+                    .unwrap_or_default();
+                let locations = navs
+                    .into_iter()
+                    .map(|nav| to_proto::location_from_nav(&snap, nav))
+                    .collect::<Result<Vec<_>>>()?;
+                Some(locations)
+            } else {
+                None
+            };
+            Ok(lsp_ext::TraitImplementationsGroup {
+                crate_id: krate.crate_id.0,
+                crate_name: krate.name,
+                is_library: krate.is_library,
+                implementations,
+            })
+        })
+        .collect()
+}
+
+/// The path of the document, if it's a `Cargo.toml`.
+fn cargo_manifest_path(uri: &Url) -> Option<ManifestPath> {
+    let file_path = uri.to_file_path().ok()?;
+    if file_path.file_name().unwrap_or_default() != "Cargo.toml" {
+        return None;
+    }
+    let abs_path_buf = AbsPathBuf::try_from(file_path).ok()?;
+    ManifestPath::try_from(abs_path_buf).ok()
+}
+
+fn reachable_targets(
+    snap: &GlobalStateSnapshot,
+    from: FileId,
+    targets: Vec<NavigationTarget>,
+) -> Vec<NavigationTarget> {
+    targets.into_iter().filter(|nav| snap.is_reachable(from, nav.file_id)).collect()
+}
+
 pub(crate) fn handle_parent_module(
     snap: GlobalStateSnapshot,
     params: lsp_types::TextDocumentPositionParams,
@@ -889,6 +1305,65 @@ pub(crate) fn handle_folding_range(
     Ok(Some(res))
 }
 
+pub(crate) fn handle_linked_editing_range(
+    snap: GlobalStateSnapshot,
+    params: lsp_types::LinkedEditingRangeParams,
+) -> Result<Option<lsp_types::LinkedEditingRanges>> {
+    let _p = profile::span("handle_linked_editing_range");
+    let position = from_proto::file_position(&snap, params.text_document_position_params)?;
+    let ranges = match snap.analysis.linked_editing_ranges(position)? {
+        Some(it) => it,
+        None => return Ok(None),
+    };
+    let line_index = snap.file_line_index(position.file_id)?;
+    let ranges = ranges.into_iter().map(|range| to_proto::range(&line_index, range)).collect();
+    Ok(Some(lsp_types::LinkedEditingRanges { ranges, word_pattern: None }))
+}
+
+pub(crate) fn handle_document_color(
+    snap: GlobalStateSnapshot,
+    params: lsp_types::DocumentColorParams,
+) -> Result<Vec<lsp_types::ColorInformation>> {
+    let _p = profile::span("handle_document_color");
+    let config = match snap.config.color_provider() {
+        Some(it) => it,
+        None => return Ok(Vec::new()),
+    };
+    let file_id = from_proto::file_id(&snap, &params.text_document.uri)?;
+    let line_index = snap.file_line_index(file_id)?;
+    let res = snap
+        .analysis
+        .document_colors(&config, file_id)?
+        .into_iter()
+        .map(|it| to_proto::color_information(&line_index, it))
+        .collect();
+    Ok(res)
+}
+
+pub(crate) fn handle_color_presentation(
+    snap: GlobalStateSnapshot,
+    params: lsp_types::ColorPresentationParams,
+) -> Result<Vec<lsp_types::ColorPresentation>> {
+    let _p = profile::span("handle_color_presentation");
+    let config = match snap.config.color_provider() {
+        Some(it) => it,
+        None => return Ok(Vec::new()),
+    };
+    let frange = from_proto::file_range(&snap, params.text_document, params.range)?;
+    let color = from_proto::color(params.color);
+    let res = snap
+        .analysis
+        .color_presentations(&config, frange, color)?
+        .into_iter()
+        .map(|label| lsp_types::ColorPresentation {
+            text_edit: Some(lsp_types::TextEdit::new(params.range, label.clone())),
+            label,
+            additional_text_edits: None,
+        })
+        .collect();
+    Ok(res)
+}
+
 pub(crate) fn handle_signature_help(
     snap: GlobalStateSnapshot,
     params: lsp_types::SignatureHelpParams,
@@ -909,6 +1384,27 @@ pub(crate) fn handle_hover(
     params: lsp_ext::HoverParams,
 ) -> Result<Option<lsp_ext::Hover>> {
     let _p = profile::span("handle_hover");
+    if let Some(manifest_path) = cargo_manifest_path(&params.text_document.uri) {
+        let position = match params.position {
+            PositionOrRange::Position(position) => position,
+            PositionOrRange::Range(range) => range.start,
+        };
+        let dep = match cargo_manifest::dependency_at(&snap, &manifest_path, position) {
+            Some(it) => it,
+            None => return Ok(None),
+        };
+        let hover = lsp_ext::Hover {
+            hover: lsp_types::Hover {
+                contents: HoverContents::Markup(lsp_types::MarkupContent {
+                    kind: lsp_types::MarkupKind::Markdown,
+                    value: dep.hover_markup(),
+                }),
+                range: Some(dep.range),
+            },
+            actions: Vec::new(),
+        };
+        return Ok(Some(hover));
+    }
     let range = match params.position {
         PositionOrRange::Position(position) => Range::new(position, position),
         PositionOrRange::Range(range) => range,
@@ -984,36 +1480,108 @@ pub(crate) fn handle_references(
     params: lsp_types::ReferenceParams,
 ) -> Result<Option<Vec<Location>>> {
     let _p = profile::span("handle_references");
-    let position = from_proto::file_position(&snap, params.text_document_position)?;
-
-    let refs = match snap.analysis.find_all_refs(position, None)? {
-        None => return Ok(None),
-        Some(refs) => refs,
+    let search = ReferenceSearch {
+        text_document_position: params.text_document_position,
+        include_declaration: params.context.include_declaration,
+        skip: 0,
+        crate_graph_generation: snap.crate_graph_generation,
     };
+    search.run(&snap, params.partial_result_params.partial_result_token)
+}
+
+pub(crate) fn handle_continue_search(
+    snap: GlobalStateSnapshot,
+    params: lsp_ext::ContinueSearchParams,
+) -> Result<Option<Vec<Location>>> {
+    let _p = profile::span("handle_continue_search");
+    let search: ReferenceSearch = serde_json::from_str(&params.continue_token)
+        .map_err(|_| invalid_params_error("invalid continue token".to_string()))?;
+    if search.crate_graph_generation != snap.crate_graph_generation {
+        // `skip` counts crates in the order of the old crate graph.
+        return Err(LspError::new(
+            ErrorCode::ContentModified as i32,
+            "the crates changed since the search was truncated".to_string(),
+        )
+        .into());
+    }
+    search.run(&snap, params.partial_result_params.partial_result_token)
+}
+
+/// A reference search, which doubles as the continue token of a search that
+/// hit the `references.limit`.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ReferenceSearch {
+    text_document_position: lsp_types::TextDocumentPositionParams,
+    include_declaration: bool,
+    /// Number of per-crate batches already reported.
+    skip: usize,
+    /// The crate graph `skip` refers to.
+    crate_graph_generation: u64,
+}
+
+impl ReferenceSearch {
+    fn run(
+        self,
+        snap: &GlobalStateSnapshot,
+        partial_result_token: Option<ProgressToken>,
+    ) -> Result<Option<Vec<Location>>> {
+        let position = from_proto::file_position(snap, self.text_document_position.clone())?;
+        let limit = snap.config.references_limit();
+
+        let mut locations = Vec::new();
+        let mut found = 0;
+        let mut truncated = false;
+        let covered = snap.analysis.find_all_refs_by_crate(position, self.skip, &mut |refs| {
+            let batch = reference_locations(snap, position.file_id, refs, self.include_declaration);
+            found += batch.len();
+            match &partial_result_token {
+                Some(token) if !batch.is_empty() => snap.send_partial_result(token.clone(), batch),
+                _ => locations.extend(batch),
+            }
+            truncated = limit.map_or(false, |limit| found >= limit);
+            truncated
+        })?;
+        let covered = match covered {
+            Some(it) => it,
+            None => return Ok(None),
+        };
+
+        if truncated {
+            let rest = ReferenceSearch { skip: covered, ..self };
+            snap.send_notification::<lsp_ext::SearchTruncated>(lsp_ext::SearchTruncatedParams {
+                text_document_position: rest.text_document_position.clone(),
+                partial_result_token,
+                continue_token: serde_json::to_string(&rest)?,
+            });
+        }
+        Ok(Some(locations))
+    }
+}
 
-    let include_declaration = params.context.include_declaration;
-    let locations = refs
+fn reference_locations(
+    snap: &GlobalStateSnapshot,
+    from: FileId,
+    refs: ReferenceSearchResult,
+    include_declaration: bool,
+) -> Vec<Location> {
+    let decl = if include_declaration {
+        refs.declaration.map(|decl| FileRange {
+            file_id: decl.nav.file_id,
+            range: decl.nav.focus_or_full_range(),
+        })
+    } else {
+        None
+    };
+    refs.references
         .into_iter()
-        .flat_map(|refs| {
-            let decl = if include_declaration {
-                refs.declaration.map(|decl| FileRange {
-                    file_id: decl.nav.file_id,
-                    range: decl.nav.focus_or_full_range(),
-                })
-            } else {
-                None
-            };
-            refs.references
-                .into_iter()
-                .flat_map(|(file_id, refs)| {
-                    refs.into_iter().map(move |(range, _)| FileRange { file_id, range })
-                })
-                .chain(decl)
+        .flat_map(|(file_id, refs)| {
+            refs.into_iter().map(move |(range, _)| FileRange { file_id, range })
         })
-        .filter_map(|frange| to_proto::location(&snap, frange).ok())
-        .collect();
-
-    Ok(Some(locations))
+        .chain(decl)
+        .filter(|frange| snap.is_reachable(from, frange.file_id))
+        .filter_map(|frange| to_proto::location(snap, frange).ok())
+        .collect()
 }
 
 pub(crate) fn handle_formatting(
@@ -1031,7 +1599,27 @@ pub(crate) fn handle_range_formatting(
 ) -> Result<Option<Vec<lsp_types::TextEdit>>> {
     let _p = profile::span("handle_range_formatting");
 
-    run_rustfmt(&snap, params.text_document, Some(params.range))
+    let rustfmt_config = match from_proto::abs_path(&params.text_document.uri) {
+        Ok(path) => snap.config.rustfmt_for(&path),
+        Err(_) => snap.config.rustfmt(),
+    };
+    if let RustfmtConfig::Rustfmt { enable_range_formatting: true, .. } = rustfmt_config {
+        match run_rustfmt(&snap, params.text_document.clone(), Some(params.range)) {
+            Err(err) if err.is::<RustfmtUnavailable>() => {
+                tracing::info!("{}, falling back to reindenting the range", err);
+            }
+            res => return res,
+        }
+    }
+
+    // Without rustfmt's range formatting, we can at least fix up indentation.
+    let frange = from_proto::file_range(&snap, params.text_document, params.range)?;
+    let line_index = snap.file_line_index(frange.file_id)?;
+    let edit = snap.analysis.reindent(frange)?;
+    if edit.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(to_proto::text_edit_vec(&line_index, edit)))
 }
 
 pub(crate) fn handle_code_action(
@@ -1160,6 +1748,31 @@ pub(crate) fn handle_code_action_resolve(
     Ok(code_action)
 }
 
+pub(crate) fn handle_batch_code_action(
+    snap: GlobalStateSnapshot,
+    params: lsp_ext::BatchCodeActionParams,
+) -> Result<lsp_ext::BatchCodeActionResult> {
+    let _p = profile::span("handle_batch_code_action");
+    let file_id = from_proto::file_id(&snap, &params.text_document.uri)?;
+    let scope = match params.scope {
+        lsp_ext::BatchCodeActionScope::File => BatchAssistScope::File,
+        lsp_ext::BatchCodeActionScope::Crate => BatchAssistScope::Crate,
+        lsp_ext::BatchCodeActionScope::Workspace => BatchAssistScope::Workspace,
+    };
+    let res = snap.analysis.batch_assist(
+        &snap.config.assist(),
+        &snap.config.diagnostics(),
+        &params.assist_id,
+        scope,
+        file_id,
+    )?;
+    Ok(lsp_ext::BatchCodeActionResult {
+        edit: to_proto::workspace_edit(&snap, res.source_change)?,
+        applied: res.applied,
+        skipped: res.skipped,
+    })
+}
+
 fn parse_action_id(action_id: &str) -> Result<(usize, SingleResolve), String> {
     let id_parts = action_id.split(':').collect::<Vec<_>>();
     match id_parts.as_slice() {
@@ -1304,14 +1917,37 @@ pub(crate) fn publish_diagnostics(
             }),
             source: Some("rust-analyzer".to_string()),
             message: d.message,
-            related_information: None,
-            tags: if d.unused { Some(vec![DiagnosticTag::UNNECESSARY]) } else { None },
+            related_information: if d.related.is_empty() {
+                None
+            } else {
+                Some(
+                    d.related
+                        .into_iter()
+                        .filter_map(|(range, message)| {
+                            let location = to_proto::location(snap, range).ok()?;
+                            Some(lsp_types::DiagnosticRelatedInformation { location, message })
+                        })
+                        .collect(),
+                )
+            },
+            tags: diagnostic_tags(d.unused, d.deprecated),
             data: None,
         })
         .collect();
     Ok(diagnostics)
 }
 
+fn diagnostic_tags(unused: bool, deprecated: bool) -> Option<Vec<DiagnosticTag>> {
+    let mut tags = Vec::new();
+    if unused {
+        tags.push(DiagnosticTag::UNNECESSARY);
+    }
+    if deprecated {
+        tags.push(DiagnosticTag::DEPRECATED);
+    }
+    (!tags.is_empty()).then(|| tags)
+}
+
 pub(crate) fn handle_inlay_hints(
     snap: GlobalStateSnapshot,
     params: InlayHintsParams,
@@ -1339,6 +1975,41 @@ pub(crate) fn handle_inlay_hints(
         .collect())
 }
 
+pub(crate) fn handle_inline_values(
+    snap: GlobalStateSnapshot,
+    params: lsp_ext::InlineValueParams,
+) -> Result<Option<Vec<lsp_ext::InlineValueVariableLookup>>> {
+    let _p = profile::span("handle_inline_values");
+    let file_id = from_proto::file_id(&snap, &params.text_document.uri)?;
+    let line_index = snap.file_line_index(file_id)?;
+    let range = from_proto::text_range(&line_index, params.range)?;
+    let stopped_at = from_proto::text_range(&line_index, params.context.stopped_location)?;
+    let res = snap
+        .analysis
+        .inline_values(file_id, range, stopped_at)?
+        .into_iter()
+        .map(|it| lsp_ext::InlineValueVariableLookup {
+            range: to_proto::range(&line_index, it.range),
+            variable_name: Some(it.name),
+            case_sensitive_lookup: true,
+        })
+        .collect();
+    Ok(Some(res))
+}
+
+pub(crate) fn handle_moniker(
+    snap: GlobalStateSnapshot,
+    params: lsp_types::MonikerParams,
+) -> Result<Option<Vec<lsp_types::Moniker>>> {
+    let _p = profile::span("handle_moniker");
+    let position = from_proto::file_position(&snap, params.text_document_position_params)?;
+    let monikers = match snap.analysis.moniker(position)? {
+        Some(it) => it.info,
+        None => return Ok(None),
+    };
+    Ok(Some(monikers.into_iter().map(to_proto::moniker).collect()))
+}
+
 pub(crate) fn handle_call_hierarchy_prepare(
     snap: GlobalStateSnapshot,
     params: CallHierarchyPrepareParams,
@@ -1372,28 +2043,49 @@ pub(crate) fn handle_call_hierarchy_incoming(
     let frange = from_proto::file_range(&snap, doc, item.selection_range)?;
     let fpos = FilePosition { file_id: frange.file_id, offset: frange.range.start() };
 
-    let call_items = match snap.analysis.incoming_calls(fpos)? {
-        None => return Ok(None),
-        Some(it) => it,
-    };
-
+    let partial_result_token = params.partial_result_params.partial_result_token;
     let mut res = vec![];
-
-    for call_item in call_items.into_iter() {
-        let file_id = call_item.target.file_id;
-        let line_index = snap.file_line_index(file_id)?;
-        let item = to_proto::call_hierarchy_item(&snap, call_item.target)?;
-        res.push(CallHierarchyIncomingCall {
-            from: item,
-            from_ranges: call_item
-                .ranges
-                .into_iter()
-                .map(|it| to_proto::range(&line_index, it))
-                .collect(),
-        });
+    let mut error = None;
+    snap.analysis.incoming_calls_by_crate(fpos, &mut |call_items| {
+        let calls = match incoming_calls(&snap, call_items) {
+            Ok(it) => it,
+            Err(err) => {
+                error = Some(err);
+                return true;
+            }
+        };
+        match &partial_result_token {
+            Some(token) if !calls.is_empty() => snap.send_partial_result(token.clone(), calls),
+            _ => res.extend(calls),
+        }
+        false
+    })?;
+    if let Some(err) = error {
+        return Err(err);
     }
 
-    Ok(Some(res))
+    return Ok(Some(res));
+
+    fn incoming_calls(
+        snap: &GlobalStateSnapshot,
+        call_items: Vec<CallItem>,
+    ) -> Result<Vec<CallHierarchyIncomingCall>> {
+        let mut res = vec![];
+        for call_item in call_items.into_iter() {
+            let file_id = call_item.target.file_id;
+            let line_index = snap.file_line_index(file_id)?;
+            let item = to_proto::call_hierarchy_item(snap, call_item.target)?;
+            res.push(CallHierarchyIncomingCall {
+                from: item,
+                from_ranges: call_item
+                    .ranges
+                    .into_iter()
+                    .map(|it| to_proto::range(&line_index, it))
+                    .collect(),
+            });
+        }
+        Ok(res)
+    }
 }
 
 pub(crate) fn handle_call_hierarchy_outgoing(
@@ -1437,17 +2129,21 @@ pub(crate) fn handle_semantic_tokens_full(
 ) -> Result<Option<SemanticTokensResult>> {
     let _p = profile::span("handle_semantic_tokens_full");
 
-    let file_id = from_proto::file_id(&snap, &params.text_document.uri)?;
-    let text = snap.analysis.file_text(file_id)?;
-    let line_index = snap.file_line_index(file_id)?;
+    let url = params.text_document.uri;
+    if let Some(cached) =
+        snap.semantic_tokens_cache.lock().get_fresh(&url, snap.semantic_tokens_revision)
+    {
+        return Ok(Some(cached.clone().into()));
+    }
 
-    let highlights = snap.analysis.highlight(file_id)?;
-    let highlight_strings = snap.config.highlighting_strings();
-    let semantic_tokens =
-        to_proto::semantic_tokens(&text, &line_index, highlights, highlight_strings);
+    let semantic_tokens = compute_semantic_tokens(&snap, &url)?;
 
     // Unconditionally cache the tokens
-    snap.semantic_tokens_cache.lock().insert(params.text_document.uri, semantic_tokens.clone());
+    snap.semantic_tokens_cache.lock().insert(
+        url,
+        snap.semantic_tokens_revision,
+        semantic_tokens.clone(),
+    );
 
     Ok(Some(semantic_tokens.into()))
 }
@@ -1458,29 +2154,34 @@ pub(crate) fn handle_semantic_tokens_full_delta(
 ) -> Result<Option<SemanticTokensFullDeltaResult>> {
     let _p = profile::span("handle_semantic_tokens_full_delta");
 
-    let file_id = from_proto::file_id(&snap, &params.text_document.uri)?;
-    let text = snap.analysis.file_text(file_id)?;
-    let line_index = snap.file_line_index(file_id)?;
-
-    let highlights = snap.analysis.highlight(file_id)?;
-    let highlight_strings = snap.config.highlighting_strings();
-    let semantic_tokens =
-        to_proto::semantic_tokens(&text, &line_index, highlights, highlight_strings);
+    let url = params.text_document.uri;
+    let revision = snap.semantic_tokens_revision;
+    let previous = {
+        let cache = snap.semantic_tokens_cache.lock();
+        match cache.get(&url) {
+            Some(cached) if cached.result_id.as_ref() == Some(&params.previous_result_id) => {
+                // Nothing changed since the client got these tokens.
+                if cache.get_fresh(&url, revision).is_some() {
+                    return Ok(Some(
+                        SemanticTokensDelta { result_id: cached.result_id.clone(), edits: vec![] }
+                            .into(),
+                    ));
+                }
+                Some(cached.clone())
+            }
+            _ => None,
+        }
+    };
 
-    let mut cache = snap.semantic_tokens_cache.lock();
-    let cached_tokens = cache.entry(params.text_document.uri).or_default();
+    let semantic_tokens = compute_semantic_tokens(&snap, &url)?;
+    snap.semantic_tokens_cache.lock().insert(url, revision, semantic_tokens.clone());
 
-    if let Some(prev_id) = &cached_tokens.result_id {
-        if *prev_id == params.previous_result_id {
-            let delta = to_proto::semantic_token_delta(cached_tokens, &semantic_tokens);
-            *cached_tokens = semantic_tokens;
-            return Ok(Some(delta.into()));
+    match previous {
+        Some(previous) => {
+            Ok(Some(to_proto::semantic_token_delta(&previous, &semantic_tokens).into()))
         }
+        None => Ok(Some(semantic_tokens.into())),
     }
-
-    *cached_tokens = semantic_tokens.clone();
-
-    Ok(Some(semantic_tokens.into()))
 }
 
 pub(crate) fn handle_semantic_tokens_range(
@@ -1489,6 +2190,17 @@ pub(crate) fn handle_semantic_tokens_range(
 ) -> Result<Option<SemanticTokensRangeResult>> {
     let _p = profile::span("handle_semantic_tokens_range");
 
+    // Serve the range from the full token set if it is still valid, so that
+    // scrolling through a file doesn't redo the highlighting work.
+    if let Some(cached) = snap
+        .semantic_tokens_cache
+        .lock()
+        .get_fresh(&params.text_document.uri, snap.semantic_tokens_revision)
+    {
+        let data = semantic_tokens::tokens_in_range(&cached.data, params.range);
+        return Ok(Some(SemanticTokens { result_id: None, data }.into()));
+    }
+
     let frange = from_proto::file_range(&snap, params.text_document, params.range)?;
     let text = snap.analysis.file_text(frange.file_id)?;
     let line_index = snap.file_line_index(frange.file_id)?;
@@ -1500,6 +2212,16 @@ pub(crate) fn handle_semantic_tokens_range(
     Ok(Some(semantic_tokens.into()))
 }
 
+fn compute_semantic_tokens(snap: &GlobalStateSnapshot, url: &Url) -> Result<SemanticTokens> {
+    let file_id = from_proto::file_id(snap, url)?;
+    let text = snap.analysis.file_text(file_id)?;
+    let line_index = snap.file_line_index(file_id)?;
+
+    let highlights = snap.analysis.highlight(file_id)?;
+    let highlight_strings = snap.config.highlighting_strings();
+    Ok(to_proto::semantic_tokens(&text, &line_index, highlights, highlight_strings))
+}
+
 pub(crate) fn handle_open_docs(
     snap: GlobalStateSnapshot,
     params: lsp_types::TextDocumentPositionParams,
@@ -1714,7 +2436,11 @@ fn run_rustfmt(
 
     let line_index = snap.file_line_index(file_id)?;
 
-    let mut rustfmt = match snap.config.rustfmt() {
+    let rustfmt_config = match from_proto::abs_path(&text_document.uri) {
+        Ok(path) => snap.config.rustfmt_for(&path),
+        Err(_) => snap.config.rustfmt(),
+    };
+    let mut rustfmt = match rustfmt_config {
         RustfmtConfig::Rustfmt { extra_args, enable_range_formatting } => {
             let mut cmd = process::Command::new(toolchain::rustfmt());
             cmd.args(extra_args);
@@ -1779,12 +2505,16 @@ fn run_rustfmt(
         }
     };
 
-    let mut rustfmt = rustfmt
-        .stdin(Stdio::piped())
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-        .context(format!("Failed to spawn {:?}", rustfmt))?;
+    let mut rustfmt =
+        match rustfmt.stdin(Stdio::piped()).stdout(Stdio::piped()).stderr(Stdio::piped()).spawn() {
+            Ok(it) => it,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                return Err(
+                    RustfmtUnavailable(format!("Failed to spawn {:?}: {}", rustfmt, err)).into()
+                )
+            }
+            Err(err) => Err(err).context(format!("Failed to spawn {:?}", rustfmt))?,
+        };
 
     rustfmt.stdin.as_mut().unwrap().write_all(file.as_bytes())?;
 
@@ -1796,8 +2526,16 @@ fn run_rustfmt(
         let rustfmt_not_installed =
             captured_stderr.contains("not installed") || captured_stderr.contains("not available");
 
+        if rustfmt_not_installed {
+            return Err(RustfmtUnavailable(format!(
+                "rustfmt is not available: {}",
+                captured_stderr.trim()
+            ))
+            .into());
+        }
+
         return match output.status.code() {
-            Some(1) if !rustfmt_not_installed => {
+            Some(1) => {
                 // While `rustfmt` doesn't have a specific exit code for parse errors this is the
                 // likely cause exiting with 1. Most Language Servers swallow parse errors on
                 // formatting because otherwise an error is surfaced to the user on top of the
@@ -1840,3 +2578,15 @@ fn run_rustfmt(
         Ok(Some(to_proto::text_edit_vec(&line_index, diff(&file, &new_text))))
     }
 }
+
+/// The error returned by [`run_rustfmt`] if there's no rustfmt to run.
+#[derive(Debug)]
+struct RustfmtUnavailable(String);
+
+impl std::fmt::Display for RustfmtUnavailable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for RustfmtUnavailable {}
@@ -24,6 +24,7 @@ mod dispatch;
 mod handlers;
 mod caps;
 mod cargo_target_spec;
+mod cargo_manifest;
 mod to_proto;
 mod from_proto;
 mod semantic_tokens;
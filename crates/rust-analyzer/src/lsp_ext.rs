@@ -70,6 +70,30 @@ impl Request for ViewHir {
     const METHOD: &'static str = "rust-analyzer/viewHir";
 }
 
+pub enum ViewDesugaredBody {}
+
+impl Request for ViewDesugaredBody {
+    type Params = lsp_types::TextDocumentPositionParams;
+    type Result = DesugaredBody;
+    const METHOD: &'static str = "rust-analyzer/viewDesugaredBody";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DesugaredBody {
+    pub text: String,
+    pub mappings: Vec<DesugaredMapping>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DesugaredMapping {
+    /// The range in `DesugaredBody::text`.
+    pub range: Range,
+    /// The source expression this range was printed from.
+    pub location: lsp_types::Location,
+}
+
 #[derive(Deserialize, Serialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct ViewCrateGraphParams {
@@ -85,6 +109,103 @@ impl Request for ViewCrateGraph {
     const METHOD: &'static str = "rust-analyzer/viewCrateGraph";
 }
 
+pub enum DeadCode {}
+
+impl Request for DeadCode {
+    type Params = ();
+    type Result = Vec<DeadCodeItem>;
+    const METHOD: &'static str = "rust-analyzer/deadCode";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DeadCodeItem {
+    /// The name of the unused item, in its declaration.
+    pub location: lsp_types::Location,
+    pub name: String,
+    /// What kind of item this is, like `function` or `struct`.
+    pub kind: String,
+    pub crate_name: String,
+}
+
+pub enum UnsafeReport {}
+
+impl Request for UnsafeReport {
+    type Params = ();
+    type Result = Vec<UnsafeReportItem>;
+    const METHOD: &'static str = "rust-analyzer/unsafeReport";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UnsafeReportItem {
+    /// The name of the item, or the whole block for `unsafe` blocks.
+    pub location: lsp_types::Location,
+    /// One of `unsafe fn`, `unsafe block`, `unsafe trait` or `unsafe impl`.
+    pub kind: String,
+    pub name: String,
+    pub operations: Vec<UnsafeOperation>,
+    pub crate_name: String,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UnsafeOperation {
+    pub location: lsp_types::Location,
+    /// What the operation is, like `dereference of raw pointer`.
+    pub description: String,
+}
+
+pub enum FeatureUsages {}
+
+impl Request for FeatureUsages {
+    type Params = FeatureUsagesParams;
+    type Result = Vec<FeatureUsage>;
+    const METHOD: &'static str = "rust-analyzer/featureUsages";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct FeatureUsagesParams {
+    pub feature: String,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct FeatureUsage {
+    pub location: lsp_types::Location,
+    /// `cfg`, `cfg_attr`, `cfg!`, or `activation` for an entry of a
+    /// `[features]` table enabling it.
+    pub kind: String,
+}
+
+pub enum TraitImplementations {}
+
+impl Request for TraitImplementations {
+    type Params = TraitImplementationsParams;
+    type Result = Vec<TraitImplementationsGroup>;
+    const METHOD: &'static str = "rust-analyzer/traitImplementations";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TraitImplementationsParams {
+    #[serde(flatten)]
+    pub position: lsp_types::TextDocumentPositionParams,
+    /// Only return the impls in this crate, from an earlier response.
+    pub crate_id: Option<u32>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TraitImplementationsGroup {
+    pub crate_id: u32,
+    pub crate_name: Option<String>,
+    pub is_library: bool,
+    /// `None` for dependencies, unless they were asked for with `crate_id`.
+    pub implementations: Option<Vec<lsp_types::Location>>,
+}
+
 #[derive(Deserialize, Serialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct ViewItemTreeParams {
@@ -99,6 +220,28 @@ impl Request for ViewItemTree {
     const METHOD: &'static str = "rust-analyzer/viewItemTree";
 }
 
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ViewCHeaderParams {
+    pub text_document: TextDocumentIdentifier,
+}
+
+pub enum ViewCHeader {}
+
+impl Request for ViewCHeader {
+    type Params = ViewCHeaderParams;
+    type Result = String;
+    const METHOD: &'static str = "rust-analyzer/viewCHeader";
+}
+
+pub enum ViewTraitSolverOverflows {}
+
+impl Request for ViewTraitSolverOverflows {
+    type Params = lsp_types::TextDocumentPositionParams;
+    type Result = String;
+    const METHOD: &'static str = "rust-analyzer/viewTraitSolverOverflows";
+}
+
 pub enum ExpandMacro {}
 
 impl Request for ExpandMacro {
@@ -121,6 +264,129 @@ pub struct ExpandedMacro {
     pub expansion: String,
 }
 
+pub enum ExpansionUri {}
+
+impl Request for ExpansionUri {
+    type Params = lsp_types::TextDocumentPositionParams;
+    type Result = Option<lsp_types::Url>;
+    const METHOD: &'static str = "rust-analyzer/expansionUri";
+}
+
+pub enum ViewExpansion {}
+
+impl Request for ViewExpansion {
+    type Params = ViewExpansionParams;
+    type Result = Option<ExpansionDocument>;
+    const METHOD: &'static str = "rust-analyzer/viewExpansion";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ViewExpansionParams {
+    /// A `rust-analyzer-expansion` uri returned by `ExpansionUri`.
+    pub uri: lsp_types::Url,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ExpansionDocument {
+    pub text: String,
+    pub mappings: Vec<ExpansionMapping>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ExpansionMapping {
+    /// The range in `ExpansionDocument::text`.
+    pub range: Range,
+    /// The source token this range was expanded from.
+    pub location: lsp_types::Location,
+}
+
+pub enum TypeOfRange {}
+
+impl Request for TypeOfRange {
+    type Params = TypeOfRangeParams;
+    type Result = Option<TypeOfRangeResult>;
+    const METHOD: &'static str = "rust-analyzer/typeOfRange";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TypeOfRangeParams {
+    pub text_document: TextDocumentIdentifier,
+    pub range: Range,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TypeOfRangeResult {
+    /// The range of the expression or pattern the type belongs to.
+    pub range: Range,
+    #[serde(rename = "type")]
+    pub ty: String,
+    /// The type after coercions, if any were applied.
+    pub adjusted_type: Option<String>,
+}
+
+pub enum ExplainImpl {}
+
+impl Request for ExplainImpl {
+    type Params = ExplainImplParams;
+    type Result = ImplExplanation;
+    const METHOD: &'static str = "rust-analyzer/explainImpl";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ExplainImplParams {
+    pub text_document: TextDocumentIdentifier,
+    pub range: Range,
+    /// The path of the trait, resolved at the start of `range`.
+    #[serde(rename = "trait")]
+    pub trait_: String,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ImplExplanation {
+    /// The goal being explained, like `Rc<u8>: Send`.
+    pub goal: String,
+    pub holds: bool,
+    pub reason: String,
+    pub children: Vec<ImplExplanation>,
+    /// The whole tree as indented text, only set on the root.
+    pub rendered: Option<String>,
+}
+
+pub enum TraitImplMatrix {}
+
+impl Request for TraitImplMatrix {
+    type Params = lsp_types::TextDocumentPositionParams;
+    type Result = Option<TraitImplMatrixResult>;
+    const METHOD: &'static str = "rust-analyzer/traitImplMatrix";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TraitImplMatrixResult {
+    #[serde(rename = "type")]
+    pub ty: String,
+    pub rows: Vec<TraitImplRow>,
+    /// Derives all the missing traits which can be derived.
+    pub derive_missing: Option<lsp_types::WorkspaceEdit>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TraitImplRow {
+    /// The trait, as it would be written in a derive.
+    #[serde(rename = "trait")]
+    pub trait_: String,
+    pub implemented: bool,
+    pub derivable: bool,
+}
+
 pub enum MatchingBrace {}
 
 impl Request for MatchingBrace {
@@ -281,6 +547,38 @@ pub struct InlayHintLabelPart {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub command: Option<lsp_types::Command>,
 }
+
+pub enum InlineValueRequest {}
+
+impl Request for InlineValueRequest {
+    type Params = InlineValueParams;
+    type Result = Option<Vec<InlineValueVariableLookup>>;
+    const METHOD: &'static str = "textDocument/inlineValue";
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InlineValueParams {
+    pub text_document: TextDocumentIdentifier,
+    pub range: Range,
+    pub context: InlineValueContext,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InlineValueContext {
+    pub frame_id: i32,
+    pub stopped_location: Range,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InlineValueVariableLookup {
+    pub range: Range,
+    pub variable_name: Option<String>,
+    pub case_sensitive_lookup: bool,
+}
+
 pub enum Ssr {}
 
 impl Request for Ssr {
@@ -326,6 +624,87 @@ pub enum Health {
     Error,
 }
 
+pub enum LoadingProgress {}
+
+impl Notification for LoadingProgress {
+    type Params = LoadingProgressParams;
+    const METHOD: &'static str = "rust-analyzer/loadingProgress";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LoadingProgressParams {
+    pub phase: LoadingPhase,
+    pub state: LoadingState,
+    /// How many of the `total` units of work are done, if the phase counts them.
+    pub done: Option<usize>,
+    pub total: Option<usize>,
+    /// The crates or, for `cargoMetadata`, the manifests the phase is working on right now.
+    pub current: Vec<String>,
+    /// Whether the phase can be stopped with `rust-analyzer/cancelLoadingPhase`.
+    pub cancellable: bool,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum LoadingPhase {
+    CargoMetadata,
+    BuildScripts,
+    ProcMacros,
+    Vfs,
+    PrimeCaches,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum LoadingState {
+    Begin,
+    Report,
+    End,
+}
+
+pub enum CancelLoadingPhase {}
+
+impl Request for CancelLoadingPhase {
+    type Params = CancelLoadingPhaseParams;
+    type Result = ();
+    const METHOD: &'static str = "rust-analyzer/cancelLoadingPhase";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelLoadingPhaseParams {
+    pub phase: LoadingPhase,
+}
+
+pub enum ConfigurationApplied {}
+
+impl Notification for ConfigurationApplied {
+    type Params = ConfigurationAppliedParams;
+    const METHOD: &'static str = "experimental/configurationApplied";
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct ConfigurationAppliedParams {
+    pub reinitialized: Vec<Subsystem>,
+}
+
+/// A part of the server which is restarted when the settings it depends on
+/// change.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub enum Subsystem {
+    /// `cargo metadata` is re-run and the workspaces are reloaded.
+    ProjectModel,
+    /// The crate graph and source roots are rebuilt from the loaded workspaces.
+    CrateGraph,
+    ProcMacroServer,
+    Flycheck,
+    /// Settings which invalidate the analysis results, like the trait solver
+    /// fuel.
+    Analysis,
+}
+
 pub enum CodeActionRequest {}
 
 impl Request for CodeActionRequest {
@@ -341,6 +720,39 @@ impl Request for CodeActionResolveRequest {
     const METHOD: &'static str = "codeAction/resolve";
 }
 
+pub enum BatchCodeAction {}
+
+impl Request for BatchCodeAction {
+    type Params = BatchCodeActionParams;
+    type Result = BatchCodeActionResult;
+    const METHOD: &'static str = "rust-analyzer/batchCodeAction";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchCodeActionParams {
+    pub text_document: TextDocumentIdentifier,
+    /// The id of the assist or diagnostic fix, like `add_missing_impl_members`.
+    pub assist_id: String,
+    pub scope: BatchCodeActionScope,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum BatchCodeActionScope {
+    File,
+    Crate,
+    Workspace,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchCodeActionResult {
+    pub edit: lsp_types::WorkspaceEdit,
+    pub applied: usize,
+    pub skipped: usize,
+}
+
 #[derive(Debug, PartialEq, Clone, Default, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CodeAction {
@@ -567,3 +979,49 @@ pub struct CompletionImport {
 pub struct ClientCommandOptions {
     pub commands: Vec<String>,
 }
+
+pub enum RegisterVirtualDocument {}
+
+impl Notification for RegisterVirtualDocument {
+    type Params = RegisterVirtualDocumentParams;
+    const METHOD: &'static str = "rust-analyzer/registerVirtualDocument";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RegisterVirtualDocumentParams {
+    pub uri: Url,
+    pub anchor: TextDocumentIdentifier,
+}
+
+pub enum SearchTruncated {}
+
+impl Notification for SearchTruncated {
+    type Params = SearchTruncatedParams;
+    const METHOD: &'static str = "rust-analyzer/searchTruncated";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchTruncatedParams {
+    #[serde(flatten)]
+    pub text_document_position: lsp_types::TextDocumentPositionParams,
+    pub partial_result_token: Option<lsp_types::ProgressToken>,
+    pub continue_token: String,
+}
+
+pub enum ContinueSearch {}
+
+impl Request for ContinueSearch {
+    type Params = ContinueSearchParams;
+    type Result = Option<Vec<lsp_types::Location>>;
+    const METHOD: &'static str = "rust-analyzer/continueSearch";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ContinueSearchParams {
+    #[serde(flatten)]
+    pub partial_result_params: PartialResultParams,
+    pub continue_token: String,
+}
@@ -8,6 +8,7 @@ use crate::{
     from_proto,
     global_state::GlobalState,
     line_index::{LineEndings, LineIndex, OffsetEncoding},
+    lsp_ext::{self, LoadingPhase},
     LspError,
 };
 
@@ -39,6 +40,66 @@ impl Progress {
     }
 }
 
+/// How far a phase of loading the workspace got.
+#[derive(Debug, Default)]
+pub(crate) struct PhaseProgress {
+    pub(crate) done: Option<usize>,
+    pub(crate) total: Option<usize>,
+    /// The crates, or manifests when fetching metadata, the phase is working on.
+    pub(crate) current: Vec<String>,
+}
+
+/// The title of the work done progress of `phase`, which also makes up its token.
+pub(crate) fn loading_phase_title(phase: LoadingPhase) -> &'static str {
+    match phase {
+        LoadingPhase::CargoMetadata => "Fetching cargo metadata",
+        LoadingPhase::BuildScripts => "Running build scripts",
+        LoadingPhase::ProcMacros => "Loading proc macros",
+        LoadingPhase::Vfs => "Scanning files",
+        LoadingPhase::PrimeCaches => "Indexing",
+    }
+}
+
+/// Whether the rest of `phase` can be skipped, which is only the case for the phases the
+/// analysis can do without.
+pub(crate) fn loading_phase_is_cancellable(phase: LoadingPhase) -> bool {
+    match phase {
+        // The rest of the crates get primed lazily by the requests needing them.
+        LoadingPhase::PrimeCaches => true,
+        LoadingPhase::CargoMetadata
+        | LoadingPhase::BuildScripts
+        | LoadingPhase::ProcMacros
+        | LoadingPhase::Vfs => false,
+    }
+}
+
+/// The message and fraction of the work done progress showing `progress`.
+fn loading_progress_message(progress: &PhaseProgress) -> (Option<String>, Option<f64>) {
+    let count = match (progress.done, progress.total) {
+        (Some(done), Some(total)) => Some(format!("{}/{}", done, total)),
+        (Some(done), None) => Some(done.to_string()),
+        _ => None,
+    };
+    let current = match &progress.current[..] {
+        [] => None,
+        [name] => Some(name.clone()),
+        [name, rest @ ..] => Some(format!("{} + {} more", name, rest.len())),
+    };
+    let message = match (count, current) {
+        (Some(count), Some(current)) => Some(format!("{} ({})", count, current)),
+        (count, current) => count.or(current),
+    };
+    let fraction = match (progress.done, progress.total) {
+        (Some(done), Some(total)) => Some(Progress::fraction(done, total)),
+        _ => None,
+    };
+    (message, fraction)
+}
+
+pub(crate) fn progress_token(title: &str) -> lsp_types::ProgressToken {
+    lsp_types::ProgressToken::String(format!("rustAnalyzer/{}", title))
+}
+
 impl GlobalState {
     pub(crate) fn show_message(&mut self, typ: lsp_types::MessageType, message: String) {
         let message = message;
@@ -72,6 +133,46 @@ impl GlobalState {
         state: Progress,
         message: Option<String>,
         fraction: Option<f64>,
+    ) {
+        self.send_progress(title, state, message, fraction, false)
+    }
+
+    /// Reports the progress of a phase of loading the workspace as a work done
+    /// progress of its own and, if the client opted in, as a
+    /// `rust-analyzer/loadingProgress` notification.
+    pub(crate) fn report_loading_progress(
+        &mut self,
+        phase: LoadingPhase,
+        state: Progress,
+        progress: PhaseProgress,
+    ) {
+        let cancellable = loading_phase_is_cancellable(phase);
+        if self.config.loading_progress_notification() {
+            self.send_notification::<lsp_ext::LoadingProgress>(lsp_ext::LoadingProgressParams {
+                phase,
+                state: match state {
+                    Progress::Begin => lsp_ext::LoadingState::Begin,
+                    Progress::Report => lsp_ext::LoadingState::Report,
+                    Progress::End => lsp_ext::LoadingState::End,
+                },
+                done: progress.done,
+                total: progress.total,
+                current: progress.current.clone(),
+                cancellable,
+            });
+        }
+
+        let (message, fraction) = loading_progress_message(&progress);
+        self.send_progress(loading_phase_title(phase), state, message, fraction, cancellable);
+    }
+
+    fn send_progress(
+        &mut self,
+        title: &str,
+        state: Progress,
+        message: Option<String>,
+        fraction: Option<f64>,
+        cancellable: bool,
     ) {
         if !self.config.work_done_progress() {
             return;
@@ -80,7 +181,7 @@ impl GlobalState {
             assert!((0.0..=1.0).contains(&f));
             (f * 100.0) as u32
         });
-        let token = lsp_types::ProgressToken::String(format!("rustAnalyzer/{}", title));
+        let token = progress_token(title);
         let work_done_progress = match state {
             Progress::Begin => {
                 self.send_request::<lsp_types::request::WorkDoneProgressCreate>(
@@ -90,14 +191,14 @@ impl GlobalState {
 
                 lsp_types::WorkDoneProgress::Begin(lsp_types::WorkDoneProgressBegin {
                     title: title.into(),
-                    cancellable: None,
+                    cancellable: Some(cancellable),
                     message,
                     percentage,
                 })
             }
             Progress::Report => {
                 lsp_types::WorkDoneProgress::Report(lsp_types::WorkDoneProgressReport {
-                    cancellable: None,
+                    cancellable: Some(cancellable),
                     message,
                     percentage,
                 })
@@ -208,6 +309,42 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn loading_progress_messages() {
+        let check = |done, total, current: &[&str], expected: (Option<&str>, Option<f64>)| {
+            let progress = PhaseProgress {
+                done,
+                total,
+                current: current.iter().map(|it| it.to_string()).collect(),
+            };
+            let (message, fraction) = loading_progress_message(&progress);
+            assert_eq!((message.as_deref(), fraction), expected);
+        };
+        check(None, None, &[], (None, None));
+        check(None, None, &["Cargo.toml"], (Some("Cargo.toml"), None));
+        check(Some(2), None, &[], (Some("2"), None));
+        check(Some(1), Some(4), &["core"], (Some("1/4 (core)"), Some(0.25)));
+        check(
+            Some(3),
+            Some(4),
+            &["std", "alloc", "core"],
+            (Some("3/4 (std + 2 more)"), Some(0.75)),
+        );
+    }
+
+    #[test]
+    fn only_priming_caches_is_cancellable() {
+        assert!(loading_phase_is_cancellable(LoadingPhase::PrimeCaches));
+        for phase in [
+            LoadingPhase::CargoMetadata,
+            LoadingPhase::BuildScripts,
+            LoadingPhase::ProcMacros,
+            LoadingPhase::Vfs,
+        ] {
+            assert!(!loading_phase_is_cancellable(phase));
+        }
+    }
+
     #[test]
     fn test_apply_document_changes() {
         macro_rules! c {
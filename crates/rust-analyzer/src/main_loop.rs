@@ -1,8 +1,9 @@
 //! The main loop of `rust-analyzer` responsible for dispatching LSP
 //! requests/replies and notifications back to the client.
 use std::{
-    fmt,
-    sync::Arc,
+    fmt, iter, mem,
+    ops::ControlFlow,
+    sync::{atomic::Ordering, Arc},
     time::{Duration, Instant},
 };
 
@@ -10,8 +11,9 @@ use always_assert::always;
 use crossbeam_channel::{select, Receiver};
 use ide_db::base_db::{SourceDatabaseExt, VfsPath};
 use itertools::Itertools;
-use lsp_server::{Connection, Notification, Request};
+use lsp_server::{Connection, ErrorCode, Notification, Request};
 use lsp_types::notification::Notification as _;
+use project_model::ProjectManifest;
 use vfs::{ChangeKind, FileId};
 
 use crate::{
@@ -20,10 +22,14 @@ use crate::{
     from_proto,
     global_state::{file_id_to_url, url_to_file_id, GlobalState},
     handlers, lsp_ext,
-    lsp_utils::{apply_document_changes, is_cancelled, notification_is, Progress},
+    lsp_utils::{
+        apply_document_changes, is_cancelled, loading_phase_is_cancellable, loading_phase_title,
+        notification_is, progress_token, PhaseProgress, Progress,
+    },
     mem_docs::DocumentData,
     reload::{self, BuildDataProgress, ProjectWorkspaceProgress},
-    Result,
+    thread_pool::Priority,
+    to_proto, LspError, Result,
 };
 
 pub fn main_loop(config: Config, connection: Connection) -> Result<()> {
@@ -65,6 +71,7 @@ pub(crate) enum Task {
     PrimeCaches(PrimeCachesProgress),
     FetchWorkspace(ProjectWorkspaceProgress),
     FetchBuildData(BuildDataProgress),
+    InstallRustSrc(Result<(), String>),
 }
 
 #[derive(Debug)]
@@ -231,10 +238,12 @@ impl GlobalState {
                             PrimeCachesProgress::End { .. } => prime_caches_progress.push(progress),
                         },
                         Task::FetchWorkspace(progress) => {
-                            let (state, msg) = match progress {
-                                ProjectWorkspaceProgress::Begin => (Progress::Begin, None),
-                                ProjectWorkspaceProgress::Report(msg) => {
-                                    (Progress::Report, Some(msg))
+                            let (state, progress) = match progress {
+                                ProjectWorkspaceProgress::Begin => {
+                                    (Progress::Begin, PhaseProgress::default())
+                                }
+                                ProjectWorkspaceProgress::Report(progress) => {
+                                    (Progress::Report, progress)
                                 }
                                 ProjectWorkspaceProgress::End(workspaces) => {
                                     self.fetch_workspaces_queue.op_completed(workspaces);
@@ -247,31 +256,46 @@ impl GlobalState {
                                         self.fetch_build_data_queue.request_op()
                                     }
 
-                                    (Progress::End, None)
+                                    (Progress::End, PhaseProgress::default())
                                 }
                             };
 
-                            self.report_progress("Fetching", state, msg, None);
+                            self.report_loading_progress(
+                                lsp_ext::LoadingPhase::CargoMetadata,
+                                state,
+                                progress,
+                            );
                         }
-                        Task::FetchBuildData(progress) => {
-                            let (state, msg) = match progress {
-                                BuildDataProgress::Begin => (Some(Progress::Begin), None),
-                                BuildDataProgress::Report(msg) => {
-                                    (Some(Progress::Report), Some(msg))
-                                }
-                                BuildDataProgress::End(build_data_result) => {
-                                    self.fetch_build_data_queue.op_completed(build_data_result);
-
-                                    self.switch_workspaces();
-
-                                    (Some(Progress::End), None)
-                                }
-                            };
+                        Task::FetchBuildData(progress) => match progress {
+                            BuildDataProgress::Begin => self.report_loading_progress(
+                                lsp_ext::LoadingPhase::BuildScripts,
+                                Progress::Begin,
+                                PhaseProgress::default(),
+                            ),
+                            BuildDataProgress::Report(progress) => self.report_loading_progress(
+                                lsp_ext::LoadingPhase::BuildScripts,
+                                Progress::Report,
+                                progress,
+                            ),
+                            BuildDataProgress::End(build_data_result) => {
+                                self.fetch_build_data_queue.op_completed(build_data_result);
+                                self.report_loading_progress(
+                                    lsp_ext::LoadingPhase::BuildScripts,
+                                    Progress::End,
+                                    PhaseProgress::default(),
+                                );
 
-                            if let Some(state) = state {
-                                self.report_progress("Loading", state, msg, None);
+                                // This loads the proc macros the build scripts compiled.
+                                self.switch_workspaces();
                             }
-                        }
+                        },
+                        Task::InstallRustSrc(res) => match res {
+                            Ok(()) => self.fetch_workspaces_queue.request_op(),
+                            Err(err) => self.show_message(
+                                lsp_types::MessageType::ERROR,
+                                format!("failed to install rust-src: {}", err),
+                            ),
+                        },
                     }
 
                     // Coalesce multiple task events into one loop turn
@@ -282,37 +306,23 @@ impl GlobalState {
                 }
 
                 for progress in prime_caches_progress {
-                    let (state, message, fraction);
+                    let (state, phase_progress);
                     match progress {
                         PrimeCachesProgress::Begin => {
                             state = Progress::Begin;
-                            message = None;
-                            fraction = 0.0;
+                            phase_progress = PhaseProgress::default();
                         }
                         PrimeCachesProgress::Report(report) => {
                             state = Progress::Report;
-
-                            message = match &report.crates_currently_indexing[..] {
-                                [crate_name] => Some(format!(
-                                    "{}/{} ({})",
-                                    report.crates_done, report.crates_total, crate_name
-                                )),
-                                [crate_name, rest @ ..] => Some(format!(
-                                    "{}/{} ({} + {} more)",
-                                    report.crates_done,
-                                    report.crates_total,
-                                    crate_name,
-                                    rest.len()
-                                )),
-                                _ => None,
+                            phase_progress = PhaseProgress {
+                                done: Some(report.crates_done),
+                                total: Some(report.crates_total),
+                                current: report.crates_currently_indexing,
                             };
-
-                            fraction = Progress::fraction(report.crates_done, report.crates_total);
                         }
                         PrimeCachesProgress::End { cancelled } => {
                             state = Progress::End;
-                            message = None;
-                            fraction = 1.0;
+                            phase_progress = PhaseProgress::default();
 
                             self.prime_caches_queue.op_completed(());
                             if cancelled {
@@ -321,7 +331,11 @@ impl GlobalState {
                         }
                     };
 
-                    self.report_progress("Indexing", state, message, Some(fraction));
+                    self.report_loading_progress(
+                        lsp_ext::LoadingPhase::PrimeCaches,
+                        state,
+                        phase_progress,
+                    );
                 }
             }
             Event::Vfs(mut task) => {
@@ -352,11 +366,15 @@ impl GlobalState {
                                 assert_eq!(n_done, n_total);
                                 Progress::End
                             };
-                            self.report_progress(
-                                "Roots Scanned",
+                            let progress = PhaseProgress {
+                                done: Some(n_done),
+                                total: Some(n_total),
+                                current: Vec::new(),
+                            };
+                            self.report_loading_progress(
+                                lsp_ext::LoadingPhase::Vfs,
                                 state,
-                                Some(format!("{}/{}", n_done, n_total)),
-                                Some(Progress::fraction(n_done, n_total)),
+                                progress,
                             )
                         }
                     }
@@ -441,6 +459,10 @@ impl GlobalState {
         let state_changed = self.process_changes();
         let memdocs_added_or_removed = self.mem_docs.take_changes();
 
+        if !self.opened_files_to_prime.is_empty() {
+            self.prime_caches_for_opened_files();
+        }
+
         if self.is_quiescent() {
             if !was_quiescent {
                 for flycheck in &self.flycheck {
@@ -454,7 +476,6 @@ impl GlobalState {
             if !was_quiescent || state_changed {
                 // Refresh semantic tokens if the client supports it.
                 if self.config.semantic_tokens_refresh() {
-                    self.semantic_tokens_cache.lock().clear();
                     self.send_request::<lsp_types::request::SemanticTokensRefresh>((), |_, _| ());
                 }
 
@@ -507,13 +528,20 @@ impl GlobalState {
         if self.prime_caches_queue.should_start_op() {
             let num_worker_threads = self.config.prime_caches_num_threads();
 
-            self.task_pool.handle.spawn_with_sender({
+            self.skip_prime_caches.store(false, Ordering::Relaxed);
+            self.task_pool.handle.spawn_with_sender(Priority::Normal, {
                 let analysis = self.snapshot().analysis;
+                let skip = Arc::clone(&self.skip_prime_caches);
                 move |sender| {
                     sender.send(Task::PrimeCaches(PrimeCachesProgress::Begin)).unwrap();
                     let res = analysis.parallel_prime_caches(num_worker_threads, |progress| {
                         let report = PrimeCachesProgress::Report(progress);
                         sender.send(Task::PrimeCaches(report)).unwrap();
+                        if skip.load(Ordering::Relaxed) {
+                            ControlFlow::Break(())
+                        } else {
+                            ControlFlow::Continue(())
+                        }
                     });
                     sender
                         .send(Task::PrimeCaches(PrimeCachesProgress::End {
@@ -581,6 +609,9 @@ impl GlobalState {
                 s.shutdown_requested = true;
                 Ok(())
             })?
+            .on_sync_mut::<lsp_ext::CancelLoadingPhase>(|s, params| {
+                s.cancel_loading_phase(params.phase)
+            })?
             .on_sync_mut::<lsp_ext::MemoryUsage>(handlers::handle_memory_usage)?
             .on_sync_mut::<lsp_ext::ShuffleCrateGraph>(handlers::handle_shuffle_crate_graph)?
             .on_sync::<lsp_ext::JoinLines>(handlers::handle_join_lines)?
@@ -590,40 +621,66 @@ impl GlobalState {
             .on::<lsp_ext::AnalyzerStatus>(handlers::handle_analyzer_status)
             .on::<lsp_ext::SyntaxTree>(handlers::handle_syntax_tree)
             .on::<lsp_ext::ViewHir>(handlers::handle_view_hir)
+            .on::<lsp_ext::TypeOfRange>(handlers::handle_type_of_range)
+            .on::<lsp_ext::ExplainImpl>(handlers::handle_explain_impl)
+            .on::<lsp_ext::TraitImplMatrix>(handlers::handle_trait_impl_matrix)
+            .on::<lsp_ext::ViewDesugaredBody>(handlers::handle_view_desugared_body)
             .on::<lsp_ext::ViewCrateGraph>(handlers::handle_view_crate_graph)
+            .on::<lsp_ext::DeadCode>(handlers::handle_dead_code)
+            .on::<lsp_ext::UnsafeReport>(handlers::handle_unsafe_report)
+            .on::<lsp_ext::FeatureUsages>(handlers::handle_feature_usages)
+            .on::<lsp_ext::TraitImplementations>(handlers::handle_trait_implementations)
             .on::<lsp_ext::ViewItemTree>(handlers::handle_view_item_tree)
+            .on::<lsp_ext::ViewCHeader>(handlers::handle_view_c_header)
+            .on::<lsp_ext::ViewTraitSolverOverflows>(handlers::handle_view_trait_solver_overflows)
             .on::<lsp_ext::ExpandMacro>(handlers::handle_expand_macro)
+            .on::<lsp_ext::ExpansionUri>(handlers::handle_expansion_uri)
+            .on::<lsp_ext::ViewExpansion>(handlers::handle_view_expansion)
             .on::<lsp_ext::ParentModule>(handlers::handle_parent_module)
             .on::<lsp_ext::Runnables>(handlers::handle_runnables)
             .on::<lsp_ext::RelatedTests>(handlers::handle_related_tests)
             .on::<lsp_ext::InlayHints>(handlers::handle_inlay_hints)
+            .on::<lsp_ext::InlineValueRequest>(handlers::handle_inline_values)
             .on::<lsp_ext::CodeActionRequest>(handlers::handle_code_action)
             .on::<lsp_ext::CodeActionResolveRequest>(handlers::handle_code_action_resolve)
             .on::<lsp_ext::HoverRequest>(handlers::handle_hover)
             .on::<lsp_ext::ExternalDocs>(handlers::handle_open_docs)
             .on::<lsp_ext::OpenCargoToml>(handlers::handle_open_cargo_toml)
             .on::<lsp_ext::MoveItem>(handlers::handle_move_item)
-            .on::<lsp_ext::WorkspaceSymbol>(handlers::handle_workspace_symbol)
-            .on::<lsp_types::request::OnTypeFormatting>(handlers::handle_on_type_formatting)
+            .on_long_running::<lsp_ext::WorkspaceSymbol>(handlers::handle_workspace_symbol)
+            .on_latency_sensitive::<lsp_types::request::OnTypeFormatting>(
+                handlers::handle_on_type_formatting,
+            )
             .on::<lsp_types::request::DocumentSymbolRequest>(handlers::handle_document_symbol)
             .on::<lsp_types::request::GotoDefinition>(handlers::handle_goto_definition)
             .on::<lsp_types::request::GotoDeclaration>(handlers::handle_goto_declaration)
-            .on::<lsp_types::request::GotoImplementation>(handlers::handle_goto_implementation)
+            .on_long_running::<lsp_types::request::GotoImplementation>(
+                handlers::handle_goto_implementation,
+            )
             .on::<lsp_types::request::GotoTypeDefinition>(handlers::handle_goto_type_definition)
-            .on::<lsp_types::request::Completion>(handlers::handle_completion)
-            .on::<lsp_types::request::ResolveCompletionItem>(handlers::handle_completion_resolve)
+            .on_latency_sensitive::<lsp_types::request::Completion>(handlers::handle_completion)
+            .on_latency_sensitive::<lsp_types::request::ResolveCompletionItem>(
+                handlers::handle_completion_resolve,
+            )
             .on::<lsp_types::request::CodeLensRequest>(handlers::handle_code_lens)
             .on::<lsp_types::request::CodeLensResolve>(handlers::handle_code_lens_resolve)
             .on::<lsp_types::request::FoldingRangeRequest>(handlers::handle_folding_range)
-            .on::<lsp_types::request::SignatureHelpRequest>(handlers::handle_signature_help)
+            .on::<lsp_types::request::LinkedEditingRange>(handlers::handle_linked_editing_range)
+            .on::<lsp_types::request::DocumentColor>(handlers::handle_document_color)
+            .on::<lsp_types::request::ColorPresentationRequest>(handlers::handle_color_presentation)
+            .on_latency_sensitive::<lsp_types::request::SignatureHelpRequest>(
+                handlers::handle_signature_help,
+            )
             .on::<lsp_types::request::PrepareRenameRequest>(handlers::handle_prepare_rename)
-            .on::<lsp_types::request::Rename>(handlers::handle_rename)
-            .on::<lsp_types::request::References>(handlers::handle_references)
+            .on_long_running::<lsp_types::request::Rename>(handlers::handle_rename)
+            .on_long_running::<lsp_types::request::References>(handlers::handle_references)
+            .on_long_running::<lsp_ext::ContinueSearch>(handlers::handle_continue_search)
             .on::<lsp_types::request::Formatting>(handlers::handle_formatting)
             .on::<lsp_types::request::RangeFormatting>(handlers::handle_range_formatting)
             .on::<lsp_types::request::DocumentHighlightRequest>(handlers::handle_document_highlight)
+            .on::<lsp_types::request::MonikerRequest>(handlers::handle_moniker)
             .on::<lsp_types::request::CallHierarchyPrepare>(handlers::handle_call_hierarchy_prepare)
-            .on::<lsp_types::request::CallHierarchyIncomingCalls>(
+            .on_long_running::<lsp_types::request::CallHierarchyIncomingCalls>(
                 handlers::handle_call_hierarchy_incoming,
             )
             .on::<lsp_types::request::CallHierarchyOutgoingCalls>(
@@ -639,7 +696,8 @@ impl GlobalState {
                 handlers::handle_semantic_tokens_range,
             )
             .on::<lsp_types::request::WillRenameFiles>(handlers::handle_will_rename_files)
-            .on::<lsp_ext::Ssr>(handlers::handle_ssr)
+            .on_long_running::<lsp_ext::Ssr>(handlers::handle_ssr)
+            .on_long_running::<lsp_ext::BatchCodeAction>(handlers::handle_batch_code_action)
             .finish();
         Ok(())
     }
@@ -653,14 +711,20 @@ impl GlobalState {
                 this.cancel(id);
                 Ok(())
             })?
-            .on::<lsp_types::notification::WorkDoneProgressCancel>(|_this, _params| {
-                // Just ignore this. It is OK to continue sending progress
-                // notifications for this token, as the client can't know when
-                // we accepted notification.
+            .on::<lsp_types::notification::WorkDoneProgressCancel>(|this, params| {
+                // It is OK to continue sending progress notifications for this
+                // token, as the client can't know when we accepted notification.
+                // The progress of the other phases is advertised as not
+                // cancellable, and a notification can't be rejected, so
+                // cancelling it is ignored.
+                let phase = lsp_ext::LoadingPhase::PrimeCaches;
+                if params.token == progress_token(loading_phase_title(phase)) {
+                    this.cancel_loading_phase(phase)?;
+                }
                 Ok(())
             })?
             .on::<lsp_types::notification::DidOpenTextDocument>(|this, params| {
-                if let Ok(path) = from_proto::vfs_path(&params.text_document.uri) {
+                if let Some(path) = this.doc_path(&params.text_document.uri) {
                     if this
                         .mem_docs
                         .insert(path.clone(), DocumentData::new(params.text_document.version))
@@ -668,6 +732,9 @@ impl GlobalState {
                     {
                         tracing::error!("duplicate DidOpenTextDocument: {}", path)
                     }
+                    if this.config.prime_caches_open_files() {
+                        this.opened_files_to_prime.push(path.clone());
+                    }
                     this.vfs
                         .write()
                         .0
@@ -676,7 +743,7 @@ impl GlobalState {
                 Ok(())
             })?
             .on::<lsp_types::notification::DidChangeTextDocument>(|this, params| {
-                if let Ok(path) = from_proto::vfs_path(&params.text_document.uri) {
+                if let Some(path) = this.doc_path(&params.text_document.uri) {
                     match this.mem_docs.get_mut(&path) {
                         Some(doc) => {
                             // The version passed in DidChangeTextDocument is the version after all edits are applied
@@ -699,19 +766,33 @@ impl GlobalState {
                 Ok(())
             })?
             .on::<lsp_types::notification::DidCloseTextDocument>(|this, params| {
-                if let Ok(path) = from_proto::vfs_path(&params.text_document.uri) {
+                if let Some(path) = this.doc_path(&params.text_document.uri) {
                     if this.mem_docs.remove(&path).is_err() {
                         tracing::error!("orphan DidCloseTextDocument: {}", path);
                     }
 
                     this.semantic_tokens_cache.lock().remove(&params.text_document.uri);
 
-                    if let Some(path) = path.as_path() {
-                        this.loader.handle.invalidate(path.to_path_buf());
+                    match path.as_path() {
+                        Some(path) => this.loader.handle.invalidate(path.to_path_buf()),
+                        None => {
+                            // Virtual documents only exist while they are open.
+                            this.source_root_config.virtual_docs.remove(&path);
+                            this.vfs.write().0.set_file_contents(path.clone(), None);
+                        }
                     }
                 }
                 Ok(())
             })?
+            .on::<lsp_ext::RegisterVirtualDocument>(|this, params| {
+                let path = from_proto::vfs_path(&params.uri)?;
+                if path.as_path().is_some() {
+                    return Err(format!("not a virtual document: {}", params.uri).into());
+                }
+                let anchor = from_proto::vfs_path(&params.anchor.uri)?;
+                this.source_root_config.virtual_docs.insert(path, anchor);
+                Ok(())
+            })?
             .on::<lsp_types::notification::DidSaveTextDocument>(|this, params| {
                 for flycheck in &this.flycheck {
                     flycheck.update();
@@ -726,47 +807,34 @@ impl GlobalState {
             .on::<lsp_types::notification::DidChangeConfiguration>(|this, _params| {
                 // As stated in https://github.com/microsoft/language-server-protocol/issues/676,
                 // this notification's parameters should be ignored and the actual config queried separately.
-                this.send_request::<lsp_types::request::WorkspaceConfiguration>(
-                    lsp_types::ConfigurationParams {
-                        items: vec![lsp_types::ConfigurationItem {
-                            scope_uri: None,
-                            section: Some("rust-analyzer".to_string()),
-                        }],
-                    },
-                    |this, resp| {
-                        tracing::debug!("config update response: '{:?}", resp);
-                        let lsp_server::Response { error, result, .. } = resp;
-
-                        match (error, result) {
-                            (Some(err), _) => {
-                                tracing::error!("failed to fetch the server settings: {:?}", err)
-                            }
-                            (None, Some(mut configs)) => {
-                                if let Some(json) = configs.get_mut(0) {
-                                    // Note that json can be null according to the spec if the client can't
-                                    // provide a configuration. This is handled in Config::update below.
-                                    let mut config = Config::clone(&*this.config);
-                                    if let Err(errors) = config.update(json.take()) {
-                                        let errors = errors
-                                            .iter()
-                                            .format_with("\n", |(key, e),f| {
-                                                f(key)?;
-                                                f(&": ")?;
-                                                f(e)
-                                            });
-                                        let msg= format!("Failed to deserialize config key(s):\n{}", errors);
-                                        this.show_message(lsp_types::MessageType::WARNING, msg);
-                                    }
-                                    this.update_configuration(config);
-                                }
-                            }
-                            (None, None) => tracing::error!(
-                                "received empty server settings response from the client"
-                            ),
+                this.fetch_configuration();
+                Ok(())
+            })?
+            .on::<lsp_types::notification::DidChangeWorkspaceFolders>(|this, params| {
+                let mut folders = this.config.workspace_folders.clone();
+                for removed in params.event.removed {
+                    if let Ok(path) = from_proto::abs_path(&removed.uri) {
+                        folders.retain(|it| *it != path);
+                    }
+                }
+                for added in params.event.added {
+                    if let Ok(path) = from_proto::abs_path(&added.uri) {
+                        if !folders.contains(&path) {
+                            folders.push(path);
                         }
-                    },
-                );
+                    }
+                }
 
+                let mut config = Config::clone(&*this.config);
+                if config.discovered_projects.is_some() {
+                    let discovered = ProjectManifest::discover_all(&folders);
+                    tracing::info!("discovered projects: {:?}", discovered);
+                    config.discovered_projects = Some(discovered);
+                }
+                config.set_workspace_folders(folders);
+                this.update_configuration(config);
+                // New folders may come with their own settings.
+                this.fetch_configuration();
                 Ok(())
             })?
             .on::<lsp_types::notification::DidChangeWatchedFiles>(|this, params| {
@@ -781,6 +849,104 @@ impl GlobalState {
         Ok(())
     }
 
+    /// Asks the client for the current settings, both the global ones and those
+    /// scoped to each workspace folder.
+    fn fetch_configuration(&mut self) {
+        let scopes = match self.config.workspace_folders.as_slice() {
+            // A single folder's settings are the global ones.
+            [_] => Vec::new(),
+            folders => folders.iter().map(|it| to_proto::url_from_abs_path(it)).collect(),
+        };
+        let items = iter::once(None)
+            .chain(scopes.into_iter().map(Some))
+            .map(|scope_uri| lsp_types::ConfigurationItem {
+                scope_uri,
+                section: Some("rust-analyzer".to_string()),
+            })
+            .collect();
+        self.send_request::<lsp_types::request::WorkspaceConfiguration>(
+            lsp_types::ConfigurationParams { items },
+            |this, resp| {
+                tracing::debug!("config update response: '{:?}", resp);
+                let lsp_server::Response { error, result, .. } = resp;
+
+                match (error, result) {
+                    (Some(err), _) => {
+                        tracing::error!("failed to fetch the server settings: {:?}", err)
+                    }
+                    (None, Some(serde_json::Value::Array(configs))) => {
+                        let mut configs = configs.into_iter();
+                        if let Some(json) = configs.next() {
+                            // Note that json can be null according to the spec if the client can't
+                            // provide a configuration. This is handled in Config::update below.
+                            let mut config = Config::clone(&*this.config);
+                            let mut errors = config.update(json).err().unwrap_or_default();
+                            let folders = config.workspace_folders.clone();
+                            // The folders might have changed while the request was in flight.
+                            if configs.len() == folders.len() {
+                                for (folder, json) in folders.into_iter().zip(configs) {
+                                    if let Err(e) = config.update_folder(folder, json) {
+                                        errors.extend(e);
+                                    }
+                                }
+                            }
+                            if !errors.is_empty() {
+                                let errors = errors.iter().format_with("\n", |(key, e), f| {
+                                    f(key)?;
+                                    f(&": ")?;
+                                    f(e)
+                                });
+                                let msg =
+                                    format!("Failed to deserialize config key(s):\n{}", errors);
+                                this.show_message(lsp_types::MessageType::WARNING, msg);
+                            }
+                            this.update_configuration(config);
+                        }
+                    }
+                    (None, Some(_)) => tracing::error!(
+                        "received malformed server settings response from the client"
+                    ),
+                    (None, None) => {
+                        tracing::error!("received empty server settings response from the client")
+                    }
+                }
+            },
+        );
+    }
+
+    fn cancel_loading_phase(&mut self, phase: lsp_ext::LoadingPhase) -> Result<()> {
+        if !loading_phase_is_cancellable(phase) {
+            return Err(LspError::new(
+                ErrorCode::InvalidParams as i32,
+                format!("{} can't be cancelled", loading_phase_title(phase)),
+            )
+            .into());
+        }
+        // Priming the caches is the only phase that can be skipped.
+        self.skip_prime_caches.store(true, Ordering::Relaxed);
+        Ok(())
+    }
+
+    fn prime_caches_for_opened_files(&mut self) {
+        let file_ids = {
+            let vfs = &self.vfs.read().0;
+            mem::take(&mut self.opened_files_to_prime)
+                .iter()
+                .filter_map(|path| vfs.file_id(path))
+                .collect::<Vec<_>>()
+        };
+        let analysis = self.snapshot().analysis;
+        self.task_pool.handle.spawn_with_sender(Priority::Low, move |_| {
+            for file_id in file_ids {
+                // Cancelled by the next change, after which a hover or goto
+                // computes what's needed anyway.
+                if analysis.prime_caches_for_file(file_id).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+
     fn update_diagnostics(&mut self) {
         let subscriptions = self
             .mem_docs
@@ -791,7 +957,7 @@ impl GlobalState {
         tracing::trace!("updating notifications for {:?}", subscriptions);
 
         let snapshot = self.snapshot();
-        self.task_pool.handle.spawn(move || {
+        self.task_pool.handle.spawn(Priority::Normal, move || {
             let diagnostics = subscriptions
                 .into_iter()
                 .filter_map(|file_id| {
@@ -1,36 +1,41 @@
 //! Project loading & configuration updates
-use std::{mem, sync::Arc};
+use std::{cell::Cell, mem, sync::Arc};
 
 use flycheck::{FlycheckConfig, FlycheckHandle};
-use hir::db::DefDatabase;
+use hir::db::{DefDatabase, HirDatabase};
 use ide::Change;
 use ide_db::base_db::{
     CrateGraph, Env, ProcMacro, ProcMacroExpander, ProcMacroExpansionError, ProcMacroKind,
     SourceRoot, VfsPath,
 };
 use proc_macro_api::{MacroDylib, ProcMacroServer};
-use project_model::{ProjectWorkspace, WorkspaceBuildScripts};
+use project_model::{ProjectManifest, ProjectWorkspace, Sysroot, WorkspaceBuildScripts};
+use rustc_hash::{FxHashMap, FxHashSet};
 use syntax::SmolStr;
 use vfs::{file_set::FileSetConfig, AbsPath, AbsPathBuf, ChangeKind};
 
 use crate::{
-    config::{Config, FilesWatcher, LinkedProject},
+    config::{Config, FilesConfig, FilesWatcher, LinkedProject},
     global_state::GlobalState,
     lsp_ext,
+    lsp_utils::{PhaseProgress, Progress},
     main_loop::Task,
+    thread_pool::Priority,
 };
 
+const INSTALL_RUST_SRC: &str = "Install rust-src";
+
 #[derive(Debug)]
 pub(crate) enum ProjectWorkspaceProgress {
     Begin,
-    Report(String),
+    Report(PhaseProgress),
     End(Vec<anyhow::Result<ProjectWorkspace>>),
 }
 
 #[derive(Debug)]
 pub(crate) enum BuildDataProgress {
     Begin,
-    Report(String),
+    Report(PhaseProgress),
     End((Arc<Vec<ProjectWorkspace>>, Vec<anyhow::Result<WorkspaceBuildScripts>>)),
 }
 
@@ -48,16 +53,77 @@ impl GlobalState {
         if self.config.lru_capacity() != old_config.lru_capacity() {
             self.analysis_host.update_lru_capacity(self.config.lru_capacity());
         }
-        if self.config.linked_projects() != old_config.linked_projects() {
-            self.fetch_workspaces_queue.request_op()
-        } else if self.config.flycheck() != old_config.flycheck() {
-            self.reload_flycheck();
+        // Process-wide rather than a database input, so it's set on startup too.
+        ide::set_inference_profiling(self.config.inference_profiling());
+
+        // Only restart what depends on the settings which actually changed, the
+        // rest of the analysis stays warm.
+        let mut reinitialized = Vec::new();
+        let roots = self
+            .workspaces
+            .iter()
+            .filter_map(|ws| ws.root().map(AbsPath::to_path_buf))
+            .collect::<Vec<_>>();
+        if self.config.linked_projects() != old_config.linked_projects()
+            || self.config.cargo() != old_config.cargo()
+            || roots.iter().any(|root| self.config.cargo_for(root) != old_config.cargo_for(root))
+        {
+            // Switching to the new workspaces recreates everything below.
+            self.fetch_workspaces_queue.request_op();
+            reinitialized.push(lsp_ext::Subsystem::ProjectModel);
+        } else {
+            let proc_macro_srv_changed =
+                self.config.proc_macro_srv() != old_config.proc_macro_srv();
+            if proc_macro_srv_changed {
+                self.proc_macro_client = None;
+                reinitialized.push(lsp_ext::Subsystem::ProcMacroServer);
+            }
+            if proc_macro_srv_changed
+                || self.config.dummy_replacements() != old_config.dummy_replacements()
+                || self.config.files() != old_config.files()
+            {
+                self.recreate_crate_graph();
+                reinitialized.push(lsp_ext::Subsystem::CrateGraph);
+            }
+            if self.config.flycheck() != old_config.flycheck()
+                || roots
+                    .iter()
+                    .any(|root| self.config.flycheck_for(root) != old_config.flycheck_for(root))
+            {
+                self.reload_flycheck();
+                reinitialized.push(lsp_ext::Subsystem::Flycheck);
+            }
+        }
+
+        // Setting a salsa input invalidates everything depending on it, even if
+        // the value is the same, so only touch the ones that changed.
+        let db = self.analysis_host.raw_database_mut();
+        let mut analysis_changed = false;
+        let enable_proc_attr_macros = self.config.expand_proc_attr_macros();
+        if db.enable_proc_attr_macros() != enable_proc_attr_macros {
+            db.set_enable_proc_attr_macros(enable_proc_attr_macros);
+            analysis_changed = true;
+        }
+        let trait_solver_fuel = self.config.trait_solver_fuel();
+        if db.trait_solver_fuel() != trait_solver_fuel {
+            db.set_trait_solver_fuel(trait_solver_fuel);
+            analysis_changed = true;
+        }
+        if analysis_changed {
+            reinitialized.push(lsp_ext::Subsystem::Analysis);
         }
 
-        // Apply experimental feature flags.
-        self.analysis_host
-            .raw_database_mut()
-            .set_enable_proc_attr_macros(self.config.expand_proc_attr_macros());
+        // Highlighting depends on the config, so cached tokens can't be reused as is.
+        self.semantic_tokens_cache.lock().bump_revision();
+
+        if !reinitialized.is_empty() {
+            tracing::info!("reinitialized after config change: {:?}", reinitialized);
+            if self.config.configuration_applied_notification() {
+                self.send_notification::<lsp_ext::ConfigurationApplied>(
+                    lsp_ext::ConfigurationAppliedParams { reinitialized },
+                );
+            }
+        }
     }
 
     pub(crate) fn current_status(&self) -> lsp_ext::ServerStatusParams {
@@ -94,38 +160,62 @@ impl GlobalState {
     pub(crate) fn fetch_workspaces(&mut self) {
         tracing::info!("will fetch workspaces");
 
-        self.task_pool.handle.spawn_with_sender({
-            let linked_projects = self.config.linked_projects();
+        self.task_pool.handle.spawn_with_sender(Priority::Normal, {
+            // Each project is loaded with the settings of the workspace folder
+            // it lives in.
+            let linked_projects = self
+                .config
+                .linked_projects()
+                .into_iter()
+                .map(|project| {
+                    let manifest = match &project {
+                        LinkedProject::ProjectManifest(
+                            ProjectManifest::CargoToml(manifest)
+                            | ProjectManifest::ProjectJson(manifest),
+                        ) => manifest.clone(),
+                        LinkedProject::InlineJsonProject(it) => it.path().to_path_buf(),
+                    };
+                    let cargo_config = self.config.cargo_for(&manifest);
+                    (project, cargo_config, manifest.display().to_string())
+                })
+                .collect::<Vec<_>>();
             let detached_files = self.config.detached_files().to_vec();
-            let cargo_config = self.config.cargo();
 
             move |sender| {
-                let progress = {
-                    let sender = sender.clone();
-                    move |msg| {
-                        sender
-                            .send(Task::FetchWorkspace(ProjectWorkspaceProgress::Report(msg)))
-                            .unwrap()
-                    }
-                };
-
                 sender.send(Task::FetchWorkspace(ProjectWorkspaceProgress::Begin)).unwrap();
 
+                let total = linked_projects.len();
                 let mut workspaces = linked_projects
                     .iter()
-                    .map(|project| match project {
-                        LinkedProject::ProjectManifest(manifest) => {
-                            project_model::ProjectWorkspace::load(
-                                manifest.clone(),
-                                &cargo_config,
-                                &progress,
-                            )
-                        }
-                        LinkedProject::InlineJsonProject(it) => {
-                            project_model::ProjectWorkspace::load_inline(
-                                it.clone(),
-                                cargo_config.target.as_deref(),
-                            )
+                    .enumerate()
+                    .map(|(done, (project, cargo_config, manifest))| {
+                        let progress = || {
+                            let progress = PhaseProgress {
+                                done: Some(done),
+                                total: Some(total),
+                                current: vec![manifest.clone()],
+                            };
+                            sender
+                                .send(Task::FetchWorkspace(ProjectWorkspaceProgress::Report(
+                                    progress,
+                                )))
+                                .unwrap()
+                        };
+                        progress();
+                        match project {
+                            LinkedProject::ProjectManifest(manifest) => {
+                                project_model::ProjectWorkspace::load(
+                                    manifest.clone(),
+                                    cargo_config,
+                                    &|_| progress(),
+                                )
+                            }
+                            LinkedProject::InlineJsonProject(it) => {
+                                project_model::ProjectWorkspace::load_inline(
+                                    it.clone(),
+                                    cargo_config.target.as_deref(),
+                                )
+                            }
                         }
                     })
                     .collect::<Vec<_>>();
@@ -145,19 +235,36 @@ impl GlobalState {
 
     pub(crate) fn fetch_build_data(&mut self) {
         let workspaces = Arc::clone(&self.workspaces);
-        let config = self.config.cargo();
-        self.task_pool.handle.spawn_with_sender(move |sender| {
+        let configs = workspaces
+            .iter()
+            .map(|ws| match ws.root() {
+                Some(root) => self.config.cargo_for(root),
+                None => self.config.cargo(),
+            })
+            .collect::<Vec<_>>();
+        self.task_pool.handle.spawn_with_sender(Priority::Normal, move |sender| {
             sender.send(Task::FetchBuildData(BuildDataProgress::Begin)).unwrap();
 
-            let progress = {
-                let sender = sender.clone();
-                move |msg| {
-                    sender.send(Task::FetchBuildData(BuildDataProgress::Report(msg))).unwrap()
-                }
+            // Every crate cargo is done compiling is reported as `metadata <name>`.
+            let crates_done = Cell::new(0);
+            let progress = |msg: String| {
+                let current = match msg.strip_prefix("metadata ") {
+                    Some(name) => {
+                        crates_done.set(crates_done.get() + 1);
+                        name.to_string()
+                    }
+                    None => msg,
+                };
+                let progress = PhaseProgress {
+                    done: Some(crates_done.get()),
+                    total: None,
+                    current: vec![current],
+                };
+                sender.send(Task::FetchBuildData(BuildDataProgress::Report(progress))).unwrap()
             };
             let mut res = Vec::new();
-            for ws in workspaces.iter() {
-                res.push(ws.run_build_scripts(&config, &progress));
+            for (ws, config) in workspaces.iter().zip(&configs) {
+                res.push(ws.run_build_scripts(config, &progress));
             }
             sender.send(Task::FetchBuildData(BuildDataProgress::End((workspaces, res)))).unwrap();
         });
@@ -187,33 +294,11 @@ impl GlobalState {
             .filter_map(|res| res.as_ref().ok().cloned())
             .collect::<Vec<_>>();
 
-        fn eq_ignore_build_data<'a>(
-            left: &'a ProjectWorkspace,
-            right: &'a ProjectWorkspace,
-        ) -> bool {
-            let key = |p: &'a ProjectWorkspace| match p {
-                ProjectWorkspace::Cargo {
-                    cargo,
-                    sysroot,
-                    rustc,
-                    rustc_cfg,
-                    cfg_overrides,
-
-                    build_scripts: _,
-                } => Some((cargo, sysroot, rustc, rustc_cfg, cfg_overrides)),
-                _ => None,
-            };
-            match (key(left), key(right)) {
-                (Some(lk), Some(rk)) => lk == rk,
-                _ => left == right,
-            }
-        }
-
         let same_workspaces = workspaces.len() == self.workspaces.len()
             && workspaces
                 .iter()
                 .zip(self.workspaces.iter())
-                .all(|(l, r)| eq_ignore_build_data(l, r));
+                .all(|(l, r)| l.eq_ignore_build_data(r));
 
         if same_workspaces {
             let (workspaces, build_scripts) = self.fetch_build_data_queue.last_op_result();
@@ -236,8 +321,39 @@ impl GlobalState {
                 return;
             }
         } else {
-            // Here, we completely changed the workspace (Cargo.toml edit), so
-            // we don't care about build-script results, they are stale.
+            // Here, we changed the workspace (Cargo.toml edit or a new toolchain),
+            // so build-script results are stale, except for the workspaces which
+            // didn't change. Those keep the results of the last run, which went
+            // through the workspaces as they were then, until the build scripts
+            // are rerun.
+            let (old_workspaces, build_scripts) = self.fetch_build_data_queue.last_op_result();
+            let workspaces = workspaces
+                .into_iter()
+                .map(|mut ws| {
+                    let unchanged = old_workspaces
+                        .iter()
+                        .zip(build_scripts)
+                        .find(|(old, _)| old.eq_ignore_build_data(&ws))
+                        .and_then(|(_, bs)| bs.as_ref().ok());
+                    if let Some(bs) = unchanged {
+                        ws.set_build_scripts(bs.clone());
+                    }
+                    ws
+                })
+                .collect::<Vec<_>>();
+
+            // Proc macros built for another toolchain use another ABI, so start
+            // a fresh server rather than keep serving the dylibs it has loaded.
+            let sysroots = |workspaces: &[ProjectWorkspace]| -> FxHashSet<Option<AbsPathBuf>> {
+                workspaces.iter().map(|ws| ws.sysroot().map(|it| it.root().to_path_buf())).collect()
+            };
+            if !self.workspaces.is_empty()
+                && sysroots(&workspaces) != sysroots(self.workspaces.as_slice())
+            {
+                tracing::info!("toolchain changed, restarting the proc-macro server");
+                self.proc_macro_client = None;
+            }
+
             self.workspaces = Arc::new(workspaces)
         }
 
@@ -257,6 +373,12 @@ impl GlobalState {
                             ]
                         })
                     })
+                    .chain(
+                        self.workspaces
+                            .iter()
+                            .flat_map(|ws| ws.toolchain_files())
+                            .map(|path| path.display().to_string()),
+                    )
                     .map(|glob_pattern| lsp_types::FileSystemWatcher { glob_pattern, kind: None })
                     .collect(),
             };
@@ -271,10 +393,20 @@ impl GlobalState {
             );
         }
 
+        self.recreate_crate_graph();
+        self.reload_flycheck();
+        self.report_missing_rust_src();
+        tracing::info!("did switch workspaces");
+    }
+
+    /// Reloads the VFS and rebuilds the crate graph from the current
+    /// workspaces.
+    fn recreate_crate_graph(&mut self) {
+        let _p = profile::span("GlobalState::recreate_crate_graph");
         let mut change = Change::new();
 
         let files_config = self.config.files();
-        let project_folders = ProjectFolders::new(&self.workspaces, &files_config.exclude);
+        let project_folders = ProjectFolders::new(&self.workspaces, &files_config);
 
         if self.proc_macro_client.is_none() {
             self.proc_macro_client = match self.config.proc_macro_srv() {
@@ -304,10 +436,22 @@ impl GlobalState {
             version: self.vfs_config_version,
         });
 
+        let loads_proc_macros = self.proc_macro_client.is_some();
+        if loads_proc_macros {
+            self.report_loading_progress(
+                lsp_ext::LoadingPhase::ProcMacros,
+                Progress::Begin,
+                PhaseProgress::default(),
+            );
+        }
+        let mut proc_macros_loaded = 0;
+
         // Create crate graph from all the workspaces
         let crate_graph = {
             let proc_macro_client = self.proc_macro_client.as_ref();
+            let proc_macros_loaded = &mut proc_macros_loaded;
             let mut load_proc_macro = move |path: &AbsPath, dummy_replace: &_| {
+                *proc_macros_loaded += 1;
                 load_proc_macro(proc_macro_client, path, dummy_replace)
             };
 
@@ -340,12 +484,66 @@ impl GlobalState {
         };
         change.set_crate_graph(crate_graph);
 
+        if loads_proc_macros {
+            let progress = PhaseProgress {
+                done: Some(proc_macros_loaded),
+                total: Some(proc_macros_loaded),
+                current: Vec::new(),
+            };
+            self.report_loading_progress(
+                lsp_ext::LoadingPhase::ProcMacros,
+                Progress::End,
+                progress,
+            );
+        }
+
+        let virtual_docs = mem::take(&mut self.source_root_config.virtual_docs);
         self.source_root_config = project_folders.source_root_config;
+        self.source_root_config.virtual_docs = virtual_docs;
 
+        self.semantic_tokens_cache.lock().bump_revision();
+        self.crate_graph_generation += 1;
         self.analysis_host.apply_change(change);
         self.process_changes();
-        self.reload_flycheck();
-        tracing::info!("did switch workspaces");
+    }
+
+    /// Instead of letting every `std` import fail to resolve, tell the user
+    /// once that we fell back to a stub sysroot and offer to fix it.
+    fn report_missing_rust_src(&mut self) {
+        let has_stub_sysroot = self.workspaces.iter().any(|ws| ws.has_stub_sysroot());
+        if self.rust_src_missing_reported || !has_stub_sysroot {
+            return;
+        }
+        self.rust_src_missing_reported = true;
+
+        self.send_request::<lsp_types::request::ShowMessageRequest>(
+            lsp_types::ShowMessageRequestParams {
+                typ: lsp_types::MessageType::WARNING,
+                message: "The `rust-src` component is not installed, so only a minimal stub of \
+                          the standard library is available. Install it with \
+                          `rustup component add rust-src`?"
+                    .to_string(),
+                actions: Some(vec![lsp_types::MessageActionItem {
+                    title: INSTALL_RUST_SRC.to_string(),
+                    properties: Default::default(),
+                }]),
+            },
+            |this, resp| {
+                let action = resp.result.and_then(|it| {
+                    serde_json::from_value::<Option<lsp_types::MessageActionItem>>(it)
+                        .ok()
+                        .flatten()
+                });
+                if action.map_or(true, |it| it.title != INSTALL_RUST_SRC) {
+                    return;
+                }
+                let root = this.config.root_path.clone();
+                this.task_pool.handle.spawn_with_sender(Priority::Normal, move |sender| {
+                    let res = Sysroot::install_rust_src(&root).map_err(|err| format!("{:#}", err));
+                    sender.send(Task::InstallRustSrc(res)).unwrap();
+                });
+            },
+        );
     }
 
     fn fetch_workspace_error(&self) -> Option<String> {
@@ -392,37 +590,31 @@ impl GlobalState {
 
     fn reload_flycheck(&mut self) {
         let _p = profile::span("GlobalState::reload_flycheck");
-        let config = match self.config.flycheck() {
-            Some(it) => it,
-            None => {
-                self.flycheck = Vec::new();
-                return;
-            }
-        };
-
         let sender = self.flycheck_sender.clone();
+        let config = &self.config;
         self.flycheck = self
             .workspaces
             .iter()
             .enumerate()
-            .filter_map(|(id, w)| match w {
-                ProjectWorkspace::Cargo { cargo, .. } => Some((id, cargo.workspace_root())),
-                ProjectWorkspace::Json { project, .. } => {
+            .filter_map(|(id, w)| {
+                let root = w.root()?;
+                let config = config.flycheck_for(root)?;
+                match (w, &config) {
+                    (ProjectWorkspace::Cargo { .. }, _) => Some((id, config, root)),
                     // Enable flychecks for json projects if a custom flycheck command was supplied
                     // in the workspace configuration.
-                    match config {
-                        FlycheckConfig::CustomCommand { .. } => Some((id, project.path())),
-                        _ => None,
+                    (ProjectWorkspace::Json { .. }, FlycheckConfig::CustomCommand { .. }) => {
+                        Some((id, config, root))
                     }
+                    _ => None,
                 }
-                ProjectWorkspace::DetachedFiles { .. } => None,
             })
-            .map(|(id, root)| {
+            .map(|(id, config, root)| {
                 let sender = sender.clone();
                 FlycheckHandle::spawn(
                     id,
                     Box::new(move |msg| sender.send(msg).unwrap()),
-                    config.clone(),
+                    config,
                     root.to_path_buf(),
                 )
             })
@@ -438,10 +630,7 @@ pub(crate) struct ProjectFolders {
 }
 
 impl ProjectFolders {
-    pub(crate) fn new(
-        workspaces: &[ProjectWorkspace],
-        global_excludes: &[AbsPathBuf],
-    ) -> ProjectFolders {
+    pub(crate) fn new(workspaces: &[ProjectWorkspace], files: &FilesConfig) -> ProjectFolders {
         let mut res = ProjectFolders::default();
         let mut fsc = FileSetConfig::builder();
         let mut local_filesets = vec![];
@@ -455,7 +644,7 @@ impl ProjectFolders {
                 dirs.extensions.push("rs".into());
                 dirs.include.extend(root.include);
                 dirs.exclude.extend(root.exclude);
-                for excl in global_excludes {
+                for excl in &files.exclude {
                     if dirs
                         .include
                         .iter()
@@ -464,6 +653,14 @@ impl ProjectFolders {
                         dirs.exclude.push(excl.clone());
                     }
                 }
+                dirs.exclude_globs.extend(files.exclude_globs.iter().cloned());
+                for (path, globs) in &files.root_exclude_globs {
+                    if dirs.include.contains(path) {
+                        dirs.exclude_globs.extend(globs.iter().cloned());
+                    }
+                }
+                // Dependencies and the sysroot are loaded whatever their ignore files say.
+                dirs.respect_ignore_files = files.respect_ignore_files && root.is_local;
 
                 vfs::loader::Entry::Directories(dirs)
             };
@@ -479,8 +676,19 @@ impl ProjectFolders {
             fsc.add_file_set(file_set_roots)
         }
 
+        // Loaded only to notice when they change, they don't belong to any root.
+        let mut toolchain_files =
+            workspaces.iter().flat_map(|ws| ws.toolchain_files()).collect::<Vec<_>>();
+        toolchain_files.sort();
+        toolchain_files.dedup();
+        if !toolchain_files.is_empty() {
+            res.watch.push(res.load.len());
+            res.load.push(vfs::loader::Entry::Files(toolchain_files));
+        }
+
         let fsc = fsc.build();
-        res.source_root_config = SourceRootConfig { fsc, local_filesets };
+        res.source_root_config =
+            SourceRootConfig { fsc, local_filesets, virtual_docs: FxHashMap::default() };
 
         res
     }
@@ -490,13 +698,16 @@ impl ProjectFolders {
 pub(crate) struct SourceRootConfig {
     pub(crate) fsc: FileSetConfig,
     pub(crate) local_filesets: Vec<usize>,
+    /// Virtual documents registered by the client, mapped to the file whose
+    /// source root they join.
+    pub(crate) virtual_docs: FxHashMap<VfsPath, VfsPath>,
 }
 
 impl SourceRootConfig {
     pub(crate) fn partition(&self, vfs: &vfs::Vfs) -> Vec<SourceRoot> {
         let _p = profile::span("SourceRootConfig::partition");
         self.fsc
-            .partition(vfs)
+            .partition_with_aliases(vfs, &self.virtual_docs)
             .into_iter()
             .enumerate()
             .map(|(idx, file_set)| {
@@ -601,6 +812,17 @@ pub(crate) fn load_proc_macro(
     }
 }
 
+/// Whether `path` selects a toolchain, so that changing it may change the
+/// sysroot and the proc-macro ABI.
+fn is_toolchain_file(path: &AbsPath) -> bool {
+    let file_name = path.file_name().unwrap_or_default();
+    if file_name == "rust-toolchain" || file_name == "rust-toolchain.toml" {
+        return true;
+    }
+    file_name == "settings.toml"
+        && toolchain::rustup_home().map_or(false, |home| path.as_ref() == home.join(file_name))
+}
+
 pub(crate) fn should_refresh_for_change(path: &AbsPath, change_kind: ChangeKind) -> bool {
     const IMPLICIT_TARGET_FILES: &[&str] = &["build.rs", "src/main.rs", "src/lib.rs"];
     const IMPLICIT_TARGET_DIRS: &[&str] = &["src/bin", "examples", "tests", "benches"];
@@ -609,6 +831,9 @@ pub(crate) fn should_refresh_for_change(path: &AbsPath, change_kind: ChangeKind)
     if file_name == "Cargo.toml" || file_name == "Cargo.lock" {
         return true;
     }
+    if is_toolchain_file(path) {
+        return true;
+    }
     if change_kind == ChangeKind::Modify {
         return false;
     }
@@ -636,3 +861,27 @@ pub(crate) fn should_refresh_for_change(path: &AbsPath, change_kind: ChangeKind)
     }
     false
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn toolchain_changes_refresh_the_workspace() {
+        let ws = std::env::temp_dir().join("ws");
+        let path = |rel: &str| AbsPathBuf::assert(ws.join(rel));
+
+        for file in ["rust-toolchain", "rust-toolchain.toml"] {
+            assert!(should_refresh_for_change(&path(file), ChangeKind::Modify));
+            assert!(should_refresh_for_change(&path(file), ChangeKind::Create));
+        }
+        assert!(!should_refresh_for_change(&path("settings.toml"), ChangeKind::Modify));
+        assert!(!should_refresh_for_change(&path("src/foo.rs"), ChangeKind::Modify));
+
+        let rustup_settings = toolchain::rustup_home()
+            .and_then(|home| AbsPathBuf::try_from(home.join("settings.toml")).ok());
+        if let Some(settings) = rustup_settings {
+            assert!(should_refresh_for_change(&settings, ChangeKind::Modify));
+        }
+    }
+}
@@ -4,8 +4,9 @@ use std::ops;
 
 use lsp_types::{
     Range, SemanticToken, SemanticTokenModifier, SemanticTokenType, SemanticTokens,
-    SemanticTokensEdit,
+    SemanticTokensEdit, Url,
 };
+use rustc_hash::FxHashMap;
 
 macro_rules! define_semantic_token_types {
     ($(($ident:ident, $string:literal)),*$(,)?) => {
@@ -195,6 +196,79 @@ pub(crate) fn diff_tokens(old: &[SemanticToken], new: &[SemanticToken]) -> Vec<S
     }
 }
 
+/// Returns the tokens starting inside `range`, re-encoded relative to each
+/// other.
+pub(crate) fn tokens_in_range(tokens: &[SemanticToken], range: Range) -> Vec<SemanticToken> {
+    let start = (range.start.line, range.start.character);
+    let end = (range.end.line, range.end.character);
+
+    let mut res = Vec::new();
+    let (mut line, mut char) = (0, 0);
+    let (mut prev_line, mut prev_char) = (0, 0);
+    for token in tokens {
+        if token.delta_line == 0 {
+            char += token.delta_start;
+        } else {
+            line += token.delta_line;
+            char = token.delta_start;
+        }
+        if (line, char) < start {
+            continue;
+        }
+        if (line, char) >= end {
+            break;
+        }
+
+        let delta_line = line - prev_line;
+        let delta_start = if delta_line == 0 { char - prev_char } else { char };
+        res.push(SemanticToken { delta_line, delta_start, ..*token });
+        prev_line = line;
+        prev_char = char;
+    }
+    res
+}
+
+/// The token sets last sent to the client, per document.
+///
+/// Every change to the analysis bumps the revision, so tokens computed before
+/// it can still serve as the base of a delta, but not as an answer on their own.
+#[derive(Default)]
+pub(crate) struct SemanticTokensCache {
+    revision: u64,
+    docs: FxHashMap<Url, (u64, SemanticTokens)>,
+}
+
+impl SemanticTokensCache {
+    pub(crate) fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    pub(crate) fn bump_revision(&mut self) {
+        self.revision += 1;
+    }
+
+    /// Returns the last tokens of `url`, whether or not they are up to date.
+    pub(crate) fn get(&self, url: &Url) -> Option<&SemanticTokens> {
+        self.docs.get(url).map(|(_, tokens)| tokens)
+    }
+
+    /// Returns the tokens of `url` if they were computed at `revision`.
+    pub(crate) fn get_fresh(&self, url: &Url, revision: u64) -> Option<&SemanticTokens> {
+        match self.docs.get(url) {
+            Some((rev, tokens)) if *rev == revision => Some(tokens),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn insert(&mut self, url: Url, revision: u64, tokens: SemanticTokens) {
+        self.docs.insert(url, (revision, tokens));
+    }
+
+    pub(crate) fn remove(&mut self, url: &Url) {
+        self.docs.remove(url);
+    }
+}
+
 pub(crate) fn type_index(ty: SemanticTokenType) -> u32 {
     SUPPORTED_TYPES.iter().position(|it| *it == ty).unwrap() as u32
 }
@@ -297,4 +371,23 @@ mod tests {
         let edits = diff_tokens(&before, &after);
         assert_eq!(edits[0], SemanticTokensEdit { start: 5, delete_count: 10, data: Some(vec![]) });
     }
+
+    #[test]
+    fn test_tokens_in_range() {
+        // Tokens at (0, 4), (0, 10), (2, 1), (2, 6), (5, 0).
+        let tokens = [
+            from((0, 4, 1, 0, 0)),
+            from((0, 6, 1, 1, 0)),
+            from((2, 1, 1, 2, 0)),
+            from((0, 5, 1, 3, 0)),
+            from((3, 0, 1, 4, 0)),
+        ];
+        let range = Range::new(lsp_types::Position::new(0, 8), lsp_types::Position::new(5, 0));
+
+        let sliced = tokens_in_range(&tokens, range);
+        assert_eq!(
+            sliced,
+            vec![from((0, 10, 1, 1, 0)), from((2, 1, 1, 2, 0)), from((0, 5, 1, 3, 0))]
+        );
+    }
 }
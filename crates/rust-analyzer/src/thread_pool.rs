@@ -1,46 +1,191 @@
-//! A thin wrapper around `ThreadPool` to make sure that we join all things
-//! properly.
+//! A small thread pool which runs tasks in priority order and makes sure that
+//! we join all things properly.
+//!
+//! Latency-sensitive requests (completion, on-type formatting) jump the queue,
+//! and one worker only ever picks those up, so they don't lag behind a
+//! background references search even when all other workers are busy. Running
+//! tasks are never interrupted by the pool itself: long ones checkpoint via
+//! salsa cancellation and unwind as soon as the next change is applied.
+use std::{
+    cmp::Ordering,
+    collections::BinaryHeap,
+    panic::{self, AssertUnwindSafe},
+    sync::Arc,
+};
+
 use crossbeam_channel::Sender;
+use parking_lot::{Condvar, Mutex};
+
+/// The order in which queued tasks are picked up by the workers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum Priority {
+    /// Requests which may take a long time, like workspace-wide searches.
+    Low,
+    Normal,
+    /// Requests the user is waiting on while typing.
+    High,
+}
 
 pub(crate) struct TaskPool<T> {
     sender: Sender<T>,
-    inner: threadpool::ThreadPool,
+    shared: Arc<Shared>,
+    _workers: Vec<jod_thread::JoinHandle>,
 }
 
 impl<T> TaskPool<T> {
     pub(crate) fn new(sender: Sender<T>) -> TaskPool<T> {
-        TaskPool { sender, inner: threadpool::ThreadPool::default() }
+        let shared = Arc::new(Shared::default());
+        let general = num_cpus::get().max(1);
+        let workers = (0..=general)
+            .map(|idx| {
+                // The last worker is reserved for latency-sensitive tasks.
+                let min_priority = if idx == general { Priority::High } else { Priority::Low };
+                let shared = Arc::clone(&shared);
+                jod_thread::Builder::new()
+                    .name(format!("Worker{}", idx))
+                    .spawn(move || shared.run(min_priority))
+                    .expect("failed to spawn thread")
+            })
+            .collect();
+        TaskPool { sender, shared, _workers: workers }
     }
 
-    pub(crate) fn spawn<F>(&mut self, task: F)
+    pub(crate) fn spawn<F>(&mut self, priority: Priority, task: F)
     where
         F: FnOnce() -> T + Send + 'static,
         T: Send + 'static,
     {
-        self.inner.execute({
-            let sender = self.sender.clone();
-            move || sender.send(task()).unwrap()
-        })
+        let sender = self.sender.clone();
+        self.shared.push(priority, Box::new(move || sender.send(task()).unwrap()))
     }
 
-    pub(crate) fn spawn_with_sender<F>(&mut self, task: F)
+    pub(crate) fn spawn_with_sender<F>(&mut self, priority: Priority, task: F)
     where
         F: FnOnce(Sender<T>) + Send + 'static,
         T: Send + 'static,
     {
-        self.inner.execute({
-            let sender = self.sender.clone();
-            move || task(sender)
-        })
+        let sender = self.sender.clone();
+        self.shared.push(priority, Box::new(move || task(sender)))
     }
 
     pub(crate) fn len(&self) -> usize {
-        self.inner.queued_count()
+        self.shared.state.lock().queue.len()
     }
 }
 
 impl<T> Drop for TaskPool<T> {
     fn drop(&mut self) {
-        self.inner.join()
+        // Workers drain the queue before exiting, and are joined when
+        // `_workers` is dropped right after this.
+        self.shared.state.lock().shutdown = true;
+        self.shared.cond.notify_all();
+    }
+}
+
+#[derive(Default)]
+struct Shared {
+    state: Mutex<State>,
+    cond: Condvar,
+}
+
+#[derive(Default)]
+struct State {
+    queue: BinaryHeap<Job>,
+    /// Number of jobs pushed so far, used to keep the order of jobs of equal
+    /// priority.
+    seq: u64,
+    shutdown: bool,
+}
+
+impl Shared {
+    fn push(&self, priority: Priority, f: Box<dyn FnOnce() + Send>) {
+        let mut state = self.state.lock();
+        let seq = state.seq;
+        state.seq += 1;
+        state.queue.push(Job { priority, seq, f });
+        drop(state);
+        // The woken up worker might not accept this priority, so wake them all.
+        self.cond.notify_all();
+    }
+
+    fn run(&self, min_priority: Priority) {
+        loop {
+            let job = {
+                let mut state = self.state.lock();
+                loop {
+                    if state.queue.peek().map_or(false, |job| job.priority >= min_priority) {
+                        break state.queue.pop();
+                    }
+                    if state.shutdown {
+                        break None;
+                    }
+                    self.cond.wait(&mut state);
+                }
+            };
+            match job {
+                // Tasks report their own panics; keep the worker alive.
+                Some(job) => {
+                    let _ = panic::catch_unwind(AssertUnwindSafe(job.f));
+                }
+                None => return,
+            }
+        }
+    }
+}
+
+struct Job {
+    priority: Priority,
+    seq: u64,
+    f: Box<dyn FnOnce() + Send>,
+}
+
+impl PartialEq for Job {
+    fn eq(&self, other: &Job) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Job {}
+
+impl PartialOrd for Job {
+    fn partial_cmp(&self, other: &Job) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Job {
+    fn cmp(&self, other: &Job) -> Ordering {
+        // Highest priority first, then first in, first out.
+        self.priority.cmp(&other.priority).then_with(|| other.seq.cmp(&self.seq))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn runs_jobs_by_priority_then_in_order() {
+        let shared = Shared::default();
+        let order = Arc::new(Mutex::new(Vec::new()));
+        let jobs = [
+            (Priority::Low, "low"),
+            (Priority::Normal, "normal 1"),
+            (Priority::High, "high"),
+            (Priority::Normal, "normal 2"),
+        ];
+        for (priority, name) in jobs {
+            let order = Arc::clone(&order);
+            shared.push(priority, Box::new(move || order.lock().push(name)));
+        }
+        // Lets the workers return once they run out of jobs they accept.
+        shared.state.lock().shutdown = true;
+
+        // The reserved worker leaves the rest of the queue alone.
+        shared.run(Priority::High);
+        assert_eq!(*order.lock(), ["high"]);
+
+        shared.run(Priority::Low);
+        assert_eq!(*order.lock(), ["high", "normal 1", "normal 2", "low"]);
     }
 }
@@ -6,15 +6,16 @@ use std::{
 };
 
 use ide::{
-    Annotation, AnnotationKind, Assist, AssistKind, Cancellable, CompletionItem,
-    CompletionItemKind, CompletionRelevance, Documentation, FileId, FileRange, FileSystemEdit,
-    Fold, FoldKind, Highlight, HlMod, HlOperator, HlPunct, HlRange, HlTag, Indel, InlayHint,
-    InlayKind, Markup, NavigationTarget, ReferenceCategory, RenameError, Runnable, Severity,
-    SignatureHelp, SourceChange, StructureNodeKind, SymbolKind, TextEdit, TextRange, TextSize,
+    Annotation, AnnotationKind, Assist, AssistKind, Cancellable, ColorInformation, CompletionItem,
+    CompletionItemKind, CompletionRelevance, Documentation, ExpansionId, FileId, FileRange,
+    FileSystemEdit, Fold, FoldKind, Highlight, HlMod, HlOperator, HlPunct, HlRange, HlTag, Indel,
+    InlayHint, InlayKind, Markup, MonikerKind, MonikerResult, NavigationTarget, ReferenceCategory,
+    RenameError, Runnable, Severity, SignatureHelp, SourceChange, StructureNodeKind, SymbolKind,
+    TextEdit, TextRange, TextSize,
 };
 use itertools::Itertools;
 use serde_json::to_value;
-use vfs::AbsPath;
+use vfs::{AbsPath, VfsPath};
 
 use crate::{
     cargo_target_spec::CargoTargetSpec,
@@ -91,6 +92,7 @@ pub(crate) fn document_highlight_kind(
 pub(crate) fn diagnostic_severity(severity: Severity) -> lsp_types::DiagnosticSeverity {
     match severity {
         Severity::Error => lsp_types::DiagnosticSeverity::ERROR,
+        Severity::Warning => lsp_types::DiagnosticSeverity::WARNING,
         Severity::WeakWarning => lsp_types::DiagnosticSeverity::HINT,
     }
 }
@@ -110,6 +112,7 @@ pub(crate) fn completion_item_kind(
         CompletionItemKind::Keyword => lsp_types::CompletionItemKind::KEYWORD,
         CompletionItemKind::Method => lsp_types::CompletionItemKind::METHOD,
         CompletionItemKind::Snippet => lsp_types::CompletionItemKind::SNIPPET,
+        CompletionItemKind::Expression => lsp_types::CompletionItemKind::SNIPPET,
         CompletionItemKind::UnresolvedReference => lsp_types::CompletionItemKind::REFERENCE,
         CompletionItemKind::SymbolKind(symbol) => match symbol {
             SymbolKind::Attribute => lsp_types::CompletionItemKind::FUNCTION,
@@ -435,7 +438,9 @@ pub(crate) fn inlay_hint(
             | InlayKind::TypeHint
             | InlayKind::ChainingHint
             | InlayKind::GenericParamListHint
-            | InlayKind::LifetimeHint => position(line_index, inlay_hint.range.end()),
+            | InlayKind::LifetimeHint
+            | InlayKind::DiscriminantHint
+            | InlayKind::AwaitPointHint => position(line_index, inlay_hint.range.end()),
         },
         kind: match inlay_hint.kind {
             InlayKind::ParameterHint => Some(lsp_ext::InlayHintKind::PARAMETER),
@@ -444,7 +449,9 @@ pub(crate) fn inlay_hint(
             }
             InlayKind::GenericParamListHint
             | InlayKind::LifetimeHint
-            | InlayKind::ImplicitReborrow => None,
+            | InlayKind::ImplicitReborrow
+            | InlayKind::DiscriminantHint
+            | InlayKind::AwaitPointHint => None,
         },
         tooltip: None,
         padding_left: Some(match inlay_hint.kind {
@@ -454,6 +461,8 @@ pub(crate) fn inlay_hint(
             InlayKind::GenericParamListHint => false,
             InlayKind::LifetimeHint => false,
             InlayKind::ImplicitReborrow => false,
+            InlayKind::DiscriminantHint => true,
+            InlayKind::AwaitPointHint => true,
         }),
         padding_right: Some(match inlay_hint.kind {
             InlayKind::TypeHint | InlayKind::ChainingHint | InlayKind::ClosureReturnTypeHint => {
@@ -463,6 +472,8 @@ pub(crate) fn inlay_hint(
             InlayKind::LifetimeHint => true,
             InlayKind::GenericParamListHint => false,
             InlayKind::ImplicitReborrow => false,
+            InlayKind::DiscriminantHint => false,
+            InlayKind::AwaitPointHint => false,
         }),
     }
 }
@@ -673,10 +684,50 @@ pub(crate) fn folding_range(
     }
 }
 
+pub(crate) fn color_information(
+    line_index: &LineIndex,
+    info: ColorInformation,
+) -> lsp_types::ColorInformation {
+    let color = info.color;
+    lsp_types::ColorInformation {
+        range: range(line_index, info.range),
+        color: lsp_types::Color {
+            red: color.red,
+            green: color.green,
+            blue: color.blue,
+            alpha: color.alpha,
+        },
+    }
+}
+
 pub(crate) fn url(snap: &GlobalStateSnapshot, file_id: FileId) -> lsp_types::Url {
     snap.file_id_to_url(file_id)
 }
 
+/// Returns the url of the virtual document showing the macro expansion `id` of `file_id`, see
+/// `from_proto::expansion` for the reverse.
+pub(crate) fn expansion_url(
+    snap: &GlobalStateSnapshot,
+    file_id: FileId,
+    id: &ExpansionId,
+    name: &str,
+) -> lsp_types::Url {
+    let mut res = lsp_types::Url::parse("rust-analyzer-expansion:///").unwrap();
+    res.path_segments_mut().unwrap().push(&format!("{}.rs", name));
+    res.query_pairs_mut()
+        .append_pair("source", url(snap, file_id).as_str())
+        .append_pair("macro", &id.path)
+        .append_pair("occurrence", &id.occurrence.to_string());
+    res
+}
+
+/// Returns the url of a virtual document registered by the client, see
+/// `from_proto::vfs_path`.
+pub(crate) fn url_from_virtual_path(path: &VfsPath) -> Option<lsp_types::Url> {
+    let path = path.to_string();
+    lsp_types::Url::parse(path.strip_prefix('/')?).ok()
+}
+
 /// Returns a `Url` object from a given path, will lowercase drive letters if present.
 /// This will only happen when processing windows paths.
 ///
@@ -864,6 +915,32 @@ pub(crate) fn snippet_text_document_ops(
                 rename_file,
             )))
         }
+        FileSystemEdit::MoveDir { src, src_id, dst } => {
+            let old_uri = snap.anchored_path(&src);
+            let new_uri = snap.anchored_path(&dst);
+            let mut rename_file =
+                lsp_types::RenameFile { old_uri, new_uri, options: None, annotation_id: None };
+            if snap.analysis.is_library_file(src_id).ok() == Some(true)
+                && snap.config.change_annotation_support()
+            {
+                rename_file.annotation_id = Some(outside_workspace_annotation_id())
+            }
+            ops.push(lsp_ext::SnippetDocumentChangeOperation::Op(lsp_types::ResourceOp::Rename(
+                rename_file,
+            )))
+        }
+        FileSystemEdit::DeleteFile { src } => {
+            let uri = snap.file_id_to_url(src);
+            let mut delete_file = lsp_types::DeleteFile { uri, options: None, annotation_id: None };
+            if snap.analysis.is_library_file(src).ok() == Some(true)
+                && snap.config.change_annotation_support()
+            {
+                delete_file.annotation_id = Some(outside_workspace_annotation_id())
+            }
+            ops.push(lsp_ext::SnippetDocumentChangeOperation::Op(lsp_types::ResourceOp::Delete(
+                delete_file,
+            )))
+        }
     }
     Ok(ops)
 }
@@ -875,10 +952,18 @@ pub(crate) fn snippet_workspace_edit(
     let mut document_changes: Vec<lsp_ext::SnippetDocumentChangeOperation> = Vec::new();
 
     for op in source_change.file_system_edits {
+        if touches_virtual_doc(snap, &op) {
+            // Virtual documents are read-only, and paths next to them don't exist on disk.
+            continue;
+        }
         let ops = snippet_text_document_ops(snap, op)?;
         document_changes.extend_from_slice(&ops);
     }
     for (file_id, edit) in source_change.source_file_edits {
+        if snap.is_virtual_doc(file_id) {
+            // Virtual documents are read-only.
+            continue;
+        }
         let edit = snippet_text_document_edit(snap, source_change.is_snippet, file_id, edit)?;
         document_changes.push(lsp_ext::SnippetDocumentChangeOperation::Edit(edit));
     }
@@ -905,6 +990,21 @@ pub(crate) fn snippet_workspace_edit(
     Ok(workspace_edit)
 }
 
+fn touches_virtual_doc(snap: &GlobalStateSnapshot, file_system_edit: &FileSystemEdit) -> bool {
+    match file_system_edit {
+        FileSystemEdit::CreateFile { dst, .. } => snap.is_virtual_doc(dst.anchor),
+        FileSystemEdit::MoveFile { src, dst } => {
+            snap.is_virtual_doc(*src) || snap.is_virtual_doc(dst.anchor)
+        }
+        FileSystemEdit::MoveDir { src, src_id, dst } => {
+            snap.is_virtual_doc(src.anchor)
+                || snap.is_virtual_doc(*src_id)
+                || snap.is_virtual_doc(dst.anchor)
+        }
+        FileSystemEdit::DeleteFile { src } => snap.is_virtual_doc(*src),
+    }
+}
+
 pub(crate) fn workspace_edit(
     snap: &GlobalStateSnapshot,
     source_change: SourceChange,
@@ -978,6 +1078,18 @@ pub(crate) fn call_hierarchy_item(
     })
 }
 
+pub(crate) fn moniker(moniker: MonikerResult) -> lsp_types::Moniker {
+    lsp_types::Moniker {
+        scheme: "rust-analyzer".to_string(),
+        identifier: moniker.versioned_identifier(),
+        unique: lsp_types::UniquenessLevel::Global,
+        kind: Some(match moniker.kind {
+            MonikerKind::Import => lsp_types::MonikerKind::Import,
+            MonikerKind::Export => lsp_types::MonikerKind::Export,
+        }),
+    }
+}
+
 pub(crate) fn code_action_kind(kind: AssistKind) -> lsp_types::CodeActionKind {
     match kind {
         AssistKind::None | AssistKind::Generate => lsp_types::CodeActionKind::EMPTY,
@@ -1276,6 +1388,16 @@ pub(crate) fn rename_error(err: RenameError) -> crate::LspError {
     invalid_params_error(err.to_string())
 }
 
+pub(crate) fn impl_explanation(explanation: ide::ImplExplanation) -> lsp_ext::ImplExplanation {
+    lsp_ext::ImplExplanation {
+        goal: explanation.goal,
+        holds: explanation.holds,
+        reason: explanation.reason,
+        children: explanation.children.into_iter().map(impl_explanation).collect(),
+        rendered: None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
@@ -24,10 +24,12 @@ use lsp_types::{
     },
     CodeActionContext, CodeActionParams, CompletionParams, DidOpenTextDocumentParams,
     DocumentFormattingParams, FileRename, FormattingOptions, GotoDefinitionParams, HoverParams,
-    PartialResultParams, Position, Range, RenameFilesParams, TextDocumentItem,
-    TextDocumentPositionParams, WorkDoneProgressParams,
+    PartialResultParams, Position, Range, RenameFilesParams, TextDocumentIdentifier,
+    TextDocumentItem, TextDocumentPositionParams, Url, WorkDoneProgressParams,
+};
+use rust_analyzer::lsp_ext::{
+    OnEnter, RegisterVirtualDocument, RegisterVirtualDocumentParams, Runnables, RunnablesParams,
 };
-use rust_analyzer::lsp_ext::{OnEnter, Runnables, RunnablesParams};
 use serde_json::json;
 use test_utils::skip_slow_tests;
 
@@ -503,6 +505,52 @@ fn main() {}
     );
 }
 
+#[test]
+fn test_missing_module_code_action_in_virtual_document() {
+    if skip_slow_tests() {
+        return;
+    }
+
+    let server = project(
+        r#"
+//- /Cargo.toml
+[package]
+name = "foo"
+version = "0.0.0"
+
+//- /src/lib.rs
+fn main() {}
+"#,
+    )
+    .wait_until_workspace_is_loaded();
+
+    let uri = Url::parse("untitled:Untitled-1").unwrap();
+    server.notification::<RegisterVirtualDocument>(RegisterVirtualDocumentParams {
+        uri: uri.clone(),
+        anchor: server.doc_id("src/lib.rs"),
+    });
+    server.notification::<DidOpenTextDocument>(DidOpenTextDocumentParams {
+        text_document: TextDocumentItem {
+            uri: uri.clone(),
+            language_id: "rust".to_string(),
+            version: 0,
+            text: "mod bar;\n".to_string(),
+        },
+    });
+
+    // Virtual documents are read-only, so no file is created next to them.
+    server.request::<CodeActionRequest>(
+        CodeActionParams {
+            text_document: TextDocumentIdentifier { uri },
+            range: Range::new(Position::new(0, 4), Position::new(0, 7)),
+            context: CodeActionContext::default(),
+            partial_result_params: PartialResultParams::default(),
+            work_done_progress_params: WorkDoneProgressParams::default(),
+        },
+        json!([]),
+    );
+}
+
 #[test]
 fn test_missing_module_code_action_in_json_project() {
     if skip_slow_tests() {
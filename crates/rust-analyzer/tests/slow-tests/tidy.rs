@@ -285,6 +285,8 @@ fn check_todo(path: &Path, text: &str) {
         "handlers/generate_function.rs",
         "handlers/add_missing_match_arms.rs",
         "handlers/replace_derive_with_manual_impl.rs",
+        "handlers/wrap_return_type_in_result.rs",
+        "handlers/generate_test_module.rs",
         // To support generating `todo!()` in assists, we have `expr_todo()` in
         // `ast::make`.
         "ast/make.rs",
@@ -294,6 +296,8 @@ fn check_todo(path: &Path, text: &str) {
         "ide_assists/src/tests/generated.rs",
         // The tests for missing fields
         "ide_diagnostics/src/handlers/missing_fields.rs",
+        // Typed holes are filled with `todo!()`
+        "ide_diagnostics/src/handlers/typed_hole.rs",
     ];
     if need_todo.iter().any(|p| path.ends_with(p)) {
         return;
@@ -246,6 +246,21 @@ impl ast::WhereClause {
         }
         ted::append_child(self.syntax(), predicate.syntax());
     }
+
+    pub fn remove(&self) {
+        if let Some(prev) = self.syntax().prev_sibling_or_token() {
+            if prev.kind() == SyntaxKind::WHITESPACE {
+                ted::remove(prev);
+            }
+        }
+        // A where clause on its own lines is followed by the body on a new line too.
+        if let Some(next) = self.syntax().next_sibling_or_token() {
+            if next.kind() == SyntaxKind::WHITESPACE && next.to_string().contains('\n') {
+                ted::replace(next, make::tokens::single_space());
+            }
+        }
+        ted::remove(self.syntax());
+    }
 }
 
 impl ast::TypeBoundList {
@@ -663,6 +663,14 @@ pub fn item_const(
     ast_from_text(&format!("{} const {}: {} = {};", visibility, name, ty, expr))
 }
 
+pub fn ty_alias(name: ast::Name, ty: ast::Type) -> ast::TypeAlias {
+    ast_from_text(&format!("type {} = {};", name, ty))
+}
+
+pub fn untyped_param(pat: ast::Pat) -> ast::Param {
+    ast_from_text(&format!("fn f() {{ |{}| () }}", pat))
+}
+
 pub fn param(pat: ast::Pat, ty: ast::Type) -> ast::Param {
     ast_from_text(&format!("fn f({}: {}) {{ }}", pat, ty))
 }
@@ -688,6 +696,10 @@ pub fn param_list(
     ast_from_text(&list)
 }
 
+pub fn type_bound(bound: &str) -> ast::TypeBound {
+    ast_from_text(&format!("fn f() where T: {} {{ }}", bound))
+}
+
 pub fn type_param(name: ast::Name, ty: Option<ast::TypeBoundList>) -> ast::TypeParam {
     let bound = match ty {
         Some(it) => format!(": {}", it),
@@ -321,6 +321,13 @@ impl ast::UseTreeList {
     }
 }
 
+impl ast::Trait {
+    /// The `=` of a trait alias, `trait Foo = Bar + Baz;`.
+    pub fn eq_token(&self) -> Option<SyntaxToken> {
+        support::token(self.syntax(), T![=])
+    }
+}
+
 impl ast::Impl {
     pub fn self_ty(&self) -> Option<ast::Type> {
         match self.target() {
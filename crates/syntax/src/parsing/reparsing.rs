@@ -6,7 +6,7 @@
 //!   - otherwise, we search for the nearest `{}` block which contains the edit
 //!     and try to parse only this block.
 
-use parser::Reparser;
+use parser::{Edition, Reparser};
 use text_edit::Indel;
 
 use crate::{
@@ -21,12 +21,13 @@ pub(crate) fn incremental_reparse(
     node: &SyntaxNode,
     edit: &Indel,
     errors: Vec<SyntaxError>,
+    edition: Edition,
 ) -> Option<(GreenNode, Vec<SyntaxError>, TextRange)> {
-    if let Some((green, new_errors, old_range)) = reparse_token(node, edit) {
+    if let Some((green, new_errors, old_range)) = reparse_token(node, edit, edition) {
         return Some((green, merge_errors(errors, new_errors, old_range, edit), old_range));
     }
 
-    if let Some((green, new_errors, old_range)) = reparse_block(node, edit) {
+    if let Some((green, new_errors, old_range)) = reparse_block(node, edit, edition) {
         return Some((green, merge_errors(errors, new_errors, old_range, edit), old_range));
     }
     None
@@ -35,6 +36,7 @@ pub(crate) fn incremental_reparse(
 fn reparse_token(
     root: &SyntaxNode,
     edit: &Indel,
+    edition: Edition,
 ) -> Option<(GreenNode, Vec<SyntaxError>, TextRange)> {
     let prev_token = root.covering_element(edit.delete).as_token()?.clone();
     let prev_token_kind = prev_token.kind();
@@ -49,10 +51,11 @@ fn reparse_token(
             }
 
             let mut new_text = get_text_after_edit(prev_token.clone().into(), edit);
-            let (new_token_kind, new_err) = parser::LexedStr::single_token(&new_text)?;
+            let (new_token_kind, new_err) =
+                parser::LexedStr::single_token_with_edition(&new_text, edition)?;
 
             if new_token_kind != prev_token_kind
-                || (new_token_kind == IDENT && is_contextual_kw(&new_text))
+                || (new_token_kind == IDENT && is_contextual_kw(&new_text, edition))
             {
                 return None;
             }
@@ -62,7 +65,8 @@ fn reparse_token(
             // `b` no longer remains an identifier, but becomes a part of byte string literal
             if let Some(next_char) = root.text().char_at(prev_token.text_range().end()) {
                 new_text.push(next_char);
-                let token_with_next_char = parser::LexedStr::single_token(&new_text);
+                let token_with_next_char =
+                    parser::LexedStr::single_token_with_edition(&new_text, edition);
                 if let Some((_kind, _error)) = token_with_next_char {
                     return None;
                 }
@@ -84,11 +88,12 @@ fn reparse_token(
 fn reparse_block(
     root: &SyntaxNode,
     edit: &Indel,
+    edition: Edition,
 ) -> Option<(GreenNode, Vec<SyntaxError>, TextRange)> {
     let (node, reparser) = find_reparsable_node(root, edit.delete)?;
     let text = get_text_after_edit(node.clone().into(), edit);
 
-    let lexed = parser::LexedStr::new(text.as_str());
+    let lexed = parser::LexedStr::new_with_edition(text.as_str(), edition);
     let parser_input = lexed.to_input();
     if !is_balanced(&lexed) {
         return None;
@@ -112,8 +117,12 @@ fn get_text_after_edit(element: SyntaxElement, edit: &Indel) -> String {
     text
 }
 
-fn is_contextual_kw(text: &str) -> bool {
-    matches!(text, "auto" | "default" | "union")
+fn is_contextual_kw(text: &str, edition: Edition) -> bool {
+    match text {
+        "auto" | "default" | "union" => true,
+        "dyn" => edition < Edition::Edition2018,
+        _ => false,
+    }
 }
 
 fn find_reparsable_node(node: &SyntaxNode, range: TextRange) -> Option<(SyntaxNode, Reparser)> {
@@ -180,6 +189,15 @@ mod tests {
     use crate::{AstNode, Parse, SourceFile};
 
     fn do_check(before: &str, replace_with: &str, reparsed_len: u32) {
+        do_check_with_edition(before, replace_with, reparsed_len, Edition::CURRENT)
+    }
+
+    fn do_check_with_edition(
+        before: &str,
+        replace_with: &str,
+        reparsed_len: u32,
+        edition: Edition,
+    ) {
         let (range, before) = extract_range(before);
         let edit = Indel::replace(range, replace_with.to_owned());
         let after = {
@@ -188,11 +206,12 @@ mod tests {
             after
         };
 
-        let fully_reparsed = SourceFile::parse(&after);
+        let fully_reparsed = SourceFile::parse_with_edition(&after, edition);
         let incrementally_reparsed: Parse<SourceFile> = {
-            let before = SourceFile::parse(&before);
+            let before = SourceFile::parse_with_edition(&before, edition);
             let (green, new_errors, range) =
-                incremental_reparse(before.tree().syntax(), &edit, before.errors.to_vec()).unwrap();
+                incremental_reparse(before.tree().syntax(), &edit, before.errors.to_vec(), edition)
+                    .unwrap();
             assert_eq!(range.len(), reparsed_len.into(), "reparsed fragment has wrong length");
             Parse::new(green, new_errors)
         };
@@ -401,6 +420,12 @@ enum Foo {
         );
     }
 
+    #[test]
+    fn reparse_token_with_edition() {
+        do_check_with_edition("fn a$0sy$0() {}", "sync", 3, Edition::Edition2015);
+        do_check_with_edition("fn f() { let a$0sy$0 = 1; }", "sync", 16, Edition::Edition2018);
+    }
+
     #[test]
     fn reparse_str_token_with_error_unchanged() {
         do_check(r#""$0Unclosed$0 string literal"#, "Still unclosed", 24);
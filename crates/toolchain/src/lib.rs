@@ -17,6 +17,14 @@ pub fn rustfmt() -> PathBuf {
     get_path_for_executable("rustfmt")
 }
 
+/// The directory rustup keeps its toolchains and settings in, `$RUSTUP_HOME` or `~/.rustup`.
+pub fn rustup_home() -> Option<PathBuf> {
+    if let Some(path) = env::var_os("RUSTUP_HOME") {
+        return Some(path.into());
+    }
+    home::home_dir().map(|home| home.join(".rustup"))
+}
+
 /// Return a `PathBuf` to use for the given executable.
 ///
 /// E.g., `get_path_for_executable("cargo")` may return just `cargo` if that
@@ -0,0 +1,177 @@
+//! Support for `.gitignore` and `.ignore` files.
+//!
+//! This implements the commonly used subset of the format: comments, `!`
+//! negations, anchored and directory-only patterns, and `*`, `?` and `**`
+//! wildcards.
+use std::{collections::HashMap, fs, sync::Arc};
+
+use paths::{AbsPath, AbsPathBuf};
+use vfs::Glob;
+
+const IGNORE_FILES: [&str; 2] = [".gitignore", ".ignore"];
+
+/// The rules of the ignore files in a single directory.
+#[derive(Debug)]
+struct IgnoreFile {
+    dir: AbsPathBuf,
+    /// Patterns along with whether they are negated.
+    rules: Vec<(Glob, bool)>,
+}
+
+impl IgnoreFile {
+    fn load(dir: &AbsPath) -> Option<IgnoreFile> {
+        let mut rules = Vec::new();
+        for name in IGNORE_FILES {
+            if let Ok(text) = fs::read_to_string(dir.join(name)) {
+                rules.extend(parse(&text));
+            }
+        }
+        if rules.is_empty() {
+            return None;
+        }
+        Some(IgnoreFile { dir: dir.to_path_buf(), rules })
+    }
+
+    /// Returns whether the last matching rule ignores `path`, or `None` if no
+    /// rule matches.
+    ///
+    /// Only `path` and its parents below `root` are matched: the include root
+    /// was asked for explicitly, so the rules of the directories above it
+    /// can't hide it as a whole (like a `/target` rule would for `OUT_DIR`).
+    fn check(&self, root: &AbsPath, path: &AbsPath, is_dir: bool) -> Option<bool> {
+        let components = path
+            .strip_prefix(&self.dir)?
+            .as_ref()
+            .components()
+            .map(|it| it.as_os_str().to_string_lossy().into_owned())
+            .collect::<Vec<_>>();
+        let below_root = components_below(root, &self.dir);
+        let candidates = (below_root + 1..=components.len())
+            .map(|len| (components[..len].join("/"), len < components.len() || is_dir))
+            .collect::<Vec<_>>();
+        let (_, negated) = self.rules.iter().rev().find(|(glob, _)| {
+            candidates.iter().any(|(candidate, is_dir)| glob.matches(candidate, *is_dir))
+        })?;
+        Some(!negated)
+    }
+}
+
+/// The number of components of `root` below `dir`, `0` if `root` isn't below
+/// it.
+fn components_below(root: &AbsPath, dir: &AbsPath) -> usize {
+    root.strip_prefix(dir).map_or(0, |it| it.as_ref().components().count())
+}
+
+fn parse(text: &str) -> Vec<(Glob, bool)> {
+    let mut res = Vec::new();
+    for line in text.lines().map(str::trim_end) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let rule = match line.strip_prefix('!') {
+            Some(pattern) => (Glob::new(pattern), true),
+            None => (Glob::new(line.strip_prefix('\\').unwrap_or(line)), false),
+        };
+        res.push(rule);
+    }
+    res
+}
+
+/// The ignore files read so far, so that checking the paths of watcher events
+/// doesn't read them again.
+#[derive(Debug, Default)]
+pub(crate) struct IgnoreCache {
+    /// The ignore file of each directory, or `None` if it has no rules.
+    dirs: HashMap<AbsPathBuf, Option<Arc<IgnoreFile>>>,
+}
+
+impl IgnoreCache {
+    fn get(&mut self, dir: &AbsPath) -> Option<Arc<IgnoreFile>> {
+        self.dirs
+            .entry(dir.to_path_buf())
+            .or_insert_with(|| IgnoreFile::load(dir).map(Arc::new))
+            .clone()
+    }
+
+    /// Forgets the rules of the directory of `path` if it is an ignore file.
+    /// Returns whether it was one.
+    pub(crate) fn invalidate(&mut self, path: &AbsPath) -> bool {
+        let file_name = path.file_name().unwrap_or_default();
+        if !IGNORE_FILES.iter().any(|it| file_name == *it) {
+            return false;
+        }
+        if let Some(dir) = path.parent() {
+            self.dirs.remove(dir);
+        }
+        true
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.dirs.clear();
+    }
+}
+
+/// The ignore files that apply to the directory being crawled, outermost
+/// first.
+#[derive(Debug)]
+pub(crate) struct IgnoreStack {
+    root: AbsPathBuf,
+    files: Vec<Arc<IgnoreFile>>,
+}
+
+impl IgnoreStack {
+    /// Creates the stack for crawling `root`, including the ignore files of
+    /// its parent directories up to the enclosing git repository, if any.
+    pub(crate) fn new(cache: &mut IgnoreCache, root: &AbsPath) -> IgnoreStack {
+        let mut dirs = vec![root];
+        let mut dir = root;
+        while fs::metadata(dir.join(".git")).is_err() {
+            match dir.parent() {
+                Some(parent) => dir = parent,
+                None => {
+                    dirs.truncate(1);
+                    break;
+                }
+            }
+            dirs.push(dir);
+        }
+
+        let mut res = IgnoreStack { root: root.to_path_buf(), files: Vec::new() };
+        for dir in dirs.into_iter().rev() {
+            res.enter(cache, dir);
+        }
+        res
+    }
+
+    /// Creates the stack for checking `path`, below the crawled `root`.
+    pub(crate) fn for_path(cache: &mut IgnoreCache, root: &AbsPath, path: &AbsPath) -> IgnoreStack {
+        let mut res = IgnoreStack::new(cache, root);
+        let mut dirs = Vec::new();
+        let mut dir = path.parent();
+        while let Some(it) = dir {
+            if it == root || !it.starts_with(root) {
+                break;
+            }
+            dirs.push(it);
+            dir = it.parent();
+        }
+        for dir in dirs.into_iter().rev() {
+            res.enter(cache, dir);
+        }
+        res
+    }
+
+    /// Records the ignore files of `dir`, which is about to be crawled.
+    pub(crate) fn enter(&mut self, cache: &mut IgnoreCache, dir: &AbsPath) {
+        self.files.retain(|it| dir.starts_with(&it.dir));
+        self.files.extend(cache.get(dir));
+    }
+
+    pub(crate) fn is_ignored(&self, path: &AbsPath, is_dir: bool) -> bool {
+        // Rules of deeper ignore files take precedence.
+        self.files.iter().rev().find_map(|it| it.check(&self.root, path, is_dir)).unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,49 @@
+use std::{env, fs, process};
+
+use super::*;
+
+/// A workspace with a git repository ignoring its `target` directory.
+fn workspace(name: &str) -> AbsPathBuf {
+    let dir = env::temp_dir().join(format!("vfs-notify-{}-{}", name, process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    let dir = AbsPathBuf::assert(dir);
+    let out_dir = dir.join("target/debug/build/foo-1234/out");
+    fs::create_dir_all(dir.join(".git")).unwrap();
+    fs::create_dir_all(&out_dir).unwrap();
+    fs::write(dir.join(".gitignore"), "/target\n").unwrap();
+    fs::write(dir.join("target/debug/lib.rs"), "").unwrap();
+    fs::write(out_dir.join("generated.rs"), "").unwrap();
+    dir
+}
+
+#[test]
+fn ignore_files_of_parents_do_not_hide_include_root() {
+    let ws = workspace("include-root");
+    let out_dir = ws.join("target/debug/build/foo-1234/out");
+    let mut cache = IgnoreCache::default();
+
+    let stack = IgnoreStack::new(&mut cache, &ws);
+    assert!(stack.is_ignored(&ws.join("target"), true));
+
+    let stack = IgnoreStack::new(&mut cache, &out_dir);
+    assert!(!stack.is_ignored(&out_dir.join("generated.rs"), false));
+    let generated = out_dir.join("generated.rs");
+    assert!(!IgnoreStack::for_path(&mut cache, &out_dir, &generated).is_ignored(&generated, false));
+
+    fs::remove_dir_all(&ws).unwrap();
+}
+
+#[test]
+fn ignore_files_are_read_again_once_invalidated() {
+    let ws = workspace("invalidate");
+    let lib = ws.join("target/debug/lib.rs");
+    let mut cache = IgnoreCache::default();
+    assert!(IgnoreStack::for_path(&mut cache, &ws, &lib).is_ignored(&lib, false));
+
+    fs::write(ws.join(".gitignore"), "").unwrap();
+    assert!(IgnoreStack::for_path(&mut cache, &ws, &lib).is_ignored(&lib, false));
+    assert!(cache.invalidate(&ws.join(".gitignore")));
+    assert!(!IgnoreStack::for_path(&mut cache, &ws, &lib).is_ignored(&lib, false));
+
+    fs::remove_dir_all(&ws).unwrap();
+}
@@ -6,7 +6,9 @@
 //!
 //! Hopefully, one day a reliable file watching/walking crate appears on
 //! crates.io, and we can reduce this to trivial glue code.
-use std::fs;
+mod ignore;
+
+use std::{fs, mem};
 
 use crossbeam_channel::{never, select, unbounded, Receiver, Sender};
 use notify::{RecommendedWatcher, RecursiveMode, Watcher};
@@ -14,6 +16,8 @@ use paths::{AbsPath, AbsPathBuf};
 use vfs::loader;
 use walkdir::WalkDir;
 
+use crate::ignore::{IgnoreCache, IgnoreStack};
+
 #[derive(Debug)]
 pub struct NotifyHandle {
     // Relative order of fields below is significant.
@@ -53,6 +57,7 @@ type NotifyEvent = notify::Result<notify::Event>;
 struct NotifyActor {
     sender: loader::Sender,
     watched_entries: Vec<loader::Entry>,
+    ignore_cache: IgnoreCache,
     // Drop order is significant.
     watcher: Option<(RecommendedWatcher, Receiver<NotifyEvent>)>,
 }
@@ -65,7 +70,12 @@ enum Event {
 
 impl NotifyActor {
     fn new(sender: loader::Sender) -> NotifyActor {
-        NotifyActor { sender, watched_entries: Vec::new(), watcher: None }
+        NotifyActor {
+            sender,
+            watched_entries: Vec::new(),
+            ignore_cache: IgnoreCache::default(),
+            watcher: None,
+        }
     }
     fn next_event(&self, receiver: &Receiver<Message>) -> Option<Event> {
         let watcher_receiver = self.watcher.as_ref().map(|(_, receiver)| receiver);
@@ -95,6 +105,7 @@ impl NotifyActor {
                         self.send(loader::Message::Progress { n_total, n_done: 0, config_version });
 
                         self.watched_entries.clear();
+                        self.ignore_cache.clear();
 
                         for (i, entry) in config.load.into_iter().enumerate() {
                             let watch = config.watch.contains(&i);
@@ -123,6 +134,10 @@ impl NotifyActor {
                             .into_iter()
                             .map(|path| AbsPathBuf::try_from(path).unwrap())
                             .filter_map(|path| {
+                                // Changed rules apply to the events that follow.
+                                if self.ignore_cache.invalidate(&path) {
+                                    return None;
+                                }
                                 let meta = fs::metadata(&path).ok()?;
                                 if meta.file_type().is_dir()
                                     && self
@@ -137,11 +152,11 @@ impl NotifyActor {
                                 if !meta.file_type().is_file() {
                                     return None;
                                 }
-                                if !self
-                                    .watched_entries
-                                    .iter()
-                                    .any(|entry| entry.contains_file(&path))
-                                {
+                                let ignore_cache = &mut self.ignore_cache;
+                                if !self.watched_entries.iter().any(|entry| {
+                                    entry.contains_file(&path)
+                                        && !is_ignored(ignore_cache, entry, &path)
+                                }) {
                                     return None;
                                 }
 
@@ -173,16 +188,42 @@ impl NotifyActor {
                 .collect::<Vec<_>>(),
             loader::Entry::Directories(dirs) => {
                 let mut res = Vec::new();
+                // Taken out while crawling, as `self` is also needed to watch directories.
+                let mut ignore_cache = mem::take(&mut self.ignore_cache);
 
                 for root in &dirs.include {
+                    let ignore_cache = &mut ignore_cache;
+                    let mut ignores = if dirs.respect_ignore_files {
+                        Some(IgnoreStack::new(ignore_cache, root))
+                    } else {
+                        None
+                    };
                     let walkdir =
                         WalkDir::new(root).follow_links(true).into_iter().filter_entry(|entry| {
-                            if !entry.file_type().is_dir() {
+                            let path = AbsPath::assert(entry.path());
+                            if root == path {
                                 return true;
                             }
-                            let path = AbsPath::assert(entry.path());
-                            root == path
-                                || dirs.exclude.iter().chain(&dirs.include).all(|it| it != path)
+                            let is_dir = entry.file_type().is_dir();
+                            if is_dir
+                                && dirs.exclude.iter().chain(&dirs.include).any(|it| it == path)
+                            {
+                                return false;
+                            }
+                            if let Some(rel_path) = path.strip_prefix(root) {
+                                if dirs.is_glob_excluded(rel_path, is_dir) {
+                                    return false;
+                                }
+                            }
+                            if let Some(ignores) = &mut ignores {
+                                if ignores.is_ignored(path, is_dir) {
+                                    return false;
+                                }
+                                if is_dir {
+                                    ignores.enter(ignore_cache, path);
+                                }
+                            }
+                            true
                         });
 
                     let files = walkdir.filter_map(|it| it.ok()).filter_map(|entry| {
@@ -207,6 +248,7 @@ impl NotifyActor {
                         (file, contents)
                     }));
                 }
+                self.ignore_cache = ignore_cache;
                 res
             }
         }
@@ -222,6 +264,23 @@ impl NotifyActor {
     }
 }
 
+/// Whether `path`, a file in `entry`, is ignored by an ignore file.
+fn is_ignored(cache: &mut IgnoreCache, entry: &loader::Entry, path: &AbsPath) -> bool {
+    let dirs = match entry {
+        loader::Entry::Directories(dirs) if dirs.respect_ignore_files => dirs,
+        _ => return false,
+    };
+    let root = dirs
+        .include
+        .iter()
+        .filter(|root| path.starts_with(root))
+        .max_by_key(|root| root.as_os_str().len());
+    match root {
+        Some(root) => IgnoreStack::for_path(cache, root, path).is_ignored(path, false),
+        None => false,
+    }
+}
+
 fn read(path: &AbsPath) -> Option<Vec<u8>> {
     std::fs::read(path).ok()
 }
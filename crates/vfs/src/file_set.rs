@@ -108,10 +108,24 @@ impl FileSetConfig {
     ///
     /// Creates a new [`FileSet`] for every set of prefixes in `self`.
     pub fn partition(&self, vfs: &Vfs) -> Vec<FileSet> {
+        self.partition_with_aliases(vfs, &FxHashMap::default())
+    }
+
+    /// Partition `vfs` into `FileSet`s, putting every path that has an entry
+    /// in `aliases` into the set of the aliased path instead.
+    ///
+    /// This allows files outside of any root, like virtual documents, to join
+    /// the set of a chosen file.
+    pub fn partition_with_aliases(
+        &self,
+        vfs: &Vfs,
+        aliases: &FxHashMap<VfsPath, VfsPath>,
+    ) -> Vec<FileSet> {
         let mut scratch_space = Vec::new();
         let mut res = vec![FileSet::default(); self.len()];
         for (file_id, path) in vfs.iter() {
-            let root = self.classify(path, &mut scratch_space);
+            let classified = aliases.get(path).unwrap_or(path);
+            let root = self.classify(classified, &mut scratch_space);
             res[root].insert(file_id, path.clone());
         }
         res
@@ -40,3 +40,26 @@ fn name_prefix() {
     let partition = file_set.partition(&vfs).into_iter().map(|it| it.len()).collect::<Vec<_>>();
     assert_eq!(partition, vec![1, 1, 0]);
 }
+
+#[test]
+fn aliased_path() {
+    let mut file_set = FileSetConfig::builder();
+    file_set.add_file_set(vec![VfsPath::new_virtual_path("/foo".into())]);
+    let file_set = file_set.build();
+
+    let mut vfs = Vfs::default();
+    vfs.set_file_contents(VfsPath::new_virtual_path("/foo/src/lib.rs".into()), Some(Vec::new()));
+    vfs.set_file_contents(VfsPath::new_virtual_path("/git/foo/lib.rs".into()), Some(Vec::new()));
+
+    let mut aliases = FxHashMap::default();
+    aliases.insert(
+        VfsPath::new_virtual_path("/git/foo/lib.rs".into()),
+        VfsPath::new_virtual_path("/foo/src/lib.rs".into()),
+    );
+    let partition = file_set
+        .partition_with_aliases(&vfs, &aliases)
+        .into_iter()
+        .map(|it| it.len())
+        .collect::<Vec<_>>();
+    assert_eq!(partition, vec![2, 0]);
+}
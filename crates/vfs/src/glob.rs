@@ -0,0 +1,78 @@
+//! Minimal glob patterns, as used by `.gitignore` files and exclude settings.
+//!
+//! Supported syntax:
+//!   * `*` matches anything but `/`, `?` matches a single character,
+//!   * `**` as a whole path component matches any number of components,
+//!   * a leading `/`, or a `/` in the middle, anchors the pattern to the base
+//!     directory; otherwise it may match at any depth,
+//!   * a trailing `/` makes the pattern only match directories.
+
+/// A compiled glob pattern, matched against `/`-separated relative paths.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Glob {
+    components: Vec<String>,
+    dir_only: bool,
+}
+
+impl Glob {
+    pub fn new(pattern: &str) -> Glob {
+        let (pattern, dir_only) = match pattern.strip_suffix('/') {
+            Some(it) => (it, true),
+            None => (pattern, false),
+        };
+        let anchored = pattern.contains('/');
+        let pattern = pattern.strip_prefix('/').unwrap_or(pattern);
+
+        let mut components = Vec::new();
+        if !anchored {
+            components.push("**".to_string());
+        }
+        components.extend(pattern.split('/').filter(|it| !it.is_empty()).map(String::from));
+        Glob { components, dir_only }
+    }
+
+    /// Returns `true` if `path`, relative to the base directory of the
+    /// pattern, matches.
+    pub fn matches(&self, path: &str, is_dir: bool) -> bool {
+        if self.dir_only && !is_dir {
+            return false;
+        }
+        let path = path.split('/').filter(|it| !it.is_empty()).collect::<Vec<_>>();
+        match_components(&self.components, &path)
+    }
+
+    /// Returns `true` if `path` or any of its parent directories matches.
+    pub fn matches_path_or_parent(&self, path: &str, is_dir: bool) -> bool {
+        let is_parent_match =
+            path.match_indices('/').any(|(idx, _)| idx > 0 && self.matches(&path[..idx], true));
+        is_parent_match || self.matches(path, is_dir)
+    }
+}
+
+fn match_components(pattern: &[String], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((first, rest)) if first == "**" => {
+            (0..=path.len()).any(|skip| match_components(rest, &path[skip..]))
+        }
+        Some((first, rest)) => match path.split_first() {
+            Some((component, path)) => {
+                match_component(first.as_bytes(), component.as_bytes())
+                    && match_components(rest, path)
+            }
+            None => false,
+        },
+    }
+}
+
+fn match_component(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => text.is_empty(),
+        Some((b'*', rest)) => (0..=text.len()).any(|skip| match_component(rest, &text[skip..])),
+        Some((b'?', rest)) => !text.is_empty() && match_component(rest, &text[1..]),
+        Some((c, rest)) => text.first() == Some(c) && match_component(rest, &text[1..]),
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,39 @@
+use super::*;
+
+#[test]
+fn unanchored_patterns_match_at_any_depth() {
+    let glob = Glob::new("node_modules");
+    assert!(glob.matches("node_modules", true));
+    assert!(glob.matches("web/node_modules", true));
+    assert!(!glob.matches("web/node_modules_old", true));
+
+    let glob = Glob::new("*.generated.rs");
+    assert!(glob.matches("src/api.generated.rs", false));
+    assert!(!glob.matches("src/api.rs", false));
+}
+
+#[test]
+fn anchored_patterns() {
+    let glob = Glob::new("/target");
+    assert!(glob.matches("target", true));
+    assert!(!glob.matches("sub/target", true));
+
+    let glob = Glob::new("tests/fixtures/**");
+    assert!(glob.matches("tests/fixtures/big/data.rs", false));
+    assert!(!glob.matches("src/tests/fixtures/data.rs", false));
+}
+
+#[test]
+fn dir_only_patterns() {
+    let glob = Glob::new("out/");
+    assert!(glob.matches("out", true));
+    assert!(!glob.matches("out", false));
+    assert!(glob.matches_path_or_parent("crate/out/gen.rs", false));
+}
+
+#[test]
+fn question_mark() {
+    let glob = Glob::new("v?.rs");
+    assert!(glob.matches("v1.rs", false));
+    assert!(!glob.matches("v10.rs", false));
+}
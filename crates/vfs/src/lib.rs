@@ -39,6 +39,7 @@
 //! [`Entries`]: loader::Entry
 mod anchored_path;
 pub mod file_set;
+mod glob;
 pub mod loader;
 mod path_interner;
 mod vfs_path;
@@ -49,6 +50,7 @@ use crate::path_interner::PathInterner;
 
 pub use crate::{
     anchored_path::{AnchoredPath, AnchoredPathBuf},
+    glob::Glob,
     vfs_path::VfsPath,
 };
 pub use paths::{AbsPath, AbsPathBuf};
@@ -1,7 +1,9 @@
 //! Object safe interface for file watching and reading.
 use std::fmt;
 
-use paths::{AbsPath, AbsPathBuf};
+use paths::{AbsPath, AbsPathBuf, RelPath};
+
+use crate::Glob;
 
 /// A set of files on the file system.
 #[derive(Debug, Clone)]
@@ -18,15 +20,24 @@ pub enum Entry {
 ///   * it has included extension
 ///   * it is under an `include` path
 ///   * it is not under `exclude` path
+///   * neither it nor its parent directories match an `exclude_globs` pattern,
+///     relative to the `include` path
+///   * it is not ignored by a `.gitignore` or `.ignore` file, if
+///     `respect_ignore_files` is set
 ///
 /// If many include/exclude paths match, the longest one wins.
 ///
 /// If a path is in both `include` and `exclude`, the `exclude` one wins.
+///
+/// Ignore files are only honored by the [`Handle`] when crawling and watching
+/// the directories, as they have to be read from disk.
 #[derive(Debug, Clone, Default)]
 pub struct Directories {
     pub extensions: Vec<String>,
     pub include: Vec<AbsPathBuf>,
     pub exclude: Vec<AbsPathBuf>,
+    pub exclude_globs: Vec<Glob>,
+    pub respect_ignore_files: bool,
 }
 
 /// [`Handle`]'s configuration.
@@ -143,7 +154,7 @@ impl Directories {
         }
 
         // Then, check for path inclusion...
-        self.includes_path(path)
+        self.includes_path(path, false)
     }
 
     /// Returns `true` if `path` is included in `self`.
@@ -151,7 +162,22 @@ impl Directories {
     /// Since `path` is supposed to be a directory, this will not take extension
     /// into account.
     pub fn contains_dir(&self, path: &AbsPath) -> bool {
-        self.includes_path(path)
+        self.includes_path(path, true)
+    }
+
+    /// Returns `true` if `path`, which is relative to one of the `include`
+    /// paths, matches one of the `exclude_globs`.
+    pub fn is_glob_excluded(&self, path: &RelPath, is_dir: bool) -> bool {
+        if self.exclude_globs.is_empty() {
+            return false;
+        }
+        let path = path
+            .as_ref()
+            .components()
+            .map(|it| it.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        self.exclude_globs.iter().any(|glob| glob.matches_path_or_parent(&path, is_dir))
     }
 
     /// Returns `true` if `path` is included in `self`.
@@ -160,7 +186,8 @@ impl Directories {
     ///   - An element in `self.include` is a prefix of `path`.
     ///   - This path is longer than any element in `self.exclude` that is a prefix
     ///     of `path`. In case of equality, exclusion wins.
-    fn includes_path(&self, path: &AbsPath) -> bool {
+    ///   - It doesn't match any of `self.exclude_globs`.
+    fn includes_path(&self, path: &AbsPath, is_dir: bool) -> bool {
         let mut include: Option<&AbsPathBuf> = None;
         for incl in &self.include {
             if path.starts_with(incl) {
@@ -176,7 +203,14 @@ impl Directories {
             None => return false,
         };
 
-        !self.exclude.iter().any(|excl| path.starts_with(excl) && excl.starts_with(include))
+        if self.exclude.iter().any(|excl| path.starts_with(excl) && excl.starts_with(include)) {
+            return false;
+        }
+
+        match path.strip_prefix(include) {
+            Some(rel_path) => !self.is_glob_excluded(rel_path, is_dir),
+            None => true,
+        }
     }
 }
 
@@ -190,7 +224,13 @@ impl Directories {
 /// ```
 fn dirs(base: AbsPathBuf, exclude: &[&str]) -> Directories {
     let exclude = exclude.iter().map(|it| base.join(it)).collect::<Vec<_>>();
-    Directories { extensions: vec!["rs".to_string()], include: vec![base], exclude }
+    Directories {
+        extensions: vec!["rs".to_string()],
+        include: vec![base],
+        exclude,
+        exclude_globs: Vec::new(),
+        respect_ignore_files: false,
+    }
 }
 
 impl fmt::Debug for Message {
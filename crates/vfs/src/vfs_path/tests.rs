@@ -28,3 +28,11 @@ fn virtual_path_extensions() {
         Some(("file", Some("rs")))
     );
 }
+
+#[test]
+fn virtual_path_join() {
+    let base = VirtualPath("/foo/bar".to_string());
+    assert_eq!(base.join("baz.rs").unwrap().0, "/foo/bar/baz.rs");
+    assert_eq!(base.join("../baz.rs").unwrap().0, "/foo/baz.rs");
+    assert_eq!(base.join("/out/generated.rs").unwrap().0, "/out/generated.rs");
+}
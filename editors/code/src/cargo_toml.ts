@@ -0,0 +1,30 @@
+import * as vscode from 'vscode';
+import * as lc from 'vscode-languageclient';
+import * as ra from './lsp_ext';
+
+import { Ctx } from './ctx';
+
+// `Cargo.toml` files are not synchronized with the server, so the usual providers of the
+// language client don't see them. Forward the requests for dependency entries by hand, the
+// server reads the manifest from disk.
+export function activateCargoTomlProviders(ctx: Ctx) {
+    const selector = { scheme: 'file', pattern: '**/Cargo.toml' };
+    const client = ctx.client;
+
+    ctx.pushCleanup(vscode.languages.registerDefinitionProvider(selector, {
+        async provideDefinition(document, position, token) {
+            const params = client.code2ProtocolConverter.asTextDocumentPositionParams(document, position);
+            const result = await client.sendRequest(lc.DefinitionRequest.type, params, token);
+            return client.protocol2CodeConverter.asDefinitionResult(result);
+        }
+    }));
+    ctx.pushCleanup(vscode.languages.registerHoverProvider(selector, {
+        async provideHover(document, position, token) {
+            const result = await client.sendRequest(ra.hover, {
+                textDocument: client.code2ProtocolConverter.asTextDocumentIdentifier(document),
+                position: client.code2ProtocolConverter.asPosition(position),
+            }, token);
+            return client.protocol2CodeConverter.asHover(result);
+        }
+    }));
+}
@@ -8,7 +8,7 @@ import { applySnippetWorkspaceEdit, applySnippetTextEdits } from './snippets';
 import { spawnSync } from 'child_process';
 import { RunnableQuickPick, selectRunnable, createTask, createArgs } from './run';
 import { AstInspector } from './ast_inspector';
-import { isRustDocument, isCargoTomlDocument, sleep, isRustEditor } from './util';
+import { isRustDocument, isCargoTomlDocument, sleep, isRustEditor, RustEditor } from './util';
 import { startDebugSession, makeDebugConfig } from './debug';
 import { LanguageClient } from 'vscode-languageclient/node';
 
@@ -287,6 +287,59 @@ export function ssr(ctx: Ctx): Cmd {
     };
 }
 
+export function batchCodeAction(ctx: Ctx): Cmd {
+    return async () => {
+        const editor = ctx.activeRustEditor;
+        const client = ctx.client;
+        if (!editor || !client) return;
+
+        const assistId = await vscode.window.showInputBox({
+            prompt: "Enter the id of the assist to apply, for example 'add_missing_impl_members'",
+        });
+        if (!assistId) return;
+        const scope = await vscode.window.showQuickPick(["file", "crate", "workspace"], {
+            placeHolder: "Apply the assist in",
+        }) as ra.BatchCodeActionParams["scope"] | undefined;
+        if (!scope) return;
+
+        await applyBatchCodeAction(ctx, editor, assistId, scope);
+    };
+}
+
+export function replaceGlobImports(ctx: Ctx): Cmd {
+    return async () => {
+        const editor = ctx.activeRustEditor;
+        if (!editor) return;
+
+        const scope = await vscode.window.showQuickPick(["file", "crate", "workspace"], {
+            placeHolder: "Replace the glob imports in",
+        }) as ra.BatchCodeActionParams["scope"] | undefined;
+        if (!scope) return;
+
+        await applyBatchCodeAction(ctx, editor, "replace_glob_import", scope);
+    };
+}
+
+async function applyBatchCodeAction(
+    ctx: Ctx,
+    editor: RustEditor,
+    assistId: string,
+    scope: ra.BatchCodeActionParams["scope"],
+) {
+    const client = ctx.client;
+    const textDocument = client.code2ProtocolConverter.asTextDocumentIdentifier(editor.document);
+    await vscode.window.withProgress({
+        location: vscode.ProgressLocation.Notification,
+        title: `Applying ${assistId}...`,
+        cancellable: false,
+    }, async (_progress, _token) => {
+        const res = await client.sendRequest(ra.batchCodeAction, { textDocument, assistId, scope });
+        await vscode.workspace.applyEdit(client.protocol2CodeConverter.asWorkspaceEdit(res.edit));
+        const skipped = res.skipped ? `, skipped ${res.skipped} conflicting` : "";
+        void vscode.window.showInformationMessage(`Applied ${assistId} ${res.applied} times${skipped}`);
+    });
+}
+
 export function serverVersion(ctx: Ctx): Cmd {
     return async () => {
         const { stdout } = spawnSync(ctx.serverPath, ["--version"], { encoding: "utf8" });
@@ -432,6 +485,75 @@ export function viewHir(ctx: Ctx): Cmd {
     };
 }
 
+// Opens the virtual file that will show the desugared body of the function containing the cursor
+// position. Going to the definition of anything in that file jumps to the source expression it was
+// printed from.
+export function viewDesugaredBody(ctx: Ctx): Cmd {
+    const tdcp = new class implements vscode.TextDocumentContentProvider, vscode.DefinitionProvider {
+        readonly uri = vscode.Uri.parse('rust-analyzer://viewDesugaredBody/body.rs');
+        readonly eventEmitter = new vscode.EventEmitter<vscode.Uri>();
+        mappings: ra.DesugaredMapping[] = [];
+        constructor() {
+            vscode.workspace.onDidChangeTextDocument(this.onDidChangeTextDocument, this, ctx.subscriptions);
+            vscode.window.onDidChangeActiveTextEditor(this.onDidChangeActiveTextEditor, this, ctx.subscriptions);
+        }
+
+        private onDidChangeTextDocument(event: vscode.TextDocumentChangeEvent) {
+            if (isRustDocument(event.document)) {
+                // We need to order this after language server updates, but there's no API for that.
+                // Hence, good old sleep().
+                void sleep(10).then(() => this.eventEmitter.fire(this.uri));
+            }
+        }
+        private onDidChangeActiveTextEditor(editor: vscode.TextEditor | undefined) {
+            if (editor && isRustEditor(editor)) {
+                this.eventEmitter.fire(this.uri);
+            }
+        }
+
+        async provideTextDocumentContent(_uri: vscode.Uri, ct: vscode.CancellationToken): Promise<string> {
+            const rustEditor = ctx.activeRustEditor;
+            const client = ctx.client;
+            if (!rustEditor || !client) return '';
+
+            const params = {
+                textDocument: client.code2ProtocolConverter.asTextDocumentIdentifier(rustEditor.document),
+                position: client.code2ProtocolConverter.asPosition(
+                    rustEditor.selection.active,
+                ),
+            };
+            const body = await client.sendRequest(ra.viewDesugaredBody, params, ct);
+            this.mappings = body.mappings;
+            return body.text;
+        }
+
+        provideDefinition(document: vscode.TextDocument, position: vscode.Position): vscode.Location | undefined {
+            const client = ctx.client;
+            if (!client || document.uri.toString() !== this.uri.toString()) return;
+
+            // Mappings of nested expressions come first, so this picks the innermost one.
+            const mapping = this.mappings.find(it => client.protocol2CodeConverter.asRange(it.range).contains(position));
+            return mapping && client.protocol2CodeConverter.asLocation(mapping.location);
+        }
+
+        get onDidChange(): vscode.Event<vscode.Uri> {
+            return this.eventEmitter.event;
+        }
+    };
+
+    ctx.pushCleanup(vscode.workspace.registerTextDocumentContentProvider('rust-analyzer', tdcp));
+    ctx.pushCleanup(vscode.languages.registerDefinitionProvider({ scheme: 'rust-analyzer', pattern: '**/body.rs' }, tdcp));
+
+    return async () => {
+        const document = await vscode.workspace.openTextDocument(tdcp.uri);
+        tdcp.eventEmitter.fire(tdcp.uri);
+        void await vscode.window.showTextDocument(document, {
+            viewColumn: vscode.ViewColumn.Two,
+            preserveFocus: true
+        });
+    };
+}
+
 export function viewItemTree(ctx: Ctx): Cmd {
     const tdcp = new class implements vscode.TextDocumentContentProvider {
         readonly uri = vscode.Uri.parse('rust-analyzer://viewItemTree/itemtree.rs');
@@ -482,6 +604,56 @@ export function viewItemTree(ctx: Ctx): Cmd {
     };
 }
 
+export function viewCHeader(ctx: Ctx): Cmd {
+    const tdcp = new class implements vscode.TextDocumentContentProvider {
+        readonly uri = vscode.Uri.parse('rust-analyzer://viewCHeader/header.h');
+        readonly eventEmitter = new vscode.EventEmitter<vscode.Uri>();
+        constructor() {
+            vscode.workspace.onDidChangeTextDocument(this.onDidChangeTextDocument, this, ctx.subscriptions);
+            vscode.window.onDidChangeActiveTextEditor(this.onDidChangeActiveTextEditor, this, ctx.subscriptions);
+        }
+
+        private onDidChangeTextDocument(event: vscode.TextDocumentChangeEvent) {
+            if (isRustDocument(event.document)) {
+                // We need to order this after language server updates, but there's no API for that.
+                // Hence, good old sleep().
+                void sleep(10).then(() => this.eventEmitter.fire(this.uri));
+            }
+        }
+        private onDidChangeActiveTextEditor(editor: vscode.TextEditor | undefined) {
+            if (editor && isRustEditor(editor)) {
+                this.eventEmitter.fire(this.uri);
+            }
+        }
+
+        provideTextDocumentContent(_uri: vscode.Uri, ct: vscode.CancellationToken): vscode.ProviderResult<string> {
+            const rustEditor = ctx.activeRustEditor;
+            const client = ctx.client;
+            if (!rustEditor || !client) return '';
+
+            const params = {
+                textDocument: client.code2ProtocolConverter.asTextDocumentIdentifier(rustEditor.document),
+            };
+            return client.sendRequest(ra.viewCHeader, params, ct);
+        }
+
+        get onDidChange(): vscode.Event<vscode.Uri> {
+            return this.eventEmitter.event;
+        }
+    };
+
+    ctx.pushCleanup(vscode.workspace.registerTextDocumentContentProvider('rust-analyzer', tdcp));
+
+    return async () => {
+        const document = await vscode.workspace.openTextDocument(tdcp.uri);
+        tdcp.eventEmitter.fire(tdcp.uri);
+        void await vscode.window.showTextDocument(document, {
+            viewColumn: vscode.ViewColumn.Two,
+            preserveFocus: true
+        });
+    };
+}
+
 function crateGraph(ctx: Ctx, full: boolean): Cmd {
     return async () => {
         const nodeModulesPath = vscode.Uri.file(path.join(ctx.extensionPath, "node_modules"));
@@ -540,6 +712,62 @@ function crateGraph(ctx: Ctx, full: boolean): Cmd {
     };
 }
 
+// Opens the virtual file that will show the trait solver overflows in the function containing the cursor position
+//
+// The contents of the file come from the `TextDocumentContentProvider`
+export function viewTraitSolverOverflows(ctx: Ctx): Cmd {
+    const tdcp = new class implements vscode.TextDocumentContentProvider {
+        readonly uri = vscode.Uri.parse('rust-analyzer://viewTraitSolverOverflows/overflows.txt');
+        readonly eventEmitter = new vscode.EventEmitter<vscode.Uri>();
+        constructor() {
+            vscode.workspace.onDidChangeTextDocument(this.onDidChangeTextDocument, this, ctx.subscriptions);
+            vscode.window.onDidChangeActiveTextEditor(this.onDidChangeActiveTextEditor, this, ctx.subscriptions);
+        }
+
+        private onDidChangeTextDocument(event: vscode.TextDocumentChangeEvent) {
+            if (isRustDocument(event.document)) {
+                // We need to order this after language server updates, but there's no API for that.
+                // Hence, good old sleep().
+                void sleep(10).then(() => this.eventEmitter.fire(this.uri));
+            }
+        }
+        private onDidChangeActiveTextEditor(editor: vscode.TextEditor | undefined) {
+            if (editor && isRustEditor(editor)) {
+                this.eventEmitter.fire(this.uri);
+            }
+        }
+
+        provideTextDocumentContent(_uri: vscode.Uri, ct: vscode.CancellationToken): vscode.ProviderResult<string> {
+            const rustEditor = ctx.activeRustEditor;
+            const client = ctx.client;
+            if (!rustEditor || !client) return '';
+
+            const params = {
+                textDocument: client.code2ProtocolConverter.asTextDocumentIdentifier(rustEditor.document),
+                position: client.code2ProtocolConverter.asPosition(
+                    rustEditor.selection.active,
+                ),
+            };
+            return client.sendRequest(ra.viewTraitSolverOverflows, params, ct);
+        }
+
+        get onDidChange(): vscode.Event<vscode.Uri> {
+            return this.eventEmitter.event;
+        }
+    };
+
+    ctx.pushCleanup(vscode.workspace.registerTextDocumentContentProvider('rust-analyzer', tdcp));
+
+    return async () => {
+        const document = await vscode.workspace.openTextDocument(tdcp.uri);
+        tdcp.eventEmitter.fire(tdcp.uri);
+        void await vscode.window.showTextDocument(document, {
+            viewColumn: vscode.ViewColumn.Two,
+            preserveFocus: true
+        });
+    };
+}
+
 export function viewCrateGraph(ctx: Ctx): Cmd {
     return crateGraph(ctx, false);
 }
@@ -548,59 +776,292 @@ export function viewFullCrateGraph(ctx: Ctx): Cmd {
     return crateGraph(ctx, true);
 }
 
-// Opens the virtual file that will show the syntax tree
-//
-// The contents of the file come from the `TextDocumentContentProvider`
+// Reports the unused crate-private items of the workspace as diagnostics.
+export function findDeadCode(ctx: Ctx): Cmd {
+    const diagnostics = vscode.languages.createDiagnosticCollection('rust-analyzer-dead-code');
+    ctx.pushCleanup(diagnostics);
+
+    return async () => {
+        const client = ctx.client;
+        if (!client) return;
+
+        const items = await vscode.window.withProgress({
+            location: vscode.ProgressLocation.Window,
+            title: "Searching for dead code",
+        }, () => client.sendRequest(ra.deadCode));
+
+        const byFile = new Map<string, vscode.Diagnostic[]>();
+        for (const item of items) {
+            const range = client.protocol2CodeConverter.asRange(item.location.range);
+            const diagnostic = new vscode.Diagnostic(range, `${item.kind} \`${item.name}\` is never used`, vscode.DiagnosticSeverity.Hint);
+            diagnostic.source = 'rust-analyzer';
+            diagnostic.code = 'dead-code';
+            diagnostic.tags = [vscode.DiagnosticTag.Unnecessary];
+            const forFile = byFile.get(item.location.uri) ?? [];
+            forFile.push(diagnostic);
+            byFile.set(item.location.uri, forFile);
+        }
+        diagnostics.clear();
+        for (const [uri, forFile] of byFile) {
+            diagnostics.set(vscode.Uri.parse(uri), forFile);
+        }
+        void vscode.window.showInformationMessage(`Found ${items.length} unused items.`);
+    };
+}
+
+// Lists the unsafe code of the workspace, and jumps to the picked item.
+export function unsafeReport(ctx: Ctx): Cmd {
+    return async () => {
+        const client = ctx.client;
+        if (!client) return;
+
+        const items = await vscode.window.withProgress({
+            location: vscode.ProgressLocation.Window,
+            title: "Collecting unsafe code",
+        }, () => client.sendRequest(ra.unsafeReport));
+        if (items.length === 0) {
+            void vscode.window.showInformationMessage("No unsafe code found.");
+            return;
+        }
+
+        const picks = items.map((item) => {
+            const location = client.protocol2CodeConverter.asLocation(item.location);
+            const operations = new Set(item.operations.map((op) => op.description));
+            return {
+                label: `${item.kind} ${item.name}`,
+                description: `${vscode.workspace.asRelativePath(location.uri)}:${location.range.start.line + 1}`,
+                detail: [...operations].join(", ") || undefined,
+                location,
+            };
+        });
+        const picked = await vscode.window.showQuickPick(picks, { matchOnDescription: true, matchOnDetail: true });
+        if (!picked) return;
+        await vscode.window.showTextDocument(picked.location.uri, { selection: picked.location.range });
+    };
+}
+
+// Asks for a Cargo feature, then lists the code and manifests referring to it.
+export function featureUsages(ctx: Ctx): Cmd {
+    return async () => {
+        const client = ctx.client;
+        if (!client) return;
+
+        const feature = await vscode.window.showInputBox({ prompt: "Cargo feature to find the usages of" });
+        if (!feature) return;
+
+        const usages = await vscode.window.withProgress({
+            location: vscode.ProgressLocation.Window,
+            title: `Searching for usages of feature "${feature}"`,
+        }, () => client.sendRequest(ra.featureUsages, { feature }));
+        if (usages.length === 0) {
+            void vscode.window.showInformationMessage(`Feature "${feature}" is not used.`);
+            return;
+        }
+
+        const items = usages.map((usage) => {
+            const location = client.protocol2CodeConverter.asLocation(usage.location);
+            return {
+                label: vscode.workspace.asRelativePath(location.uri),
+                description: `line ${location.range.start.line + 1}`,
+                detail: usage.kind,
+                location,
+            };
+        });
+        const picked = await vscode.window.showQuickPick(items, { matchOnDescription: true, matchOnDetail: true });
+        if (!picked) return;
+        await vscode.window.showTextDocument(picked.location.uri, { selection: picked.location.range });
+    };
+}
+
+// Asks for a trait, then explains why the selected type does or doesn't implement it.
+export function explainImpl(ctx: Ctx): Cmd {
+    return async () => {
+        const editor = ctx.activeRustEditor;
+        const client = ctx.client;
+        if (!editor || !client) return;
+
+        const trait = await vscode.window.showInputBox({
+            prompt: "Trait to check the selected type against",
+            value: "Send",
+        });
+        if (!trait) return;
+
+        const explanation = await client.sendRequest(ra.explainImpl, {
+            textDocument: client.code2ProtocolConverter.asTextDocumentIdentifier(editor.document),
+            range: client.code2ProtocolConverter.asRange(editor.selection),
+            trait,
+        });
+        const document = await vscode.workspace.openTextDocument({ content: explanation.rendered ?? explanation.goal });
+        await vscode.window.showTextDocument(document, { viewColumn: vscode.ViewColumn.Two, preserveFocus: true });
+    };
+}
+
+export function traitImplMatrix(ctx: Ctx): Cmd {
+    return async () => {
+        const editor = ctx.activeRustEditor;
+        const client = ctx.client;
+        if (!editor || !client) return;
+
+        const position = client.code2ProtocolConverter.asTextDocumentPositionParams(editor.document, editor.selection.active);
+        const matrix = await client.sendRequest(ra.traitImplMatrix, position);
+        if (!matrix) {
+            void vscode.window.showInformationMessage("No type under the cursor.");
+            return;
+        }
+
+        const items: vscode.QuickPickItem[] = matrix.rows.map((row) => ({
+            label: `${row.implemented ? "✓" : "✗"} ${row.trait}`,
+            description: row.derivable ? "derivable" : undefined,
+        }));
+        const derive = "Derive missing traits";
+        if (matrix.deriveMissing) {
+            items.push({ label: derive });
+        }
+        const picked = await vscode.window.showQuickPick(items, { placeHolder: `Traits implemented by ${matrix.type}` });
+        if (picked?.label === derive && matrix.deriveMissing) {
+            await vscode.workspace.applyEdit(client.protocol2CodeConverter.asWorkspaceEdit(matrix.deriveMissing));
+        }
+    };
+}
+
+// Picks a crate among the ones which can implement the trait under the cursor,
+// then shows its impls. The impls in dependencies are only searched once picked.
+export function traitImplementations(ctx: Ctx): Cmd {
+    return async () => {
+        const editor = ctx.activeRustEditor;
+        const client = ctx.client;
+        if (!editor || !client) return;
+
+        const position = client.code2ProtocolConverter.asTextDocumentPositionParams(editor.document, editor.selection.active);
+        const groups = await client.sendRequest(ra.traitImplementations, position);
+        if (groups.length === 0) {
+            void vscode.window.showInformationMessage("No trait under the cursor.");
+            return;
+        }
+
+        const items = groups.map((group) => ({
+            label: group.crateName ?? `crate #${group.crateId}`,
+            description: group.implementations
+                ? `${group.implementations.length} impls`
+                : "dependency, not searched yet",
+            group,
+        }));
+        const picked = await vscode.window.showQuickPick(items, { placeHolder: "Show the impls in crate" });
+        if (!picked) return;
+
+        let locations = picked.group.implementations;
+        if (!locations) {
+            const [group] = await client.sendRequest(ra.traitImplementations, { ...position, crateId: picked.group.crateId });
+            locations = group?.implementations ?? [];
+        }
+        await showReferencesImpl(client, editor.document.uri.toString(), position.position, locations);
+    };
+}
+
+// Opens the recursive expansion of the macro under the cursor as a virtual document. The document
+// follows the edits made to its source, and going to the definition of or hovering anything in it
+// forwards the request to the source it was expanded from.
 export function expandMacro(ctx: Ctx): Cmd {
-    function codeFormat(expanded: ra.ExpandedMacro): string {
-        let result = `// Recursive expansion of ${expanded.name}! macro\n`;
-        result += '// ' + '='.repeat(result.length - 3);
-        result += '\n\n';
-        result += expanded.expansion;
+    const scheme = 'rust-analyzer-expansion';
+    // The server percent-encodes the source uri into the query of the expansion uri.
+    const sourceOf = (uri: vscode.Uri) => {
+        const source = new URLSearchParams(uri.query).get('source');
+        return source && vscode.Uri.parse(source).toString();
+    };
 
-        return result;
-    }
+    const provider = new class implements vscode.TextDocumentContentProvider, vscode.DefinitionProvider, vscode.HoverProvider {
+        readonly eventEmitter = new vscode.EventEmitter<vscode.Uri>();
+        readonly mappings = new Map<string, ra.ExpansionMapping[]>();
+        constructor() {
+            vscode.workspace.onDidChangeTextDocument(this.onDidChangeTextDocument, this, ctx.subscriptions);
+            vscode.workspace.onDidCloseTextDocument(this.onDidCloseTextDocument, this, ctx.subscriptions);
+        }
 
-    const tdcp = new class implements vscode.TextDocumentContentProvider {
-        uri = vscode.Uri.parse('rust-analyzer://expandMacro/[EXPANSION].rs');
-        eventEmitter = new vscode.EventEmitter<vscode.Uri>();
-        async provideTextDocumentContent(_uri: vscode.Uri): Promise<string> {
-            const editor = vscode.window.activeTextEditor;
+        private onDidChangeTextDocument(event: vscode.TextDocumentChangeEvent) {
+            if (!isRustDocument(event.document)) return;
+
+            const source = event.document.uri.toString();
+            const expansions = vscode.workspace.textDocuments
+                .filter(it => it.uri.scheme === scheme && sourceOf(it.uri) === source);
+            // We need to order this after language server updates, but there's no API for that.
+            // Hence, good old sleep().
+            void sleep(10).then(() => expansions.forEach(it => this.eventEmitter.fire(it.uri)));
+        }
+        private onDidCloseTextDocument(document: vscode.TextDocument) {
+            this.mappings.delete(document.uri.toString());
+        }
+
+        async provideTextDocumentContent(uri: vscode.Uri, ct: vscode.CancellationToken): Promise<string> {
             const client = ctx.client;
-            if (!editor || !client) return '';
+            if (!client) return '';
 
-            const position = editor.selection.active;
+            // The server parses the query itself, so keep it the way it was sent.
+            const expansion = await client.sendRequest(ra.viewExpansion, { uri: uri.toString(true) }, ct);
+            this.mappings.set(uri.toString(), expansion?.mappings ?? []);
+            return expansion?.text ?? '// The macro does not expand anymore';
+        }
 
-            const expanded = await client.sendRequest(ra.expandMacro, {
-                textDocument: ctx.client.code2ProtocolConverter.asTextDocumentIdentifier(editor.document),
-                position,
-            });
+        // Maps a position in an expansion to the same position in the source token it came from.
+        private sourceLocation(document: vscode.TextDocument, position: vscode.Position): vscode.Location | undefined {
+            const client = ctx.client;
+            if (!client) return;
 
-            if (expanded == null) return 'Not available';
+            const mapping = this.mappings.get(document.uri.toString())
+                ?.find(it => client.protocol2CodeConverter.asRange(it.range).contains(position));
+            if (!mapping) return;
 
-            return codeFormat(expanded);
+            const location = client.protocol2CodeConverter.asLocation(mapping.location);
+            const column = position.character - mapping.range.start.character;
+            return new vscode.Location(location.uri, location.range.start.translate(0, column));
+        }
+
+        async provideDefinition(document: vscode.TextDocument, position: vscode.Position): Promise<vscode.Definition | undefined> {
+            const source = this.sourceLocation(document, position);
+            if (!source) return;
+
+            const definitions = await vscode.commands.executeCommand<(vscode.Location | vscode.LocationLink)[]>(
+                'vscode.executeDefinitionProvider', source.uri, source.range.start);
+            if (!definitions?.length) return source;
+            return definitions.map(it => it instanceof vscode.Location
+                ? it
+                : new vscode.Location(it.targetUri, it.targetSelectionRange ?? it.targetRange));
+        }
+
+        async provideHover(document: vscode.TextDocument, position: vscode.Position): Promise<vscode.Hover | undefined> {
+            const source = this.sourceLocation(document, position);
+            if (!source) return;
+
+            const hovers = await vscode.commands.executeCommand<vscode.Hover[]>(
+                'vscode.executeHoverProvider', source.uri, source.range.start);
+            // The range of the hover is one in the source, so leave it out.
+            return hovers?.length ? new vscode.Hover(hovers.flatMap(it => it.contents)) : undefined;
         }
 
         get onDidChange(): vscode.Event<vscode.Uri> {
             return this.eventEmitter.event;
         }
-    }();
+    };
 
-    ctx.pushCleanup(
-        vscode.workspace.registerTextDocumentContentProvider(
-            'rust-analyzer',
-            tdcp,
-        ),
-    );
+    ctx.pushCleanup(vscode.workspace.registerTextDocumentContentProvider(scheme, provider));
+    ctx.pushCleanup(vscode.languages.registerDefinitionProvider({ scheme }, provider));
+    ctx.pushCleanup(vscode.languages.registerHoverProvider({ scheme }, provider));
 
     return async () => {
-        const document = await vscode.workspace.openTextDocument(tdcp.uri);
-        tdcp.eventEmitter.fire(tdcp.uri);
-        return vscode.window.showTextDocument(
-            document,
-            vscode.ViewColumn.Two,
-            true,
-        );
+        const editor = ctx.activeRustEditor;
+        const client = ctx.client;
+        if (!editor || !client) return;
+
+        const params = client.code2ProtocolConverter.asTextDocumentPositionParams(editor.document, editor.selection.active);
+        const uri = await client.sendRequest(ra.expansionUri, params);
+        if (!uri) {
+            void vscode.window.showInformationMessage("No macro under the cursor.");
+            return;
+        }
+        const document = await vscode.workspace.openTextDocument(vscode.Uri.parse(uri));
+        void await vscode.window.showTextDocument(document, {
+            viewColumn: vscode.ViewColumn.Two,
+            preserveFocus: true
+        });
     };
 }
 
@@ -0,0 +1,31 @@
+import * as vscode from 'vscode';
+import * as ra from './lsp_ext';
+
+import { Ctx } from './ctx';
+import { sendRequestWithRetry } from './util';
+
+export function activateInlineValues(ctx: Ctx) {
+    const provider = vscode.languages.registerInlineValuesProvider({ scheme: 'file', language: 'rust' }, new class implements vscode.InlineValuesProvider {
+        async provideInlineValues(document: vscode.TextDocument, viewPort: vscode.Range, context: vscode.InlineValueContext, token: vscode.CancellationToken): Promise<vscode.InlineValue[]> {
+            const client = ctx.client;
+            const request = {
+                textDocument: { uri: document.uri.toString() },
+                range: client.code2ProtocolConverter.asRange(viewPort),
+                context: {
+                    frameId: context.frameId,
+                    stoppedLocation: client.code2ProtocolConverter.asRange(context.stoppedLocation),
+                },
+            };
+            const values = await sendRequestWithRetry(client, ra.inlineValues, request, token).catch(_ => null);
+            if (values == null) {
+                return [];
+            }
+            return values.map(value => new vscode.InlineValueVariableLookup(
+                client.protocol2CodeConverter.asRange(value.range),
+                value.variableName,
+                value.caseSensitiveLookup,
+            ));
+        }
+    });
+    ctx.pushCleanup(provider);
+}
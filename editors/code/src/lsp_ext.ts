@@ -19,6 +19,11 @@ export interface ServerStatusParams {
 }
 export const serverStatus = new lc.NotificationType<ServerStatusParams>("experimental/serverStatus");
 
+export interface ConfigurationAppliedParams {
+    reinitialized: ("projectModel" | "crateGraph" | "procMacroServer" | "flycheck" | "analysis")[];
+}
+export const configurationApplied = new lc.NotificationType<ConfigurationAppliedParams>("experimental/configurationApplied");
+
 export const reloadWorkspace = new lc.RequestType0<null, void>("rust-analyzer/reloadWorkspace");
 
 export const hover = new lc.RequestType<HoverParams, lc.Hover | null, void>("textDocument/hover");
@@ -36,18 +41,115 @@ export const syntaxTree = new lc.RequestType<SyntaxTreeParams, string, void>("ru
 
 export const viewHir = new lc.RequestType<lc.TextDocumentPositionParams, string, void>("rust-analyzer/viewHir");
 
+export interface TypeOfRangeParams {
+    textDocument: lc.TextDocumentIdentifier;
+    range: lc.Range;
+}
+export interface TypeOfRangeResult {
+    range: lc.Range;
+    type: string;
+    adjustedType: string | null;
+}
+export const typeOfRange = new lc.RequestType<TypeOfRangeParams, TypeOfRangeResult | null, void>("rust-analyzer/typeOfRange");
+
+export interface ExplainImplParams {
+    textDocument: lc.TextDocumentIdentifier;
+    range: lc.Range;
+    trait: string;
+}
+export interface ImplExplanation {
+    goal: string;
+    holds: boolean;
+    reason: string;
+    children: ImplExplanation[];
+    rendered: string | null;
+}
+export const explainImpl = new lc.RequestType<ExplainImplParams, ImplExplanation, void>("rust-analyzer/explainImpl");
+
+export interface TraitImplRow {
+    trait: string;
+    implemented: boolean;
+    derivable: boolean;
+}
+export interface TraitImplMatrixResult {
+    type: string;
+    rows: TraitImplRow[];
+    deriveMissing: lc.WorkspaceEdit | null;
+}
+export const traitImplMatrix = new lc.RequestType<lc.TextDocumentPositionParams, TraitImplMatrixResult | null, void>("rust-analyzer/traitImplMatrix");
+
+export interface DesugaredMapping {
+    range: lc.Range;
+    location: lc.Location;
+}
+export interface DesugaredBody {
+    text: string;
+    mappings: DesugaredMapping[];
+}
+export const viewDesugaredBody = new lc.RequestType<lc.TextDocumentPositionParams, DesugaredBody, void>("rust-analyzer/viewDesugaredBody");
+
 export interface ViewItemTreeParams {
     textDocument: lc.TextDocumentIdentifier;
 }
 
 export const viewItemTree = new lc.RequestType<ViewItemTreeParams, string, void>("rust-analyzer/viewItemTree");
 
+export interface ViewCHeaderParams {
+    textDocument: lc.TextDocumentIdentifier;
+}
+
+export const viewCHeader = new lc.RequestType<ViewCHeaderParams, string, void>("rust-analyzer/viewCHeader");
+
+export const viewTraitSolverOverflows = new lc.RequestType<lc.TextDocumentPositionParams, string, void>("rust-analyzer/viewTraitSolverOverflows");
+
 export interface ViewCrateGraphParams {
     full: boolean;
 }
 
 export const viewCrateGraph = new lc.RequestType<ViewCrateGraphParams, string, void>("rust-analyzer/viewCrateGraph");
 
+export interface DeadCodeItem {
+    location: lc.Location;
+    name: string;
+    kind: string;
+    crateName: string;
+}
+
+export const deadCode = new lc.RequestType0<DeadCodeItem[], void>("rust-analyzer/deadCode");
+
+export interface UnsafeOperation {
+    location: lc.Location;
+    description: string;
+}
+export interface UnsafeReportItem {
+    location: lc.Location;
+    kind: string;
+    name: string;
+    operations: UnsafeOperation[];
+    crateName: string;
+}
+export const unsafeReport = new lc.RequestType0<UnsafeReportItem[], void>("rust-analyzer/unsafeReport");
+
+export interface FeatureUsagesParams {
+    feature: string;
+}
+export interface FeatureUsage {
+    location: lc.Location;
+    kind: string;
+}
+export const featureUsages = new lc.RequestType<FeatureUsagesParams, FeatureUsage[], void>("rust-analyzer/featureUsages");
+
+export interface TraitImplementationsParams extends lc.TextDocumentPositionParams {
+    crateId?: number;
+}
+export interface TraitImplementationsGroup {
+    crateId: number;
+    crateName?: string;
+    isLibrary: boolean;
+    implementations?: lc.Location[];
+}
+export const traitImplementations = new lc.RequestType<TraitImplementationsParams, TraitImplementationsGroup[], void>("rust-analyzer/traitImplementations");
+
 export interface ExpandMacroParams {
     textDocument: lc.TextDocumentIdentifier;
     position: lc.Position;
@@ -58,6 +160,21 @@ export interface ExpandedMacro {
 }
 export const expandMacro = new lc.RequestType<ExpandMacroParams, ExpandedMacro | null, void>("rust-analyzer/expandMacro");
 
+export const expansionUri = new lc.RequestType<lc.TextDocumentPositionParams, string | null, void>("rust-analyzer/expansionUri");
+
+export interface ViewExpansionParams {
+    uri: string;
+}
+export interface ExpansionMapping {
+    range: lc.Range;
+    location: lc.Location;
+}
+export interface ExpansionDocument {
+    text: string;
+    mappings: ExpansionMapping[];
+}
+export const viewExpansion = new lc.RequestType<ViewExpansionParams, ExpansionDocument | null, void>("rust-analyzer/viewExpansion");
+
 export interface MatchingBraceParams {
     textDocument: lc.TextDocumentIdentifier;
     positions: lc.Position[];
@@ -106,6 +223,21 @@ export interface InlayHintsParams {
 }
 export const inlayHints = new lc.RequestType<InlayHintsParams, InlayHint[], void>("experimental/inlayHints");
 
+export interface InlineValueParams {
+    textDocument: lc.TextDocumentIdentifier;
+    range: lc.Range;
+    context: {
+        frameId: number;
+        stoppedLocation: lc.Range;
+    };
+}
+export interface InlineValueVariableLookup {
+    range: lc.Range;
+    variableName?: string;
+    caseSensitiveLookup: boolean;
+}
+export const inlineValues = new lc.RequestType<InlineValueParams, InlineValueVariableLookup[] | null, void>("textDocument/inlineValue");
+
 export interface SsrParams {
     query: string;
     parseOnly: boolean;
@@ -115,6 +247,18 @@ export interface SsrParams {
 }
 export const ssr = new lc.RequestType<SsrParams, lc.WorkspaceEdit, void>('experimental/ssr');
 
+export interface BatchCodeActionParams {
+    textDocument: lc.TextDocumentIdentifier;
+    assistId: string;
+    scope: "file" | "crate" | "workspace";
+}
+export interface BatchCodeActionResult {
+    edit: lc.WorkspaceEdit;
+    applied: number;
+    skipped: number;
+}
+export const batchCodeAction = new lc.RequestType<BatchCodeActionParams, BatchCodeActionResult, void>('rust-analyzer/batchCodeAction');
+
 export interface CommandLink extends lc.Command {
     /**
      * A tooltip for the command, when represented in the UI.
@@ -147,3 +291,20 @@ export const enum Direction {
     Up = "Up",
     Down = "Down"
 }
+
+export interface RegisterVirtualDocumentParams {
+    uri: string;
+    anchor: lc.TextDocumentIdentifier;
+}
+export const registerVirtualDocument = new lc.NotificationType<RegisterVirtualDocumentParams>("rust-analyzer/registerVirtualDocument");
+
+export interface SearchTruncatedParams extends lc.TextDocumentPositionParams {
+    partialResultToken?: lc.ProgressToken;
+    continueToken: string;
+}
+export const searchTruncated = new lc.NotificationType<SearchTruncatedParams>("rust-analyzer/searchTruncated");
+
+export interface ContinueSearchParams extends lc.PartialResultParams {
+    continueToken: string;
+}
+export const continueSearch = new lc.RequestType<ContinueSearchParams, lc.Location[] | null, void>("rust-analyzer/continueSearch");
@@ -3,6 +3,8 @@ import * as os from "os";
 
 import * as commands from './commands';
 import { activateInlayHints } from './inlay_hints';
+import { activateInlineValues } from './inline_values';
+import { activateCargoTomlProviders } from './cargo_toml';
 import { Ctx } from './ctx';
 import { Config } from './config';
 import { log, isValidExecutable, isRustDocument } from './util';
@@ -55,6 +57,8 @@ async function tryActivate(context: vscode.ExtensionContext) {
     await initCommonContext(context, ctx);
 
     activateInlayHints(ctx);
+    activateInlineValues(ctx);
+    activateCargoTomlProviders(ctx);
     warnAboutExtensionConflicts();
 
     ctx.pushCleanup(configureLanguage());
@@ -114,9 +118,18 @@ async function initCommonContext(context: vscode.ExtensionContext, ctx: Ctx) {
     ctx.registerCommand('parentModule', commands.parentModule);
     ctx.registerCommand('syntaxTree', commands.syntaxTree);
     ctx.registerCommand('viewHir', commands.viewHir);
+    ctx.registerCommand('viewDesugaredBody', commands.viewDesugaredBody);
     ctx.registerCommand('viewItemTree', commands.viewItemTree);
+    ctx.registerCommand('viewCHeader', commands.viewCHeader);
+    ctx.registerCommand('viewTraitSolverOverflows', commands.viewTraitSolverOverflows);
     ctx.registerCommand('viewCrateGraph', commands.viewCrateGraph);
     ctx.registerCommand('viewFullCrateGraph', commands.viewFullCrateGraph);
+    ctx.registerCommand('findDeadCode', commands.findDeadCode);
+    ctx.registerCommand('unsafeReport', commands.unsafeReport);
+    ctx.registerCommand('featureUsages', commands.featureUsages);
+    ctx.registerCommand('traitImplementations', commands.traitImplementations);
+    ctx.registerCommand('explainImpl', commands.explainImpl);
+    ctx.registerCommand('traitImplMatrix', commands.traitImplMatrix);
     ctx.registerCommand('expandMacro', commands.expandMacro);
     ctx.registerCommand('run', commands.run);
     ctx.registerCommand('copyRunCommandLine', commands.copyRunCommandLine);
@@ -132,6 +145,8 @@ async function initCommonContext(context: vscode.ExtensionContext, ctx: Ctx) {
     ctx.registerCommand('onEnter', commands.onEnter);
 
     ctx.registerCommand('ssr', commands.ssr);
+    ctx.registerCommand('batchCodeAction', commands.batchCodeAction);
+    ctx.registerCommand('replaceGlobImports', commands.replaceGlobImports);
     ctx.registerCommand('serverVersion', commands.serverVersion);
     ctx.registerCommand('toggleInlayHints', commands.toggleInlayHints);
 
@@ -46,6 +46,13 @@ impl<T, V> ArenaMap<Idx<T>, V> {
         self.v.iter().enumerate().filter_map(|(idx, o)| Some((Self::from_idx(idx), o.as_ref()?)))
     }
 
+    /// Releases the memory of the map not used by its values.
+    pub fn shrink_to_fit(&mut self) {
+        let len = self.v.iter().rposition(Option::is_some).map_or(0, |last| last + 1);
+        self.v.truncate(len);
+        self.v.shrink_to_fit();
+    }
+
     fn to_idx(idx: Idx<T>) -> usize {
         u32::from(idx.into_raw()) as usize
     }